@@ -1,3 +1,21 @@
+use std::path::Path;
+
+#[path = "src/utils/fingerprint.rs"]
+mod fingerprint;
+
 fn main() {
+    let list = "src/utils/common_passwords.txt";
+    println!("cargo:rerun-if-changed={}", list);
+    println!("cargo:rerun-if-changed=src/utils/fingerprint.rs");
+
+    let content = std::fs::read_to_string(list).expect("Failed to read common passwords list");
+    let table = fingerprint::fingerprints(&content)
+        .into_iter()
+        .flat_map(u64::to_le_bytes)
+        .collect::<Vec<_>>();
+    let out_dir = std::env::var("OUT_DIR").expect("OUT_DIR is not set");
+    std::fs::write(Path::new(&out_dir).join("common_passwords.bin"), table)
+        .expect("Failed to write common passwords table");
+
     tauri_build::build()
 }
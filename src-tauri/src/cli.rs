@@ -0,0 +1,268 @@
+use log::{error, info};
+use std::fs;
+use std::path::PathBuf;
+
+use crate::commands::record_audit;
+use crate::utils::{AuditEvent, LoginGuard, PasswordSource};
+use crate::{Auth, BackupManager, Config, PasswordManager};
+
+/// Launch argument that creates a backup without opening a window.
+pub const BACKUP_ARG: &str = "--backup";
+/// Launch argument that writes an encrypted export to a target directory, e.g. from cron.
+const EXPORT_ARG: &str = "--export";
+/// Launch argument that stores the master password in the OS keyring for exports.
+const STORE_KEYRING_ARG: &str = "--store-keyring";
+/// Launch argument naming the user whose vault is backed up.
+const USER_ARG: &str = "--user";
+/// Launch argument naming the directory an export is written to.
+const TARGET_ARG: &str = "--target";
+/// Launch argument setting how many exports are kept in the target directory.
+const KEEP_ARG: &str = "--keep";
+/// The number of exports kept in the target directory if not given.
+const DEFAULT_KEEP: usize = 7;
+
+/// Run a command line action if one was requested in the launch arguments.
+///
+/// The master password is read from a file descriptor, an askpass helper or piped stdin
+/// and never from the arguments, so scripted backups do not leak it into process listings.
+/// Exports read it from the OS keyring, so they can run unattended.
+///
+/// # Arguments
+///
+/// * `args` - The launch arguments without the program name.
+///
+/// # Returns
+///
+/// The exit code of the action or None if no action was requested.
+pub fn run(args: &[String]) -> Option<i32> {
+    let has = |name: &str| args.iter().any(|arg| arg == name);
+    let (action, result) = if has(BACKUP_ARG) {
+        (
+            "Backup",
+            backup(args).map(|path| path.display().to_string()),
+        )
+    } else if has(EXPORT_ARG) {
+        (
+            "Export",
+            export(args).map(|path| path.display().to_string()),
+        )
+    } else if has(STORE_KEYRING_ARG) {
+        (
+            "Storing the master password",
+            store_keyring(args).map(|_| "Master password stored in the OS keyring".to_string()),
+        )
+    } else {
+        return None;
+    };
+
+    match result {
+        Ok(output) => {
+            println!("{}", output);
+            Some(0)
+        }
+        Err(e) => {
+            error!("Command line action failed: {}: {}", action, e);
+            eprintln!("{} failed: {}", action, e);
+            Some(1)
+        }
+    }
+}
+
+/// Log in with the master password and create a backup with the configured policy.
+///
+/// # Arguments
+///
+/// * `args` - The launch arguments without the program name.
+///
+/// # Returns
+///
+/// A Result containing the path to the created backup or an error.
+///
+/// # Errors
+///
+/// If no user is given, the login fails or the backup cannot be created.
+fn backup(args: &[String]) -> Result<PathBuf, Box<dyn std::error::Error>> {
+    let username = arg_value(args, USER_ARG)?;
+
+    let config = Config::load()?;
+    let config_dir = Config::get_config_dir()?;
+    let (pm, master_pass) = login(username, PasswordSource::detect()?)?;
+
+    let naming = config.backup_naming();
+    let backup_path = config.backup.backup_path;
+    fs::create_dir_all(&backup_path)?;
+
+    let recipients = config
+        .backup
+        .recipients
+        .iter()
+        .map(|r| r.public_key.clone())
+        .collect();
+    let mut bm = BackupManager::new(&pm.db)
+        .with_recipients(recipients)
+        .with_naming(naming);
+    if config.backup.incremental {
+        bm = bm.with_incremental(config.backup.full_backup_every);
+    }
+    let path = bm.backup(&backup_path, &config_dir, &master_pass)?;
+    record_audit(
+        AuditEvent::Backup,
+        &format!(
+            "Backup created in {} from the command line",
+            backup_path.display()
+        ),
+    );
+    info!("Command line backup created: {}", path.display());
+
+    Ok(path)
+}
+
+/// Log in with the master password from the OS keyring and write an encrypted export.
+///
+/// The export is a full backup, encrypted with the master password and to the configured
+/// recipients. Plaintext formats are not offered, because exports are meant to be copied
+/// off-site unattended. Only the newest exports in the target directory are kept.
+///
+/// # Arguments
+///
+/// * `args` - The launch arguments without the program name.
+///
+/// # Returns
+///
+/// A Result containing the path to the created export or an error.
+///
+/// # Errors
+///
+/// If the user or target is missing, the keyring has no password, the login fails or the
+/// export cannot be written.
+fn export(args: &[String]) -> Result<PathBuf, Box<dyn std::error::Error>> {
+    let username = arg_value(args, USER_ARG)?;
+    let target = PathBuf::from(arg_value(args, TARGET_ARG)?);
+    let keep = match arg_value(args, KEEP_ARG) {
+        Ok(keep) => keep
+            .parse::<usize>()
+            .ok()
+            .filter(|keep| *keep > 0)
+            .ok_or(format!("{} must be a positive number", KEEP_ARG))?,
+        Err(_) => DEFAULT_KEEP,
+    };
+
+    let config = Config::load()?;
+    let config_dir = Config::get_config_dir()?;
+    let (pm, master_pass) = login(username, PasswordSource::Keyring(username.clone()))?;
+
+    fs::create_dir_all(&target)?;
+    let recipients = config
+        .backup
+        .recipients
+        .iter()
+        .map(|r| r.public_key.clone())
+        .collect();
+    let naming = config.backup_naming();
+    let path = BackupManager::new(&pm.db)
+        .with_recipients(recipients)
+        .with_naming(naming.clone())
+        .create_backup(&target, &config_dir, &master_pass)?;
+    let removed = BackupManager::prune_backups(&target, keep, &naming)?;
+    record_audit(
+        AuditEvent::Export,
+        &format!(
+            "Encrypted export written to {} from the command line",
+            target.display()
+        ),
+    );
+    info!(
+        "Command line export created: {}, {} old exports removed",
+        path.display(),
+        removed.len()
+    );
+
+    Ok(path)
+}
+
+/// Check the master password and store it in the OS keyring for unattended exports.
+///
+/// # Arguments
+///
+/// * `args` - The launch arguments without the program name.
+///
+/// # Returns
+///
+/// A Result containing a unit or an error.
+///
+/// # Errors
+///
+/// If no user is given, the login fails or the keyring is not available.
+fn store_keyring(args: &[String]) -> Result<(), Box<dyn std::error::Error>> {
+    let username = arg_value(args, USER_ARG)?;
+    let (_pm, master_pass) = login(username, PasswordSource::detect()?)?;
+    PasswordSource::store_in_keyring(username, &master_pass)?;
+    info!(
+        "Stored the master password of {} in the OS keyring",
+        username
+    );
+
+    Ok(())
+}
+
+/// Read the master password from the source and log in, counting failures like the login screen.
+///
+/// # Arguments
+///
+/// * `username` - The user to log in.
+/// * `source` - Where to read the master password from.
+///
+/// # Returns
+///
+/// A Result containing the opened password manager and the master password or an error.
+///
+/// # Errors
+///
+/// If the login is locked, the password cannot be read or is wrong.
+fn login(
+    username: &str,
+    source: PasswordSource,
+) -> Result<(PasswordManager, String), Box<dyn std::error::Error>> {
+    let config_dir = Config::get_config_dir()?;
+    let guard = LoginGuard::new(&config_dir);
+    let remaining = guard.remaining_lockout();
+    if remaining > 0 {
+        return Err(format!("Login locked for {}s", remaining).into());
+    }
+
+    let master_pass = source.read()?;
+    let pm = guard
+        .attempt(|| {
+            let pm = PasswordManager::new(&master_pass)?;
+            Auth::new(&pm.db).login(username, &master_pass)?;
+            Ok(pm)
+        })
+        .inspect_err(|_| record_audit(AuditEvent::FailedLogin, username))?;
+
+    Ok((pm, master_pass))
+}
+
+/// Get the value following a launch argument.
+///
+/// # Arguments
+///
+/// * `args` - The launch arguments without the program name.
+/// * `name` - The launch argument, e.g. `--user`.
+///
+/// # Returns
+///
+/// A Result containing the value or an error.
+///
+/// # Errors
+///
+/// If the argument or its value is missing.
+fn arg_value<'a>(args: &'a [String], name: &str) -> Result<&'a String, String> {
+    args.iter()
+        .position(|arg| arg == name)
+        .and_then(|i| args.get(i + 1))
+        .ok_or(format!(
+            "Missing {} <{}>",
+            name,
+            name.trim_start_matches('-')
+        ))
+}
@@ -1,10 +1,15 @@
 pub use super::PasswordManagerState;
-use crate::Auth;
-use crate::PasswordManager;
+use crate::commands::{
+    close_snapshot_window, migrate_field_encryption, refresh_tray_menu, HealthCache,
+};
+use crate::service::VaultService;
+use crate::utils::{Cipher, SecureClipboard, EXPIRY_WARNING_DAYS};
+use crate::Config;
 use log::{error, info};
 use tauri::AppHandle;
 use tauri::State;
 use tauri_plugin_dialog::{DialogExt, MessageDialogButtons, MessageDialogKind};
+use tauri_plugin_notification::NotificationExt;
 
 #[tauri::command(rename_all = "camelCase")]
 /// Register a new user.
@@ -13,6 +18,7 @@ use tauri_plugin_dialog::{DialogExt, MessageDialogButtons, MessageDialogKind};
 ///
 /// * `username` - The username to register.
 /// * `master_pass` - The master password to use for the database.
+/// * `cipher` - The algorithm to encrypt a new vault with, AES-256-GCM if not given.
 ///
 /// # Returns
 ///
@@ -23,8 +29,10 @@ use tauri_plugin_dialog::{DialogExt, MessageDialogButtons, MessageDialogKind};
 /// If the user cannot be registered.
 pub async fn register(
     app_handle: AppHandle,
+    state: State<'_, PasswordManagerState>,
     username: String,
     master_pass: String,
+    cipher: Option<Cipher>,
 ) -> Result<(), String> {
     let confirmed = app_handle
         .dialog()
@@ -35,28 +43,7 @@ pub async fn register(
         .blocking_show();
 
     if confirmed {
-        match PasswordManager::new(&master_pass) {
-            Ok(pm) => {
-                let auth = Auth::new(&pm.db);
-                match auth.register(&username, &master_pass) {
-                    Ok(_) => {
-                        info!("Successfully registered user: {}", username);
-                        Ok(())
-                    }
-                    Err(e) => {
-                        error!("Failed to register user {}: {}", username, e);
-                        Err(e.to_string())
-                    }
-                }
-            }
-            Err(e) => {
-                error!(
-                    "Failed to create PasswordManager during registration: {}",
-                    e
-                );
-                Err(e.to_string())
-            }
-        }
+        VaultService::new(&state).register(&username, &master_pass, cipher.unwrap_or_default())
     } else {
         info!("Registration cancelled by user");
         Err("Setup abgebrochen".to_string())
@@ -66,6 +53,8 @@ pub async fn register(
 #[tauri::command(rename_all = "camelCase")]
 /// Login a user.
 ///
+/// Failed attempts are counted and lock the login for an increasing time.
+///
 /// # Arguments
 ///
 /// * `username` - The username to login.
@@ -77,31 +66,105 @@ pub async fn register(
 ///
 /// # Errors
 ///
-/// If the user cannot be logged in.
+/// If the login is locked or the user cannot be logged in.
 pub async fn login(
+    app: AppHandle,
     state: State<'_, PasswordManagerState>,
     username: String,
     master_pass: String,
 ) -> Result<(), String> {
-    info!("Login attempt for user: {}", username);
+    let service = VaultService::new(&state);
+    service.login(&username, &master_pass)?;
 
-    match PasswordManager::new(&master_pass) {
-        Ok(mut pm) => match pm.login(&username, &master_pass) {
-            Ok(_) => {
-                info!("Successfully logged in user: {}", username);
-                *state.0.lock().unwrap() = Some(pm);
-                Ok(())
-            }
-            Err(e) => {
-                error!("Failed to login user {}: {}", username, e);
-                Err(e.to_string())
-            }
-        },
-        Err(e) => {
-            error!("Failed to create PasswordManager during login: {}", e);
-            Err(e.to_string())
-        }
+    if Config::load().is_ok_and(|config| config.app.expiry_reminder) {
+        notify_expiring_entries(&app, &service);
     }
+    refresh_tray_menu(&app);
+    migrate_field_encryption(app);
+
+    Ok(())
+}
+
+/// Show a desktop notification about expired or soon expiring passwords.
+///
+/// # Arguments
+///
+/// * `app` - The app handle.
+/// * `service` - The vault service of the user who logged in.
+fn notify_expiring_entries(app: &AppHandle, service: &VaultService) {
+    let entries = match service.expiring_entries(EXPIRY_WARNING_DAYS) {
+        Ok(entries) if !entries.is_empty() => entries,
+        _ => return,
+    };
+
+    let today = chrono::Local::now().format("%Y-%m-%d").to_string();
+    let expired = entries
+        .iter()
+        .filter(|entry| entry.expires_on.as_deref() < Some(today.as_str()))
+        .count();
+    let services = entries
+        .iter()
+        .take(3)
+        .map(|entry| entry.service.as_str())
+        .collect::<Vec<_>>()
+        .join(", ");
+    let more = if entries.len() > 3 { ", …" } else { "" };
+    let body = format!(
+        "{} abgelaufen, {} laufen in den nächsten {} Tagen ab: {}{}",
+        expired,
+        entries.len() - expired,
+        EXPIRY_WARNING_DAYS,
+        services,
+        more
+    );
+
+    match app
+        .notification()
+        .builder()
+        .title("Ablaufende Passwörter")
+        .body(body)
+        .show()
+    {
+        Ok(_) => info!("Notified about {} expiring passwords", entries.len()),
+        Err(e) => error!("Failed to show the expiry notification: {}", e),
+    }
+}
+
+#[tauri::command]
+/// Open the demo vault with sample entries.
+///
+/// The demo vault only lives in memory and is discarded on logout, so it can be explored
+/// before the app is set up.
+///
+/// # Returns
+///
+/// A Result containing the completion status or an error.
+///
+/// # Errors
+///
+/// If the demo vault cannot be created.
+pub async fn start_demo(
+    app: AppHandle,
+    state: State<'_, PasswordManagerState>,
+) -> Result<(), String> {
+    VaultService::new(&state).start_demo()?;
+    refresh_tray_menu(&app);
+
+    Ok(())
+}
+
+#[tauri::command]
+/// Get the remaining lockout time of the login.
+///
+/// # Returns
+///
+/// A Result containing the remaining lockout time in seconds or an error.
+///
+/// # Errors
+///
+/// If the config directory cannot be found.
+pub async fn get_login_lockout(state: State<'_, PasswordManagerState>) -> Result<u64, String> {
+    VaultService::new(&state).login_lockout()
 }
 
 #[tauri::command]
@@ -114,10 +177,18 @@ pub async fn login(
 /// # Errors
 ///
 /// If the user cannot be logged out.
-pub async fn logout(state: State<'_, PasswordManagerState>) -> Result<(), String> {
-    if let Some(pm) = &*state.0.lock().unwrap() {
-        pm.logout().map_err(|e| e.to_string())?;
+pub async fn logout(
+    app: AppHandle,
+    state: State<'_, PasswordManagerState>,
+    clipboard: State<'_, SecureClipboard>,
+    health_cache: State<'_, HealthCache>,
+) -> Result<(), String> {
+    VaultService::new(&state).logout()?;
+    health_cache.clear();
+    close_snapshot_window(&app);
+    if let Err(e) = clipboard.clear() {
+        error!("Failed to clear clipboard on logout: {}", e);
     }
-    *state.0.lock().unwrap() = None;
+    refresh_tray_menu(&app);
     Ok(())
 }
@@ -0,0 +1,439 @@
+use log::{error, info, warn};
+use serde::{Deserialize, Serialize};
+use serde_json::Value;
+use std::collections::HashMap;
+use std::sync::atomic::{AtomicU64, Ordering};
+use std::sync::mpsc::{self, Sender};
+use std::sync::Mutex;
+use std::time::Duration;
+use tauri::{AppHandle, Emitter, Manager, State};
+
+use super::{record_audit, PasswordManagerState};
+use crate::service::parse_params;
+use crate::utils::{
+    base_domain, install_host, AuditEvent, BrowserConfig, RpcError, INVALID_PARAMS,
+    METHOD_NOT_FOUND, SERVER_ERROR, UNAUTHORIZED,
+};
+use crate::{Config, PasswordManager};
+
+/// Event asking the main window to authorize a request of a browser extension.
+pub const BROWSER_AUTHORIZATION_EVENT: &str = "browser-authorization";
+/// How long a request waits for the user before it is denied.
+const AUTHORIZATION_TIMEOUT: Duration = Duration::from_secs(60);
+
+/// What a browser extension wants to do on a site.
+#[derive(Clone, Copy, Serialize, PartialEq)]
+#[serde(rename_all = "snake_case")]
+enum BrowserAction {
+    Fill,
+    Save,
+}
+
+#[derive(Clone, Serialize)]
+struct AuthorizationRequest {
+    request_id: u64,
+    site: String,
+    origin: String,
+    action: BrowserAction,
+    username: Option<String>,
+}
+
+/// The answer of the user to an authorization request.
+struct Authorization {
+    allow: bool,
+    remember: bool,
+}
+
+/// The requests of browser extensions waiting for an answer of the user.
+pub struct BrowserState {
+    pending: Mutex<HashMap<u64, Sender<Authorization>>>,
+    next_id: AtomicU64,
+}
+
+impl BrowserState {
+    /// Create a new state without pending requests.
+    ///
+    /// # Returns
+    ///
+    /// A new browser state.
+    pub fn new() -> Self {
+        Self {
+            pending: Mutex::new(HashMap::new()),
+            next_id: AtomicU64::new(1),
+        }
+    }
+}
+
+#[derive(Deserialize)]
+struct LoginsRequest {
+    url: String,
+    origin: String,
+}
+
+#[derive(Deserialize)]
+struct FillRequest {
+    url: String,
+    id: i32,
+    origin: String,
+}
+
+#[derive(Deserialize)]
+struct SaveRequest {
+    url: String,
+    username: String,
+    password: String,
+    origin: String,
+    #[serde(default)]
+    service: Option<String>,
+}
+
+#[derive(Serialize)]
+struct BrowserLogin {
+    id: Option<i32>,
+    service: String,
+    username: String,
+}
+
+/// Execute a request of a browser extension, forwarded by the native messaging host.
+///
+/// Reading the logins of a site needs the approval of the user, once or permanently for the
+/// site. Saving a login is approved every time.
+///
+/// # Arguments
+///
+/// * `app` - The app handle.
+/// * `method` - The name of the method without the browser prefix.
+/// * `params` - The parameters of the method, including the origin of the extension.
+///
+/// # Returns
+///
+/// The result of the method.
+///
+/// # Errors
+///
+/// If the method is unknown, the vault is locked, the user denied the request or the
+/// method fails.
+pub fn handle_browser_request(
+    app: &AppHandle,
+    method: &str,
+    params: Value,
+) -> Result<Value, RpcError> {
+    match method {
+        "get_logins" => {
+            let request: LoginsRequest = parse_params(params)?;
+            let site = site_of(&request.url)?;
+            ensure_unlocked(app)?;
+            authorize(app, &site, &request.origin, BrowserAction::Fill, None)?;
+
+            with_manager(app, |pm| {
+                let logins: Vec<BrowserLogin> = pm
+                    .find_logins(&request.url)
+                    .map_err(|e| e.to_string())?
+                    .into_iter()
+                    .map(|entry| BrowserLogin {
+                        id: entry.id,
+                        service: entry.service,
+                        username: entry.username,
+                    })
+                    .collect();
+                Ok(serde_json::json!(logins))
+            })
+        }
+        "request_fill" => {
+            let request: FillRequest = parse_params(params)?;
+            let site = site_of(&request.url)?;
+            ensure_unlocked(app)?;
+            authorize(app, &site, &request.origin, BrowserAction::Fill, None)?;
+
+            with_manager(app, |pm| {
+                let entry = pm
+                    .find_logins(&request.url)
+                    .map_err(|e| e.to_string())?
+                    .into_iter()
+                    .find(|entry| entry.id == Some(request.id))
+                    .ok_or("The entry does not belong to this site")?;
+                let password = pm
+                    .decrypt_password_by_id(request.id, None)
+                    .map_err(|e| e.to_string())?;
+                pm.record_recent_entry(request.id);
+                info!("Filled entry {} for {}", request.id, site);
+
+                Ok(serde_json::json!({
+                    "username": entry.username,
+                    "password": password,
+                }))
+            })
+        }
+        "save_login" => {
+            let request: SaveRequest = parse_params(params)?;
+            let site = site_of(&request.url)?;
+            ensure_unlocked(app)?;
+            authorize(
+                app,
+                &site,
+                &request.origin,
+                BrowserAction::Save,
+                Some(request.username.clone()),
+            )?;
+
+            with_manager(app, |pm| {
+                pm.add_password(
+                    request.service.unwrap_or_else(|| site.clone()),
+                    request.username,
+                    request.password,
+                    request.url,
+                    None,
+                    false,
+                )
+                .map_err(|e| e.to_string())?;
+                info!("Saved login for {} from the browser", site);
+                record_audit(
+                    AuditEvent::EntryCreated,
+                    "Password entry saved from the browser",
+                );
+
+                Ok(Value::Null)
+            })
+        }
+        _ => Err(RpcError::new(METHOD_NOT_FOUND, "Method not found")),
+    }
+}
+
+/// Get the site of a URL, which authorizations are granted for.
+fn site_of(url: &str) -> Result<String, RpcError> {
+    base_domain(url).ok_or_else(|| RpcError::new(INVALID_PARAMS, "The URL has no domain"))
+}
+
+/// Check that the vault is unlocked before the user is asked.
+fn ensure_unlocked(app: &AppHandle) -> Result<(), RpcError> {
+    with_manager(app, |_| Ok(()))
+}
+
+/// Run an operation with the logged in password manager.
+fn with_manager<T>(
+    app: &AppHandle,
+    f: impl FnOnce(&mut PasswordManager) -> Result<T, String>,
+) -> Result<T, RpcError> {
+    let state = app.state::<PasswordManagerState>();
+    let mut state = state.0.lock().unwrap();
+    let pm = state
+        .as_mut()
+        .ok_or_else(|| RpcError::new(SERVER_ERROR, "Not logged in"))?;
+
+    f(pm).map_err(|e| RpcError::new(SERVER_ERROR, e))
+}
+
+/// Ask the user in the main window to approve a request, unless the site is authorized.
+///
+/// Blocks until the user answers or the request times out.
+///
+/// # Arguments
+///
+/// * `app` - The app handle.
+/// * `site` - The base domain of the site.
+/// * `origin` - The origin or ID of the extension.
+/// * `action` - What the extension wants to do.
+/// * `username` - The username of a login to save.
+///
+/// # Returns
+///
+/// A Result containing the completion status or an error.
+///
+/// # Errors
+///
+/// If the user denied the request or did not answer in time.
+fn authorize(
+    app: &AppHandle,
+    site: &str,
+    origin: &str,
+    action: BrowserAction,
+    username: Option<String>,
+) -> Result<(), RpcError> {
+    if action == BrowserAction::Fill
+        && Config::load().is_ok_and(|config| config.browser.is_authorized(site))
+    {
+        return Ok(());
+    }
+
+    let state = app.state::<BrowserState>();
+    let request_id = state.next_id.fetch_add(1, Ordering::Relaxed);
+    let (sender, receiver) = mpsc::channel();
+    state.pending.lock().unwrap().insert(request_id, sender);
+
+    if let Some(window) = app.get_webview_window("main") {
+        let _ = window.show();
+        let _ = window.unminimize();
+        let _ = window.set_focus();
+    }
+    let request = AuthorizationRequest {
+        request_id,
+        site: site.to_string(),
+        origin: origin.to_string(),
+        action,
+        username,
+    };
+    if let Err(e) = app.emit(BROWSER_AUTHORIZATION_EVENT, request) {
+        error!("Failed to emit browser authorization event: {}", e);
+    }
+
+    let answer = receiver.recv_timeout(AUTHORIZATION_TIMEOUT);
+    state.pending.lock().unwrap().remove(&request_id);
+
+    match answer {
+        Ok(answer) if answer.allow => {
+            if answer.remember && action == BrowserAction::Fill {
+                let result = Config::load().and_then(|mut config| {
+                    config.browser.authorize_site(site);
+                    config.save()
+                });
+                if let Err(e) = result {
+                    error!("Failed to remember authorized site {}: {}", site, e);
+                }
+            }
+            Ok(())
+        }
+        Ok(_) => {
+            warn!("User denied browser request for {}", site);
+            Err(RpcError::new(UNAUTHORIZED, "Denied by the user"))
+        }
+        Err(_) => {
+            warn!("Browser request for {} timed out", site);
+            Err(RpcError::new(UNAUTHORIZED, "No answer from the user"))
+        }
+    }
+}
+
+#[tauri::command(rename_all = "camelCase")]
+/// Answer the authorization request of a browser extension.
+///
+/// # Arguments
+///
+/// * `request_id` - The ID of the request.
+/// * `allow` - Whether the request is approved.
+/// * `remember` - Whether the site may read its logins without asking from now on.
+///
+/// # Returns
+///
+/// A Result containing the completion status or an error.
+///
+/// # Errors
+///
+/// If the request has timed out.
+pub async fn answer_browser_authorization(
+    state: State<'_, BrowserState>,
+    request_id: u64,
+    allow: bool,
+    remember: bool,
+) -> Result<(), String> {
+    let sender = state
+        .pending
+        .lock()
+        .unwrap()
+        .remove(&request_id)
+        .ok_or("The request has expired")?;
+    sender
+        .send(Authorization { allow, remember })
+        .map_err(|_| "The request has expired".to_string())
+}
+
+#[tauri::command]
+/// Get the settings of the browser extension.
+///
+/// # Returns
+///
+/// A Result containing the browser settings or an error.
+///
+/// # Errors
+///
+/// If the config cannot be loaded.
+pub async fn get_browser_settings() -> Result<BrowserConfig, String> {
+    let config = Config::load().map_err(|e| e.to_string())?;
+
+    Ok(config.browser)
+}
+
+#[tauri::command(rename_all = "camelCase")]
+/// Save the settings of the browser extension.
+///
+/// The extension is served by the JSON-RPC server of the app, so enabling it takes effect
+/// after a restart.
+///
+/// # Arguments
+///
+/// * `enabled` - Whether browser extensions may connect.
+/// * `extension_ids` - The IDs of the extensions allowed to start the native messaging host.
+///
+/// # Returns
+///
+/// A Result containing the completion status or an error.
+///
+/// # Errors
+///
+/// If the config cannot be saved.
+pub async fn save_browser_settings(
+    enabled: bool,
+    extension_ids: Vec<String>,
+) -> Result<(), String> {
+    let mut config = Config::load().map_err(|e| e.to_string())?;
+    config.browser.enabled = enabled;
+    config.browser.extension_ids.clear();
+    for id in extension_ids.iter().map(|id| id.trim()) {
+        if !id.is_empty() && !config.browser.extension_ids.iter().any(|known| known == id) {
+            config.browser.extension_ids.push(id.to_string());
+        }
+    }
+    config.save().map_err(|e| e.to_string())?;
+
+    Ok(())
+}
+
+#[tauri::command]
+/// Revoke the permanent authorization of a site.
+///
+/// # Arguments
+///
+/// * `site` - The base domain of the site.
+///
+/// # Returns
+///
+/// A Result containing the completion status or an error.
+///
+/// # Errors
+///
+/// If the config cannot be saved.
+pub async fn revoke_browser_site(site: String) -> Result<(), String> {
+    let mut config = Config::load().map_err(|e| e.to_string())?;
+    config.browser.authorized_sites.retain(|s| *s != site);
+    config.save().map_err(|e| e.to_string())?;
+    info!("Revoked browser authorization for {}", site);
+
+    Ok(())
+}
+
+#[tauri::command]
+/// Register the app as native messaging host for the installed browsers.
+///
+/// # Returns
+///
+/// A Result containing the number of browsers the host was registered for or an error.
+///
+/// # Errors
+///
+/// If no supported browser is installed or the host manifests cannot be written.
+pub async fn install_browser_host() -> Result<usize, String> {
+    let config = Config::load().map_err(|e| e.to_string())?;
+    let exe = std::env::current_exe().map_err(|e| e.to_string())?;
+    let config_dir = Config::get_config_dir().map_err(|e| e.to_string())?;
+
+    let installed = install_host(&exe, &config.browser.extension_ids, &config_dir)
+        .map_err(|e| e.to_string())?;
+    if installed == 0 {
+        return Err("No supported browser found".to_string());
+    }
+    info!(
+        "Registered native messaging host for {} browsers",
+        installed
+    );
+
+    Ok(installed)
+}
@@ -1,4 +1,10 @@
-use crate::PasswordManager;
+use tauri::State;
+
+use super::PasswordManagerState;
+use crate::{
+    service::VaultService,
+    utils::{GeneratedPassword, GeneratorOptions},
+};
 
 #[tauri::command(rename_all = "camelCase")]
 /// Generate a password.
@@ -6,6 +12,7 @@ use crate::PasswordManager;
 /// # Arguments
 ///
 /// * `length` - The length of the password to generate.
+/// * `options` - The character classes and mode, the configured defaults if omitted.
 ///
 /// # Returns
 ///
@@ -14,6 +21,52 @@ use crate::PasswordManager;
 /// # Errors
 ///
 /// If the password cannot be generated.
-pub async fn generate_password(length: usize) -> Result<String, String> {
-    PasswordManager::generate_password(length).map_err(|e| e.to_string())
+pub async fn generate_password(
+    state: State<'_, PasswordManagerState>,
+    length: usize,
+    options: Option<GeneratorOptions>,
+) -> Result<String, String> {
+    VaultService::new(&state).generate_password(length, options)
+}
+
+#[tauri::command(rename_all = "camelCase")]
+/// Get the passwords from the generator history.
+///
+/// # Arguments
+///
+/// * `master_pass` - The master password.
+///
+/// # Returns
+///
+/// A Result containing the generated passwords, newest first, or an error.
+///
+/// # Errors
+///
+/// If the user is not logged in or the master password is wrong.
+pub async fn get_generator_history(
+    state: State<'_, PasswordManagerState>,
+    master_pass: String,
+) -> Result<Vec<GeneratedPassword>, String> {
+    let state = state.0.lock().unwrap();
+    let pm = state.as_ref().ok_or("Not logged in")?;
+
+    pm.get_generator_history(&master_pass)
+        .map_err(|e| e.to_string())
+}
+
+#[tauri::command]
+/// Remove all passwords from the generator history.
+///
+/// # Returns
+///
+/// A Result containing the completion status or an error.
+///
+/// # Errors
+///
+/// If the user is not logged in or the history cannot be removed.
+pub async fn clear_generator_history(state: State<'_, PasswordManagerState>) -> Result<(), String> {
+    let state = state.0.lock().unwrap();
+    let pm = state.as_ref().ok_or("Not logged in")?;
+
+    pm.clear_generator_history().map_err(|e| e.to_string())
 }
@@ -1,11 +1,20 @@
+use chrono::Utc;
 use log::{error, info};
-use serde::Serialize;
+use serde::{Deserialize, Serialize};
+use std::collections::HashMap;
+use std::sync::Mutex;
 use tauri::State;
 
 use super::PasswordManagerState;
+use crate::utils::{HealthSnapshot, PasswordHealth, PasswordIssue};
+use crate::{BackupManager, Config};
 
-#[derive(Serialize)]
+/// The number of days shown in the trends if the frontend does not ask for a range.
+const DEFAULT_TREND_DAYS: usize = 90;
+
+#[derive(Serialize, Clone)]
 pub struct PasswordHealthResponse {
+    pub id: Option<i32>,
     pub service: String,
     pub username: String,
     pub score: u8,
@@ -15,39 +24,546 @@ pub struct PasswordHealthResponse {
     pub last_modified: String,
 }
 
+#[derive(Serialize, Clone)]
+pub struct HealthSummary {
+    pub checked_at: String,
+    pub passwords: Vec<PasswordHealthResponse>,
+}
+
+/// The result of the last health check, kept until logout so the dashboard can show it
+/// while a new check is running.
+pub struct HealthCache(pub Mutex<Option<HealthSummary>>);
+
+#[derive(Deserialize, Clone, Copy)]
+#[serde(rename_all = "lowercase")]
+pub enum HealthFilter {
+    Breached,
+    Outdated,
+    Duplicates,
+}
+
+/// Which entries a CSV export contains, based on the health check.
+#[derive(Deserialize, Clone, Copy, Default, PartialEq)]
+#[serde(rename_all = "snake_case")]
+pub enum ExportFilter {
+    #[default]
+    All,
+    /// Leave out entries flagged as common, breached or too weak.
+    ExcludeFlagged,
+    /// Only export entries without any issue.
+    HealthyOnly,
+}
+
+impl From<&PasswordHealth> for PasswordHealthResponse {
+    fn from(password: &PasswordHealth) -> Self {
+        Self {
+            id: password.entry_id,
+            service: password.service.clone(),
+            username: password.username.clone(),
+            score: password.score,
+            strength: format!("{:?}", password.strength),
+            issues: password.issues.iter().map(|i| format!("{:?}", i)).collect(),
+            suggestions: password.suggestions.clone(),
+            last_modified: password.last_modified.to_rfc3339(),
+        }
+    }
+}
+
+impl HealthFilter {
+    /// Check if a password health result matches the filter.
+    ///
+    /// Breached passwords are the ones found in the common passwords list or, if the breach
+    /// check ran, in Have I Been Pwned.
+    ///
+    /// # Arguments
+    ///
+    /// * `health` - The password health result.
+    ///
+    /// # Returns
+    ///
+    /// A boolean indicating if the result matches the filter.
+    fn matches(&self, health: &PasswordHealthResponse) -> bool {
+        let issues: &[PasswordIssue] = match self {
+            HealthFilter::Breached => &[PasswordIssue::Common, PasswordIssue::Breached],
+            HealthFilter::Outdated => &[PasswordIssue::Outdated],
+            HealthFilter::Duplicates => &[PasswordIssue::Duplicate],
+        };
+        issues
+            .iter()
+            .any(|issue| health.issues.contains(&format!("{:?}", issue)))
+    }
+}
+
+impl ExportFilter {
+    /// Check if a checked password is left out of the export.
+    ///
+    /// # Arguments
+    ///
+    /// * `health` - The password health result.
+    ///
+    /// # Returns
+    ///
+    /// A boolean indicating if the entry is not exported.
+    pub fn excludes(&self, health: &PasswordHealthResponse) -> bool {
+        match self {
+            ExportFilter::All => false,
+            ExportFilter::ExcludeFlagged => [
+                PasswordIssue::Common,
+                PasswordIssue::Breached,
+                PasswordIssue::TooWeak,
+            ]
+            .iter()
+            .any(|issue| health.issues.contains(&format!("{:?}", issue))),
+            ExportFilter::HealthyOnly => !health.issues.is_empty(),
+        }
+    }
+}
+
+impl PasswordHealthResponse {
+    /// Flag the password as found in data breaches, once.
+    ///
+    /// # Arguments
+    ///
+    /// * `count` - How often the password was seen in breaches.
+    fn mark_breached(&mut self, count: u64) {
+        let issue = format!("{:?}", PasswordIssue::Breached);
+        if self.issues.contains(&issue) {
+            return;
+        }
+
+        self.issues.push(issue);
+        self.suggestions.push(format!(
+            "Dieses Passwort wurde {} Mal in Datenlecks gefunden. Ändere es so bald wie möglich.",
+            count
+        ));
+    }
+}
+
+impl HealthSummary {
+    /// Get the average score of all checked passwords.
+    ///
+    /// # Returns
+    ///
+    /// The average score or None if no passwords were checked.
+    pub fn overall_score(&self) -> Option<u8> {
+        if self.passwords.is_empty() {
+            return None;
+        }
+
+        let total: usize = self.passwords.iter().map(|p| p.score as usize).sum();
+        Some((total / self.passwords.len()) as u8)
+    }
+}
+
+impl HealthCache {
+    /// Create a new, empty health cache.
+    ///
+    /// # Returns
+    ///
+    /// A new health cache.
+    pub fn new() -> Self {
+        Self(Mutex::new(None))
+    }
+
+    /// Remove the cached health check, e.g. after the vault was locked.
+    pub fn clear(&self) {
+        *self.0.lock().unwrap() = None;
+    }
+}
+
+#[derive(Serialize)]
+pub struct UnlockSummary {
+    pub entry_count: usize,
+    pub due_for_rotation: usize,
+    pub health_issues: usize,
+    pub score: Option<u8>,
+    pub last_backup: Option<String>,
+    pub last_backup_days: Option<i64>,
+}
+
+#[derive(Serialize)]
+pub struct BreachReport {
+    pub checked: usize,
+    pub breached: usize,
+}
+
+#[derive(Serialize)]
+pub struct DuplicateEntryResponse {
+    pub id: Option<i32>,
+    pub service: String,
+    pub username: String,
+}
+
+#[derive(Serialize)]
+pub struct DuplicateGroupResponse {
+    /// The provider all entries log in to, None if the password is reused across providers.
+    pub provider: Option<String>,
+    pub entries: Vec<DuplicateEntryResponse>,
+}
+
 #[tauri::command]
+/// Check the health of the stored passwords.
+///
+/// A check without filter runs the analysis again and updates the cache. Filtered checks
+/// reuse the cached result if there is one.
+///
+/// # Arguments
+///
+/// * `filter` - Only return the passwords with the given issue.
+///
+/// # Returns
+///
+/// A Result containing the health of the passwords or an error.
+///
+/// # Errors
+///
+/// If the user is not logged in or the passwords cannot be checked.
 pub async fn check_passwords(
     state: State<'_, PasswordManagerState>,
+    cache: State<'_, HealthCache>,
+    filter: Option<HealthFilter>,
 ) -> Result<Vec<PasswordHealthResponse>, String> {
+    let cached = filter.and(cache.0.lock().unwrap().clone());
+    let summary = match cached {
+        Some(summary) => summary,
+        None => {
+            let summary = run_health_check(&state)?;
+            *cache.0.lock().unwrap() = Some(summary.clone());
+            summary
+        }
+    };
+
+    Ok(summary
+        .passwords
+        .into_iter()
+        .filter(|password| filter.is_none_or(|filter| filter.matches(password)))
+        .collect())
+}
+
+#[tauri::command]
+/// Check the stored passwords against the Have I Been Pwned password API.
+///
+/// Each password is hashed with SHA-1 and only the first five characters of the hash are
+/// sent, the matching suffixes are compared locally. Breached passwords are flagged in the
+/// cached health check, so the dashboard shows them until the next check.
+///
+/// # Returns
+///
+/// A Result containing the number of checked and breached passwords or an error.
+///
+/// # Errors
+///
+/// If the breach check is disabled, offline mode is enabled, the user is not logged in or
+/// the API cannot be reached.
+pub async fn check_breached_passwords(
+    state: State<'_, PasswordManagerState>,
+    cache: State<'_, HealthCache>,
+) -> Result<BreachReport, String> {
+    let config = Config::load().map_err(|e| e.to_string())?;
+    if !config.app.breach_check {
+        return Err("The breach check is disabled".into());
+    }
+    if config.app.offline_mode {
+        return Err("Offline mode is enabled".into());
+    }
+
+    let hashes = {
+        let state = state.0.lock().unwrap();
+        let pm = state.as_ref().ok_or("Not logged in")?;
+        pm.get_breach_hashes().map_err(|e| {
+            error!("Failed to hash passwords for the breach check: {}", e);
+            e.to_string()
+        })?
+    };
+
+    if rustls::crypto::CryptoProvider::get_default().is_none() {
+        let _ = rustls::crypto::ring::default_provider().install_default();
+    }
+
+    let client = reqwest::Client::new();
+    let mut ranges: HashMap<String, String> = HashMap::new();
+    let mut breached = HashMap::new();
+    for (id, hash) in &hashes {
+        if !ranges.contains_key(&hash.prefix) {
+            let range = client
+                .get(hash.range_url())
+                .header("Add-Padding", "true")
+                .send()
+                .await
+                .and_then(|response| response.error_for_status())
+                .map_err(|e| {
+                    error!("Failed to query the breach API: {}", e);
+                    e.to_string()
+                })?
+                .text()
+                .await
+                .map_err(|e| e.to_string())?;
+            ranges.insert(hash.prefix.clone(), range);
+        }
+
+        let count = hash.count_in(&ranges[&hash.prefix]);
+        if count > 0 {
+            breached.insert(*id, count);
+        }
+    }
+
+    let mut summary = cached_health(&state, &cache)?;
+    for password in &mut summary.passwords {
+        if let Some(count) = password.id.and_then(|id| breached.get(&id)) {
+            password.mark_breached(*count);
+        }
+    }
+    *cache.0.lock().unwrap() = Some(summary);
+    info!(
+        "Breach check finished: {} of {} passwords breached",
+        breached.len(),
+        hashes.len()
+    );
+
+    Ok(BreachReport {
+        checked: hashes.len(),
+        breached: breached.len(),
+    })
+}
+
+#[tauri::command]
+/// Get the result of the last health check.
+///
+/// # Returns
+///
+/// A Result containing the cached health check, if any, or an error.
+///
+/// # Errors
+///
+/// If the user is not logged in.
+pub async fn get_cached_health(
+    state: State<'_, PasswordManagerState>,
+    cache: State<'_, HealthCache>,
+) -> Result<Option<HealthSummary>, String> {
+    if state.0.lock().unwrap().is_none() {
+        return Err("Not logged in".into());
+    }
+
+    Ok(cache.0.lock().unwrap().clone())
+}
+
+#[tauri::command]
+/// Get the average password score of the last health check.
+///
+/// The score is read from the cache, so calling this does not analyze the passwords again.
+///
+/// # Returns
+///
+/// A Result containing the average score, if passwords were checked, or an error.
+///
+/// # Errors
+///
+/// If the user is not logged in.
+pub async fn get_overall_score(
+    state: State<'_, PasswordManagerState>,
+    cache: State<'_, HealthCache>,
+) -> Result<Option<u8>, String> {
+    if state.0.lock().unwrap().is_none() {
+        return Err("Not logged in".into());
+    }
+
+    Ok(cache
+        .0
+        .lock()
+        .unwrap()
+        .as_ref()
+        .and_then(HealthSummary::overall_score))
+}
+
+#[tauri::command]
+/// Get the groups of password entries sharing the same password.
+///
+/// The groups only contain the service and username of each entry, never the password.
+/// Groups within one provider, e.g. through single sign-on, carry the provider and come
+/// after the groups reused across providers.
+///
+/// # Returns
+///
+/// A Result containing the duplicate groups or an error.
+///
+/// # Errors
+///
+/// If the user is not logged in or the passwords cannot be checked.
+pub async fn get_duplicate_groups(
+    state: State<'_, PasswordManagerState>,
+) -> Result<Vec<DuplicateGroupResponse>, String> {
+    let state = state.0.lock().unwrap();
+    let pm = state.as_ref().ok_or("Not logged in")?;
+
+    let groups = pm.get_duplicate_groups().map_err(|e| {
+        error!("Failed to group duplicate passwords: {}", e);
+        e.to_string()
+    })?;
+
+    Ok(groups
+        .into_iter()
+        .map(|group| DuplicateGroupResponse {
+            provider: group.provider,
+            entries: group
+                .entries
+                .into_iter()
+                .map(|entry| DuplicateEntryResponse {
+                    id: entry.id,
+                    service: entry.service,
+                    username: entry.username,
+                })
+                .collect(),
+        })
+        .collect())
+}
+
+#[tauri::command]
+/// Get the entry count and average score of the vault over time.
+///
+/// A snapshot is stored with every full health check, at most one per day.
+///
+/// # Arguments
+///
+/// * `days` - The maximum number of days with a snapshot, 90 if not given.
+///
+/// # Returns
+///
+/// A Result containing the snapshots, oldest first, or an error.
+///
+/// # Errors
+///
+/// If the user is not logged in or the snapshots cannot be read.
+pub async fn get_trends(
+    state: State<'_, PasswordManagerState>,
+    days: Option<usize>,
+) -> Result<Vec<HealthSnapshot>, String> {
+    let state = state.0.lock().unwrap();
+    let pm = state.as_ref().ok_or("Not logged in")?;
+
+    pm.get_health_history(days.unwrap_or(DEFAULT_TREND_DAYS))
+        .map_err(|e| {
+            error!("Failed to load health trends: {}", e);
+            e.to_string()
+        })
+}
+
+#[tauri::command]
+/// Get a summary of the vault to show right after unlocking it.
+///
+/// The passwords are analyzed if there is no cached health check yet, so the dashboard can
+/// reuse the result afterwards.
+///
+/// # Returns
+///
+/// A Result containing the number of entries, the entries older than 90 days, the entries
+/// with other issues, the average score and the age of the last backup, or an error.
+///
+/// # Errors
+///
+/// If the user is not logged in or the passwords cannot be checked.
+pub async fn get_unlock_summary(
+    state: State<'_, PasswordManagerState>,
+    cache: State<'_, HealthCache>,
+) -> Result<UnlockSummary, String> {
+    let summary = cached_health(&state, &cache)?;
+
+    let outdated = format!("{:?}", PasswordIssue::Outdated);
+    let due_for_rotation = summary
+        .passwords
+        .iter()
+        .filter(|p| p.issues.contains(&outdated))
+        .count();
+    let health_issues = summary
+        .passwords
+        .iter()
+        .filter(|p| p.issues.iter().any(|issue| issue != &outdated))
+        .count();
+
+    let last_backup = Config::load()
+        .map_err(|e| e.to_string())
+        .and_then(|config| {
+            BackupManager::last_backup_time(&config.backup.backup_path).map_err(|e| e.to_string())
+        })
+        .unwrap_or_else(|e| {
+            error!("Failed to read the last backup time: {}", e);
+            None
+        });
+
+    Ok(UnlockSummary {
+        entry_count: summary.passwords.len(),
+        due_for_rotation,
+        health_issues,
+        score: summary.overall_score(),
+        last_backup: last_backup.map(|time| time.to_rfc3339()),
+        last_backup_days: last_backup.map(|time| (Utc::now() - time).num_days()),
+    })
+}
+
+/// Analyze all stored passwords.
+///
+/// # Arguments
+///
+/// * `state` - The password manager state.
+///
+/// # Returns
+///
+/// A Result containing the health summary or an error.
+///
+/// # Errors
+///
+/// If the user is not logged in or the passwords cannot be checked.
+/// Get the cached health check or run a new one if nothing is cached.
+///
+/// # Arguments
+///
+/// * `state` - The state holding the logged in password manager.
+/// * `cache` - The cache of the last health check.
+///
+/// # Returns
+///
+/// A Result containing the health check or an error.
+///
+/// # Errors
+///
+/// If the user is not logged in or the passwords cannot be checked.
+pub fn cached_health(
+    state: &PasswordManagerState,
+    cache: &HealthCache,
+) -> Result<HealthSummary, String> {
+    if let Some(summary) = cache.0.lock().unwrap().clone() {
+        return Ok(summary);
+    }
+
+    let summary = run_health_check(state)?;
+    *cache.0.lock().unwrap() = Some(summary.clone());
+    Ok(summary)
+}
+
+fn run_health_check(state: &PasswordManagerState) -> Result<HealthSummary, String> {
     info!("Checking passwords");
     let state = state.0.lock().unwrap();
-    match state.as_ref() {
-        Some(pm) => match pm.check_passwords_health() {
-            Ok(passwords) => {
-                info!("Successfully checked passwords");
-                let mut password_responses = Vec::new();
-                for password in &passwords {
-                    let response = PasswordHealthResponse {
-                        service: password.service.clone(),
-                        username: password.username.clone(),
-                        score: password.score,
-                        strength: format!("{:?}", password.strength),
-                        issues: password.issues.iter().map(|i| format!("{:?}", i)).collect(),
-                        suggestions: password.suggestions.clone(),
-                        last_modified: password.last_modified.to_rfc3339(),
-                    };
-                    password_responses.push(response);
+    let Some(pm) = state.as_ref() else {
+        error!("Attempted to check passwords without being logged in");
+        return Err("Not logged in".into());
+    };
+
+    match pm.check_passwords_health() {
+        Ok(passwords) => {
+            info!("Successfully checked passwords");
+            let summary = HealthSummary {
+                checked_at: Utc::now().to_rfc3339(),
+                passwords: passwords.iter().map(PasswordHealthResponse::from).collect(),
+            };
+            if let Some(score) = summary.overall_score() {
+                if let Err(e) = pm.record_health_snapshot(summary.passwords.len(), score) {
+                    error!("Failed to record health snapshot: {}", e);
                 }
-                Ok(password_responses)
             }
-            Err(e) => {
-                error!("Failed to check passwords: {}", e);
-                Err(e.to_string())
-            }
-        },
-        None => {
-            error!("Attempted to check passwords without being logged in");
-            Err("Not logged in".into())
+            Ok(summary)
+        }
+        Err(e) => {
+            error!("Failed to check passwords: {}", e);
+            Err(e.to_string())
         }
     }
 }
@@ -1,42 +1,68 @@
-use log::{error, info};
+use log::{error, info, warn};
+use std::fs;
+use std::time::Duration;
 use tauri::{AppHandle, State};
 use tauri_plugin_dialog::{DialogExt, MessageDialogButtons, MessageDialogKind};
+use tauri_plugin_opener::OpenerExt;
 
-use crate::PasswordEntry;
+use crate::commands::{record_audit, refresh_tray_menu, HealthCache};
+use crate::service::{AddEntryResult, EntryRequest, VaultService};
+use crate::utils::{
+    Attachment, AuditEvent, Categorizer, CategorySuggestion, CategoryTarget, ClipboardProtection,
+    Config, EntrySummary, ExpiringNote, Folder, Identity, PaymentCard, RecoveryCodeCount,
+    RecoveryCodes, SearchMode, SearchResult, SecureClipboard, SecureNote, Server, Tag, TotpCode,
+    UrlMetadata, EXPIRY_WARNING_DAYS,
+};
 
 pub use super::PasswordManagerState;
 
+/// The time a page or favicon may take to load for the URL metadata.
+const METADATA_TIMEOUT: Duration = Duration::from_secs(5);
+/// The number of bytes of a page searched for the title and favicon.
+const MAX_PAGE_SIZE: usize = 512 * 1024;
+/// The largest favicon that is shown.
+const MAX_ICON_SIZE: usize = 100 * 1024;
+
 #[tauri::command]
-/// Get all passwords.
+/// Get the metadata of all passwords.
+///
+/// The passwords themselves are not included, they are decrypted per entry with
+/// `decrypt_password` when the user reveals or copies one.
 ///
 /// # Returns
 ///
-/// A Result containing a vector of all password entries or an error.
+/// A Result containing a vector of the metadata of all password entries or an error.
 ///
 /// # Errors
 ///
 /// If the passwords cannot be fetched.
 pub async fn get_passwords(
     state: State<'_, PasswordManagerState>,
-) -> Result<Vec<PasswordEntry>, String> {
-    info!("Fetching passwords");
-    let state = state.0.lock().unwrap();
-    match state.as_ref() {
-        Some(pm) => match pm.get_passwords() {
-            Ok(passwords) => {
-                info!("Successfully fetched {} passwords", passwords.len());
-                Ok(passwords)
-            }
-            Err(e) => {
-                error!("Failed to fetch passwords: {}", e);
-                Err(e.to_string())
-            }
-        },
-        None => {
-            error!("Attempted to fetch passwords without being logged in");
-            Err("Not logged in".into())
-        }
-    }
+) -> Result<Vec<EntrySummary>, String> {
+    VaultService::new(&state).list_entries()
+}
+
+#[tauri::command]
+/// Search the passwords in the service, username, URL and notes.
+///
+/// # Arguments
+///
+/// * `query` - The text or pattern to search for.
+/// * `mode` - Whether the query is plain text, a regex or a glob, plain text if not given.
+///
+/// # Returns
+///
+/// A Result containing the matching entries with the positions of the hits or an error.
+///
+/// # Errors
+///
+/// If the pattern is invalid or the passwords cannot be fetched.
+pub async fn search_passwords(
+    state: State<'_, PasswordManagerState>,
+    query: String,
+    mode: Option<SearchMode>,
+) -> Result<Vec<SearchResult>, String> {
+    VaultService::new(&state).search_entries(&query, mode.unwrap_or_default())
 }
 
 #[tauri::command]
@@ -49,10 +75,13 @@ pub async fn get_passwords(
 /// * `password` - The password for the service.
 /// * `url` - The URL of the service.
 /// * `notes` - Optional notes for the password.
+/// * `require_master` - Whether the master password is required to reveal the password.
+/// * `totp_secret` - The TOTP secret, the password may be empty if it is given.
 ///
 /// # Returns
 ///
 /// A Result containing the completion status or an error.
+#[allow(clippy::too_many_arguments)]
 pub async fn add_password(
     state: State<'_, PasswordManagerState>,
     service: String,
@@ -60,38 +89,53 @@ pub async fn add_password(
     password: String,
     url: String,
     notes: Option<String>,
+    require_master: Option<bool>,
+    totp_secret: Option<String>,
 ) -> Result<(), String> {
-    info!("Adding new password entry for service: {}", service);
-    let state = state.0.lock().unwrap();
-    match state.as_ref() {
-        Some(pm) => match pm.add_password(service.clone(), username, password, url, notes) {
-            Ok(_) => {
-                info!("Successfully added password for service: {}", service);
-                Ok(())
-            }
-            Err(e) => {
-                error!("Failed to add password for service {}: {}", service, e);
-                Err(e.to_string())
-            }
-        },
-        None => {
-            error!("Attempted to add password without being logged in");
-            Err("Not logged in".into())
-        }
-    }
+    VaultService::new(&state).add_entry(EntryRequest {
+        service,
+        username,
+        password: Some(password),
+        url,
+        notes,
+        require_master,
+        totp_secret,
+    })
 }
 
 #[tauri::command]
-/// Update a password.
+/// Add several passwords in one transaction.
+///
+/// Each entry is validated on its own, the valid entries are added together.
+///
+/// # Arguments
+///
+/// * `entries` - The entries to add.
+///
+/// # Returns
+///
+/// A Result containing the new ID or the validation error of each entry, or an error.
+///
+/// # Errors
+///
+/// If not logged in or the valid entries cannot be added.
+pub async fn add_passwords(
+    state: State<'_, PasswordManagerState>,
+    entries: Vec<EntryRequest>,
+) -> Result<Vec<AddEntryResult>, String> {
+    VaultService::new(&state).add_entries(entries)
+}
+
+#[tauri::command]
+/// Quickly add a password for a new service.
+///
+/// Uses the given password, e.g. the one just generated, or the text in the clipboard.
 ///
 /// # Arguments
 ///
-/// * `id` - The ID of the password entry to update.
 /// * `service` - The service for which the password is used.
 /// * `username` - The username for the service.
-/// * `password` - The password for the service.
-/// * `url` - The URL of the service.
-/// * `notes` - Optional notes for the password.
+/// * `password` - The password for the service, the clipboard content is used if None.
 ///
 /// # Returns
 ///
@@ -99,89 +143,1810 @@ pub async fn add_password(
 ///
 /// # Errors
 ///
-/// If the password cannot be updated.
-pub async fn update_password(
+/// If the service is empty, the clipboard contains no text or the entry cannot be added.
+pub async fn quick_add_password(
     state: State<'_, PasswordManagerState>,
-    id: i32,
+    clipboard: State<'_, SecureClipboard>,
     service: String,
-    username: String,
-    password: String,
-    url: String,
-    notes: Option<String>,
+    username: Option<String>,
+    password: Option<String>,
 ) -> Result<(), String> {
-    info!("Updating password entry {} for service: {}", id, service);
-    let state = state.0.lock().unwrap();
-    match state.as_ref() {
-        Some(pm) => match pm.update_password(id, service.clone(), username, password, url, notes) {
-            Ok(_) => {
-                info!(
-                    "Successfully updated password {} for service: {}",
-                    id, service
-                );
-                Ok(())
-            }
-            Err(e) => {
-                error!(
-                    "Failed to update password {} for service {}: {}",
-                    id, service, e
-                );
-                Err(e.to_string())
-            }
-        },
-        None => {
-            error!("Attempted to update password without being logged in");
-            Err("Not logged in".into())
-        }
+    let service = service.trim().to_string();
+    if service.is_empty() {
+        return Err("Service must not be empty".into());
+    }
+
+    let password = match password {
+        Some(password) => password,
+        None => clipboard
+            .read_text()
+            .map(|text| text.trim().to_string())
+            .map_err(|e| e.to_string())?,
+    };
+    if password.is_empty() {
+        return Err("Password must not be empty".into());
     }
+
+    info!("Quick adding password entry for service: {}", service);
+    VaultService::new(&state).add_entry(EntryRequest {
+        service,
+        username: username.unwrap_or_default(),
+        password: Some(password),
+        url: String::new(),
+        notes: None,
+        require_master: None,
+        totp_secret: None,
+    })
+}
+
+#[tauri::command]
+/// Add sample entries with varied password health.
+///
+/// Only available on an empty vault or the demo vault.
+///
+/// # Returns
+///
+/// A Result containing the number of added entries or an error.
+///
+/// # Errors
+///
+/// If not logged in, the vault is not empty or the entries cannot be added.
+pub async fn seed_demo_data(
+    state: State<'_, PasswordManagerState>,
+    health_cache: State<'_, HealthCache>,
+) -> Result<usize, String> {
+    let state = state.0.lock().unwrap();
+    let pm = state.as_ref().ok_or("Not logged in")?;
+
+    let added = pm.seed_demo_data().map_err(|e| {
+        error!("Failed to seed demo data: {}", e);
+        e.to_string()
+    })?;
+    health_cache.clear();
+    info!("Seeded {} demo entries", added);
+
+    Ok(added)
+}
+
+#[tauri::command(rename_all = "camelCase")]
+/// Get the recovery codes of a password entry.
+///
+/// # Arguments
+///
+/// * `id` - The ID of the password entry.
+/// * `master_pass` - The master password, required for entries that are protected by it.
+///
+/// # Returns
+///
+/// A Result containing the recovery codes or an error.
+///
+/// # Errors
+///
+/// If not logged in, the master password is missing or wrong or the codes cannot be read.
+pub async fn get_recovery_codes(
+    state: State<'_, PasswordManagerState>,
+    id: i32,
+    master_pass: Option<String>,
+) -> Result<RecoveryCodes, String> {
+    let state = state.0.lock().unwrap();
+    let pm = state.as_ref().ok_or("Not logged in")?;
+
+    pm.get_recovery_codes(id, master_pass.as_deref())
+        .map_err(|e| e.to_string())
 }
 
 #[tauri::command]
-/// Delete a password.
+/// Replace the recovery codes of a password entry.
 ///
 /// # Arguments
 ///
-/// * `id` - The ID of the password entry to delete.
+/// * `id` - The ID of the password entry.
+/// * `codes` - The recovery codes, separated by line breaks or commas.
 ///
 /// # Returns
 ///
-/// A Result containing the completion status or an error.
+/// A Result containing the new recovery codes or an error.
 ///
 /// # Errors
 ///
-/// If the password cannot be deleted.
-pub async fn delete_password(
-    app_handle: AppHandle,
+/// If not logged in or the codes cannot be saved.
+pub async fn set_recovery_codes(
+    state: State<'_, PasswordManagerState>,
+    id: i32,
+    codes: String,
+) -> Result<RecoveryCodes, String> {
+    let state = state.0.lock().unwrap();
+    let pm = state.as_ref().ok_or("Not logged in")?;
+
+    let codes = pm.set_recovery_codes(id, &codes).map_err(|e| {
+        error!("Failed to save recovery codes of entry {}: {}", id, e);
+        e.to_string()
+    })?;
+    info!(
+        "Saved {} recovery codes for entry {}",
+        codes.codes.len(),
+        id
+    );
+
+    Ok(codes)
+}
+
+#[tauri::command(rename_all = "camelCase")]
+/// Get the expiring notes of a password entry, expired ones are redacted first.
+///
+/// # Arguments
+///
+/// * `id` - The ID of the password entry.
+/// * `master_pass` - The master password, required for entries that are protected by it.
+///
+/// # Returns
+///
+/// A Result containing the notes or an error.
+///
+/// # Errors
+///
+/// If not logged in, the master password is missing or wrong or the notes cannot be read.
+pub async fn get_expiring_notes(
+    state: State<'_, PasswordManagerState>,
+    id: i32,
+    master_pass: Option<String>,
+) -> Result<Vec<ExpiringNote>, String> {
+    let state = state.0.lock().unwrap();
+    let pm = state.as_ref().ok_or("Not logged in")?;
+
+    pm.get_expiring_notes(id, master_pass.as_deref())
+        .map_err(|e| e.to_string())
+}
+
+#[tauri::command(rename_all = "camelCase")]
+/// Add a note to a password entry that is redacted after the given day.
+///
+/// # Arguments
+///
+/// * `id` - The ID of the password entry.
+/// * `content` - The content of the note.
+/// * `expires_on` - The last day the note is kept as `YYYY-MM-DD`.
+///
+/// # Returns
+///
+/// A Result containing the ID of the new note or an error.
+///
+/// # Errors
+///
+/// If not logged in, the note or date is invalid or the note cannot be saved.
+pub async fn add_expiring_note(
+    state: State<'_, PasswordManagerState>,
+    id: i32,
+    content: String,
+    expires_on: String,
+) -> Result<i32, String> {
+    let state = state.0.lock().unwrap();
+    let pm = state.as_ref().ok_or("Not logged in")?;
+
+    let note_id = pm
+        .add_expiring_note(id, &content, &expires_on)
+        .map_err(|e| {
+            error!("Failed to add expiring note to entry {}: {}", id, e);
+            e.to_string()
+        })?;
+    info!("Added expiring note to entry {} until {}", id, expires_on);
+
+    Ok(note_id)
+}
+
+#[tauri::command(rename_all = "camelCase")]
+/// Delete an expiring note of a password entry.
+///
+/// # Arguments
+///
+/// * `id` - The ID of the password entry.
+/// * `note_id` - The ID of the note.
+///
+/// # Returns
+///
+/// A Result containing a unit or an error.
+///
+/// # Errors
+///
+/// If not logged in or the note cannot be deleted.
+pub async fn delete_expiring_note(
     state: State<'_, PasswordManagerState>,
     id: i32,
+    note_id: i32,
 ) -> Result<(), String> {
-    let confirmed = app_handle
-        .dialog()
-        .message("Möchten Sie dieses Passwort wirklich löschen?")
-        .title("Passwort löschen")
-        .kind(MessageDialogKind::Info)
-        .buttons(MessageDialogButtons::YesNo)
-        .blocking_show();
+    let state = state.0.lock().unwrap();
+    let pm = state.as_ref().ok_or("Not logged in")?;
 
-    if confirmed {
-        let state = state.0.lock().unwrap();
-        match state.as_ref() {
-            Some(pm) => match pm.delete_password(id) {
-                Ok(_) => {
-                    info!("Successfully deleted password entry: {}", id);
-                    Ok(())
-                }
-                Err(e) => {
-                    error!("Failed to delete password entry {}: {}", id, e);
-                    Err(e.to_string())
-                }
-            },
-            None => {
-                error!("Attempted to delete password without being logged in");
-                Err("Not logged in".into())
-            }
-        }
-    } else {
-        info!("Password deletion cancelled by user");
-        Err("Löschen abgebrochen".into())
+    pm.delete_expiring_note(id, note_id).map_err(|e| {
+        error!("Failed to delete expiring note of entry {}: {}", id, e);
+        e.to_string()
+    })
+}
+
+#[tauri::command]
+/// Get the attached files of a password entry.
+///
+/// # Arguments
+///
+/// * `id` - The ID of the password entry.
+///
+/// # Returns
+///
+/// A Result containing the attachments without their content or an error.
+///
+/// # Errors
+///
+/// If not logged in or the entry cannot be read.
+pub async fn get_attachments(
+    state: State<'_, PasswordManagerState>,
+    id: i32,
+) -> Result<Vec<Attachment>, String> {
+    let state = state.0.lock().unwrap();
+    let pm = state.as_ref().ok_or("Not logged in")?;
+
+    pm.get_attachments(id).map_err(|e| e.to_string())
+}
+
+#[tauri::command]
+/// Pick a file and attach it encrypted to a password entry.
+///
+/// The allowed extensions, the size limit and the scanner command are taken from the
+/// entry settings.
+///
+/// # Arguments
+///
+/// * `id` - The ID of the password entry.
+///
+/// # Returns
+///
+/// A Result containing the ID of the attachment, None if no file was picked, or an error.
+///
+/// # Errors
+///
+/// If not logged in, the file is not allowed or rejected by the scanner or cannot be stored.
+pub async fn add_attachment(
+    app: AppHandle,
+    state: State<'_, PasswordManagerState>,
+    id: i32,
+) -> Result<Option<i32>, String> {
+    let state = state.0.lock().unwrap();
+    let pm = state.as_ref().ok_or("Not logged in")?;
+    let config = Config::load().map_err(|e| e.to_string())?;
+
+    let mut dialog = app.dialog().file();
+    if !config.entry.attachment_extensions.is_empty() {
+        let extensions = config
+            .entry
+            .attachment_extensions
+            .iter()
+            .map(|extension| extension.trim_start_matches('.'))
+            .collect::<Vec<_>>();
+        dialog = dialog.add_filter("Anhänge", &extensions);
     }
+    let path = match dialog.blocking_pick_file() {
+        Some(path) => path.into_path().map_err(|e| e.to_string())?,
+        None => return Ok(None),
+    };
+
+    let attachment_id = pm.add_attachment(id, &path, &config.entry).map_err(|e| {
+        error!("Failed to attach {} to entry {}: {}", path.display(), id, e);
+        e.to_string()
+    })?;
+    info!("Attached a file to entry {}", id);
+
+    Ok(Some(attachment_id))
+}
+
+#[tauri::command(rename_all = "camelCase")]
+/// Decrypt an attached file of a password entry and save it where the user picks.
+///
+/// # Arguments
+///
+/// * `id` - The ID of the password entry.
+/// * `attachment_id` - The ID of the attachment.
+/// * `master_pass` - The master password, required for protected entries.
+///
+/// # Returns
+///
+/// A Result containing the path of the saved file, None if no path was picked, or an error.
+///
+/// # Errors
+///
+/// If not logged in, the master password is wrong or the file cannot be written.
+pub async fn get_attachment(
+    app: AppHandle,
+    state: State<'_, PasswordManagerState>,
+    id: i32,
+    attachment_id: i32,
+    master_pass: Option<String>,
+) -> Result<Option<String>, String> {
+    let state = state.0.lock().unwrap();
+    let pm = state.as_ref().ok_or("Not logged in")?;
+
+    let (file_name, content) = pm
+        .get_attachment(id, attachment_id, master_pass.as_deref())
+        .map_err(|e| e.to_string())?;
+    let path = match app
+        .dialog()
+        .file()
+        .set_file_name(&file_name)
+        .blocking_save_file()
+    {
+        Some(path) => path.into_path().map_err(|e| e.to_string())?,
+        None => return Ok(None),
+    };
+
+    fs::write(&path, content).map_err(|e| {
+        error!("Failed to save attachment {}: {}", attachment_id, e);
+        e.to_string()
+    })?;
+    record_audit(
+        AuditEvent::Export,
+        &format!(
+            "Attachment {} of entry {} saved to {}",
+            attachment_id,
+            id,
+            path.display()
+        ),
+    );
+
+    Ok(Some(path.display().to_string()))
+}
+
+#[tauri::command(rename_all = "camelCase")]
+/// Delete an attached file of a password entry.
+///
+/// # Arguments
+///
+/// * `id` - The ID of the password entry.
+/// * `attachment_id` - The ID of the attachment.
+///
+/// # Returns
+///
+/// A Result containing a unit or an error.
+///
+/// # Errors
+///
+/// If not logged in or the attachment cannot be deleted.
+pub async fn delete_attachment(
+    state: State<'_, PasswordManagerState>,
+    id: i32,
+    attachment_id: i32,
+) -> Result<(), String> {
+    let state = state.0.lock().unwrap();
+    let pm = state.as_ref().ok_or("Not logged in")?;
+
+    pm.delete_attachment(id, attachment_id).map_err(|e| {
+        error!("Failed to delete attachment of entry {}: {}", id, e);
+        e.to_string()
+    })
+}
+
+#[tauri::command]
+/// Get the secure notes of the logged in user.
+///
+/// # Returns
+///
+/// A Result containing the notes sorted by title or an error.
+///
+/// # Errors
+///
+/// If not logged in or the notes cannot be read.
+pub async fn get_secure_notes(
+    state: State<'_, PasswordManagerState>,
+) -> Result<Vec<SecureNote>, String> {
+    let state = state.0.lock().unwrap();
+    let pm = state.as_ref().ok_or("Not logged in")?;
+
+    pm.get_secure_notes().map_err(|e| e.to_string())
+}
+
+#[tauri::command]
+/// Add a secure note, e.g. for license keys that do not belong to a login.
+///
+/// # Arguments
+///
+/// * `title` - The title of the note.
+/// * `content` - The content of the note.
+///
+/// # Returns
+///
+/// A Result containing the ID of the new note or an error.
+///
+/// # Errors
+///
+/// If not logged in, the note is invalid or cannot be saved.
+pub async fn add_secure_note(
+    state: State<'_, PasswordManagerState>,
+    title: String,
+    content: String,
+) -> Result<i32, String> {
+    let state = state.0.lock().unwrap();
+    let pm = state.as_ref().ok_or("Not logged in")?;
+
+    let id = pm.add_secure_note(&title, &content).map_err(|e| {
+        error!("Failed to add secure note: {}", e);
+        e.to_string()
+    })?;
+    info!("Added secure note {}", id);
+
+    Ok(id)
+}
+
+#[tauri::command]
+/// Replace the title and content of a secure note.
+///
+/// # Arguments
+///
+/// * `id` - The ID of the note.
+/// * `title` - The new title of the note.
+/// * `content` - The new content of the note.
+///
+/// # Returns
+///
+/// A Result containing a unit or an error.
+///
+/// # Errors
+///
+/// If not logged in, the note is invalid or does not exist.
+pub async fn update_secure_note(
+    state: State<'_, PasswordManagerState>,
+    id: i32,
+    title: String,
+    content: String,
+) -> Result<(), String> {
+    let state = state.0.lock().unwrap();
+    let pm = state.as_ref().ok_or("Not logged in")?;
+
+    pm.update_secure_note(id, &title, &content).map_err(|e| {
+        error!("Failed to update secure note {}: {}", id, e);
+        e.to_string()
+    })?;
+    info!("Updated secure note {}", id);
+
+    Ok(())
+}
+
+#[tauri::command]
+/// Delete a secure note.
+///
+/// # Arguments
+///
+/// * `id` - The ID of the note.
+///
+/// # Returns
+///
+/// A Result containing a unit or an error.
+///
+/// # Errors
+///
+/// If not logged in or the note cannot be deleted.
+pub async fn delete_secure_note(
+    state: State<'_, PasswordManagerState>,
+    id: i32,
+) -> Result<(), String> {
+    let state = state.0.lock().unwrap();
+    let pm = state.as_ref().ok_or("Not logged in")?;
+
+    pm.delete_secure_note(id).map_err(|e| {
+        error!("Failed to delete secure note {}: {}", id, e);
+        e.to_string()
+    })?;
+    info!("Deleted secure note {}", id);
+
+    Ok(())
+}
+
+#[tauri::command]
+/// Get the payment cards of the logged in user.
+///
+/// # Returns
+///
+/// A Result containing the payment cards sorted by title or an error.
+///
+/// # Errors
+///
+/// If not logged in or the payment cards cannot be read.
+pub async fn get_payment_cards(
+    state: State<'_, PasswordManagerState>,
+) -> Result<Vec<PaymentCard>, String> {
+    let state = state.0.lock().unwrap();
+    let pm = state.as_ref().ok_or("Not logged in")?;
+
+    pm.get_payment_cards().map_err(|e| e.to_string())
+}
+
+#[tauri::command]
+/// Add a payment card.
+///
+/// # Arguments
+///
+/// * `card` - The payment card to add.
+///
+/// # Returns
+///
+/// A Result containing the ID of the new payment card or an error.
+///
+/// # Errors
+///
+/// If not logged in, the payment card is invalid or cannot be saved.
+pub async fn add_payment_card(
+    state: State<'_, PasswordManagerState>,
+    card: PaymentCard,
+) -> Result<i32, String> {
+    let state = state.0.lock().unwrap();
+    let pm = state.as_ref().ok_or("Not logged in")?;
+
+    let id = pm.add_payment_card(&card).map_err(|e| {
+        error!("Failed to add payment card: {}", e);
+        e.to_string()
+    })?;
+    info!("Added payment card {}", id);
+
+    Ok(id)
+}
+
+#[tauri::command]
+/// Replace the fields of a payment card.
+///
+/// # Arguments
+///
+/// * `card` - The payment card with the ID of the payment card to replace.
+///
+/// # Returns
+///
+/// A Result containing a unit or an error.
+///
+/// # Errors
+///
+/// If not logged in, the payment card is invalid or does not exist.
+pub async fn update_payment_card(
+    state: State<'_, PasswordManagerState>,
+    card: PaymentCard,
+) -> Result<(), String> {
+    let state = state.0.lock().unwrap();
+    let pm = state.as_ref().ok_or("Not logged in")?;
+
+    pm.update_payment_card(&card).map_err(|e| {
+        error!("Failed to update payment card {}: {}", card.id, e);
+        e.to_string()
+    })?;
+    info!("Updated payment card {}", card.id);
+
+    Ok(())
+}
+
+#[tauri::command]
+/// Delete a payment card.
+///
+/// # Arguments
+///
+/// * `id` - The ID of the payment card.
+///
+/// # Returns
+///
+/// A Result containing a unit or an error.
+///
+/// # Errors
+///
+/// If not logged in or the payment card cannot be deleted.
+pub async fn delete_payment_card(
+    state: State<'_, PasswordManagerState>,
+    id: i32,
+) -> Result<(), String> {
+    let state = state.0.lock().unwrap();
+    let pm = state.as_ref().ok_or("Not logged in")?;
+
+    pm.delete_payment_card(id).map_err(|e| {
+        error!("Failed to delete payment card {}: {}", id, e);
+        e.to_string()
+    })?;
+    info!("Deleted payment card {}", id);
+
+    Ok(())
+}
+
+#[tauri::command]
+/// Get the identitys of the logged in user.
+///
+/// # Returns
+///
+/// A Result containing the identitys sorted by title or an error.
+///
+/// # Errors
+///
+/// If not logged in or the identitys cannot be read.
+pub async fn get_identities(
+    state: State<'_, PasswordManagerState>,
+) -> Result<Vec<Identity>, String> {
+    let state = state.0.lock().unwrap();
+    let pm = state.as_ref().ok_or("Not logged in")?;
+
+    pm.get_identities().map_err(|e| e.to_string())
+}
+
+#[tauri::command]
+/// Add an identity.
+///
+/// # Arguments
+///
+/// * `identity` - The identity to add.
+///
+/// # Returns
+///
+/// A Result containing the ID of the new identity or an error.
+///
+/// # Errors
+///
+/// If not logged in, the identity is invalid or cannot be saved.
+pub async fn add_identity(
+    state: State<'_, PasswordManagerState>,
+    identity: Identity,
+) -> Result<i32, String> {
+    let state = state.0.lock().unwrap();
+    let pm = state.as_ref().ok_or("Not logged in")?;
+
+    let id = pm.add_identity(&identity).map_err(|e| {
+        error!("Failed to add identity: {}", e);
+        e.to_string()
+    })?;
+    info!("Added identity {}", id);
+
+    Ok(id)
+}
+
+#[tauri::command]
+/// Replace the fields of an identity.
+///
+/// # Arguments
+///
+/// * `identity` - The identity with the ID of the identity to replace.
+///
+/// # Returns
+///
+/// A Result containing a unit or an error.
+///
+/// # Errors
+///
+/// If not logged in, the identity is invalid or does not exist.
+pub async fn update_identity(
+    state: State<'_, PasswordManagerState>,
+    identity: Identity,
+) -> Result<(), String> {
+    let state = state.0.lock().unwrap();
+    let pm = state.as_ref().ok_or("Not logged in")?;
+
+    pm.update_identity(&identity).map_err(|e| {
+        error!("Failed to update identity {}: {}", identity.id, e);
+        e.to_string()
+    })?;
+    info!("Updated identity {}", identity.id);
+
+    Ok(())
+}
+
+#[tauri::command]
+/// Delete an identity.
+///
+/// # Arguments
+///
+/// * `id` - The ID of the identity.
+///
+/// # Returns
+///
+/// A Result containing a unit or an error.
+///
+/// # Errors
+///
+/// If not logged in or the identity cannot be deleted.
+pub async fn delete_identity(
+    state: State<'_, PasswordManagerState>,
+    id: i32,
+) -> Result<(), String> {
+    let state = state.0.lock().unwrap();
+    let pm = state.as_ref().ok_or("Not logged in")?;
+
+    pm.delete_identity(id).map_err(|e| {
+        error!("Failed to delete identity {}: {}", id, e);
+        e.to_string()
+    })?;
+    info!("Deleted identity {}", id);
+
+    Ok(())
+}
+
+#[tauri::command]
+/// Get the servers of the logged in user.
+///
+/// # Returns
+///
+/// A Result containing the servers sorted by title or an error.
+///
+/// # Errors
+///
+/// If not logged in or the servers cannot be read.
+pub async fn get_servers(state: State<'_, PasswordManagerState>) -> Result<Vec<Server>, String> {
+    let state = state.0.lock().unwrap();
+    let pm = state.as_ref().ok_or("Not logged in")?;
+
+    pm.get_servers().map_err(|e| e.to_string())
+}
+
+#[tauri::command]
+/// Add a server.
+///
+/// # Arguments
+///
+/// * `server` - The server to add.
+///
+/// # Returns
+///
+/// A Result containing the ID of the new server or an error.
+///
+/// # Errors
+///
+/// If not logged in, the server is invalid or cannot be saved.
+pub async fn add_server(
+    state: State<'_, PasswordManagerState>,
+    server: Server,
+) -> Result<i32, String> {
+    let state = state.0.lock().unwrap();
+    let pm = state.as_ref().ok_or("Not logged in")?;
+
+    let id = pm.add_server(&server).map_err(|e| {
+        error!("Failed to add server: {}", e);
+        e.to_string()
+    })?;
+    info!("Added server {}", id);
+
+    Ok(id)
+}
+
+#[tauri::command]
+/// Replace the fields of a server.
+///
+/// # Arguments
+///
+/// * `server` - The server with the ID of the server to replace.
+///
+/// # Returns
+///
+/// A Result containing a unit or an error.
+///
+/// # Errors
+///
+/// If not logged in, the server is invalid or does not exist.
+pub async fn update_server(
+    state: State<'_, PasswordManagerState>,
+    server: Server,
+) -> Result<(), String> {
+    let state = state.0.lock().unwrap();
+    let pm = state.as_ref().ok_or("Not logged in")?;
+
+    pm.update_server(&server).map_err(|e| {
+        error!("Failed to update server {}: {}", server.id, e);
+        e.to_string()
+    })?;
+    info!("Updated server {}", server.id);
+
+    Ok(())
+}
+
+#[tauri::command]
+/// Delete a server.
+///
+/// # Arguments
+///
+/// * `id` - The ID of the server.
+///
+/// # Returns
+///
+/// A Result containing a unit or an error.
+///
+/// # Errors
+///
+/// If not logged in or the server cannot be deleted.
+pub async fn delete_server(state: State<'_, PasswordManagerState>, id: i32) -> Result<(), String> {
+    let state = state.0.lock().unwrap();
+    let pm = state.as_ref().ok_or("Not logged in")?;
+
+    pm.delete_server(id).map_err(|e| {
+        error!("Failed to delete server {}: {}", id, e);
+        e.to_string()
+    })?;
+    info!("Deleted server {}", id);
+
+    Ok(())
+}
+
+#[tauri::command]
+/// Copy the connection string of a server, e.g. `ssh -p 2222 admin@nas`, to the clipboard.
+///
+/// # Arguments
+///
+/// * `id` - The ID of the server.
+///
+/// # Returns
+///
+/// A Result containing the applied clipboard protection of the platform or an error.
+///
+/// # Errors
+///
+/// If not logged in, the server does not exist or cannot be copied to the clipboard.
+pub async fn copy_server_connection(
+    state: State<'_, PasswordManagerState>,
+    clipboard: State<'_, SecureClipboard>,
+    id: i32,
+) -> Result<ClipboardProtection, String> {
+    let connection = {
+        let state = state.0.lock().unwrap();
+        let pm = state.as_ref().ok_or("Not logged in")?;
+        pm.server_connection(id).map_err(|e| e.to_string())?
+    };
+
+    clipboard.copy(&connection).map_err(|e| {
+        error!("Failed to copy to clipboard: {}", e);
+        e.to_string()
+    })
+}
+
+#[tauri::command]
+/// Copy the SSH key linked to a server to the clipboard.
+///
+/// # Arguments
+///
+/// * `id` - The ID of the server.
+///
+/// # Returns
+///
+/// A Result containing the applied clipboard protection of the platform or an error.
+///
+/// # Errors
+///
+/// If not logged in, the server has no SSH key, the decrypt limit is reached or the key
+/// cannot be copied to the clipboard.
+pub async fn copy_server_key(
+    state: State<'_, PasswordManagerState>,
+    clipboard: State<'_, SecureClipboard>,
+    id: i32,
+) -> Result<ClipboardProtection, String> {
+    let key = {
+        let state = state.0.lock().unwrap();
+        let pm = state.as_ref().ok_or("Not logged in")?;
+        pm.server_key(id).map_err(|e| {
+            error!("Failed to read the SSH key of server {}: {}", id, e);
+            e.to_string()
+        })?
+    };
+    info!("Copied the SSH key of server {}", id);
+
+    clipboard.copy(&key).map_err(|e| {
+        error!("Failed to copy to clipboard: {}", e);
+        e.to_string()
+    })
+}
+
+#[tauri::command]
+/// Store the TOTP secret of a password entry.
+///
+/// # Arguments
+///
+/// * `id` - The ID of the password entry.
+/// * `secret` - The base32 secret or `otpauth://` URI, empty to remove the secret.
+///
+/// # Returns
+///
+/// A Result containing the completion status or an error.
+///
+/// # Errors
+///
+/// If not logged in, the secret is invalid or it cannot be saved.
+pub async fn set_totp_secret(
+    state: State<'_, PasswordManagerState>,
+    id: i32,
+    secret: String,
+) -> Result<(), String> {
+    let state = state.0.lock().unwrap();
+    let pm = state.as_ref().ok_or("Not logged in")?;
+
+    pm.set_totp_secret(id, &secret).map_err(|e| {
+        error!("Failed to save TOTP secret of entry {}: {}", id, e);
+        e.to_string()
+    })?;
+    info!("Saved TOTP secret for entry {}", id);
+
+    Ok(())
+}
+
+#[tauri::command(rename_all = "camelCase")]
+/// Compute the current TOTP code of a password entry.
+///
+/// # Arguments
+///
+/// * `id` - The ID of the password entry.
+/// * `master_pass` - The master password, required for entries that are protected by it.
+///
+/// # Returns
+///
+/// A Result containing the code and the seconds it stays valid or an error.
+///
+/// # Errors
+///
+/// If not logged in, the entry has no secret or the master password is missing or wrong.
+pub async fn get_totp_code(
+    state: State<'_, PasswordManagerState>,
+    id: i32,
+    master_pass: Option<String>,
+) -> Result<TotpCode, String> {
+    let state = state.0.lock().unwrap();
+    let pm = state.as_ref().ok_or("Not logged in")?;
+
+    pm.get_totp_code(id, master_pass.as_deref())
+        .map_err(|e| e.to_string())
+}
+
+#[tauri::command]
+/// Mark a recovery code of a password entry as used or unused.
+///
+/// # Arguments
+///
+/// * `id` - The ID of the password entry.
+/// * `index` - The position of the code in the list.
+/// * `used` - Whether the code was used.
+/// * `master_pass` - The master password, required if the entry requires it.
+///
+/// # Returns
+///
+/// A Result containing the number of used and unused codes or an error.
+///
+/// # Errors
+///
+/// If not logged in, the entry is locked, the master password is wrong, the code does not
+/// exist or the codes cannot be saved.
+pub async fn mark_recovery_code(
+    state: State<'_, PasswordManagerState>,
+    id: i32,
+    index: usize,
+    used: bool,
+    master_pass: Option<String>,
+) -> Result<RecoveryCodeCount, String> {
+    let state = state.0.lock().unwrap();
+    let pm = state.as_ref().ok_or("Not logged in")?;
+
+    pm.mark_recovery_code(id, index, used, master_pass.as_deref())
+        .map_err(|e| e.to_string())
+}
+
+#[tauri::command]
+/// Share a password entry read-only with another user of the database.
+///
+/// # Arguments
+///
+/// * `id` - The ID of the password entry.
+/// * `username` - The name of the user to share the entry with.
+///
+/// # Returns
+///
+/// A Result containing the completion status or an error.
+///
+/// # Errors
+///
+/// If not logged in, the entry belongs to another user or the user does not exist.
+pub async fn share_entry(
+    state: State<'_, PasswordManagerState>,
+    id: i32,
+    username: String,
+) -> Result<(), String> {
+    VaultService::new(&state).share_entry(id, &username)
+}
+
+#[tauri::command]
+/// Stop sharing a password entry with another user.
+///
+/// # Arguments
+///
+/// * `id` - The ID of the password entry.
+/// * `username` - The name of the user the entry is shared with.
+///
+/// # Returns
+///
+/// A Result containing the completion status or an error.
+///
+/// # Errors
+///
+/// If not logged in, the entry belongs to another user or the user does not exist.
+pub async fn unshare_entry(
+    state: State<'_, PasswordManagerState>,
+    id: i32,
+    username: String,
+) -> Result<(), String> {
+    VaultService::new(&state).unshare_entry(id, &username)
+}
+
+#[tauri::command]
+/// Open the URL of a password entry in the default browser.
+///
+/// # Arguments
+///
+/// * `id` - The ID of the password entry.
+///
+/// # Returns
+///
+/// A Result containing the completion status or an error.
+///
+/// # Errors
+///
+/// If not logged in, the entry has no web URL or the browser cannot be opened.
+pub async fn open_entry_url(
+    app: AppHandle,
+    state: State<'_, PasswordManagerState>,
+    id: i32,
+) -> Result<(), String> {
+    let url = {
+        let state = state.0.lock().unwrap();
+        let pm = state.as_ref().ok_or("Not logged in")?;
+
+        pm.get_entry_url(id).map_err(|e| e.to_string())?
+    };
+
+    app.opener()
+        .open_url(url, None::<&str>)
+        .map_err(|e| e.to_string())
+}
+
+#[tauri::command]
+/// Pin a password entry to the tray menu.
+///
+/// # Arguments
+///
+/// * `id` - The ID of the password entry.
+///
+/// # Returns
+///
+/// A Result containing the completion status or an error.
+///
+/// # Errors
+///
+/// If not logged in, the entry requires the master password or too many entries are pinned.
+pub async fn pin_entry(
+    app: AppHandle,
+    state: State<'_, PasswordManagerState>,
+    id: i32,
+) -> Result<(), String> {
+    {
+        let state = state.0.lock().unwrap();
+        let pm = state.as_ref().ok_or("Not logged in")?;
+
+        pm.pin_entry(id).map_err(|e| e.to_string())?;
+    }
+    refresh_tray_menu(&app);
+
+    Ok(())
+}
+
+#[tauri::command]
+/// Remove a password entry from the tray menu.
+///
+/// # Arguments
+///
+/// * `id` - The ID of the password entry.
+///
+/// # Returns
+///
+/// A Result containing the completion status or an error.
+///
+/// # Errors
+///
+/// If not logged in or the pin cannot be removed.
+pub async fn unpin_entry(
+    app: AppHandle,
+    state: State<'_, PasswordManagerState>,
+    id: i32,
+) -> Result<(), String> {
+    {
+        let state = state.0.lock().unwrap();
+        let pm = state.as_ref().ok_or("Not logged in")?;
+
+        pm.unpin_entry(id).map_err(|e| e.to_string())?;
+    }
+    refresh_tray_menu(&app);
+
+    Ok(())
+}
+
+#[tauri::command]
+/// Get the IDs of the entries pinned to the tray menu.
+///
+/// # Returns
+///
+/// A Result containing the IDs of the pinned entries or an error.
+///
+/// # Errors
+///
+/// If not logged in or the pinned entries cannot be retrieved.
+pub async fn get_pinned_entries(
+    state: State<'_, PasswordManagerState>,
+) -> Result<Vec<i32>, String> {
+    let state = state.0.lock().unwrap();
+    let pm = state.as_ref().ok_or("Not logged in")?;
+
+    Ok(pm
+        .get_pinned_entries()
+        .map_err(|e| e.to_string())?
+        .into_iter()
+        .filter_map(|e| e.id)
+        .collect())
+}
+
+#[tauri::command]
+/// Update a password.
+///
+/// # Arguments
+///
+/// * `id` - The ID of the password entry to update.
+/// * `service` - The service for which the password is used.
+/// * `username` - The username for the service.
+/// * `password` - The new password for the service, the current one is kept if None.
+/// * `url` - The URL of the service.
+/// * `notes` - Optional notes for the password.
+/// * `require_master` - Whether the master password is required to reveal the password, the
+///   current setting is kept if None.
+/// * `master_pass` - The master password, required to remove the protection of the entry.
+///
+/// # Returns
+///
+/// A Result containing the completion status or an error.
+///
+/// # Errors
+///
+/// If the protection is removed without the correct master password or the password cannot
+/// be updated.
+#[allow(clippy::too_many_arguments)]
+pub async fn update_password(
+    state: State<'_, PasswordManagerState>,
+    id: i32,
+    service: String,
+    username: String,
+    password: Option<String>,
+    url: String,
+    notes: Option<String>,
+    require_master: Option<bool>,
+    master_pass: Option<String>,
+) -> Result<(), String> {
+    VaultService::new(&state).update_entry(
+        id,
+        EntryRequest {
+            service,
+            username,
+            password,
+            url,
+            notes,
+            require_master,
+            totp_secret: None,
+        },
+        master_pass.as_deref(),
+    )
+}
+
+#[tauri::command]
+/// Move a password to the trash.
+///
+/// # Arguments
+///
+/// * `id` - The ID of the password entry to delete.
+///
+/// # Returns
+///
+/// A Result containing the completion status or an error.
+///
+/// # Errors
+///
+/// If the password cannot be deleted.
+pub async fn delete_password(
+    app_handle: AppHandle,
+    state: State<'_, PasswordManagerState>,
+    id: i32,
+) -> Result<(), String> {
+    let confirmed = app_handle
+        .dialog()
+        .message("Möchten Sie dieses Passwort in den Papierkorb verschieben?")
+        .title("Passwort löschen")
+        .kind(MessageDialogKind::Info)
+        .buttons(MessageDialogButtons::YesNo)
+        .blocking_show();
+
+    if confirmed {
+        VaultService::new(&state).delete_entry(id)
+    } else {
+        info!("Password deletion cancelled by user");
+        Err("Löschen abgebrochen".into())
+    }
+}
+
+#[tauri::command]
+/// Get the metadata of the passwords in the trash.
+///
+/// # Returns
+///
+/// A Result containing the trashed entries, most recently deleted first, or an error.
+///
+/// # Errors
+///
+/// If not logged in or the entries cannot be read.
+pub async fn get_trashed_passwords(
+    state: State<'_, PasswordManagerState>,
+) -> Result<Vec<EntrySummary>, String> {
+    let state = state.0.lock().unwrap();
+    let pm = state.as_ref().ok_or("Not logged in")?;
+
+    let trashed = pm.get_trashed_passwords().map_err(|e| e.to_string())?;
+
+    Ok(trashed.iter().map(EntrySummary::from).collect())
+}
+
+#[tauri::command]
+/// Restore a password from the trash.
+///
+/// # Arguments
+///
+/// * `id` - The ID of the password entry to restore.
+///
+/// # Returns
+///
+/// A Result containing the completion status or an error.
+///
+/// # Errors
+///
+/// If not logged in, the entry belongs to another user or is not in the trash.
+pub async fn restore_password(
+    state: State<'_, PasswordManagerState>,
+    id: i32,
+) -> Result<(), String> {
+    let state = state.0.lock().unwrap();
+    let pm = state.as_ref().ok_or("Not logged in")?;
+
+    pm.restore_password(id).map_err(|e| {
+        error!("Failed to restore password entry {}: {}", id, e);
+        e.to_string()
+    })?;
+    info!("Restored password entry {} from the trash", id);
+    record_audit(
+        AuditEvent::EntryUpdated,
+        &format!("Password entry {} restored from the trash", id),
+    );
+
+    Ok(())
+}
+
+#[tauri::command]
+/// Permanently delete all passwords in the trash.
+///
+/// # Returns
+///
+/// A Result containing the number of deleted entries or an error.
+///
+/// # Errors
+///
+/// If the user cancels, is not logged in or the entries cannot be deleted.
+pub async fn purge_trash(
+    app_handle: AppHandle,
+    state: State<'_, PasswordManagerState>,
+) -> Result<usize, String> {
+    let confirmed = app_handle
+        .dialog()
+        .message("Möchten Sie den Papierkorb endgültig leeren?")
+        .title("Papierkorb leeren")
+        .kind(MessageDialogKind::Warning)
+        .buttons(MessageDialogButtons::YesNo)
+        .blocking_show();
+    if !confirmed {
+        info!("Emptying the trash cancelled by user");
+        return Err("Leeren abgebrochen".into());
+    }
+
+    let state = state.0.lock().unwrap();
+    let pm = state.as_ref().ok_or("Not logged in")?;
+
+    let purged = pm.purge_trash().map_err(|e| {
+        error!("Failed to empty the trash: {}", e);
+        e.to_string()
+    })?;
+    info!("Purged {} password entries from the trash", purged);
+    record_audit(
+        AuditEvent::EntryDeleted,
+        &format!("{} password entries purged from the trash", purged),
+    );
+
+    Ok(purged)
+}
+
+#[tauri::command]
+/// Get the folders of the logged in user.
+///
+/// # Returns
+///
+/// A Result containing the folders sorted by name or an error.
+///
+/// # Errors
+///
+/// If not logged in or the folders cannot be read.
+pub async fn get_folders(state: State<'_, PasswordManagerState>) -> Result<Vec<Folder>, String> {
+    let state = state.0.lock().unwrap();
+    let pm = state.as_ref().ok_or("Not logged in")?;
+
+    pm.get_folders().map_err(|e| e.to_string())
+}
+
+#[tauri::command(rename_all = "camelCase")]
+/// Create a folder.
+///
+/// # Arguments
+///
+/// * `name` - The name of the folder.
+/// * `parent_id` - The folder to create it in, None for the top level.
+///
+/// # Returns
+///
+/// A Result containing the new folder or an error.
+///
+/// # Errors
+///
+/// If not logged in, the name is invalid or the parent folder does not exist.
+pub async fn create_folder(
+    state: State<'_, PasswordManagerState>,
+    name: String,
+    parent_id: Option<i32>,
+) -> Result<Folder, String> {
+    let state = state.0.lock().unwrap();
+    let pm = state.as_ref().ok_or("Not logged in")?;
+
+    let folder = pm.create_folder(&name, parent_id).map_err(|e| {
+        error!("Failed to create folder: {}", e);
+        e.to_string()
+    })?;
+    info!("Created folder {}", folder.id);
+
+    Ok(folder)
+}
+
+#[tauri::command]
+/// Rename a folder.
+///
+/// # Arguments
+///
+/// * `id` - The ID of the folder.
+/// * `name` - The new name.
+///
+/// # Returns
+///
+/// A Result containing the completion status or an error.
+///
+/// # Errors
+///
+/// If not logged in, the folder does not exist or the name is invalid.
+pub async fn rename_folder(
+    state: State<'_, PasswordManagerState>,
+    id: i32,
+    name: String,
+) -> Result<(), String> {
+    let state = state.0.lock().unwrap();
+    let pm = state.as_ref().ok_or("Not logged in")?;
+
+    pm.rename_folder(id, &name).map_err(|e| e.to_string())
+}
+
+#[tauri::command]
+/// Delete a folder, its entries and subfolders are moved into the parent folder.
+///
+/// # Arguments
+///
+/// * `id` - The ID of the folder.
+///
+/// # Returns
+///
+/// A Result containing the completion status or an error.
+///
+/// # Errors
+///
+/// If not logged in or the folder does not exist.
+pub async fn delete_folder(state: State<'_, PasswordManagerState>, id: i32) -> Result<(), String> {
+    let state = state.0.lock().unwrap();
+    let pm = state.as_ref().ok_or("Not logged in")?;
+
+    pm.delete_folder(id).map_err(|e| {
+        error!("Failed to delete folder {}: {}", id, e);
+        e.to_string()
+    })?;
+    info!("Deleted folder {}", id);
+
+    Ok(())
+}
+
+#[tauri::command(rename_all = "camelCase")]
+/// Move a password entry into a folder.
+///
+/// # Arguments
+///
+/// * `id` - The ID of the password entry.
+/// * `folder_id` - The ID of the folder, None to take the entry out of its folder.
+///
+/// # Returns
+///
+/// A Result containing the completion status or an error.
+///
+/// # Errors
+///
+/// If not logged in, the entry belongs to another user or the folder does not exist.
+pub async fn move_entry_to_folder(
+    state: State<'_, PasswordManagerState>,
+    id: i32,
+    folder_id: Option<i32>,
+) -> Result<(), String> {
+    let state = state.0.lock().unwrap();
+    let pm = state.as_ref().ok_or("Not logged in")?;
+
+    pm.move_entry_to_folder(id, folder_id)
+        .map_err(|e| e.to_string())
+}
+
+#[tauri::command]
+/// Lock or unlock a password entry against changes.
+///
+/// # Arguments
+///
+/// * `id` - The ID of the password entry.
+/// * `locked` - Whether the entry is read-only.
+///
+/// # Returns
+///
+/// A Result containing the completion status or an error.
+///
+/// # Errors
+///
+/// If not logged in or the entry belongs to another user.
+pub async fn set_entry_locked(
+    state: State<'_, PasswordManagerState>,
+    id: i32,
+    locked: bool,
+) -> Result<(), String> {
+    let state = state.0.lock().unwrap();
+    let pm = state.as_ref().ok_or("Not logged in")?;
+
+    pm.set_entry_locked(id, locked).map_err(|e| {
+        error!("Failed to change the lock of entry {}: {}", id, e);
+        e.to_string()
+    })?;
+    info!(
+        "{} entry {}",
+        if locked { "Locked" } else { "Unlocked" },
+        id
+    );
+
+    Ok(())
+}
+
+#[tauri::command(rename_all = "camelCase")]
+/// Set or remove the expiry date of a password entry.
+///
+/// # Arguments
+///
+/// * `id` - The ID of the password entry.
+/// * `expires_on` - The last day the password is valid as `YYYY-MM-DD`, None to remove it.
+///
+/// # Returns
+///
+/// A Result containing the completion status or an error.
+///
+/// # Errors
+///
+/// If not logged in, the date is invalid or the entry belongs to another user.
+pub async fn set_entry_expiry(
+    state: State<'_, PasswordManagerState>,
+    id: i32,
+    expires_on: Option<String>,
+) -> Result<(), String> {
+    let state = state.0.lock().unwrap();
+    let pm = state.as_ref().ok_or("Not logged in")?;
+
+    pm.set_entry_expiry(id, expires_on.as_deref())
+        .map_err(|e| {
+            error!("Failed to change the expiry date of entry {}: {}", id, e);
+            e.to_string()
+        })?;
+    info!("Changed the expiry date of entry {}", id);
+
+    Ok(())
+}
+
+#[tauri::command]
+/// Get the password entries that expired or expire soon.
+///
+/// # Returns
+///
+/// A Result containing the entries sorted by expiry date or an error.
+///
+/// # Errors
+///
+/// If not logged in or the entries cannot be read.
+pub async fn get_expiring_entries(
+    state: State<'_, PasswordManagerState>,
+) -> Result<Vec<EntrySummary>, String> {
+    VaultService::new(&state).expiring_entries(EXPIRY_WARNING_DAYS)
+}
+
+#[tauri::command]
+/// Get the tags of the logged in user.
+///
+/// # Returns
+///
+/// A Result containing the tags with their entries sorted by name or an error.
+///
+/// # Errors
+///
+/// If not logged in or the tags cannot be read.
+pub async fn get_tags(state: State<'_, PasswordManagerState>) -> Result<Vec<Tag>, String> {
+    let state = state.0.lock().unwrap();
+    let pm = state.as_ref().ok_or("Not logged in")?;
+
+    pm.get_tags().map_err(|e| e.to_string())
+}
+
+#[tauri::command]
+/// Create a tag.
+///
+/// # Arguments
+///
+/// * `name` - The name of the tag.
+///
+/// # Returns
+///
+/// A Result containing the new tag or an error.
+///
+/// # Errors
+///
+/// If not logged in or the name is invalid.
+pub async fn create_tag(
+    state: State<'_, PasswordManagerState>,
+    name: String,
+) -> Result<Tag, String> {
+    let state = state.0.lock().unwrap();
+    let pm = state.as_ref().ok_or("Not logged in")?;
+
+    let tag = pm.create_tag(&name).map_err(|e| {
+        error!("Failed to create tag: {}", e);
+        e.to_string()
+    })?;
+    info!("Created tag {}", tag.id);
+
+    Ok(tag)
+}
+
+#[tauri::command]
+/// Rename a tag.
+///
+/// # Arguments
+///
+/// * `id` - The ID of the tag.
+/// * `name` - The new name.
+///
+/// # Returns
+///
+/// A Result containing the completion status or an error.
+///
+/// # Errors
+///
+/// If not logged in, the tag does not exist or the name is invalid.
+pub async fn rename_tag(
+    state: State<'_, PasswordManagerState>,
+    id: i32,
+    name: String,
+) -> Result<(), String> {
+    let state = state.0.lock().unwrap();
+    let pm = state.as_ref().ok_or("Not logged in")?;
+
+    pm.rename_tag(id, &name).map_err(|e| e.to_string())
+}
+
+#[tauri::command]
+/// Delete a tag, the entries carrying it are kept.
+///
+/// # Arguments
+///
+/// * `id` - The ID of the tag.
+///
+/// # Returns
+///
+/// A Result containing the completion status or an error.
+///
+/// # Errors
+///
+/// If not logged in or the tag does not exist.
+pub async fn delete_tag(state: State<'_, PasswordManagerState>, id: i32) -> Result<(), String> {
+    let state = state.0.lock().unwrap();
+    let pm = state.as_ref().ok_or("Not logged in")?;
+
+    pm.delete_tag(id).map_err(|e| {
+        error!("Failed to delete tag {}: {}", id, e);
+        e.to_string()
+    })?;
+    info!("Deleted tag {}", id);
+
+    Ok(())
+}
+
+#[tauri::command(rename_all = "camelCase")]
+/// Add a tag to or remove it from a password entry.
+///
+/// # Arguments
+///
+/// * `id` - The ID of the password entry.
+/// * `tag_id` - The ID of the tag.
+/// * `tagged` - Whether the entry carries the tag.
+///
+/// # Returns
+///
+/// A Result containing the completion status or an error.
+///
+/// # Errors
+///
+/// If not logged in, the entry belongs to another user or the tag does not exist.
+pub async fn set_entry_tag(
+    state: State<'_, PasswordManagerState>,
+    id: i32,
+    tag_id: i32,
+    tagged: bool,
+) -> Result<(), String> {
+    let state = state.0.lock().unwrap();
+    let pm = state.as_ref().ok_or("Not logged in")?;
+
+    pm.set_entry_tag(id, tag_id, tagged)
+        .map_err(|e| e.to_string())
+}
+
+#[tauri::command]
+/// Suggest categories for entries based on their domain.
+///
+/// The rules are read from `category_rules.txt` in the config directory if it exists,
+/// otherwise the bundled rules are used.
+///
+/// # Arguments
+///
+/// * `ids` - The IDs of the entries, e.g. the ones just added or imported, all if None.
+///
+/// # Returns
+///
+/// A Result containing the suggestions or an error.
+///
+/// # Errors
+///
+/// If not logged in or the entries cannot be read.
+pub async fn get_category_suggestions(
+    state: State<'_, PasswordManagerState>,
+    ids: Option<Vec<i32>>,
+) -> Result<Vec<CategorySuggestion>, String> {
+    let categorizer = Categorizer::load(&Config::get_config_dir().map_err(|e| e.to_string())?);
+    let state = state.0.lock().unwrap();
+    let pm = state.as_ref().ok_or("Not logged in")?;
+
+    pm.category_suggestions(&categorizer, ids.as_deref())
+        .map_err(|e| e.to_string())
+}
+
+#[tauri::command]
+/// Apply reviewed category suggestions as tags or folders.
+///
+/// # Arguments
+///
+/// * `suggestions` - The suggestions accepted by the user.
+/// * `target` - Whether the categories are applied as tags or folders.
+///
+/// # Returns
+///
+/// A Result containing the number of changed entries or an error.
+///
+/// # Errors
+///
+/// If not logged in, an entry belongs to another user or a tag or folder cannot be created.
+pub async fn apply_category_suggestions(
+    state: State<'_, PasswordManagerState>,
+    suggestions: Vec<CategorySuggestion>,
+    target: CategoryTarget,
+) -> Result<usize, String> {
+    let state = state.0.lock().unwrap();
+    let pm = state.as_ref().ok_or("Not logged in")?;
+
+    pm.apply_category_suggestions(&suggestions, target)
+        .map_err(|e| e.to_string())
+}
+
+#[tauri::command]
+/// Derive the service name and favicon of a new entry from its URL.
+///
+/// The service name is the title of the page and the favicon is returned as data URL. In
+/// offline mode or if the page cannot be loaded, only the host is used as service name.
+///
+/// # Arguments
+///
+/// * `url` - The URL entered by the user, https is assumed if no scheme is given.
+///
+/// # Returns
+///
+/// A Result containing the normalized URL, the service name and the favicon or an error.
+///
+/// # Errors
+///
+/// If the URL is invalid or the config cannot be loaded.
+pub async fn create_entry_from_url(url: String) -> Result<UrlMetadata, String> {
+    let mut metadata = UrlMetadata::from_url(&url).map_err(|e| e.to_string())?;
+    let config = Config::load().map_err(|e| e.to_string())?;
+    if config.app.offline_mode {
+        return Ok(metadata);
+    }
+
+    if rustls::crypto::CryptoProvider::get_default().is_none() {
+        let _ = rustls::crypto::ring::default_provider().install_default();
+    }
+
+    let client = reqwest::Client::builder()
+        .timeout(METADATA_TIMEOUT)
+        .build()
+        .map_err(|e| e.to_string())?;
+
+    let html = match fetch_limited(&client, metadata.url.as_str(), MAX_PAGE_SIZE).await {
+        Ok((_, page)) => String::from_utf8_lossy(&page).into_owned(),
+        Err(e) => {
+            warn!(
+                "Failed to load {} for the entry metadata: {}",
+                metadata.url, e
+            );
+            return Ok(metadata);
+        }
+    };
+    metadata.apply_title(&html);
+
+    if let Some(icon_url) = metadata.icon_url(&html) {
+        match fetch_limited(&client, icon_url.as_str(), MAX_ICON_SIZE).await {
+            Ok((content_type, icon)) if icon.len() < MAX_ICON_SIZE => {
+                metadata.set_icon(&content_type, &icon)
+            }
+            Ok(_) => warn!("Favicon {} is too large", icon_url),
+            Err(e) => warn!("Failed to load favicon {}: {}", icon_url, e),
+        }
+    }
+
+    Ok(metadata)
+}
+
+/// Download at most `limit` bytes of a URL.
+///
+/// # Returns
+///
+/// A Result containing the content type and the downloaded bytes or an error.
+///
+/// # Errors
+///
+/// If the request fails or the server responds with an error status.
+async fn fetch_limited(
+    client: &reqwest::Client,
+    url: &str,
+    limit: usize,
+) -> Result<(String, Vec<u8>), reqwest::Error> {
+    let mut response = client.get(url).send().await?.error_for_status()?;
+    let content_type = response
+        .headers()
+        .get(reqwest::header::CONTENT_TYPE)
+        .and_then(|value| value.to_str().ok())
+        .unwrap_or_default()
+        .to_string();
+
+    let mut bytes = Vec::new();
+    while let Some(chunk) = response.chunk().await? {
+        bytes.extend_from_slice(&chunk);
+        if bytes.len() >= limit {
+            bytes.truncate(limit);
+            break;
+        }
+    }
+
+    Ok((content_type, bytes))
 }
@@ -1,26 +1,90 @@
 mod auth;
+mod browser;
 mod generator;
 mod health_checker;
 mod manager;
+mod repair;
 mod settings;
 mod setup;
 
-pub use auth::{login, logout, register};
-pub use generator::generate_password;
-pub use manager::{add_password, delete_password, get_passwords, update_password};
+pub use auth::{get_login_lockout, login, logout, register, start_demo};
+pub use browser::{
+    answer_browser_authorization, get_browser_settings, handle_browser_request,
+    install_browser_host, revoke_browser_site, save_browser_settings, BrowserState,
+};
+pub use generator::{clear_generator_history, generate_password, get_generator_history};
+pub use manager::{
+    add_attachment, add_expiring_note, add_identity, add_password, add_passwords, add_payment_card,
+    add_secure_note, add_server, apply_category_suggestions, copy_server_connection,
+    copy_server_key, create_entry_from_url, create_folder, create_tag, delete_attachment,
+    delete_expiring_note, delete_folder, delete_identity, delete_password, delete_payment_card,
+    delete_secure_note, delete_server, delete_tag, get_attachment, get_attachments,
+    get_category_suggestions, get_expiring_entries, get_expiring_notes, get_folders,
+    get_identities, get_passwords, get_payment_cards, get_pinned_entries, get_recovery_codes,
+    get_secure_notes, get_servers, get_tags, get_totp_code, get_trashed_passwords,
+    mark_recovery_code, move_entry_to_folder, open_entry_url, pin_entry, purge_trash,
+    quick_add_password, rename_folder, rename_tag, restore_password, search_passwords,
+    seed_demo_data, set_entry_expiry, set_entry_locked, set_entry_tag, set_recovery_codes,
+    set_totp_secret, share_entry, unpin_entry, unshare_entry, update_identity, update_password,
+    update_payment_card, update_secure_note, update_server,
+};
 pub use settings::{
-    check_update, create_backup, export_passwords, get_auto_logout_time, get_database_settings,
-    get_default_generator_length, import_passwords, is_autostart_enabled, open_log_folder,
-    restore_backup, save_app_settings, save_database_settings, save_security_settings,
-    toggle_autostart, update_master_password,
+    add_backup_recipient, apply_content_protection, apply_window_pin, check_update,
+    close_quick_search, close_quick_search_window, close_snapshot, close_snapshot_window,
+    copy_quick_search_entry, copy_snapshot_password, create_backup, create_tray,
+    decrypt_snapshot_password, detect_browser_profiles, detect_cloud_sync, export_audit_log,
+    export_encrypted, export_passwords, export_settings, extend_session, generate_backup_keypair,
+    get_auto_lock_settings, get_auto_logout_time, get_autostart_mode, get_backup_recipients,
+    get_breach_check, get_content_protection, get_crypto_info, get_database_settings,
+    get_database_stats, get_decrypt_limit, get_default_generator_length, get_expiry_reminder,
+    get_generator_defaults, get_key_info, get_launch_view, get_minimize_to_tray, get_offline_mode,
+    get_quick_search_shortcut, get_rpc_enabled, get_session_max_lifetime, get_snapshot,
+    get_soft_keyboard, get_view_settings, get_window_pin, handle_auto_lock, has_confirmation_pin,
+    hide_to_tray, import_browser_export, import_otp_secrets, import_passwords,
+    import_passwords_from_text, import_settings, is_autostart_enabled, list_backups,
+    migrate_field_encryption, open_generator_window, open_log_folder, open_snapshot,
+    prepare_export, preview_csv_import, preview_otp_import, prune_backups, quick_search,
+    quick_search_shortcut_plugin, refresh_tray_menu, register_quick_search_shortcut,
+    remove_backup_recipient, restore_backup, rotate_vault_keys, sandbox_restore, save_app_settings,
+    save_database_settings, save_last_view, save_security_settings, set_always_on_top,
+    set_breach_check, set_confirmation_pin, set_expiry_reminder, set_metadata_encryption,
+    set_minimize_to_tray, set_offline_mode, set_quick_search_shortcut, set_rpc_enabled,
+    set_window_opacity_percent, start_in_tray, toggle_autostart, update_common_passwords,
+    update_master_password, watch_session, watch_system_activity, AutoLockSettings, AutoLockState,
+    LaunchState, SnapshotState, AUTOSTART_ARG, QUICK_SEARCH_WINDOW, SNAPSHOT_WINDOW,
 };
 
+pub use repair::{
+    choose_backup_location, choose_database_location, delete_vault, get_startup_issues,
+    regenerate_config,
+};
 pub use setup::{complete_setup, get_default_config};
 
-pub use health_checker::check_passwords;
+pub use health_checker::{
+    cached_health, check_breached_passwords, check_passwords, get_cached_health,
+    get_duplicate_groups, get_overall_score, get_trends, get_unlock_summary, ExportFilter,
+    HealthCache,
+};
 
+use log::error;
 use std::sync::Mutex;
 
-use crate::PasswordManager;
+use crate::utils::{AuditEvent, AuditLog};
+use crate::{Config, PasswordManager};
 
 pub struct PasswordManagerState(pub Mutex<Option<PasswordManager>>);
+
+/// Append an event to the audit log and log failures instead of failing the command.
+///
+/// # Arguments
+///
+/// * `event` - The event to record.
+/// * `details` - A short description of the event.
+pub fn record_audit(event: AuditEvent, details: &str) {
+    let result =
+        Config::get_config_dir().and_then(|dir| AuditLog::new(&dir).record(event, details));
+
+    if let Err(e) = result {
+        error!("Failed to write audit log: {}", e);
+    }
+}
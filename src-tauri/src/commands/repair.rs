@@ -0,0 +1,157 @@
+use std::fs;
+
+use chrono::Utc;
+use log::info;
+use tauri::{AppHandle, State};
+use tauri_plugin_dialog::DialogExt;
+
+use crate::{
+    commands::{refresh_tray_menu, HealthCache, PasswordManagerState},
+    service::VaultService,
+    utils::{DeletionReport, StartupCheck, StartupIssue},
+    Config,
+};
+
+#[tauri::command]
+/// Check the installation for missing or inconsistent files.
+///
+/// # Returns
+///
+/// A Result containing the found issues or an error.
+///
+/// # Errors
+///
+/// If the config directory cannot be found.
+pub async fn get_startup_issues() -> Result<Vec<StartupIssue>, String> {
+    let config_dir = Config::get_config_dir().map_err(|e| e.to_string())?;
+
+    Ok(StartupCheck::new(&config_dir).run())
+}
+
+#[tauri::command]
+/// Replace the config file with the default configuration.
+///
+/// The broken config file is kept next to the new one. If the vault salt still exists the
+/// app stays initialized, so the database path can be chosen afterwards.
+///
+/// # Returns
+///
+/// A Result containing the completion status or an error.
+///
+/// # Errors
+///
+/// If the config file cannot be moved or written.
+pub async fn regenerate_config() -> Result<(), String> {
+    let config_dir = Config::get_config_dir().map_err(|e| e.to_string())?;
+    let config_path = config_dir.join("config.toml");
+
+    if config_path.exists() {
+        let broken_path = config_dir.join(format!(
+            "config.toml.broken_{}",
+            Utc::now().format("%Y%m%d_%H%M%S")
+        ));
+        fs::rename(&config_path, &broken_path).map_err(|e| e.to_string())?;
+        info!("Moved broken config to {}", broken_path.display());
+    }
+
+    let mut config = Config::default();
+    config.app.is_initialized = config_dir.join(".salt").exists();
+    config.save().map_err(|e| e.to_string())?;
+
+    Ok(())
+}
+
+#[tauri::command]
+/// Choose the location of an existing database file.
+///
+/// # Returns
+///
+/// A Result containing the completion status or an error.
+///
+/// # Errors
+///
+/// If no file is selected or the config cannot be saved.
+pub async fn choose_database_location(app: AppHandle) -> Result<(), String> {
+    let db_file = app
+        .dialog()
+        .file()
+        .set_title("Datenbank auswählen")
+        .add_filter("KarnCrypt Datenbank", &["db"])
+        .blocking_pick_file()
+        .ok_or("Keine Datenbank ausgewählt")?;
+    let db_file = db_file.as_path().ok_or("Ungültiger Dateipfad")?;
+
+    let db_path = db_file.parent().ok_or("Ungültiger Dateipfad")?;
+    let db_name = db_file
+        .file_name()
+        .and_then(|name| name.to_str())
+        .ok_or("Ungültiger Dateiname")?;
+
+    let mut config = Config::load().map_err(|e| e.to_string())?;
+    config.database.db_path = db_path.to_path_buf();
+    config.database.db_name = db_name.to_string();
+    config.save().map_err(|e| e.to_string())?;
+    info!("Database location changed to {}", db_file.display());
+
+    Ok(())
+}
+
+#[tauri::command]
+/// Choose a new backup folder.
+///
+/// # Returns
+///
+/// A Result containing the completion status or an error.
+///
+/// # Errors
+///
+/// If no folder is selected or the config cannot be saved.
+pub async fn choose_backup_location(app: AppHandle) -> Result<(), String> {
+    let backup_path = app
+        .dialog()
+        .file()
+        .set_title("Backup-Ordner auswählen")
+        .blocking_pick_folder()
+        .ok_or("Kein Ordner ausgewählt")?;
+    let backup_path = backup_path.as_path().ok_or("Ungültiger Ordnerpfad")?;
+
+    let mut config = Config::load().map_err(|e| e.to_string())?;
+    config.backup.backup_path = backup_path.to_path_buf();
+    config.save().map_err(|e| e.to_string())?;
+    info!("Backup location changed to {}", backup_path.display());
+
+    Ok(())
+}
+
+#[tauri::command(rename_all = "camelCase")]
+/// Delete the vault and its key parameters, so the app can be set up again.
+///
+/// The session ends and the database is closed before its files are removed. Backups and
+/// exports are kept.
+///
+/// # Arguments
+///
+/// * `master_pass` - The master password.
+/// * `overwrite` - Whether to overwrite the files with random data before removing them.
+///
+/// # Returns
+///
+/// A Result containing the removed files and why their data might still be recoverable on
+/// this platform.
+///
+/// # Errors
+///
+/// If not logged in, the master password is wrong or a file cannot be removed.
+pub async fn delete_vault(
+    app: AppHandle,
+    state: State<'_, PasswordManagerState>,
+    health_cache: State<'_, HealthCache>,
+    master_pass: String,
+    overwrite: bool,
+) -> Result<DeletionReport, String> {
+    let report = VaultService::new(&state).delete_vault(&master_pass, overwrite)?;
+    health_cache.clear();
+    refresh_tray_menu(&app);
+
+    Ok(report)
+}
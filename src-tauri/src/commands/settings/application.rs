@@ -1,10 +1,26 @@
 use serde::Serialize;
+use std::fs;
+use tauri::{AppHandle, State};
+use tauri_plugin_dialog::DialogExt;
 
-use crate::Config;
+use super::{apply_content_protection, AutoLockState};
+use crate::{
+    commands::PasswordManagerState,
+    service::VaultService,
+    utils::{
+        Collation, DefaultTab, GeneratorConfig, GeneratorOptions, RowAction, SortOrder, ViewConfig,
+    },
+    Config,
+};
 
 #[derive(Serialize)]
 pub struct AppSettingsConfig {
     default_length: usize,
+    max_notes_length: usize,
+    trash_retention_days: u32,
+    max_attachment_kb: u64,
+    attachment_extensions: Vec<String>,
+    attachment_scanner: Option<String>,
 }
 
 #[tauri::command]
@@ -22,14 +38,155 @@ pub async fn get_default_generator_length() -> Result<AppSettingsConfig, String>
 
     Ok(AppSettingsConfig {
         default_length: config.generator.default_length,
+        max_notes_length: config.entry.max_notes_length,
+        trash_retention_days: config.entry.trash_retention_days,
+        max_attachment_kb: config.entry.max_attachment_kb,
+        attachment_extensions: config.entry.attachment_extensions,
+        attachment_scanner: config.entry.attachment_scanner,
     })
 }
 
+#[tauri::command]
+/// Get the defaults of the password generator.
+///
+/// # Returns
+///
+/// A Result containing the default length and generator options or an error.
+///
+/// # Errors
+///
+/// If the config cannot be loaded.
+pub async fn get_generator_defaults() -> Result<GeneratorConfig, String> {
+    let config = Config::load().map_err(|e| e.to_string())?;
+
+    Ok(config.generator)
+}
+
+#[tauri::command]
+/// Get the default view after unlocking the vault.
+///
+/// # Returns
+///
+/// A Result containing the default tab and sort order or an error.
+///
+/// # Errors
+///
+/// If the config cannot be loaded.
+pub async fn get_view_settings() -> Result<ViewConfig, String> {
+    let config = Config::load().map_err(|e| e.to_string())?;
+
+    Ok(config.view)
+}
+
 #[tauri::command(rename_all = "camelCase")]
-pub async fn save_app_settings(default_length: usize) -> Result<(), String> {
+#[allow(clippy::too_many_arguments)]
+pub async fn save_app_settings(
+    default_length: usize,
+    max_notes_length: usize,
+    trash_retention_days: u32,
+    max_attachment_kb: u64,
+    attachment_extensions: Vec<String>,
+    attachment_scanner: Option<String>,
+    default_tab: DefaultTab,
+    default_sort: SortOrder,
+    double_click: RowAction,
+    generator_options: GeneratorOptions,
+    generator_history_size: usize,
+    locale: String,
+    state: State<'_, PasswordManagerState>,
+) -> Result<(), String> {
+    let collation = Collation::new(&locale).map_err(|e| e.to_string())?;
+    generator_options
+        .check_custom_symbols()
+        .map_err(|e| e.to_string())?;
+
     let mut config = Config::load().map_err(|e| e.to_string())?;
     config.generator.default_length = default_length;
+    config.generator.options = generator_options;
+    config.generator.history_size = generator_history_size;
+    config.entry.max_notes_length = max_notes_length;
+    config.entry.trash_retention_days = trash_retention_days;
+    config.entry.max_attachment_kb = max_attachment_kb;
+    config.entry.attachment_extensions = attachment_extensions
+        .iter()
+        .map(|extension| extension.trim().trim_start_matches('.').to_lowercase())
+        .filter(|extension| !extension.is_empty())
+        .collect();
+    config.entry.attachment_scanner = attachment_scanner
+        .map(|command| command.trim().to_string())
+        .filter(|command| !command.is_empty());
+    config.view.default_tab = default_tab;
+    config.view.default_sort = default_sort;
+    config.view.double_click = double_click;
+    config.view.locale = locale;
     config.save().map_err(|e| e.to_string())?;
 
+    VaultService::new(&state).apply_collation(collation);
+
+    Ok(())
+}
+
+#[tauri::command]
+/// Export the application settings to a file.
+///
+/// The file contains no passwords or keys and leaves out machine specific paths, so it can
+/// be imported on another machine.
+///
+/// # Returns
+///
+/// A Result containing the path of the settings file or an error.
+///
+/// # Errors
+///
+/// If no file was selected or the settings cannot be written.
+pub async fn export_settings(app: AppHandle) -> Result<String, String> {
+    let config = Config::load().map_err(|e| e.to_string())?;
+    let settings = config.export_settings().map_err(|e| e.to_string())?;
+
+    let file = app
+        .dialog()
+        .file()
+        .add_filter("KarnCrypt Einstellungen", &["toml"])
+        .set_file_name("karncrypt_settings.toml")
+        .blocking_save_file()
+        .ok_or("Keine Datei ausgewählt")?;
+    let path = file.as_path().ok_or("Ungültiger Dateipfad")?;
+
+    fs::write(path, settings).map_err(|e| e.to_string())?;
+
+    Ok(path.display().to_string())
+}
+
+#[tauri::command]
+/// Import application settings from a file exported on another machine.
+///
+/// # Returns
+///
+/// A Result containing the completion status or an error.
+///
+/// # Errors
+///
+/// If no file was selected or the file contains invalid settings.
+pub async fn import_settings(
+    app: AppHandle,
+    auto_lock: State<'_, AutoLockState>,
+) -> Result<(), String> {
+    let file = app
+        .dialog()
+        .file()
+        .add_filter("KarnCrypt Einstellungen", &["toml"])
+        .blocking_pick_file()
+        .ok_or("Keine Datei ausgewählt")?;
+    let path = file.as_path().ok_or("Ungültiger Dateipfad")?;
+    let content = fs::read_to_string(path).map_err(|e| e.to_string())?;
+
+    let config = Config::load()
+        .and_then(|config| config.import_settings(&content))
+        .map_err(|e| e.to_string())?;
+    config.save().map_err(|e| e.to_string())?;
+
+    apply_content_protection(&app, config.app.content_protection);
+    auto_lock.reload(&config);
+
     Ok(())
 }
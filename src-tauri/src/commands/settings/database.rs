@@ -1,13 +1,103 @@
-use std::{fs, path::PathBuf};
+use std::{
+    fs,
+    path::{Path, PathBuf},
+    sync::Mutex,
+};
 
 use log::{error, info};
-use tauri::{AppHandle, State};
+use serde::Serialize;
+use tauri::{AppHandle, Manager, State, WebviewUrl, WebviewWindowBuilder};
 use tauri_plugin_dialog::{DialogExt, MessageDialogButtons, MessageDialogKind};
 
-use crate::{commands::PasswordManagerState, BackupManager, Config};
+use chrono::{NaiveDate, Utc};
+
+use crate::{
+    commands::{cached_health, record_audit, ExportFilter, HealthCache, PasswordManagerState},
+    service::{DatabaseStats, ImportSummary, OtpMapping, OtpPreview, VaultService},
+    utils::{
+        detect_cloud_provider, AuditEvent, AuditExportFormat, BackupInfo, BackupNaming,
+        BackupPreview, BackupRecipient, BackupSnapshot, BrowserKind, BrowserProfile,
+        ClipboardProtection, CloudProvider, CsvLayout, CsvPreview, CsvProfile, ExportFormat,
+        ExportScope, ImportFormat, OtpFormat, RecipientEncryption, RecipientKeyPair,
+        SecureClipboard, SnapshotEntry, EXPORT_EXTENSION,
+    },
+    BackupManager, Config,
+};
+
+/// Determine the scope of an export and the entries it leaves out.
+fn export_scope(
+    state: &State<'_, PasswordManagerState>,
+    cache: &State<'_, HealthCache>,
+    format: ExportFormat,
+    filter: ExportFilter,
+) -> Result<(ExportScope, Vec<i32>), String> {
+    let excluded: Vec<i32> = if format == ExportFormat::Encrypted || filter == ExportFilter::All {
+        Vec::new()
+    } else {
+        cached_health(state, cache)?
+            .passwords
+            .iter()
+            .filter(|password| filter.excludes(password))
+            .filter_map(|password| password.id)
+            .collect()
+    };
+
+    let scope = VaultService::new(state).export_scope(format, &excluded)?;
+
+    Ok((scope, excluded))
+}
+
+/// Check that an export still matches the scope the user confirmed.
+fn check_export_scope(scope: &ExportScope, confirmed: &ExportScope) -> Result<(), String> {
+    if scope != confirmed {
+        info!("Export cancelled, the vault changed since it was confirmed");
+        return Err(
+            "Der Tresor hat sich seit der Bestätigung geändert, bitte starte den Export erneut."
+                .into(),
+        );
+    }
+
+    Ok(())
+}
+
+#[tauri::command]
+/// Summarize what an export writes out of the vault.
+///
+/// The summary has to be confirmed by the user and is passed back to the export, which only
+/// runs if the vault still matches it.
+///
+/// # Arguments
+///
+/// * `format` - The format of the export.
+/// * `filter` - Which entries to export, all if not given. Only used for CSV exports.
+///
+/// # Returns
+///
+/// A Result containing the scope of the export or an error.
+///
+/// # Errors
+///
+/// If the user is not logged in or the entries cannot be read.
+pub async fn prepare_export(
+    state: State<'_, PasswordManagerState>,
+    cache: State<'_, HealthCache>,
+    format: ExportFormat,
+    filter: Option<ExportFilter>,
+) -> Result<ExportScope, String> {
+    export_scope(&state, &cache, format, filter.unwrap_or_default()).map(|(scope, _)| scope)
+}
 
 #[tauri::command(rename_all = "camelCase")]
-/// Export all passwords to a CSV file.
+/// Export the passwords to a CSV file.
+///
+/// Flagged entries can be left out based on the cached health check, e.g. when the file is
+/// stored somewhere less secure. If no check is cached, the passwords are checked first.
+///
+/// # Arguments
+///
+/// * `filter` - Which entries to export, all if not given.
+/// * `scope` - The scope returned by `prepare_export` and confirmed by the user.
+/// * `confirmation_pin` - The confirmation PIN, required if one is set.
 ///
 /// # Returns
 ///
@@ -15,69 +105,677 @@ use crate::{commands::PasswordManagerState, BackupManager, Config};
 ///
 /// # Errors
 ///
-/// If the passwords cannot be exported.
+/// If the confirmation PIN is missing or wrong, the vault changed since the scope was
+/// confirmed or the passwords cannot be exported.
 pub async fn export_passwords(
     app: AppHandle,
     state: State<'_, PasswordManagerState>,
+    cache: State<'_, HealthCache>,
+    filter: Option<ExportFilter>,
+    scope: ExportScope,
+    confirmation_pin: Option<String>,
 ) -> Result<(), String> {
-    let state = state.0.lock().unwrap();
-    let pm = state.as_ref().ok_or("Not logged in")?;
+    Config::load()
+        .and_then(|config| config.check_confirmation_pin(confirmation_pin.as_deref()))
+        .map_err(|e| e.to_string())?;
 
-    let config = Config::load().map_err(|e| e.to_string())?;
+    let (current, excluded) = export_scope(
+        &state,
+        &cache,
+        ExportFormat::Csv,
+        filter.unwrap_or_default(),
+    )?;
+    check_export_scope(&current, &scope)?;
 
-    let confirmed = app
-        .dialog()
-        .message(
-            "Achtung: Die exportierte CSV-Datei wird deine Passwörter im Klartext enthalten.\n\n\
-            Bitte beachte:\n\
-            • Bewahre diese Datei sicher auf\n\
-            • Lösche die Datei nach dem Import in ein anderes System\n\
-            • Schütze die Datei ggf. mit einem zusätzlichen Passwort\n\
-            • Teile diese Datei niemals unverschlüsselt\n\n\
-            Möchtest du trotzdem fortfahren?",
-        )
-        .title("Sicherheitswarnung - Export")
-        .kind(MessageDialogKind::Warning)
-        .buttons(MessageDialogButtons::YesNo)
-        .blocking_show();
+    let path = &current.destination;
+    if !path.exists() {
+        let confirm = app
+            .dialog()
+            .message(format!(
+                "Der Export-Ordner existiert noch nicht.\n\n\
+                Folgender Ordner wird benötigt:\n\
+                {}\n\n\
+                Ordner jetzt anlegen?",
+                path.display()
+            ))
+            .title("Export-Ordner erstellen")
+            .kind(MessageDialogKind::Warning)
+            .buttons(MessageDialogButtons::OkCancel)
+            .blocking_show();
 
-    if confirmed {
-        let path = config.backup.export_path;
+        if confirm {
+            fs::create_dir_all(path).map_err(|e| e.to_string())?;
+        } else {
+            return Err("Du hast die Erstellung des Export-Ordners abgebrochen.".into());
+        }
+    }
 
-        if !path.exists() {
-            let confirm = app
-                .dialog()
+    VaultService::new(&state).export_csv(&current, &excluded)?;
+
+    Ok(())
+}
+
+#[tauri::command(rename_all = "camelCase")]
+/// Export the passwords to a file protected with a passphrase.
+///
+/// The file is written to the export folder and can be imported on another machine with the
+/// same passphrase, so no secrets are stored in plain text.
+///
+/// # Arguments
+///
+/// * `passphrase` - The passphrase chosen to protect the export.
+/// * `scope` - The scope returned by `prepare_export` and confirmed by the user.
+/// * `confirmation_pin` - The confirmation PIN, required if one is set.
+///
+/// # Returns
+///
+/// A Result containing the path to the export or an error.
+///
+/// # Errors
+///
+/// If the confirmation PIN is missing or wrong, the passphrase is too short, the vault
+/// changed since the scope was confirmed or the passwords cannot be exported.
+pub async fn export_encrypted(
+    state: State<'_, PasswordManagerState>,
+    cache: State<'_, HealthCache>,
+    passphrase: String,
+    scope: ExportScope,
+    confirmation_pin: Option<String>,
+) -> Result<String, String> {
+    Config::load()
+        .and_then(|config| config.check_confirmation_pin(confirmation_pin.as_deref()))
+        .map_err(|e| e.to_string())?;
+
+    let (current, _) = export_scope(&state, &cache, ExportFormat::Encrypted, ExportFilter::All)?;
+    check_export_scope(&current, &scope)?;
+
+    let export_file = VaultService::new(&state).export_encrypted(&current, &passphrase)?;
+
+    Ok(export_file.display().to_string())
+}
+
+#[derive(Serialize)]
+pub struct CsvImportPreview {
+    pub path: PathBuf,
+    #[serde(flatten)]
+    pub preview: CsvPreview,
+}
+
+#[tauri::command]
+/// Pick a CSV file and preview how its columns are imported.
+///
+/// The layout is detected from the header row, exports of Chrome, Firefox and Edge are
+/// recognized. The returned layout can be adjusted and passed to `import_passwords`.
+///
+/// # Arguments
+///
+/// * `path` - The CSV file, a file dialog is shown if None.
+/// * `profile` - The profile to read the file with, detected if None.
+///
+/// # Returns
+///
+/// A Result containing the preview, None if no file was picked, or an error.
+///
+/// # Errors
+///
+/// If the file is unsafe or does not match the profile.
+pub async fn preview_csv_import(
+    app: AppHandle,
+    state: State<'_, PasswordManagerState>,
+    path: Option<PathBuf>,
+    profile: Option<CsvProfile>,
+) -> Result<Option<CsvImportPreview>, String> {
+    let service = VaultService::new(&state);
+    service.ensure_logged_in()?;
+
+    let path = match path {
+        Some(path) => path,
+        None => match app
+            .dialog()
+            .file()
+            .add_filter("CSV", &["csv"])
+            .blocking_pick_file()
+        {
+            Some(path) => path.into_path().map_err(|e| e.to_string())?,
+            None => return Ok(None),
+        },
+    };
+
+    let preview = service.preview_csv(&path, profile)?;
+
+    Ok(Some(CsvImportPreview { path, preview }))
+}
+
+#[tauri::command]
+/// Import passwords from a CSV file, a KeePass database, a Bitwarden export or an
+/// encrypted KarnCrypt export.
+///
+/// KeePass databases in the KDBX 4 format are decrypted with the given password, their
+/// groups become folders. Bitwarden JSON exports need the password only if they are
+/// password protected, encrypted KarnCrypt exports always need their passphrase.
+///
+/// # Arguments
+///
+/// * `format` - The format of the file to import, CSV if None.
+/// * `password` - The password of the KeePass database, the Bitwarden export or the passphrase
+///   of an encrypted export.
+/// * `path` - The file to import, a file dialog is shown if None.
+/// * `layout` - The layout of a CSV file, detected from its header row if None.
+///
+/// # Returns
+///
+/// A Result containing the completion status or an error.
+///
+/// # Errors
+///
+/// If the passwords cannot be imported or a KeePass database or encrypted export is picked
+/// without password.
+pub async fn import_passwords(
+    app: AppHandle,
+    state: State<'_, PasswordManagerState>,
+    format: Option<ImportFormat>,
+    password: Option<String>,
+    path: Option<PathBuf>,
+    layout: Option<CsvLayout>,
+) -> Result<String, String> {
+    let service = VaultService::new(&state);
+    service.ensure_logged_in()?;
+
+    let format = format.unwrap_or_default();
+    let file_path = match path {
+        Some(path) => Some(path),
+        None => {
+            let dialog = app.dialog().file();
+            let picked = match format {
+                ImportFormat::Csv => dialog.blocking_pick_file(),
+                ImportFormat::Keepass => {
+                    dialog.add_filter("KeePass", &["kdbx"]).blocking_pick_file()
+                }
+                ImportFormat::Bitwarden => dialog
+                    .add_filter("Bitwarden", &["json"])
+                    .blocking_pick_file(),
+                ImportFormat::Kcexport => dialog
+                    .add_filter("KarnCrypt", &[EXPORT_EXTENSION])
+                    .blocking_pick_file(),
+            };
+            picked
+                .map(|path| path.into_path())
+                .transpose()
+                .map_err(|e| e.to_string())?
+        }
+    };
+
+    match file_path {
+        Some(path) => {
+            let result =
+                service.import_file(&path, format, password.as_deref(), layout.as_ref())?;
+            app.dialog()
                 .message(format!(
-                    "Der Export-Ordner existiert noch nicht.\n\n\
-                    Folgender Ordner wird benötigt:\n\
-                    {}\n\n\
-                    Ordner jetzt anlegen?",
-                    path.display()
+                    "Import erfolgreich:\n
+                    {} Einträge importiert\n
+                    {} Dublikate überstrungen\n
+                    {} Fehlerhaft",
+                    result.imported,
+                    result.skipped,
+                    result.errors.len()
                 ))
-                .title("Export-Ordner erstellen")
-                .kind(MessageDialogKind::Warning)
-                .buttons(MessageDialogButtons::OkCancel)
+                .title("Import abgeschlossen")
+                .kind(MessageDialogKind::Info)
                 .blocking_show();
+            Ok("Import erfolgreich".to_string())
+        }
+        None => Ok("Import abgebrochen".to_string()),
+    }
+}
+
+#[tauri::command]
+/// Find the browser profiles on this computer that have saved passwords.
+///
+/// # Returns
+///
+/// The profiles of Chrome, Edge and Firefox with the number of their saved logins.
+pub async fn detect_browser_profiles() -> Vec<BrowserProfile> {
+    let profiles = BrowserProfile::detect();
+    info!("Detected {} browser profiles", profiles.len());
+
+    profiles
+}
+
+#[tauri::command]
+/// Pick the password export of a browser and import it with the layout of the browser.
+///
+/// Browsers encrypt their password stores with their own or the system's keys, so the
+/// passwords are imported from the CSV file the browser exports.
+///
+/// # Arguments
+///
+/// * `browser` - The browser the file was exported from.
+///
+/// # Returns
+///
+/// A Result containing the outcome, None if no file was picked, or an error.
+///
+/// # Errors
+///
+/// If the file is unsafe, is no export of the browser or the import fails.
+pub async fn import_browser_export(
+    app: AppHandle,
+    state: State<'_, PasswordManagerState>,
+    browser: BrowserKind,
+) -> Result<Option<ImportSummary>, String> {
+    let service = VaultService::new(&state);
+    service.ensure_logged_in()?;
+
+    let mut dialog = app.dialog().file().add_filter("CSV", &["csv"]);
+    if let Some(downloads) = dirs::download_dir() {
+        dialog = dialog.set_directory(downloads);
+    }
+    let path = match dialog.blocking_pick_file() {
+        Some(path) => path.into_path().map_err(|e| e.to_string())?,
+        None => return Ok(None),
+    };
+
+    service.import_browser_export(&path, browser).map(Some)
+}
+
+#[derive(Serialize)]
+pub struct OtpImportPreview {
+    pub path: PathBuf,
+    #[serde(flatten)]
+    pub preview: OtpPreview,
+}
+
+#[tauri::command]
+/// Pick the backup of an authenticator app and suggest the entries of its TOTP accounts.
+///
+/// Plaintext exports of Aegis and plain JSON backups of andOTP are supported. The secrets
+/// are not returned, the accounts are mapped to entries and passed to `import_otp_secrets`.
+///
+/// # Arguments
+///
+/// * `format` - The app the backup was created with.
+///
+/// # Returns
+///
+/// A Result containing the accounts, None if no file was picked, or an error.
+///
+/// # Errors
+///
+/// If the backup is encrypted or no backup of the app.
+pub async fn preview_otp_import(
+    app: AppHandle,
+    state: State<'_, PasswordManagerState>,
+    format: OtpFormat,
+) -> Result<Option<OtpImportPreview>, String> {
+    let service = VaultService::new(&state);
+    service.ensure_logged_in()?;
+
+    let path = match app
+        .dialog()
+        .file()
+        .add_filter("JSON", &["json"])
+        .blocking_pick_file()
+    {
+        Some(path) => path.into_path().map_err(|e| e.to_string())?,
+        None => return Ok(None),
+    };
+
+    let preview = service.preview_otp_import(&path, format)?;
+    info!(
+        "Read {} TOTP accounts from the {:?} backup",
+        preview.accounts.len(),
+        format
+    );
+
+    Ok(Some(OtpImportPreview { path, preview }))
+}
+
+#[tauri::command]
+/// Import the TOTP secrets of an authenticator backup into the entries the user chose.
+///
+/// # Arguments
+///
+/// * `path` - The backup file returned by `preview_otp_import`.
+/// * `format` - The app the backup was created with.
+/// * `mappings` - The entry per account, accounts without mapping are skipped and accounts
+///   without entry become new entries.
+///
+/// # Returns
+///
+/// A Result containing a summary of the import or an error.
+///
+/// # Errors
+///
+/// If the backup cannot be read or the new entries cannot be created.
+pub async fn import_otp_secrets(
+    state: State<'_, PasswordManagerState>,
+    path: PathBuf,
+    format: OtpFormat,
+    mappings: Vec<OtpMapping>,
+) -> Result<ImportSummary, String> {
+    VaultService::new(&state).import_otp_secrets(&path, format, &mappings)
+}
+
+#[tauri::command]
+/// Import passwords from pasted CSV or text rows.
+///
+/// The rows are validated and mapped like a CSV file, see `import_passwords`.
+///
+/// # Arguments
+///
+/// * `text` - The pasted rows, the clipboard content is used if None.
+///
+/// # Returns
+///
+/// A Result containing a summary of the import or an error.
+///
+/// # Errors
+///
+/// If the clipboard contains no text or the text is invalid.
+pub async fn import_passwords_from_text(
+    state: State<'_, PasswordManagerState>,
+    clipboard: State<'_, SecureClipboard>,
+    text: Option<String>,
+) -> Result<String, String> {
+    let text = match text {
+        Some(text) => text,
+        None => clipboard.read_text().map_err(|e| e.to_string())?,
+    };
+
+    let result = VaultService::new(&state).import_text(&text)?;
+
+    Ok(format!(
+        "{} Einträge importiert, {} Duplikate übersprungen, {} fehlerhaft",
+        result.imported,
+        result.skipped,
+        result.errors.len()
+    ))
+}
+
+#[tauri::command]
+/// Export the audit log to the export folder.
+///
+/// # Arguments
+///
+/// * `format` - The format of the export file, either `csv` or `json`.
+/// * `from` - The first day to include as `YYYY-MM-DD`.
+/// * `to` - The last day to include as `YYYY-MM-DD`.
+///
+/// # Returns
+///
+/// A Result containing the path of the export file or an error.
+///
+/// # Errors
+///
+/// If a date is invalid or the audit log cannot be exported.
+pub async fn export_audit_log(
+    state: State<'_, PasswordManagerState>,
+    format: AuditExportFormat,
+    from: Option<String>,
+    to: Option<String>,
+) -> Result<String, String> {
+    let parse_date = |date: Option<String>| {
+        date.filter(|d| !d.is_empty())
+            .map(|d| NaiveDate::parse_from_str(&d, "%Y-%m-%d"))
+            .transpose()
+            .map_err(|e| e.to_string())
+    };
+    let from = parse_date(from)?;
+    let to = parse_date(to)?;
+
+    let export_file = VaultService::new(&state).export_audit_log(format, from, to)?;
+
+    Ok(export_file.display().to_string())
+}
+
+#[tauri::command]
+/// Create a backup.
+///
+/// # Arguments
+///
+/// * `master_pass` - The master password to use for the backup.
+///
+/// # Returns
+///
+/// A Result containing the completion status or an error.
+///
+/// # Errors
+///
+/// If the backup cannot be created.
+pub async fn create_backup(
+    app: AppHandle,
+    state: State<'_, PasswordManagerState>,
+    master_pass: String,
+) -> Result<(), String> {
+    let service = VaultService::new(&state);
+    service.ensure_logged_in()?;
+
+    let backup_path = Config::load()
+        .map_err(|e| e.to_string())?
+        .backup
+        .backup_path;
+
+    if !backup_path.exists() {
+        let confirm = app
+            .dialog()
+            .message(format!(
+                "Der Backup-Ordner existiert noch nicht.\n\n\
+                Folgender Ordner wird benötigt:\n\
+                {}\n\n\
+                Ordner jetzt anlegen?",
+                backup_path.display()
+            ))
+            .title("Backup-Ordner erstellen")
+            .kind(MessageDialogKind::Warning)
+            .buttons(MessageDialogButtons::OkCancel)
+            .blocking_show();
+
+        if confirm {
+            fs::create_dir_all(&backup_path).map_err(|e| e.to_string())?;
+        } else {
+            return Err("Du hast die Erstellung des Backup-Ordners abgebrochen.".into());
+        }
+    }
+
+    service.create_backup(&master_pass)?;
+
+    Ok(())
+}
+
+#[tauri::command]
+/// Restore a backup.
+///
+/// # Arguments
+///
+/// * `master_pass` - The master password to use for the backup.
+/// * `confirmation_pin` - The confirmation PIN, required if one is set.
+///
+/// # Returns
+///
+/// A Result containing the completion status or an error.
+///
+/// # Errors
+///
+/// If the confirmation PIN is missing or wrong or the backup cannot be restored.
+pub async fn restore_backup(
+    app: AppHandle,
+    master_pass: String,
+    confirmation_pin: Option<String>,
+) -> Result<(), String> {
+    // Without a readable config there is no PIN to check, e.g. in the repair assistant.
+    if let Ok(config) = Config::load() {
+        config
+            .check_confirmation_pin(confirmation_pin.as_deref())
+            .map_err(|e| e.to_string())?;
+    }
+    let (backup_path, secret_key) = pick_backup(&app)?;
+    let config_dir = Config::get_config_dir().map_err(|e| e.to_string())?;
+
+    BackupManager::restore_backup(
+        &backup_path,
+        &config_dir,
+        &master_pass,
+        secret_key.as_deref(),
+    )
+    .map_err(|e| e.to_string())?;
+    record_audit(
+        AuditEvent::Restore,
+        &format!("Backup restored from {}", backup_path.display()),
+    );
+
+    app.dialog()
+        .message("Backup erfolgreich wiederhergestellt")
+        .title("Backup wiederherstellen")
+        .kind(MessageDialogKind::Info)
+        .buttons(MessageDialogButtons::Ok)
+        .blocking_show();
+
+    app.restart();
+}
+
+#[tauri::command]
+/// Restore a backup into an isolated sandbox to check its contents.
+///
+/// The current vault and configuration are not touched.
+///
+/// # Arguments
+///
+/// * `master_pass` - The master password the backup was created with.
+///
+/// # Returns
+///
+/// A Result containing the entries of the backup without their passwords or an error.
+///
+/// # Errors
+///
+/// If no backup is selected or the backup cannot be restored.
+pub async fn sandbox_restore(app: AppHandle, master_pass: String) -> Result<BackupPreview, String> {
+    let (backup_path, secret_key) = pick_backup(&app)?;
+    let sandbox_dir = std::env::temp_dir().join(format!(
+        "karncrypt_sandbox_{}",
+        Utc::now().format("%Y%m%d_%H%M%S")
+    ));
+
+    let preview = BackupManager::sandbox_restore(
+        &backup_path,
+        &sandbox_dir,
+        &master_pass,
+        secret_key.as_deref(),
+    )
+    .map_err(|e| e.to_string())?;
+    info!(
+        "Checked backup {} with {} entries",
+        backup_path.display(),
+        preview.entries.len()
+    );
+
+    Ok(preview)
+}
+
+/// Let the user pick a backup file and, if it is encrypted to recipients, the secret key.
+///
+/// # Arguments
+///
+/// * `app` - The app handle.
+///
+/// # Returns
+///
+/// A Result containing the path of the backup and the secret key if needed or an error.
+///
+/// # Errors
+///
+/// If no backup or no required secret key is selected.
+fn pick_backup(app: &AppHandle) -> Result<(PathBuf, Option<String>), String> {
+    let backup_file = app
+        .dialog()
+        .file()
+        .add_filter("KarnCrypt Backup", &["pmbackup", "pminc"])
+        .blocking_pick_file();
+
+    let backup_path = match backup_file {
+        Some(path) => path.as_path().unwrap().to_path_buf(),
+        None => return Err("Keine Backup-Datei ausgewählt".into()),
+    };
+    let secret_key = pick_secret_key(app, &backup_path)?;
+
+    Ok((backup_path, secret_key))
+}
+
+/// Let the user pick the secret key if a backup is encrypted to recipients.
+///
+/// # Arguments
+///
+/// * `app` - The app handle.
+/// * `backup_path` - The path of the backup.
+///
+/// # Returns
+///
+/// A Result containing the secret key, None if the backup does not need one, or an error.
+///
+/// # Errors
+///
+/// If the backup cannot be read or no required secret key is selected.
+fn pick_secret_key(app: &AppHandle, backup_path: &Path) -> Result<Option<String>, String> {
+    let requires_key =
+        BackupManager::requires_secret_key(backup_path).map_err(|e| e.to_string())?;
+    let secret_key = if requires_key {
+        let key_file = app
+            .dialog()
+            .file()
+            .set_title("Privaten Backup-Schlüssel auswählen")
+            .add_filter("KarnCrypt Schlüssel", &["kckey"])
+            .blocking_pick_file();
 
-            if confirm {
-                fs::create_dir_all(&path).map_err(|e| e.to_string())?;
-            } else {
-                return Err("Du hast die Erstellung des Export-Ordners abgebrochen.".into());
+        match key_file {
+            Some(path) => {
+                let path = path.as_path().ok_or("Ungültiger Dateipfad")?.to_path_buf();
+                Some(fs::read_to_string(path).map_err(|e| e.to_string())?)
             }
+            None => return Err("Kein privater Schlüssel ausgewählt".into()),
         }
+    } else {
+        None
+    };
 
-        let bm = BackupManager::new(&pm.db);
-        bm.export_csv(&path).map_err(|e| e.to_string())?;
+    Ok(secret_key)
+}
 
-        Ok(())
-    } else {
-        info!("Export cancelled by user");
-        Err("Export wurde abgebrochen!".into())
+/// The label of the window that shows an opened snapshot.
+pub const SNAPSHOT_WINDOW: &str = "snapshot";
+
+/// The backup opened read-only in the snapshot window, kept until the window is closed or
+/// the user logs out.
+pub struct SnapshotState(pub Mutex<Option<BackupSnapshot>>);
+
+impl SnapshotState {
+    /// Create a new state without an opened snapshot.
+    ///
+    /// # Returns
+    ///
+    /// A new snapshot state.
+    pub fn new() -> Self {
+        Self(Mutex::new(None))
+    }
+
+    /// Close the opened snapshot and remove its restored files.
+    pub fn clear(&self) {
+        self.0.lock().unwrap().take();
     }
 }
 
-#[tauri::command]
-/// Import passwords from a CSV file.
+#[derive(Serialize)]
+pub struct SnapshotView {
+    name: String,
+    created_at: Option<String>,
+    entries: Vec<SnapshotEntry>,
+}
+
+/// Show the snapshot window, or reload it if it already shows another snapshot.
+///
+/// # Arguments
+///
+/// * `app` - The app handle.
+/// * `name` - The file name of the opened backup.
 ///
 /// # Returns
 ///
@@ -85,53 +783,52 @@ pub async fn export_passwords(
 ///
 /// # Errors
 ///
-/// If the passwords cannot be imported.
-pub async fn import_passwords(
-    app: AppHandle,
-    state: State<'_, PasswordManagerState>,
-) -> Result<String, String> {
-    let state = state.0.lock().unwrap();
-    let pm = state.as_ref().ok_or("Not logged in")?;
+/// If the window cannot be created or focused.
+fn show_snapshot_window(app: &AppHandle, name: &str) -> tauri::Result<()> {
+    let title = format!("KarnCrypt Snapshot – {}", name);
+    if let Some(window) = app.get_webview_window(SNAPSHOT_WINDOW) {
+        window.set_title(&title)?;
+        window.eval("location.reload()")?;
+        window.show()?;
+        return window.set_focus();
+    }
 
-    let file_path = app.dialog().file().blocking_pick_file();
+    WebviewWindowBuilder::new(
+        app,
+        SNAPSHOT_WINDOW,
+        WebviewUrl::App("index.html?view=snapshot".into()),
+    )
+    .title(title)
+    .inner_size(900.0, 600.0)
+    .build()?;
 
-    match file_path {
-        Some(path) => {
-            let path = path.as_path().unwrap();
-            let bm = BackupManager::new(&pm.db);
-            match bm.import_csv(path) {
-                Ok(result) => {
-                    app.dialog()
-                        .message(format!(
-                            "Import erfolgreich:\n
-                            {} Einträge importiert\n
-                            {} Dublikate überstrungen\n
-                            {} Fehlerhaft",
-                            result.imported,
-                            result.skipped,
-                            result.errors.len()
-                        ))
-                        .title("Import abgeschlossen")
-                        .kind(MessageDialogKind::Info)
-                        .blocking_show();
-                    Ok("Import erfolgreich".to_string())
-                }
-                Err(e) => {
-                    error!("Failed to import passwords: {}", e);
-                    Err(e.to_string())
-                }
-            }
+    Ok(())
+}
+
+/// Close the snapshot window and the opened snapshot, e.g. on logout.
+///
+/// # Arguments
+///
+/// * `app` - The app handle.
+pub fn close_snapshot_window(app: &AppHandle) {
+    app.state::<SnapshotState>().clear();
+    if let Some(window) = app.get_webview_window(SNAPSHOT_WINDOW) {
+        if let Err(e) = window.destroy() {
+            error!("Failed to close snapshot window: {}", e);
         }
-        None => Ok("Import abgebrochen".to_string()),
     }
 }
 
-#[tauri::command]
-/// Create a backup.
+#[tauri::command(rename_all = "camelCase")]
+/// Open a backup read-only in its own window to browse it as it was.
+///
+/// The backup is restored into a temporary directory, the current vault and configuration
+/// are not touched. The snapshot stays open until its window is closed or the user logs out.
 ///
 /// # Arguments
 ///
-/// * `master_pass` - The master password to use for the backup.
+/// * `name` - The file name of the backup in the backup folder.
+/// * `master_pass` - The master password the backup was created with.
 ///
 /// # Returns
 ///
@@ -139,87 +836,157 @@ pub async fn import_passwords(
 ///
 /// # Errors
 ///
-/// If the backup cannot be created.
-pub async fn create_backup(
+/// If the user is not logged in, the backup does not exist or cannot be opened.
+pub async fn open_snapshot(
     app: AppHandle,
     state: State<'_, PasswordManagerState>,
+    snapshot_state: State<'_, SnapshotState>,
+    name: String,
     master_pass: String,
 ) -> Result<(), String> {
-    let state = state.0.lock().unwrap();
-    let pm = state.as_ref().ok_or("Not logged in")?;
+    VaultService::new(&state).ensure_logged_in()?;
 
     let config = Config::load().map_err(|e| e.to_string())?;
-    let config_dir = Config::get_config_dir().map_err(|e| e.to_string())?;
-    let backup_path = config.backup.backup_path;
-
-    if !backup_path.exists() {
-        let confirm = app
-            .dialog()
-            .message(format!(
-                "Der Backup-Ordner existiert noch nicht.\n\n\
-                Folgender Ordner wird benötigt:\n\
-                {}\n\n\
-                Ordner jetzt anlegen?",
-                backup_path.display()
-            ))
-            .title("Backup-Ordner erstellen")
-            .kind(MessageDialogKind::Warning)
-            .buttons(MessageDialogButtons::OkCancel)
-            .blocking_show();
-
-        if confirm {
-            fs::create_dir_all(&backup_path).map_err(|e| e.to_string())?;
-        } else {
-            return Err("Du hast die Erstellung des Backup-Ordners abgebrochen.".into());
-        }
+    if Path::new(&name).file_name().and_then(|n| n.to_str()) != Some(name.as_str()) {
+        return Err(format!("Ungültiger Backup-Name: {}", name));
     }
+    let backup_path = config.backup.backup_path.join(&name);
+    if !backup_path.is_file() {
+        return Err(format!("Backup nicht gefunden: {}", name));
+    }
+    let secret_key = pick_secret_key(&app, &backup_path)?;
 
-    let bm = BackupManager::new(&pm.db);
-    bm.create_backup(&backup_path, &config_dir, &master_pass)
-        .map_err(|e| e.to_string())?;
+    snapshot_state.clear();
+    let snapshot_dir = std::env::temp_dir().join(format!(
+        "karncrypt_snapshot_{}",
+        Utc::now().format("%Y%m%d_%H%M%S")
+    ));
+    let snapshot = BackupSnapshot::open(
+        &backup_path,
+        &snapshot_dir,
+        &master_pass,
+        secret_key.as_deref(),
+    )
+    .map_err(|e| e.to_string())?;
+    *snapshot_state.0.lock().unwrap() = Some(snapshot);
+    record_audit(
+        AuditEvent::SnapshotOpened,
+        &format!("Snapshot opened from {}", backup_path.display()),
+    );
 
-    Ok(())
+    show_snapshot_window(&app, &name).map_err(|e| e.to_string())
 }
 
 #[tauri::command]
-/// Restore a backup.
+/// Get the entries of the opened snapshot.
+///
+/// # Returns
+///
+/// A Result containing the name, creation time and entries of the snapshot without their
+/// passwords or an error.
+///
+/// # Errors
+///
+/// If no snapshot is open or its entries cannot be read.
+pub async fn get_snapshot(
+    snapshot_state: State<'_, SnapshotState>,
+) -> Result<SnapshotView, String> {
+    let snapshot_state = snapshot_state.0.lock().unwrap();
+    let snapshot = snapshot_state.as_ref().ok_or("Kein Snapshot geöffnet")?;
+
+    Ok(SnapshotView {
+        name: snapshot.name().to_string(),
+        created_at: snapshot.created_at().map(str::to_string),
+        entries: snapshot.entries().map_err(|e| e.to_string())?,
+    })
+}
+
+/// Decrypt the password of a snapshot entry while the user is logged in.
 ///
 /// # Arguments
 ///
-/// * `master_pass` - The master password to use for the backup.
+/// * `state` - The password manager state.
+/// * `snapshot_state` - The snapshot state.
+/// * `id` - The ID of the entry in the snapshot.
 ///
 /// # Returns
 ///
-/// A Result containing the completion status or an error.
+/// A Result containing the decrypted password or an error.
 ///
 /// # Errors
 ///
-/// If the backup cannot be restored.
-pub async fn restore_backup(app: AppHandle, master_pass: String) -> Result<(), String> {
-    let backup_file = app
-        .dialog()
-        .file()
-        .add_filter("KarnCrypt Backup", &["pmbackup"])
-        .blocking_pick_file();
+/// If the user is not logged in, no snapshot is open or the password cannot be decrypted.
+fn snapshot_password(
+    state: &PasswordManagerState,
+    snapshot_state: &SnapshotState,
+    id: i32,
+) -> Result<String, String> {
+    VaultService::new(state).ensure_logged_in()?;
 
-    let backup_path = match backup_file {
-        Some(path) => path.as_path().unwrap().to_path_buf(),
-        None => return Err("Keine Backup-Datei ausgewählt".into()),
-    };
+    let snapshot_state = snapshot_state.0.lock().unwrap();
+    let snapshot = snapshot_state.as_ref().ok_or("Kein Snapshot geöffnet")?;
+    snapshot.password(id).map_err(|e| e.to_string())
+}
 
-    let config_dir = Config::get_config_dir().map_err(|e| e.to_string())?;
+#[tauri::command]
+/// Decrypt the password of a snapshot entry to reveal it.
+///
+/// # Arguments
+///
+/// * `id` - The ID of the entry in the snapshot.
+///
+/// # Returns
+///
+/// A Result containing the password as it was when the backup was created or an error.
+///
+/// # Errors
+///
+/// If the user is not logged in, no snapshot is open or the password cannot be decrypted.
+pub async fn decrypt_snapshot_password(
+    state: State<'_, PasswordManagerState>,
+    snapshot_state: State<'_, SnapshotState>,
+    id: i32,
+) -> Result<String, String> {
+    snapshot_password(&state, &snapshot_state, id)
+}
 
-    BackupManager::restore_backup(&backup_path, &config_dir, &master_pass)
-        .map_err(|e| e.to_string())?;
+#[tauri::command]
+/// Decrypt the password of a snapshot entry and copy it to the clipboard.
+///
+/// # Arguments
+///
+/// * `id` - The ID of the entry in the snapshot.
+///
+/// # Returns
+///
+/// A Result containing the applied clipboard protection of the platform or an error.
+///
+/// # Errors
+///
+/// If the password cannot be decrypted or copied to the clipboard.
+pub async fn copy_snapshot_password(
+    state: State<'_, PasswordManagerState>,
+    snapshot_state: State<'_, SnapshotState>,
+    clipboard: State<'_, SecureClipboard>,
+    id: i32,
+) -> Result<ClipboardProtection, String> {
+    let password = snapshot_password(&state, &snapshot_state, id)?;
 
-    app.dialog()
-        .message("Backup erfolgreich wiederhergestellt")
-        .title("Backup wiederherstellen")
-        .kind(MessageDialogKind::Info)
-        .buttons(MessageDialogButtons::Ok)
-        .blocking_show();
+    clipboard.copy(&password).map_err(|e| {
+        error!("Failed to copy to clipboard: {}", e);
+        e.to_string()
+    })
+}
 
-    app.restart();
+#[tauri::command]
+/// Close the snapshot window and remove the restored backup.
+///
+/// # Returns
+///
+/// A Result containing the completion status or an error.
+pub async fn close_snapshot(app: AppHandle) -> Result<(), String> {
+    close_snapshot_window(&app);
+    Ok(())
 }
 
 #[derive(serde::Serialize)]
@@ -231,6 +998,12 @@ pub struct DatabaseSettingsConfig {
     max_backups: usize,
     backup_path: String,
     export_path: String,
+    incremental_backups: bool,
+    full_backup_every: usize,
+    backup_quota_mb: u64,
+    backup_file_name: String,
+    sync_safe_mode: bool,
+    cloud_provider: Option<CloudProvider>,
 }
 
 #[tauri::command]
@@ -261,9 +1034,29 @@ pub async fn get_database_settings() -> Result<DatabaseSettingsConfig, String> {
         max_backups: config.backup.max_backups,
         backup_path: config.backup.backup_path.to_string_lossy().to_string(),
         export_path: config.backup.export_path.to_string_lossy().to_string(),
+        incremental_backups: config.backup.incremental,
+        full_backup_every: config.backup.full_backup_every,
+        backup_quota_mb: config.backup.quota_mb,
+        backup_file_name: config.backup.file_name_template.clone(),
+        sync_safe_mode: config.database.sync_safe_mode,
+        cloud_provider: detect_cloud_provider(&config.database.db_path),
     })
 }
 
+#[tauri::command]
+/// Detect if a folder is synced by a cloud storage client.
+///
+/// # Arguments
+///
+/// * `path` - The folder to check.
+///
+/// # Returns
+///
+/// The detected cloud provider or None if the folder does not seem to be synced.
+pub async fn detect_cloud_sync(path: String) -> Option<CloudProvider> {
+    detect_cloud_provider(&PathBuf::from(path))
+}
+
 #[tauri::command(rename_all = "camelCase")]
 /// Save the database settings.
 ///
@@ -276,6 +1069,11 @@ pub async fn get_database_settings() -> Result<DatabaseSettingsConfig, String> {
 /// * `max_backups` - The maximum number of backups to keep.
 /// * `backup_path` - The path to store backups.
 /// * `export_path` - The path to store exported passwords.
+/// * `incremental_backups` - Whether to create incremental backups.
+/// * `full_backup_every` - The number of increments before a new full backup is created.
+/// * `backup_quota_mb` - The disk quota for the backup folder in MB, 0 disables the warning.
+/// * `backup_file_name` - The file name template of backups, e.g. `{hostname}_{vault}`.
+/// * `sync_safe_mode` - Whether to avoid the write-ahead log for cloud-synced folders.
 ///
 /// # Returns
 ///
@@ -283,8 +1081,10 @@ pub async fn get_database_settings() -> Result<DatabaseSettingsConfig, String> {
 ///
 /// # Errors
 ///
-/// If the settings cannot be saved.
+/// If the file name template is invalid or the settings cannot be saved.
+#[allow(clippy::too_many_arguments)]
 pub async fn save_database_settings(
+    state: State<'_, PasswordManagerState>,
     db_name: String,
     db_path: String,
     auto_backup: bool,
@@ -292,7 +1092,14 @@ pub async fn save_database_settings(
     max_backups: usize,
     backup_path: String,
     export_path: String,
+    incremental_backups: bool,
+    full_backup_every: usize,
+    backup_quota_mb: u64,
+    backup_file_name: String,
+    sync_safe_mode: bool,
 ) -> Result<(), String> {
+    BackupNaming::new(&backup_file_name, &db_name).map_err(|e| e.to_string())?;
+
     let mut config = Config::load().map_err(|e| e.to_string())?;
 
     config.database.db_name = db_name;
@@ -308,8 +1115,243 @@ pub async fn save_database_settings(
     config.backup.max_backups = max_backups;
     config.backup.backup_path = PathBuf::from(backup_path);
     config.backup.export_path = PathBuf::from(export_path);
+    config.backup.incremental = incremental_backups;
+    config.backup.full_backup_every = full_backup_every.max(1);
+    config.backup.quota_mb = backup_quota_mb;
+    config.backup.file_name_template = backup_file_name;
+    config.database.sync_safe_mode = sync_safe_mode;
+
+    config.save().map_err(|e| e.to_string())?;
+
+    VaultService::new(&state).apply_sync_safe_mode(sync_safe_mode)
+}
+
+#[tauri::command]
+/// Get statistics about the database and its backups.
+///
+/// # Returns
+///
+/// A Result containing the number of entries, the size of the database on disk and the
+/// time of the newest backup or an error.
+///
+/// # Errors
+///
+/// If the user is not logged in or the entries cannot be read.
+pub async fn get_database_stats(
+    state: State<'_, PasswordManagerState>,
+) -> Result<DatabaseStats, String> {
+    VaultService::new(&state).database_stats()
+}
+
+#[derive(serde::Serialize)]
+pub struct BackupList {
+    backups: Vec<BackupInfo>,
+    total_size: u64,
+    quota_bytes: Option<u64>,
+    quota_exceeded: bool,
+}
+
+#[tauri::command]
+/// List the backups in the backup folder.
+///
+/// # Returns
+///
+/// A Result containing the backups with their sizes and the quota status or an error.
+///
+/// # Errors
+///
+/// If the backup folder cannot be read.
+pub async fn list_backups() -> Result<BackupList, String> {
+    let config = Config::load().map_err(|e| e.to_string())?;
+    let backup_path = &config.backup.backup_path;
+
+    let backups = if backup_path.exists() {
+        BackupManager::list_backups(backup_path).map_err(|e| e.to_string())?
+    } else {
+        Vec::new()
+    };
+
+    let total_size = backups.iter().map(|b| b.size).sum();
+    let quota_bytes = (config.backup.quota_mb > 0).then(|| config.backup.quota_mb * 1024 * 1024);
+    let quota_exceeded = quota_bytes.is_some_and(|quota| total_size > quota);
+
+    if quota_exceeded {
+        info!("Backup folder exceeds the configured quota");
+    }
+
+    Ok(BackupList {
+        backups,
+        total_size,
+        quota_bytes,
+        quota_exceeded,
+    })
+}
+
+#[tauri::command]
+/// Remove old backups according to the retention policy.
+///
+/// # Returns
+///
+/// A Result containing the number of removed backup files or an error.
+///
+/// # Errors
+///
+/// If the backups cannot be removed.
+pub async fn prune_backups() -> Result<usize, String> {
+    let config = Config::load().map_err(|e| e.to_string())?;
+
+    let removed = BackupManager::prune_backups(
+        &config.backup.backup_path,
+        config.backup.max_backups,
+        &config.backup_naming(),
+    )
+    .map_err(|e| {
+        error!("Failed to prune backups: {}", e);
+        e.to_string()
+    })?;
+
+    Ok(removed.len())
+}
+
+#[tauri::command]
+/// Get the configured backup recipients.
+///
+/// # Returns
+///
+/// A Result containing the backup recipients or an error.
+///
+/// # Errors
+///
+/// If the config cannot be loaded.
+pub async fn get_backup_recipients() -> Result<Vec<BackupRecipient>, String> {
+    let config = Config::load().map_err(|e| e.to_string())?;
+
+    Ok(config.backup.recipients)
+}
+
+#[tauri::command(rename_all = "camelCase")]
+/// Add a backup recipient.
+///
+/// # Arguments
+///
+/// * `name` - A display name for the recipient.
+/// * `public_key` - The encoded public key of the recipient.
+///
+/// # Returns
+///
+/// A Result containing the completion status or an error.
+///
+/// # Errors
+///
+/// If the public key is invalid or already added.
+pub async fn add_backup_recipient(name: String, public_key: String) -> Result<(), String> {
+    let public_key = public_key.trim().to_string();
+    RecipientEncryption::validate_public_key(&public_key)
+        .map_err(|_| "Ungültiger öffentlicher Schlüssel".to_string())?;
 
+    let mut config = Config::load().map_err(|e| e.to_string())?;
+    if config
+        .backup
+        .recipients
+        .iter()
+        .any(|r| r.public_key == public_key)
+    {
+        return Err("Dieser Empfänger ist bereits hinterlegt".into());
+    }
+
+    config
+        .backup
+        .recipients
+        .push(BackupRecipient { name, public_key });
+    config.save().map_err(|e| e.to_string())?;
+
+    info!("Added backup recipient");
+    Ok(())
+}
+
+#[tauri::command(rename_all = "camelCase")]
+/// Remove a backup recipient.
+///
+/// # Arguments
+///
+/// * `public_key` - The encoded public key of the recipient to remove.
+///
+/// # Returns
+///
+/// A Result containing the completion status or an error.
+///
+/// # Errors
+///
+/// If the config cannot be saved.
+pub async fn remove_backup_recipient(public_key: String) -> Result<(), String> {
+    let mut config = Config::load().map_err(|e| e.to_string())?;
+    config
+        .backup
+        .recipients
+        .retain(|r| r.public_key != public_key);
     config.save().map_err(|e| e.to_string())?;
 
+    info!("Removed backup recipient");
     Ok(())
 }
+
+#[tauri::command]
+/// Generate a new backup key pair.
+///
+/// The private key is written to a file chosen by the user and is never stored by the app.
+/// The public key is added to the backup recipients.
+///
+/// # Arguments
+///
+/// * `name` - A display name for the new recipient.
+///
+/// # Returns
+///
+/// A Result containing the encoded public key or an error.
+///
+/// # Errors
+///
+/// If the key pair cannot be generated or the private key cannot be saved.
+pub async fn generate_backup_keypair(app: AppHandle, name: String) -> Result<String, String> {
+    let key_pair = RecipientKeyPair::generate().map_err(|e| e.to_string())?;
+
+    let key_file = app
+        .dialog()
+        .file()
+        .set_title("Privaten Backup-Schlüssel speichern")
+        .add_filter("KarnCrypt Schlüssel", &["kckey"])
+        .set_file_name("karncrypt_backup.kckey")
+        .blocking_save_file();
+
+    let key_path = match key_file {
+        Some(path) => path.as_path().ok_or("Ungültiger Dateipfad")?.to_path_buf(),
+        None => return Err("Speichern des privaten Schlüssels abgebrochen".into()),
+    };
+
+    fs::write(&key_path, &key_pair.secret_key).map_err(|e| {
+        error!("Failed to write backup secret key: {}", e);
+        e.to_string()
+    })?;
+
+    let mut config = Config::load().map_err(|e| e.to_string())?;
+    config.backup.recipients.push(BackupRecipient {
+        name,
+        public_key: key_pair.public_key.clone(),
+    });
+    config.save().map_err(|e| e.to_string())?;
+
+    app.dialog()
+        .message(format!(
+            "Der private Schlüssel wurde gespeichert unter:\n\n{}\n\n\
+            Bewahre ihn getrennt von deinen Backups auf. Ohne diesen Schlüssel \
+            können verschlüsselte Backups nicht wiederhergestellt werden.",
+            key_path.display()
+        ))
+        .title("Backup-Schlüssel erstellt")
+        .kind(MessageDialogKind::Warning)
+        .buttons(MessageDialogButtons::Ok)
+        .blocking_show();
+
+    info!("Generated new backup key pair");
+    Ok(key_pair.public_key)
+}
@@ -1,6 +1,104 @@
-use tauri::State;
+use log::{error, info};
+use serde::Serialize;
+use std::sync::atomic::{AtomicBool, AtomicU64, Ordering};
+use std::sync::Mutex;
+use std::thread;
+use std::time::{Duration, SystemTime};
+use tauri::{AppHandle, Emitter, Manager, State, Window, WindowEvent};
 
-use crate::{commands::PasswordManagerState, Config};
+use super::close_snapshot_window;
+use crate::{
+    commands::{refresh_tray_menu, HealthCache, PasswordManagerState},
+    service::{CryptoInfo, KeyInfo, VaultService},
+    utils::{system_idle_time, ConfirmationPin, SecureClipboard, SuspendDetector},
+    Config,
+};
+
+/// Event emitted while the vault keys are rotated.
+const ROTATION_PROGRESS_EVENT: &str = "vault-rotation-progress";
+/// Event emitted while usernames, URLs and notes are converted to the field encryption.
+const FIELD_ENCRYPTION_PROGRESS_EVENT: &str = "field-encryption-progress";
+/// The number of entries converted at once, the vault is locked only for one batch.
+const FIELD_MIGRATION_BATCH: usize = 50;
+/// Whether usernames, URLs and notes are being converted in the background.
+static FIELD_MIGRATION_RUNNING: AtomicBool = AtomicBool::new(false);
+/// Event emitted shortly before the session ends because of inactivity.
+const SESSION_EXPIRING_EVENT: &str = "session-expiring";
+/// Seconds before the end of the session the frontend is warned.
+const SESSION_WARNING_SECS: u64 = 60;
+/// How often the session watcher checks the time left.
+const SESSION_CHECK_INTERVAL: Duration = Duration::from_secs(10);
+/// How often the system idle time and suspends are checked.
+const SYSTEM_CHECK_INTERVAL: Duration = Duration::from_secs(5);
+
+#[derive(Clone, Serialize)]
+pub struct AutoLockSettings {
+    pub lock_on_minimize: bool,
+    pub lock_on_blur: bool,
+    pub lock_delay: u64,
+    pub lock_on_idle: u64,
+    pub lock_on_suspend: bool,
+}
+
+#[derive(Clone, Serialize)]
+pub struct RotationProgress {
+    pub done: usize,
+    pub total: usize,
+}
+
+pub struct AutoLockState {
+    generation: AtomicU64,
+    settings: Mutex<AutoLockSettings>,
+}
+
+impl AutoLockSettings {
+    /// Read the auto lock settings from the config.
+    ///
+    /// # Arguments
+    ///
+    /// * `config` - The config to read from.
+    ///
+    /// # Returns
+    ///
+    /// The auto lock settings.
+    pub fn from_config(config: &Config) -> Self {
+        Self {
+            lock_on_minimize: config.app.lock_on_minimize,
+            lock_on_blur: config.app.lock_on_blur,
+            lock_delay: config.app.lock_delay,
+            lock_on_idle: config.app.lock_on_idle,
+            lock_on_suspend: config.app.lock_on_suspend,
+        }
+    }
+}
+
+impl AutoLockState {
+    /// Create a new auto lock state.
+    ///
+    /// # Arguments
+    ///
+    /// * `settings` - The auto lock settings.
+    ///
+    /// # Returns
+    ///
+    /// A new auto lock state.
+    pub fn new(settings: AutoLockSettings) -> Self {
+        Self {
+            generation: AtomicU64::new(0),
+            settings: Mutex::new(settings),
+        }
+    }
+
+    /// Apply changed auto lock settings and cancel a pending lock.
+    ///
+    /// # Arguments
+    ///
+    /// * `config` - The config to read the settings from.
+    pub fn reload(&self, config: &Config) {
+        *self.settings.lock().unwrap() = AutoLockSettings::from_config(config);
+        self.generation.fetch_add(1, Ordering::SeqCst);
+    }
+}
 
 #[tauri::command]
 /// Get the auto logout time.
@@ -18,6 +116,22 @@ pub async fn get_auto_logout_time() -> Result<u64, String> {
     Ok(config.app.auto_logout_duration)
 }
 
+#[tauri::command]
+/// Get the absolute session lifetime.
+///
+/// # Returns
+///
+/// A Result containing the absolute session lifetime in minutes or an error.
+///
+/// # Errors
+///
+/// If the config cannot be loaded.
+pub async fn get_session_max_lifetime() -> Result<u64, String> {
+    let config = Config::load().map_err(|e| e.to_string())?;
+
+    Ok(config.app.session_max_lifetime)
+}
+
 #[tauri::command(rename_all = "camelCase")]
 /// Update the master password.
 ///
@@ -38,20 +152,514 @@ pub async fn update_master_password(
     current_password: String,
     new_password: String,
 ) -> Result<(), String> {
-    let mut state = state.0.lock().unwrap();
-    match state.as_mut() {
-        Some(pm) => match pm.update_master_password(&current_password, &new_password) {
-            Ok(_) => Ok(()),
-            Err(e) => Err(e.to_string()),
-        },
-        None => Err("Not logged in".into()),
+    VaultService::new(&state).update_master_password(&current_password, &new_password)
+}
+
+#[tauri::command(rename_all = "camelCase")]
+/// Re-encrypt the vault with a fresh salt and the current key parameters.
+///
+/// A full backup is created before anything is changed. The progress is emitted as
+/// `vault-rotation-progress` events and the session ends afterwards.
+///
+/// # Arguments
+///
+/// * `master_pass` - The master password.
+///
+/// # Returns
+///
+/// A Result containing the completion status or an error.
+///
+/// # Errors
+///
+/// If not logged in, the master password is wrong, the backup fails or the vault cannot
+/// be re-encrypted.
+pub async fn rotate_vault_keys(
+    app: AppHandle,
+    state: State<'_, PasswordManagerState>,
+    master_pass: String,
+) -> Result<(), String> {
+    VaultService::new(&state).rotate_vault_keys(&master_pass, |done, total| {
+        if let Err(e) = app.emit(ROTATION_PROGRESS_EVENT, RotationProgress { done, total }) {
+            error!("Failed to report key rotation progress: {}", e);
+        }
+    })
+}
+
+#[tauri::command]
+/// Get the key parameters of the open vault.
+///
+/// # Returns
+///
+/// A Result containing the key version, the iterations, the cipher and whether a rotation
+/// is recommended.
+///
+/// # Errors
+///
+/// If not logged in.
+pub async fn get_key_info(state: State<'_, PasswordManagerState>) -> Result<KeyInfo, String> {
+    VaultService::new(&state).key_info()
+}
+
+#[tauri::command]
+/// Get the encryption status of the open vault.
+///
+/// # Returns
+///
+/// A Result containing the SQLCipher version, the key derivation parameters, the cipher,
+/// the salt fingerprint and the date of the last key rotation.
+///
+/// # Errors
+///
+/// If not logged in or the SQLCipher version cannot be read.
+pub async fn get_crypto_info(state: State<'_, PasswordManagerState>) -> Result<CryptoInfo, String> {
+    VaultService::new(&state).crypto_info()
+}
+
+#[tauri::command]
+/// Encrypt usernames, URLs and notes in addition to the database ("paranoid mode").
+///
+/// The existing entries are converted in the background, the progress is emitted as
+/// `field-encryption-progress` events.
+///
+/// # Arguments
+///
+/// * `enabled` - Whether to encrypt the fields, false to store them as plaintext again.
+///
+/// # Returns
+///
+/// A Result containing the completion status or an error.
+///
+/// # Errors
+///
+/// If not logged in or the setting cannot be saved.
+pub async fn set_metadata_encryption(
+    app: AppHandle,
+    state: State<'_, PasswordManagerState>,
+    enabled: bool,
+) -> Result<(), String> {
+    VaultService::new(&state).set_field_encryption(enabled)?;
+
+    migrate_field_encryption(app);
+    Ok(())
+}
+
+/// Convert the usernames, URLs and notes of the vault to the current field encryption.
+///
+/// The entries are converted in batches in a background thread. The thread stops at logout
+/// and the remaining entries are converted after the next login.
+///
+/// # Arguments
+///
+/// * `app` - The app handle.
+pub fn migrate_field_encryption(app: AppHandle) {
+    if FIELD_MIGRATION_RUNNING.swap(true, Ordering::SeqCst) {
+        return;
     }
+
+    thread::spawn(move || {
+        let mut done = 0;
+        loop {
+            let state = app.state::<PasswordManagerState>();
+            let result = VaultService::new(&state).migrate_fields(FIELD_MIGRATION_BATCH);
+
+            match result {
+                Ok(None) | Ok(Some((0, _))) => break,
+                Ok(Some((migrated, pending))) => {
+                    let total = done + pending;
+                    done += migrated;
+                    if let Err(e) = app.emit(
+                        FIELD_ENCRYPTION_PROGRESS_EVENT,
+                        RotationProgress { done, total },
+                    ) {
+                        error!("Failed to report field encryption progress: {}", e);
+                    }
+                }
+                Err(e) => {
+                    error!("Failed to convert the field encryption: {}", e);
+                    break;
+                }
+            }
+        }
+        FIELD_MIGRATION_RUNNING.store(false, Ordering::SeqCst);
+    });
 }
 
 #[tauri::command]
-pub async fn save_security_settings(auto_logout_duration: u64) -> Result<(), String> {
+/// Check if the content protection of the main window is enabled.
+///
+/// # Returns
+///
+/// A Result containing a boolean indicating if content protection is enabled or an error.
+///
+/// # Errors
+///
+/// If the config cannot be loaded.
+pub async fn get_content_protection() -> Result<bool, String> {
+    let config = Config::load().map_err(|e| e.to_string())?;
+
+    Ok(config.app.content_protection)
+}
+
+#[tauri::command]
+/// Check if the on-screen keyboard for the master password is enabled.
+///
+/// # Returns
+///
+/// A Result containing a boolean indicating if the on-screen keyboard is enabled or an error.
+///
+/// # Errors
+///
+/// If the config cannot be loaded.
+pub async fn get_soft_keyboard() -> Result<bool, String> {
+    let config = Config::load().map_err(|e| e.to_string())?;
+
+    Ok(config.app.soft_keyboard)
+}
+
+#[tauri::command]
+/// Get how many secrets can be decrypted per minute without the master password.
+///
+/// # Returns
+///
+/// A Result containing the decryptions allowed per minute, 0 if unlimited, or an error.
+///
+/// # Errors
+///
+/// If the config cannot be loaded.
+pub async fn get_decrypt_limit() -> Result<u32, String> {
+    let config = Config::load().map_err(|e| e.to_string())?;
+
+    Ok(config.app.decrypt_limit)
+}
+
+#[tauri::command(rename_all = "camelCase")]
+/// Save the security settings.
+///
+/// # Arguments
+///
+/// * `auto_logout_duration` - The inactivity time in minutes before the session ends.
+/// * `session_max_lifetime` - The absolute session lifetime in minutes.
+/// * `content_protection` - Whether to hide the window content from screenshots and screen sharing.
+/// * `soft_keyboard` - Whether to offer an on-screen keyboard for the master password.
+/// * `lock_on_minimize` - Whether to lock the vault when the window is minimized.
+/// * `lock_on_blur` - Whether to lock the vault when the window loses focus.
+/// * `lock_delay` - The time in seconds before the vault is locked.
+/// * `lock_on_idle` - The minutes without input in the system before the vault is locked, 0 to disable.
+/// * `lock_on_suspend` - Whether to lock the vault when the system suspends.
+/// * `decrypt_limit` - The secrets that can be decrypted per minute without the master password, 0 if unlimited.
+///
+/// # Returns
+///
+/// A Result containing the completion status or an error.
+///
+/// # Errors
+///
+/// If the settings cannot be saved.
+#[allow(clippy::too_many_arguments)]
+pub async fn save_security_settings(
+    app: AppHandle,
+    auto_lock: State<'_, AutoLockState>,
+    state: State<'_, PasswordManagerState>,
+    auto_logout_duration: u64,
+    session_max_lifetime: u64,
+    content_protection: bool,
+    soft_keyboard: bool,
+    lock_on_minimize: bool,
+    lock_on_blur: bool,
+    lock_delay: u64,
+    lock_on_idle: u64,
+    lock_on_suspend: bool,
+    decrypt_limit: u32,
+) -> Result<(), String> {
+    if session_max_lifetime < auto_logout_duration {
+        return Err("Session lifetime must not be shorter than the auto logout time".into());
+    }
+
     let mut config = Config::load().map_err(|e| e.to_string())?;
     config.app.auto_logout_duration = auto_logout_duration;
+    config.app.session_max_lifetime = session_max_lifetime;
+    config.app.content_protection = content_protection;
+    config.app.soft_keyboard = soft_keyboard;
+    config.app.lock_on_minimize = lock_on_minimize;
+    config.app.lock_on_blur = lock_on_blur;
+    config.app.lock_delay = lock_delay;
+    config.app.lock_on_idle = lock_on_idle;
+    config.app.lock_on_suspend = lock_on_suspend;
+    config.app.decrypt_limit = decrypt_limit;
+    config.save().map_err(|e| e.to_string())?;
+
+    VaultService::new(&state).apply_decrypt_limit(decrypt_limit);
+
+    apply_content_protection(&app, content_protection);
+    auto_lock.reload(&config);
+    Ok(())
+}
+
+#[tauri::command]
+/// Check whether destructive actions have to be confirmed with a PIN.
+///
+/// # Returns
+///
+/// A Result containing whether a confirmation PIN is set or an error.
+///
+/// # Errors
+///
+/// If the config cannot be loaded.
+pub async fn has_confirmation_pin() -> Result<bool, String> {
+    let config = Config::load().map_err(|e| e.to_string())?;
+
+    Ok(config.app.confirmation_pin.is_some())
+}
+
+#[tauri::command(rename_all = "camelCase")]
+/// Set or remove the PIN that confirms exports and vault restores.
+///
+/// # Arguments
+///
+/// * `master_pass` - The master password, required to change the PIN.
+/// * `pin` - The new PIN, None to remove it.
+///
+/// # Returns
+///
+/// A Result containing the completion status or an error.
+///
+/// # Errors
+///
+/// If not logged in, the master password is wrong, the PIN is too short or equals the
+/// master password, or the config cannot be saved.
+pub async fn set_confirmation_pin(
+    state: State<'_, PasswordManagerState>,
+    master_pass: String,
+    pin: Option<String>,
+) -> Result<(), String> {
+    VaultService::new(&state).verify_master_password(&master_pass)?;
+
+    let confirmation_pin = match pin {
+        Some(pin) if pin == master_pass => {
+            return Err("The confirmation PIN must differ from the master password".into())
+        }
+        Some(pin) => Some(ConfirmationPin::new(&pin).map_err(|e| e.to_string())?),
+        None => None,
+    };
+
+    let mut config = Config::load().map_err(|e| e.to_string())?;
+    config.app.confirmation_pin = confirmation_pin;
     config.save().map_err(|e| e.to_string())?;
+    info!(
+        "Confirmation PIN {}",
+        if config.app.confirmation_pin.is_some() {
+            "set"
+        } else {
+            "removed"
+        }
+    );
+
     Ok(())
 }
+
+#[tauri::command]
+/// Get the auto lock settings.
+///
+/// # Returns
+///
+/// A Result containing the auto lock settings or an error.
+///
+/// # Errors
+///
+/// If the config cannot be loaded.
+pub async fn get_auto_lock_settings() -> Result<AutoLockSettings, String> {
+    let config = Config::load().map_err(|e| e.to_string())?;
+
+    Ok(AutoLockSettings::from_config(&config))
+}
+
+/// Schedule or cancel the auto lock for a window event.
+///
+/// Minimizing or unfocusing the window starts the lock timer if enabled, focusing the
+/// window again cancels it.
+///
+/// # Arguments
+///
+/// * `window` - The window that emitted the event.
+/// * `event` - The window event.
+pub fn handle_auto_lock(window: &Window, event: &WindowEvent) {
+    let state = window.state::<AutoLockState>();
+    let settings = state.settings.lock().unwrap().clone();
+
+    let should_lock = match event {
+        WindowEvent::Focused(true) => {
+            state.generation.fetch_add(1, Ordering::SeqCst);
+            return;
+        }
+        WindowEvent::Focused(false) => settings.lock_on_blur,
+        WindowEvent::Resized(_) => {
+            settings.lock_on_minimize && window.is_minimized().unwrap_or(false)
+        }
+        _ => false,
+    };
+
+    if !should_lock {
+        return;
+    }
+
+    let generation = state.generation.fetch_add(1, Ordering::SeqCst) + 1;
+    let app = window.app_handle().clone();
+    thread::spawn(move || {
+        thread::sleep(Duration::from_secs(settings.lock_delay));
+        if app
+            .state::<AutoLockState>()
+            .generation
+            .load(Ordering::SeqCst)
+            == generation
+        {
+            lock_vault(&app);
+        }
+    });
+}
+
+#[tauri::command]
+/// Keep the session alive, either after the inactivity warning or when the frontend
+/// reports user activity.
+///
+/// # Returns
+///
+/// A Result containing a unit or an error.
+///
+/// # Errors
+///
+/// If not logged in or the session already ended.
+pub async fn extend_session(state: State<'_, PasswordManagerState>) -> Result<(), String> {
+    VaultService::new(&state).extend_session()
+}
+
+/// Watch the session of the logged in user in the background.
+///
+/// The frontend is warned once a minute before the session ends, so the user can stay
+/// logged in. An ended session locks the vault.
+///
+/// # Arguments
+///
+/// * `app` - The app handle.
+pub fn watch_session(app: AppHandle) {
+    thread::spawn(move || {
+        let mut warned = false;
+        loop {
+            thread::sleep(SESSION_CHECK_INTERVAL);
+
+            let timeout = match &*app.state::<PasswordManagerState>().0.lock().unwrap() {
+                Some(pm) => pm.session_timeout().unwrap_or_else(|e| {
+                    error!("Failed to read session timeout: {}", e);
+                    None
+                }),
+                None => {
+                    warned = false;
+                    continue;
+                }
+            };
+
+            match timeout {
+                Some(timeout) if timeout.seconds > SESSION_WARNING_SECS => warned = false,
+                Some(timeout) if timeout.seconds > 0 => {
+                    if !warned {
+                        warned = true;
+                        if let Err(e) = app.emit(SESSION_EXPIRING_EVENT, timeout) {
+                            error!("Failed to warn frontend about session end: {}", e);
+                        }
+                    }
+                }
+                _ => {
+                    warned = false;
+                    lock_vault(&app);
+                }
+            }
+        }
+    });
+}
+
+/// Watch the idle time of the system and suspends in the background.
+///
+/// The vault is locked once nobody used the keyboard or mouse for the configured minutes,
+/// even in other apps, or right after the system resumes from a suspend if enabled.
+///
+/// # Arguments
+///
+/// * `app` - The app handle.
+pub fn watch_system_activity(app: AppHandle) {
+    thread::spawn(move || {
+        let mut suspend = SuspendDetector::new(SYSTEM_CHECK_INTERVAL, SystemTime::now());
+        loop {
+            thread::sleep(SYSTEM_CHECK_INTERVAL);
+            let suspended = suspend.check(SystemTime::now());
+
+            if app
+                .state::<PasswordManagerState>()
+                .0
+                .lock()
+                .unwrap()
+                .is_none()
+            {
+                continue;
+            }
+            let settings = app
+                .state::<AutoLockState>()
+                .settings
+                .lock()
+                .unwrap()
+                .clone();
+
+            if suspended && settings.lock_on_suspend {
+                info!("System resumed from suspend");
+                lock_vault(&app);
+            } else if settings.lock_on_idle > 0
+                && system_idle_time()
+                    .is_some_and(|idle| idle >= Duration::from_secs(settings.lock_on_idle * 60))
+            {
+                info!("System idle for {} minutes", settings.lock_on_idle);
+                lock_vault(&app);
+            }
+        }
+    });
+}
+
+/// Log out the current user and tell the frontend to show the login again.
+///
+/// # Arguments
+///
+/// * `app` - The app handle.
+pub fn lock_vault(app: &AppHandle) {
+    let Some(pm) = app.state::<PasswordManagerState>().0.lock().unwrap().take() else {
+        return;
+    };
+
+    if let Err(e) = pm.logout() {
+        error!("Failed to clear session while locking: {}", e);
+    }
+    app.state::<HealthCache>().clear();
+    close_snapshot_window(app);
+    if let Err(e) = app.state::<SecureClipboard>().clear() {
+        error!("Failed to clear clipboard while locking: {}", e);
+    }
+    info!("Vault locked");
+    refresh_tray_menu(app);
+
+    if let Err(e) = app.emit("vault-locked", ()) {
+        error!("Failed to notify frontend about lock: {}", e);
+    }
+}
+
+/// Set the content protection flag of the main window.
+///
+/// Screenshots and screen sharing tools show a black window while the flag is set.
+/// Platforms without support ignore the flag.
+///
+/// # Arguments
+///
+/// * `app` - The app handle.
+/// * `enabled` - Whether content protection should be enabled.
+pub fn apply_content_protection(app: &AppHandle, enabled: bool) {
+    if let Some(window) = app.get_webview_window("main") {
+        if let Err(e) = window.set_content_protected(enabled) {
+            error!("Failed to set content protection: {}", e);
+        }
+    }
+}
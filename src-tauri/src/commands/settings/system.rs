@@ -1,10 +1,625 @@
-use log::info;
-use tauri::AppHandle;
+use log::{error, info};
+use serde::Serialize;
+use std::fs;
+use std::path::PathBuf;
+use std::time::Duration;
+use tauri::{
+    menu::{Menu, MenuItem, PredefinedMenuItem},
+    tray::TrayIconBuilder,
+    AppHandle, Manager, State, WebviewUrl, WebviewWindow, WebviewWindowBuilder, Window,
+};
 use tauri_plugin_autostart::ManagerExt;
+use tauri_plugin_global_shortcut::{GlobalShortcutExt, Shortcut, ShortcutState};
 use tauri_plugin_opener::OpenerExt;
 use tauri_plugin_updater::UpdaterExt;
 
-use crate::Config;
+use super::lock_vault;
+use crate::{
+    commands::{record_audit, PasswordManagerState},
+    utils::{
+        AuditEvent, AutostartMode, CommonPasswords, DefaultTab, EntrySummary, SecureClipboard,
+    },
+    BackupManager, Config,
+};
+
+/// Launch argument the autostart entry passes to the app.
+pub const AUTOSTART_ARG: &str = "--autostart";
+
+const COMMON_PASSWORDS_URL: &str =
+    "https://raw.githubusercontent.com/KarnesTH/karncrypt/main/src-tauri/src/utils/common_passwords.txt";
+const MAX_COMMON_PASSWORDS_SIZE: u64 = 16 * 1024 * 1024;
+const GENERATOR_WINDOW: &str = "generator";
+pub const QUICK_SEARCH_WINDOW: &str = "quick-search";
+/// The maximum number of entries the quick search shows.
+const QUICK_SEARCH_LIMIT: usize = 8;
+const TRAY_ID: &str = "main";
+/// Prefix of the tray menu items that copy the password of a pinned or recent entry.
+const COPY_ITEM_PREFIX: &str = "copy:";
+/// Time after which a password copied from the tray is removed from the clipboard.
+const CLIPBOARD_CLEAR_DELAY: Duration = Duration::from_secs(30);
+/// The lowest opacity of the main window in percent, so it stays readable.
+const MIN_WINDOW_OPACITY: u8 = 60;
+
+/// Whether the main window is kept on top and how opaque it is then.
+#[derive(Serialize)]
+pub struct WindowPin {
+    always_on_top: bool,
+    opacity: u8,
+}
+
+pub struct LaunchState {
+    pub autostarted: bool,
+}
+
+impl LaunchState {
+    /// Detect from the launch arguments if the app was started by the autostart.
+    ///
+    /// # Returns
+    ///
+    /// The launch state of the app.
+    pub fn from_args() -> Self {
+        Self {
+            autostarted: std::env::args().any(|arg| arg == AUTOSTART_ARG),
+        }
+    }
+
+    /// Get the autostart mode the app was launched in.
+    ///
+    /// # Arguments
+    ///
+    /// * `config` - The config of the app.
+    ///
+    /// # Returns
+    ///
+    /// The configured autostart mode or None if the app was started manually.
+    pub fn autostart_mode(&self, config: &Config) -> Option<AutostartMode> {
+        self.autostarted.then_some(config.app.autostart_mode)
+    }
+}
+
+/// Start the app hidden in the system tray.
+///
+/// The main window is hidden until it is opened from the tray menu. The vault stays locked,
+/// so opening the window shows the login.
+///
+/// # Arguments
+///
+/// * `app` - The app handle.
+///
+/// # Returns
+///
+/// A Result containing the completion status or an error.
+///
+/// # Errors
+///
+/// If the tray icon cannot be created.
+pub fn start_in_tray(app: &AppHandle) -> tauri::Result<()> {
+    create_tray(app)?;
+
+    if let Some(window) = app.get_webview_window("main") {
+        window.hide()?;
+    }
+
+    Ok(())
+}
+
+/// Create the tray icon with its menu, if it does not exist yet.
+///
+/// # Arguments
+///
+/// * `app` - The app handle.
+///
+/// # Returns
+///
+/// A Result containing the completion status or an error.
+///
+/// # Errors
+///
+/// If the tray icon cannot be created.
+pub fn create_tray(app: &AppHandle) -> tauri::Result<()> {
+    if app.tray_by_id(TRAY_ID).is_some() {
+        return Ok(());
+    }
+    let menu = build_tray_menu(app)?;
+
+    let mut tray = TrayIconBuilder::with_id(TRAY_ID)
+        .tooltip("KarnCrypt")
+        .menu(&menu)
+        .on_menu_event(|app, event| match event.id.as_ref() {
+            "open" => {
+                if let Some(window) = app.get_webview_window("main") {
+                    let _ = window.show();
+                    let _ = window.set_focus();
+                }
+            }
+            "generator" => {
+                if let Err(e) = show_generator_window(app) {
+                    error!("Failed to open generator window: {}", e);
+                }
+            }
+            "lock" => lock_vault(app),
+            "quit" => app.exit(0),
+            id => {
+                if let Some(entry_id) = id
+                    .strip_prefix(COPY_ITEM_PREFIX)
+                    .and_then(|id| id.parse().ok())
+                {
+                    if let Err(e) = copy_entry_password(app, entry_id) {
+                        error!("Failed to copy password from the tray: {}", e);
+                    }
+                }
+            }
+        });
+    if let Some(icon) = app.default_window_icon() {
+        tray = tray.icon(icon.clone());
+    }
+    tray.build(app)?;
+
+    Ok(())
+}
+
+/// Remove the tray icon, unless the app was started hidden in the tray.
+///
+/// # Arguments
+///
+/// * `app` - The app handle.
+fn remove_tray(app: &AppHandle) {
+    let launch = app.state::<LaunchState>();
+    let started_in_tray = Config::load()
+        .is_ok_and(|config| launch.autostart_mode(&config) == Some(AutostartMode::Tray));
+    if !started_in_tray {
+        app.remove_tray_by_id(TRAY_ID);
+    }
+}
+
+/// Hide the main window in the tray instead of closing it, if configured.
+///
+/// # Arguments
+///
+/// * `window` - The main window that is about to be closed.
+///
+/// # Returns
+///
+/// True if the window was hidden and must not be closed.
+pub fn hide_to_tray(window: &Window) -> bool {
+    let app = window.app_handle();
+    let enabled = Config::load().is_ok_and(|config| config.app.minimize_to_tray);
+    if !enabled || app.tray_by_id(TRAY_ID).is_none() {
+        return false;
+    }
+
+    if let Err(e) = window.hide() {
+        error!("Failed to hide window in the tray: {}", e);
+        return false;
+    }
+    true
+}
+
+/// Build the tray menu with the pinned and recently used entries of the logged in user.
+///
+/// # Arguments
+///
+/// * `app` - The app handle.
+///
+/// # Returns
+///
+/// A Result containing the tray menu or an error.
+///
+/// # Errors
+///
+/// If the menu cannot be created.
+fn build_tray_menu(app: &AppHandle) -> tauri::Result<Menu<tauri::Wry>> {
+    let menu = Menu::new(app)?;
+    menu.append(&MenuItem::with_id(
+        app,
+        "open",
+        "KarnCrypt öffnen",
+        true,
+        None::<&str>,
+    )?)?;
+    menu.append(&MenuItem::with_id(
+        app,
+        "generator",
+        "Passwort generieren",
+        true,
+        None::<&str>,
+    )?)?;
+
+    let (logged_in, pinned, recent) = match app
+        .state::<PasswordManagerState>()
+        .0
+        .lock()
+        .unwrap()
+        .as_ref()
+    {
+        Some(pm) => (
+            true,
+            pm.get_pinned_entries().unwrap_or_else(|e| {
+                error!("Failed to load pinned entries for the tray: {}", e);
+                Vec::new()
+            }),
+            pm.get_recent_entries().unwrap_or_else(|e| {
+                error!("Failed to load recent entries for the tray: {}", e);
+                Vec::new()
+            }),
+        ),
+        None => (false, Vec::new(), Vec::new()),
+    };
+    menu.append(&MenuItem::with_id(
+        app,
+        "lock",
+        "Tresor sperren",
+        logged_in,
+        None::<&str>,
+    )?)?;
+
+    let recent: Vec<_> = recent
+        .into_iter()
+        .filter(|entry| !pinned.iter().any(|pinned| pinned.id == entry.id))
+        .collect();
+    for (entries, label) in [
+        (&pinned, "Passwort kopieren"),
+        (&recent, "Zuletzt verwendet"),
+    ] {
+        if entries.is_empty() {
+            continue;
+        }
+        menu.append(&PredefinedMenuItem::separator(app)?)?;
+        for entry in entries {
+            let Some(id) = entry.id else {
+                continue;
+            };
+            menu.append(&MenuItem::with_id(
+                app,
+                format!("{}{}", COPY_ITEM_PREFIX, id),
+                format!("{}: {}", label, entry.service),
+                true,
+                None::<&str>,
+            )?)?;
+        }
+    }
+    menu.append(&PredefinedMenuItem::separator(app)?)?;
+
+    menu.append(&MenuItem::with_id(
+        app,
+        "quit",
+        "Beenden",
+        true,
+        None::<&str>,
+    )?)?;
+
+    Ok(menu)
+}
+
+/// Rebuild the tray menu, e.g. after the pinned entries or the login changed.
+///
+/// Does nothing if the app was not started in the tray.
+///
+/// # Arguments
+///
+/// * `app` - The app handle.
+pub fn refresh_tray_menu(app: &AppHandle) {
+    let Some(tray) = app.tray_by_id(TRAY_ID) else {
+        return;
+    };
+
+    if let Err(e) = build_tray_menu(app).and_then(|menu| tray.set_menu(Some(menu))) {
+        error!("Failed to refresh tray menu: {}", e);
+    }
+}
+
+/// Copy the password of an entry from the tray or the quick search and clear the clipboard
+/// again after a delay.
+///
+/// The clipboard is only cleared if nothing else was copied in the meantime.
+///
+/// # Arguments
+///
+/// * `app` - The app handle.
+/// * `id` - The ID of the entry.
+///
+/// # Returns
+///
+/// A Result containing the completion status or an error.
+///
+/// # Errors
+///
+/// If not logged in, the entry is protected by the master password or the password cannot
+/// be copied.
+fn copy_entry_password(app: &AppHandle, id: i32) -> Result<(), String> {
+    let password = {
+        let state = app.state::<PasswordManagerState>();
+        let mut state = state.0.lock().unwrap();
+        let pm = state.as_mut().ok_or("Not logged in")?;
+        let password = pm
+            .decrypt_password_by_id(id, None)
+            .map_err(|e| e.to_string())?;
+        pm.record_recent_entry(id);
+        password
+    };
+
+    let clipboard = app.state::<SecureClipboard>();
+    clipboard.copy(&password).map_err(|e| e.to_string())?;
+    refresh_tray_menu(app);
+    let copies = clipboard.copies();
+
+    let app = app.clone();
+    std::thread::spawn(move || {
+        std::thread::sleep(CLIPBOARD_CLEAR_DELAY);
+        if let Err(e) = app
+            .state::<SecureClipboard>()
+            .clear_unless_copied_since(copies)
+        {
+            error!("Failed to clear clipboard after quick copy: {}", e);
+        }
+    });
+
+    Ok(())
+}
+
+/// Show the standalone password generator, creating its window if needed.
+///
+/// The generator does not need the vault, so it can be used while the vault is locked.
+///
+/// # Arguments
+///
+/// * `app` - The app handle.
+///
+/// # Returns
+///
+/// A Result containing the completion status or an error.
+///
+/// # Errors
+///
+/// If the window cannot be created or focused.
+fn show_generator_window(app: &AppHandle) -> tauri::Result<()> {
+    if let Some(window) = app.get_webview_window(GENERATOR_WINDOW) {
+        window.show()?;
+        return window.set_focus();
+    }
+
+    WebviewWindowBuilder::new(
+        app,
+        GENERATOR_WINDOW,
+        WebviewUrl::App("index.html?view=generator".into()),
+    )
+    .title("KarnCrypt Generator")
+    .inner_size(760.0, 420.0)
+    .resizable(false)
+    .build()?;
+
+    Ok(())
+}
+
+/// Show the quick search popup on top of all windows, creating it if needed.
+///
+/// # Arguments
+///
+/// * `app` - The app handle.
+///
+/// # Returns
+///
+/// A Result containing the completion status or an error.
+///
+/// # Errors
+///
+/// If the window cannot be created.
+fn show_quick_search_window(app: &AppHandle) -> tauri::Result<()> {
+    if let Some(window) = app.get_webview_window(QUICK_SEARCH_WINDOW) {
+        window.show()?;
+        return window.set_focus();
+    }
+
+    WebviewWindowBuilder::new(
+        app,
+        QUICK_SEARCH_WINDOW,
+        WebviewUrl::App("index.html?view=quick-search".into()),
+    )
+    .title("KarnCrypt Schnellsuche")
+    .inner_size(520.0, 380.0)
+    .resizable(false)
+    .decorations(false)
+    .always_on_top(true)
+    .skip_taskbar(true)
+    .center()
+    .focused(true)
+    .build()?;
+
+    Ok(())
+}
+
+/// Close the quick search popup, if it is open.
+///
+/// # Arguments
+///
+/// * `app` - The app handle.
+pub fn close_quick_search_window(app: &AppHandle) {
+    if let Some(window) = app.get_webview_window(QUICK_SEARCH_WINDOW) {
+        if let Err(e) = window.destroy() {
+            error!("Failed to close quick search window: {}", e);
+        }
+    }
+}
+
+/// Create the plugin that opens the quick search when the global shortcut is pressed.
+///
+/// # Returns
+///
+/// The global shortcut plugin.
+pub fn quick_search_shortcut_plugin() -> tauri::plugin::TauriPlugin<tauri::Wry> {
+    tauri_plugin_global_shortcut::Builder::new()
+        .with_handler(|app, _, event| {
+            if event.state() == ShortcutState::Pressed {
+                if let Err(e) = show_quick_search_window(app) {
+                    error!("Failed to open quick search window: {}", e);
+                }
+            }
+        })
+        .build()
+}
+
+/// Register the global shortcut that opens the quick search.
+///
+/// A previously registered shortcut is released first.
+///
+/// # Arguments
+///
+/// * `app` - The app handle.
+/// * `shortcut` - The shortcut, e.g. `Ctrl+Shift+K`, None to register none.
+///
+/// # Returns
+///
+/// A Result containing the completion status or an error.
+///
+/// # Errors
+///
+/// If the shortcut is invalid, has no modifier key or is taken by another application.
+pub fn register_quick_search_shortcut(
+    app: &AppHandle,
+    shortcut: Option<&str>,
+) -> Result<(), Box<dyn std::error::Error>> {
+    let shortcut = shortcut
+        .map(|shortcut| {
+            shortcut
+                .parse::<Shortcut>()
+                .map_err(|_| format!("Invalid shortcut: {}", shortcut))
+        })
+        .transpose()?;
+    if shortcut.is_some_and(|shortcut| shortcut.mods.is_empty()) {
+        return Err("The shortcut needs at least one modifier key".into());
+    }
+
+    let global_shortcut = app.global_shortcut();
+    global_shortcut.unregister_all()?;
+    if let Some(shortcut) = shortcut {
+        global_shortcut.register(shortcut)?;
+        info!("Registered quick search shortcut {}", shortcut);
+    }
+
+    Ok(())
+}
+
+#[tauri::command]
+/// Get the global shortcut that opens the quick search.
+///
+/// # Returns
+///
+/// A Result containing the shortcut, None if it is disabled, or an error.
+///
+/// # Errors
+///
+/// If the config cannot be loaded.
+pub async fn get_quick_search_shortcut() -> Result<Option<String>, String> {
+    let config = Config::load().map_err(|e| e.to_string())?;
+
+    Ok(config.app.quick_search_shortcut)
+}
+
+#[tauri::command]
+/// Set the global shortcut that opens the quick search.
+///
+/// The shortcut is registered right away, so a shortcut taken by another application is
+/// rejected before it is saved.
+///
+/// # Arguments
+///
+/// * `shortcut` - The shortcut, e.g. `Ctrl+Shift+K`, None or empty to disable it.
+///
+/// # Returns
+///
+/// A Result containing the completion status or an error.
+///
+/// # Errors
+///
+/// If the shortcut cannot be registered or the config cannot be saved.
+pub async fn set_quick_search_shortcut(
+    app: AppHandle,
+    shortcut: Option<String>,
+) -> Result<(), String> {
+    let shortcut = shortcut
+        .map(|shortcut| shortcut.trim().to_string())
+        .filter(|shortcut| !shortcut.is_empty());
+    register_quick_search_shortcut(&app, shortcut.as_deref()).map_err(|e| {
+        error!("Failed to register quick search shortcut: {}", e);
+        e.to_string()
+    })?;
+
+    let mut config = Config::load().map_err(|e| e.to_string())?;
+    config.app.quick_search_shortcut = shortcut;
+    config.save().map_err(|e| e.to_string())?;
+
+    Ok(())
+}
+
+#[tauri::command]
+/// Find the entries for the quick search popup.
+///
+/// # Arguments
+///
+/// * `query` - The text typed by the user, empty for the recently used entries.
+///
+/// # Returns
+///
+/// A Result containing the best matching entries, best first, or an error.
+///
+/// # Errors
+///
+/// If not logged in or the entries cannot be retrieved.
+pub async fn quick_search(
+    state: State<'_, PasswordManagerState>,
+    query: String,
+) -> Result<Vec<EntrySummary>, String> {
+    let state = state.0.lock().unwrap();
+    let pm = state.as_ref().ok_or("Not logged in")?;
+
+    let entries = pm
+        .quick_search(&query, QUICK_SEARCH_LIMIT)
+        .map_err(|e| e.to_string())?;
+    Ok(entries.iter().map(EntrySummary::from).collect())
+}
+
+#[tauri::command]
+/// Copy the password of the entry selected in the quick search and close the popup.
+///
+/// The clipboard is cleared again after a delay.
+///
+/// # Arguments
+///
+/// * `id` - The ID of the password entry.
+///
+/// # Returns
+///
+/// A Result containing the completion status or an error.
+///
+/// # Errors
+///
+/// If not logged in or the password cannot be copied.
+pub async fn copy_quick_search_entry(app: AppHandle, id: i32) -> Result<(), String> {
+    copy_entry_password(&app, id)?;
+    close_quick_search_window(&app);
+
+    Ok(())
+}
+
+#[tauri::command]
+/// Close the quick search popup.
+pub async fn close_quick_search(app: AppHandle) {
+    close_quick_search_window(&app);
+}
+
+#[tauri::command]
+/// Open the password generator in its own window.
+///
+/// # Returns
+///
+/// A Result containing the completion status or an error.
+///
+/// # Errors
+///
+/// If the window cannot be opened.
+pub async fn open_generator_window(app: AppHandle) -> Result<(), String> {
+    show_generator_window(&app).map_err(|e| e.to_string())
+}
 
 #[tauri::command]
 /// Open the log folder in the file manager.
@@ -29,6 +644,9 @@ pub async fn open_log_folder(app: AppHandle) -> Result<(), String> {
 #[tauri::command]
 /// Check for updates and install them if available.
 ///
+/// A safety backup of the vault is created before an update is installed. If the backup
+/// fails, the update is skipped. Does nothing while offline mode is enabled.
+///
 /// # Returns
 ///
 /// A Result containing the completion status or an error.
@@ -37,7 +655,18 @@ pub async fn open_log_folder(app: AppHandle) -> Result<(), String> {
 ///
 /// If the updater fails to check for updates or install them.
 pub async fn check_update(app: AppHandle) -> tauri_plugin_updater::Result<()> {
+    if Config::load().is_ok_and(|config| config.app.offline_mode) {
+        info!("offline mode enabled, skipping update check");
+        return Ok(());
+    }
+
     if let Some(update) = app.updater()?.check().await? {
+        info!("update {} found", update.version);
+        if let Err(e) = create_update_backup() {
+            error!("safety backup before update failed, skipping update: {}", e);
+            return Ok(());
+        }
+
         let mut downloaded = 0;
 
         update
@@ -62,9 +691,13 @@ pub async fn check_update(app: AppHandle) -> tauri_plugin_updater::Result<()> {
 #[tauri::command]
 /// Toggle the autostart setting.
 ///
+/// The autostart entry launches the app with the autostart argument, the mode decides what
+/// the app starts into when launched this way.
+///
 /// # Arguments
 ///
 /// * `enable` - A boolean indicating if autostart should be enabled.
+/// * `mode` - What the app starts into when launched by the autostart.
 ///
 /// # Returns
 ///
@@ -72,8 +705,16 @@ pub async fn check_update(app: AppHandle) -> tauri_plugin_updater::Result<()> {
 ///
 /// # Errors
 ///
-/// If the autostart setting cannot be toggled.
-pub async fn toggle_autostart(app: AppHandle, enable: bool) -> Result<(), String> {
+/// If the autostart setting cannot be toggled or the config cannot be saved.
+pub async fn toggle_autostart(
+    app: AppHandle,
+    enable: bool,
+    mode: AutostartMode,
+) -> Result<(), String> {
+    let mut config = Config::load().map_err(|e| e.to_string())?;
+    config.app.autostart_mode = mode;
+    config.save().map_err(|e| e.to_string())?;
+
     #[cfg(desktop)]
     {
         if enable {
@@ -106,3 +747,509 @@ pub async fn is_autostart_enabled(app: AppHandle) -> Result<bool, String> {
         Ok(false)
     }
 }
+
+#[tauri::command]
+/// Get the autostart mode.
+///
+/// # Returns
+///
+/// A Result containing what the app starts into when launched by the autostart or an error.
+///
+/// # Errors
+///
+/// If the config cannot be loaded.
+pub async fn get_autostart_mode() -> Result<AutostartMode, String> {
+    let config = Config::load().map_err(|e| e.to_string())?;
+
+    Ok(config.app.autostart_mode)
+}
+
+#[tauri::command]
+/// Get the tab to show after unlocking the vault.
+///
+/// # Returns
+///
+/// A Result containing the last used tab if the app was autostarted into the last view,
+/// otherwise the default tab, or an error.
+///
+/// # Errors
+///
+/// If the config cannot be loaded.
+pub async fn get_launch_view(launch: State<'_, LaunchState>) -> Result<DefaultTab, String> {
+    let config = Config::load().map_err(|e| e.to_string())?;
+
+    Ok(config.view.launch_tab(launch.autostart_mode(&config)))
+}
+
+#[tauri::command]
+/// Remember the currently shown tab for the next autostart.
+///
+/// # Arguments
+///
+/// * `tab` - The currently shown tab.
+///
+/// # Returns
+///
+/// A Result containing the completion status or an error.
+///
+/// # Errors
+///
+/// If the config cannot be saved.
+pub async fn save_last_view(tab: DefaultTab) -> Result<(), String> {
+    let mut config = Config::load().map_err(|e| e.to_string())?;
+    if config.view.last_tab != tab {
+        config.view.last_tab = tab;
+        config.save().map_err(|e| e.to_string())?;
+    }
+
+    Ok(())
+}
+
+#[tauri::command]
+/// Check if offline mode is enabled.
+///
+/// # Returns
+///
+/// A Result containing a boolean indicating if offline mode is enabled or an error.
+///
+/// # Errors
+///
+/// If the config cannot be loaded.
+pub async fn get_offline_mode() -> Result<bool, String> {
+    let config = Config::load().map_err(|e| e.to_string())?;
+
+    Ok(config.app.offline_mode)
+}
+
+#[tauri::command]
+/// Enable or disable offline mode.
+///
+/// While offline mode is enabled the app does not connect to the internet.
+///
+/// # Arguments
+///
+/// * `enable` - A boolean indicating if offline mode should be enabled.
+///
+/// # Returns
+///
+/// A Result containing the completion status or an error.
+///
+/// # Errors
+///
+/// If the config cannot be saved.
+pub async fn set_offline_mode(enable: bool) -> Result<(), String> {
+    let mut config = Config::load().map_err(|e| e.to_string())?;
+    config.app.offline_mode = enable;
+    config.save().map_err(|e| e.to_string())?;
+
+    Ok(())
+}
+
+#[tauri::command]
+/// Check if the passwords are checked against Have I Been Pwned.
+///
+/// # Returns
+///
+/// A Result containing a boolean indicating if the breach check is enabled or an error.
+///
+/// # Errors
+///
+/// If the config cannot be loaded.
+pub async fn get_breach_check() -> Result<bool, String> {
+    let config = Config::load().map_err(|e| e.to_string())?;
+
+    Ok(config.app.breach_check)
+}
+
+#[tauri::command]
+/// Enable or disable the breach check.
+///
+/// The check is opt-in, because it sends the first characters of each password hash to
+/// Have I Been Pwned.
+///
+/// # Arguments
+///
+/// * `enable` - A boolean indicating if the breach check should be enabled.
+///
+/// # Returns
+///
+/// A Result containing the completion status or an error.
+///
+/// # Errors
+///
+/// If the config cannot be saved.
+pub async fn set_breach_check(enable: bool) -> Result<(), String> {
+    let mut config = Config::load().map_err(|e| e.to_string())?;
+    config.app.breach_check = enable;
+    config.save().map_err(|e| e.to_string())?;
+
+    Ok(())
+}
+
+#[tauri::command]
+/// Check if the local JSON-RPC interface is enabled.
+///
+/// # Returns
+///
+/// A Result containing a boolean indicating if the interface is enabled or an error.
+///
+/// # Errors
+///
+/// If the config cannot be loaded.
+pub async fn get_rpc_enabled() -> Result<bool, String> {
+    let config = Config::load().map_err(|e| e.to_string())?;
+
+    Ok(config.app.rpc_enabled)
+}
+
+#[tauri::command]
+/// Enable or disable the local JSON-RPC interface.
+///
+/// The interface only listens on the loopback interface and is started with the app, so
+/// the change takes effect after a restart.
+///
+/// # Arguments
+///
+/// * `enable` - A boolean indicating if the interface should be enabled.
+///
+/// # Returns
+///
+/// A Result containing the completion status or an error.
+///
+/// # Errors
+///
+/// If the config cannot be saved.
+pub async fn set_rpc_enabled(enable: bool) -> Result<(), String> {
+    let mut config = Config::load().map_err(|e| e.to_string())?;
+    config.app.rpc_enabled = enable;
+    config.save().map_err(|e| e.to_string())?;
+
+    Ok(())
+}
+
+#[tauri::command]
+/// Check if closing the main window hides it in the tray.
+///
+/// # Returns
+///
+/// A Result containing a boolean indicating if the window is hidden in the tray or an error.
+///
+/// # Errors
+///
+/// If the config cannot be loaded.
+pub async fn get_minimize_to_tray() -> Result<bool, String> {
+    let config = Config::load().map_err(|e| e.to_string())?;
+
+    Ok(config.app.minimize_to_tray)
+}
+
+#[tauri::command]
+/// Check if a notification about expiring passwords is shown after login.
+///
+/// # Returns
+///
+/// A Result containing a boolean indicating if the notification is shown or an error.
+///
+/// # Errors
+///
+/// If the config cannot be loaded.
+pub async fn get_expiry_reminder() -> Result<bool, String> {
+    let config = Config::load().map_err(|e| e.to_string())?;
+
+    Ok(config.app.expiry_reminder)
+}
+
+#[tauri::command]
+/// Enable or disable the notification about expiring passwords after login.
+///
+/// # Arguments
+///
+/// * `enable` - A boolean indicating if the notification should be shown.
+///
+/// # Returns
+///
+/// A Result containing the completion status or an error.
+///
+/// # Errors
+///
+/// If the config cannot be saved.
+pub async fn set_expiry_reminder(enable: bool) -> Result<(), String> {
+    let mut config = Config::load().map_err(|e| e.to_string())?;
+    config.app.expiry_reminder = enable;
+    config.save().map_err(|e| e.to_string())?;
+    info!(
+        "{} the expiry reminder",
+        if enable { "Enabled" } else { "Disabled" }
+    );
+
+    Ok(())
+}
+
+/// Keep the main window above other windows, slightly transparent if configured.
+///
+/// The window is opaque while it is not kept on top. Linux needs a compositor for the
+/// transparency.
+///
+/// # Arguments
+///
+/// * `app` - The app handle.
+/// * `always_on_top` - Whether the window is kept above other windows.
+/// * `opacity` - The opacity in percent while the window is kept on top.
+pub fn apply_window_pin(app: &AppHandle, always_on_top: bool, opacity: u8) {
+    let Some(window) = app.get_webview_window("main") else {
+        return;
+    };
+    if let Err(e) = window.set_always_on_top(always_on_top) {
+        error!("Failed to set always on top: {}", e);
+    }
+
+    let opacity = if always_on_top {
+        f64::from(opacity.clamp(MIN_WINDOW_OPACITY, 100)) / 100.0
+    } else {
+        1.0
+    };
+    if let Err(e) = app.run_on_main_thread(move || set_window_opacity(&window, opacity)) {
+        error!("Failed to set window opacity: {}", e);
+    }
+}
+
+/// Set the opacity of a window, must run on the main thread.
+fn set_window_opacity(window: &WebviewWindow, opacity: f64) {
+    #[cfg(target_os = "linux")]
+    {
+        use gtk::prelude::WidgetExt;
+
+        match window.gtk_window() {
+            Ok(gtk_window) => gtk_window.set_opacity(opacity),
+            Err(e) => error!("Failed to set window opacity: {}", e),
+        }
+    }
+
+    #[cfg(target_os = "windows")]
+    {
+        use windows_sys::Win32::UI::WindowsAndMessaging::{
+            GetWindowLongPtrW, SetLayeredWindowAttributes, SetWindowLongPtrW, GWL_EXSTYLE,
+            LWA_ALPHA, WS_EX_LAYERED,
+        };
+
+        match window.hwnd() {
+            Ok(hwnd) => {
+                let hwnd = hwnd.0 as _;
+                // SAFETY: `hwnd` is the handle of the open main window and used on the main
+                // thread that owns it.
+                unsafe {
+                    let style = GetWindowLongPtrW(hwnd, GWL_EXSTYLE);
+                    SetWindowLongPtrW(hwnd, GWL_EXSTYLE, style | WS_EX_LAYERED as isize);
+                    SetLayeredWindowAttributes(hwnd, 0, (opacity * 255.0).round() as u8, LWA_ALPHA);
+                }
+            }
+            Err(e) => error!("Failed to set window opacity: {}", e),
+        }
+    }
+
+    #[cfg(target_os = "macos")]
+    {
+        match window.ns_window() {
+            Ok(ns_window) => {
+                // SAFETY: the pointer is the NSWindow of the open main window and used on the
+                // main thread.
+                let ns_window = unsafe { &*(ns_window as *const objc2_app_kit::NSWindow) };
+                ns_window.setAlphaValue(opacity);
+            }
+            Err(e) => error!("Failed to set window opacity: {}", e),
+        }
+    }
+
+    #[cfg(not(any(target_os = "linux", target_os = "windows", target_os = "macos")))]
+    {
+        let _ = (window, opacity);
+    }
+}
+
+#[tauri::command]
+/// Get whether the main window is kept on top and its opacity then.
+///
+/// # Returns
+///
+/// A Result containing the window settings or an error.
+///
+/// # Errors
+///
+/// If the config cannot be loaded.
+pub async fn get_window_pin() -> Result<WindowPin, String> {
+    let config = Config::load().map_err(|e| e.to_string())?;
+
+    Ok(WindowPin {
+        always_on_top: config.app.always_on_top,
+        opacity: config.app.window_opacity,
+    })
+}
+
+#[tauri::command]
+/// Keep the main window above other windows or release it.
+///
+/// # Arguments
+///
+/// * `enable` - A boolean indicating if the window should be kept on top.
+///
+/// # Returns
+///
+/// A Result containing the completion status or an error.
+///
+/// # Errors
+///
+/// If the config cannot be saved.
+pub async fn set_always_on_top(app: AppHandle, enable: bool) -> Result<(), String> {
+    let mut config = Config::load().map_err(|e| e.to_string())?;
+    config.app.always_on_top = enable;
+    config.save().map_err(|e| e.to_string())?;
+
+    apply_window_pin(&app, enable, config.app.window_opacity);
+
+    Ok(())
+}
+
+#[tauri::command]
+/// Set the opacity of the main window while it is kept on top.
+///
+/// # Arguments
+///
+/// * `opacity` - The opacity in percent.
+///
+/// # Returns
+///
+/// A Result containing the completion status or an error.
+///
+/// # Errors
+///
+/// If the opacity is out of range or the config cannot be saved.
+pub async fn set_window_opacity_percent(app: AppHandle, opacity: u8) -> Result<(), String> {
+    if !(MIN_WINDOW_OPACITY..=100).contains(&opacity) {
+        return Err(format!(
+            "The opacity must be between {} and 100 percent",
+            MIN_WINDOW_OPACITY
+        ));
+    }
+
+    let mut config = Config::load().map_err(|e| e.to_string())?;
+    config.app.window_opacity = opacity;
+    config.save().map_err(|e| e.to_string())?;
+
+    apply_window_pin(&app, config.app.always_on_top, opacity);
+
+    Ok(())
+}
+
+#[tauri::command]
+/// Hide the main window in the tray instead of quitting when it is closed.
+///
+/// The tray icon is shown while the setting is enabled.
+///
+/// # Arguments
+///
+/// * `enable` - A boolean indicating if the window should be hidden in the tray.
+///
+/// # Returns
+///
+/// A Result containing the completion status or an error.
+///
+/// # Errors
+///
+/// If the config cannot be saved or the tray icon cannot be created.
+pub async fn set_minimize_to_tray(app: AppHandle, enable: bool) -> Result<(), String> {
+    let mut config = Config::load().map_err(|e| e.to_string())?;
+    config.app.minimize_to_tray = enable;
+    config.save().map_err(|e| e.to_string())?;
+
+    if enable {
+        create_tray(&app).map_err(|e| e.to_string())?;
+    } else {
+        remove_tray(&app);
+    }
+
+    Ok(())
+}
+
+#[tauri::command]
+/// Download the latest common passwords list for the password health check.
+///
+/// # Returns
+///
+/// A Result containing the number of common passwords in the new list or an error.
+///
+/// # Errors
+///
+/// If offline mode is enabled, the download fails or the list is invalid.
+pub async fn update_common_passwords() -> Result<usize, String> {
+    let config = Config::load().map_err(|e| e.to_string())?;
+    if config.app.offline_mode {
+        return Err("Offline mode is enabled".into());
+    }
+
+    if rustls::crypto::CryptoProvider::get_default().is_none() {
+        let _ = rustls::crypto::ring::default_provider().install_default();
+    }
+
+    let response = reqwest::get(COMMON_PASSWORDS_URL)
+        .await
+        .and_then(|response| response.error_for_status())
+        .map_err(|e| e.to_string())?;
+
+    if response
+        .content_length()
+        .is_some_and(|length| length > MAX_COMMON_PASSWORDS_SIZE)
+    {
+        return Err("Common passwords list is too large".into());
+    }
+
+    let content = response.text().await.map_err(|e| e.to_string())?;
+    if content.len() as u64 > MAX_COMMON_PASSWORDS_SIZE {
+        return Err("Common passwords list is too large".into());
+    }
+
+    let config_dir = Config::get_config_dir().map_err(|e| e.to_string())?;
+    let count = CommonPasswords::update(&config_dir, &content).map_err(|e| e.to_string())?;
+    info!("downloaded common passwords list with {count} entries");
+
+    Ok(count)
+}
+
+/// Create a safety backup of the vault before an update is installed.
+///
+/// # Returns
+///
+/// A Result containing the path of the backup, or None if there is no vault yet, or an error.
+///
+/// # Errors
+///
+/// If the backup cannot be created.
+fn create_update_backup() -> Result<Option<PathBuf>, Box<dyn std::error::Error>> {
+    let config = Config::load()?;
+    let db_path = config.database.db_path.join(&config.database.db_name);
+    if !db_path.exists() {
+        return Ok(None);
+    }
+
+    let config_dir = Config::get_config_dir()?;
+    let backup_path = &config.backup.backup_path;
+    fs::create_dir_all(backup_path)?;
+
+    let recipients = config
+        .backup
+        .recipients
+        .iter()
+        .map(|r| r.public_key.clone())
+        .collect::<Vec<_>>();
+
+    let backup =
+        BackupManager::create_safety_backup(&db_path, backup_path, &config_dir, &recipients)?;
+    record_audit(
+        AuditEvent::Backup,
+        &format!(
+            "Safety backup before update created at {}",
+            backup.display()
+        ),
+    );
+
+    Ok(Some(backup))
+}
@@ -36,7 +36,10 @@ pub async fn get_default_config() -> Result<DefaultConfig, String> {
 ///
 /// # Arguments
 ///
-/// * `custom_path` - An optional custom path for the database.
+/// * `db_path` - The path to the database.
+/// * `db_name` - The name of the database.
+/// * `backup_path` - The path to store backups.
+/// * `sync_safe_mode` - Whether to avoid the write-ahead log for cloud-synced folders.
 ///
 /// # Returns
 ///
@@ -45,6 +48,7 @@ pub async fn complete_setup(
     db_path: String,
     db_name: String,
     backup_path: String,
+    sync_safe_mode: bool,
 ) -> Result<(), String> {
     info!("Complete Setup called!");
     let mut config = Config::load().unwrap();
@@ -52,6 +56,7 @@ pub async fn complete_setup(
     config.database.db_name = db_name;
     config.database.db_path = PathBuf::from(db_path);
     config.backup.backup_path = PathBuf::from(backup_path);
+    config.database.sync_safe_mode = sync_safe_mode;
     config.save().map_err(|e| e.to_string())?;
 
     Ok(())
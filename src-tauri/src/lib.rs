@@ -1,19 +1,60 @@
+mod cli;
 mod commands;
+mod native_host;
 mod password_manager;
+mod service;
 mod utils;
 
-use log::error;
+use log::{error, info};
 use std::sync::Mutex;
 use tauri_plugin_autostart::MacosLauncher;
 
-use commands::PasswordManagerState;
 use commands::{
-    add_password, check_passwords, check_update, complete_setup, create_backup, delete_password,
-    export_passwords, generate_password, get_auto_logout_time, get_database_settings,
-    get_default_config, get_default_generator_length, get_passwords, import_passwords,
-    is_autostart_enabled, login, logout, open_log_folder, register, restore_backup,
-    save_app_settings, save_database_settings, save_security_settings, toggle_autostart,
-    update_master_password, update_password,
+    add_attachment, add_backup_recipient, add_expiring_note, add_identity, add_password,
+    add_passwords, add_payment_card, add_secure_note, add_server, answer_browser_authorization,
+    apply_category_suggestions, apply_content_protection, check_breached_passwords,
+    check_passwords, check_update, choose_backup_location, choose_database_location,
+    clear_generator_history, close_quick_search, close_snapshot, complete_setup,
+    copy_quick_search_entry, copy_server_connection, copy_server_key, copy_snapshot_password,
+    create_backup, create_entry_from_url, create_folder, create_tag, decrypt_snapshot_password,
+    delete_attachment, delete_expiring_note, delete_folder, delete_identity, delete_password,
+    delete_payment_card, delete_secure_note, delete_server, delete_tag, delete_vault,
+    detect_browser_profiles, detect_cloud_sync, export_audit_log, export_encrypted,
+    export_passwords, export_settings, extend_session, generate_backup_keypair, generate_password,
+    get_attachment, get_attachments, get_auto_lock_settings, get_auto_logout_time,
+    get_autostart_mode, get_backup_recipients, get_breach_check, get_browser_settings,
+    get_cached_health, get_category_suggestions, get_content_protection, get_crypto_info,
+    get_database_settings, get_database_stats, get_decrypt_limit, get_default_config,
+    get_default_generator_length, get_duplicate_groups, get_expiring_entries, get_expiring_notes,
+    get_expiry_reminder, get_folders, get_generator_defaults, get_generator_history,
+    get_identities, get_key_info, get_launch_view, get_login_lockout, get_minimize_to_tray,
+    get_offline_mode, get_overall_score, get_passwords, get_payment_cards, get_pinned_entries,
+    get_quick_search_shortcut, get_recovery_codes, get_rpc_enabled, get_secure_notes, get_servers,
+    get_session_max_lifetime, get_snapshot, get_soft_keyboard, get_startup_issues, get_tags,
+    get_totp_code, get_trashed_passwords, get_trends, get_unlock_summary, get_view_settings,
+    get_window_pin, handle_auto_lock, has_confirmation_pin, import_browser_export,
+    import_otp_secrets, import_passwords, import_passwords_from_text, import_settings,
+    install_browser_host, is_autostart_enabled, list_backups, login, logout, mark_recovery_code,
+    move_entry_to_folder, open_entry_url, open_generator_window, open_log_folder, open_snapshot,
+    pin_entry, prepare_export, preview_csv_import, preview_otp_import, prune_backups, purge_trash,
+    quick_add_password, quick_search, regenerate_config, register, remove_backup_recipient,
+    rename_folder, rename_tag, restore_backup, restore_password, revoke_browser_site,
+    rotate_vault_keys, sandbox_restore, save_app_settings, save_browser_settings,
+    save_database_settings, save_last_view, save_security_settings, search_passwords,
+    seed_demo_data, set_always_on_top, set_breach_check, set_confirmation_pin, set_entry_expiry,
+    set_entry_locked, set_entry_tag, set_expiry_reminder, set_metadata_encryption,
+    set_minimize_to_tray, set_offline_mode, set_quick_search_shortcut, set_recovery_codes,
+    set_rpc_enabled, set_totp_secret, set_window_opacity_percent, share_entry, start_demo,
+    toggle_autostart, unpin_entry, unshare_entry, update_common_passwords, update_identity,
+    update_master_password, update_password, update_payment_card, update_secure_note,
+    update_server,
+};
+use commands::{
+    apply_window_pin, close_quick_search_window, create_tray, hide_to_tray,
+    quick_search_shortcut_plugin, refresh_tray_menu, register_quick_search_shortcut, start_in_tray,
+    watch_session, watch_system_activity, AutoLockSettings, AutoLockState, BrowserState,
+    HealthCache, LaunchState, PasswordManagerState, SnapshotState, AUTOSTART_ARG,
+    QUICK_SEARCH_WINDOW, SNAPSHOT_WINDOW,
 };
 
 pub use password_manager::PasswordManager;
@@ -21,13 +62,13 @@ pub use password_manager::PasswordManager;
 use tauri::AppHandle;
 use tauri::Manager;
 use tauri::State;
-use tauri_plugin_clipboard_manager::ClipboardExt;
 use tauri_plugin_dialog::DialogExt;
 pub use utils::Auth;
 pub use utils::BackupManager;
 pub use utils::Config;
 pub use utils::Encryption;
 pub use utils::TokenManager;
+use utils::{AutostartMode, ClipboardProtection, CommonPasswords, RpcServer, SecureClipboard};
 pub use utils::{Database, PasswordEntry};
 
 #[tauri::command]
@@ -44,22 +85,28 @@ async fn check_is_initialized() -> Result<bool, String> {
 #[tauri::command]
 /// Copy text to the clipboard.
 ///
+/// The copied text is excluded from the clipboard history where the platform supports it
+/// and cleared again on logout or exit.
+///
 /// # Arguments
 ///
 /// * `text` - The text to copy to the clipboard.
 ///
 /// # Returns
 ///
-/// A Result containing the completion status or an error.
+/// A Result containing the applied clipboard protection of the platform or an error.
 ///
 /// # Errors
 ///
 /// If the text cannot be copied to the clipboard.
-async fn copy_to_clipboard(app_handle: AppHandle, text: String) -> Result<(), String> {
-    app_handle
-        .clipboard()
-        .write_text(text)
-        .map_err(|e| e.to_string())
+async fn copy_to_clipboard(
+    clipboard: State<'_, SecureClipboard>,
+    text: String,
+) -> Result<ClipboardProtection, String> {
+    clipboard.copy(&text).map_err(|e| {
+        error!("Failed to copy to clipboard: {}", e);
+        e.to_string()
+    })
 }
 
 #[tauri::command(rename_all = "camelCase")]
@@ -88,11 +135,15 @@ async fn verify_master_password(
 }
 
 #[tauri::command(rename_all = "camelCase")]
-/// Decrypt a password.
+/// Decrypt the password of an entry to reveal it.
+///
+/// The list of entries carries no password material, so this is the only way the
+/// frontend gets to see a password.
 ///
 /// # Arguments
 ///
-/// * `encrypted_password` - The encrypted password to decrypt.
+/// * `id` - The ID of the password entry.
+/// * `master_pass` - The master password, required for entries that are protected by it.
 ///
 /// # Returns
 ///
@@ -100,18 +151,65 @@ async fn verify_master_password(
 ///
 /// # Errors
 ///
-/// If the password cannot be decrypted.
+/// If the entry is not visible to the user, the master password is missing or wrong or
+/// the password cannot be decrypted.
 async fn decrypt_password(
     state: State<'_, PasswordManagerState>,
-    encrypted_password: String,
+    id: i32,
+    master_pass: Option<String>,
 ) -> Result<String, String> {
     let state = state.0.lock().unwrap();
     let pm = state.as_ref().ok_or("Not logged in")?;
 
-    pm.decrypt_password(&encrypted_password)
+    pm.decrypt_password_by_id(id, master_pass.as_deref())
         .map_err(|e| e.to_string())
 }
 
+#[tauri::command(rename_all = "camelCase")]
+/// Decrypt the password of an entry and copy it to the clipboard.
+///
+/// The password never leaves the backend, so entries that are protected by the
+/// master password cannot be copied without it.
+///
+/// # Arguments
+///
+/// * `id` - The ID of the password entry.
+/// * `master_pass` - The master password, required for entries that are protected by it.
+///
+/// # Returns
+///
+/// A Result containing the applied clipboard protection of the platform or an error.
+///
+/// # Errors
+///
+/// If the password cannot be decrypted or copied to the clipboard.
+async fn copy_password(
+    app: AppHandle,
+    state: State<'_, PasswordManagerState>,
+    clipboard: State<'_, SecureClipboard>,
+    id: i32,
+    master_pass: Option<String>,
+) -> Result<ClipboardProtection, String> {
+    let password = {
+        let mut state = state.0.lock().unwrap();
+        let pm = state.as_mut().ok_or("Not logged in")?;
+
+        let password = pm
+            .decrypt_password_by_id(id, master_pass.as_deref())
+            .map_err(|e| e.to_string())?;
+        pm.record_recent_entry(id);
+        password
+    };
+
+    let protection = clipboard.copy(&password).map_err(|e| {
+        error!("Failed to copy to clipboard: {}", e);
+        e.to_string()
+    })?;
+    refresh_tray_menu(&app);
+
+    Ok(protection)
+}
+
 #[tauri::command]
 /// Select a folder.
 ///
@@ -145,25 +243,93 @@ async fn check_users_session(state: State<'_, PasswordManagerState>) -> Result<b
     }
 }
 
+/// Install a panic hook that removes the session token.
+///
+/// A panic skips the regular exit cleanup, so the hook removes the `.session_token` file
+/// before the default hook runs. The password manager is only dropped when the panicking
+/// command does not hold its lock, and dropping it does not wipe the keys from memory.
+///
+/// # Arguments
+///
+/// * `app` - The app handle.
+fn install_panic_cleanup(app: AppHandle) {
+    let session_path = Config::get_config_dir()
+        .map(|dir| dir.join(".session_token"))
+        .ok();
+    let default_hook = std::panic::take_hook();
+
+    std::panic::set_hook(Box::new(move |info| {
+        if let Ok(mut state) = app.state::<PasswordManagerState>().0.try_lock() {
+            state.take();
+        }
+        if let Some(path) = session_path.as_ref().filter(|path| path.exists()) {
+            if let Err(e) = std::fs::remove_file(path) {
+                error!("Failed to remove session token after panic: {}", e);
+            }
+        }
+        error!("Backend panicked, session token removed");
+
+        default_hook(info);
+    }));
+}
+
 #[cfg_attr(mobile, tauri::mobile_entry_point)]
 /// Run the Tauri application.
 ///
-/// This function sets up the logger and runs the Tauri application.
+/// This function sets up the logger and runs the Tauri application. Broken files do not
+/// abort the start, the frontend reports them through the repair assistant instead.
+/// Command line actions like `--backup` run without a window and exit afterwards.
 pub fn run() {
-    let config = Config::load().expect("error while loading config");
-    config
-        .setup_logger()
-        .expect("error while setting up logger");
-    PasswordManager::cleanup_on_startup().expect("error while cleaning up");
+    let config = Config::load().unwrap_or_else(|e| {
+        eprintln!("Failed to load config, using defaults: {}", e);
+        Config::default()
+    });
+    if let Err(e) = config.setup_logger() {
+        eprintln!("Failed to set up logger: {}", e);
+    }
+    let args: Vec<String> = std::env::args().skip(1).collect();
+    if let Some(code) = native_host::run(&args) {
+        std::process::exit(code);
+    }
+    if let Some(code) = cli::run(&args) {
+        std::process::exit(code);
+    }
+    if let Err(e) = PasswordManager::cleanup_on_startup() {
+        error!("Failed to clean up on startup: {}", e);
+    }
+    let launch = LaunchState::from_args();
+    let start_hidden = launch.autostart_mode(&config) == Some(AutostartMode::Tray);
     tauri::Builder::default()
-        .plugin(tauri_plugin_autostart::init(MacosLauncher::LaunchAgent, None))
+        .plugin(tauri_plugin_autostart::init(
+            MacosLauncher::LaunchAgent,
+            Some(vec![AUTOSTART_ARG]),
+        ))
         .plugin(tauri_plugin_updater::Builder::new().build())
         .plugin(tauri_plugin_opener::init())
         .plugin(tauri_plugin_clipboard_manager::init())
         .plugin(tauri_plugin_dialog::init())
+        .plugin(tauri_plugin_notification::init())
         .plugin(tauri_plugin_shell::init())
+        .plugin(quick_search_shortcut_plugin())
         .manage(PasswordManagerState(Mutex::new(None)))
-        .setup(|app| {
+        .manage(SecureClipboard::new())
+        .manage(HealthCache::new())
+        .manage(SnapshotState::new())
+        .manage(BrowserState::new())
+        .manage(AutoLockState::new(AutoLockSettings::from_config(&config)))
+        .manage(launch)
+        .setup(move |app| {
+            install_panic_cleanup(app.handle().clone());
+            watch_session(app.handle().clone());
+            watch_system_activity(app.handle().clone());
+
+            match Config::get_config_dir() {
+                Ok(config_dir) => {
+                    CommonPasswords::load(&config_dir);
+                }
+                Err(e) => error!("Failed to load common passwords list: {}", e),
+            }
+
             let handle = app.handle().clone();
             tauri::async_runtime::spawn(async move {
                 if let Err(e) = check_update(handle).await {
@@ -171,6 +337,51 @@ pub fn run() {
                 }
             });
 
+            if config.app.content_protection {
+                apply_content_protection(app.handle(), true);
+            }
+
+            if config.app.always_on_top {
+                apply_window_pin(app.handle(), true, config.app.window_opacity);
+            }
+
+            if config.app.rpc_enabled || config.browser.enabled {
+                let server = Config::get_config_dir()
+                    .and_then(|dir| RpcServer::load_or_create_token(&dir))
+                    .and_then(|token| {
+                        RpcServer::start(
+                            config.app.rpc_port,
+                            token,
+                            service::rpc_handler(
+                                app.handle().clone(),
+                                config.app.rpc_enabled,
+                                config.browser.enabled,
+                            ),
+                        )
+                    });
+                match server {
+                    Ok(server) => info!("JSON-RPC server listening on {}", server.addr()),
+                    Err(e) => error!("Failed to start JSON-RPC server: {}", e),
+                }
+            }
+
+            if let Err(e) = register_quick_search_shortcut(
+                app.handle(),
+                config.app.quick_search_shortcut.as_deref(),
+            ) {
+                error!("Failed to register quick search shortcut: {}", e);
+            }
+
+            if start_hidden {
+                if let Err(e) = start_in_tray(app.handle()) {
+                    error!("Failed to start in tray: {}", e);
+                }
+            } else if config.app.minimize_to_tray {
+                if let Err(e) = create_tray(app.handle()) {
+                    error!("Failed to create tray icon: {}", e);
+                }
+            }
+
             #[cfg(not(debug_assertions))]
             {
                 let window = app.get_webview_window("main").unwrap();
@@ -179,19 +390,52 @@ pub fn run() {
 
             Ok(())
         })
-        .on_window_event(|handle, event| if let tauri::WindowEvent::Destroyed = &event {
-            if let Some(pm) = &*handle.state::<PasswordManagerState>().0.lock().unwrap() {
-                pm.cleanup_on_exit().expect("error during exit cleanup");
+        .on_window_event(|handle, event| {
+            if handle.label() == SNAPSHOT_WINDOW {
+                if let tauri::WindowEvent::Destroyed = &event {
+                    handle.state::<SnapshotState>().clear();
+                }
+                return;
+            }
+            if handle.label() == QUICK_SEARCH_WINDOW {
+                if let tauri::WindowEvent::Focused(false) = &event {
+                    close_quick_search_window(handle.app_handle());
+                }
+                return;
+            }
+            if handle.label() != "main" {
+                return;
+            }
+            if let tauri::WindowEvent::CloseRequested { api, .. } = &event {
+                if hide_to_tray(handle) {
+                    api.prevent_close();
+                    return;
+                }
+            }
+            handle_auto_lock(handle, event);
+            if let tauri::WindowEvent::Destroyed = &event {
+                handle.state::<SnapshotState>().clear();
+                handle.state::<SecureClipboard>().shutdown();
+                match handle.state::<PasswordManagerState>().0.lock() {
+                    Ok(state) => {
+                        if let Some(Err(e)) = state.as_ref().map(|pm| pm.cleanup_on_exit()) {
+                            error!("Failed to clean up on exit: {}", e);
+                        }
+                    }
+                    Err(e) => error!("Failed to lock password manager on exit: {}", e),
+                }
             }
         })
         .invoke_handler(tauri::generate_handler![
             login,
+            get_login_lockout,
             register,
             logout,
             generate_password,
             check_is_initialized,
             complete_setup,
             copy_to_clipboard,
+            copy_password,
             get_passwords,
             add_password,
             update_password,
@@ -216,7 +460,147 @@ pub fn run() {
             check_update,
             toggle_autostart,
             is_autostart_enabled,
-            check_passwords
+            check_passwords,
+            get_backup_recipients,
+            add_backup_recipient,
+            remove_backup_recipient,
+            generate_backup_keypair,
+            list_backups,
+            prune_backups,
+            get_content_protection,
+            get_auto_lock_settings,
+            get_session_max_lifetime,
+            export_audit_log,
+            get_offline_mode,
+            set_offline_mode,
+            update_common_passwords,
+            get_duplicate_groups,
+            get_cached_health,
+            get_overall_score,
+            export_settings,
+            import_settings,
+            get_startup_issues,
+            regenerate_config,
+            choose_database_location,
+            choose_backup_location,
+            delete_vault,
+            detect_cloud_sync,
+            get_database_stats,
+            get_view_settings,
+            get_autostart_mode,
+            get_launch_view,
+            save_last_view,
+            sandbox_restore,
+            quick_add_password,
+            get_rpc_enabled,
+            set_rpc_enabled,
+            start_demo,
+            seed_demo_data,
+            open_generator_window,
+            get_recovery_codes,
+            set_recovery_codes,
+            mark_recovery_code,
+            get_soft_keyboard,
+            get_trends,
+            share_entry,
+            unshare_entry,
+            rotate_vault_keys,
+            get_key_info,
+            get_unlock_summary,
+            extend_session,
+            pin_entry,
+            unpin_entry,
+            get_pinned_entries,
+            search_passwords,
+            open_entry_url,
+            get_generator_defaults,
+            get_generator_history,
+            clear_generator_history,
+            import_passwords_from_text,
+            get_totp_code,
+            set_totp_secret,
+            get_folders,
+            create_folder,
+            rename_folder,
+            delete_folder,
+            move_entry_to_folder,
+            has_confirmation_pin,
+            set_confirmation_pin,
+            set_entry_locked,
+            get_tags,
+            create_tag,
+            rename_tag,
+            delete_tag,
+            set_entry_tag,
+            get_crypto_info,
+            get_trashed_passwords,
+            restore_password,
+            purge_trash,
+            add_passwords,
+            get_expiring_notes,
+            add_expiring_note,
+            delete_expiring_note,
+            preview_csv_import,
+            export_encrypted,
+            get_breach_check,
+            set_breach_check,
+            check_breached_passwords,
+            create_entry_from_url,
+            open_snapshot,
+            get_snapshot,
+            decrypt_snapshot_password,
+            copy_snapshot_password,
+            close_snapshot,
+            set_metadata_encryption,
+            get_minimize_to_tray,
+            set_minimize_to_tray,
+            quick_search,
+            copy_quick_search_entry,
+            close_quick_search,
+            get_quick_search_shortcut,
+            set_quick_search_shortcut,
+            answer_browser_authorization,
+            get_browser_settings,
+            install_browser_host,
+            revoke_browser_site,
+            save_browser_settings,
+            get_window_pin,
+            set_always_on_top,
+            set_window_opacity_percent,
+            detect_browser_profiles,
+            import_browser_export,
+            add_secure_note,
+            delete_secure_note,
+            get_secure_notes,
+            update_secure_note,
+            add_payment_card,
+            add_identity,
+            delete_payment_card,
+            delete_identity,
+            get_payment_cards,
+            get_identities,
+            update_payment_card,
+            update_identity,
+            get_attachments,
+            add_attachment,
+            get_attachment,
+            delete_attachment,
+            prepare_export,
+            get_decrypt_limit,
+            get_category_suggestions,
+            apply_category_suggestions,
+            add_server,
+            copy_server_connection,
+            copy_server_key,
+            delete_server,
+            get_servers,
+            update_server,
+            get_expiring_entries,
+            set_entry_expiry,
+            get_expiry_reminder,
+            set_expiry_reminder,
+            import_otp_secrets,
+            preview_otp_import
         ])
         .run(tauri::generate_context!())
         .expect("error while running tauri application");
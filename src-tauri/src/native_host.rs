@@ -0,0 +1,117 @@
+use log::{error, info};
+use serde_json::{json, Value};
+use std::io;
+
+use crate::utils::{
+    extension_origin, read_message, write_message, RpcClient, RpcServer, BROWSER_METHOD_PREFIX,
+    SERVER_ERROR,
+};
+use crate::Config;
+
+/// Relay a browser extension to the running app if the browser launched it as native
+/// messaging host.
+///
+/// The host only forwards messages, the vault stays in the app window, which asks before a
+/// site gets access. Every message is `{"id", "method", "params"}`, the response carries the
+/// same ID with either a `result` or an `error`.
+///
+/// # Arguments
+///
+/// * `args` - The launch arguments without the program name.
+///
+/// # Returns
+///
+/// The exit code of the host or None if the app was not launched by a browser.
+pub fn run(args: &[String]) -> Option<i32> {
+    let origin = extension_origin(args)?;
+    info!("Native messaging host started by {}", origin);
+
+    match relay(&origin) {
+        Ok(()) => Some(0),
+        Err(e) => {
+            error!("Native messaging host failed: {}", e);
+            Some(1)
+        }
+    }
+}
+
+/// Forward the messages of the extension until the browser closes the connection.
+///
+/// # Arguments
+///
+/// * `origin` - The origin or ID of the extension, sent with every request.
+///
+/// # Returns
+///
+/// A Result containing the completion status or an error.
+///
+/// # Errors
+///
+/// If the messages from the browser cannot be read or the responses cannot be written.
+fn relay(origin: &str) -> Result<(), Box<dyn std::error::Error>> {
+    let mut stdin = io::stdin().lock();
+    let mut stdout = io::stdout().lock();
+    let mut client: Option<RpcClient> = None;
+
+    while let Some(message) = read_message(&mut stdin)? {
+        let id = message.get("id").cloned().unwrap_or(Value::Null);
+        let method = message
+            .get("method")
+            .and_then(Value::as_str)
+            .unwrap_or_default();
+        let mut params = message.get("params").cloned().unwrap_or_else(|| json!({}));
+        if let Some(params) = params.as_object_mut() {
+            params.insert("origin".to_string(), json!(origin));
+        }
+
+        if client.is_none() {
+            client = connect().ok();
+        }
+        let response = match client.as_mut() {
+            Some(rpc) => match rpc.call(
+                id.clone(),
+                &format!("{}{}", BROWSER_METHOD_PREFIX, method),
+                params,
+            ) {
+                Ok(response) => response,
+                Err(e) => {
+                    error!("Lost the connection to KarnCrypt: {}", e);
+                    client = None;
+                    not_running(&id)
+                }
+            },
+            None => not_running(&id),
+        };
+
+        write_message(
+            &mut stdout,
+            &json!({
+                "id": id,
+                "result": response.get("result"),
+                "error": response.get("error"),
+            }),
+        )?;
+    }
+
+    info!("Browser closed the native messaging connection");
+    Ok(())
+}
+
+/// Connect to the JSON-RPC server of the running app.
+fn connect() -> Result<RpcClient, Box<dyn std::error::Error>> {
+    let config = Config::load()?;
+    let token = RpcServer::load_or_create_token(&Config::get_config_dir()?)?;
+
+    Ok(RpcClient::connect(config.app.rpc_port, token)?)
+}
+
+/// The response if the app does not run or has the browser extension disabled.
+fn not_running(id: &Value) -> Value {
+    json!({
+        "id": id,
+        "error": {
+            "code": SERVER_ERROR,
+            "message": "KarnCrypt is not running",
+        },
+    })
+}
@@ -0,0 +1,262 @@
+use log::{error, info, warn};
+use std::path::{Path, PathBuf};
+
+use super::VaultService;
+use crate::commands::record_audit;
+use crate::utils::{
+    AuditEvent, Cipher, DeletionReport, LoginGuard, SecureDelete, VAULT_LOCATION_UNAVAILABLE,
+};
+use crate::{Auth, Config, PasswordManager};
+
+impl VaultService<'_> {
+    /// Register a new user
+    ///
+    /// # Arguments
+    ///
+    /// * `username` - The username to register
+    /// * `master_pass` - The master password of the vault
+    /// * `cipher` - The algorithm to encrypt a new vault with
+    ///
+    /// # Errors
+    ///
+    /// Returns an error if the vault cannot be created or the user cannot be registered
+    pub fn register(
+        &self,
+        username: &str,
+        master_pass: &str,
+        cipher: Cipher,
+    ) -> Result<(), String> {
+        let pm = PasswordManager::with_cipher(master_pass, cipher).map_err(|e| {
+            error!(
+                "Failed to create PasswordManager during registration: {}",
+                e
+            );
+            e.to_string()
+        })?;
+
+        Auth::new(&pm.db)
+            .register(username, master_pass)
+            .map_err(|e| {
+                error!("Failed to register user {}: {}", username, e);
+                e.to_string()
+            })?;
+        info!("Successfully registered user: {}", username);
+
+        Ok(())
+    }
+
+    /// Open the vault and log in a user
+    ///
+    /// Expired entries are purged from the trash and expired notes are redacted. Failed
+    /// attempts are counted and lock the login for an increasing time.
+    ///
+    /// # Arguments
+    ///
+    /// * `username` - The username to log in
+    /// * `master_pass` - The master password of the vault
+    ///
+    /// # Errors
+    ///
+    /// Returns an error if the login is locked, the vault location is unavailable or the
+    /// user cannot be logged in
+    pub fn login(&self, username: &str, master_pass: &str) -> Result<(), String> {
+        info!("Login attempt for user: {}", username);
+
+        let config_dir = Config::get_config_dir().map_err(|e| e.to_string())?;
+        let guard = LoginGuard::new(&config_dir);
+
+        let remaining = guard.remaining_lockout();
+        if remaining > 0 {
+            warn!(
+                "Login for user {} rejected, locked for {}s",
+                username, remaining
+            );
+            return Err(format!("Login locked for {}s", remaining));
+        }
+
+        let config = Config::load().map_err(|e| e.to_string())?;
+        if !config.is_vault_available() {
+            warn!(
+                "Vault location unavailable: {}",
+                config.database.db_path.display()
+            );
+            return Err(VAULT_LOCATION_UNAVAILABLE.into());
+        }
+
+        let error = match Self::open_vault(username, master_pass, &config) {
+            Ok(pm) => {
+                *self.state.0.lock().unwrap() = Some(pm);
+                if let Err(e) = guard.reset() {
+                    error!("Failed to reset login attempts: {}", e);
+                }
+                record_audit(AuditEvent::Login, username);
+                return Ok(());
+            }
+            Err(e) => e,
+        };
+
+        record_audit(AuditEvent::FailedLogin, username);
+        match guard.record_failure() {
+            Ok(Some(lockout)) => {
+                record_audit(
+                    AuditEvent::Lockout,
+                    &format!("{} locked for {}s", username, lockout),
+                );
+                Err(format!("Login locked for {}s", lockout))
+            }
+            Ok(None) => Err(error),
+            Err(e) => {
+                error!("Failed to record failed login attempt: {}", e);
+                Err(error)
+            }
+        }
+    }
+
+    /// Open the vault with the master password and log in the user
+    fn open_vault(
+        username: &str,
+        master_pass: &str,
+        config: &Config,
+    ) -> Result<PasswordManager, String> {
+        let mut pm = PasswordManager::new(master_pass).map_err(|e| {
+            error!("Failed to create PasswordManager during login: {}", e);
+            e.to_string()
+        })?;
+        pm.login(username, master_pass).map_err(|e| {
+            error!("Failed to login user {}: {}", username, e);
+            e.to_string()
+        })?;
+        info!("Successfully logged in user: {}", username);
+
+        match pm.purge_expired_trash(config.entry.trash_retention_days) {
+            Ok(0) => {}
+            Ok(purged) => info!("Purged {} expired entries from the trash", purged),
+            Err(e) => error!("Failed to purge expired entries from the trash: {}", e),
+        }
+        match pm.redact_expired_notes() {
+            Ok(0) => {}
+            Ok(redacted) => info!("Redacted {} expired notes", redacted),
+            Err(e) => error!("Failed to redact expired notes: {}", e),
+        }
+
+        Ok(pm)
+    }
+
+    /// Open the demo vault with sample entries
+    ///
+    /// The demo vault only lives in memory and is discarded on logout.
+    ///
+    /// # Errors
+    ///
+    /// Returns an error if the demo vault cannot be created
+    pub fn start_demo(&self) -> Result<(), String> {
+        info!("Starting demo vault");
+        let pm = PasswordManager::demo().map_err(|e| {
+            error!("Failed to create demo vault: {}", e);
+            e.to_string()
+        })?;
+        *self.state.0.lock().unwrap() = Some(pm);
+
+        Ok(())
+    }
+
+    /// Get the remaining lockout time of the login
+    ///
+    /// # Returns
+    ///
+    /// The remaining lockout time in seconds
+    ///
+    /// # Errors
+    ///
+    /// Returns an error if the config directory cannot be found
+    pub fn login_lockout(&self) -> Result<u64, String> {
+        let config_dir = Config::get_config_dir().map_err(|e| e.to_string())?;
+
+        Ok(LoginGuard::new(&config_dir).remaining_lockout())
+    }
+
+    /// Log out the current user and close the vault
+    ///
+    /// # Errors
+    ///
+    /// Returns an error if the session cannot be cleared
+    pub fn logout(&self) -> Result<(), String> {
+        let mut state = self.state.0.lock().unwrap();
+        if let Some(pm) = state.as_ref() {
+            pm.logout().map_err(|e| e.to_string())?;
+        }
+        *state = None;
+
+        Ok(())
+    }
+
+    /// Delete the vault and its key parameters, so the app can be set up again
+    ///
+    /// The session ends and the database is closed before its files are removed. Backups and
+    /// exports are kept.
+    ///
+    /// # Arguments
+    ///
+    /// * `master_pass` - The master password
+    /// * `overwrite` - Whether to overwrite the files with random data before removing them
+    ///
+    /// # Returns
+    ///
+    /// The removed files and why their data might still be recoverable on this platform
+    ///
+    /// # Errors
+    ///
+    /// Returns an error if not logged in, the master password is wrong or a file cannot be
+    /// removed
+    pub fn delete_vault(
+        &self,
+        master_pass: &str,
+        overwrite: bool,
+    ) -> Result<DeletionReport, String> {
+        let mut config = Config::load().map_err(|e| e.to_string())?;
+        let config_dir = Config::get_config_dir().map_err(|e| e.to_string())?;
+
+        {
+            let mut state = self.state.0.lock().unwrap();
+            let pm = state.as_ref().ok_or("Not logged in")?;
+            if !pm
+                .verify_master_password(master_pass)
+                .map_err(|e| e.to_string())?
+            {
+                return Err("Invalid master password".into());
+            }
+            pm.logout().map_err(|e| e.to_string())?;
+            *state = None;
+        }
+
+        let report = SecureDelete::new(overwrite)
+            .delete_files(&Self::vault_files(&config, &config_dir))
+            .map_err(|e| {
+                error!("Failed to delete the vault: {}", e);
+                e.to_string()
+            })?;
+
+        config.app.is_initialized = false;
+        config.save().map_err(|e| e.to_string())?;
+        info!("Deleted the vault, {} files removed", report.removed.len());
+
+        Ok(report)
+    }
+
+    /// List the database with its SQLite side files and the key parameters of the vault
+    fn vault_files(config: &Config, config_dir: &Path) -> Vec<PathBuf> {
+        let db_path = config.database.db_path.join(&config.database.db_name);
+        let mut files: Vec<PathBuf> = ["-wal", "-shm", "-journal"]
+            .iter()
+            .map(|suffix| {
+                let mut name = db_path.clone().into_os_string();
+                name.push(suffix);
+                PathBuf::from(name)
+            })
+            .collect();
+        files.insert(0, db_path);
+        files.push(config_dir.join(".salt"));
+
+        files
+    }
+}
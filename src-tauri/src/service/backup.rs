@@ -0,0 +1,127 @@
+use chrono::{DateTime, Utc};
+use serde::Serialize;
+use std::fs;
+use std::path::PathBuf;
+
+use super::VaultService;
+use crate::commands::record_audit;
+use crate::utils::{AuditEvent, VaultStorage};
+use crate::{BackupManager, Config, PasswordManager};
+
+/// Statistics about the vault and its backups.
+#[derive(Serialize)]
+pub struct DatabaseStats {
+    pub entry_count: usize,
+    pub database_size: u64,
+    pub backup_count: usize,
+    pub last_backup: Option<String>,
+}
+
+impl VaultService<'_> {
+    /// Create a backup in the configured backup folder
+    ///
+    /// The backup is encrypted to the configured recipients and is an increment of the last
+    /// full backup if incremental backups are enabled.
+    ///
+    /// # Arguments
+    ///
+    /// * `master_pass` - The master password of the vault
+    ///
+    /// # Returns
+    ///
+    /// The path of the created backup
+    ///
+    /// # Errors
+    ///
+    /// Returns an error if not logged in or the backup cannot be created
+    pub fn create_backup(&self, master_pass: &str) -> Result<PathBuf, String> {
+        let config = Config::load().map_err(|e| e.to_string())?;
+        let config_dir = Config::get_config_dir().map_err(|e| e.to_string())?;
+        let backup_path = &config.backup.backup_path;
+
+        self.with_manager("create a backup", |pm| {
+            fs::create_dir_all(backup_path).map_err(|e| e.to_string())?;
+
+            let mut bm = BackupManager::new(&pm.db)
+                .with_recipients(Self::backup_recipients(&config))
+                .with_naming(config.backup_naming());
+            if config.backup.incremental {
+                bm = bm.with_incremental(config.backup.full_backup_every);
+            }
+            let path = bm
+                .backup(backup_path, &config_dir, master_pass)
+                .map_err(|e| e.to_string())?;
+            record_audit(
+                AuditEvent::Backup,
+                &format!("Backup created in {}", backup_path.display()),
+            );
+
+            Ok(path)
+        })
+    }
+
+    /// Create a full backup in the configured backup folder, e.g. before the keys are rotated
+    pub(super) fn full_backup<S: VaultStorage>(
+        pm: &PasswordManager<S>,
+        master_pass: &str,
+    ) -> Result<PathBuf, Box<dyn std::error::Error>> {
+        let config = Config::load()?;
+        let config_dir = Config::get_config_dir()?;
+        let backup_path = &config.backup.backup_path;
+        fs::create_dir_all(backup_path)?;
+
+        BackupManager::new(&pm.db)
+            .with_recipients(Self::backup_recipients(&config))
+            .with_naming(config.backup_naming())
+            .create_backup(backup_path, &config_dir, master_pass)
+    }
+
+    /// The public keys backups are encrypted to
+    fn backup_recipients(config: &Config) -> Vec<String> {
+        config
+            .backup
+            .recipients
+            .iter()
+            .map(|r| r.public_key.clone())
+            .collect()
+    }
+
+    /// Get statistics about the vault and its backups
+    ///
+    /// # Returns
+    ///
+    /// The number of entries, the size of the vault on disk and the number and time of the
+    /// backups
+    ///
+    /// # Errors
+    ///
+    /// Returns an error if not logged in, the entries or the backup folder cannot be read
+    pub fn database_stats(&self) -> Result<DatabaseStats, String> {
+        let (entry_count, database_size) = self.with_manager("read the vault size", |pm| {
+            let entries = pm.get_passwords().map_err(|e| e.to_string())?;
+            Ok((entries.len(), pm.db.disk_usage()))
+        })?;
+
+        let config = Config::load().map_err(|e| e.to_string())?;
+        let backups = if config.backup.backup_path.exists() {
+            BackupManager::list_backups(&config.backup.backup_path).map_err(|e| e.to_string())?
+        } else {
+            Vec::new()
+        };
+        let last_backup = backups
+            .first()
+            .and_then(|backup| {
+                fs::metadata(&backup.path)
+                    .and_then(|meta| meta.modified())
+                    .ok()
+            })
+            .map(|modified| DateTime::<Utc>::from(modified).to_rfc3339());
+
+        Ok(DatabaseStats {
+            entry_count,
+            database_size,
+            backup_count: backups.len(),
+            last_backup,
+        })
+    }
+}
@@ -0,0 +1,572 @@
+mod auth;
+mod backup;
+mod settings;
+mod transfer;
+
+pub use backup::DatabaseStats;
+pub use settings::{CryptoInfo, KeyInfo};
+pub use transfer::{ImportSummary, OtpMapping, OtpPreview};
+
+use log::{error, info};
+use serde::de::DeserializeOwned;
+use serde::{Deserialize, Serialize};
+use serde_json::Value;
+use std::sync::Arc;
+use tauri::{AppHandle, Manager};
+
+use crate::commands::{handle_browser_request, record_audit, PasswordManagerState};
+use crate::password_manager::NewEntry;
+use crate::utils::{
+    AuditEvent, EntrySummary, GeneratorOptions, RpcError, RpcHandler, SearchMode, SearchResult,
+    BROWSER_METHOD_PREFIX, INVALID_PARAMS, METHOD_NOT_FOUND, SERVER_ERROR,
+};
+use crate::{Config, PasswordManager};
+
+#[derive(Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct EntryRequest {
+    pub service: String,
+    pub username: String,
+    /// The password in plain text, None keeps the current password of an updated entry.
+    #[serde(default)]
+    pub password: Option<String>,
+    pub url: String,
+    pub notes: Option<String>,
+    pub require_master: Option<bool>,
+    /// The TOTP secret of a new entry, entries with one do not need a password.
+    #[serde(default)]
+    pub totp_secret: Option<String>,
+}
+
+impl From<EntryRequest> for NewEntry {
+    fn from(request: EntryRequest) -> Self {
+        NewEntry {
+            service: request.service,
+            username: request.username,
+            password: request.password.unwrap_or_default(),
+            url: request.url,
+            notes: request.notes,
+            require_master: request.require_master.unwrap_or(false),
+            totp_secret: request.totp_secret,
+        }
+    }
+}
+
+/// The outcome of one entry of `add_entries`, either the new ID or the reason it was rejected.
+#[derive(Serialize)]
+pub struct AddEntryResult {
+    pub id: Option<i32>,
+    pub error: Option<String>,
+}
+
+#[derive(Deserialize)]
+struct EntriesRequest {
+    entries: Vec<EntryRequest>,
+}
+
+#[derive(Deserialize)]
+#[serde(rename_all = "camelCase")]
+struct UpdateEntryRequest {
+    id: i32,
+    #[serde(flatten)]
+    entry: EntryRequest,
+    /// The master password, required to remove the protection of the entry.
+    #[serde(default)]
+    master_pass: Option<String>,
+}
+
+#[derive(Deserialize)]
+struct IdRequest {
+    id: i32,
+}
+
+#[derive(Deserialize)]
+struct ShareRequest {
+    id: i32,
+    username: String,
+}
+
+#[derive(Deserialize)]
+#[serde(rename_all = "camelCase")]
+struct BackupRequest {
+    master_pass: String,
+}
+
+#[derive(Deserialize)]
+struct TextRequest {
+    text: String,
+}
+
+#[derive(Deserialize)]
+struct SearchRequest {
+    query: String,
+    #[serde(default)]
+    mode: SearchMode,
+}
+
+#[derive(Deserialize)]
+struct GenerateRequest {
+    length: usize,
+    #[serde(default)]
+    options: Option<GeneratorOptions>,
+}
+
+/// Core operations on the vault, shared by the Tauri commands and the JSON-RPC server.
+pub struct VaultService<'a> {
+    state: &'a PasswordManagerState,
+}
+
+impl<'a> VaultService<'a> {
+    /// Create a new VaultService
+    ///
+    /// # Arguments
+    ///
+    /// * `state` - The state holding the logged in password manager
+    ///
+    /// # Returns
+    ///
+    /// A new VaultService instance
+    pub fn new(state: &'a PasswordManagerState) -> Self {
+        Self { state }
+    }
+
+    /// Get the metadata of all password entries
+    ///
+    /// The list never contains password material, passwords are decrypted per entry.
+    ///
+    /// # Returns
+    ///
+    /// The metadata of the password entries of the vault
+    ///
+    /// # Errors
+    ///
+    /// Returns an error if not logged in or the entries cannot be read
+    pub fn list_entries(&self) -> Result<Vec<EntrySummary>, String> {
+        info!("Fetching passwords");
+        self.with_manager("fetch passwords", |pm| {
+            let passwords = pm.get_passwords().map_err(|e| {
+                error!("Failed to fetch passwords: {}", e);
+                e.to_string()
+            })?;
+            info!("Successfully fetched {} passwords", passwords.len());
+
+            Ok(passwords.iter().map(EntrySummary::from).collect())
+        })
+    }
+
+    /// Search the password entries
+    ///
+    /// # Arguments
+    ///
+    /// * `query` - The text to search for in the service, username, URL and notes
+    /// * `mode` - Whether the query is plain text, a regex or a glob
+    ///
+    /// # Returns
+    ///
+    /// The matching entries with the positions of the hits
+    ///
+    /// # Errors
+    ///
+    /// Returns an error if not logged in, the pattern is invalid or the entries cannot be read
+    pub fn search_entries(
+        &self,
+        query: &str,
+        mode: SearchMode,
+    ) -> Result<Vec<SearchResult>, String> {
+        self.with_manager("search passwords", |pm| {
+            pm.search_passwords(query, mode).map_err(|e| {
+                error!("Failed to search passwords: {}", e);
+                e.to_string()
+            })
+        })
+    }
+
+    /// Add a new password entry
+    ///
+    /// # Arguments
+    ///
+    /// * `request` - The entry to add
+    ///
+    /// # Errors
+    ///
+    /// Returns an error if not logged in, neither a password nor a TOTP secret is given or the
+    /// entry cannot be added
+    pub fn add_entry(&self, request: EntryRequest) -> Result<(), String> {
+        let service = request.service.clone();
+        info!("Adding new password entry for service: {}", service);
+        self.with_manager("add password", |pm| {
+            pm.add_passwords(vec![NewEntry::from(request)])
+                .map_err(|e| e.to_string())
+                .and_then(|results| results.into_iter().next().unwrap_or(Ok(0)))
+                .map_err(|e| {
+                    error!("Failed to add password for service {}: {}", service, e);
+                    e
+                })?;
+            info!("Successfully added password for service: {}", service);
+            record_audit(AuditEvent::EntryCreated, "Password entry added");
+
+            Ok(())
+        })
+    }
+
+    /// Add several password entries in one transaction
+    ///
+    /// # Arguments
+    ///
+    /// * `requests` - The entries to add
+    ///
+    /// # Returns
+    ///
+    /// The new ID or the validation error of each entry, in the order of the requests
+    ///
+    /// # Errors
+    ///
+    /// Returns an error if not logged in or the valid entries cannot be added
+    pub fn add_entries(&self, requests: Vec<EntryRequest>) -> Result<Vec<AddEntryResult>, String> {
+        info!("Adding {} password entries", requests.len());
+        let entries = requests.into_iter().map(NewEntry::from).collect();
+
+        self.with_manager("add passwords", |pm| {
+            let results = pm.add_passwords(entries).map_err(|e| {
+                error!("Failed to add passwords: {}", e);
+                e.to_string()
+            })?;
+            let added = results.iter().filter(|result| result.is_ok()).count();
+            info!("Added {} of {} password entries", added, results.len());
+            record_audit(
+                AuditEvent::EntryCreated,
+                &format!("{} password entries added", added),
+            );
+
+            Ok(results
+                .into_iter()
+                .map(|result| match result {
+                    Ok(id) => AddEntryResult {
+                        id: Some(id),
+                        error: None,
+                    },
+                    Err(e) => AddEntryResult {
+                        id: None,
+                        error: Some(e),
+                    },
+                })
+                .collect())
+        })
+    }
+
+    /// Update a password entry
+    ///
+    /// # Arguments
+    ///
+    /// * `id` - The ID of the entry to update
+    /// * `request` - The new content of the entry, no `require_master` keeps the current one
+    /// * `master_pass` - The master password, required to remove the protection of the entry
+    ///
+    /// # Errors
+    ///
+    /// Returns an error if not logged in, the protection is removed without the correct
+    /// master password or the entry cannot be updated
+    pub fn update_entry(
+        &self,
+        id: i32,
+        request: EntryRequest,
+        master_pass: Option<&str>,
+    ) -> Result<(), String> {
+        let service = request.service.clone();
+        info!("Updating password entry {} for service: {}", id, service);
+        self.with_manager("update password", |pm| {
+            pm.update_password(
+                id,
+                request.service,
+                request.username,
+                request.password,
+                request.url,
+                request.notes,
+                request.require_master,
+                master_pass,
+            )
+            .map_err(|e| {
+                error!(
+                    "Failed to update password {} for service {}: {}",
+                    id, service, e
+                );
+                e.to_string()
+            })?;
+            info!(
+                "Successfully updated password {} for service: {}",
+                id, service
+            );
+            record_audit(
+                AuditEvent::EntryUpdated,
+                &format!("Password entry {} updated", id),
+            );
+
+            Ok(())
+        })
+    }
+
+    /// Move a password entry to the trash
+    ///
+    /// # Arguments
+    ///
+    /// * `id` - The ID of the entry to delete
+    ///
+    /// # Errors
+    ///
+    /// Returns an error if not logged in or the entry cannot be deleted
+    pub fn delete_entry(&self, id: i32) -> Result<(), String> {
+        self.with_manager("delete password", |pm| {
+            pm.delete_password(id).map_err(|e| {
+                error!("Failed to delete password entry {}: {}", id, e);
+                e.to_string()
+            })?;
+            info!("Successfully moved password entry {} to the trash", id);
+            record_audit(
+                AuditEvent::EntryDeleted,
+                &format!("Password entry {} moved to the trash", id),
+            );
+
+            Ok(())
+        })
+    }
+
+    /// Get the entries that expired or expire soon
+    ///
+    /// # Arguments
+    ///
+    /// * `days` - The number of days ahead an entry counts as expiring
+    ///
+    /// # Returns
+    ///
+    /// The metadata of the entries sorted by expiry date
+    ///
+    /// # Errors
+    ///
+    /// Returns an error if not logged in or the entries cannot be read
+    pub fn expiring_entries(&self, days: i64) -> Result<Vec<EntrySummary>, String> {
+        self.with_manager("check for expiring passwords", |pm| {
+            let entries = pm.get_expiring_entries(days).map_err(|e| {
+                error!("Failed to check for expiring passwords: {}", e);
+                e.to_string()
+            })?;
+
+            Ok(entries.iter().map(EntrySummary::from).collect())
+        })
+    }
+
+    /// Share a password entry with another user
+    ///
+    /// # Arguments
+    ///
+    /// * `id` - The ID of the entry to share
+    /// * `username` - The name of the user to share the entry with
+    ///
+    /// # Errors
+    ///
+    /// Returns an error if not logged in, the entry belongs to another user or the user does
+    /// not exist
+    pub fn share_entry(&self, id: i32, username: &str) -> Result<(), String> {
+        self.with_manager("share an entry", |pm| {
+            pm.share_entry(id, username).map_err(|e| e.to_string())?;
+            info!("Shared entry {} with {}", id, username);
+
+            Ok(())
+        })
+    }
+
+    /// Stop sharing a password entry with another user
+    ///
+    /// # Arguments
+    ///
+    /// * `id` - The ID of the shared entry
+    /// * `username` - The name of the user the entry is shared with
+    ///
+    /// # Errors
+    ///
+    /// Returns an error if not logged in, the entry belongs to another user or the user does
+    /// not exist
+    pub fn unshare_entry(&self, id: i32, username: &str) -> Result<(), String> {
+        self.with_manager("stop sharing an entry", |pm| {
+            pm.unshare_entry(id, username).map_err(|e| e.to_string())?;
+            info!("Stopped sharing entry {} with {}", id, username);
+
+            Ok(())
+        })
+    }
+
+    /// Generate a random password
+    ///
+    /// If the generator history is enabled and the vault is unlocked, the password is added
+    /// to the encrypted history.
+    ///
+    /// # Arguments
+    ///
+    /// * `length` - The length of the password
+    /// * `options` - The character classes and mode, the configured defaults if None
+    ///
+    /// # Returns
+    ///
+    /// The generated password
+    ///
+    /// # Errors
+    ///
+    /// Returns an error if the password cannot be generated
+    pub fn generate_password(
+        &self,
+        length: usize,
+        options: Option<GeneratorOptions>,
+    ) -> Result<String, String> {
+        let config = Config::load().map_err(|e| e.to_string())?.generator;
+        let options = options.unwrap_or(config.options);
+        let password =
+            PasswordManager::generate_password_with(length, &options).map_err(|e| e.to_string())?;
+
+        if config.history_size > 0 {
+            if let Some(pm) = self.state.0.lock().unwrap().as_ref() {
+                if let Err(e) = pm.record_generated_password(&password, config.history_size) {
+                    error!("Failed to add password to generator history: {}", e);
+                }
+            }
+        }
+
+        Ok(password)
+    }
+
+    /// Execute a JSON-RPC method
+    ///
+    /// # Arguments
+    ///
+    /// * `method` - The name of the method
+    /// * `params` - The parameters of the method
+    ///
+    /// # Returns
+    ///
+    /// The result of the method
+    ///
+    /// # Errors
+    ///
+    /// Returns an error if the method is unknown, the parameters are invalid or the
+    /// method fails
+    pub fn dispatch(&self, method: &str, params: Value) -> Result<Value, RpcError> {
+        let result = match method {
+            "list_entries" => self
+                .list_entries()
+                .map(|entries| serde_json::json!(entries)),
+            "search_entries" => {
+                let request: SearchRequest = parse_params(params)?;
+                self.search_entries(&request.query, request.mode)
+                    .map(|results| serde_json::json!(results))
+            }
+            "add_entry" => self.add_entry(parse_params(params)?).map(|_| Value::Null),
+            "add_entries" => {
+                let request: EntriesRequest = parse_params(params)?;
+                self.add_entries(request.entries)
+                    .map(|results| serde_json::json!(results))
+            }
+            "update_entry" => {
+                let request: UpdateEntryRequest = parse_params(params)?;
+                self.update_entry(request.id, request.entry, request.master_pass.as_deref())
+                    .map(|_| Value::Null)
+            }
+            "delete_entry" => {
+                let request: IdRequest = parse_params(params)?;
+                self.delete_entry(request.id).map(|_| Value::Null)
+            }
+            "generate_password" => {
+                let request: GenerateRequest = parse_params(params)?;
+                self.generate_password(request.length, request.options)
+                    .map(Value::String)
+            }
+            "share_entry" => {
+                let request: ShareRequest = parse_params(params)?;
+                self.share_entry(request.id, &request.username)
+                    .map(|_| Value::Null)
+            }
+            "unshare_entry" => {
+                let request: ShareRequest = parse_params(params)?;
+                self.unshare_entry(request.id, &request.username)
+                    .map(|_| Value::Null)
+            }
+            "import_text" => {
+                let request: TextRequest = parse_params(params)?;
+                self.import_text(&request.text)
+                    .map(|result| serde_json::json!(ImportSummary::from(result)))
+            }
+            "create_backup" => {
+                let request: BackupRequest = parse_params(params)?;
+                self.create_backup(&request.master_pass)
+                    .map(|path| Value::String(path.display().to_string()))
+            }
+            "database_stats" => self.database_stats().map(|stats| serde_json::json!(stats)),
+            "key_info" => self.key_info().map(|info| serde_json::json!(info)),
+            _ => return Err(RpcError::new(METHOD_NOT_FOUND, "Method not found")),
+        };
+
+        result.map_err(|e| RpcError::new(SERVER_ERROR, e))
+    }
+
+    /// Check that a user is logged in
+    ///
+    /// # Errors
+    ///
+    /// Returns an error if not logged in
+    pub fn ensure_logged_in(&self) -> Result<(), String> {
+        self.with_manager("access the vault", |_| Ok(()))
+    }
+
+    /// Run an operation with the logged in password manager
+    fn with_manager<T>(
+        &self,
+        action: &str,
+        f: impl FnOnce(&PasswordManager) -> Result<T, String>,
+    ) -> Result<T, String> {
+        self.with_manager_mut(action, |pm| f(pm))
+    }
+
+    /// Run an operation that changes the logged in password manager
+    fn with_manager_mut<T>(
+        &self,
+        action: &str,
+        f: impl FnOnce(&mut PasswordManager) -> Result<T, String>,
+    ) -> Result<T, String> {
+        let mut state = self.state.0.lock().unwrap();
+        match state.as_mut() {
+            Some(pm) => f(pm),
+            None => {
+                error!("Attempted to {} without being logged in", action);
+                Err("Not logged in".into())
+            }
+        }
+    }
+}
+
+/// Build the handler the JSON-RPC server uses to run methods on the vault.
+///
+/// Methods with the browser prefix come from the native messaging host and are only
+/// available with the browser extension enabled, all others only with the JSON-RPC
+/// interface enabled.
+///
+/// # Arguments
+///
+/// * `app` - The app handle, used to access the password manager state.
+/// * `vault_methods` - Whether the methods of the JSON-RPC interface are available.
+/// * `browser_methods` - Whether the methods of the browser extension are available.
+///
+/// # Returns
+///
+/// The JSON-RPC handler.
+pub fn rpc_handler(app: AppHandle, vault_methods: bool, browser_methods: bool) -> Arc<RpcHandler> {
+    Arc::new(
+        move |method: &str, params: Value| match method.strip_prefix(BROWSER_METHOD_PREFIX) {
+            Some(method) if browser_methods => handle_browser_request(&app, method, params),
+            None if vault_methods => VaultService::new(app.state::<PasswordManagerState>().inner())
+                .dispatch(method, params),
+            _ => Err(RpcError::new(METHOD_NOT_FOUND, "Method not found")),
+        },
+    )
+}
+
+/// Parse the parameters of a JSON-RPC method.
+pub(crate) fn parse_params<T: DeserializeOwned>(params: Value) -> Result<T, RpcError> {
+    serde_json::from_value(params).map_err(|e| RpcError::new(INVALID_PARAMS, e.to_string()))
+}
@@ -0,0 +1,302 @@
+use log::{error, info};
+use serde::Serialize;
+
+use super::VaultService;
+use crate::commands::record_audit;
+use crate::utils::{AuditEvent, AuditLog, Cipher, Collation, KEY_DERIVATION};
+use crate::Config;
+
+#[derive(Serialize)]
+pub struct KeyInfo {
+    pub version: u8,
+    pub iterations: u32,
+    pub cipher: Cipher,
+    pub outdated: bool,
+}
+
+#[derive(Serialize)]
+pub struct CryptoInfo {
+    pub sqlcipher_version: String,
+    pub kdf: &'static str,
+    pub iterations: u32,
+    pub key_version: u8,
+    pub cipher: Cipher,
+    pub salt_fingerprint: String,
+    /// When the keys were last rotated, None if the audit log has no rotation.
+    pub last_rotation: Option<String>,
+    pub outdated: bool,
+    /// Whether usernames, URLs and notes are encrypted in addition to the database.
+    pub encrypt_metadata: bool,
+    /// The number of entries not yet converted to the field encryption.
+    pub pending_field_migration: usize,
+}
+
+impl VaultService<'_> {
+    /// Check the master password of the logged in user
+    ///
+    /// # Arguments
+    ///
+    /// * `master_pass` - The master password to check
+    ///
+    /// # Errors
+    ///
+    /// Returns an error if not logged in or the master password is wrong
+    pub fn verify_master_password(&self, master_pass: &str) -> Result<(), String> {
+        self.with_manager("verify the master password", |pm| {
+            if !pm
+                .verify_master_password(master_pass)
+                .map_err(|e| e.to_string())?
+            {
+                return Err("Invalid master password".into());
+            }
+
+            Ok(())
+        })
+    }
+
+    /// Change the master password
+    ///
+    /// # Arguments
+    ///
+    /// * `current_password` - The current master password
+    /// * `new_password` - The new master password
+    ///
+    /// # Errors
+    ///
+    /// Returns an error if not logged in, the current password is wrong or the vault cannot
+    /// be re-encrypted
+    pub fn update_master_password(
+        &self,
+        current_password: &str,
+        new_password: &str,
+    ) -> Result<(), String> {
+        self.with_manager_mut("update the master password", |pm| {
+            pm.update_master_password(current_password, new_password)
+                .map_err(|e| e.to_string())
+        })
+    }
+
+    /// Re-encrypt the vault with a fresh salt and the current key parameters
+    ///
+    /// A full backup is created before anything is changed.
+    ///
+    /// # Arguments
+    ///
+    /// * `master_pass` - The master password
+    /// * `on_progress` - Called with the number of re-encrypted and total entries
+    ///
+    /// # Errors
+    ///
+    /// Returns an error if not logged in, the master password is wrong, the backup fails or
+    /// the vault cannot be re-encrypted
+    pub fn rotate_vault_keys(
+        &self,
+        master_pass: &str,
+        on_progress: impl FnMut(usize, usize),
+    ) -> Result<(), String> {
+        self.with_manager_mut("rotate the vault keys", |pm| {
+            if !pm
+                .verify_master_password(master_pass)
+                .map_err(|e| e.to_string())?
+            {
+                return Err("Invalid master password".into());
+            }
+
+            let backup = Self::full_backup(pm, master_pass).map_err(|e| {
+                error!("Failed to create backup before key rotation: {}", e);
+                e.to_string()
+            })?;
+            info!("Created backup before key rotation: {}", backup.display());
+
+            let params = pm
+                .rotate_vault_keys(master_pass, on_progress)
+                .map_err(|e| {
+                    error!("Failed to rotate vault keys: {}", e);
+                    e.to_string()
+                })?;
+            record_audit(
+                AuditEvent::KeyRotation,
+                &format!(
+                    "Keys rotated to version {}, backup {}",
+                    params.version,
+                    backup.display()
+                ),
+            );
+
+            Ok(())
+        })
+    }
+
+    /// Get the key parameters of the open vault
+    ///
+    /// # Returns
+    ///
+    /// The key version, the iterations, the cipher and whether a rotation is recommended
+    ///
+    /// # Errors
+    ///
+    /// Returns an error if not logged in
+    pub fn key_info(&self) -> Result<KeyInfo, String> {
+        self.with_manager("read the key parameters", |pm| {
+            let params = pm.db.encryption.params();
+
+            Ok(KeyInfo {
+                version: params.version,
+                iterations: params.iterations,
+                cipher: params.cipher,
+                outdated: params.is_outdated(),
+            })
+        })
+    }
+
+    /// Get the encryption status of the open vault
+    ///
+    /// # Returns
+    ///
+    /// The SQLCipher version, the key derivation parameters, the cipher, the salt
+    /// fingerprint and the date of the last key rotation
+    ///
+    /// # Errors
+    ///
+    /// Returns an error if not logged in or the SQLCipher version cannot be read
+    pub fn crypto_info(&self) -> Result<CryptoInfo, String> {
+        let last_rotation = Config::get_config_dir()
+            .and_then(|dir| AuditLog::new(&dir).last_event(AuditEvent::KeyRotation))
+            .unwrap_or_else(|e| {
+                error!("Failed to read the last key rotation: {}", e);
+                None
+            })
+            .map(|entry| entry.timestamp);
+
+        self.with_manager("read the encryption status", |pm| {
+            let params = pm.db.encryption.params();
+
+            Ok(CryptoInfo {
+                sqlcipher_version: pm.db.cipher_version().map_err(|e| e.to_string())?,
+                kdf: KEY_DERIVATION,
+                iterations: params.iterations,
+                key_version: params.version,
+                cipher: params.cipher,
+                salt_fingerprint: params.salt_fingerprint(),
+                last_rotation,
+                outdated: params.is_outdated(),
+                encrypt_metadata: pm.db.field_encryption(),
+                pending_field_migration: pm
+                    .db
+                    .pending_field_migration()
+                    .map_err(|e| e.to_string())?,
+            })
+        })
+    }
+
+    /// Encrypt usernames, URLs and notes in addition to the database
+    ///
+    /// New and changed entries use the setting right away, the existing entries are
+    /// converted by `migrate_fields`.
+    ///
+    /// # Arguments
+    ///
+    /// * `enabled` - Whether to encrypt the fields, false to store them as plaintext again
+    ///
+    /// # Errors
+    ///
+    /// Returns an error if not logged in, the demo vault is open or the setting cannot be
+    /// saved
+    pub fn set_field_encryption(&self, enabled: bool) -> Result<(), String> {
+        self.with_manager_mut("change the field encryption", |pm| {
+            if pm.db.is_in_memory() {
+                return Err("Not available in the demo vault".into());
+            }
+
+            let mut config = Config::load().map_err(|e| e.to_string())?;
+            config.database.encrypt_metadata = enabled;
+            config.save().map_err(|e| e.to_string())?;
+            pm.db.set_field_encryption(enabled);
+            info!(
+                "Field encryption of usernames, URLs and notes {}",
+                if enabled { "enabled" } else { "disabled" }
+            );
+
+            Ok(())
+        })
+    }
+
+    /// Convert a batch of entries to the current field encryption
+    ///
+    /// # Arguments
+    ///
+    /// * `batch` - The maximum number of entries to convert
+    ///
+    /// # Returns
+    ///
+    /// The number of converted entries and of the entries that were pending before, None if
+    /// not logged in
+    ///
+    /// # Errors
+    ///
+    /// Returns an error if the entries cannot be converted
+    pub fn migrate_fields(&self, batch: usize) -> Result<Option<(usize, usize)>, String> {
+        let state = self.state.0.lock().unwrap();
+        let Some(pm) = state.as_ref() else {
+            return Ok(None);
+        };
+
+        let pending = pm.db.pending_field_migration().map_err(|e| e.to_string())?;
+        let migrated = pm.db.migrate_fields(batch).map_err(|e| e.to_string())?;
+
+        Ok(Some((migrated, pending)))
+    }
+
+    /// Keep the session of the logged in user alive
+    ///
+    /// # Errors
+    ///
+    /// Returns an error if not logged in or the session already ended
+    pub fn extend_session(&self) -> Result<(), String> {
+        self.with_manager("extend the session", |pm| {
+            pm.extend_session().map_err(|e| {
+                error!("Failed to extend session: {}", e);
+                e.to_string()
+            })
+        })
+    }
+
+    /// Apply a changed decrypt limit to the open vault, nothing happens if not logged in
+    ///
+    /// # Arguments
+    ///
+    /// * `limit` - The secrets that can be decrypted per minute without the master
+    ///   password, 0 if unlimited
+    pub fn apply_decrypt_limit(&self, limit: u32) {
+        if let Some(pm) = self.state.0.lock().unwrap().as_mut() {
+            pm.set_decrypt_limit(limit);
+        }
+    }
+
+    /// Apply a changed sort order to the open vault, nothing happens if not logged in
+    ///
+    /// # Arguments
+    ///
+    /// * `collation` - The collation of the configured locale
+    pub fn apply_collation(&self, collation: Collation) {
+        if let Some(pm) = self.state.0.lock().unwrap().as_mut() {
+            pm.set_collation(collation);
+        }
+    }
+
+    /// Apply the sync-safe mode to the open vault, nothing happens if not logged in
+    ///
+    /// # Arguments
+    ///
+    /// * `enabled` - Whether to avoid the write-ahead log for cloud-synced folders
+    ///
+    /// # Errors
+    ///
+    /// Returns an error if the journal mode cannot be changed
+    pub fn apply_sync_safe_mode(&self, enabled: bool) -> Result<(), String> {
+        match self.state.0.lock().unwrap().as_ref() {
+            Some(pm) => pm.db.set_sync_safe_mode(enabled).map_err(|e| e.to_string()),
+            None => Ok(()),
+        }
+    }
+}
@@ -0,0 +1,492 @@
+use chrono::NaiveDate;
+use log::{error, info};
+use serde::{Deserialize, Serialize};
+use std::fs;
+use std::path::{Path, PathBuf};
+
+use super::VaultService;
+use crate::commands::record_audit;
+use crate::password_manager::NewEntry;
+use crate::utils::{
+    AuditEvent, AuditExportFormat, AuditLog, BrowserKind, CsvLayout, CsvPreview, CsvProfile,
+    EntrySummary, ExportFormat, ExportScope, ImportFormat, ImportResult, OtpExport, OtpFormat,
+};
+use crate::{BackupManager, Config};
+
+/// The outcome of an import without the messages of the rejected rows.
+#[derive(Serialize)]
+pub struct ImportSummary {
+    pub imported: usize,
+    pub skipped: usize,
+    pub errors: usize,
+}
+
+impl From<ImportResult> for ImportSummary {
+    fn from(result: ImportResult) -> Self {
+        Self {
+            imported: result.imported,
+            skipped: result.skipped,
+            errors: result.errors.len(),
+        }
+    }
+}
+
+/// A TOTP account of an authenticator backup and the entry it is suggested for.
+#[derive(Serialize)]
+pub struct OtpMatch {
+    /// The position of the account in the backup.
+    pub index: usize,
+    pub issuer: String,
+    pub account: String,
+    /// The suggested entry, None if no entry matches unambiguously.
+    pub entry_id: Option<i32>,
+}
+
+/// The TOTP accounts of an authenticator backup, without their secrets.
+#[derive(Serialize)]
+pub struct OtpPreview {
+    pub accounts: Vec<OtpMatch>,
+    /// The number of accounts that cannot be imported, e.g. HOTP accounts.
+    pub skipped: usize,
+}
+
+/// The entry the user chose for an account of an authenticator backup.
+#[derive(Deserialize)]
+pub struct OtpMapping {
+    /// The position of the account in the backup.
+    pub index: usize,
+    /// The entry to store the secret in, a new entry is created if None.
+    pub entry_id: Option<i32>,
+}
+
+impl VaultService<'_> {
+    /// Summarize what an export writes out of the vault
+    ///
+    /// # Arguments
+    ///
+    /// * `format` - The format of the export
+    /// * `excluded` - The IDs of the entries left out of the export
+    ///
+    /// # Returns
+    ///
+    /// The scope of the export, written to the configured export folder
+    ///
+    /// # Errors
+    ///
+    /// Returns an error if not logged in or the entries cannot be read
+    pub fn export_scope(
+        &self,
+        format: ExportFormat,
+        excluded: &[i32],
+    ) -> Result<ExportScope, String> {
+        let config = Config::load().map_err(|e| e.to_string())?;
+
+        self.with_manager("prepare an export", |pm| {
+            BackupManager::new(&pm.db)
+                .export_scope(format, excluded, &config.backup.export_path)
+                .map_err(|e| e.to_string())
+        })
+    }
+
+    /// Export the passwords to a CSV file
+    ///
+    /// # Arguments
+    ///
+    /// * `scope` - The confirmed scope of the export, the file is written to its destination
+    /// * `excluded` - The IDs of the entries left out of the export
+    ///
+    /// # Returns
+    ///
+    /// The path of the export file
+    ///
+    /// # Errors
+    ///
+    /// Returns an error if not logged in or the passwords cannot be exported
+    pub fn export_csv(&self, scope: &ExportScope, excluded: &[i32]) -> Result<PathBuf, String> {
+        self.with_manager("export passwords", |pm| {
+            let export_file = BackupManager::new(&pm.db)
+                .export_csv(&scope.destination, excluded)
+                .map_err(|e| e.to_string())?;
+            record_audit(
+                AuditEvent::Export,
+                &format!(
+                    "Passwords exported to {}: {}",
+                    export_file.display(),
+                    scope.summary()
+                ),
+            );
+
+            Ok(export_file)
+        })
+    }
+
+    /// Export the passwords to a file protected with a passphrase
+    ///
+    /// # Arguments
+    ///
+    /// * `scope` - The confirmed scope of the export, the file is written to its destination
+    /// * `passphrase` - The passphrase chosen to protect the export
+    ///
+    /// # Returns
+    ///
+    /// The path of the export file
+    ///
+    /// # Errors
+    ///
+    /// Returns an error if not logged in, the passphrase is too short or the passwords cannot
+    /// be exported
+    pub fn export_encrypted(
+        &self,
+        scope: &ExportScope,
+        passphrase: &str,
+    ) -> Result<PathBuf, String> {
+        self.with_manager("export passwords", |pm| {
+            fs::create_dir_all(&scope.destination).map_err(|e| e.to_string())?;
+            let export_file = BackupManager::new(&pm.db)
+                .export_encrypted(&scope.destination, passphrase)
+                .map_err(|e| {
+                    error!("Failed to export passwords encrypted: {}", e);
+                    e.to_string()
+                })?;
+            record_audit(
+                AuditEvent::Export,
+                &format!(
+                    "Passwords exported encrypted to {}: {}",
+                    export_file.display(),
+                    scope.summary()
+                ),
+            );
+
+            Ok(export_file)
+        })
+    }
+
+    /// Preview how the columns of a CSV file are imported
+    ///
+    /// # Arguments
+    ///
+    /// * `path` - The CSV file
+    /// * `profile` - The profile to read the file with, detected if None
+    ///
+    /// # Returns
+    ///
+    /// The detected layout and the first rows of the file
+    ///
+    /// # Errors
+    ///
+    /// Returns an error if not logged in, the file is unsafe or does not match the profile
+    pub fn preview_csv(
+        &self,
+        path: &Path,
+        profile: Option<CsvProfile>,
+    ) -> Result<CsvPreview, String> {
+        self.with_manager("preview an import", |pm| {
+            BackupManager::new(&pm.db)
+                .preview_csv(path, profile)
+                .map_err(|e| e.to_string())
+        })
+    }
+
+    /// Import passwords from a file
+    ///
+    /// # Arguments
+    ///
+    /// * `path` - The file to import
+    /// * `format` - The format of the file
+    /// * `password` - The password of a KeePass database or a Bitwarden export, or the
+    ///   passphrase of an encrypted export
+    /// * `layout` - The layout of a CSV file, detected from its header row if None
+    ///
+    /// # Returns
+    ///
+    /// The number of imported, skipped and rejected entries
+    ///
+    /// # Errors
+    ///
+    /// Returns an error if not logged in, a required password is missing or the passwords
+    /// cannot be imported
+    pub fn import_file(
+        &self,
+        path: &Path,
+        format: ImportFormat,
+        password: Option<&str>,
+        layout: Option<&CsvLayout>,
+    ) -> Result<ImportResult, String> {
+        self.with_manager("import passwords", |pm| {
+            let bm = BackupManager::new(&pm.db);
+            let result = match format {
+                ImportFormat::Csv => bm.import_csv(path, layout),
+                ImportFormat::Keepass => bm.import_kdbx(
+                    path,
+                    password.ok_or("The KeePass database needs its master password")?,
+                ),
+                ImportFormat::Bitwarden => bm.import_bitwarden(path, password),
+                ImportFormat::Kcexport => bm.import_encrypted(
+                    path,
+                    password.ok_or("The encrypted export needs its passphrase")?,
+                ),
+            }
+            .map_err(|e| {
+                error!("Failed to import passwords: {}", e);
+                e.to_string()
+            })?;
+            record_audit(
+                AuditEvent::Import,
+                &format!(
+                    "{} entries imported from {}",
+                    result.imported,
+                    path.display()
+                ),
+            );
+
+            Ok(result)
+        })
+    }
+
+    /// Import the password export of a browser with the layout of the browser
+    ///
+    /// # Arguments
+    ///
+    /// * `path` - The CSV file exported by the browser
+    /// * `browser` - The browser the file was exported from
+    ///
+    /// # Returns
+    ///
+    /// The number of imported, skipped and rejected entries
+    ///
+    /// # Errors
+    ///
+    /// Returns an error if not logged in, the file is unsafe, is no export of the browser or
+    /// the import fails
+    pub fn import_browser_export(
+        &self,
+        path: &Path,
+        browser: BrowserKind,
+    ) -> Result<ImportSummary, String> {
+        self.with_manager("import passwords", |pm| {
+            let content = fs::read_to_string(path).map_err(|e| e.to_string())?;
+            let layout = CsvLayout::for_profile(
+                browser.csv_profile(),
+                content.lines().next().unwrap_or(""),
+            )?;
+            let result = BackupManager::new(&pm.db)
+                .import_csv(path, Some(&layout))
+                .map_err(|e| {
+                    error!("Failed to import {:?} export: {}", browser, e);
+                    e.to_string()
+                })?;
+            record_audit(
+                AuditEvent::Import,
+                &format!(
+                    "{} entries imported from the {:?} export {}",
+                    result.imported,
+                    browser,
+                    path.display()
+                ),
+            );
+
+            Ok(ImportSummary::from(result))
+        })
+    }
+
+    /// Import passwords from pasted CSV or text rows
+    ///
+    /// # Arguments
+    ///
+    /// * `text` - The pasted rows
+    ///
+    /// # Returns
+    ///
+    /// The number of imported, skipped and rejected entries
+    ///
+    /// # Errors
+    ///
+    /// Returns an error if not logged in or the text is invalid
+    pub fn import_text(&self, text: &str) -> Result<ImportResult, String> {
+        self.with_manager("import passwords", |pm| {
+            let result = BackupManager::new(&pm.db).import_text(text).map_err(|e| {
+                error!("Failed to import pasted passwords: {}", e);
+                e.to_string()
+            })?;
+            record_audit(
+                AuditEvent::Import,
+                &format!("{} entries imported from pasted text", result.imported),
+            );
+
+            Ok(result)
+        })
+    }
+
+    /// Read the TOTP accounts of an authenticator backup and suggest their entries
+    ///
+    /// The secrets stay in the backup, `import_otp_secrets` reads it again with the mapping
+    /// the user confirmed.
+    ///
+    /// # Arguments
+    ///
+    /// * `path` - The backup file
+    /// * `format` - The app the backup was created with
+    ///
+    /// # Returns
+    ///
+    /// The accounts with the entries they are suggested for
+    ///
+    /// # Errors
+    ///
+    /// Returns an error if not logged in, the backup is encrypted or no backup of the app
+    pub fn preview_otp_import(&self, path: &Path, format: OtpFormat) -> Result<OtpPreview, String> {
+        self.with_manager("preview a TOTP import", |pm| {
+            let export = OtpExport::read(path, format).map_err(|e| e.to_string())?;
+            let entries: Vec<EntrySummary> = pm
+                .get_passwords()
+                .map_err(|e| e.to_string())?
+                .iter()
+                .map(EntrySummary::from)
+                .collect();
+
+            let accounts = export
+                .accounts
+                .iter()
+                .enumerate()
+                .map(|(index, account)| OtpMatch {
+                    index,
+                    entry_id: account.matching_entry(&entries),
+                    issuer: account.issuer.clone(),
+                    account: account.account.clone(),
+                })
+                .collect();
+
+            Ok(OtpPreview {
+                accounts,
+                skipped: export.skipped,
+            })
+        })
+    }
+
+    /// Import the TOTP secrets of an authenticator backup into the chosen entries
+    ///
+    /// Accounts without mapping are skipped, accounts mapped to no entry become new entries
+    /// that only hold the TOTP secret.
+    ///
+    /// # Arguments
+    ///
+    /// * `path` - The backup file
+    /// * `format` - The app the backup was created with
+    /// * `mappings` - The entries the user chose for the accounts
+    ///
+    /// # Returns
+    ///
+    /// The number of imported, skipped and rejected accounts
+    ///
+    /// # Errors
+    ///
+    /// Returns an error if not logged in, the backup cannot be read or the new entries
+    /// cannot be created
+    pub fn import_otp_secrets(
+        &self,
+        path: &Path,
+        format: OtpFormat,
+        mappings: &[OtpMapping],
+    ) -> Result<ImportSummary, String> {
+        self.with_manager("import TOTP secrets", |pm| {
+            let export = OtpExport::read(path, format).map_err(|e| e.to_string())?;
+            let mut summary = ImportSummary {
+                imported: 0,
+                skipped: export.skipped + export.accounts.len().saturating_sub(mappings.len()),
+                errors: 0,
+            };
+
+            let mut new_entries = Vec::new();
+            for mapping in mappings {
+                let Some(account) = export.accounts.get(mapping.index) else {
+                    summary.errors += 1;
+                    continue;
+                };
+                match mapping.entry_id {
+                    Some(id) => match pm.set_totp_secret(id, &account.secret) {
+                        Ok(()) => summary.imported += 1,
+                        Err(e) => {
+                            error!("Failed to import the TOTP secret of entry {}: {}", id, e);
+                            summary.errors += 1;
+                        }
+                    },
+                    None => new_entries.push(NewEntry {
+                        service: if account.issuer.is_empty() {
+                            account.account.clone()
+                        } else {
+                            account.issuer.clone()
+                        },
+                        username: account.account.clone(),
+                        password: String::new(),
+                        url: String::new(),
+                        notes: None,
+                        require_master: false,
+                        totp_secret: Some(account.secret.clone()),
+                    }),
+                }
+            }
+
+            if !new_entries.is_empty() {
+                for created in pm.add_passwords(new_entries).map_err(|e| e.to_string())? {
+                    match created {
+                        Ok(_) => summary.imported += 1,
+                        Err(e) => {
+                            error!("Failed to create an entry for a TOTP secret: {}", e);
+                            summary.errors += 1;
+                        }
+                    }
+                }
+            }
+            record_audit(
+                AuditEvent::Import,
+                &format!(
+                    "{} TOTP secrets imported from {}",
+                    summary.imported,
+                    path.display()
+                ),
+            );
+
+            Ok(summary)
+        })
+    }
+
+    /// Export the audit log to the configured export folder
+    ///
+    /// # Arguments
+    ///
+    /// * `format` - The format of the export file
+    /// * `from` - The first day to include, all if None
+    /// * `to` - The last day to include, all if None
+    ///
+    /// # Returns
+    ///
+    /// The path of the export file
+    ///
+    /// # Errors
+    ///
+    /// Returns an error if not logged in or the audit log cannot be exported
+    pub fn export_audit_log(
+        &self,
+        format: AuditExportFormat,
+        from: Option<NaiveDate>,
+        to: Option<NaiveDate>,
+    ) -> Result<PathBuf, String> {
+        self.ensure_logged_in()?;
+
+        let config = Config::load().map_err(|e| e.to_string())?;
+        let config_dir = Config::get_config_dir().map_err(|e| e.to_string())?;
+        let export_path = config.backup.export_path;
+        fs::create_dir_all(&export_path).map_err(|e| e.to_string())?;
+
+        let export_file = AuditLog::new(&config_dir)
+            .export(&export_path, format, from, to)
+            .map_err(|e| e.to_string())?;
+        info!("Exported audit log to {}", export_file.display());
+        record_audit(
+            AuditEvent::Export,
+            &format!("Audit log exported to {}", export_file.display()),
+        );
+
+        Ok(export_file)
+    }
+}
@@ -0,0 +1,178 @@
+/// Second-level labels that belong to the public suffix, e.g. `co.uk`.
+const MULTI_PART_SUFFIXES: &[&str] = &[
+    "co.uk", "org.uk", "ac.uk", "gov.uk", "com.au", "net.au", "org.au", "co.nz", "co.jp", "com.br",
+    "co.at", "or.at", "gv.at", "com.tr", "co.in", "co.za",
+];
+
+/// Services that share one account, usually through single sign-on.
+const RELATED_SERVICES: &[(&str, &[&str])] = &[
+    (
+        "Google",
+        &[
+            "google.com",
+            "google.de",
+            "gmail.com",
+            "youtube.com",
+            "android.com",
+        ],
+    ),
+    (
+        "Microsoft",
+        &[
+            "microsoft.com",
+            "microsoftonline.com",
+            "live.com",
+            "outlook.com",
+            "hotmail.com",
+            "office.com",
+            "xbox.com",
+            "skype.com",
+        ],
+    ),
+    ("Apple", &["apple.com", "icloud.com", "me.com"]),
+    (
+        "Amazon",
+        &[
+            "amazon.com",
+            "amazon.de",
+            "amazon.co.uk",
+            "audible.com",
+            "audible.de",
+            "primevideo.com",
+        ],
+    ),
+    (
+        "Meta",
+        &[
+            "facebook.com",
+            "instagram.com",
+            "messenger.com",
+            "threads.net",
+        ],
+    ),
+    (
+        "Atlassian",
+        &[
+            "atlassian.com",
+            "atlassian.net",
+            "bitbucket.org",
+            "trello.com",
+        ],
+    ),
+    ("Adobe", &["adobe.com", "behance.net"]),
+];
+
+/// Get the provider an entry logs in to
+///
+/// Subdomains belong to the same provider as their base domain, e.g. `accounts.google.com`
+/// and `mail.google.com`, and known related services to the same provider, e.g. Gmail and
+/// YouTube to Google. Without a URL, a service name that is a domain or names a related
+/// service is used.
+///
+/// # Arguments
+///
+/// * `service` - The service name of the entry
+/// * `url` - The URL of the entry, may be empty
+///
+/// # Returns
+///
+/// The name of the provider or the base domain, None if neither is known
+pub fn account_provider(service: &str, url: &str) -> Option<String> {
+    let domain = base_domain(url).or_else(|| {
+        let service = service.trim();
+        (service.contains('.') && !service.contains(char::is_whitespace))
+            .then(|| base_domain(service))
+            .flatten()
+    });
+
+    if let Some(domain) = domain {
+        let provider = RELATED_SERVICES
+            .iter()
+            .find(|(_, domains)| domains.contains(&domain.as_str()))
+            .map(|(provider, _)| provider.to_string());
+        return Some(provider.unwrap_or(domain));
+    }
+
+    let name = service.trim().to_lowercase();
+    RELATED_SERVICES
+        .iter()
+        .find(|(provider, domains)| {
+            provider.to_lowercase() == name
+                || domains
+                    .iter()
+                    .any(|domain| domain.split('.').next() == Some(name.as_str()))
+        })
+        .map(|(provider, _)| provider.to_string())
+}
+
+/// Get the registrable domain of a URL or host, e.g. `google.com` for `mail.google.com`
+///
+/// # Arguments
+///
+/// * `url` - The URL or host
+///
+/// # Returns
+///
+/// The lowercase base domain, None if the URL has no host with a dot
+pub fn base_domain(url: &str) -> Option<String> {
+    let url = url.trim();
+    let host = url.split_once("://").map_or(url, |(_, rest)| rest);
+    let host = host.split(['/', '?', '#']).next()?;
+    let host = host.rsplit('@').next()?;
+    let host = host.split(':').next()?.trim_end_matches('.').to_lowercase();
+
+    let labels: Vec<&str> = host.split('.').collect();
+    if labels.len() < 2 || labels.iter().any(|label| label.is_empty()) {
+        return None;
+    }
+
+    let suffix = labels[labels.len() - 2..].join(".");
+    let keep = if labels.len() > 2 && MULTI_PART_SUFFIXES.contains(&suffix.as_str()) {
+        3
+    } else {
+        2
+    };
+
+    Some(labels[labels.len() - keep..].join("."))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_base_domain() {
+        assert_eq!(
+            base_domain("https://accounts.google.com/signin?hl=de").as_deref(),
+            Some("google.com")
+        );
+        assert_eq!(
+            base_domain("user@mail.Example.org:8443").as_deref(),
+            Some("example.org")
+        );
+        assert_eq!(
+            base_domain("https://www.amazon.co.uk/").as_deref(),
+            Some("amazon.co.uk")
+        );
+        assert_eq!(base_domain("http://localhost:8080"), None);
+        assert_eq!(base_domain(""), None);
+    }
+
+    #[test]
+    fn test_account_provider() {
+        assert_eq!(
+            account_provider("Gmail", "https://mail.google.com").as_deref(),
+            Some("Google")
+        );
+        assert_eq!(account_provider("YouTube", "").as_deref(), Some("Google"));
+        assert_eq!(
+            account_provider("Office", "https://login.microsoftonline.com").as_deref(),
+            account_provider("Xbox", "xbox.com").as_deref()
+        );
+        assert_eq!(
+            account_provider("Wiki", "https://wiki.example.org").as_deref(),
+            Some("example.org")
+        );
+        assert_eq!(account_provider("Mein Forum", ""), None);
+    }
+}
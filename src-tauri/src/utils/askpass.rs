@@ -0,0 +1,220 @@
+use std::io::{IsTerminal, Read};
+use std::process::{Command, Stdio};
+use zeroize::Zeroize;
+
+/// Environment variable naming a helper program that prints the master password.
+pub const ASKPASS_ENV: &str = "KARNCRYPT_ASKPASS";
+/// Environment variable naming an inherited file descriptor to read the master password from.
+pub const PASSWORD_FD_ENV: &str = "KARNCRYPT_PASSWORD_FD";
+/// Service name of the master passwords stored in the OS keyring, the account is the username.
+pub const KEYRING_SERVICE: &str = "karncrypt";
+
+const PROMPT: &str = "KarnCrypt Master-Passwort:";
+
+#[derive(Debug, PartialEq)]
+pub enum PasswordSource {
+    Fd(u32),
+    Askpass(String),
+    Stdin,
+    /// The master password stored in the OS keyring for the given username.
+    Keyring(String),
+}
+
+impl PasswordSource {
+    /// Find the source of the master password for the command line
+    ///
+    /// The password is never taken from the arguments, because these show up in process
+    /// listings. A file descriptor takes precedence over the askpass helper, piped stdin is
+    /// the fallback.
+    ///
+    /// # Arguments
+    ///
+    /// * `env` - Lookup for environment variables
+    /// * `stdin_is_terminal` - Whether stdin is an interactive terminal
+    ///
+    /// # Returns
+    ///
+    /// The password source
+    ///
+    /// # Errors
+    ///
+    /// Returns an error if the file descriptor is invalid or no source is available
+    pub fn from_env(
+        env: impl Fn(&str) -> Option<String>,
+        stdin_is_terminal: bool,
+    ) -> Result<Self, Box<dyn std::error::Error>> {
+        if let Some(fd) = env(PASSWORD_FD_ENV).filter(|fd| !fd.is_empty()) {
+            let fd = fd
+                .trim()
+                .parse()
+                .map_err(|_| format!("{} is not a valid file descriptor", PASSWORD_FD_ENV))?;
+            return Ok(Self::Fd(fd));
+        }
+
+        if let Some(program) = env(ASKPASS_ENV).filter(|p| !p.is_empty()) {
+            return Ok(Self::Askpass(program));
+        }
+
+        if stdin_is_terminal {
+            return Err(format!(
+                "No master password source, set {} or {} or pipe the password to stdin",
+                PASSWORD_FD_ENV, ASKPASS_ENV
+            )
+            .into());
+        }
+
+        Ok(Self::Stdin)
+    }
+
+    /// Find the source of the master password from the process environment
+    ///
+    /// # Returns
+    ///
+    /// The password source
+    ///
+    /// # Errors
+    ///
+    /// Returns an error if no source is available
+    pub fn detect() -> Result<Self, Box<dyn std::error::Error>> {
+        Self::from_env(
+            |key| std::env::var(key).ok(),
+            std::io::stdin().is_terminal(),
+        )
+    }
+
+    /// Read the master password from the source
+    ///
+    /// Only the first line is used, so helpers may end their output with a line break.
+    ///
+    /// # Returns
+    ///
+    /// The master password
+    ///
+    /// # Errors
+    ///
+    /// Returns an error if the source cannot be read or the password is empty
+    pub fn read(&self) -> Result<String, Box<dyn std::error::Error>> {
+        let mut raw = match self {
+            Self::Fd(fd) => Self::read_fd(*fd)?,
+            Self::Askpass(program) => {
+                let output = Command::new(program)
+                    .arg(PROMPT)
+                    .stdin(Stdio::inherit())
+                    .stderr(Stdio::inherit())
+                    .output()?;
+                if !output.status.success() {
+                    return Err(format!("{} exited with {}", program, output.status).into());
+                }
+                output.stdout
+            }
+            Self::Stdin => {
+                let mut raw = Vec::new();
+                std::io::stdin().read_to_end(&mut raw)?;
+                raw
+            }
+            Self::Keyring(username) => keyring::Entry::new(KEYRING_SERVICE, username)?
+                .get_password()
+                .map_err(|e| format!("No master password in the OS keyring: {}", e))?
+                .into_bytes(),
+        };
+
+        let password = Self::first_line(&raw);
+        raw.zeroize();
+        password
+    }
+
+    /// Store the master password of a user in the OS keyring
+    ///
+    /// # Arguments
+    ///
+    /// * `username` - The user the password belongs to
+    /// * `password` - The master password
+    ///
+    /// # Errors
+    ///
+    /// Returns an error if the keyring is not available
+    pub fn store_in_keyring(
+        username: &str,
+        password: &str,
+    ) -> Result<(), Box<dyn std::error::Error>> {
+        keyring::Entry::new(KEYRING_SERVICE, username)?.set_password(password)?;
+
+        Ok(())
+    }
+
+    #[cfg(unix)]
+    fn read_fd(fd: u32) -> Result<Vec<u8>, Box<dyn std::error::Error>> {
+        Ok(std::fs::read(format!("/dev/fd/{}", fd))?)
+    }
+
+    #[cfg(not(unix))]
+    fn read_fd(_fd: u32) -> Result<Vec<u8>, Box<dyn std::error::Error>> {
+        Err(format!("{} is only supported on Unix", PASSWORD_FD_ENV).into())
+    }
+
+    fn first_line(raw: &[u8]) -> Result<String, Box<dyn std::error::Error>> {
+        let text = std::str::from_utf8(raw)?;
+        let line = text.lines().next().unwrap_or_default();
+        if line.is_empty() {
+            return Err("The master password is empty".into());
+        }
+
+        Ok(line.to_string())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use std::collections::HashMap;
+
+    fn lookup(vars: &[(&str, &str)]) -> impl Fn(&str) -> Option<String> {
+        let vars: HashMap<String, String> = vars
+            .iter()
+            .map(|(k, v)| (k.to_string(), v.to_string()))
+            .collect();
+        move |key| vars.get(key).cloned()
+    }
+
+    #[test]
+    fn test_source_precedence() {
+        let source = PasswordSource::from_env(
+            lookup(&[(PASSWORD_FD_ENV, "3"), (ASKPASS_ENV, "/usr/bin/askpass")]),
+            true,
+        )
+        .unwrap();
+        assert_eq!(source, PasswordSource::Fd(3));
+
+        let source =
+            PasswordSource::from_env(lookup(&[(ASKPASS_ENV, "/usr/bin/askpass")]), true).unwrap();
+        assert_eq!(
+            source,
+            PasswordSource::Askpass("/usr/bin/askpass".to_string())
+        );
+
+        let source = PasswordSource::from_env(lookup(&[]), false).unwrap();
+        assert_eq!(source, PasswordSource::Stdin);
+
+        assert!(PasswordSource::from_env(lookup(&[]), true).is_err());
+        assert!(PasswordSource::from_env(lookup(&[(PASSWORD_FD_ENV, "stdin")]), false).is_err());
+    }
+
+    #[test]
+    fn test_first_line() {
+        assert_eq!(
+            PasswordSource::first_line(b"geheim 123\r\nrest").unwrap(),
+            "geheim 123"
+        );
+        assert!(PasswordSource::first_line(b"\nrest").is_err());
+        assert!(PasswordSource::first_line(b"").is_err());
+    }
+
+    #[cfg(unix)]
+    #[test]
+    fn test_read_askpass() {
+        let password = PasswordSource::Askpass("echo".to_string()).read().unwrap();
+        assert_eq!(password, PROMPT);
+
+        assert!(PasswordSource::Askpass("false".to_string()).read().is_err());
+    }
+}
@@ -0,0 +1,104 @@
+use std::path::Path;
+use std::process::{Command, Stdio};
+
+/// An external command, e.g. a virus scanner, that has to accept a file before it is attached.
+#[derive(Debug, PartialEq)]
+pub struct AttachmentScanner {
+    program: String,
+    args: Vec<String>,
+}
+
+impl AttachmentScanner {
+    /// Create a scanner from the configured command line
+    ///
+    /// The command is split at whitespace, the first part is the program and the rest are
+    /// arguments passed before the path of the file.
+    ///
+    /// # Arguments
+    ///
+    /// * `command` - The command line, e.g. `clamscan --no-summary`
+    ///
+    /// # Returns
+    ///
+    /// The scanner, None if the command is empty
+    pub fn new(command: &str) -> Option<Self> {
+        let mut parts = command.split_whitespace().map(str::to_string);
+
+        Some(Self {
+            program: parts.next()?,
+            args: parts.collect(),
+        })
+    }
+
+    /// Scan a file before it is attached
+    ///
+    /// # Arguments
+    ///
+    /// * `path` - The file to scan
+    ///
+    /// # Errors
+    ///
+    /// Returns an error if the scanner cannot be started or rejects the file
+    pub fn scan(&self, path: &Path) -> Result<(), Box<dyn std::error::Error>> {
+        let output = Command::new(&self.program)
+            .args(&self.args)
+            .arg(path)
+            .stdin(Stdio::null())
+            .output()
+            .map_err(|e| format!("Failed to start the scanner {}: {}", self.program, e))?;
+        if !output.status.success() {
+            let report = String::from_utf8_lossy(&output.stdout);
+            let report = report.lines().find(|line| !line.trim().is_empty());
+            return Err(match report {
+                Some(line) => format!("The scanner rejected the file: {}", line.trim()),
+                None => format!("The scanner rejected the file ({})", output.status),
+            }
+            .into());
+        }
+
+        Ok(())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use tempfile::NamedTempFile;
+
+    #[test]
+    fn test_new() {
+        assert_eq!(AttachmentScanner::new("  "), None);
+        assert_eq!(
+            AttachmentScanner::new("clamscan --no-summary"),
+            Some(AttachmentScanner {
+                program: "clamscan".to_string(),
+                args: vec!["--no-summary".to_string()],
+            })
+        );
+    }
+
+    #[cfg(unix)]
+    #[test]
+    fn test_scan() {
+        let file = NamedTempFile::new().unwrap();
+
+        assert!(AttachmentScanner::new("test -f")
+            .unwrap()
+            .scan(file.path())
+            .is_ok());
+        assert!(AttachmentScanner::new("test -d")
+            .unwrap()
+            .scan(file.path())
+            .is_err());
+        assert!(AttachmentScanner::new("echo")
+            .unwrap()
+            .scan(file.path())
+            .is_ok());
+
+        let error = AttachmentScanner::new("karncrypt-missing-scanner")
+            .unwrap()
+            .scan(file.path())
+            .unwrap_err();
+        assert!(error.to_string().contains("Failed to start the scanner"));
+    }
+}
@@ -0,0 +1,305 @@
+use chrono::{DateTime, NaiveDate, Utc};
+use serde::{Deserialize, Serialize};
+use std::fs::{self, OpenOptions};
+use std::io::Write;
+use std::path::{Path, PathBuf};
+
+#[derive(Serialize, Deserialize, Clone, Copy, Debug, PartialEq)]
+#[serde(rename_all = "snake_case")]
+pub enum AuditEvent {
+    Login,
+    FailedLogin,
+    Lockout,
+    EntryCreated,
+    EntryUpdated,
+    EntryDeleted,
+    Export,
+    Import,
+    Backup,
+    Restore,
+    KeyRotation,
+    SnapshotOpened,
+    SessionRejected,
+}
+
+#[derive(Serialize, Deserialize, Clone, Copy, Debug, PartialEq)]
+#[serde(rename_all = "lowercase")]
+pub enum AuditExportFormat {
+    Csv,
+    Json,
+}
+
+#[derive(Serialize, Deserialize, Clone, Debug)]
+pub struct AuditEntry {
+    pub timestamp: String,
+    pub event: AuditEvent,
+    pub details: String,
+}
+
+pub struct AuditLog {
+    log_path: PathBuf,
+}
+
+impl AuditLog {
+    /// Create a new audit log
+    ///
+    /// The audit log lives outside the vault so events before a successful login can be
+    /// recorded as well.
+    ///
+    /// # Arguments
+    ///
+    /// * `config_dir` - The directory to store the audit log
+    ///
+    /// # Returns
+    ///
+    /// A new audit log
+    pub fn new(config_dir: &Path) -> Self {
+        Self {
+            log_path: config_dir.join("audit.log"),
+        }
+    }
+
+    /// Append an event to the audit log
+    ///
+    /// # Arguments
+    ///
+    /// * `event` - The event to record
+    /// * `details` - A short description of the event
+    ///
+    /// # Returns
+    ///
+    /// A Result containing a unit or an error
+    ///
+    /// # Errors
+    ///
+    /// If the audit log cannot be written
+    pub fn record(
+        &self,
+        event: AuditEvent,
+        details: &str,
+    ) -> Result<(), Box<dyn std::error::Error>> {
+        let entry = AuditEntry {
+            timestamp: Utc::now().to_rfc3339(),
+            event,
+            details: details.to_string(),
+        };
+
+        let mut file = OpenOptions::new()
+            .create(true)
+            .append(true)
+            .open(&self.log_path)?;
+        writeln!(file, "{}", serde_json::to_string(&entry)?)?;
+
+        Ok(())
+    }
+
+    /// Read the entries of the audit log within a date range
+    ///
+    /// # Arguments
+    ///
+    /// * `from` - The first day to include, or all entries before `to` if not set
+    /// * `to` - The last day to include, or all entries after `from` if not set
+    ///
+    /// # Returns
+    ///
+    /// A Result containing the entries in the order they were recorded or an error
+    ///
+    /// # Errors
+    ///
+    /// If the audit log cannot be read or contains invalid entries
+    pub fn read_range(
+        &self,
+        from: Option<NaiveDate>,
+        to: Option<NaiveDate>,
+    ) -> Result<Vec<AuditEntry>, Box<dyn std::error::Error>> {
+        if !self.log_path.exists() {
+            return Ok(Vec::new());
+        }
+
+        let content = fs::read_to_string(&self.log_path)?;
+        let mut entries = Vec::new();
+
+        for line in content.lines().filter(|line| !line.trim().is_empty()) {
+            let entry: AuditEntry = serde_json::from_str(line)?;
+            let date = DateTime::parse_from_rfc3339(&entry.timestamp)?
+                .with_timezone(&Utc)
+                .date_naive();
+
+            if from.is_some_and(|from| date < from) || to.is_some_and(|to| date > to) {
+                continue;
+            }
+
+            entries.push(entry);
+        }
+
+        Ok(entries)
+    }
+
+    /// Find the most recent entry of an event
+    ///
+    /// # Arguments
+    ///
+    /// * `event` - The event to look for
+    ///
+    /// # Returns
+    ///
+    /// A Result containing the latest entry of the event, None if it was never recorded
+    ///
+    /// # Errors
+    ///
+    /// If the audit log cannot be read or contains invalid entries
+    pub fn last_event(
+        &self,
+        event: AuditEvent,
+    ) -> Result<Option<AuditEntry>, Box<dyn std::error::Error>> {
+        Ok(self
+            .read_range(None, None)?
+            .into_iter()
+            .rev()
+            .find(|entry| entry.event == event))
+    }
+
+    /// Export the entries of the audit log within a date range
+    ///
+    /// # Arguments
+    ///
+    /// * `path` - The directory to export to
+    /// * `format` - The format of the export file
+    /// * `from` - The first day to include
+    /// * `to` - The last day to include
+    ///
+    /// # Returns
+    ///
+    /// A Result containing the path of the export file or an error
+    ///
+    /// # Errors
+    ///
+    /// If the audit log cannot be read or the export file cannot be written
+    pub fn export(
+        &self,
+        path: &Path,
+        format: AuditExportFormat,
+        from: Option<NaiveDate>,
+        to: Option<NaiveDate>,
+    ) -> Result<PathBuf, Box<dyn std::error::Error>> {
+        let entries = self.read_range(from, to)?;
+        let timestamp = Utc::now().format("%Y%m%d_%H%M%S").to_string();
+
+        let export_path = match format {
+            AuditExportFormat::Csv => {
+                let export_path = path.join(format!("audit_log_{}.csv", timestamp));
+                let mut writer = csv::Writer::from_path(&export_path)?;
+                writer.write_record(["Timestamp", "Event", "Details"])?;
+
+                for entry in &entries {
+                    let event = serde_json::to_value(entry.event)?;
+                    writer.write_record([
+                        entry.timestamp.as_str(),
+                        event.as_str().unwrap_or_default(),
+                        entry.details.as_str(),
+                    ])?;
+                }
+
+                writer.flush()?;
+                export_path
+            }
+            AuditExportFormat::Json => {
+                let export_path = path.join(format!("audit_log_{}.json", timestamp));
+                fs::write(&export_path, serde_json::to_string_pretty(&entries)?)?;
+                export_path
+            }
+        };
+
+        Ok(export_path)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use tempfile::TempDir;
+
+    #[test]
+    fn test_record() {
+        let temp = TempDir::new().unwrap();
+        let audit = AuditLog::new(temp.path());
+
+        audit.record(AuditEvent::FailedLogin, "test").unwrap();
+        audit.record(AuditEvent::Lockout, "locked for 30s").unwrap();
+
+        let content = fs::read_to_string(temp.path().join("audit.log")).unwrap();
+        let entries = content
+            .lines()
+            .map(|line| serde_json::from_str::<AuditEntry>(line).unwrap())
+            .collect::<Vec<_>>();
+
+        assert_eq!(entries.len(), 2);
+        assert_eq!(entries[0].event, AuditEvent::FailedLogin);
+        assert_eq!(entries[1].event, AuditEvent::Lockout);
+        assert_eq!(entries[1].details, "locked for 30s");
+    }
+
+    #[test]
+    fn test_read_range() {
+        let temp = TempDir::new().unwrap();
+        let audit = AuditLog::new(temp.path());
+
+        let lines = [
+            r#"{"timestamp":"2024-01-10T10:00:00+00:00","event":"login","details":"a"}"#,
+            r#"{"timestamp":"2024-02-10T10:00:00+00:00","event":"export","details":"b"}"#,
+            r#"{"timestamp":"2024-03-10T10:00:00+00:00","event":"entry_deleted","details":"c"}"#,
+        ];
+        fs::write(temp.path().join("audit.log"), lines.join("\n")).unwrap();
+
+        assert_eq!(audit.read_range(None, None).unwrap().len(), 3);
+
+        let from = NaiveDate::from_ymd_opt(2024, 2, 10);
+        let entries = audit.read_range(from, None).unwrap();
+        assert_eq!(entries.len(), 2);
+        assert_eq!(entries[0].event, AuditEvent::Export);
+
+        let to = NaiveDate::from_ymd_opt(2024, 2, 10);
+        let entries = audit.read_range(from, to).unwrap();
+        assert_eq!(entries.len(), 1);
+        assert_eq!(entries[0].details, "b");
+    }
+
+    #[test]
+    fn test_last_event() {
+        let temp = TempDir::new().unwrap();
+        let audit = AuditLog::new(temp.path());
+        assert!(audit.last_event(AuditEvent::KeyRotation).unwrap().is_none());
+
+        audit.record(AuditEvent::KeyRotation, "first").unwrap();
+        audit.record(AuditEvent::Login, "test").unwrap();
+        audit.record(AuditEvent::KeyRotation, "second").unwrap();
+        audit.record(AuditEvent::Login, "test").unwrap();
+
+        let last = audit.last_event(AuditEvent::KeyRotation).unwrap().unwrap();
+        assert_eq!(last.details, "second");
+        assert!(audit.last_event(AuditEvent::Restore).unwrap().is_none());
+    }
+
+    #[test]
+    fn test_export() {
+        let temp = TempDir::new().unwrap();
+        let audit = AuditLog::new(temp.path());
+        audit.record(AuditEvent::Login, "test").unwrap();
+        audit.record(AuditEvent::Export, "export").unwrap();
+
+        let csv_path = audit
+            .export(temp.path(), AuditExportFormat::Csv, None, None)
+            .unwrap();
+        let content = fs::read_to_string(csv_path).unwrap();
+        assert!(content.starts_with("Timestamp,Event,Details"));
+        assert!(content.contains(",login,test"));
+        assert!(content.contains(",export,export"));
+
+        let json_path = audit
+            .export(temp.path(), AuditExportFormat::Json, None, None)
+            .unwrap();
+        let entries: Vec<AuditEntry> =
+            serde_json::from_str(&fs::read_to_string(json_path).unwrap()).unwrap();
+        assert_eq!(entries.len(), 2);
+    }
+}
@@ -3,10 +3,11 @@ use base64::Engine;
 use chrono::Utc;
 use log::{error, info};
 
-use super::database::{Database, User};
+use super::database::User;
+use super::VaultStorage;
 
 pub struct Auth<'a> {
-    pub db: &'a Database,
+    pub db: &'a dyn VaultStorage,
 }
 
 impl<'a> Auth<'a> {
@@ -14,12 +15,12 @@ impl<'a> Auth<'a> {
     ///
     /// # Arguments
     ///
-    /// * `db` - The database to use for authentication.
+    /// * `db` - The vault storage to use for authentication.
     ///
     /// # Returns
     ///
     /// A new Auth instance.
-    pub fn new(db: &'a Database) -> Self {
+    pub fn new(db: &'a dyn VaultStorage) -> Self {
         Self { db }
     }
 
@@ -44,7 +45,7 @@ impl<'a> Auth<'a> {
     ) -> Result<(), Box<dyn std::error::Error>> {
         info!("Attempting to register new user: {}", username);
 
-        let encrypted_master = self.db.encryption.encrypt(master_pass).unwrap();
+        let encrypted_master = self.db.encryption().encrypt(master_pass).unwrap();
         let encode_master = STANDARD.encode(encrypted_master);
 
         let user = User {
@@ -55,7 +56,7 @@ impl<'a> Auth<'a> {
             last_login: Utc::now().to_rfc3339(),
         };
 
-        match self.db.create(&user) {
+        match self.db.create_user(&user) {
             Ok(_) => {
                 info!("Successfully registered new user: {}", username);
                 Ok(())
@@ -88,7 +89,7 @@ impl<'a> Auth<'a> {
     ) -> Result<i32, Box<dyn std::error::Error>> {
         info!("Attempting to verify credentials");
 
-        let users = self.db.read_all::<User>()?;
+        let users = self.db.users()?;
         info!("Found {} users", users.len());
 
         if let Some(user) = users.first() {
@@ -98,12 +99,12 @@ impl<'a> Auth<'a> {
             }
 
             let decode_master = STANDARD.decode(&user.master_key).unwrap();
-            if let Ok(decrypted) = self.db.encryption.decrypt(&decode_master) {
+            if let Ok(decrypted) = self.db.encryption().decrypt(&decode_master) {
                 if decrypted == master_pass {
                     info!("Password verified successfully");
                     let mut updated_user = user.clone();
                     updated_user.last_login = Utc::now().to_rfc3339();
-                    self.db.update(&updated_user)?;
+                    self.db.update_user(&updated_user)?;
 
                     return Ok(user.id.unwrap());
                 }
@@ -120,13 +121,13 @@ impl<'a> Auth<'a> {
 #[cfg(test)]
 mod tests {
     use super::*;
+    use crate::utils::{Database, KeyParams};
     use tempfile::TempDir;
 
     fn setup_test_db() -> (TempDir, Database) {
         let temp = TempDir::new().unwrap();
         let db_path = temp.path().join("test.db");
-        let salt = [0u8; 16];
-        let db = Database::new(db_path, "test_password", &salt).unwrap();
+        let db = Database::new(db_path, "test_password", &KeyParams::legacy([0u8; 16])).unwrap();
         (temp, db)
     }
 
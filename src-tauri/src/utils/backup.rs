@@ -1,15 +1,114 @@
 use crate::utils::Database;
 use base64::engine::general_purpose::STANDARD;
 use base64::Engine;
-use chrono::Utc;
+use chrono::{DateTime, NaiveDateTime, Utc};
 use log::{error, info};
+use serde::{Deserialize, Serialize};
+use std::collections::HashMap;
 use std::fs;
 use std::fs::File;
 use std::io::Read;
 use std::path::{Path, PathBuf};
 
-use super::PasswordEntry;
-use super::{BackupCompressor, BackupFile};
+use super::{
+    BackupCompressor, BackupFile, BackupNaming, FsStorage, RecipientEncryption, Storage,
+    DEFAULT_BACKUP_NAME,
+};
+use super::{
+    BitwardenExport, CsvLayout, CsvPreview, CsvProfile, EncryptedExport, ExportEntry, Folder,
+    KdbxDatabase, KdbxGroup, KeyParams, PasswordEntry, TableRows, Tag, Totp, User, VaultStorage,
+    BITWARDEN_LOGIN, EXPORT_EXTENSION,
+};
+
+const FULL_EXTENSION: &str = "pmbackup";
+const INCREMENT_EXTENSION: &str = "pminc";
+const INCREMENT_FILE: &str = "increment.bin";
+const TIMESTAMP_FORMAT: &str = "%Y%m%d_%H%M%S";
+/// The length of a timestamp formatted with `TIMESTAMP_FORMAT`.
+const TIMESTAMP_LENGTH: usize = 15;
+const INCREMENT_MARKER: &str = "_inc_";
+const MANIFEST_FILE: &str = "manifest.json";
+const APP_VERSION: &str = env!("CARGO_PKG_VERSION");
+/// The largest CSV file or pasted text that is imported, in bytes.
+const MAX_IMPORT_SIZE: u64 = 10_000_000;
+/// The delimiters a pasted text may use, the first one is the CSV default.
+const IMPORT_DELIMITERS: [u8; 3] = [b';', b'\t', b','];
+/// The tag given to entries that are favorites in the imported vault.
+const FAVORITES_TAG: &str = "Favoriten";
+
+/// The format of a file to import passwords from.
+#[derive(Clone, Copy, Debug, Default, PartialEq, Deserialize)]
+#[serde(rename_all = "lowercase")]
+pub enum ImportFormat {
+    #[default]
+    Csv,
+    Keepass,
+    Bitwarden,
+    Kcexport,
+}
+
+/// The format the passwords are exported to.
+#[derive(Clone, Copy, Debug, Default, PartialEq, Serialize, Deserialize)]
+#[serde(rename_all = "lowercase")]
+pub enum ExportFormat {
+    #[default]
+    Csv,
+    Encrypted,
+}
+
+/// What an export writes out of the vault, shown to the user before it runs.
+///
+/// Secure notes, cards and identities are never exported, attachments stay in the vault as
+/// well and are only counted so the user knows they are left out.
+#[derive(Clone, Debug, Default, PartialEq, Serialize, Deserialize)]
+pub struct ExportScope {
+    pub format: ExportFormat,
+    /// The number of entries written to the file.
+    pub entries: usize,
+    /// The exported entries with a password.
+    pub logins: usize,
+    /// The exported entries that only hold a TOTP secret.
+    pub totp_only: usize,
+    /// The number of TOTP secrets written to the file.
+    pub totp_secrets: usize,
+    /// Whether folders and tags are written to the file.
+    pub folders_and_tags: bool,
+    /// The attachments of the exported entries, which are left out.
+    pub attachments: usize,
+    /// The entries left out because they are flagged by the health check.
+    pub excluded: usize,
+    /// The directory the file is written to.
+    pub destination: PathBuf,
+}
+
+impl ExportScope {
+    /// Describe the scope for the audit log
+    ///
+    /// # Returns
+    ///
+    /// A one-line summary of what was exported and where to
+    pub fn summary(&self) -> String {
+        format!(
+            "{} entries ({} logins, {} TOTP only) as {}, {} TOTP secrets, {}, {} attachments and {} flagged entries left out, to {}",
+            self.entries,
+            self.logins,
+            self.totp_only,
+            match self.format {
+                ExportFormat::Csv => "plain CSV",
+                ExportFormat::Encrypted => "encrypted file",
+            },
+            self.totp_secrets,
+            if self.folders_and_tags {
+                "with folders and tags"
+            } else {
+                "without folders and tags"
+            },
+            self.attachments,
+            self.excluded,
+            self.destination.display()
+        )
+    }
+}
 
 pub struct ImportResult {
     pub imported: usize,
@@ -17,11 +116,79 @@ pub struct ImportResult {
     pub errors: Vec<String>,
 }
 
-pub struct BackupManager<'a> {
-    pub db: &'a Database,
+#[derive(Serialize)]
+pub struct BackupInfo {
+    pub name: String,
+    pub path: PathBuf,
+    pub incremental: bool,
+    pub encrypted: bool,
+    pub size: u64,
+    pub uncompressed_size: Option<u64>,
+    pub app_version: Option<String>,
+}
+
+#[derive(Serialize)]
+pub struct BackupPreview {
+    pub entries: Vec<BackupPreviewEntry>,
+    pub app_version: Option<String>,
+}
+
+#[derive(Serialize)]
+pub struct BackupPreviewEntry {
+    pub service: String,
+    pub username: String,
+    pub url: String,
+    pub updated_at: String,
+}
+
+/// An entry of an opened snapshot, the password is only decrypted on request.
+#[derive(Serialize)]
+pub struct SnapshotEntry {
+    pub id: i32,
+    pub service: String,
+    pub username: String,
+    pub url: String,
+    pub notes: String,
+    pub updated_at: String,
+}
+
+/// A backup restored into its own directory and kept open for browsing.
+///
+/// The snapshot only offers reads, so nothing can be changed in the backup or the current
+/// vault. The restored files are removed again when the snapshot is dropped.
+pub struct BackupSnapshot {
+    db: Option<Database>,
+    dir: PathBuf,
+    name: String,
+    created_at: Option<String>,
+}
+
+#[derive(Serialize, Deserialize)]
+struct BackupManifest {
+    app_version: String,
+    created_at: String,
+    #[serde(default)]
+    raw_database: bool,
+}
+
+#[derive(Serialize, Deserialize)]
+struct IncrementData {
+    base: String,
+    users: Vec<User>,
+    changed: Vec<PasswordEntry>,
+    password_ids: Vec<i32>,
+    #[serde(default)]
+    tables: Vec<TableRows>,
+}
+
+pub struct BackupManager<'a, S: ?Sized = Database> {
+    pub db: &'a S,
+    recipients: Vec<String>,
+    full_backup_every: Option<usize>,
+    naming: BackupNaming,
 }
 
-impl<'a> BackupManager<'a> {
+impl<'a, S: VaultStorage + ?Sized> BackupManager<'a, S> {
     /// Create a new BackupManager
     ///
     /// # Arguments
@@ -31,8 +198,92 @@ impl<'a> BackupManager<'a> {
     /// # Returns
     ///
     /// A new BackupManager instance
-    pub fn new(db: &'a Database) -> Self {
-        Self { db }
+    pub fn new(db: &'a S) -> Self {
+        Self {
+            db,
+            recipients: Vec::new(),
+            full_backup_every: None,
+            naming: BackupNaming::default(),
+        }
+    }
+
+    /// Name created backups after the given template
+    ///
+    /// Only backups with a matching name are continued incrementally and pruned, so other
+    /// machines can keep their backups in the same folder.
+    ///
+    /// # Arguments
+    ///
+    /// * `naming` - The file name template of the backups
+    ///
+    /// # Returns
+    ///
+    /// The BackupManager instance
+    pub fn with_naming(mut self, naming: BackupNaming) -> Self {
+        self.naming = naming;
+        self
+    }
+
+    /// Encrypt created backups to the given recipient public keys
+    ///
+    /// # Arguments
+    ///
+    /// * `recipients` - The encoded public keys allowed to decrypt the backups
+    ///
+    /// # Returns
+    ///
+    /// The BackupManager instance
+    pub fn with_recipients(mut self, recipients: Vec<String>) -> Self {
+        self.recipients = recipients;
+        self
+    }
+
+    /// Create incremental backups and force a new full backup after the given number of increments
+    ///
+    /// # Arguments
+    ///
+    /// * `full_backup_every` - The number of increments before a new full backup is created
+    ///
+    /// # Returns
+    ///
+    /// The BackupManager instance
+    pub fn with_incremental(mut self, full_backup_every: usize) -> Self {
+        self.full_backup_every = Some(full_backup_every);
+        self
+    }
+
+    /// Create a full or incremental backup depending on the backup policy
+    ///
+    /// # Arguments
+    ///
+    /// * `backup_path` - The directory to store the backup
+    /// * `config_dir` - The directory containing the configuration files
+    /// * `master_password` - The master password to encrypt the backup
+    ///
+    /// # Returns
+    ///
+    /// The path to the created backup file
+    ///
+    /// # Errors
+    ///
+    /// Returns an error if the backup creation fails
+    pub fn backup(
+        &self,
+        backup_path: &Path,
+        config_dir: &Path,
+        master_password: &str,
+    ) -> Result<PathBuf, Box<dyn std::error::Error>> {
+        if let Some(full_backup_every) = self.full_backup_every {
+            if let Some((base, increments)) =
+                BackupManager::latest_chain(backup_path, &self.naming)?
+            {
+                if increments < full_backup_every {
+                    return self.create_incremental_backup(&base, backup_path, config_dir);
+                }
+            }
+        }
+
+        self.create_backup(backup_path, config_dir, master_password)
     }
 
     /// Create a backup of the database and configuration files
@@ -58,18 +309,19 @@ impl<'a> BackupManager<'a> {
         master_password: &str,
     ) -> Result<PathBuf, Box<dyn std::error::Error>> {
         info!("Creating backup");
-        let timestamp = Utc::now().format("%Y%m%d_%H%M%S").to_string();
+        let now = Utc::now();
+        let timestamp = now.format(TIMESTAMP_FORMAT).to_string();
         let temp_dir = backup_path.join(format!("backup_{}", timestamp));
         fs::create_dir_all(&temp_dir)?;
 
         let mut backup_files = Vec::new();
 
-        let dump_path = temp_dir.join(self.db.path.file_name().unwrap());
+        let dump_path = temp_dir.join(self.db.path().file_name().unwrap());
         self.db.create_dump(&dump_path, master_password)?;
         backup_files.push(BackupFile {
             name: self
                 .db
-                .path
+                .path()
                 .file_name()
                 .unwrap()
                 .to_str()
@@ -78,26 +330,405 @@ impl<'a> BackupManager<'a> {
             data: fs::read(dump_path)?,
         });
 
-        for file_name in &["config.toml", ".salt"] {
+        backup_files.append(&mut BackupManager::config_files(config_dir, false)?);
+
+        let final_backup_path = BackupManager::write_full_backup(
+            &backup_files,
+            backup_path,
+            &format!("{}_{}", self.naming.render(now), timestamp),
+            &self.recipients,
+        )?;
+
+        fs::remove_dir_all(temp_dir)?;
+
+        info!("Backup created successfully");
+        Ok(final_backup_path)
+    }
+
+    /// Create an incremental backup containing the entries changed since the given full backup
+    ///
+    /// Folders, tags, pins, shares, notes, cards, identities, servers and attachments are
+    /// carried in full, only password entries are stored as changes.
+    ///
+    /// # Arguments
+    ///
+    /// * `base` - The path to the full backup the increment is based on
+    /// * `backup_path` - The directory to store the backup
+    /// * `config_dir` - The directory containing the configuration files
+    ///
+    /// # Returns
+    ///
+    /// The path to the created increment file
+    ///
+    /// # Errors
+    ///
+    /// Returns an error if the backup creation fails
+    pub fn create_incremental_backup(
+        &self,
+        base: &Path,
+        backup_path: &Path,
+        config_dir: &Path,
+    ) -> Result<PathBuf, Box<dyn std::error::Error>> {
+        info!("Creating incremental backup");
+        let (base_stem, base_timestamp) = BackupManager::full_name(base)
+            .map(|(name, timestamp)| (format!("{}_{}", name, timestamp), timestamp))
+            .ok_or("Invalid base backup name")?;
+        let since = NaiveDateTime::parse_from_str(&base_timestamp, TIMESTAMP_FORMAT)?.and_utc();
+
+        let entries = self.db.entries()?;
+        let password_ids = entries.iter().filter_map(|e| e.id).collect();
+        let changed = entries
+            .into_iter()
+            .filter(|e| {
+                DateTime::parse_from_rfc3339(&e.updated_at)
+                    .map_or(true, |updated| updated.with_timezone(&Utc) >= since)
+            })
+            .collect::<Vec<_>>();
+
+        let data = IncrementData {
+            base: base_timestamp.clone(),
+            users: self.db.users()?,
+            changed,
+            password_ids,
+            tables: self.db.increment_tables()?,
+        };
+        let encrypted = self
+            .db
+            .encryption()
+            .encrypt(&serde_json::to_string(&data)?)
+            .map_err(|_| "Failed to encrypt increment")?;
+
+        let backup_files = vec![
+            BackupFile {
+                name: INCREMENT_FILE.to_string(),
+                data: encrypted,
+            },
+            BackupFile {
+                name: "config.toml".to_string(),
+                data: fs::read(config_dir.join("config.toml"))?,
+            },
+        ];
+
+        let compressor = BackupCompressor::new();
+        let mut compressed = compressor.compress(&backup_files)?;
+
+        if !self.recipients.is_empty() {
+            info!("Encrypt increment to {} recipients", self.recipients.len());
+            compressed = RecipientEncryption::encrypt(&compressed, &self.recipients)?;
+        }
+
+        let timestamp = Utc::now().format(TIMESTAMP_FORMAT).to_string();
+        let increment_path = backup_path.join(format!(
+            "{}{}{}.{}",
+            base_stem, INCREMENT_MARKER, timestamp, INCREMENT_EXTENSION
+        ));
+        fs::write(&increment_path, compressed)?;
+
+        info!(
+            "Incremental backup created with {} changed entries",
+            data.changed.len()
+        );
+        Ok(increment_path)
+    }
+
+    /// Automatically create a backup and remove old backups if the maximum number of backups is reached
+    ///
+    /// Only full backups are counted, increments are removed together with their full backup.
+    ///
+    /// # Arguments
+    ///
+    /// * `db` - The database to backup
+    /// * `backup_path` - The directory to store the backup
+    /// * `config_dir` - The directory containing the configuration files
+    /// * `master_password` - The master password to encrypt the backup
+    /// * `max_backups` - The maximum number of backups to keep
+    ///
+    /// # Returns
+    ///
+    /// The path to the created backup file
+    ///
+    /// # Errors
+    ///
+    /// Returns an error if the backup creation fails
+    pub fn auto_backup(
+        &self,
+        backup_path: &Path,
+        config_dir: &Path,
+        master_password: &str,
+        max_backups: usize,
+    ) -> Result<PathBuf, Box<dyn std::error::Error>> {
+        let new_backup = self.backup(backup_path, config_dir, master_password)?;
+        BackupManager::prune_backups(backup_path, max_backups, &self.naming)?;
+
+        Ok(new_backup)
+    }
+}
+
+impl BackupManager<'_> {
+    /// Create a safety backup of the database file without opening the vault
+    ///
+    /// The database file is already encrypted with the master password, so it is stored
+    /// together with its write-ahead log as is. This allows a backup before an app update
+    /// even if no user is logged in.
+    ///
+    /// # Arguments
+    ///
+    /// * `db_path` - The path to the database file
+    /// * `backup_path` - The directory to store the backup
+    /// * `config_dir` - The directory containing the configuration files
+    /// * `recipients` - The encoded public keys allowed to decrypt the backup
+    ///
+    /// # Returns
+    ///
+    /// The path to the created backup file
+    ///
+    /// # Errors
+    ///
+    /// Returns an error if the database file is missing or the backup cannot be written
+    pub fn create_safety_backup(
+        db_path: &Path,
+        backup_path: &Path,
+        config_dir: &Path,
+        recipients: &[String],
+    ) -> Result<PathBuf, Box<dyn std::error::Error>> {
+        info!("Creating safety backup");
+        let timestamp = Utc::now().format(TIMESTAMP_FORMAT).to_string();
+        let db_name = db_path
+            .file_name()
+            .and_then(|name| name.to_str())
+            .ok_or("Invalid database path")?;
+
+        let mut backup_files = vec![BackupFile {
+            name: db_name.to_string(),
+            data: fs::read(db_path)?,
+        }];
+
+        let wal_path = db_path.with_file_name(format!("{}-wal", db_name));
+        if wal_path.exists() {
             backup_files.push(BackupFile {
+                name: format!("{}-wal", db_name),
+                data: fs::read(wal_path)?,
+            });
+        }
+
+        backup_files.append(&mut Self::config_files(config_dir, true)?);
+
+        let final_backup_path = Self::write_full_backup(
+            &backup_files,
+            backup_path,
+            &format!("{}_{}", DEFAULT_BACKUP_NAME, timestamp),
+            recipients,
+        )?;
+
+        info!("Safety backup created successfully");
+        Ok(final_backup_path)
+    }
+
+    /// Read the configuration files and create the manifest for a full backup
+    fn config_files(
+        config_dir: &Path,
+        raw_database: bool,
+    ) -> Result<Vec<BackupFile>, Box<dyn std::error::Error>> {
+        let mut files = Vec::new();
+        for file_name in &["config.toml", ".salt"] {
+            files.push(BackupFile {
                 name: file_name.to_string(),
                 data: fs::read(config_dir.join(file_name))?,
             });
         }
 
+        let manifest = BackupManifest {
+            app_version: APP_VERSION.to_string(),
+            created_at: Utc::now().to_rfc3339(),
+            raw_database,
+        };
+        files.push(BackupFile {
+            name: MANIFEST_FILE.to_string(),
+            data: serde_json::to_vec(&manifest)?,
+        });
+
+        Ok(files)
+    }
+
+    /// Compress the backup files, encrypt them to the recipients and write the full backup
+    fn write_full_backup(
+        backup_files: &[BackupFile],
+        backup_path: &Path,
+        file_stem: &str,
+        recipients: &[String],
+    ) -> Result<PathBuf, Box<dyn std::error::Error>> {
         info!("Compress backup files");
         let compressor = BackupCompressor::new();
-        let compressed = compressor.compress(&backup_files)?;
+        let mut compressed = compressor.compress(backup_files)?;
 
-        let final_backup_path = backup_path.join(format!("backup_{}.pmbackup", timestamp));
-        fs::write(&final_backup_path, compressed)?;
+        if !recipients.is_empty() {
+            info!("Encrypt backup to {} recipients", recipients.len());
+            compressed = RecipientEncryption::encrypt(&compressed, recipients)?;
+        }
 
-        fs::remove_dir_all(temp_dir)?;
+        let final_backup_path = backup_path.join(format!("{}.{}", file_stem, FULL_EXTENSION));
+        fs::write(&final_backup_path, compressed)?;
 
-        info!("Backup created successfully");
         Ok(final_backup_path)
     }
 
+    /// Read the manifest of a full backup, if it has one
+    fn read_manifest(files: &[BackupFile]) -> Option<BackupManifest> {
+        let manifest = files.iter().find(|f| f.name == MANIFEST_FILE)?;
+        serde_json::from_slice(&manifest.data).ok()
+    }
+
+    /// Resolve the chain of backup files needed to restore a backup
+    ///
+    /// # Arguments
+    ///
+    /// * `backup_file` - The path to a full backup or an increment
+    ///
+    /// # Returns
+    ///
+    /// The full backup followed by all increments up to the given one, oldest first
+    ///
+    /// # Errors
+    ///
+    /// Returns an error if the full backup of an increment is missing
+    pub fn resolve_chain(backup_file: &Path) -> Result<Vec<PathBuf>, Box<dyn std::error::Error>> {
+        if backup_file
+            .extension()
+            .is_none_or(|ext| ext != INCREMENT_EXTENSION)
+        {
+            return Ok(vec![backup_file.to_path_buf()]);
+        }
+
+        let base_stem = Self::increment_base(backup_file).ok_or("Invalid increment name")?;
+        let dir = backup_file.parent().ok_or("Invalid backup path")?;
+
+        let base = dir.join(format!("{}.{}", base_stem, FULL_EXTENSION));
+        if !base.exists() {
+            return Err("Full backup for increment not found".into());
+        }
+
+        let selected = backup_file.file_name().ok_or("Invalid backup path")?;
+        let mut increments: Vec<PathBuf> = Self::increments_of(&FsStorage, dir, &base_stem)?
+            .into_iter()
+            .filter(|p| p.file_name().is_some_and(|name| name <= selected))
+            .collect();
+        increments.sort();
+
+        let mut chain = vec![base];
+        chain.append(&mut increments);
+        Ok(chain)
+    }
+
+    /// Find the newest full backup and count its increments
+    ///
+    /// # Arguments
+    ///
+    /// * `backup_path` - The directory containing the backups
+    /// * `naming` - The file name template, backups with other names are ignored
+    ///
+    /// # Returns
+    ///
+    /// The newest full backup and the number of increments based on it, if any
+    ///
+    /// # Errors
+    ///
+    /// Returns an error if the backup directory cannot be read
+    fn latest_chain(
+        backup_path: &Path,
+        naming: &BackupNaming,
+    ) -> Result<Option<(PathBuf, usize)>, Box<dyn std::error::Error>> {
+        let latest = fs::read_dir(backup_path)?
+            .filter_map(|entry| entry.ok())
+            .map(|entry| entry.path())
+            .filter_map(|path| {
+                let (name, timestamp) = Self::full_name(&path)?;
+                naming.matches(&name).then_some((timestamp, path))
+            })
+            .max();
+
+        match latest {
+            Some((_, base)) => {
+                let stem = base.file_stem().and_then(|stem| stem.to_str());
+                let stem = stem.ok_or("Invalid backup name")?;
+                let increments = Self::increments_of(&FsStorage, backup_path, stem)?.len();
+                Ok(Some((base, increments)))
+            }
+            None => Ok(None),
+        }
+    }
+
+    /// List all increments based on the full backup with the given file stem
+    fn increments_of(
+        storage: &dyn Storage,
+        dir: &Path,
+        base_stem: &str,
+    ) -> Result<Vec<PathBuf>, Box<dyn std::error::Error>> {
+        Ok(storage
+            .list(dir)?
+            .into_iter()
+            .filter(|path| Self::increment_base(path).is_some_and(|base| base == base_stem))
+            .collect())
+    }
+
+    /// Split a file stem into the backup name and the timestamp appended to it
+    fn split_stem(stem: &str) -> Option<(&str, &str)> {
+        let split = stem.len().checked_sub(TIMESTAMP_LENGTH + 1)?;
+        let name = stem.get(..split).filter(|name| !name.is_empty())?;
+        let timestamp = stem.get(split..)?.strip_prefix('_')?;
+        NaiveDateTime::parse_from_str(timestamp, TIMESTAMP_FORMAT).ok()?;
+
+        Some((name, timestamp))
+    }
+
+    /// Get the name and timestamp of a full backup from its file name
+    fn full_name(path: &Path) -> Option<(String, String)> {
+        if path.extension()? != FULL_EXTENSION {
+            return None;
+        }
+        let (name, timestamp) = Self::split_stem(path.file_stem()?.to_str()?)?;
+        Some((name.to_string(), timestamp.to_string()))
+    }
+
+    /// Get the timestamp of a full backup from its file name
+    fn full_timestamp(path: &Path) -> Option<String> {
+        Self::full_name(path).map(|(_, timestamp)| timestamp)
+    }
+
+    /// Get the file stem of the full backup an increment is based on
+    fn increment_base(path: &Path) -> Option<String> {
+        if path.extension()? != INCREMENT_EXTENSION {
+            return None;
+        }
+        let (base, timestamp) = path.file_stem()?.to_str()?.rsplit_once(INCREMENT_MARKER)?;
+        NaiveDateTime::parse_from_str(timestamp, TIMESTAMP_FORMAT).ok()?;
+        Self::split_stem(base)?;
+
+        Some(base.to_string())
+    }
+
+    /// Get the base and own timestamp of an increment from its file name
+    fn increment_timestamps(path: &Path) -> Option<(String, String)> {
+        let base = Self::increment_base(path)?;
+        let (_, base_timestamp) = Self::split_stem(&base)?;
+        let (_, timestamp) = path.file_stem()?.to_str()?.rsplit_once(INCREMENT_MARKER)?;
+        Some((base_timestamp.to_string(), timestamp.to_string()))
+    }
+
+    /// Read and decrypt a backup file
+    fn read_backup_files(
+        backup_file: &Path,
+        secret_key: Option<&str>,
+    ) -> Result<Vec<BackupFile>, Box<dyn std::error::Error>> {
+        let mut backup_data = fs::read(backup_file)?;
+        if RecipientEncryption::is_encrypted(&backup_data) {
+            let secret_key = secret_key.ok_or("Backup requires a recipient secret key")?;
+            backup_data = RecipientEncryption::decrypt(&backup_data, secret_key)?;
+        }
+
+        let compressor = BackupCompressor::new();
+        compressor.decompress(&backup_data)
+    }
+
     /// Restore a backup of the database and configuration files
     ///
     /// # Arguments
@@ -106,6 +737,7 @@ impl<'a> BackupManager<'a> {
     /// * `config_dir` - The directory containing the configuration files
     /// * `db` - The database to restore
     /// * `master_password` - The master password to decrypt the backup
+    /// * `secret_key` - The recipient secret key if the backup is encrypted to recipients
     ///
     /// # Returns
     ///
@@ -118,12 +750,13 @@ impl<'a> BackupManager<'a> {
         backup_file: &Path,
         config_dir: &Path,
         master_password: &str,
+        secret_key: Option<&str>,
     ) -> Result<(), Box<dyn std::error::Error>> {
         info!("Starting backup restoration from {:?}", backup_file);
 
-        let backup_data = fs::read(backup_file)?;
-        let compressor = BackupCompressor::new();
-        let files = compressor.decompress(&backup_data)?;
+        let chain = Self::resolve_chain(backup_file)?;
+        let (full_backup, increments) = chain.split_first().ok_or("Empty backup chain")?;
+        let files = Self::read_backup_files(full_backup, secret_key)?;
 
         let has_db = files.iter().any(|f| f.name.ends_with(".db"));
         let has_config = files.iter().any(|f| f.name == "config.toml");
@@ -133,7 +766,11 @@ impl<'a> BackupManager<'a> {
             return Err("Backup is missing required files".into());
         }
 
-        let temp_dir = backup_file.parent().unwrap().join("restore_temp");
+        if let Some(manifest) = Self::read_manifest(&files) {
+            info!("Backup was created by version {}", manifest.app_version);
+        }
+
+        let temp_dir = full_backup.parent().unwrap().join("restore_temp");
         if temp_dir.exists() {
             fs::remove_dir_all(&temp_dir)?;
         }
@@ -157,8 +794,7 @@ impl<'a> BackupManager<'a> {
             fs::copy(temp_dir.join("config.toml"), config_dir.join("config.toml"))?;
             fs::copy(temp_dir.join(".salt"), config_dir.join(".salt"))?;
 
-            let salt_data = fs::read(temp_dir.join(".salt"))?;
-            let salt = salt_data[..16].try_into()?;
+            let params = KeyParams::from_bytes(&fs::read(temp_dir.join(".salt"))?)?;
 
             let db_name = files
                 .iter()
@@ -166,9 +802,43 @@ impl<'a> BackupManager<'a> {
                 .map(|f| &f.name)
                 .ok_or("DB file not found")?;
 
-            let db = Database::new(config_dir.join(db_name), master_password, &salt)?;
+            let mut dump_path = temp_dir.join(db_name);
+            if Self::read_manifest(&files).is_some_and(|manifest| manifest.raw_database) {
+                let raw_path = dump_path;
+                dump_path = temp_dir.join(format!("dump_{}", db_name));
+                Database::new(raw_path, master_password, &params)?
+                    .create_dump(&dump_path, master_password)?;
+            }
 
-            db.restore_from_dump(&temp_dir.join(db_name), master_password)?;
+            let db = Database::new(config_dir.join(db_name), master_password, &params)?;
+
+            db.restore_from_dump(&dump_path, master_password)?;
+
+            for increment in increments {
+                info!("Applying increment {:?}", increment);
+                let files = Self::read_backup_files(increment, secret_key)?;
+
+                let data = files
+                    .iter()
+                    .find(|f| f.name == INCREMENT_FILE)
+                    .ok_or("Increment is missing required files")?;
+                let json = db
+                    .encryption
+                    .decrypt(&data.data)
+                    .map_err(|_| "Failed to decrypt increment")?;
+                let increment: IncrementData = serde_json::from_str(&json)?;
+
+                db.apply_increment(
+                    &increment.users,
+                    &increment.changed,
+                    &increment.password_ids,
+                    &increment.tables,
+                )?;
+
+                if let Some(config) = files.iter().find(|f| f.name == "config.toml") {
+                    fs::write(config_dir.join("config.toml"), &config.data)?;
+                }
+            }
 
             Ok(())
         })();
@@ -190,63 +860,305 @@ impl<'a> BackupManager<'a> {
         result
     }
 
-    /// Automatically create a backup and remove old backups if the maximum number of backups is reached
+    /// Restore a backup into an isolated sandbox and read its contents
+    ///
+    /// The backup is restored into the sandbox directory instead of the configuration
+    /// directory, so the current vault and configuration stay untouched. The sandbox is
+    /// removed again after its contents are read. Passwords are not part of the preview.
     ///
     /// # Arguments
     ///
-    /// * `db` - The database to backup
-    /// * `backup_path` - The directory to store the backup
-    /// * `config_dir` - The directory containing the configuration files
-    /// * `master_password` - The master password to encrypt the backup
-    /// * `max_backups` - The maximum number of backups to keep
+    /// * `backup_file` - The path to the backup file
+    /// * `sandbox_dir` - The directory to restore the backup into
+    /// * `master_password` - The master password to decrypt the backup
+    /// * `secret_key` - The recipient secret key if the backup is encrypted to recipients
     ///
     /// # Returns
     ///
-    /// The path to the created backup file
+    /// The entries contained in the backup
     ///
     /// # Errors
     ///
-    /// Returns an error if the backup creation fails
-    pub fn auto_backup(
-        &self,
-        backup_path: &Path,
-        config_dir: &Path,
+    /// Returns an error if the backup cannot be restored or read
+    pub fn sandbox_restore(
+        backup_file: &Path,
+        sandbox_dir: &Path,
         master_password: &str,
-        max_backups: usize,
-    ) -> Result<PathBuf, Box<dyn std::error::Error>> {
-        let new_backup = self.create_backup(backup_path, config_dir, master_password)?;
-        let mut backups: Vec<_> = fs::read_dir(backup_path)?
-            .filter_map(|entry| entry.ok())
-            .filter(|entry| {
-                entry
-                    .path()
-                    .extension()
-                    .map_or(false, |ext| ext == "pmbackup")
-            })
-            .map(|entry| {
-                let metadata = entry.metadata().unwrap();
-                (entry.path(), metadata.created().unwrap())
+        secret_key: Option<&str>,
+    ) -> Result<BackupPreview, Box<dyn std::error::Error>> {
+        info!("Restoring {:?} into sandbox", backup_file);
+        if sandbox_dir.exists() {
+            fs::remove_dir_all(sandbox_dir)?;
+        }
+        fs::create_dir_all(sandbox_dir)?;
+
+        let result: Result<BackupPreview, Box<dyn std::error::Error>> = (|| {
+            let db = Self::open_sandbox(backup_file, sandbox_dir, master_password, secret_key)?;
+            let entries = db
+                .read_all::<PasswordEntry>()?
+                .into_iter()
+                .filter(|entry| entry.deleted_at.is_none())
+                .map(|entry| BackupPreviewEntry {
+                    service: entry.service,
+                    username: entry.username,
+                    url: entry.url,
+                    updated_at: entry.updated_at,
+                })
+                .collect();
+
+            let full_backup = Self::resolve_chain(backup_file)?.remove(0);
+            let app_version = Self::read_backup_files(&full_backup, secret_key)
+                .ok()
+                .and_then(|files| Self::read_manifest(&files))
+                .map(|manifest| manifest.app_version);
+
+            Ok(BackupPreview {
+                entries,
+                app_version,
             })
-            .collect();
+        })();
+
+        fs::remove_dir_all(sandbox_dir)?;
+
+        result
+    }
+
+    /// Restore a backup into a sandbox directory and open its database
+    fn open_sandbox(
+        backup_file: &Path,
+        sandbox_dir: &Path,
+        master_password: &str,
+        secret_key: Option<&str>,
+    ) -> Result<Database, Box<dyn std::error::Error>> {
+        Self::restore_backup(backup_file, sandbox_dir, master_password, secret_key)?;
+
+        let db_path = fs::read_dir(sandbox_dir)?
+            .filter_map(|entry| entry.ok())
+            .map(|entry| entry.path())
+            .find(|path| path.extension().is_some_and(|ext| ext == "db"))
+            .ok_or("DB file not found")?;
+        let params = KeyParams::from_bytes(&fs::read(sandbox_dir.join(".salt"))?)?;
+
+        Database::new(db_path, master_password, &params)
+    }
+
+    /// Get the creation time of a full backup or increment from its file name
+    fn backup_time(path: &Path) -> Option<DateTime<Utc>> {
+        let timestamp = Self::full_timestamp(path)
+            .or_else(|| Self::increment_timestamps(path).map(|(_, timestamp)| timestamp))?;
+        NaiveDateTime::parse_from_str(&timestamp, TIMESTAMP_FORMAT)
+            .ok()
+            .map(|time| time.and_utc())
+    }
+
+    /// Check if a backup file or its backup chain is encrypted to recipients
+    ///
+    /// # Arguments
+    ///
+    /// * `backup_file` - The path to the backup file
+    ///
+    /// # Returns
+    ///
+    /// Returns `true` if a recipient secret key is needed to restore the backup
+    ///
+    /// # Errors
+    ///
+    /// Returns an error if the backup file cannot be read
+    pub fn requires_secret_key(backup_file: &Path) -> Result<bool, Box<dyn std::error::Error>> {
+        for file in Self::resolve_chain(backup_file)? {
+            let mut header = [0u8; 5];
+            let bytes_read = File::open(file)?.read(&mut header)?;
+
+            if RecipientEncryption::is_encrypted(&header[..bytes_read]) {
+                return Ok(true);
+            }
+        }
+
+        Ok(false)
+    }
+
+    /// Remove the oldest full backups and their increments until the retention limit is met
+    ///
+    /// # Arguments
+    ///
+    /// * `backup_path` - The directory containing the backups
+    /// * `max_backups` - The maximum number of full backups to keep
+    /// * `naming` - The file name template, backups with other names are kept
+    ///
+    /// # Returns
+    ///
+    /// The paths of the removed backup files
+    ///
+    /// # Errors
+    ///
+    /// Returns an error if the backups cannot be removed
+    pub fn prune_backups(
+        backup_path: &Path,
+        max_backups: usize,
+        naming: &BackupNaming,
+    ) -> Result<Vec<PathBuf>, Box<dyn std::error::Error>> {
+        Self::prune_backups_with(&FsStorage, backup_path, max_backups, naming)
+    }
+
+    /// Remove the oldest full backups and their increments from the given storage
+    ///
+    /// Backups are ordered by their modification time, ties are broken by file name.
+    ///
+    /// # Arguments
+    ///
+    /// * `storage` - The storage containing the backups
+    /// * `backup_path` - The directory containing the backups
+    /// * `max_backups` - The maximum number of full backups to keep
+    /// * `naming` - The file name template, backups with other names are kept
+    ///
+    /// # Returns
+    ///
+    /// The paths of the removed backup files
+    ///
+    /// # Errors
+    ///
+    /// Returns an error if the backups cannot be listed or removed
+    fn prune_backups_with(
+        storage: &dyn Storage,
+        backup_path: &Path,
+        max_backups: usize,
+        naming: &BackupNaming,
+    ) -> Result<Vec<PathBuf>, Box<dyn std::error::Error>> {
+        let mut backups = Vec::new();
+        for path in storage.list(backup_path)? {
+            if Self::full_name(&path).is_some_and(|(name, _)| naming.matches(&name)) {
+                let modified = storage.modified(&path)?;
+                backups.push((path, modified));
+            }
+        }
 
-        backups.sort_by(|a, b| a.1.cmp(&b.1));
+        backups.sort_by(|a, b| a.1.cmp(&b.1).then_with(|| a.0.cmp(&b.0)));
 
+        let mut removed = Vec::new();
         while backups.len() > max_backups {
             if let Some((path, _)) = backups.first() {
-                fs::remove_file(path)?;
-                backups.remove(0);
+                if let Some(stem) = path.file_stem().and_then(|stem| stem.to_str()) {
+                    for increment in Self::increments_of(storage, backup_path, stem)? {
+                        storage.remove(&increment)?;
+                        removed.push(increment);
+                    }
+                }
+                storage.remove(path)?;
+                removed.push(backups.remove(0).0);
             }
         }
 
-        Ok(new_backup)
+        if !removed.is_empty() {
+            info!("Pruned {} backup files", removed.len());
+        }
+        Ok(removed)
+    }
+
+    /// List all backups in the backup directory with their sizes
+    ///
+    /// The uncompressed size is only available for backups that are not encrypted to recipients.
+    ///
+    /// # Arguments
+    ///
+    /// * `backup_path` - The directory containing the backups
+    ///
+    /// # Returns
+    ///
+    /// The backups sorted from newest to oldest
+    ///
+    /// # Errors
+    ///
+    /// Returns an error if the backup directory cannot be read
+    pub fn list_backups(backup_path: &Path) -> Result<Vec<BackupInfo>, Box<dyn std::error::Error>> {
+        let mut backups = Vec::new();
+
+        for entry in fs::read_dir(backup_path)?.filter_map(|entry| entry.ok()) {
+            let path = entry.path();
+            let incremental = Self::increment_timestamps(&path).is_some();
+            if !incremental && Self::full_timestamp(&path).is_none() {
+                continue;
+            }
+
+            let data = fs::read(&path)?;
+            let encrypted = RecipientEncryption::is_encrypted(&data);
+            let files = if encrypted {
+                None
+            } else {
+                BackupCompressor::new().decompress(&data).ok()
+            };
+            let uncompressed_size = files
+                .as_ref()
+                .map(|files| files.iter().map(|f| f.data.len() as u64).sum());
+            let app_version = files
+                .as_deref()
+                .and_then(Self::read_manifest)
+                .map(|manifest| manifest.app_version);
+
+            backups.push(BackupInfo {
+                name: entry.file_name().to_string_lossy().to_string(),
+                path,
+                incremental,
+                encrypted,
+                size: data.len() as u64,
+                uncompressed_size,
+                app_version,
+            });
+        }
+
+        backups.sort_by(|a, b| b.name.cmp(&a.name));
+        Ok(backups)
     }
 
-    /// Export all password entries to a CSV file
+    /// Get the creation time of the newest full backup or increment
+    ///
+    /// # Arguments
+    ///
+    /// * `backup_path` - The directory containing the backups
+    ///
+    /// # Returns
+    ///
+    /// The time of the newest backup, None if there is no backup or the directory is missing
+    ///
+    /// # Errors
+    ///
+    /// Returns an error if the backup directory cannot be read
+    pub fn last_backup_time(
+        backup_path: &Path,
+    ) -> Result<Option<DateTime<Utc>>, Box<dyn std::error::Error>> {
+        if !backup_path.exists() {
+            return Ok(None);
+        }
+        Self::last_backup_time_with(&FsStorage, backup_path)
+    }
+
+    /// Get the creation time of the newest backup in the given storage from the file names
+    fn last_backup_time_with(
+        storage: &dyn Storage,
+        backup_path: &Path,
+    ) -> Result<Option<DateTime<Utc>>, Box<dyn std::error::Error>> {
+        Ok(storage
+            .list(backup_path)?
+            .iter()
+            .filter_map(|path| {
+                Self::full_timestamp(path)
+                    .or_else(|| Self::increment_timestamps(path).map(|(_, timestamp)| timestamp))
+            })
+            .filter_map(|timestamp| {
+                NaiveDateTime::parse_from_str(&timestamp, TIMESTAMP_FORMAT)
+                    .ok()
+                    .map(|time| time.and_utc())
+            })
+            .max())
+    }
+}
+
+impl<'a, S: VaultStorage + ?Sized> BackupManager<'a, S> {
+    /// Export the password entries to a CSV file
     ///
     /// # Arguments
     ///
-    /// * `db` - The database to export
     /// * `path` - The directory to store the CSV file
+    /// * `excluded` - The IDs of the entries to leave out, e.g. flagged by the health check
     ///
     /// # Returns
     ///
@@ -255,18 +1167,37 @@ impl<'a> BackupManager<'a> {
     /// # Errors
     ///
     /// Returns an error if the export fails
-    pub fn export_csv(&self, path: &Path) -> Result<PathBuf, Box<dyn std::error::Error>> {
+    pub fn export_csv(
+        &self,
+        path: &Path,
+        excluded: &[i32],
+    ) -> Result<PathBuf, Box<dyn std::error::Error>> {
         let timestamp = Utc::now().format("%Y%m%d_%H%M%S").to_string();
         let export_path = path.join(format!("password_export_{}.csv", timestamp));
 
-        let entries = self.db.read_all::<PasswordEntry>()?;
+        let entries = self.db.entries()?;
 
         let mut writer = csv::Writer::from_path(&export_path)?;
         writer.write_record(["Service", "Username", "Password", "URL", "Notes"])?;
 
         for entry in entries {
+            if entry.deleted_at.is_some() || entry.id.is_some_and(|id| excluded.contains(&id)) {
+                continue;
+            }
+
+            if entry.password.is_empty() {
+                writer.write_record([
+                    &entry.service,
+                    &entry.username,
+                    "",
+                    &entry.url,
+                    &entry.notes,
+                ])?;
+                continue;
+            }
+
             let password = STANDARD.decode(&entry.password)?;
-            match self.db.encryption.decrypt(&password) {
+            match self.db.encryption().decrypt(&password) {
                 Ok(decrypted_pass) => {
                     writer.write_record([
                         &entry.service,
@@ -294,6 +1225,267 @@ impl<'a> BackupManager<'a> {
         Ok(export_path)
     }
 
+    /// Determine what an export writes out of the vault
+    ///
+    /// Counts the same entries `export_csv` and `export_encrypted` write, so the result can
+    /// be confirmed by the user and compared again right before the export runs.
+    ///
+    /// # Arguments
+    ///
+    /// * `format` - The format of the export
+    /// * `excluded` - The IDs of the entries to leave out, e.g. flagged by the health check
+    /// * `destination` - The directory the export is written to
+    ///
+    /// # Returns
+    ///
+    /// The scope of the export
+    ///
+    /// # Errors
+    ///
+    /// Returns an error if the entries cannot be read
+    pub fn export_scope(
+        &self,
+        format: ExportFormat,
+        excluded: &[i32],
+        destination: &Path,
+    ) -> Result<ExportScope, Box<dyn std::error::Error>> {
+        let mut scope = ExportScope {
+            format,
+            folders_and_tags: format == ExportFormat::Encrypted,
+            destination: destination.to_path_buf(),
+            ..Default::default()
+        };
+
+        for entry in self.db.entries()? {
+            if entry.deleted_at.is_some() {
+                continue;
+            }
+            if entry.id.is_some_and(|id| excluded.contains(&id)) {
+                scope.excluded += 1;
+                continue;
+            }
+
+            scope.entries += 1;
+            if entry.password.is_empty() {
+                scope.totp_only += 1;
+            } else {
+                scope.logins += 1;
+            }
+            if format == ExportFormat::Encrypted && !entry.totp_secret.is_empty() {
+                scope.totp_secrets += 1;
+            }
+            if let Some(id) = entry.id {
+                scope.attachments += self.db.attachments(id)?.len();
+            }
+        }
+
+        Ok(scope)
+    }
+
+    /// Export all password entries to a passphrase protected file
+    ///
+    /// Unlike the CSV export, the entries are written encrypted together with their TOTP
+    /// secret, folder and tags, so the file can be moved to another machine safely.
+    ///
+    /// # Arguments
+    ///
+    /// * `path` - The directory to write the export to
+    /// * `passphrase` - The passphrase chosen to protect the export
+    ///
+    /// # Returns
+    ///
+    /// The path to the created `.kcexport` file
+    ///
+    /// # Errors
+    ///
+    /// Returns an error if the passphrase is too short or the export fails
+    pub fn export_encrypted(
+        &self,
+        path: &Path,
+        passphrase: &str,
+    ) -> Result<PathBuf, Box<dyn std::error::Error>> {
+        let timestamp = Utc::now().format(TIMESTAMP_FORMAT).to_string();
+        let export_path = path.join(format!(
+            "password_export_{}.{}",
+            timestamp, EXPORT_EXTENSION
+        ));
+
+        let mut folders = HashMap::new();
+        let mut tags = HashMap::new();
+        let mut entries = Vec::new();
+        for entry in self.db.entries()? {
+            if entry.deleted_at.is_some() {
+                continue;
+            }
+            if let std::collections::hash_map::Entry::Vacant(vacant) = folders.entry(entry.user_id)
+            {
+                vacant.insert(self.db.folders(entry.user_id)?);
+                tags.insert(entry.user_id, self.db.tags(entry.user_id)?);
+            }
+
+            let mut folder = Vec::new();
+            let mut folder_id = entry.folder_id;
+            while let Some(current) =
+                folder_id.and_then(|id| folders[&entry.user_id].iter().find(|f| f.id == id))
+            {
+                folder.insert(0, current.name.clone());
+                folder_id = current.parent_id;
+            }
+
+            entries.push(ExportEntry {
+                password: if entry.password.is_empty() {
+                    String::new()
+                } else {
+                    self.decrypt_field(&entry.password)?
+                },
+                totp_secret: if entry.totp_secret.is_empty() {
+                    String::new()
+                } else {
+                    self.decrypt_field(&entry.totp_secret)?
+                },
+                folder,
+                tags: tags[&entry.user_id]
+                    .iter()
+                    .filter(|tag| entry.id.is_some_and(|id| tag.entry_ids.contains(&id)))
+                    .map(|tag| tag.name.clone())
+                    .collect(),
+                require_master: entry.require_master,
+                service: entry.service,
+                username: entry.username,
+                url: entry.url,
+                notes: entry.notes,
+            });
+        }
+
+        let count = entries.len();
+        fs::write(&export_path, EncryptedExport::seal(entries, passphrase)?)?;
+
+        info!("Exported {} entries encrypted", count);
+        Ok(export_path)
+    }
+
+    /// Import password entries from a passphrase protected export
+    ///
+    /// Folders and tags are created if they do not exist yet. Entries that already exist
+    /// with the same service and username are skipped.
+    ///
+    /// # Arguments
+    ///
+    /// * `file_path` - The path to the `.kcexport` file
+    /// * `passphrase` - The passphrase the export is protected with
+    ///
+    /// # Returns
+    ///
+    /// The result of the import operation
+    ///
+    /// # Errors
+    ///
+    /// Returns an error if the file is too large, no export or the passphrase is wrong
+    pub fn import_encrypted(
+        &self,
+        file_path: &Path,
+        passphrase: &str,
+    ) -> Result<ImportResult, Box<dyn std::error::Error>> {
+        if fs::metadata(file_path)?.len() > MAX_IMPORT_SIZE {
+            return Err("The export is too large".into());
+        }
+        let exported = EncryptedExport::open(&fs::read_to_string(file_path)?, passphrase)?;
+
+        let mut result = ImportResult {
+            imported: 0,
+            skipped: 0,
+            errors: Vec::new(),
+        };
+        let mut existing_entries = self.db.entries()?;
+        existing_entries.retain(|entry| entry.deleted_at.is_none());
+        let mut new_entries = Vec::new();
+        for exported in exported {
+            let is_duplicate = existing_entries.iter().any(|entry| {
+                entry.service == exported.service && entry.username == exported.username
+            });
+            if is_duplicate {
+                result.skipped += 1;
+                continue;
+            }
+
+            let encrypted = self
+                .db
+                .encryption()
+                .encrypt(&exported.password)
+                .map_err(|_| "Failed to encrypt imported password")?;
+            let mut entry = PasswordEntry::new(
+                1,
+                exported.service.clone(),
+                exported.username.clone(),
+                STANDARD.encode(encrypted),
+                exported.url.clone(),
+                exported.notes.clone(),
+            );
+            entry.require_master = exported.require_master;
+
+            existing_entries.push(entry.clone());
+            new_entries.push((entry, exported));
+        }
+
+        let (entries, exported): (Vec<_>, Vec<_>) = new_entries.into_iter().unzip();
+        let ids = self.db.create_entries(&entries)?;
+        let mut folders = self.db.folders(1)?;
+        let mut tags = self.db.tags(1)?;
+        for (id, exported) in ids.iter().copied().zip(exported) {
+            let mut folder_id = None;
+            for name in &exported.folder {
+                folder_id = Some(self.import_folder(&mut folders, name, folder_id)?);
+            }
+            if folder_id.is_some() {
+                self.db.move_entry_to_folder(id, folder_id)?;
+            }
+
+            if !exported.totp_secret.is_empty() {
+                let encrypted = self
+                    .db
+                    .encryption()
+                    .encrypt(&exported.totp_secret)
+                    .map_err(|_| "Failed to encrypt TOTP secret")?;
+                self.db
+                    .update_totp_secret(id, &STANDARD.encode(encrypted))?;
+            }
+
+            for name in &exported.tags {
+                let tag_id = match tags.iter().find(|tag| tag.name.eq_ignore_ascii_case(name)) {
+                    Some(tag) => tag.id,
+                    None => {
+                        let tag_id = self.db.create_tag(1, name)?;
+                        tags.push(Tag {
+                            id: tag_id,
+                            name: name.clone(),
+                            entry_ids: Vec::new(),
+                        });
+                        tag_id
+                    }
+                };
+                self.db.add_entry_tag(id, tag_id)?;
+            }
+        }
+        result.imported = ids.len();
+
+        info!(
+            "Imported {} entries from encrypted export, {} skipped",
+            result.imported, result.skipped
+        );
+        Ok(result)
+    }
+
+    /// Decrypt a base64 encoded field of a password entry
+    fn decrypt_field(&self, encoded: &str) -> Result<String, Box<dyn std::error::Error>> {
+        let decoded = STANDARD.decode(encoded)?;
+
+        Ok(self
+            .db
+            .encryption()
+            .decrypt(&decoded)
+            .map_err(|_| "Failed to decrypt field")?)
+    }
+
     /// Check if a CSV file is valid and safe to import
     ///
     /// # Arguments
@@ -316,7 +1508,7 @@ impl<'a> BackupManager<'a> {
         }
 
         let file_size = metadata.len();
-        if file_size > 10_000_000 {
+        if file_size > MAX_IMPORT_SIZE {
             info!("File exceeds size limit");
             return Ok(false);
         }
@@ -363,12 +1555,38 @@ impl<'a> BackupManager<'a> {
         Ok(true)
     }
 
+    /// Read the first rows of a CSV file to preview how its columns are imported
+    ///
+    /// # Arguments
+    ///
+    /// * `file_path` - The path to the CSV file
+    /// * `profile` - The profile to read the file with, detected from the header if None
+    ///
+    /// # Returns
+    ///
+    /// The preview with the layout to import the file with
+    ///
+    /// # Errors
+    ///
+    /// Returns an error if the file is unsafe or does not match the profile
+    pub fn preview_csv(
+        &self,
+        file_path: &Path,
+        profile: Option<CsvProfile>,
+    ) -> Result<CsvPreview, Box<dyn std::error::Error>> {
+        if !self.is_valid_csv(file_path)? {
+            return Err("Invalid or potentially unsafe CSV file".into());
+        }
+
+        Ok(CsvPreview::read(&fs::read_to_string(file_path)?, profile)?)
+    }
+
     /// Import password entries from a CSV file
     ///
     /// # Arguments
     ///
-    /// * `db` - The database to import the entries into
     /// * `file_path` - The path to the CSV file
+    /// * `layout` - The layout of the file, detected from the first row if None
     ///
     /// # Returns
     ///
@@ -376,163 +1594,1077 @@ impl<'a> BackupManager<'a> {
     ///
     /// # Errors
     ///
-    /// Returns an error if the import fails
-    pub fn import_csv(&self, file_path: &Path) -> Result<ImportResult, Box<dyn std::error::Error>> {
+    /// Returns an error if the file is unsafe, the layout is invalid or the import fails
+    pub fn import_csv(
+        &self,
+        file_path: &Path,
+        layout: Option<&CsvLayout>,
+    ) -> Result<ImportResult, Box<dyn std::error::Error>> {
         if !self.is_valid_csv(file_path)? {
             return Err("Invalid or potentially unsafe CSV file".into());
         }
 
-        let mut imported = 0;
-        let mut skipped = 0;
-        let mut errors = Vec::new();
+        let layout = match layout {
+            Some(layout) => layout.clone(),
+            None => {
+                let content = fs::read_to_string(file_path)?;
+                let first_row = content.lines().next().unwrap_or("");
+                CsvLayout::detect(first_row)
+            }
+        };
+        layout.validate()?;
 
-        let mut rdr = csv::ReaderBuilder::new()
-            .delimiter(b';')
-            .has_headers(false)
+        let rdr = csv::ReaderBuilder::new()
+            .delimiter(layout.delimiter)
+            .has_headers(layout.has_header)
             .flexible(true)
             .trim(csv::Trim::All)
             .from_path(file_path)?;
 
-        let mut existing_entries = self.db.read_all::<PasswordEntry>()?;
-
-        for (index, result) in rdr.records().enumerate() {
-            let record = match result {
-                Ok(rec) => {
-                    println!("Record content: {:?}", rec);
-                    rec
-                }
-                Err(e) => {
-                    println!("Error: {}", e);
-                    errors.push(format!("Error reading line {}: {}", index + 1, e));
-                    continue;
-                }
-            };
-
-            if record.len() != 5 {
-                errors.push(format!("Line {} has invalid number of fields", index + 1));
-                continue;
-            }
-
-            let is_duplicate = existing_entries
-                .iter()
-                .any(|entry| entry.service == record[0] && entry.username == record[1]);
+        self.import_records(rdr, &layout)
+    }
 
-            if is_duplicate {
-                skipped += 1;
+    /// Import password entries from pasted text, e.g. rows copied from a website table
+    ///
+    /// The text is validated like a CSV file and uses the same columns, browser exports are
+    /// detected by their header row. Besides `;`, rows separated by tabs or commas are
+    /// accepted, the delimiter is taken from the first row.
+    ///
+    /// # Arguments
+    ///
+    /// * `text` - The pasted rows
+    ///
+    /// # Returns
+    ///
+    /// The result of the import operation
+    ///
+    /// # Errors
+    ///
+    /// Returns an error if the text is empty, too large or unsafe
+    pub fn import_text(&self, text: &str) -> Result<ImportResult, Box<dyn std::error::Error>> {
+        if text.trim().is_empty() {
+            return Err("No text to import".into());
+        }
+        if text.len() as u64 > MAX_IMPORT_SIZE || text.contains('\0') {
+            return Err("Invalid or potentially unsafe CSV text".into());
+        }
+
+        let first_row = text
+            .lines()
+            .find(|line| !line.trim().is_empty())
+            .unwrap_or("");
+        let mut layout = CsvLayout::detect(first_row);
+        if layout.profile == CsvProfile::Karncrypt {
+            layout.delimiter = IMPORT_DELIMITERS
+                .into_iter()
+                .max_by_key(|d| {
+                    let count = first_row.bytes().filter(|b| b == d).count();
+                    (count, d == &IMPORT_DELIMITERS[0])
+                })
+                .unwrap_or(IMPORT_DELIMITERS[0]);
+        }
+
+        let rdr = csv::ReaderBuilder::new()
+            .delimiter(layout.delimiter)
+            .has_headers(layout.has_header)
+            .flexible(true)
+            .trim(csv::Trim::All)
+            .from_reader(text.trim_start().as_bytes());
+
+        self.import_records(rdr, &layout)
+    }
+
+    /// Import the rows of a CSV reader, skipping entries that already exist
+    fn import_records<R: Read>(
+        &self,
+        mut rdr: csv::Reader<R>,
+        layout: &CsvLayout,
+    ) -> Result<ImportResult, Box<dyn std::error::Error>> {
+        let mut skipped = 0;
+        let mut errors = Vec::new();
+
+        let existing_entries = self.db.entries()?;
+        let mut new_entries: Vec<PasswordEntry> = Vec::new();
+
+        for (index, result) in rdr.records().enumerate() {
+            let record = match result {
+                Ok(rec) => {
+                    println!("Record content: {:?}", rec);
+                    rec
+                }
+                Err(e) => {
+                    println!("Error: {}", e);
+                    errors.push(format!("Error reading line {}: {}", index + 1, e));
+                    continue;
+                }
+            };
+
+            let line = index + 1 + usize::from(layout.has_header);
+            let Some(fields) = layout.fields(&record) else {
+                errors.push(format!("Line {} has invalid number of fields", line));
+                continue;
+            };
+            if fields.service.is_empty() {
+                errors.push(format!("Line {} has no service", line));
                 continue;
             }
 
-            let encrypted = self.db.encryption.encrypt(&record[2]).unwrap();
+            let is_duplicate = existing_entries
+                .iter()
+                .filter(|entry| entry.deleted_at.is_none())
+                .chain(&new_entries)
+                .any(|entry| entry.service == fields.service && entry.username == fields.username);
+
+            if is_duplicate {
+                skipped += 1;
+                continue;
+            }
+
+            let encrypted = self
+                .db
+                .encryption()
+                .encrypt(&fields.password)
+                .map_err(|_| "Failed to encrypt imported password")?;
             let encoded = STANDARD.encode(encrypted);
 
             let entry = PasswordEntry::new(
                 1,
-                record[0].to_string(),
-                record[1].to_string(),
+                fields.service,
+                fields.username,
                 encoded,
-                record[3].to_string(),
-                record[4].to_string(),
+                fields.url,
+                fields.notes,
+            );
+
+            new_entries.push(entry);
+        }
+
+        let imported = self.db.create_entries(&new_entries)?.len();
+
+        Ok(ImportResult {
+            imported,
+            skipped,
+            errors,
+        })
+    }
+
+    /// Import password entries from a KeePass database
+    ///
+    /// The groups below the root group become folders, folders with the same name and parent
+    /// are reused. Entries that already exist with the same service and username are skipped.
+    ///
+    /// # Arguments
+    ///
+    /// * `file_path` - The path to the `.kdbx` file
+    /// * `password` - The master password of the KeePass database
+    ///
+    /// # Returns
+    ///
+    /// The result of the import operation
+    ///
+    /// # Errors
+    ///
+    /// Returns an error if the file is too large or cannot be decrypted
+    pub fn import_kdbx(
+        &self,
+        file_path: &Path,
+        password: &str,
+    ) -> Result<ImportResult, Box<dyn std::error::Error>> {
+        if fs::metadata(file_path)?.len() > MAX_IMPORT_SIZE {
+            return Err("The KeePass database is too large".into());
+        }
+        let database = KdbxDatabase::open(&fs::read(file_path)?, password)?;
+
+        let mut result = ImportResult {
+            imported: 0,
+            skipped: 0,
+            errors: Vec::new(),
+        };
+        let mut folders = self.db.folders(1)?;
+        let mut existing_entries = self.db.entries()?;
+        existing_entries.retain(|entry| entry.deleted_at.is_none());
+        let mut new_entries = Vec::new();
+
+        self.import_kdbx_group(
+            &database.root,
+            None,
+            &mut folders,
+            &mut existing_entries,
+            &mut new_entries,
+            &mut result,
+        )?;
+
+        let (entries, folder_ids): (Vec<_>, Vec<_>) = new_entries.into_iter().unzip();
+        let ids = self.db.create_entries(&entries)?;
+        for (id, folder_id) in ids.iter().zip(folder_ids) {
+            if folder_id.is_some() {
+                self.db.move_entry_to_folder(*id, folder_id)?;
+            }
+        }
+        result.imported = ids.len();
+
+        info!(
+            "Imported {} entries from KeePass database, {} skipped",
+            result.imported, result.skipped
+        );
+        Ok(result)
+    }
+
+    /// Collect the entries of a KeePass group and its subgroups with their folder
+    fn import_kdbx_group(
+        &self,
+        group: &KdbxGroup,
+        folder_id: Option<i32>,
+        folders: &mut Vec<Folder>,
+        existing_entries: &mut Vec<PasswordEntry>,
+        new_entries: &mut Vec<(PasswordEntry, Option<i32>)>,
+        result: &mut ImportResult,
+    ) -> Result<(), Box<dyn std::error::Error>> {
+        for kdbx_entry in &group.entries {
+            let service = if kdbx_entry.title.is_empty() {
+                kdbx_entry.url.clone()
+            } else {
+                kdbx_entry.title.clone()
+            };
+            if service.is_empty() {
+                result
+                    .errors
+                    .push("Skipped an entry without title and URL".to_string());
+                continue;
+            }
+
+            let is_duplicate = existing_entries
+                .iter()
+                .any(|entry| entry.service == service && entry.username == kdbx_entry.username);
+            if is_duplicate {
+                result.skipped += 1;
+                continue;
+            }
+
+            let encrypted = self
+                .db
+                .encryption()
+                .encrypt(&kdbx_entry.password)
+                .map_err(|_| "Failed to encrypt imported password")?;
+            let entry = PasswordEntry::new(
+                1,
+                service,
+                kdbx_entry.username.clone(),
+                STANDARD.encode(encrypted),
+                kdbx_entry.url.clone(),
+                kdbx_entry.notes.clone(),
+            );
+
+            existing_entries.push(entry.clone());
+            new_entries.push((entry, folder_id));
+        }
+
+        for subgroup in &group.groups {
+            let subfolder_id = self.import_folder(folders, &subgroup.name, folder_id)?;
+            self.import_kdbx_group(
+                subgroup,
+                Some(subfolder_id),
+                folders,
+                existing_entries,
+                new_entries,
+                result,
+            )?;
+        }
+
+        Ok(())
+    }
+
+    /// Import password entries from a Bitwarden JSON export
+    ///
+    /// Folders are created with their nesting, favorites get the tag `Favoriten` and TOTP
+    /// seeds are stored encrypted. Custom fields are appended to the notes, as entries have
+    /// no fields of their own. Only logins are imported, cards, identities and secure notes
+    /// are reported as errors.
+    ///
+    /// # Arguments
+    ///
+    /// * `file_path` - The path to the `.json` file
+    /// * `password` - The password of a password protected export
+    ///
+    /// # Returns
+    ///
+    /// The result of the import operation
+    ///
+    /// # Errors
+    ///
+    /// Returns an error if the file is too large, no Bitwarden export or cannot be decrypted
+    pub fn import_bitwarden(
+        &self,
+        file_path: &Path,
+        password: Option<&str>,
+    ) -> Result<ImportResult, Box<dyn std::error::Error>> {
+        if fs::metadata(file_path)?.len() > MAX_IMPORT_SIZE {
+            return Err("The Bitwarden export is too large".into());
+        }
+        let export = BitwardenExport::parse(&fs::read_to_string(file_path)?, password)?;
+
+        let mut result = ImportResult {
+            imported: 0,
+            skipped: 0,
+            errors: Vec::new(),
+        };
+        let mut folders = self.db.folders(1)?;
+        let mut folder_ids = HashMap::new();
+        for bitwarden_folder in export.folders.iter().flatten() {
+            let mut folder_id = None;
+            for name in bitwarden_folder
+                .name
+                .split('/')
+                .map(str::trim)
+                .filter(|name| !name.is_empty())
+            {
+                folder_id = Some(self.import_folder(&mut folders, name, folder_id)?);
+            }
+            folder_ids.insert(bitwarden_folder.id.as_str(), folder_id);
+        }
+
+        let mut existing_entries = self.db.entries()?;
+        existing_entries.retain(|entry| entry.deleted_at.is_none());
+        let mut new_entries = Vec::new();
+        for item in export.items.iter().flatten() {
+            let login = match &item.login {
+                Some(login) if item.item_type == BITWARDEN_LOGIN => login,
+                _ => {
+                    result
+                        .errors
+                        .push(format!("Skipped {}, only logins are imported", item.name));
+                    continue;
+                }
+            };
+            let username = login.username.clone().unwrap_or_default();
+
+            let is_duplicate = existing_entries
+                .iter()
+                .any(|entry| entry.service == item.name && entry.username == username);
+            if is_duplicate {
+                result.skipped += 1;
+                continue;
+            }
+
+            let encrypted = self
+                .db
+                .encryption()
+                .encrypt(login.password.as_deref().unwrap_or_default())
+                .map_err(|_| "Failed to encrypt imported password")?;
+            let url = login
+                .uris
+                .iter()
+                .flatten()
+                .find_map(|uri| uri.uri.clone())
+                .unwrap_or_default();
+            let entry = PasswordEntry::new(
+                1,
+                item.name.clone(),
+                username,
+                STANDARD.encode(encrypted),
+                url,
+                item.notes_with_fields(),
             );
 
-            match self.db.create(&entry) {
-                Ok(_) => {
-                    imported += 1;
-                    existing_entries = self.db.read_all::<PasswordEntry>()?;
-                }
-                Err(e) => {
-                    errors.push(format!("Error importing entry: {}", e));
-                }
-            }
-        }
+            existing_entries.push(entry.clone());
+            new_entries.push((entry, item));
+        }
+
+        let (entries, items): (Vec<_>, Vec<_>) = new_entries.into_iter().unzip();
+        let ids = self.db.create_entries(&entries)?;
+        let mut favorites_tag = None;
+        for (id, item) in ids.iter().copied().zip(items) {
+            let folder_id = item
+                .folder_id
+                .as_deref()
+                .and_then(|folder| folder_ids.get(folder).copied().flatten());
+            if folder_id.is_some() {
+                self.db.move_entry_to_folder(id, folder_id)?;
+            }
+
+            if let Some(totp) = item.login.as_ref().and_then(|login| login.totp.as_deref()) {
+                match Totp::parse(totp) {
+                    Ok(_) => {
+                        let encrypted = self
+                            .db
+                            .encryption()
+                            .encrypt(totp.trim())
+                            .map_err(|_| "Failed to encrypt TOTP secret")?;
+                        self.db
+                            .update_totp_secret(id, &STANDARD.encode(encrypted))?;
+                    }
+                    Err(e) => result
+                        .errors
+                        .push(format!("Skipped the TOTP secret of {}: {}", item.name, e)),
+                }
+            }
+
+            if item.favorite {
+                let tag_id = match favorites_tag {
+                    Some(tag_id) => tag_id,
+                    None => {
+                        let existing = self
+                            .db
+                            .tags(1)?
+                            .into_iter()
+                            .find(|tag| tag.name.eq_ignore_ascii_case(FAVORITES_TAG));
+                        let tag_id = match existing {
+                            Some(tag) => tag.id,
+                            None => self.db.create_tag(1, FAVORITES_TAG)?,
+                        };
+                        *favorites_tag.insert(tag_id)
+                    }
+                };
+                self.db.add_entry_tag(id, tag_id)?;
+            }
+        }
+        result.imported = ids.len();
+
+        info!(
+            "Imported {} entries from Bitwarden export, {} skipped",
+            result.imported, result.skipped
+        );
+        Ok(result)
+    }
+
+    /// Find a folder by name and parent or create it if it does not exist yet
+    fn import_folder(
+        &self,
+        folders: &mut Vec<Folder>,
+        name: &str,
+        parent_id: Option<i32>,
+    ) -> Result<i32, Box<dyn std::error::Error>> {
+        if let Some(folder) = folders
+            .iter()
+            .find(|f| f.parent_id == parent_id && f.name == name)
+        {
+            return Ok(folder.id);
+        }
+
+        let id = self.db.create_folder(1, name, parent_id)?;
+        folders.push(Folder {
+            id,
+            parent_id,
+            name: name.to_string(),
+        });
+        Ok(id)
+    }
+}
+
+impl BackupSnapshot {
+    /// Restore a backup into the snapshot directory and keep it open for browsing
+    ///
+    /// # Arguments
+    ///
+    /// * `backup_file` - The path to the backup file
+    /// * `snapshot_dir` - The directory to restore the backup into
+    /// * `master_password` - The master password the backup was created with
+    /// * `secret_key` - The recipient secret key if the backup is encrypted to recipients
+    ///
+    /// # Returns
+    ///
+    /// The opened snapshot
+    ///
+    /// # Errors
+    ///
+    /// Returns an error if the backup cannot be restored or opened
+    pub fn open(
+        backup_file: &Path,
+        snapshot_dir: &Path,
+        master_password: &str,
+        secret_key: Option<&str>,
+    ) -> Result<Self, Box<dyn std::error::Error>> {
+        info!("Opening {:?} as snapshot", backup_file);
+        if snapshot_dir.exists() {
+            fs::remove_dir_all(snapshot_dir)?;
+        }
+        fs::create_dir_all(snapshot_dir)?;
+
+        match BackupManager::open_sandbox(backup_file, snapshot_dir, master_password, secret_key) {
+            Ok(db) => Ok(Self {
+                db: Some(db),
+                dir: snapshot_dir.to_path_buf(),
+                name: backup_file
+                    .file_name()
+                    .map(|name| name.to_string_lossy().into_owned())
+                    .unwrap_or_default(),
+                created_at: BackupManager::backup_time(backup_file).map(|time| time.to_rfc3339()),
+            }),
+            Err(e) => {
+                fs::remove_dir_all(snapshot_dir)?;
+                Err(e)
+            }
+        }
+    }
+
+    /// Get the file name of the backup
+    pub fn name(&self) -> &str {
+        &self.name
+    }
+
+    /// Get the creation time of the backup, None if the file was renamed
+    pub fn created_at(&self) -> Option<&str> {
+        self.created_at.as_deref()
+    }
+
+    /// Read the entries of the backup without their passwords
+    ///
+    /// # Returns
+    ///
+    /// The entries that were not in the trash when the backup was created
+    ///
+    /// # Errors
+    ///
+    /// Returns an error if the entries cannot be read
+    pub fn entries(&self) -> Result<Vec<SnapshotEntry>, Box<dyn std::error::Error>> {
+        Ok(self
+            .db()?
+            .read_all::<PasswordEntry>()?
+            .into_iter()
+            .filter(|entry| entry.deleted_at.is_none())
+            .filter_map(|entry| {
+                Some(SnapshotEntry {
+                    id: entry.id?,
+                    service: entry.service,
+                    username: entry.username,
+                    url: entry.url,
+                    notes: entry.notes,
+                    updated_at: entry.updated_at,
+                })
+            })
+            .collect())
+    }
+
+    /// Decrypt the password of an entry as it was when the backup was created
+    ///
+    /// # Arguments
+    ///
+    /// * `id` - The ID of the entry in the backup
+    ///
+    /// # Returns
+    ///
+    /// The decrypted password
+    ///
+    /// # Errors
+    ///
+    /// Returns an error if the entry does not exist, has no password or cannot be decrypted
+    pub fn password(&self, id: i32) -> Result<String, Box<dyn std::error::Error>> {
+        let db = self.db()?;
+        let entry = db.read_by_id::<PasswordEntry>(id)?;
+        if entry.deleted_at.is_some() {
+            return Err("Entry not found".into());
+        }
+        if entry.password.is_empty() {
+            return Err("The entry has no password".into());
+        }
+
+        let decoded = STANDARD.decode(entry.password.as_bytes())?;
+        Ok(db
+            .encryption
+            .decrypt(&decoded)
+            .map_err(|_| "Failed to decrypt password")?)
+    }
+
+    /// Get the database of the snapshot
+    fn db(&self) -> Result<&Database, Box<dyn std::error::Error>> {
+        Ok(self.db.as_ref().ok_or("Snapshot is closed")?)
+    }
+}
+
+impl Drop for BackupSnapshot {
+    fn drop(&mut self) {
+        // The database has to be closed before its files can be removed on Windows.
+        self.db.take();
+        if let Err(e) = fs::remove_dir_all(&self.dir) {
+            error!("Failed to remove snapshot {:?}: {}", self.dir, e);
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::utils::database::User;
+    use crate::utils::test_rng::Rng;
+    use crate::utils::{FixedClock, MemoryStorage, RecipientKeyPair};
+    use chrono::Duration;
+    use csv::StringRecord;
+    use std::fs::File;
+    use std::io::Write;
+    use std::sync::Arc;
+    use tempfile::TempDir;
+
+    fn setup_test_env() -> (TempDir, Database, PathBuf, PathBuf) {
+        let temp = TempDir::new().unwrap();
+        let config_dir = temp.path().join("config");
+        let backup_dir = temp.path().join("backups");
+
+        fs::create_dir_all(&config_dir).unwrap();
+        fs::create_dir_all(&backup_dir).unwrap();
+
+        let salt = KeyParams::legacy([0u8; 16]);
+        fs::write(config_dir.join(".salt"), salt.to_bytes()).unwrap();
+        fs::write(config_dir.join("config.toml"), "test config").unwrap();
+
+        let db = Database::new(config_dir.join("test.db"), "test_password", &salt).unwrap();
+
+        let user = User {
+            id: None,
+            username: "testuser".to_string(),
+            master_key: vec![1, 2, 3],
+            created_at: Utc::now().to_rfc3339(),
+            last_login: Utc::now().to_rfc3339(),
+        };
+        db.create(&user).unwrap();
+
+        (temp, db, config_dir, backup_dir)
+    }
+
+    #[test]
+    fn test_create_backup() {
+        let (_temp, db, config_dir, backup_dir) = setup_test_env();
+
+        let backup_manager = BackupManager::new(&db);
+        let backup_path = backup_manager
+            .create_backup(&backup_dir, &config_dir, "test_password")
+            .unwrap();
+
+        assert!(backup_path.exists());
+        assert_eq!(backup_path.extension().unwrap(), "pmbackup");
+
+        let temp_backups = fs::read_dir(&backup_dir)
+            .unwrap()
+            .filter_map(|entry| entry.ok())
+            .filter(|entry| entry.path().is_dir())
+            .count();
+        assert_eq!(temp_backups, 0);
+    }
+
+    #[test]
+    fn test_backup_restore() {
+        let (_temp, db, config_dir, backup_dir) = setup_test_env();
+
+        let backup_manager = BackupManager::new(&db);
+        let backup_path = backup_manager
+            .create_backup(&backup_dir, &config_dir, "test_password")
+            .unwrap();
+
+        fs::remove_file(&db.path).unwrap();
+        fs::remove_file(config_dir.join("config.toml")).unwrap();
+        fs::remove_file(config_dir.join(".salt")).unwrap();
+
+        fs::create_dir_all(&config_dir).unwrap();
+
+        let new_db = Database::new(
+            db.path.clone(),
+            "test_password",
+            &KeyParams::legacy([0u8; 16]),
+        )
+        .unwrap();
+
+        BackupManager::restore_backup(&backup_path, &config_dir, "test_password", None).unwrap();
+
+        assert!(config_dir.join("config.toml").exists());
+        assert!(config_dir.join(".salt").exists());
+
+        let users = new_db.read_all::<User>().unwrap();
+        assert_eq!(users.len(), 1);
+        assert_eq!(users[0].username, "testuser");
+    }
+
+    #[test]
+    fn test_backup_contains_latest_entry() {
+        let (_temp, db, config_dir, backup_dir) = setup_test_env();
+        let user_id = db.read_all::<User>().unwrap()[0].id.unwrap();
+        let entry = PasswordEntry::new(
+            user_id,
+            "Fresh".to_string(),
+            "user1".to_string(),
+            "pass1".to_string(),
+            "https://fresh.com".to_string(),
+            "".to_string(),
+        );
+        db.create(&entry).unwrap();
+
+        let backup_path = BackupManager::new(&db)
+            .create_backup(&backup_dir, &config_dir, "test_password")
+            .unwrap();
+
+        fs::remove_file(&db.path).unwrap();
+        let new_db = Database::new(
+            db.path.clone(),
+            "test_password",
+            &KeyParams::legacy([0u8; 16]),
+        )
+        .unwrap();
+        BackupManager::restore_backup(&backup_path, &config_dir, "test_password", None).unwrap();
+
+        let entries = new_db.read_all::<PasswordEntry>().unwrap();
+        assert_eq!(entries.len(), 1);
+        assert_eq!(entries[0].service, "Fresh");
+    }
+
+    #[test]
+    fn test_sandbox_restore() {
+        let (temp, db, config_dir, backup_dir) = setup_test_env();
+        let user_id = db.read_all::<User>().unwrap()[0].id.unwrap();
+        let entry = PasswordEntry::new(
+            user_id,
+            "Service1".to_string(),
+            "user1".to_string(),
+            "pass1".to_string(),
+            "https://service1.com".to_string(),
+            "".to_string(),
+        );
+        db.create(&entry).unwrap();
+
+        let backup_path = BackupManager::new(&db)
+            .create_backup(&backup_dir, &config_dir, "test_password")
+            .unwrap();
+        let config_before = fs::read(config_dir.join("config.toml")).unwrap();
+
+        let sandbox_dir = temp.path().join("sandbox");
+        let preview =
+            BackupManager::sandbox_restore(&backup_path, &sandbox_dir, "test_password", None)
+                .unwrap();
+
+        assert_eq!(preview.entries.len(), 1);
+        assert_eq!(preview.entries[0].service, "Service1");
+        assert_eq!(preview.app_version.as_deref(), Some(APP_VERSION));
+        assert!(!sandbox_dir.exists());
+        assert_eq!(
+            fs::read(config_dir.join("config.toml")).unwrap(),
+            config_before
+        );
+        assert_eq!(db.read_all::<PasswordEntry>().unwrap().len(), 1);
+
+        assert!(
+            BackupManager::sandbox_restore(&backup_path, &sandbox_dir, "wrong_password", None)
+                .is_err()
+        );
+        assert!(!sandbox_dir.exists());
+    }
+
+    #[test]
+    fn test_backup_snapshot() {
+        let (temp, db, config_dir, backup_dir) = setup_test_env();
+        let user_id = db.read_all::<User>().unwrap()[0].id.unwrap();
+        let entry = PasswordEntry::new(
+            user_id,
+            "Service1".to_string(),
+            "user1".to_string(),
+            STANDARD.encode(db.encryption.encrypt("old_pass").unwrap()),
+            "https://service1.com".to_string(),
+            "".to_string(),
+        );
+        db.create(&entry).unwrap();
+
+        let backup_path = BackupManager::new(&db)
+            .create_backup(&backup_dir, &config_dir, "test_password")
+            .unwrap();
+        let mut current = db.read_all::<PasswordEntry>().unwrap().remove(0);
+        current.password = STANDARD.encode(db.encryption.encrypt("new_pass").unwrap());
+        db.update(&current).unwrap();
+
+        let snapshot_dir = temp.path().join("snapshot");
+        let snapshot =
+            BackupSnapshot::open(&backup_path, &snapshot_dir, "test_password", None).unwrap();
+        assert!(snapshot_dir.exists());
+        assert!(snapshot.created_at().is_some());
+        assert_eq!(
+            snapshot.name(),
+            backup_path.file_name().unwrap().to_str().unwrap()
+        );
+
+        let entries = snapshot.entries().unwrap();
+        assert_eq!(entries.len(), 1);
+        assert_eq!(entries[0].service, "Service1");
+        assert_eq!(snapshot.password(entries[0].id).unwrap(), "old_pass");
+        assert!(snapshot.password(entries[0].id + 1).is_err());
+
+        drop(snapshot);
+        assert!(!snapshot_dir.exists());
+
+        assert!(BackupSnapshot::open(&backup_path, &snapshot_dir, "wrong_password", None).is_err());
+        assert!(!snapshot_dir.exists());
+    }
+
+    #[test]
+    fn test_safety_backup_restore() {
+        let (_temp, db, config_dir, backup_dir) = setup_test_env();
+
+        let backup_path =
+            BackupManager::create_safety_backup(&db.path, &backup_dir, &config_dir, &[]).unwrap();
+        assert_eq!(backup_path.extension().unwrap(), "pmbackup");
+
+        let backups = BackupManager::list_backups(&backup_dir).unwrap();
+        assert_eq!(backups[0].app_version.as_deref(), Some(APP_VERSION));
+
+        let db_path = db.path.clone();
+        drop(db);
+        for suffix in ["", "-wal", "-shm"] {
+            let _ = fs::remove_file(format!("{}{}", db_path.display(), suffix));
+        }
+
+        let new_db =
+            Database::new(db_path, "test_password", &KeyParams::legacy([0u8; 16])).unwrap();
+        BackupManager::restore_backup(&backup_path, &config_dir, "test_password", None).unwrap();
+
+        let users = new_db.read_all::<User>().unwrap();
+        assert_eq!(users.len(), 1);
+        assert_eq!(users[0].username, "testuser");
+    }
+
+    #[test]
+    fn test_recipient_backup_restore() {
+        let (_temp, db, config_dir, backup_dir) = setup_test_env();
+        let key_pair = RecipientKeyPair::generate().unwrap();
+
+        let backup_manager =
+            BackupManager::new(&db).with_recipients(vec![key_pair.public_key.clone()]);
+        let backup_path = backup_manager
+            .create_backup(&backup_dir, &config_dir, "test_password")
+            .unwrap();
+
+        assert!(BackupManager::requires_secret_key(&backup_path).unwrap());
+        assert!(
+            BackupManager::restore_backup(&backup_path, &config_dir, "test_password", None)
+                .is_err()
+        );
+
+        BackupManager::restore_backup(
+            &backup_path,
+            &config_dir,
+            "test_password",
+            Some(&key_pair.secret_key),
+        )
+        .unwrap();
 
-        Ok(ImportResult {
-            imported,
-            skipped,
-            errors,
-        })
+        let users = db.read_all::<User>().unwrap();
+        assert_eq!(users.len(), 1);
+        assert_eq!(users[0].username, "testuser");
     }
-}
 
-#[cfg(test)]
-mod tests {
-    use super::*;
-    use crate::utils::database::User;
-    use csv::StringRecord;
-    use std::fs::File;
-    use std::io::Write;
-    use tempfile::TempDir;
+    #[test]
+    fn test_incremental_backup_restore() {
+        let (_temp, db, config_dir, backup_dir) = setup_test_env();
+        let user_id = db.read_all::<User>().unwrap()[0].id.unwrap();
+
+        let entry = PasswordEntry::new(
+            user_id,
+            "Service1".to_string(),
+            "user1".to_string(),
+            "pass1".to_string(),
+            "https://service1.com".to_string(),
+            "".to_string(),
+        );
+        db.create(&entry).unwrap();
 
-    fn setup_test_env() -> (TempDir, Database, PathBuf, PathBuf) {
-        let temp = TempDir::new().unwrap();
-        let config_dir = temp.path().join("config");
-        let backup_dir = temp.path().join("backups");
+        let backup_manager = BackupManager::new(&db);
+        let full_backup = backup_manager
+            .create_backup(&backup_dir, &config_dir, "test_password")
+            .unwrap();
 
-        fs::create_dir_all(&config_dir).unwrap();
-        fs::create_dir_all(&backup_dir).unwrap();
+        let first = db.read_all::<PasswordEntry>().unwrap().remove(0);
+        db.delete::<PasswordEntry>(first.id.unwrap()).unwrap();
+        let entry = PasswordEntry::new(
+            user_id,
+            "Service2".to_string(),
+            "user2".to_string(),
+            "pass2".to_string(),
+            "https://service2.com".to_string(),
+            "".to_string(),
+        );
+        db.create(&entry).unwrap();
+        let second = db.read_all::<PasswordEntry>().unwrap().remove(0);
+        db.update_expiry(second.id.unwrap(), Some("2030-01-31"))
+            .unwrap();
 
-        let salt = [0u8; 16];
-        fs::write(config_dir.join(".salt"), &salt).unwrap();
-        fs::write(config_dir.join("config.toml"), "test config").unwrap();
+        let increment = backup_manager
+            .create_incremental_backup(&full_backup, &backup_dir, &config_dir)
+            .unwrap();
+        assert_eq!(increment.extension().unwrap(), "pminc");
 
-        let db = Database::new(config_dir.join("test.db"), "test_password", &salt).unwrap();
+        let chain = BackupManager::resolve_chain(&increment).unwrap();
+        assert_eq!(chain, vec![full_backup.clone(), increment.clone()]);
 
-        let user = User {
-            id: None,
-            username: "testuser".to_string(),
-            master_key: vec![1, 2, 3],
-            created_at: Utc::now().to_rfc3339(),
-            last_login: Utc::now().to_rfc3339(),
-        };
-        db.create(&user).unwrap();
+        BackupManager::restore_backup(&full_backup, &config_dir, "test_password", None).unwrap();
+        let entries = db.read_all::<PasswordEntry>().unwrap();
+        assert_eq!(entries.len(), 1);
+        assert_eq!(entries[0].service, "Service1");
 
-        (temp, db, config_dir, backup_dir)
+        BackupManager::restore_backup(&increment, &config_dir, "test_password", None).unwrap();
+        let entries = db.read_all::<PasswordEntry>().unwrap();
+        assert_eq!(entries.len(), 1);
+        assert_eq!(entries[0].service, "Service2");
+        assert_eq!(entries[0].expires_on.as_deref(), Some("2030-01-31"));
     }
 
     #[test]
-    fn test_create_backup() {
+    fn test_incremental_restore_keeps_entry_children() {
         let (_temp, db, config_dir, backup_dir) = setup_test_env();
+        let user_id = db.read_all::<User>().unwrap()[0].id.unwrap();
+
+        let entry = PasswordEntry::new(
+            user_id,
+            "Service1".to_string(),
+            "user1".to_string(),
+            "pass1".to_string(),
+            "https://service1.com".to_string(),
+            "".to_string(),
+        );
+        db.create(&entry).unwrap();
+        let mut entry = db.read_all::<PasswordEntry>().unwrap().remove(0);
+        let entry_id = entry.id.unwrap();
+        let tag_id = db.create_tag(user_id, "Work").unwrap();
+        db.add_entry_tag(entry_id, tag_id).unwrap();
+        db.create_attachment(entry_id, "key.pem", 3, "abc").unwrap();
 
         let backup_manager = BackupManager::new(&db);
-        let backup_path = backup_manager
+        let full_backup = backup_manager
             .create_backup(&backup_dir, &config_dir, "test_password")
             .unwrap();
 
-        assert!(backup_path.exists());
-        assert_eq!(backup_path.extension().unwrap(), "pmbackup");
+        entry.service = "Service1 renamed".to_string();
+        entry.updated_at = Utc::now().to_rfc3339();
+        db.update(&entry).unwrap();
+        let increment = backup_manager
+            .create_incremental_backup(&full_backup, &backup_dir, &config_dir)
+            .unwrap();
 
-        let temp_backups = fs::read_dir(&backup_dir)
-            .unwrap()
-            .filter_map(|entry| entry.ok())
-            .filter(|entry| entry.path().is_dir())
-            .count();
-        assert_eq!(temp_backups, 0);
+        BackupManager::restore_backup(&increment, &config_dir, "test_password", None).unwrap();
+
+        let entries = db.read_all::<PasswordEntry>().unwrap();
+        assert_eq!(entries[0].service, "Service1 renamed");
+        assert_eq!(db.tags(user_id).unwrap()[0].entry_ids, vec![entry_id]);
+        assert_eq!(db.attachments(entry_id).unwrap()[0].file_name, "key.pem");
     }
 
     #[test]
-    fn test_backup_restore() {
+    fn test_incremental_backup_carries_tables() {
         let (_temp, db, config_dir, backup_dir) = setup_test_env();
+        let user_id = db.read_all::<User>().unwrap()[0].id.unwrap();
+
+        let entry = PasswordEntry::new(
+            user_id,
+            "Service1".to_string(),
+            "user1".to_string(),
+            "pass1".to_string(),
+            "https://service1.com".to_string(),
+            "".to_string(),
+        );
+        db.create(&entry).unwrap();
+        let entry_id = db.read_all::<PasswordEntry>().unwrap()[0].id.unwrap();
+        let attachment_id = db.create_attachment(entry_id, "old.txt", 3, "abc").unwrap();
 
         let backup_manager = BackupManager::new(&db);
-        let backup_path = backup_manager
+        let full_backup = backup_manager
             .create_backup(&backup_dir, &config_dir, "test_password")
             .unwrap();
 
-        fs::remove_file(&db.path).unwrap();
-        fs::remove_file(config_dir.join("config.toml")).unwrap();
-        fs::remove_file(config_dir.join(".salt")).unwrap();
+        db.delete_attachment(entry_id, attachment_id).unwrap();
+        db.pin_entry(entry_id, user_id).unwrap();
+        let folder_id = db.create_folder(user_id, "Work", None).unwrap();
+        db.move_entry_to_folder(entry_id, Some(folder_id)).unwrap();
+        let tag_id = db.create_tag(user_id, "Shared").unwrap();
+        db.add_entry_tag(entry_id, tag_id).unwrap();
+        db.create_secure_note(user_id, "Wifi", "secret").unwrap();
 
-        fs::create_dir_all(&config_dir).unwrap();
+        let increment = backup_manager
+            .create_incremental_backup(&full_backup, &backup_dir, &config_dir)
+            .unwrap();
 
-        let new_db = Database::new(db.path.clone(), "test_password", &[0u8; 16]).unwrap();
+        BackupManager::restore_backup(&full_backup, &config_dir, "test_password", None).unwrap();
+        assert!(db.pinned_entry_ids(user_id).unwrap().is_empty());
+        assert_eq!(db.attachments(entry_id).unwrap().len(), 1);
+
+        BackupManager::restore_backup(&increment, &config_dir, "test_password", None).unwrap();
+        assert_eq!(db.pinned_entry_ids(user_id).unwrap(), vec![entry_id]);
+        assert!(db.attachments(entry_id).unwrap().is_empty());
+        assert_eq!(db.folders(user_id).unwrap()[0].name, "Work");
+        assert_eq!(
+            db.read_all::<PasswordEntry>().unwrap()[0].folder_id,
+            Some(folder_id)
+        );
+        assert_eq!(db.tags(user_id).unwrap()[0].entry_ids, vec![entry_id]);
+        assert_eq!(db.secure_notes(user_id).unwrap()[0].title, "Wifi");
+    }
 
-        BackupManager::restore_backup(&backup_path, &config_dir, "test_password").unwrap();
+    #[test]
+    fn test_incremental_policy() {
+        let (_temp, db, config_dir, backup_dir) = setup_test_env();
 
-        assert!(config_dir.join("config.toml").exists());
-        assert!(config_dir.join(".salt").exists());
+        let backup_manager = BackupManager::new(&db).with_incremental(1);
 
-        let users = new_db.read_all::<User>().unwrap();
-        assert_eq!(users.len(), 1);
-        assert_eq!(users[0].username, "testuser");
+        let first = backup_manager
+            .backup(&backup_dir, &config_dir, "test_password")
+            .unwrap();
+        assert_eq!(first.extension().unwrap(), "pmbackup");
+
+        let second = backup_manager
+            .backup(&backup_dir, &config_dir, "test_password")
+            .unwrap();
+        assert_eq!(second.extension().unwrap(), "pminc");
+
+        std::thread::sleep(std::time::Duration::from_secs(1));
+
+        let third = backup_manager
+            .backup(&backup_dir, &config_dir, "test_password")
+            .unwrap();
+        assert_eq!(third.extension().unwrap(), "pmbackup");
+        assert_ne!(first, third);
+    }
+
+    #[test]
+    fn test_backup_naming() {
+        let (_temp, db, config_dir, backup_dir) = setup_test_env();
+
+        let other = BackupManager::new(&db)
+            .create_backup(&backup_dir, &config_dir, "test_password")
+            .unwrap();
+        let naming = BackupNaming::new("{vault}-kc", "test.db").unwrap();
+        let backup_manager = BackupManager::new(&db)
+            .with_incremental(5)
+            .with_naming(naming.clone());
+
+        let full = backup_manager
+            .backup(&backup_dir, &config_dir, "test_password")
+            .unwrap();
+        let full_name = full.file_name().unwrap().to_str().unwrap();
+        assert!(full_name.starts_with("test-kc_"));
+        assert!(full_name.ends_with(FULL_EXTENSION));
+
+        let increment = backup_manager
+            .backup(&backup_dir, &config_dir, "test_password")
+            .unwrap();
+        assert!(increment
+            .file_name()
+            .unwrap()
+            .to_str()
+            .unwrap()
+            .starts_with("test-kc_"));
+        assert_eq!(
+            BackupManager::resolve_chain(&increment).unwrap(),
+            vec![full.clone(), increment.clone()]
+        );
+
+        let removed = BackupManager::prune_backups(&backup_dir, 0, &naming).unwrap();
+        assert_eq!(removed, vec![increment, full]);
+        assert!(other.exists());
+    }
+
+    #[test]
+    fn test_increment_without_full_backup() {
+        let (_temp, _db, _config_dir, backup_dir) = setup_test_env();
+
+        let orphan = backup_dir.join("backup_20240101_000000_inc_20240102_000000.pminc");
+        fs::write(&orphan, b"data").unwrap();
+
+        assert!(BackupManager::resolve_chain(&orphan).is_err());
+
+        let invalid = backup_dir.join("backup_1_inc_2.pminc");
+        fs::write(&invalid, b"data").unwrap();
+        assert!(BackupManager::resolve_chain(&invalid).is_err());
     }
 
     #[test]
@@ -565,7 +2697,7 @@ mod tests {
                 entry
                     .path()
                     .extension()
-                    .map_or(false, |ext| ext == "pmbackup")
+                    .is_some_and(|ext| ext == "pmbackup")
             })
             .collect();
 
@@ -579,11 +2711,126 @@ mod tests {
         assert!(backup3_time > backup2_time);
     }
 
+    #[test]
+    fn test_list_and_prune_backups() {
+        let (_temp, db, config_dir, backup_dir) = setup_test_env();
+
+        let backup_manager = BackupManager::new(&db);
+        let full_backup = backup_manager
+            .create_backup(&backup_dir, &config_dir, "test_password")
+            .unwrap();
+        backup_manager
+            .create_incremental_backup(&full_backup, &backup_dir, &config_dir)
+            .unwrap();
+
+        let backups = BackupManager::list_backups(&backup_dir).unwrap();
+        assert_eq!(backups.len(), 2);
+        assert!(backups.iter().any(|b| b.incremental));
+        for backup in &backups {
+            assert!(!backup.encrypted);
+            assert_eq!(backup.size, fs::metadata(&backup.path).unwrap().len());
+            assert!(backup.uncompressed_size.unwrap() > 0);
+        }
+
+        std::thread::sleep(std::time::Duration::from_secs(1));
+        backup_manager
+            .create_backup(&backup_dir, &config_dir, "test_password")
+            .unwrap();
+
+        let removed =
+            BackupManager::prune_backups(&backup_dir, 1, &BackupNaming::default()).unwrap();
+        assert_eq!(removed.len(), 2);
+        assert!(!full_backup.exists());
+        assert_eq!(BackupManager::list_backups(&backup_dir).unwrap().len(), 1);
+    }
+
+    #[test]
+    fn test_last_backup_time() {
+        let storage = MemoryStorage::new(Arc::new(FixedClock::new(Utc::now())));
+        let dir = Path::new("/backups");
+        assert_eq!(
+            BackupManager::last_backup_time_with(&storage, dir).unwrap(),
+            None
+        );
+
+        for name in [
+            format!("backup_20240101_120000.{}", FULL_EXTENSION),
+            format!(
+                "backup_20240101_120000_inc_20240301_080000.{}",
+                INCREMENT_EXTENSION
+            ),
+            format!("backup_20240201_120000.{}", FULL_EXTENSION),
+            format!("backup_invalid.{}", FULL_EXTENSION),
+            "notes.txt".to_string(),
+        ] {
+            storage.write(&dir.join(name), b"data").unwrap();
+        }
+
+        let expected = NaiveDateTime::parse_from_str("20240301_080000", TIMESTAMP_FORMAT)
+            .unwrap()
+            .and_utc();
+        assert_eq!(
+            BackupManager::last_backup_time_with(&storage, dir).unwrap(),
+            Some(expected)
+        );
+    }
+
+    #[test]
+    fn test_prune_backups_by_modification_time() {
+        let clock = Arc::new(FixedClock::new(Utc::now()));
+        let storage = MemoryStorage::new(clock.clone());
+        let dir = Path::new("/backups");
+
+        let naming = BackupNaming::default();
+
+        // Names sort in the opposite order of creation to make sure the time decides
+        for name in [
+            "backup_20240103_000000",
+            "backup_20240102_000000",
+            "backup_20240101_000000",
+        ] {
+            storage
+                .write(&dir.join(format!("{}.{}", name, FULL_EXTENSION)), b"full")
+                .unwrap();
+            clock.advance(Duration::days(1));
+        }
+        let increment = dir.join(format!(
+            "backup_20240103_000000_inc_20240104_000000.{}",
+            INCREMENT_EXTENSION
+        ));
+        storage.write(&increment, b"inc").unwrap();
+        storage.write(&dir.join("notes.txt"), b"other").unwrap();
+        let other_machine = dir.join(format!("laptop_20240101_000000.{}", FULL_EXTENSION));
+        storage.write(&other_machine, b"full").unwrap();
+
+        let removed = BackupManager::prune_backups_with(&storage, dir, 2, &naming).unwrap();
+        assert_eq!(
+            removed,
+            vec![
+                increment,
+                dir.join(format!("backup_20240103_000000.{}", FULL_EXTENSION)),
+            ]
+        );
+        assert_eq!(storage.list(dir).unwrap().len(), 4);
+
+        assert!(BackupManager::prune_backups_with(&storage, dir, 2, &naming)
+            .unwrap()
+            .is_empty());
+        assert_eq!(
+            BackupManager::prune_backups_with(&storage, dir, 0, &naming)
+                .unwrap()
+                .len(),
+            2
+        );
+        assert!(storage.exists(&dir.join("notes.txt")));
+        assert!(storage.exists(&other_machine));
+    }
+
     #[test]
     fn test_export_csv() {
         let (_temp, db, _config_dir, backup_dir) = setup_test_env();
 
-        let entries = vec![
+        let entries = [
             (
                 "Service1",
                 "user1",
@@ -616,7 +2863,7 @@ mod tests {
         }
 
         let backup_manager = BackupManager::new(&db);
-        let export_path = backup_manager.export_csv(&backup_dir).unwrap();
+        let export_path = backup_manager.export_csv(&backup_dir, &[]).unwrap();
 
         let mut rdr = csv::Reader::from_path(export_path).unwrap();
         let records: Vec<StringRecord> = rdr.records().map(|r| r.unwrap()).collect();
@@ -630,6 +2877,61 @@ mod tests {
             assert_eq!(record[3].to_string(), url);
             assert_eq!(record[4].to_string(), notes);
         }
+
+        let filtered_dir = backup_dir.join("filtered");
+        fs::create_dir_all(&filtered_dir).unwrap();
+        let export_path = backup_manager.export_csv(&filtered_dir, &[1]).unwrap();
+        let mut rdr = csv::Reader::from_path(export_path).unwrap();
+        let records: Vec<StringRecord> = rdr.records().map(|r| r.unwrap()).collect();
+        assert_eq!(records.len(), 1);
+        assert_eq!(&records[0][0], "Service2");
+    }
+
+    #[test]
+    fn test_export_scope() {
+        let (_temp, db, _config_dir, backup_dir) = setup_test_env();
+
+        let password = STANDARD.encode(db.encryption.encrypt("pass1").unwrap());
+        for (service, password) in [
+            ("Service1", password.as_str()),
+            ("Service2", ""),
+            ("Service3", ""),
+        ] {
+            let entry = PasswordEntry::new(
+                1,
+                service.to_string(),
+                "user".to_string(),
+                password.to_string(),
+                String::new(),
+                String::new(),
+            );
+            db.create(&entry).unwrap();
+        }
+        db.update_totp_secret(2, "secret").unwrap();
+        db.create_attachment(1, "file.txt", 4, "content").unwrap();
+
+        let backup_manager = BackupManager::new(&db);
+        let scope = backup_manager
+            .export_scope(ExportFormat::Csv, &[3], &backup_dir)
+            .unwrap();
+        assert_eq!(scope.entries, 2);
+        assert_eq!(scope.logins, 1);
+        assert_eq!(scope.totp_only, 1);
+        assert_eq!(scope.totp_secrets, 0);
+        assert!(!scope.folders_and_tags);
+        assert_eq!(scope.attachments, 1);
+        assert_eq!(scope.excluded, 1);
+        assert_eq!(scope.destination, backup_dir);
+
+        let scope = backup_manager
+            .export_scope(ExportFormat::Encrypted, &[], &backup_dir)
+            .unwrap();
+        assert_eq!(scope.entries, 3);
+        assert_eq!(scope.totp_secrets, 1);
+        assert!(scope.folders_and_tags);
+        assert!(scope
+            .summary()
+            .contains("3 entries (1 logins, 2 TOTP only)"));
     }
 
     #[test]
@@ -688,6 +2990,91 @@ mod tests {
         }
     }
 
+    #[test]
+    fn test_import_malformed_csv_does_not_panic() {
+        let (_temp, db, _config_dir, backup_dir) = setup_test_env();
+        let backup_manager = BackupManager::new(&db);
+        let mut rng = Rng::new(0xc5f);
+        let fragments = [
+            "Service",
+            "user",
+            "pass",
+            "https://example.com",
+            "note",
+            ";",
+            ";;",
+            "\"",
+            "\"\"",
+            "\n",
+            "\r\n",
+            " ",
+            "ä",
+            "😀",
+            "\t",
+            "\\",
+            "=cmd|' /C calc'!A0",
+        ];
+
+        for round in 0..40 {
+            let mut content = Vec::new();
+            for _ in 0..rng.below(12) {
+                for _ in 0..rng.below(16) {
+                    content.extend_from_slice(rng.pick(&fragments).as_bytes());
+                }
+                if rng.below(10) == 0 {
+                    let len = rng.below(8);
+                    content.extend(rng.bytes(len));
+                }
+                content.push(b'\n');
+            }
+
+            let test_file = backup_dir.join(format!("fuzz_{}.csv", round));
+            fs::write(&test_file, &content).unwrap();
+
+            if let Ok(result) = backup_manager.import_csv(&test_file, None) {
+                assert!(result.imported + result.skipped + result.errors.len() <= content.len());
+            }
+        }
+    }
+
+    #[test]
+    fn test_restore_malformed_backup_does_not_panic() {
+        let (_temp, db, config_dir, backup_dir) = setup_test_env();
+        let valid = fs::read(
+            BackupManager::new(&db)
+                .create_backup(&backup_dir, &config_dir, "test_password")
+                .unwrap(),
+        )
+        .unwrap();
+        let mut rng = Rng::new(0xfa11);
+
+        for round in 0..20 {
+            let mut data = if round % 2 == 0 {
+                valid.clone()
+            } else {
+                let len = rng.below(256);
+                rng.bytes(len)
+            };
+            if !data.is_empty() {
+                for _ in 0..=rng.below(4) {
+                    let index = rng.below(data.len());
+                    data[index] = rng.next() as u8;
+                }
+                data.truncate(rng.below(data.len() + 1));
+            }
+
+            let backup_file = backup_dir.join(format!("fuzz_{}.pmbackup", round));
+            fs::write(&backup_file, &data).unwrap();
+            assert!(BackupManager::restore_backup(
+                &backup_file,
+                &config_dir,
+                "test_password",
+                None
+            )
+            .is_err());
+        }
+    }
+
     #[test]
     fn test_import_csv() {
         let (_temp, db, _config_dir, backup_dir) = setup_test_env();
@@ -710,7 +3097,7 @@ mod tests {
         file.write_all(b"Service3;user3;pass3;https://service3.com\n")
             .unwrap();
 
-        let result = backup_manager.import_csv(&test_file).unwrap();
+        let result = backup_manager.import_csv(&test_file, None).unwrap();
 
         assert_eq!(result.imported, 2);
         assert_eq!(result.skipped, 1);
@@ -729,4 +3116,261 @@ mod tests {
         assert_eq!(second.url, "https://service2.com");
         assert_eq!(second.notes, "note2");
     }
+
+    #[test]
+    fn test_import_browser_csv() {
+        let (_temp, db, _config_dir, backup_dir) = setup_test_env();
+        let backup_manager = BackupManager::new(&db);
+
+        let test_file = backup_dir.join("chrome.csv");
+        fs::write(
+            &test_file,
+            "name,url,username,password,note\n\
+             Mail,https://mail.example,me,pass1,privat\n\
+             ,https://www.shop.example/login,me,\"pa,ss2\",\n\
+             broken,row\n",
+        )
+        .unwrap();
+
+        let preview = backup_manager.preview_csv(&test_file, None).unwrap();
+        assert_eq!(preview.layout.profile, CsvProfile::Chrome);
+        assert_eq!(preview.rows.len(), 3);
+
+        let mut layout = preview.layout;
+        layout.mapping.notes = None;
+        let result = backup_manager
+            .import_csv(&test_file, Some(&layout))
+            .unwrap();
+        assert_eq!(result.imported, 2);
+        assert_eq!(result.errors, vec!["Line 4 has invalid number of fields"]);
+
+        let entries = db.read_all::<PasswordEntry>().unwrap();
+        let shop = entries
+            .iter()
+            .find(|e| e.service == "shop.example")
+            .unwrap();
+        assert_eq!(shop.url, "https://www.shop.example/login");
+        assert!(entries.iter().all(|e| e.notes.is_empty()));
+
+        layout.mapping.password = None;
+        assert!(backup_manager
+            .import_csv(&test_file, Some(&layout))
+            .is_err());
+    }
+
+    #[test]
+    fn test_import_text() {
+        let (_temp, db, _config_dir, _backup_dir) = setup_test_env();
+        let backup_manager = BackupManager::new(&db);
+
+        let pasted = "\nMail\tme@example.com\tpass1\thttps://mail.example\tprivate\n\
+                      Shop\tme\tpass2\thttps://shop.example\t\n";
+        let result = backup_manager.import_text(pasted).unwrap();
+        assert_eq!(result.imported, 2);
+        assert!(result.errors.is_empty());
+
+        let result = backup_manager
+            .import_text("Bank,me,pass3,https://bank.example,\nMail;me@example.com;x;;\n")
+            .unwrap();
+        assert_eq!(result.imported, 1);
+        assert_eq!(result.errors.len(), 1);
+
+        let result = backup_manager
+            .import_text("Forum;me;pass4;https://forum.example;note\nMail;me@example.com;x;;")
+            .unwrap();
+        assert_eq!(result.imported, 1);
+        assert_eq!(result.skipped, 1);
+
+        let entries = db.read_all::<PasswordEntry>().unwrap();
+        assert_eq!(entries.len(), 4);
+        let mail = entries.iter().find(|e| e.service == "Mail").unwrap();
+        assert_eq!(mail.url, "https://mail.example");
+        assert_eq!(mail.notes, "private");
+        let password = db
+            .encryption
+            .decrypt(&STANDARD.decode(&mail.password).unwrap())
+            .unwrap();
+        assert_eq!(password, "pass1");
+
+        assert!(backup_manager.import_text("  \n ").is_err());
+        assert!(backup_manager.import_text("a;b;c\0;d;e").is_err());
+    }
+
+    #[test]
+    fn test_backup_manager_on_vault_storage() {
+        let (_temp, db, config_dir, backup_dir) = setup_test_env();
+        let storage: &dyn VaultStorage = &db;
+        let backup_manager = BackupManager::new(storage);
+
+        let result = backup_manager
+            .import_text("Mail;me@example.com;pass1;https://mail.example;")
+            .unwrap();
+        assert_eq!(result.imported, 1);
+
+        let export_file = backup_manager.export_csv(&backup_dir, &[]).unwrap();
+        let content = fs::read_to_string(export_file).unwrap();
+        assert!(content.contains("Mail,me@example.com,pass1,https://mail.example"));
+
+        let backup = backup_manager
+            .create_backup(&backup_dir, &config_dir, "test_password")
+            .unwrap();
+        assert!(backup.exists());
+    }
+
+    #[test]
+    fn test_import_kdbx() {
+        let (_temp, db, _config_dir, backup_dir) = setup_test_env();
+        let backup_manager = BackupManager::new(&db);
+
+        let kdbx_file = backup_dir.join("keepass.kdbx");
+        fs::write(&kdbx_file, crate::utils::kdbx::tests::sample_kdbx("master")).unwrap();
+        assert!(backup_manager.import_kdbx(&kdbx_file, "falsch").is_err());
+
+        let result = backup_manager.import_kdbx(&kdbx_file, "master").unwrap();
+        assert_eq!(result.imported, 2);
+        assert_eq!(result.skipped, 0);
+        assert!(result.errors.is_empty());
+
+        let folders = db.folders(1).unwrap();
+        assert_eq!(folders.len(), 2);
+        let work = folders.iter().find(|f| f.name == "Arbeit").unwrap();
+        let server = folders.iter().find(|f| f.name == "Server").unwrap();
+        assert_eq!(work.parent_id, None);
+        assert_eq!(server.parent_id, Some(work.id));
+
+        let entries = db.read_all::<PasswordEntry>().unwrap();
+        let mail = entries.iter().find(|e| e.service == "Mail").unwrap();
+        assert_eq!(mail.folder_id, None);
+        assert_eq!(mail.url, "https://mail.example.com");
+        assert_eq!(mail.notes, "Zeile 1\nZeile 2");
+        let ssh = entries.iter().find(|e| e.service == "SSH").unwrap();
+        assert_eq!(ssh.folder_id, Some(server.id));
+        let password = db
+            .encryption
+            .decrypt(&STANDARD.decode(&ssh.password).unwrap())
+            .unwrap();
+        assert_eq!(password, "pa$$wort");
+
+        let result = backup_manager.import_kdbx(&kdbx_file, "master").unwrap();
+        assert_eq!(result.imported, 0);
+        assert_eq!(result.skipped, 2);
+        assert_eq!(db.folders(1).unwrap().len(), 2);
+    }
+
+    #[test]
+    fn test_import_bitwarden() {
+        let (_temp, db, _config_dir, backup_dir) = setup_test_env();
+        let backup_manager = BackupManager::new(&db);
+
+        let export_file = backup_dir.join("bitwarden.json");
+        fs::write(
+            &export_file,
+            crate::utils::bitwarden::tests::sample_protected_export("master"),
+        )
+        .unwrap();
+        assert!(backup_manager.import_bitwarden(&export_file, None).is_err());
+
+        let result = backup_manager
+            .import_bitwarden(&export_file, Some("master"))
+            .unwrap();
+        assert_eq!(result.imported, 2);
+        assert_eq!(result.errors.len(), 1);
+
+        let folders = db.folders(1).unwrap();
+        assert_eq!(folders.len(), 2);
+        let work = folders.iter().find(|f| f.name == "Arbeit").unwrap();
+        let server = folders.iter().find(|f| f.name == "Server").unwrap();
+        assert_eq!(server.parent_id, Some(work.id));
+
+        let entries = db.read_all::<PasswordEntry>().unwrap();
+        let ssh = entries.iter().find(|e| e.service == "SSH").unwrap();
+        assert_eq!(ssh.folder_id, Some(server.id));
+        assert!(ssh.totp_secret.is_empty());
+
+        let mail = entries.iter().find(|e| e.service == "Mail").unwrap();
+        assert_eq!(mail.folder_id, None);
+        assert_eq!(mail.url, "https://mail.example.com");
+        assert_eq!(mail.notes, "Privat\n\nPIN: 1234");
+        let totp = db
+            .encryption
+            .decrypt(&STANDARD.decode(&mail.totp_secret).unwrap())
+            .unwrap();
+        assert!(totp.contains("GEZDGNBVGY3TQOJQ"));
+
+        let tags = db.tags(1).unwrap();
+        assert_eq!(tags.len(), 1);
+        assert_eq!(tags[0].name, FAVORITES_TAG);
+        assert_eq!(tags[0].entry_ids, vec![mail.id.unwrap()]);
+
+        let result = backup_manager
+            .import_bitwarden(&export_file, Some("master"))
+            .unwrap();
+        assert_eq!(result.imported, 0);
+        assert_eq!(result.skipped, 2);
+        assert_eq!(db.tags(1).unwrap().len(), 1);
+    }
+
+    #[test]
+    fn test_encrypted_export() {
+        let (_temp, db, _config_dir, backup_dir) = setup_test_env();
+        let backup_manager = BackupManager::new(&db);
+        let bitwarden_file = backup_dir.join("bitwarden.json");
+        fs::write(
+            &bitwarden_file,
+            crate::utils::bitwarden::tests::sample_protected_export("master"),
+        )
+        .unwrap();
+        backup_manager
+            .import_bitwarden(&bitwarden_file, Some("master"))
+            .unwrap();
+
+        assert!(backup_manager
+            .export_encrypted(&backup_dir, "zu kurz")
+            .is_err());
+        let export_file = backup_manager
+            .export_encrypted(&backup_dir, "ein langes Passwort")
+            .unwrap();
+        assert_eq!(
+            export_file.extension().unwrap().to_str(),
+            Some(EXPORT_EXTENSION)
+        );
+        assert!(!fs::read_to_string(&export_file).unwrap().contains("SSH"));
+
+        let (_other_temp, other_db, _other_config, _other_backups) = setup_test_env();
+        let other_manager = BackupManager::new(&other_db);
+        assert!(other_manager
+            .import_encrypted(&export_file, "ein falsches Passwort")
+            .is_err());
+        let result = other_manager
+            .import_encrypted(&export_file, "ein langes Passwort")
+            .unwrap();
+        assert_eq!(result.imported, 2);
+
+        let entries = other_db.read_all::<PasswordEntry>().unwrap();
+        let ssh = entries.iter().find(|e| e.service == "SSH").unwrap();
+        let server = other_db
+            .folders(1)
+            .unwrap()
+            .into_iter()
+            .find(|f| f.name == "Server")
+            .unwrap();
+        assert_eq!(ssh.folder_id, Some(server.id));
+        assert!(server.parent_id.is_some());
+
+        let mail = entries.iter().find(|e| e.service == "Mail").unwrap();
+        let totp = other_db
+            .encryption
+            .decrypt(&STANDARD.decode(&mail.totp_secret).unwrap())
+            .unwrap();
+        assert!(totp.contains("GEZDGNBVGY3TQOJQ"));
+        assert_eq!(
+            other_db.tags(1).unwrap()[0].entry_ids,
+            vec![mail.id.unwrap()]
+        );
+
+        let result = other_manager
+            .import_encrypted(&export_file, "ein langes Passwort")
+            .unwrap();
+        assert_eq!(result.skipped, 2);
+    }
 }
@@ -61,36 +61,30 @@ impl BackupCompressor {
     ///
     /// # Errors
     ///
-    /// Returns an error if the decompression fails
+    /// Returns an error if the decompression fails, e.g. because the data is truncated
     pub fn decompress(&self, data: &[u8]) -> Result<Vec<BackupFile>, Box<dyn std::error::Error>> {
         if data.len() < 5 {
             return Err("Invalid data length".into());
         }
 
         let mut cursor = 0;
-        let version = data[cursor];
-        cursor += 1;
+        let version = Self::take(data, &mut cursor, 1)?[0];
 
         if version != self.version {
             return Err("Invalid version".into());
         }
 
-        let file_count = u32::from_le_bytes(data[cursor..cursor + 4].try_into()?);
-        cursor += 4;
+        let file_count = u32::from_le_bytes(Self::take(data, &mut cursor, 4)?.try_into()?);
 
         let mut files = Vec::new();
         for _ in 0..file_count {
-            let name_len = u32::from_le_bytes(data[cursor..cursor + 4].try_into()?) as usize;
-            cursor += 4;
+            let name_len = u32::from_le_bytes(Self::take(data, &mut cursor, 4)?.try_into()?);
+            let name = String::from_utf8(
+                Self::take(data, &mut cursor, usize::try_from(name_len)?)?.to_vec(),
+            )?;
 
-            let name = String::from_utf8(data[cursor..cursor + name_len].to_vec())?;
-            cursor += name_len;
-
-            let content_len = u64::from_le_bytes(data[cursor..cursor + 8].try_into()?) as usize;
-            cursor += 8;
-
-            let content = data[cursor..cursor + content_len].to_vec();
-            cursor += content_len;
+            let content_len = u64::from_le_bytes(Self::take(data, &mut cursor, 8)?.try_into()?);
+            let content = Self::take(data, &mut cursor, usize::try_from(content_len)?)?.to_vec();
 
             files.push(BackupFile {
                 name,
@@ -100,11 +94,32 @@ impl BackupCompressor {
 
         Ok(files)
     }
+
+    /// Read the next bytes of the data and advance the cursor
+    ///
+    /// # Errors
+    ///
+    /// Returns an error if the data ends before the requested number of bytes
+    fn take<'d>(
+        data: &'d [u8],
+        cursor: &mut usize,
+        len: usize,
+    ) -> Result<&'d [u8], Box<dyn std::error::Error>> {
+        let end = cursor
+            .checked_add(len)
+            .filter(|end| *end <= data.len())
+            .ok_or("Backup data is truncated")?;
+        let bytes = &data[*cursor..end];
+        *cursor = end;
+
+        Ok(bytes)
+    }
 }
 
 #[cfg(test)]
 mod tests {
     use super::*;
+    use crate::utils::test_rng::Rng;
 
     #[test]
     fn test_compress_decompress() {
@@ -138,4 +153,71 @@ mod tests {
         assert!(compressor.decompress(&[1, 2, 3]).is_err());
         assert!(compressor.decompress(&[2, 0, 0, 0, 0]).is_err());
     }
+
+    #[test]
+    fn test_roundtrip_random_files() {
+        let compressor = BackupCompressor::new();
+        let mut rng = Rng::new(0x5eed);
+
+        for _ in 0..200 {
+            let files = (0..rng.below(5))
+                .map(|i| BackupFile {
+                    name: format!("file_{}.{}", i, rng.below(1000)),
+                    data: {
+                        let len = rng.below(512);
+                        rng.bytes(len)
+                    },
+                })
+                .collect::<Vec<_>>();
+
+            let decompressed = compressor
+                .decompress(&compressor.compress(&files).unwrap())
+                .unwrap();
+            assert_eq!(decompressed.len(), files.len());
+            for (original, restored) in files.iter().zip(&decompressed) {
+                assert_eq!(original.name, restored.name);
+                assert_eq!(original.data, restored.data);
+            }
+        }
+    }
+
+    #[test]
+    fn test_malformed_data_does_not_panic() {
+        let compressor = BackupCompressor::new();
+        let mut rng = Rng::new(0xbac0);
+        let valid = compressor
+            .compress(&[
+                BackupFile {
+                    name: "pass.db".to_string(),
+                    data: rng.bytes(256),
+                },
+                BackupFile {
+                    name: "config.toml".to_string(),
+                    data: b"[app]".to_vec(),
+                },
+            ])
+            .unwrap();
+
+        for len in 0..valid.len() {
+            assert!(compressor.decompress(&valid[..len]).is_err());
+        }
+
+        for _ in 0..2000 {
+            let mut data = valid.clone();
+            for _ in 0..=rng.below(8) {
+                let index = rng.below(data.len());
+                data[index] = rng.next() as u8;
+            }
+            let _ = compressor.decompress(&data);
+
+            let mut random = vec![1];
+            let len = rng.below(64);
+            random.extend(rng.bytes(len));
+            let _ = compressor.decompress(&random);
+        }
+
+        let mut oversized = vec![1, 1, 0, 0, 0, 0xff, 0xff, 0xff, 0xff];
+        oversized.extend_from_slice(&[0u8; 8]);
+        assert!(compressor.decompress(&oversized).is_err());
+    }
 }
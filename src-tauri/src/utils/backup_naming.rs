@@ -0,0 +1,234 @@
+use chrono::{DateTime, Utc};
+use regex::Regex;
+use std::fs;
+use std::path::Path;
+
+/// The name of backups if no template is configured.
+pub const DEFAULT_BACKUP_NAME: &str = "backup";
+/// The maximum length of a file name template.
+const MAX_TEMPLATE_LENGTH: usize = 64;
+/// Separates an increment from the name of its full backup.
+const INCREMENT_MARKER: &str = "_inc_";
+
+/// A part of a file name template.
+#[derive(Debug, Clone, PartialEq)]
+enum Part {
+    Literal(String),
+    Date,
+    Hostname,
+    Vault,
+}
+
+/// The name of backup files, rendered from a template with placeholders.
+///
+/// The template names the backup, the timestamp is always appended, so backups of several
+/// machines or vaults can share one folder, e.g. `{hostname}_{vault}`.
+#[derive(Debug, Clone)]
+pub struct BackupNaming {
+    parts: Vec<Part>,
+    hostname: String,
+    vault: String,
+}
+
+impl BackupNaming {
+    /// Parse a file name template
+    ///
+    /// The placeholders are `{date}`, `{hostname}` and `{vault}`, everything else may only
+    /// contain letters, digits, `-`, `_` and `.`.
+    ///
+    /// # Arguments
+    ///
+    /// * `template` - The file name template
+    /// * `db_name` - The file name of the vault, used for `{vault}`
+    ///
+    /// # Returns
+    ///
+    /// The naming of the backups
+    ///
+    /// # Errors
+    ///
+    /// Returns an error if the template is empty, too long or contains an unknown placeholder
+    /// or character
+    pub fn new(template: &str, db_name: &str) -> Result<Self, Box<dyn std::error::Error>> {
+        if template.is_empty() || template.chars().count() > MAX_TEMPLATE_LENGTH {
+            return Err(format!(
+                "The file name template must have 1 to {} characters",
+                MAX_TEMPLATE_LENGTH
+            )
+            .into());
+        }
+        if template.contains(INCREMENT_MARKER) {
+            return Err(format!(
+                "The file name template must not contain {}",
+                INCREMENT_MARKER
+            )
+            .into());
+        }
+
+        let mut parts = Vec::new();
+        let mut rest = template;
+        while !rest.is_empty() {
+            if let Some(placeholder) = rest.strip_prefix('{') {
+                let (name, after) = placeholder
+                    .split_once('}')
+                    .ok_or("Unclosed placeholder in the file name template")?;
+                parts.push(match name {
+                    "date" => Part::Date,
+                    "hostname" => Part::Hostname,
+                    "vault" => Part::Vault,
+                    _ => return Err(format!("Unknown placeholder: {{{}}}", name).into()),
+                });
+                rest = after;
+            } else {
+                let end = rest.find('{').unwrap_or(rest.len());
+                let literal = &rest[..end];
+                if let Some(c) = literal
+                    .chars()
+                    .find(|c| !c.is_ascii_alphanumeric() && !"-_.".contains(*c))
+                {
+                    return Err(
+                        format!("Invalid character in the file name template: {:?}", c).into(),
+                    );
+                }
+                parts.push(Part::Literal(literal.to_string()));
+                rest = &rest[end..];
+            }
+        }
+
+        let vault = Path::new(db_name)
+            .file_stem()
+            .map(|stem| stem.to_string_lossy().into_owned())
+            .unwrap_or_default();
+
+        Ok(Self {
+            parts,
+            hostname: sanitize(&hostname()),
+            vault: sanitize(&vault),
+        })
+    }
+
+    /// Render the name of a new backup
+    ///
+    /// # Arguments
+    ///
+    /// * `now` - The time of the backup, used for `{date}`
+    ///
+    /// # Returns
+    ///
+    /// The name without timestamp and extension
+    pub fn render(&self, now: DateTime<Utc>) -> String {
+        self.parts
+            .iter()
+            .map(|part| match part {
+                Part::Literal(literal) => literal.clone(),
+                Part::Date => now.format("%Y-%m-%d").to_string(),
+                Part::Hostname => self.hostname.clone(),
+                Part::Vault => self.vault.clone(),
+            })
+            .collect()
+    }
+
+    /// Check if a backup name was rendered from this template on this machine
+    ///
+    /// # Arguments
+    ///
+    /// * `name` - The name of a backup without timestamp and extension
+    ///
+    /// # Returns
+    ///
+    /// True if the name matches the template with any date
+    pub fn matches(&self, name: &str) -> bool {
+        let pattern: String = self
+            .parts
+            .iter()
+            .map(|part| match part {
+                Part::Literal(literal) => regex::escape(literal),
+                Part::Date => r"\d{4}-\d{2}-\d{2}".to_string(),
+                Part::Hostname => regex::escape(&self.hostname),
+                Part::Vault => regex::escape(&self.vault),
+            })
+            .collect();
+
+        Regex::new(&format!("^{}$", pattern)).is_ok_and(|regex| regex.is_match(name))
+    }
+}
+
+impl Default for BackupNaming {
+    fn default() -> Self {
+        Self {
+            parts: vec![Part::Literal(DEFAULT_BACKUP_NAME.to_string())],
+            hostname: String::new(),
+            vault: String::new(),
+        }
+    }
+}
+
+/// Get the name of this machine
+pub(super) fn hostname() -> String {
+    std::env::var("COMPUTERNAME")
+        .or_else(|_| std::env::var("HOSTNAME"))
+        .ok()
+        .or_else(|| fs::read_to_string("/etc/hostname").ok())
+        .map(|name| name.trim().to_string())
+        .filter(|name| !name.is_empty())
+        .unwrap_or_else(|| "unknown".to_string())
+}
+
+/// Replace the characters that may not appear in a placeholder value with dashes
+fn sanitize(value: &str) -> String {
+    value
+        .chars()
+        .map(|c| if c.is_ascii_alphanumeric() { c } else { '-' })
+        .collect()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use chrono::TimeZone;
+
+    #[test]
+    fn test_render() {
+        let now = Utc.with_ymd_and_hms(2025, 3, 7, 12, 0, 0).unwrap();
+        let naming = BackupNaming::new("kc-{vault}_{date}", "Mein Tresor.db").unwrap();
+        assert_eq!(naming.render(now), "kc-Mein-Tresor_2025-03-07");
+
+        let naming = BackupNaming::new("{hostname}", "pass.db").unwrap();
+        assert!(!naming.render(now).is_empty());
+        assert!(naming
+            .render(now)
+            .chars()
+            .all(|c| c.is_ascii_alphanumeric() || c == '-'));
+
+        assert_eq!(BackupNaming::default().render(now), DEFAULT_BACKUP_NAME);
+    }
+
+    #[test]
+    fn test_invalid_template() {
+        for template in [
+            "",
+            "{name}",
+            "{date",
+            "backup/other",
+            "a_inc_b",
+            &"a".repeat(65),
+        ] {
+            assert!(
+                BackupNaming::new(template, "pass.db").is_err(),
+                "{}",
+                template
+            );
+        }
+    }
+
+    #[test]
+    fn test_matches() {
+        let naming = BackupNaming::new("{vault}-{date}", "pass.db").unwrap();
+        assert!(naming.matches("pass-2025-03-07"));
+        assert!(naming.matches(&naming.render(Utc::now())));
+        assert!(!naming.matches("other-2025-03-07"));
+        assert!(!naming.matches("backup"));
+
+        assert!(BackupNaming::default().matches(DEFAULT_BACKUP_NAME));
+    }
+}
@@ -0,0 +1,411 @@
+use aes::cipher::{block_padding::Pkcs7, BlockDecryptMut, KeyIvInit};
+use aes::Aes256;
+use argon2::{Algorithm, Argon2, Params, Version};
+use base64::engine::general_purpose::STANDARD;
+use base64::Engine;
+use ring::{digest, hmac, pbkdf2};
+use serde::Deserialize;
+use std::num::NonZeroU32;
+use zeroize::Zeroizing;
+
+const KDF_PBKDF2: u8 = 0;
+const KDF_ARGON2ID: u8 = 1;
+/// The most PBKDF2 iterations a crafted export may ask for.
+const MAX_PBKDF2_ITERATIONS: u32 = 10_000_000;
+/// The most memory a crafted export may make Argon2 allocate, in MiB.
+const MAX_ARGON2_MEMORY: u32 = 1024;
+/// The Bitwarden item type of logins, other types like cards are not imported.
+pub const BITWARDEN_LOGIN: u8 = 1;
+const FIELD_TEXT: u8 = 0;
+const FIELD_HIDDEN: u8 = 1;
+const FIELD_BOOLEAN: u8 = 2;
+
+/// A Bitwarden vault export in the JSON format.
+#[derive(Debug, Default, Deserialize)]
+pub struct BitwardenExport {
+    #[serde(default)]
+    pub folders: Option<Vec<BitwardenFolder>>,
+    #[serde(default)]
+    pub items: Option<Vec<BitwardenItem>>,
+}
+
+/// A folder of a Bitwarden export, nested folders use `/` in the name.
+#[derive(Debug, Deserialize)]
+pub struct BitwardenFolder {
+    pub id: String,
+    pub name: String,
+}
+
+/// An item of a Bitwarden export.
+#[derive(Debug, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct BitwardenItem {
+    #[serde(rename = "type")]
+    pub item_type: u8,
+    pub name: String,
+    pub notes: Option<String>,
+    #[serde(default)]
+    pub favorite: bool,
+    pub folder_id: Option<String>,
+    pub fields: Option<Vec<BitwardenField>>,
+    pub login: Option<BitwardenLogin>,
+}
+
+/// A custom field of a Bitwarden item.
+#[derive(Debug, Deserialize)]
+pub struct BitwardenField {
+    pub name: Option<String>,
+    pub value: Option<String>,
+    #[serde(rename = "type")]
+    pub field_type: u8,
+}
+
+/// The login data of a Bitwarden item.
+#[derive(Debug, Deserialize)]
+pub struct BitwardenLogin {
+    pub username: Option<String>,
+    pub password: Option<String>,
+    pub totp: Option<String>,
+    pub uris: Option<Vec<BitwardenUri>>,
+}
+
+/// A URI of a Bitwarden login.
+#[derive(Debug, Deserialize)]
+pub struct BitwardenUri {
+    pub uri: Option<String>,
+}
+
+/// The envelope of an export, password protected exports carry the vault in `data`.
+#[derive(Deserialize)]
+#[serde(rename_all = "camelCase")]
+struct ExportEnvelope {
+    #[serde(default)]
+    encrypted: bool,
+    #[serde(default)]
+    password_protected: bool,
+    salt: Option<String>,
+    kdf_type: Option<u8>,
+    kdf_iterations: Option<u32>,
+    kdf_memory: Option<u32>,
+    kdf_parallelism: Option<u32>,
+    data: Option<String>,
+}
+
+impl BitwardenExport {
+    /// Parse a Bitwarden JSON export
+    ///
+    /// Unencrypted exports and exports protected by a password are supported. Exports
+    /// encrypted with the account key can only be read by Bitwarden itself.
+    ///
+    /// # Arguments
+    ///
+    /// * `content` - The content of the `.json` file
+    /// * `password` - The password of a password protected export
+    ///
+    /// # Returns
+    ///
+    /// The parsed export
+    ///
+    /// # Errors
+    ///
+    /// Returns an error if the file is no Bitwarden export, the password is missing or wrong
+    /// or the export is encrypted with the account key
+    pub fn parse(
+        content: &str,
+        password: Option<&str>,
+    ) -> Result<Self, Box<dyn std::error::Error>> {
+        let envelope: ExportEnvelope = serde_json::from_str(content)?;
+        if !envelope.encrypted {
+            return Ok(serde_json::from_str(content)?);
+        }
+        if !envelope.password_protected {
+            return Err(
+                "Exports encrypted with the account key are not supported, export with a password instead"
+                    .into(),
+            );
+        }
+
+        let password = password
+            .filter(|p| !p.is_empty())
+            .ok_or("The export is protected by a password")?;
+        let salt = envelope.salt.as_deref().ok_or("The export has no salt")?;
+        let key = Self::derive_key(password, salt, &envelope)?;
+        let data = Self::decrypt(
+            envelope.data.as_deref().ok_or("The export has no data")?,
+            &key,
+        )?;
+
+        Ok(serde_json::from_slice(&data)?)
+    }
+
+    /// Derive the key of a password protected export and stretch it into the AES and HMAC keys
+    fn derive_key(
+        password: &str,
+        salt: &str,
+        envelope: &ExportEnvelope,
+    ) -> Result<Zeroizing<Vec<u8>>, Box<dyn std::error::Error>> {
+        let iterations = envelope
+            .kdf_iterations
+            .ok_or("The export has no KDF iterations")?;
+        let mut key = Zeroizing::new(vec![0u8; 32]);
+        match envelope.kdf_type.unwrap_or(KDF_PBKDF2) {
+            KDF_PBKDF2 => {
+                if iterations > MAX_PBKDF2_ITERATIONS {
+                    return Err("The key derivation needs too many iterations".into());
+                }
+                pbkdf2::derive(
+                    pbkdf2::PBKDF2_HMAC_SHA256,
+                    NonZeroU32::new(iterations).ok_or("Invalid KDF iterations")?,
+                    salt.as_bytes(),
+                    password.as_bytes(),
+                    &mut key,
+                );
+            }
+            KDF_ARGON2ID => {
+                let memory = envelope.kdf_memory.ok_or("The export has no KDF memory")?;
+                if memory > MAX_ARGON2_MEMORY {
+                    return Err("The key derivation needs too much memory".into());
+                }
+                let params = Params::new(
+                    memory * 1024,
+                    iterations,
+                    envelope
+                        .kdf_parallelism
+                        .ok_or("The export has no KDF parallelism")?,
+                    Some(32),
+                )
+                .map_err(|e| format!("Invalid Argon2 parameters: {}", e))?;
+                Argon2::new(Algorithm::Argon2id, Version::V0x13, params)
+                    .hash_password_into(
+                        password.as_bytes(),
+                        digest::digest(&digest::SHA256, salt.as_bytes()).as_ref(),
+                        &mut key,
+                    )
+                    .map_err(|e| format!("Failed to derive the key: {}", e))?;
+            }
+            _ => return Err("The key derivation of the export is not supported".into()),
+        }
+
+        let expand = |info: &[u8]| {
+            hmac::sign(
+                &hmac::Key::new(hmac::HMAC_SHA256, &key),
+                &[info, &[1]].concat(),
+            )
+        };
+        let mut stretched = Zeroizing::new(expand(b"enc").as_ref().to_vec());
+        stretched.extend_from_slice(expand(b"mac").as_ref());
+
+        Ok(stretched)
+    }
+
+    /// Verify and decrypt an AES-256-CBC encrypted string of the form `2.iv|data|mac`
+    fn decrypt(
+        encrypted: &str,
+        key: &[u8],
+    ) -> Result<Zeroizing<Vec<u8>>, Box<dyn std::error::Error>> {
+        let parts = encrypted
+            .strip_prefix("2.")
+            .ok_or("Unsupported encryption of the export")?
+            .split('|')
+            .map(|part| STANDARD.decode(part))
+            .collect::<Result<Vec<_>, _>>()?;
+        let [iv, data, mac] = parts.as_slice() else {
+            return Err("The encrypted data of the export is invalid".into());
+        };
+
+        hmac::verify(
+            &hmac::Key::new(hmac::HMAC_SHA256, &key[32..]),
+            &[iv.as_slice(), data].concat(),
+            mac,
+        )
+        .map_err(|_| "Wrong password or corrupted export")?;
+
+        let mut buffer = Zeroizing::new(data.clone());
+        let len = cbc::Decryptor::<Aes256>::new_from_slices(&key[..32], iv)
+            .map_err(|_| "Invalid AES key or IV")?
+            .decrypt_padded_mut::<Pkcs7>(&mut buffer)
+            .map_err(|_| "Wrong password or corrupted export")?
+            .len();
+        buffer.truncate(len);
+
+        Ok(buffer)
+    }
+}
+
+impl BitwardenItem {
+    /// Get the notes with the text, hidden and boolean custom fields appended as `name: value`
+    ///
+    /// # Returns
+    ///
+    /// The notes of the item, empty if it has neither notes nor fields
+    pub fn notes_with_fields(&self) -> String {
+        let fields = self
+            .fields
+            .iter()
+            .flatten()
+            .filter(|f| matches!(f.field_type, FIELD_TEXT | FIELD_HIDDEN | FIELD_BOOLEAN))
+            .map(|f| {
+                format!(
+                    "{}: {}",
+                    f.name.as_deref().unwrap_or_default(),
+                    f.value.as_deref().unwrap_or_default()
+                )
+            })
+            .collect::<Vec<_>>();
+
+        let notes = self.notes.as_deref().unwrap_or_default().trim_end();
+        match (notes.is_empty(), fields.is_empty()) {
+            (_, true) => notes.to_string(),
+            (true, false) => fields.join("\n"),
+            (false, false) => format!("{}\n\n{}", notes, fields.join("\n")),
+        }
+    }
+}
+
+#[cfg(test)]
+pub(crate) mod tests {
+    use super::*;
+    use aes::cipher::BlockEncryptMut;
+
+    const VAULT: &str = r#"{
+        "encrypted": false,
+        "folders": [
+            { "id": "f1", "name": "Arbeit" },
+            { "id": "f2", "name": "Arbeit/Server" }
+        ],
+        "items": [
+            {
+                "id": "i1",
+                "folderId": null,
+                "type": 1,
+                "name": "Mail",
+                "notes": "Privat",
+                "favorite": true,
+                "fields": [
+                    { "name": "PIN", "value": "1234", "type": 1 },
+                    { "name": "Verknüpft", "value": null, "type": 3, "linkedId": 100 }
+                ],
+                "login": {
+                    "username": "karl@example.com",
+                    "password": "geheim",
+                    "totp": "otpauth://totp/Mail?secret=GEZDGNBVGY3TQOJQ",
+                    "uris": [{ "match": null, "uri": "https://mail.example.com" }]
+                }
+            },
+            {
+                "id": "i2",
+                "folderId": "f2",
+                "type": 1,
+                "name": "SSH",
+                "notes": null,
+                "favorite": false,
+                "fields": null,
+                "login": { "username": "root", "password": "pa$$wort", "totp": null, "uris": null }
+            },
+            {
+                "id": "i3",
+                "folderId": null,
+                "type": 3,
+                "name": "Kreditkarte",
+                "favorite": false,
+                "card": { "number": "4111111111111111" }
+            }
+        ]
+    }"#;
+
+    /// Write the sample vault as an export protected by a password with PBKDF2
+    pub(crate) fn sample_protected_export(password: &str) -> String {
+        protected_export(password, r#""kdfType": 0, "kdfIterations": 1000"#)
+    }
+
+    fn protected_export(password: &str, kdf: &str) -> String {
+        let envelope = format!(
+            r#"{{ "encrypted": true, "passwordProtected": true, "salt": "c2FsdA==", {} }}"#,
+            kdf
+        );
+        let parsed: ExportEnvelope = serde_json::from_str(&envelope).unwrap();
+        let key = BitwardenExport::derive_key(password, "c2FsdA==", &parsed).unwrap();
+
+        let iv = [9u8; 16];
+        let len = VAULT.len();
+        let mut buffer = VAULT.as_bytes().to_vec();
+        buffer.resize(len + 16, 0);
+        let data = cbc::Encryptor::<Aes256>::new_from_slices(&key[..32], &iv)
+            .unwrap()
+            .encrypt_padded_mut::<Pkcs7>(&mut buffer, len)
+            .unwrap()
+            .to_vec();
+        let mac = hmac::sign(
+            &hmac::Key::new(hmac::HMAC_SHA256, &key[32..]),
+            &[&iv[..], &data].concat(),
+        );
+        let encrypted = format!(
+            "2.{}|{}|{}",
+            STANDARD.encode(iv),
+            STANDARD.encode(&data),
+            STANDARD.encode(mac)
+        );
+
+        format!(
+            r#"{{ "encrypted": true, "passwordProtected": true, "salt": "c2FsdA==", {}, "encKeyValidation_DO_NOT_EDIT": "", "data": "{}" }}"#,
+            kdf, encrypted
+        )
+    }
+
+    fn assert_vault(export: &BitwardenExport) {
+        let folders = export.folders.as_ref().unwrap();
+        assert_eq!(folders[1].name, "Arbeit/Server");
+
+        let items = export.items.as_ref().unwrap();
+        assert_eq!(items.len(), 3);
+        let mail = &items[0];
+        assert!(mail.favorite);
+        let login = mail.login.as_ref().unwrap();
+        assert_eq!(login.password.as_deref(), Some("geheim"));
+        assert!(login.totp.as_deref().unwrap().starts_with("otpauth://"));
+        assert_eq!(mail.notes_with_fields(), "Privat\n\nPIN: 1234");
+
+        assert_eq!(items[1].folder_id.as_deref(), Some("f2"));
+        assert_eq!(items[1].notes_with_fields(), "");
+        assert_eq!(items[2].item_type, 3);
+    }
+
+    #[test]
+    fn test_parse_unencrypted_export() {
+        assert_vault(&BitwardenExport::parse(VAULT, None).unwrap());
+        assert!(BitwardenExport::parse("keine Datei", None).is_err());
+    }
+
+    #[test]
+    fn test_parse_protected_export() {
+        let export = sample_protected_export("master");
+        assert_vault(&BitwardenExport::parse(&export, Some("master")).unwrap());
+
+        let error = BitwardenExport::parse(&export, Some("falsch")).unwrap_err();
+        assert_eq!(error.to_string(), "Wrong password or corrupted export");
+        assert!(BitwardenExport::parse(&export, None).is_err());
+
+        let argon2 = protected_export(
+            "master",
+            r#""kdfType": 1, "kdfIterations": 2, "kdfMemory": 1, "kdfParallelism": 1"#,
+        );
+        assert_vault(&BitwardenExport::parse(&argon2, Some("master")).unwrap());
+    }
+
+    #[test]
+    fn test_parse_account_encrypted_export() {
+        let export =
+            r#"{ "encrypted": true, "encKeyValidation_DO_NOT_EDIT": "2.a|b|c", "items": [] }"#;
+        assert!(BitwardenExport::parse(export, None).is_err());
+
+        let greedy = protected_export_envelope(r#""kdfType": 0, "kdfIterations": 100000000"#);
+        assert!(BitwardenExport::parse(&greedy, Some("master")).is_err());
+    }
+
+    fn protected_export_envelope(kdf: &str) -> String {
+        format!(
+            r#"{{ "encrypted": true, "passwordProtected": true, "salt": "c2FsdA==", {}, "data": "2.AA==|AA==|AA==" }}"#,
+            kdf
+        )
+    }
+}
@@ -0,0 +1,93 @@
+use ring::digest::{digest, SHA1_FOR_LEGACY_USE_ONLY};
+
+/// The range endpoint of the Have I Been Pwned password API.
+pub const BREACH_RANGE_URL: &str = "https://api.pwnedpasswords.com/range/";
+/// The number of hex characters of the hash sent to the API.
+const PREFIX_LENGTH: usize = 5;
+
+/// The SHA-1 hash of a password, split for a k-anonymity range query.
+///
+/// Only the prefix leaves the device, the suffix is compared locally against the range.
+#[derive(Debug, Clone, PartialEq, Eq, Hash)]
+pub struct BreachHash {
+    pub prefix: String,
+    pub suffix: String,
+}
+
+impl BreachHash {
+    /// Hash a password for the range query
+    ///
+    /// # Arguments
+    ///
+    /// * `password` - The password to hash
+    ///
+    /// # Returns
+    ///
+    /// The uppercase hex hash split into prefix and suffix
+    pub fn new(password: &str) -> Self {
+        let hash: String = digest(&SHA1_FOR_LEGACY_USE_ONLY, password.as_bytes())
+            .as_ref()
+            .iter()
+            .map(|byte| format!("{:02X}", byte))
+            .collect();
+        let (prefix, suffix) = hash.split_at(PREFIX_LENGTH);
+
+        Self {
+            prefix: prefix.to_string(),
+            suffix: suffix.to_string(),
+        }
+    }
+
+    /// Get the URL of the range containing the hash
+    pub fn range_url(&self) -> String {
+        format!("{}{}", BREACH_RANGE_URL, self.prefix)
+    }
+
+    /// Look up how often the password appeared in breaches
+    ///
+    /// Padding entries the API adds with a count of 0 are ignored.
+    ///
+    /// # Arguments
+    ///
+    /// * `range` - The response of the range query, one `SUFFIX:COUNT` per line
+    ///
+    /// # Returns
+    ///
+    /// The number of times the password was seen in breaches, 0 if it was not
+    pub fn count_in(&self, range: &str) -> u64 {
+        range
+            .lines()
+            .filter_map(|line| line.trim().split_once(':'))
+            .find(|(suffix, _)| suffix.eq_ignore_ascii_case(&self.suffix))
+            .and_then(|(_, count)| count.trim().parse().ok())
+            .unwrap_or(0)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_breach_hash() {
+        let hash = BreachHash::new("password");
+        assert_eq!(hash.prefix, "5BAA6");
+        assert_eq!(hash.suffix, "1E4C9B93F3F0682250B6CF8331B7EE68FD8");
+        assert_eq!(
+            hash.range_url(),
+            "https://api.pwnedpasswords.com/range/5BAA6"
+        );
+    }
+
+    #[test]
+    fn test_count_in_range() {
+        let hash = BreachHash::new("password");
+        let range = "003D68EB55068C33ACE09247EE4C639306B:3\r\n\
+                     1e4c9b93f3f0682250b6cf8331b7ee68fd8:10434004\r\n\
+                     01330C689E5D64F660D6947A93AD634EF8F:0\r\n";
+        assert_eq!(hash.count_in(range), 10434004);
+
+        assert_eq!(BreachHash::new("not in the range").count_in(range), 0);
+        assert_eq!(hash.count_in(""), 0);
+    }
+}
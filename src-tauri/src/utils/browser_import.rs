@@ -0,0 +1,298 @@
+use rusqlite::{Connection, OpenFlags};
+use serde::{Deserialize, Serialize};
+use serde_json::Value;
+use std::fs;
+use std::path::{Path, PathBuf};
+
+use super::csv_import::CsvProfile;
+
+/// The file Chromium based browsers store saved logins in, per profile.
+const CHROMIUM_LOGINS: &str = "Login Data";
+/// The file Firefox stores saved logins in, per profile.
+const FIREFOX_LOGINS: &str = "logins.json";
+
+/// A browser whose saved passwords can be imported from its CSV export.
+#[derive(Clone, Copy, Debug, PartialEq, Serialize, Deserialize)]
+#[serde(rename_all = "lowercase")]
+pub enum BrowserKind {
+    Chrome,
+    Edge,
+    Firefox,
+}
+
+impl BrowserKind {
+    /// Get the CSV layout of the password export of the browser
+    pub fn csv_profile(&self) -> CsvProfile {
+        match self {
+            BrowserKind::Chrome => CsvProfile::Chrome,
+            BrowserKind::Edge => CsvProfile::Edge,
+            BrowserKind::Firefox => CsvProfile::Firefox,
+        }
+    }
+
+    /// Get the directory the browser keeps its profiles in on this platform
+    fn data_dir(&self) -> Option<PathBuf> {
+        let dir = match self {
+            #[cfg(target_os = "linux")]
+            BrowserKind::Chrome => dirs::config_dir()?.join("google-chrome"),
+            #[cfg(target_os = "linux")]
+            BrowserKind::Edge => dirs::config_dir()?.join("microsoft-edge"),
+            #[cfg(target_os = "linux")]
+            BrowserKind::Firefox => dirs::home_dir()?.join(".mozilla/firefox"),
+            #[cfg(target_os = "macos")]
+            BrowserKind::Chrome => dirs::data_dir()?.join("Google/Chrome"),
+            #[cfg(target_os = "macos")]
+            BrowserKind::Edge => dirs::data_dir()?.join("Microsoft Edge"),
+            #[cfg(target_os = "macos")]
+            BrowserKind::Firefox => dirs::data_dir()?.join("Firefox"),
+            #[cfg(target_os = "windows")]
+            BrowserKind::Chrome => dirs::data_local_dir()?.join(r"Google\Chrome\User Data"),
+            #[cfg(target_os = "windows")]
+            BrowserKind::Edge => dirs::data_local_dir()?.join(r"Microsoft\Edge\User Data"),
+            #[cfg(target_os = "windows")]
+            BrowserKind::Firefox => dirs::data_dir()?.join(r"Mozilla\Firefox"),
+            #[cfg(not(any(target_os = "linux", target_os = "macos", target_os = "windows")))]
+            _ => return None,
+        };
+
+        Some(dir)
+    }
+}
+
+/// A browser profile with a store of saved passwords.
+#[derive(Clone, Debug, PartialEq, Serialize)]
+pub struct BrowserProfile {
+    pub browser: BrowserKind,
+    /// The name the browser shows for the profile.
+    pub name: String,
+    pub path: PathBuf,
+    /// The number of saved logins, None if the store cannot be read.
+    pub logins: Option<usize>,
+}
+
+impl BrowserProfile {
+    /// Detect the profiles of the installed browsers that have saved passwords
+    ///
+    /// # Returns
+    ///
+    /// The profiles of Chrome, Edge and Firefox, in this order
+    pub fn detect() -> Vec<Self> {
+        [BrowserKind::Chrome, BrowserKind::Edge, BrowserKind::Firefox]
+            .into_iter()
+            .filter_map(|browser| Some((browser, browser.data_dir()?)))
+            .flat_map(|(browser, dir)| Self::detect_in(browser, &dir))
+            .collect()
+    }
+
+    /// Detect the profiles of a browser with saved passwords in its data directory
+    ///
+    /// # Arguments
+    ///
+    /// * `browser` - The browser the directory belongs to
+    /// * `data_dir` - The directory the browser keeps its profiles in
+    ///
+    /// # Returns
+    ///
+    /// The profiles sorted by name, empty if the browser is not installed
+    pub fn detect_in(browser: BrowserKind, data_dir: &Path) -> Vec<Self> {
+        let mut profiles = match browser {
+            BrowserKind::Chrome | BrowserKind::Edge => chromium_profiles(browser, data_dir),
+            BrowserKind::Firefox => firefox_profiles(data_dir),
+        };
+        profiles.sort_by(|a, b| a.name.cmp(&b.name));
+
+        profiles
+    }
+}
+
+/// Find the profiles of a Chromium based browser, named as in its `Local State` file.
+fn chromium_profiles(browser: BrowserKind, data_dir: &Path) -> Vec<BrowserProfile> {
+    let local_state = fs::read_to_string(data_dir.join("Local State"))
+        .ok()
+        .and_then(|content| serde_json::from_str::<Value>(&content).ok());
+    let Ok(entries) = fs::read_dir(data_dir) else {
+        return Vec::new();
+    };
+
+    entries
+        .filter_map(|entry| entry.ok())
+        .map(|entry| entry.path())
+        .filter(|path| path.join(CHROMIUM_LOGINS).is_file())
+        .filter_map(|path| {
+            let dir_name = path.file_name()?.to_str()?.to_string();
+            if dir_name != "Default" && !dir_name.starts_with("Profile ") {
+                return None;
+            }
+            let name = local_state
+                .as_ref()
+                .and_then(|state| state["profile"]["info_cache"][&dir_name]["name"].as_str())
+                .map(str::to_string)
+                .unwrap_or(dir_name);
+
+            Some(BrowserProfile {
+                browser,
+                name,
+                logins: count_chromium_logins(&path.join(CHROMIUM_LOGINS)),
+                path,
+            })
+        })
+        .collect()
+}
+
+/// Count the saved logins of a Chromium profile without the sites that are never saved.
+///
+/// The database is opened as immutable, so a running browser keeping it locked does not
+/// matter and nothing is written to it.
+fn count_chromium_logins(path: &Path) -> Option<usize> {
+    let uri = format!("file:{}?immutable=1", path.to_str()?.replace('\\', "/"));
+    let connection = Connection::open_with_flags(
+        uri,
+        OpenFlags::SQLITE_OPEN_READ_ONLY | OpenFlags::SQLITE_OPEN_URI,
+    )
+    .ok()?;
+    connection
+        .query_row(
+            "SELECT COUNT(*) FROM logins WHERE blacklisted_by_user = 0",
+            [],
+            |row| row.get::<_, i64>(0),
+        )
+        .ok()
+        .map(|count| count as usize)
+}
+
+/// Find the Firefox profiles listed in `profiles.ini` that have a login store.
+fn firefox_profiles(data_dir: &Path) -> Vec<BrowserProfile> {
+    let Ok(ini) = fs::read_to_string(data_dir.join("profiles.ini")) else {
+        return Vec::new();
+    };
+
+    // Only the [ProfileN] sections describe profiles, the others are kept as None.
+    let mut sections: Vec<Option<Vec<(&str, &str)>>> = Vec::new();
+    for line in ini.lines().map(str::trim) {
+        if line.starts_with('[') {
+            sections.push(line.starts_with("[Profile").then(Vec::new));
+        } else if let (Some(Some(section)), Some((key, value))) =
+            (sections.last_mut(), line.split_once('='))
+        {
+            section.push((key.trim(), value.trim()));
+        }
+    }
+
+    sections
+        .iter()
+        .flatten()
+        .filter_map(|section| {
+            let value = |key: &str| section.iter().find(|(k, _)| *k == key).map(|(_, v)| *v);
+            let path = value("Path")?;
+            let path = if value("IsRelative") == Some("0") {
+                PathBuf::from(path)
+            } else {
+                data_dir.join(path)
+            };
+            let logins_file = path.join(FIREFOX_LOGINS);
+            if !logins_file.is_file() {
+                return None;
+            }
+
+            let logins = fs::read_to_string(&logins_file)
+                .ok()
+                .and_then(|content| serde_json::from_str::<Value>(&content).ok())
+                .and_then(|logins| logins["logins"].as_array().map(Vec::len));
+            Some(BrowserProfile {
+                browser: BrowserKind::Firefox,
+                name: value("Name").unwrap_or("default").to_string(),
+                path,
+                logins,
+            })
+        })
+        .collect()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use tempfile::TempDir;
+
+    #[test]
+    fn test_detect_chromium_profiles() {
+        let temp_dir = TempDir::new().unwrap();
+        let data_dir = temp_dir.path();
+        for dir in ["Default", "Profile 1", "System Profile"] {
+            fs::create_dir(data_dir.join(dir)).unwrap();
+        }
+        fs::create_dir(data_dir.join("Crashpad")).unwrap();
+        fs::write(
+            data_dir.join("Local State"),
+            r#"{"profile": {"info_cache": {"Profile 1": {"name": "Arbeit"}}}}"#,
+        )
+        .unwrap();
+
+        let connection = Connection::open(data_dir.join("Default").join(CHROMIUM_LOGINS)).unwrap();
+        connection
+            .execute_batch(
+                "CREATE TABLE logins (origin_url TEXT, blacklisted_by_user INTEGER);
+                 INSERT INTO logins VALUES ('https://example.com', 0);
+                 INSERT INTO logins VALUES ('https://example.org', 0);
+                 INSERT INTO logins VALUES ('https://never.example.com', 1);",
+            )
+            .unwrap();
+        drop(connection);
+        fs::write(data_dir.join("Profile 1").join(CHROMIUM_LOGINS), b"").unwrap();
+        fs::write(data_dir.join("System Profile").join(CHROMIUM_LOGINS), b"").unwrap();
+
+        let profiles = BrowserProfile::detect_in(BrowserKind::Chrome, data_dir);
+
+        assert_eq!(profiles.len(), 2);
+        assert_eq!(profiles[0].name, "Arbeit");
+        assert_eq!(profiles[0].logins, None);
+        assert_eq!(profiles[1].name, "Default");
+        assert_eq!(profiles[1].logins, Some(2));
+        assert_eq!(profiles[1].browser.csv_profile(), CsvProfile::Chrome);
+    }
+
+    #[test]
+    fn test_detect_firefox_profiles() {
+        let temp_dir = TempDir::new().unwrap();
+        let data_dir = temp_dir.path();
+        let external = TempDir::new().unwrap();
+        fs::write(
+            data_dir.join("profiles.ini"),
+            format!(
+                "[General]\nStartWithLastProfile=1\n\n\
+                 [Profile0]\nName=default-release\nIsRelative=1\nPath=Profiles/abc.default-release\n\n\
+                 [Profile1]\nName=empty\nIsRelative=1\nPath=Profiles/def.empty\n\n\
+                 [Profile2]\nName=extern\nIsRelative=0\nPath={}\n\n\
+                 [Install4F96D1932A9F858E]\nDefault=Profiles/abc.default-release\n",
+                external.path().display()
+            ),
+        )
+        .unwrap();
+        let release = data_dir.join("Profiles/abc.default-release");
+        fs::create_dir_all(&release).unwrap();
+        fs::create_dir_all(data_dir.join("Profiles/def.empty")).unwrap();
+        fs::write(
+            release.join(FIREFOX_LOGINS),
+            r#"{"nextId": 3, "logins": [{"id": 1}, {"id": 2}]}"#,
+        )
+        .unwrap();
+        fs::write(external.path().join(FIREFOX_LOGINS), "invalid").unwrap();
+
+        let profiles = BrowserProfile::detect_in(BrowserKind::Firefox, data_dir);
+
+        assert_eq!(profiles.len(), 2);
+        assert_eq!(profiles[0].name, "default-release");
+        assert_eq!(profiles[0].path, release);
+        assert_eq!(profiles[0].logins, Some(2));
+        assert_eq!(profiles[1].name, "extern");
+        assert_eq!(profiles[1].logins, None);
+    }
+
+    #[test]
+    fn test_detect_missing_browser() {
+        let temp_dir = TempDir::new().unwrap();
+        let missing = temp_dir.path().join("missing");
+
+        assert!(BrowserProfile::detect_in(BrowserKind::Edge, &missing).is_empty());
+        assert!(BrowserProfile::detect_in(BrowserKind::Firefox, &missing).is_empty());
+    }
+}
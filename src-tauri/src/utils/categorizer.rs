@@ -0,0 +1,215 @@
+use log::{info, warn};
+use serde::{Deserialize, Serialize};
+use std::fs;
+use std::path::Path;
+
+use super::base_domain;
+
+/// The rules shipped with the app.
+const BUNDLED_RULES: &str = include_str!("category_rules.txt");
+/// The file in the config directory that replaces the bundled rules.
+const RULES_FILE: &str = "category_rules.txt";
+
+/// A category suggested for an entry.
+#[derive(Clone, Debug, PartialEq, Serialize, Deserialize)]
+pub struct CategorySuggestion {
+    pub id: i32,
+    pub service: String,
+    pub category: String,
+}
+
+/// Whether suggested categories are applied as tags or folders.
+#[derive(Clone, Copy, Debug, Default, PartialEq, Deserialize)]
+#[serde(rename_all = "lowercase")]
+pub enum CategoryTarget {
+    #[default]
+    Tag,
+    Folder,
+}
+
+/// Suggests a category for an entry from lists of known domains.
+#[derive(Debug, Default, PartialEq)]
+pub struct Categorizer {
+    rules: Vec<(String, Vec<String>)>,
+}
+
+impl Categorizer {
+    /// Load the category rules
+    ///
+    /// Rules in the config directory are preferred over the bundled ones, so they can be
+    /// updated without a new release. A broken file keeps the bundled rules.
+    ///
+    /// # Arguments
+    ///
+    /// * `config_dir` - The directory the updated rules are stored in
+    ///
+    /// # Returns
+    ///
+    /// The categorizer
+    pub fn load(config_dir: &Path) -> Self {
+        let path = config_dir.join(RULES_FILE);
+        if let Ok(content) = fs::read_to_string(&path) {
+            match Self::parse(&content) {
+                Ok(categorizer) => {
+                    info!("Using the category rules from {}", path.display());
+                    return categorizer;
+                }
+                Err(e) => warn!("Ignoring the category rules in {}: {}", path.display(), e),
+            }
+        }
+
+        Self::parse(BUNDLED_RULES).unwrap_or_default()
+    }
+
+    /// Parse category rules
+    ///
+    /// Each section starts with the category in brackets, followed by one domain per line.
+    /// Empty lines and lines starting with `#` are skipped.
+    ///
+    /// # Arguments
+    ///
+    /// * `content` - The rules to parse
+    ///
+    /// # Returns
+    ///
+    /// The categorizer
+    ///
+    /// # Errors
+    ///
+    /// Returns an error if a line is no valid domain, a domain comes before the first category
+    /// or no category has a domain
+    pub fn parse(content: &str) -> Result<Self, Box<dyn std::error::Error>> {
+        let mut rules: Vec<(String, Vec<String>)> = Vec::new();
+        for (number, line) in content.lines().enumerate() {
+            let line = line.trim();
+            if line.is_empty() || line.starts_with('#') {
+                continue;
+            }
+
+            if let Some(category) = line.strip_prefix('[').and_then(|l| l.strip_suffix(']')) {
+                rules.push((category.trim().to_string(), Vec::new()));
+                continue;
+            }
+
+            let domain = base_domain(line)
+                .ok_or_else(|| format!("Invalid domain in line {}", number + 1))?;
+            rules
+                .last_mut()
+                .ok_or_else(|| format!("Domain without a category in line {}", number + 1))?
+                .1
+                .push(domain);
+        }
+
+        rules.retain(|(category, domains)| !category.is_empty() && !domains.is_empty());
+        if rules.is_empty() {
+            return Err("The rules contain no category".into());
+        }
+
+        Ok(Self { rules })
+    }
+
+    /// Suggest a category for an entry
+    ///
+    /// The domain of the URL is matched against the rules. Without a URL, a service name
+    /// that is a domain or equals the name of a listed domain is used, e.g. `PayPal`.
+    ///
+    /// # Arguments
+    ///
+    /// * `service` - The service name of the entry
+    /// * `url` - The URL of the entry, may be empty
+    ///
+    /// # Returns
+    ///
+    /// The suggested category, None if no rule matches
+    pub fn category(&self, service: &str, url: &str) -> Option<&str> {
+        let service = service.trim();
+        let domain = base_domain(url).or_else(|| {
+            (service.contains('.') && !service.contains(char::is_whitespace))
+                .then(|| base_domain(service))
+                .flatten()
+        });
+
+        let name: String = service
+            .to_lowercase()
+            .chars()
+            .filter(|c| c.is_alphanumeric())
+            .collect();
+        let matches = |rule: &String| match &domain {
+            Some(domain) => rule == domain,
+            None => {
+                !name.is_empty()
+                    && rule.split('.').next().is_some_and(|label| {
+                        label
+                            .chars()
+                            .filter(|c| c.is_alphanumeric())
+                            .eq(name.chars())
+                    })
+            }
+        };
+
+        self.rules
+            .iter()
+            .find(|(_, domains)| domains.iter().any(matches))
+            .map(|(category, _)| category.as_str())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use tempfile::TempDir;
+
+    #[test]
+    fn test_parse() {
+        let categorizer =
+            Categorizer::parse("# Rules\n\n[Banking]\nPayPal.com\n[Empty]\n").unwrap();
+        assert_eq!(
+            categorizer.rules,
+            vec![("Banking".to_string(), vec!["paypal.com".to_string()])]
+        );
+
+        assert!(Categorizer::parse("paypal.com\n[Banking]").is_err());
+        assert!(Categorizer::parse("[Banking]\nnot a domain").is_err());
+        assert!(Categorizer::parse("# Nothing").is_err());
+        assert!(Categorizer::parse(BUNDLED_RULES).is_ok());
+    }
+
+    #[test]
+    fn test_category() {
+        let categorizer = Categorizer::parse(BUNDLED_RULES).unwrap();
+
+        assert_eq!(
+            categorizer.category("Konto", "https://www.paypal.com/signin"),
+            Some("Banking")
+        );
+        assert_eq!(categorizer.category("gmx.net", ""), Some("E-Mail"));
+        assert_eq!(categorizer.category("Deutsche Bank", ""), Some("Banking"));
+        assert_eq!(
+            categorizer.category("LinkedIn", ""),
+            Some("Soziale Netzwerke")
+        );
+        assert_eq!(categorizer.category("Amazon", "https://example.com"), None);
+        assert_eq!(categorizer.category("Forum", ""), None);
+        assert_eq!(categorizer.category("", ""), None);
+    }
+
+    #[test]
+    fn test_load() {
+        let temp = TempDir::new().unwrap();
+        assert_eq!(
+            Categorizer::load(temp.path()),
+            Categorizer::parse(BUNDLED_RULES).unwrap()
+        );
+
+        fs::write(temp.path().join(RULES_FILE), "[Arbeit]\nexample.com\n").unwrap();
+        let categorizer = Categorizer::load(temp.path());
+        assert_eq!(categorizer.category("", "example.com"), Some("Arbeit"));
+        assert_eq!(categorizer.category("", "paypal.com"), None);
+
+        fs::write(temp.path().join(RULES_FILE), "broken").unwrap();
+        assert_eq!(
+            Categorizer::load(temp.path()),
+            Categorizer::parse(BUNDLED_RULES).unwrap()
+        );
+    }
+}
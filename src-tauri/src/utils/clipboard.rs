@@ -0,0 +1,250 @@
+use arboard::Clipboard;
+use log::{error, info};
+use ring::digest::{digest, SHA256};
+use serde::Serialize;
+use std::sync::atomic::{AtomicU64, Ordering};
+use std::sync::Mutex;
+
+#[cfg(target_os = "macos")]
+use arboard::SetExtApple;
+#[cfg(target_os = "linux")]
+use arboard::SetExtLinux;
+#[cfg(target_os = "windows")]
+use arboard::SetExtWindows;
+
+#[derive(Clone, Serialize)]
+pub struct ClipboardProtection {
+    pub platform: String,
+    pub excluded_from_history: bool,
+    pub excluded_from_cloud: bool,
+    pub note: String,
+}
+
+pub struct SecureClipboard {
+    clipboard: Mutex<Option<Clipboard>>,
+    last_copied: Mutex<Option<Vec<u8>>>,
+    copies: AtomicU64,
+}
+
+impl ClipboardProtection {
+    /// Describe how copied secrets are protected on the current platform
+    ///
+    /// # Returns
+    ///
+    /// The clipboard protection of the current platform
+    pub fn current() -> Self {
+        #[cfg(target_os = "windows")]
+        {
+            Self {
+                platform: "windows".to_string(),
+                excluded_from_history: true,
+                excluded_from_cloud: true,
+                note: "Kopierte Passwörter werden vom Windows-Clipboard-Verlauf und der Cloud-Synchronisierung ausgeschlossen.".to_string(),
+            }
+        }
+
+        #[cfg(target_os = "macos")]
+        {
+            Self {
+                platform: "macos".to_string(),
+                excluded_from_history: true,
+                excluded_from_cloud: false,
+                note: "Kopierte Passwörter werden als vertraulich markiert und von Clipboard-Managern ignoriert, die dies unterstützen. Die universelle Zwischenablage kann sie dennoch übertragen.".to_string(),
+            }
+        }
+
+        #[cfg(target_os = "linux")]
+        {
+            Self {
+                platform: "linux".to_string(),
+                excluded_from_history: true,
+                excluded_from_cloud: true,
+                note: "Kopierte Passwörter werden als Passwort markiert und von KDE Klipper und kompatiblen Clipboard-Managern nicht gespeichert.".to_string(),
+            }
+        }
+
+        #[cfg(not(any(target_os = "windows", target_os = "macos", target_os = "linux")))]
+        {
+            Self {
+                platform: std::env::consts::OS.to_string(),
+                excluded_from_history: false,
+                excluded_from_cloud: false,
+                note:
+                    "Auf dieser Plattform kann der Clipboard-Verlauf kopierte Passwörter speichern."
+                        .to_string(),
+            }
+        }
+    }
+}
+
+impl SecureClipboard {
+    /// Create a new SecureClipboard
+    ///
+    /// # Returns
+    ///
+    /// A new SecureClipboard instance
+    pub fn new() -> Self {
+        let clipboard = match Clipboard::new() {
+            Ok(clipboard) => Some(clipboard),
+            Err(e) => {
+                error!("Failed to open clipboard: {}", e);
+                None
+            }
+        };
+
+        Self {
+            clipboard: Mutex::new(clipboard),
+            last_copied: Mutex::new(None),
+            copies: AtomicU64::new(0),
+        }
+    }
+
+    /// Copy text to the clipboard and exclude it from clipboard history where supported
+    ///
+    /// # Arguments
+    ///
+    /// * `text` - The text to copy
+    ///
+    /// # Returns
+    ///
+    /// The clipboard protection that was applied
+    ///
+    /// # Errors
+    ///
+    /// Returns an error if the clipboard is not available or the text cannot be copied
+    pub fn copy(&self, text: &str) -> Result<ClipboardProtection, Box<dyn std::error::Error>> {
+        let mut clipboard = self.clipboard.lock().unwrap();
+        let clipboard = clipboard.as_mut().ok_or("Clipboard not available")?;
+
+        let set = clipboard.set();
+
+        #[cfg(target_os = "windows")]
+        let set = set.exclude_from_history().exclude_from_cloud();
+        #[cfg(any(target_os = "linux", target_os = "macos"))]
+        let set = set.exclude_from_history();
+
+        set.text(text)?;
+
+        *self.last_copied.lock().unwrap() = Some(Self::fingerprint(text));
+        self.copies.fetch_add(1, Ordering::SeqCst);
+
+        Ok(ClipboardProtection::current())
+    }
+
+    /// Read the text currently in the clipboard
+    ///
+    /// # Returns
+    ///
+    /// The text in the clipboard
+    ///
+    /// # Errors
+    ///
+    /// Returns an error if the clipboard is not available or does not contain text
+    pub fn read_text(&self) -> Result<String, Box<dyn std::error::Error>> {
+        let mut clipboard = self.clipboard.lock().unwrap();
+        let clipboard = clipboard.as_mut().ok_or("Clipboard not available")?;
+
+        Ok(clipboard.get_text()?)
+    }
+
+    /// Clear the clipboard if it still contains the last copied text
+    ///
+    /// # Returns
+    ///
+    /// Returns `true` if the clipboard was cleared
+    ///
+    /// # Errors
+    ///
+    /// Returns an error if the clipboard cannot be cleared
+    pub fn clear(&self) -> Result<bool, Box<dyn std::error::Error>> {
+        let Some(last_copied) = self.last_copied.lock().unwrap().take() else {
+            return Ok(false);
+        };
+
+        let mut clipboard = self.clipboard.lock().unwrap();
+        let Some(clipboard) = clipboard.as_mut() else {
+            return Ok(false);
+        };
+
+        let still_ours = clipboard
+            .get_text()
+            .is_ok_and(|text| Self::fingerprint(&text) == last_copied);
+
+        if still_ours {
+            clipboard.clear()?;
+            info!("Cleared copied secret from clipboard");
+        }
+
+        Ok(still_ours)
+    }
+
+    /// Count the texts copied so far
+    ///
+    /// # Returns
+    ///
+    /// The number of copies, used to detect if something was copied in the meantime
+    pub fn copies(&self) -> u64 {
+        self.copies.load(Ordering::SeqCst)
+    }
+
+    /// Clear the clipboard unless another text was copied since the given copy
+    ///
+    /// # Arguments
+    ///
+    /// * `copies` - The number of copies right after the text was copied
+    ///
+    /// # Returns
+    ///
+    /// Returns `true` if the clipboard was cleared
+    ///
+    /// # Errors
+    ///
+    /// Returns an error if the clipboard cannot be cleared
+    pub fn clear_unless_copied_since(
+        &self,
+        copies: u64,
+    ) -> Result<bool, Box<dyn std::error::Error>> {
+        if self.copies() != copies {
+            return Ok(false);
+        }
+
+        self.clear()
+    }
+
+    /// Release the clipboard before the application exits
+    pub fn shutdown(&self) {
+        if let Err(e) = self.clear() {
+            error!("Failed to clear clipboard: {}", e);
+        }
+        self.clipboard.lock().unwrap().take();
+    }
+
+    /// Hash the text so the copied secret is not kept in memory
+    fn fingerprint(text: &str) -> Vec<u8> {
+        digest(&SHA256, text.as_bytes()).as_ref().to_vec()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_protection_matches_platform() {
+        let protection = ClipboardProtection::current();
+        assert_eq!(protection.platform, std::env::consts::OS);
+        assert!(!protection.note.is_empty());
+    }
+
+    #[test]
+    fn test_fingerprint() {
+        assert_eq!(
+            SecureClipboard::fingerprint("secret"),
+            SecureClipboard::fingerprint("secret")
+        );
+        assert_ne!(
+            SecureClipboard::fingerprint("secret"),
+            SecureClipboard::fingerprint("other")
+        );
+    }
+}
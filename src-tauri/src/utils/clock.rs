@@ -0,0 +1,81 @@
+#[cfg(test)]
+use chrono::Duration;
+use chrono::{DateTime, Utc};
+#[cfg(test)]
+use std::sync::Mutex;
+
+/// Source of the current time, so time-based logic can be tested deterministically.
+pub trait Clock: Send + Sync {
+    /// Get the current time
+    fn now(&self) -> DateTime<Utc>;
+
+    /// Get the current time in seconds since the Unix epoch
+    fn timestamp(&self) -> u64 {
+        self.now().timestamp().max(0) as u64
+    }
+}
+
+/// The system clock.
+pub struct SystemClock;
+
+impl Clock for SystemClock {
+    fn now(&self) -> DateTime<Utc> {
+        Utc::now()
+    }
+}
+
+/// A clock that only moves when it is advanced.
+#[cfg(test)]
+pub struct FixedClock {
+    now: Mutex<DateTime<Utc>>,
+}
+
+#[cfg(test)]
+impl FixedClock {
+    /// Create a new clock stopped at the given time
+    ///
+    /// # Arguments
+    ///
+    /// * `now` - The time the clock reports
+    ///
+    /// # Returns
+    ///
+    /// A new FixedClock
+    pub fn new(now: DateTime<Utc>) -> Self {
+        Self {
+            now: Mutex::new(now),
+        }
+    }
+
+    /// Move the clock forward
+    ///
+    /// # Arguments
+    ///
+    /// * `duration` - The time to advance the clock by
+    pub fn advance(&self, duration: Duration) {
+        *self.now.lock().unwrap() += duration;
+    }
+}
+
+#[cfg(test)]
+impl Clock for FixedClock {
+    fn now(&self) -> DateTime<Utc> {
+        *self.now.lock().unwrap()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_fixed_clock() {
+        let start = DateTime::from_timestamp(1_700_000_000, 0).unwrap();
+        let clock = FixedClock::new(start);
+        assert_eq!(clock.now(), start);
+        assert_eq!(clock.timestamp(), 1_700_000_000);
+
+        clock.advance(Duration::minutes(5));
+        assert_eq!(clock.timestamp(), 1_700_000_300);
+    }
+}
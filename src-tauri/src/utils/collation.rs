@@ -0,0 +1,111 @@
+use icu_collator::options::{CollatorOptions, Strength};
+use icu_collator::{Collator, CollatorBorrowed};
+use icu_locale_core::Locale;
+use std::cmp::Ordering;
+
+/// The locale used for sorting and searching if none is configured.
+pub const DEFAULT_LOCALE: &str = "de";
+
+/// Locale-aware comparison of text, so umlauts sort next to their base letter.
+pub struct Collation {
+    /// Compares whole strings for sorting.
+    sort: CollatorBorrowed<'static>,
+    /// Compares single letters for searching, ignoring case and accents.
+    search: CollatorBorrowed<'static>,
+}
+
+impl Collation {
+    /// Create the collation of a locale
+    ///
+    /// # Arguments
+    ///
+    /// * `locale` - The BCP 47 language tag, e.g. `de` or `de-u-co-phonebk`
+    ///
+    /// # Returns
+    ///
+    /// The collation of the locale
+    ///
+    /// # Errors
+    ///
+    /// Returns an error if the locale is invalid or has no collation data
+    pub fn new(locale: &str) -> Result<Self, Box<dyn std::error::Error>> {
+        let locale =
+            Locale::try_from_str(locale).map_err(|_| format!("Invalid locale: {}", locale))?;
+
+        let sort = Collator::try_new((&locale).into(), CollatorOptions::default())
+            .map_err(|e| format!("No collation for {}: {}", locale, e))?;
+        let mut options = CollatorOptions::default();
+        options.strength = Some(Strength::Primary);
+        let search = Collator::try_new((&locale).into(), options)
+            .map_err(|e| format!("No collation for {}: {}", locale, e))?;
+
+        Ok(Self { sort, search })
+    }
+
+    /// Compare two strings in the order of the locale
+    pub fn compare(&self, a: &str, b: &str) -> Ordering {
+        self.sort.compare(a, b)
+    }
+
+    /// Check if two characters are the same letter in the locale
+    ///
+    /// Case and accents are ignored where the locale treats them as variants of the same
+    /// letter, e.g. `u` matches `ü` in German but `a` does not match `å` in Swedish.
+    pub fn same_letter(&self, a: char, b: char) -> bool {
+        if fold(a) == fold(b) {
+            return true;
+        }
+
+        let (mut a_buf, mut b_buf) = ([0; 4], [0; 4]);
+        self.search
+            .compare(a.encode_utf8(&mut a_buf), b.encode_utf8(&mut b_buf))
+            == Ordering::Equal
+    }
+}
+
+impl Default for Collation {
+    fn default() -> Self {
+        Self::new(DEFAULT_LOCALE).expect("The default locale has collation data")
+    }
+}
+
+/// Lowercase a character while keeping one character per character.
+pub fn fold(c: char) -> char {
+    c.to_lowercase().next().unwrap_or(c)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_compare() {
+        let collation = Collation::new("de").unwrap();
+        let mut services = vec!["Zalando", "Übersee", "apple", "Amazon", "Ärzte"];
+        services.sort_by(|a, b| collation.compare(a, b));
+        assert_eq!(
+            services,
+            vec!["Amazon", "apple", "Ärzte", "Übersee", "Zalando"]
+        );
+
+        let swedish = Collation::new("sv").unwrap();
+        assert_eq!(swedish.compare("Ärzte", "Zalando"), Ordering::Greater);
+
+        let phonebook = Collation::new("de-u-co-phonebk").unwrap();
+        assert_eq!(phonebook.compare("Müller", "Mueller"), Ordering::Greater);
+        assert_eq!(phonebook.compare("Müller", "Mufti"), Ordering::Less);
+
+        assert!(Collation::new("not a locale!").is_err());
+    }
+
+    #[test]
+    fn test_same_letter() {
+        let collation = Collation::default();
+        assert!(collation.same_letter('u', 'Ü'));
+        assert!(collation.same_letter('E', 'é'));
+        assert!(!collation.same_letter('u', 'o'));
+
+        let swedish = Collation::new("sv").unwrap();
+        assert!(!swedish.same_letter('a', 'å'));
+    }
+}
@@ -1,8 +1,28 @@
 use env_logger::{Builder, WriteStyle};
-use log::LevelFilter;
+use log::{warn, LevelFilter};
 use serde::{Deserialize, Serialize};
 use std::fs::OpenOptions;
-use std::path::PathBuf;
+use std::path::{Path, PathBuf};
+
+use super::{
+    BackupNaming, Collation, ConfirmationPin, DEFAULT_BACKUP_NAME, DEFAULT_LOCALE,
+    FIREFOX_EXTENSION_ID,
+};
+
+pub const VAULT_LOCATION_UNAVAILABLE: &str = "Vault location unavailable";
+
+/// The maximum number of characters in a custom symbol set of the generator.
+const MAX_CUSTOM_SYMBOLS: usize = 32;
+const PORTABLE_SECTIONS: [&str; 6] = ["logging", "app", "generator", "backup", "entry", "view"];
+const MACHINE_SPECIFIC_KEYS: [(&str, &str); 7] = [
+    ("app", "is_initialized"),
+    ("app", "confirmation_pin"),
+    ("view", "last_tab"),
+    ("backup", "backup_path"),
+    ("backup", "export_path"),
+    ("backup", "last_backup"),
+    ("entry", "attachment_scanner"),
+];
 
 #[derive(Clone, Serialize, Deserialize)]
 pub struct Config {
@@ -11,12 +31,92 @@ pub struct Config {
     pub app: AppConfig,
     pub generator: GeneratorConfig,
     pub backup: BackupConfig,
+    #[serde(default)]
+    pub entry: EntryConfig,
+    #[serde(default)]
+    pub view: ViewConfig,
+    #[serde(default)]
+    pub browser: BrowserConfig,
 }
 
 #[derive(Clone, Serialize, Deserialize)]
 pub struct AppConfig {
     pub is_initialized: bool,
     pub auto_logout_duration: u64,
+    #[serde(default = "default_session_max_lifetime")]
+    pub session_max_lifetime: u64,
+    #[serde(default)]
+    pub content_protection: bool,
+    #[serde(default)]
+    pub soft_keyboard: bool,
+    #[serde(default)]
+    pub lock_on_minimize: bool,
+    #[serde(default)]
+    pub lock_on_blur: bool,
+    #[serde(default = "default_lock_delay")]
+    pub lock_delay: u64,
+    /// The minutes without input in the whole system before the vault is locked, 0 if disabled.
+    #[serde(default)]
+    pub lock_on_idle: u64,
+    #[serde(default)]
+    pub lock_on_suspend: bool,
+    #[serde(default)]
+    pub offline_mode: bool,
+    /// Check the passwords against Have I Been Pwned, which sends hash prefixes to the API.
+    #[serde(default)]
+    pub breach_check: bool,
+    #[serde(default)]
+    pub autostart_mode: AutostartMode,
+    #[serde(default)]
+    pub rpc_enabled: bool,
+    /// Hide the main window in the tray instead of quitting when it is closed.
+    #[serde(default)]
+    pub minimize_to_tray: bool,
+    /// The global shortcut that opens the quick search, e.g. `Ctrl+Shift+K`, None if disabled.
+    #[serde(default)]
+    pub quick_search_shortcut: Option<String>,
+    /// Keep the main window above the windows of other applications.
+    #[serde(default)]
+    pub always_on_top: bool,
+    /// The opacity of the main window in percent while it is kept on top.
+    #[serde(default = "default_window_opacity")]
+    pub window_opacity: u8,
+    #[serde(default = "default_rpc_port")]
+    pub rpc_port: u16,
+    /// The PIN required before exporting passwords or replacing the vault, None if disabled.
+    #[serde(default)]
+    pub confirmation_pin: Option<ConfirmationPin>,
+    /// The secrets that can be decrypted per minute without the master password, 0 if unlimited.
+    #[serde(default)]
+    pub decrypt_limit: u32,
+    /// Show a desktop notification about expired or soon expiring passwords after login.
+    #[serde(default)]
+    pub expiry_reminder: bool,
+}
+
+#[derive(Clone, Copy, Default, Debug, PartialEq, Serialize, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum AutostartMode {
+    Tray,
+    #[default]
+    Locked,
+    LastView,
+}
+
+fn default_window_opacity() -> u8 {
+    100
+}
+
+fn default_rpc_port() -> u16 {
+    47653
+}
+
+fn default_session_max_lifetime() -> u64 {
+    480
+}
+
+fn default_lock_delay() -> u64 {
+    30
 }
 
 #[derive(Clone, Serialize, Deserialize)]
@@ -28,11 +128,383 @@ pub struct LogConfig {
 pub struct DatabaseConfig {
     pub db_name: String,
     pub db_path: PathBuf,
+    #[serde(default)]
+    pub sync_safe_mode: bool,
+    /// Encrypt usernames, URLs and notes in addition to the database ("paranoid mode").
+    #[serde(default)]
+    pub encrypt_metadata: bool,
 }
 
 #[derive(Clone, Serialize, Deserialize)]
 pub struct GeneratorConfig {
     pub default_length: usize,
+    #[serde(default)]
+    pub options: GeneratorOptions,
+    /// The number of generated passwords kept in the encrypted history, 0 to keep none.
+    #[serde(default)]
+    pub history_size: usize,
+}
+
+/// Whether the generator builds passwords from characters or passphrases from words.
+#[derive(Clone, Copy, Default, Debug, PartialEq, Serialize, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum GeneratorMode {
+    #[default]
+    Characters,
+    Passphrase,
+}
+
+/// The settings of the password generator besides the length.
+#[derive(Clone, Debug, PartialEq, Serialize, Deserialize)]
+pub struct GeneratorOptions {
+    #[serde(default = "enabled")]
+    pub uppercase: bool,
+    #[serde(default = "enabled")]
+    pub lowercase: bool,
+    #[serde(default = "enabled")]
+    pub digits: bool,
+    #[serde(default = "enabled")]
+    pub symbols: bool,
+    /// Leave out characters that are easily confused, like `l`, `1` and `O`.
+    #[serde(default)]
+    pub exclude_ambiguous: bool,
+    /// The special characters to use instead of the default ones, empty for the default.
+    #[serde(default)]
+    pub custom_symbols: String,
+    #[serde(default)]
+    pub mode: GeneratorMode,
+    #[serde(default = "default_passphrase_words")]
+    pub passphrase_words: usize,
+}
+
+fn enabled() -> bool {
+    true
+}
+
+fn default_passphrase_words() -> usize {
+    6
+}
+
+impl Default for GeneratorOptions {
+    fn default() -> Self {
+        Self {
+            uppercase: true,
+            lowercase: true,
+            digits: true,
+            symbols: true,
+            exclude_ambiguous: false,
+            custom_symbols: String::new(),
+            mode: GeneratorMode::default(),
+            passphrase_words: default_passphrase_words(),
+        }
+    }
+}
+
+impl GeneratorOptions {
+    /// Check the custom symbol set.
+    ///
+    /// The set may only contain printable ASCII characters that are neither letters nor
+    /// digits, so every character counts as a special character.
+    ///
+    /// # Returns
+    ///
+    /// A Result containing a unit or an error.
+    ///
+    /// # Errors
+    ///
+    /// If the set is too long or contains a character that is not allowed.
+    pub fn check_custom_symbols(&self) -> Result<(), Box<dyn std::error::Error>> {
+        if self.custom_symbols.chars().count() > MAX_CUSTOM_SYMBOLS {
+            return Err(format!(
+                "Custom symbols must not exceed {} characters",
+                MAX_CUSTOM_SYMBOLS
+            )
+            .into());
+        }
+        if let Some(c) = self
+            .custom_symbols
+            .chars()
+            .find(|c| !c.is_ascii_punctuation())
+        {
+            return Err(format!("Invalid custom symbol: {:?}", c).into());
+        }
+
+        Ok(())
+    }
+}
+
+#[derive(Clone, Serialize, Deserialize)]
+pub struct ViewConfig {
+    #[serde(default)]
+    pub default_tab: DefaultTab,
+    #[serde(default)]
+    pub default_sort: SortOrder,
+    #[serde(default)]
+    pub last_tab: DefaultTab,
+    #[serde(default)]
+    pub double_click: RowAction,
+    /// The language tag that decides how entries are sorted and searched.
+    #[serde(default = "default_locale")]
+    pub locale: String,
+}
+
+fn default_locale() -> String {
+    DEFAULT_LOCALE.to_string()
+}
+
+impl Default for ViewConfig {
+    fn default() -> Self {
+        Self {
+            default_tab: DefaultTab::default(),
+            default_sort: SortOrder::default(),
+            last_tab: DefaultTab::default(),
+            double_click: RowAction::default(),
+            locale: default_locale(),
+        }
+    }
+}
+
+impl ViewConfig {
+    /// Get the tab to show after unlocking the vault.
+    ///
+    /// # Arguments
+    ///
+    /// * `autostart_mode` - The autostart mode, if the app was launched by the autostart.
+    ///
+    /// # Returns
+    ///
+    /// The last used tab when autostarted into the last view, otherwise the default tab.
+    pub fn launch_tab(&self, autostart_mode: Option<AutostartMode>) -> DefaultTab {
+        match autostart_mode {
+            Some(AutostartMode::LastView) => self.last_tab,
+            _ => self.default_tab,
+        }
+    }
+
+    /// Get the collation of the configured locale.
+    ///
+    /// # Returns
+    ///
+    /// The collation of the locale or of the default locale if it is not supported.
+    pub fn collation(&self) -> Collation {
+        Collation::new(&self.locale).unwrap_or_else(|e| {
+            warn!("Falling back to the default locale: {}", e);
+            Collation::default()
+        })
+    }
+}
+
+#[derive(Clone, Copy, Default, Debug, PartialEq, Serialize, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum DefaultTab {
+    #[default]
+    Dashboard,
+    Passwords,
+    Notes,
+    Wallet,
+    Servers,
+    Generator,
+}
+
+#[derive(Clone, Copy, Default, Debug, PartialEq, Serialize, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum SortOrder {
+    #[default]
+    ServiceAsc,
+    ServiceDesc,
+}
+
+/// What double-clicking an entry in the password table does.
+#[derive(Clone, Copy, Default, Debug, PartialEq, Serialize, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum RowAction {
+    #[default]
+    CopyPassword,
+    CopyUsername,
+    OpenDetail,
+    OpenUrl,
+}
+
+#[derive(Clone, Serialize, Deserialize)]
+pub struct EntryConfig {
+    #[serde(default = "default_max_notes_length")]
+    pub max_notes_length: usize,
+    /// Days a deleted entry stays in the trash before it is purged, 0 keeps it until the trash is emptied.
+    #[serde(default = "default_trash_retention_days")]
+    pub trash_retention_days: u32,
+    /// The largest file that can be attached to an entry, in KiB.
+    #[serde(default = "default_max_attachment_kb")]
+    pub max_attachment_kb: u64,
+    /// The extensions of the files that can be attached, any file is allowed if empty.
+    #[serde(default = "default_attachment_extensions")]
+    pub attachment_extensions: Vec<String>,
+    /// A command that scans files before they are attached, it gets the path as the last
+    /// argument and a non-zero exit code rejects the file.
+    #[serde(default)]
+    pub attachment_scanner: Option<String>,
+}
+
+fn default_max_notes_length() -> usize {
+    1000
+}
+
+fn default_max_attachment_kb() -> u64 {
+    5120
+}
+
+fn default_attachment_extensions() -> Vec<String> {
+    ["pdf", "txt", "png", "jpg", "jpeg"]
+        .map(String::from)
+        .to_vec()
+}
+
+fn default_trash_retention_days() -> u32 {
+    30
+}
+
+impl Default for EntryConfig {
+    fn default() -> Self {
+        Self {
+            max_notes_length: default_max_notes_length(),
+            trash_retention_days: default_trash_retention_days(),
+            max_attachment_kb: default_max_attachment_kb(),
+            attachment_extensions: default_attachment_extensions(),
+            attachment_scanner: None,
+        }
+    }
+}
+
+/// The connection to browser extensions over native messaging.
+#[derive(Clone, Serialize, Deserialize)]
+pub struct BrowserConfig {
+    /// Let browser extensions fill and save logins, needs the JSON-RPC server of the app.
+    #[serde(default)]
+    pub enabled: bool,
+    /// The IDs of the extensions allowed to start the native messaging host.
+    #[serde(default = "default_extension_ids")]
+    pub extension_ids: Vec<String>,
+    /// The base domains whose logins extensions may read without asking.
+    #[serde(default)]
+    pub authorized_sites: Vec<String>,
+}
+
+fn default_extension_ids() -> Vec<String> {
+    vec![FIREFOX_EXTENSION_ID.to_string()]
+}
+
+impl Default for BrowserConfig {
+    fn default() -> Self {
+        Self {
+            enabled: false,
+            extension_ids: default_extension_ids(),
+            authorized_sites: Vec::new(),
+        }
+    }
+}
+
+impl BrowserConfig {
+    /// Check if extensions may read the logins of a site without asking.
+    ///
+    /// # Arguments
+    ///
+    /// * `site` - The base domain of the site.
+    ///
+    /// # Returns
+    ///
+    /// True if the site was authorized permanently.
+    pub fn is_authorized(&self, site: &str) -> bool {
+        self.authorized_sites.iter().any(|s| s == site)
+    }
+
+    /// Let extensions read the logins of a site without asking.
+    ///
+    /// # Arguments
+    ///
+    /// * `site` - The base domain of the site.
+    pub fn authorize_site(&mut self, site: &str) {
+        if !self.is_authorized(site) {
+            self.authorized_sites.push(site.to_string());
+            self.authorized_sites.sort();
+        }
+    }
+}
+
+impl EntryConfig {
+    /// Check the notes of an entry against the configured limit.
+    ///
+    /// The length is counted in characters, so umlauts count the same as other letters.
+    ///
+    /// # Arguments
+    ///
+    /// * `notes` - The notes to check.
+    ///
+    /// # Returns
+    ///
+    /// A Result containing a unit or an error.
+    ///
+    /// # Errors
+    ///
+    /// If the notes exceed the limit, the error contains the current usage.
+    pub fn check_notes(&self, notes: &str) -> Result<(), Box<dyn std::error::Error>> {
+        let length = notes.chars().count();
+        if length > self.max_notes_length {
+            return Err(format!(
+                "Notes must not exceed {} characters ({} used)",
+                self.max_notes_length, length
+            )
+            .into());
+        }
+
+        Ok(())
+    }
+
+    /// Check a file against the allowed extensions and the size limit of attachments.
+    ///
+    /// # Arguments
+    ///
+    /// * `file_name` - The name of the file.
+    /// * `size` - The size of the file in bytes.
+    ///
+    /// # Returns
+    ///
+    /// A Result containing a unit or an error.
+    ///
+    /// # Errors
+    ///
+    /// If the file type is not allowed or the file is too large.
+    pub fn check_attachment(
+        &self,
+        file_name: &str,
+        size: u64,
+    ) -> Result<(), Box<dyn std::error::Error>> {
+        let extension = Path::new(file_name)
+            .extension()
+            .and_then(|extension| extension.to_str())
+            .unwrap_or_default()
+            .to_lowercase();
+        if !self.attachment_extensions.is_empty()
+            && !self.attachment_extensions.iter().any(|allowed| {
+                allowed
+                    .trim_start_matches('.')
+                    .eq_ignore_ascii_case(&extension)
+            })
+        {
+            return Err(format!(
+                "Only {} files can be attached",
+                self.attachment_extensions.join(", ")
+            )
+            .into());
+        }
+        if size > self.max_attachment_kb * 1024 {
+            return Err(format!(
+                "Attachments must not be larger than {} KiB",
+                self.max_attachment_kb
+            )
+            .into());
+        }
+
+        Ok(())
+    }
 }
 
 #[derive(Clone, Serialize, Deserialize)]
@@ -43,22 +515,42 @@ pub struct BackupConfig {
     pub backup_path: PathBuf,
     pub last_backup: Option<String>,
     pub export_path: PathBuf,
+    #[serde(default)]
+    pub recipients: Vec<BackupRecipient>,
+    #[serde(default)]
+    pub incremental: bool,
+    #[serde(default = "default_full_backup_every")]
+    pub full_backup_every: usize,
+    #[serde(default)]
+    pub quota_mb: u64,
+    /// The name of backup files with the placeholders `{date}`, `{hostname}` and `{vault}`.
+    #[serde(default = "default_file_name_template")]
+    pub file_name_template: String,
+}
+
+fn default_full_backup_every() -> usize {
+    6
+}
+
+fn default_file_name_template() -> String {
+    DEFAULT_BACKUP_NAME.to_string()
 }
 
 #[derive(Clone, Serialize, Deserialize)]
+pub struct BackupRecipient {
+    pub name: String,
+    pub public_key: String,
+}
+
+#[derive(Clone, Default, Serialize, Deserialize)]
 pub enum BackupInterval {
     Daily,
+    #[default]
     Weekly,
     Monthly,
     Yearly,
 }
 
-impl Default for BackupInterval {
-    fn default() -> Self {
-        Self::Weekly
-    }
-}
-
 impl Default for Config {
     fn default() -> Self {
         let config_dir = Config::get_config_dir().unwrap();
@@ -72,12 +564,41 @@ impl Default for Config {
             database: DatabaseConfig {
                 db_name: "pass.db".to_string(),
                 db_path: config_dir.clone(),
+                sync_safe_mode: false,
+                encrypt_metadata: false,
             },
             app: AppConfig {
                 is_initialized: false,
                 auto_logout_duration: 10,
+                session_max_lifetime: default_session_max_lifetime(),
+                content_protection: false,
+                soft_keyboard: false,
+                lock_on_minimize: false,
+                lock_on_blur: false,
+                lock_delay: default_lock_delay(),
+                lock_on_idle: 0,
+                lock_on_suspend: false,
+                offline_mode: false,
+                breach_check: false,
+                autostart_mode: AutostartMode::default(),
+                rpc_enabled: false,
+                minimize_to_tray: false,
+                quick_search_shortcut: None,
+                always_on_top: false,
+                window_opacity: default_window_opacity(),
+                rpc_port: default_rpc_port(),
+                confirmation_pin: None,
+                decrypt_limit: 0,
+                expiry_reminder: false,
+            },
+            generator: GeneratorConfig {
+                default_length: 16,
+                options: GeneratorOptions::default(),
+                history_size: 0,
             },
-            generator: GeneratorConfig { default_length: 16 },
+            entry: EntryConfig::default(),
+            view: ViewConfig::default(),
+            browser: BrowserConfig::default(),
             backup: BackupConfig {
                 enabled: false,
                 interval: BackupInterval::default(),
@@ -85,6 +606,11 @@ impl Default for Config {
                 backup_path: config_dir.join("backups"),
                 last_backup: None,
                 export_path,
+                recipients: Vec::new(),
+                incremental: false,
+                full_backup_every: default_full_backup_every(),
+                quota_mb: 0,
+                file_name_template: default_file_name_template(),
             },
         }
     }
@@ -130,6 +656,20 @@ impl Config {
         Ok(())
     }
 
+    /// Get the naming of backup files from the configured template.
+    ///
+    /// # Returns
+    ///
+    /// The naming of the template or the default naming if the template is invalid.
+    pub fn backup_naming(&self) -> BackupNaming {
+        BackupNaming::new(&self.backup.file_name_template, &self.database.db_name).unwrap_or_else(
+            |e| {
+                warn!("Falling back to the default backup name: {}", e);
+                BackupNaming::default()
+            },
+        )
+    }
+
     /// Get the config directory.
     ///
     /// # Returns
@@ -209,7 +749,9 @@ impl Config {
 
     /// Get the database directory.
     ///
-    /// If the database directory does not exist, create it.
+    /// If the database directory does not exist during the setup, create it. Once the app
+    /// is initialized a missing directory means the vault location is unavailable, e.g. an
+    /// unmounted drive, and is never created again.
     ///
     /// # Returns
     ///
@@ -217,17 +759,29 @@ impl Config {
     ///
     /// # Errors
     ///
-    /// If the database file path cannot be created.
+    /// If the database file path cannot be created or the vault location is unavailable.
     pub fn get_db_dir(&self) -> Result<&PathBuf, Box<dyn std::error::Error>> {
         let db_dir = &self.database.db_path;
 
         if !db_dir.exists() {
+            if self.app.is_initialized {
+                return Err(VAULT_LOCATION_UNAVAILABLE.into());
+            }
             std::fs::create_dir_all(db_dir)?;
         }
 
         Ok(db_dir)
     }
 
+    /// Check if the database of an initialized app can be reached.
+    ///
+    /// # Returns
+    ///
+    /// A boolean indicating if the database directory and file exist.
+    pub fn is_vault_available(&self) -> bool {
+        !self.app.is_initialized || self.database.db_path.join(&self.database.db_name).is_file()
+    }
+
     /// Get the directory for backups.
     ///
     /// If the backup directory does not exist, create it.
@@ -248,4 +802,300 @@ impl Config {
 
         Ok(backup_dir)
     }
+
+    /// Export the settings that can be used on another machine.
+    ///
+    /// The database location, the backup and export paths, the backup history and the
+    /// initialization state belong to this machine and are left out, as is the
+    /// confirmation PIN.
+    ///
+    /// # Returns
+    ///
+    /// A Result containing the settings as TOML or an error.
+    ///
+    /// # Errors
+    ///
+    /// If the settings cannot be serialized.
+    pub fn export_settings(&self) -> Result<String, Box<dyn std::error::Error>> {
+        let mut exported = toml::Table::new();
+        let config = toml::Table::try_from(self)?;
+
+        for section in PORTABLE_SECTIONS {
+            if let Some(toml::Value::Table(table)) = config.get(section) {
+                let table = table
+                    .iter()
+                    .filter(|(key, _)| !Self::is_machine_specific(section, key))
+                    .map(|(key, value)| (key.clone(), value.clone()))
+                    .collect();
+                exported.insert(section.to_string(), toml::Value::Table(table));
+            }
+        }
+
+        Ok(toml::to_string_pretty(&exported)?)
+    }
+
+    /// Apply exported settings to this configuration.
+    ///
+    /// Machine specific and unknown settings in the file are ignored.
+    ///
+    /// # Arguments
+    ///
+    /// * `content` - The settings exported by `export_settings`.
+    ///
+    /// # Returns
+    ///
+    /// A Result containing the updated configuration or an error.
+    ///
+    /// # Errors
+    ///
+    /// If the settings file is invalid or contains values of the wrong type.
+    pub fn import_settings(&self, content: &str) -> Result<Config, Box<dyn std::error::Error>> {
+        let imported: toml::Table = toml::from_str(content)?;
+        let mut config = toml::Table::try_from(self)?;
+
+        for section in PORTABLE_SECTIONS {
+            let (Some(toml::Value::Table(source)), Some(toml::Value::Table(target))) =
+                (imported.get(section), config.get_mut(section))
+            else {
+                continue;
+            };
+
+            for (key, value) in source {
+                if !Self::is_machine_specific(section, key) {
+                    target.insert(key.clone(), value.clone());
+                }
+            }
+        }
+
+        Ok(config.try_into()?)
+    }
+
+    /// Check the confirmation PIN before a destructive action.
+    ///
+    /// # Arguments
+    ///
+    /// * `pin` - The PIN entered by the user.
+    ///
+    /// # Returns
+    ///
+    /// A Result containing a unit or an error.
+    ///
+    /// # Errors
+    ///
+    /// If a confirmation PIN is set and the given PIN is missing or wrong.
+    pub fn check_confirmation_pin(
+        &self,
+        pin: Option<&str>,
+    ) -> Result<(), Box<dyn std::error::Error>> {
+        match (&self.app.confirmation_pin, pin) {
+            (None, _) => Ok(()),
+            (Some(_), None) => Err("The confirmation PIN is required".into()),
+            (Some(stored), Some(pin)) if stored.verify(pin) => Ok(()),
+            (Some(_), Some(_)) => Err("Invalid confirmation PIN".into()),
+        }
+    }
+
+    fn is_machine_specific(section: &str, key: &str) -> bool {
+        MACHINE_SPECIFIC_KEYS.contains(&(section, key))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn test_config() -> Config {
+        toml::from_str(
+            r#"
+            [logging]
+            level = "info"
+
+            [database]
+            db_name = "pass.db"
+            db_path = "/config"
+
+            [app]
+            is_initialized = false
+            auto_logout_duration = 10
+
+            [generator]
+            default_length = 16
+
+            [backup]
+            enabled = false
+            interval = "Weekly"
+            max_backups = 7
+            backup_path = "/config/backups"
+            export_path = "/documents/exports"
+            "#,
+        )
+        .unwrap()
+    }
+
+    #[test]
+    fn test_launch_tab() {
+        let mut config = test_config();
+        config.view.default_tab = DefaultTab::Passwords;
+        config.view.last_tab = DefaultTab::Generator;
+
+        assert_eq!(config.view.launch_tab(None), DefaultTab::Passwords);
+        assert_eq!(
+            config.view.launch_tab(Some(AutostartMode::Locked)),
+            DefaultTab::Passwords
+        );
+        assert_eq!(
+            config.view.launch_tab(Some(AutostartMode::LastView)),
+            DefaultTab::Generator
+        );
+        assert!(!config.export_settings().unwrap().contains("last_tab"));
+    }
+
+    #[test]
+    fn test_check_notes() {
+        let mut config = test_config();
+        assert_eq!(config.entry.max_notes_length, 1000);
+
+        config.entry.max_notes_length = 5;
+        assert!(config.entry.check_notes("").is_ok());
+        assert!(config.entry.check_notes("Größe").is_ok());
+
+        let error = config.entry.check_notes("Größer").unwrap_err();
+        assert!(error.to_string().contains("(6 used)"));
+    }
+
+    #[test]
+    fn test_check_attachment() {
+        let mut config = test_config();
+        assert!(config.entry.check_attachment("scan.pdf", 1024).is_ok());
+        assert!(config.entry.check_attachment("Codes.TXT", 0).is_ok());
+        assert!(config.entry.check_attachment("setup.exe", 1024).is_err());
+        assert!(config.entry.check_attachment("README", 1024).is_err());
+        assert!(config
+            .entry
+            .check_attachment("scan.pdf", 5120 * 1024 + 1)
+            .is_err());
+
+        config.entry.attachment_extensions = vec![".exe".to_string()];
+        config.entry.max_attachment_kb = 1;
+        assert!(config.entry.check_attachment("setup.exe", 1024).is_ok());
+        assert!(config.entry.check_attachment("scan.pdf", 1024).is_err());
+
+        config.entry.attachment_extensions.clear();
+        assert!(config.entry.check_attachment("README", 1024).is_ok());
+        assert!(config.entry.check_attachment("README", 1025).is_err());
+    }
+
+    #[test]
+    fn test_check_custom_symbols() {
+        let mut options = test_config().generator.options;
+        assert!(options.custom_symbols.is_empty());
+        assert!(options.check_custom_symbols().is_ok());
+
+        options.custom_symbols = "!?.-_".to_string();
+        assert!(options.check_custom_symbols().is_ok());
+
+        for invalid in ["!a", "#5", "- _", "!§", &"!".repeat(33)] {
+            options.custom_symbols = invalid.to_string();
+            assert!(options.check_custom_symbols().is_err(), "{}", invalid);
+        }
+    }
+
+    #[test]
+    fn test_get_db_dir_unavailable() {
+        let temp = tempfile::TempDir::new().unwrap();
+        let mut config = test_config();
+        config.database.db_path = temp.path().join("usb");
+
+        config.app.is_initialized = true;
+        assert!(config.get_db_dir().is_err());
+        assert!(!config.database.db_path.exists());
+        assert!(!config.is_vault_available());
+
+        config.app.is_initialized = false;
+        assert!(config.get_db_dir().is_ok());
+        assert!(config.database.db_path.exists());
+        assert!(config.is_vault_available());
+    }
+
+    #[test]
+    fn test_export_settings() {
+        let mut config = test_config();
+        config.app.is_initialized = true;
+        config.app.confirmation_pin = Some(ConfirmationPin::new("4711").unwrap());
+        config.backup.last_backup = Some("2024-01-01".to_string());
+
+        let exported = config.export_settings().unwrap();
+
+        assert!(exported.contains("auto_logout_duration"));
+        assert!(exported.contains("default_length"));
+        assert!(!exported.contains("is_initialized"));
+        assert!(!exported.contains("confirmation_pin"));
+        assert!(!exported.contains("last_backup"));
+        assert!(!exported.contains("backup_path"));
+        assert!(!exported.contains("db_path"));
+    }
+
+    #[test]
+    fn test_browser_sites() {
+        let mut config = test_config();
+        assert!(!config.browser.enabled);
+        assert_eq!(config.browser.extension_ids, vec![FIREFOX_EXTENSION_ID]);
+
+        config.browser.authorize_site("github.com");
+        config.browser.authorize_site("example.org");
+        config.browser.authorize_site("github.com");
+        assert_eq!(
+            config.browser.authorized_sites,
+            vec!["example.org", "github.com"]
+        );
+        assert!(config.browser.is_authorized("github.com"));
+        assert!(!config.browser.is_authorized("gitlab.com"));
+    }
+
+    #[test]
+    fn test_check_confirmation_pin() {
+        let mut config = test_config();
+        assert!(config.check_confirmation_pin(None).is_ok());
+
+        config.app.confirmation_pin = Some(ConfirmationPin::new("4711").unwrap());
+        assert!(config.check_confirmation_pin(None).is_err());
+        assert!(config.check_confirmation_pin(Some("0000")).is_err());
+        assert!(config.check_confirmation_pin(Some("4711")).is_ok());
+    }
+
+    #[test]
+    fn test_import_settings() {
+        let mut source = test_config();
+        source.generator.default_length = 32;
+        source.app.lock_on_blur = true;
+        source.view.default_tab = DefaultTab::Passwords;
+        source.view.double_click = RowAction::OpenUrl;
+        source.backup.backup_path = PathBuf::from("/elsewhere/backups");
+        source.generator.options.mode = GeneratorMode::Passphrase;
+        source.generator.options.symbols = false;
+        source.entry.max_attachment_kb = 100;
+        source.entry.attachment_scanner = Some("/usr/bin/scanner".to_string());
+        let exported = source.export_settings().unwrap();
+
+        let mut target = test_config();
+        target.app.is_initialized = true;
+        target.database.db_path = PathBuf::from("/local/db");
+        let imported = target.import_settings(&exported).unwrap();
+
+        assert_eq!(imported.generator.default_length, 32);
+        assert_eq!(imported.generator.options.mode, GeneratorMode::Passphrase);
+        assert!(!imported.generator.options.symbols);
+        assert_eq!(imported.view.default_tab, DefaultTab::Passwords);
+        assert_eq!(imported.view.double_click, RowAction::OpenUrl);
+        assert!(imported.app.lock_on_blur);
+        assert!(imported.app.is_initialized);
+        assert_eq!(imported.database.db_path, PathBuf::from("/local/db"));
+        assert_eq!(imported.backup.backup_path, target.backup.backup_path);
+        assert_eq!(imported.entry.max_attachment_kb, 100);
+        assert_eq!(imported.entry.attachment_scanner, None);
+
+        assert!(target
+            .import_settings("[generator]\ndefault_length = \"long\"")
+            .is_err());
+    }
 }
@@ -0,0 +1,125 @@
+use base64::engine::general_purpose::STANDARD;
+use base64::Engine;
+use ring::{
+    pbkdf2,
+    rand::{SecureRandom, SystemRandom},
+};
+use serde::{Deserialize, Serialize};
+use std::num::NonZeroU32;
+
+const MIN_PIN_LENGTH: usize = 4;
+const PIN_ITERATIONS: u32 = 100_000;
+const SALT_LEN: usize = 16;
+const HASH_LEN: usize = 32;
+
+/// A hashed PIN that has to be entered before destructive actions.
+///
+/// The PIN is a speed bump against mistakes, not a second key, so it is stored in the
+/// configuration as a salted PBKDF2 hash.
+#[derive(Clone, Debug, PartialEq, Serialize, Deserialize)]
+pub struct ConfirmationPin {
+    salt: String,
+    hash: String,
+}
+
+impl ConfirmationPin {
+    /// Hash a new confirmation PIN
+    ///
+    /// # Arguments
+    ///
+    /// * `pin` - The PIN to hash
+    ///
+    /// # Returns
+    ///
+    /// The hashed PIN
+    ///
+    /// # Errors
+    ///
+    /// Returns an error if the PIN is too short or no salt can be generated
+    pub fn new(pin: &str) -> Result<Self, Box<dyn std::error::Error>> {
+        if pin.chars().count() < MIN_PIN_LENGTH {
+            return Err(format!(
+                "The confirmation PIN must have at least {} characters",
+                MIN_PIN_LENGTH
+            )
+            .into());
+        }
+
+        let mut salt = [0u8; SALT_LEN];
+        SystemRandom::new()
+            .fill(&mut salt)
+            .map_err(|_| "Failed to generate salt")?;
+
+        let mut hash = [0u8; HASH_LEN];
+        pbkdf2::derive(
+            pbkdf2::PBKDF2_HMAC_SHA256,
+            Self::iterations(),
+            &salt,
+            pin.as_bytes(),
+            &mut hash,
+        );
+
+        Ok(Self {
+            salt: STANDARD.encode(salt),
+            hash: STANDARD.encode(hash),
+        })
+    }
+
+    /// Check a PIN against the stored hash in constant time
+    ///
+    /// # Arguments
+    ///
+    /// * `pin` - The PIN to check
+    ///
+    /// # Returns
+    ///
+    /// True if the PIN matches
+    pub fn verify(&self, pin: &str) -> bool {
+        let (Ok(salt), Ok(hash)) = (STANDARD.decode(&self.salt), STANDARD.decode(&self.hash))
+        else {
+            return false;
+        };
+
+        pbkdf2::verify(
+            pbkdf2::PBKDF2_HMAC_SHA256,
+            Self::iterations(),
+            &salt,
+            pin.as_bytes(),
+            &hash,
+        )
+        .is_ok()
+    }
+
+    fn iterations() -> NonZeroU32 {
+        NonZeroU32::new(PIN_ITERATIONS).expect("Iterations must not be zero")
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_confirmation_pin() {
+        let pin = ConfirmationPin::new("4711").unwrap();
+        assert!(pin.verify("4711"));
+        assert!(!pin.verify("4712"));
+        assert!(!pin.verify(""));
+
+        let other = ConfirmationPin::new("4711").unwrap();
+        assert_ne!(pin.salt, other.salt);
+        assert_ne!(pin.hash, other.hash);
+
+        assert!(ConfirmationPin::new("123").is_err());
+    }
+
+    #[test]
+    fn test_confirmation_pin_roundtrip() {
+        let pin = ConfirmationPin::new("geheim").unwrap();
+        let stored = toml::to_string(&pin).unwrap();
+        let loaded: ConfirmationPin = toml::from_str(&stored).unwrap();
+
+        assert!(loaded.verify("geheim"));
+        assert!(!stored.contains("geheim"));
+    }
+}
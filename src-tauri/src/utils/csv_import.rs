@@ -0,0 +1,408 @@
+use csv::StringRecord;
+use serde::{Deserialize, Serialize};
+
+/// The number of rows shown in the preview of a CSV file.
+const PREVIEW_ROWS: usize = 5;
+/// Shown in the preview instead of the cells of the password column.
+const MASKED_CELL: &str = "••••••••";
+
+/// A known layout of a CSV file with passwords.
+#[derive(Clone, Copy, Debug, Default, PartialEq, Serialize, Deserialize)]
+#[serde(rename_all = "lowercase")]
+pub enum CsvProfile {
+    /// The layout of the own CSV export: service, username, password, URL and notes
+    /// separated by `;`, without a header row.
+    #[default]
+    Karncrypt,
+    Chrome,
+    Firefox,
+    Edge,
+}
+
+impl CsvProfile {
+    /// Get the delimiter of the profile
+    pub fn delimiter(&self) -> u8 {
+        match self {
+            CsvProfile::Karncrypt => b';',
+            CsvProfile::Chrome | CsvProfile::Firefox | CsvProfile::Edge => b',',
+        }
+    }
+
+    /// Get the header names of the service, username, password, URL and notes columns
+    ///
+    /// # Returns
+    ///
+    /// The header names, None for a field the export does not have, or None if the profile
+    /// has no header row
+    fn header_names(&self) -> Option<[Option<&'static str>; 5]> {
+        match self {
+            CsvProfile::Karncrypt => None,
+            CsvProfile::Chrome | CsvProfile::Edge => Some([
+                Some("name"),
+                Some("username"),
+                Some("password"),
+                Some("url"),
+                Some("note"),
+            ]),
+            CsvProfile::Firefox => {
+                Some([None, Some("username"), Some("password"), Some("url"), None])
+            }
+        }
+    }
+}
+
+/// The columns of a CSV file the fields of an entry are read from.
+#[derive(Clone, Debug, Default, PartialEq, Serialize, Deserialize)]
+pub struct ColumnMapping {
+    /// The service falls back to the host of the URL if no column is mapped.
+    pub service: Option<usize>,
+    pub username: Option<usize>,
+    pub password: Option<usize>,
+    pub url: Option<usize>,
+    pub notes: Option<usize>,
+}
+
+/// The fields of an entry read from a CSV row.
+#[derive(Debug, PartialEq)]
+pub struct CsvFields {
+    pub service: String,
+    pub username: String,
+    pub password: String,
+    pub url: String,
+    pub notes: String,
+}
+
+/// How a CSV file is read, detected from its first row or chosen by the user.
+#[derive(Clone, Debug, PartialEq, Serialize, Deserialize)]
+pub struct CsvLayout {
+    pub profile: CsvProfile,
+    pub delimiter: u8,
+    pub has_header: bool,
+    /// The number of columns every row must have.
+    pub columns: usize,
+    pub mapping: ColumnMapping,
+}
+
+impl Default for CsvLayout {
+    fn default() -> Self {
+        Self {
+            profile: CsvProfile::Karncrypt,
+            delimiter: CsvProfile::Karncrypt.delimiter(),
+            has_header: false,
+            columns: 5,
+            mapping: ColumnMapping {
+                service: Some(0),
+                username: Some(1),
+                password: Some(2),
+                url: Some(3),
+                notes: Some(4),
+            },
+        }
+    }
+}
+
+impl CsvLayout {
+    /// Detect the layout of a CSV file from its first row
+    ///
+    /// Exports of Chrome and Edge share the same layout and are detected as Chrome. Rows
+    /// without a known header are read with the default layout.
+    ///
+    /// # Arguments
+    ///
+    /// * `first_row` - The first line of the file
+    ///
+    /// # Returns
+    ///
+    /// The detected layout
+    pub fn detect(first_row: &str) -> Self {
+        let headers = Self::parse_header(first_row, b',');
+        let has = |name: &str| headers.iter().any(|h| h == name);
+
+        let profile = if has("httprealm") || has("formactionorigin") {
+            CsvProfile::Firefox
+        } else if has("name") && has("url") && has("username") && has("password") {
+            CsvProfile::Chrome
+        } else {
+            return Self::default();
+        };
+
+        Self::for_profile(profile, first_row).unwrap_or_default()
+    }
+
+    /// Create the layout of a profile, mapping the columns by the header row
+    ///
+    /// # Arguments
+    ///
+    /// * `profile` - The profile of the file
+    /// * `first_row` - The first line of the file
+    ///
+    /// # Returns
+    ///
+    /// The layout of the profile
+    ///
+    /// # Errors
+    ///
+    /// Returns an error if the header row lacks the password column of the profile
+    pub fn for_profile(profile: CsvProfile, first_row: &str) -> Result<Self, String> {
+        let Some(names) = profile.header_names() else {
+            return Ok(Self::default());
+        };
+
+        let headers = Self::parse_header(first_row, profile.delimiter());
+        let column = |name: Option<&str>| name.and_then(|n| headers.iter().position(|h| h == n));
+        let layout = Self {
+            profile,
+            delimiter: profile.delimiter(),
+            has_header: true,
+            columns: headers.len(),
+            mapping: ColumnMapping {
+                service: column(names[0]),
+                username: column(names[1]),
+                password: column(names[2]),
+                url: column(names[3]),
+                notes: column(names[4]),
+            },
+        };
+        if layout.mapping.password.is_none() {
+            return Err(format!(
+                "The file has no password column of the {:?} layout",
+                profile
+            ));
+        }
+
+        Ok(layout)
+    }
+
+    /// Check that the mapping has a password column and only refers to existing columns
+    ///
+    /// # Errors
+    ///
+    /// Returns an error if the mapping is invalid
+    pub fn validate(&self) -> Result<(), String> {
+        let m = &self.mapping;
+        if m.password.is_none() {
+            return Err("A password column must be mapped".to_string());
+        }
+        if [m.service, m.username, m.password, m.url, m.notes]
+            .into_iter()
+            .flatten()
+            .any(|column| column >= self.columns)
+        {
+            return Err("The mapping refers to a column the file does not have".to_string());
+        }
+
+        Ok(())
+    }
+
+    /// Read the fields of an entry from a row
+    ///
+    /// # Arguments
+    ///
+    /// * `record` - The row of the CSV file
+    ///
+    /// # Returns
+    ///
+    /// The fields, or None if the row has another number of columns than the layout
+    pub fn fields(&self, record: &StringRecord) -> Option<CsvFields> {
+        if record.len() != self.columns {
+            return None;
+        }
+
+        let get =
+            |column: Option<usize>| column.and_then(|c| record.get(c)).unwrap_or("").to_string();
+        let url = get(self.mapping.url);
+        let mut service = get(self.mapping.service);
+        if service.is_empty() {
+            service = host(&url).to_string();
+        }
+
+        Some(CsvFields {
+            service,
+            username: get(self.mapping.username),
+            password: get(self.mapping.password),
+            url,
+            notes: get(self.mapping.notes),
+        })
+    }
+
+    /// Split a header row into lowercase column names
+    fn parse_header(first_row: &str, delimiter: u8) -> Vec<String> {
+        csv::ReaderBuilder::new()
+            .delimiter(delimiter)
+            .has_headers(false)
+            .trim(csv::Trim::All)
+            .from_reader(first_row.as_bytes())
+            .records()
+            .next()
+            .and_then(Result::ok)
+            .map(|record| record.iter().map(|h| h.to_lowercase()).collect())
+            .unwrap_or_default()
+    }
+}
+
+/// The first rows of a CSV file with the layout they are read with.
+#[derive(Serialize)]
+pub struct CsvPreview {
+    pub layout: CsvLayout,
+    /// The header row, or numbered columns for files without one.
+    pub headers: Vec<String>,
+    /// The first rows, the cells of the password column are masked.
+    pub rows: Vec<Vec<String>>,
+}
+
+impl CsvPreview {
+    /// Read the preview of a CSV file
+    ///
+    /// # Arguments
+    ///
+    /// * `content` - The content of the file
+    /// * `profile` - The profile to read the file with, detected if None
+    ///
+    /// # Returns
+    ///
+    /// The preview of the file
+    ///
+    /// # Errors
+    ///
+    /// Returns an error if the file does not match the given profile
+    pub fn read(content: &str, profile: Option<CsvProfile>) -> Result<Self, String> {
+        let first_row = content
+            .lines()
+            .find(|line| !line.trim().is_empty())
+            .unwrap_or("");
+        let layout = match profile {
+            Some(profile) => CsvLayout::for_profile(profile, first_row)?,
+            None => CsvLayout::detect(first_row),
+        };
+
+        let mut rdr = csv::ReaderBuilder::new()
+            .delimiter(layout.delimiter)
+            .has_headers(layout.has_header)
+            .flexible(true)
+            .trim(csv::Trim::All)
+            .from_reader(content.as_bytes());
+        let headers = if layout.has_header {
+            rdr.headers()
+                .map(|h| h.iter().map(str::to_string).collect())
+                .unwrap_or_default()
+        } else {
+            (1..=layout.columns)
+                .map(|c| format!("Spalte {}", c))
+                .collect()
+        };
+        let rows = rdr
+            .records()
+            .filter_map(Result::ok)
+            .take(PREVIEW_ROWS)
+            .map(|record| {
+                record
+                    .iter()
+                    .enumerate()
+                    .map(|(column, cell)| {
+                        if Some(column) == layout.mapping.password && !cell.is_empty() {
+                            MASKED_CELL.to_string()
+                        } else {
+                            cell.to_string()
+                        }
+                    })
+                    .collect()
+            })
+            .collect();
+
+        Ok(Self {
+            layout,
+            headers,
+            rows,
+        })
+    }
+}
+
+/// Get the host of a URL, e.g. to name an entry without service
+fn host(url: &str) -> &str {
+    let rest = url.split_once("://").map_or(url, |(_, rest)| rest);
+    let end = rest.find(['/', '?', '#']).unwrap_or(rest.len());
+    let host = &rest[..end];
+    let host = host.rsplit_once('@').map_or(host, |(_, host)| host);
+    let host = host.split(':').next().unwrap_or(host);
+
+    host.strip_prefix("www.").unwrap_or(host)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    const CHROME: &str = "name,url,username,password,note\n\
+                          example.com,https://example.com/login,me,geheim,privat\n";
+    const FIREFOX: &str = "\"url\",\"username\",\"password\",\"httpRealm\",\"formActionOrigin\",\"guid\",\"timeCreated\",\"timeLastUsed\",\"timePasswordChanged\"\n\
+                           \"https://www.shop.example:8443/cart\",\"me\",\"pa,ss\",,\"https://www.shop.example\",\"{1}\",\"1\",\"1\",\"1\"\n";
+
+    #[test]
+    fn test_detect() {
+        let layout = CsvLayout::detect("Mail;me;geheim;https://mail.example;");
+        assert_eq!(layout, CsvLayout::default());
+
+        let layout = CsvLayout::detect(CHROME.lines().next().unwrap());
+        assert_eq!(layout.profile, CsvProfile::Chrome);
+        assert!(layout.has_header);
+        assert_eq!(layout.mapping.url, Some(1));
+        assert_eq!(layout.mapping.notes, Some(4));
+
+        let layout = CsvLayout::detect(FIREFOX.lines().next().unwrap());
+        assert_eq!(layout.profile, CsvProfile::Firefox);
+        assert_eq!(layout.columns, 9);
+        assert_eq!(layout.mapping.service, None);
+        assert_eq!(layout.mapping.password, Some(2));
+    }
+
+    #[test]
+    fn test_for_profile() {
+        let layout =
+            CsvLayout::for_profile(CsvProfile::Edge, "name,url,username,password").unwrap();
+        assert_eq!(layout.profile, CsvProfile::Edge);
+        assert_eq!(layout.mapping.notes, None);
+        assert!(layout.validate().is_ok());
+
+        assert!(CsvLayout::for_profile(CsvProfile::Chrome, "a;b;c").is_err());
+
+        let mut invalid = layout.clone();
+        invalid.mapping.url = Some(7);
+        assert!(invalid.validate().is_err());
+        invalid.mapping = ColumnMapping::default();
+        assert!(invalid.validate().is_err());
+    }
+
+    #[test]
+    fn test_fields() {
+        let layout = CsvLayout::detect(FIREFOX.lines().next().unwrap());
+        let mut rdr = csv::ReaderBuilder::new()
+            .has_headers(true)
+            .from_reader(FIREFOX.as_bytes());
+        let record = rdr.records().next().unwrap().unwrap();
+
+        let fields = layout.fields(&record).unwrap();
+        assert_eq!(fields.service, "shop.example");
+        assert_eq!(fields.password, "pa,ss");
+        assert_eq!(fields.url, "https://www.shop.example:8443/cart");
+
+        assert!(layout
+            .fields(&StringRecord::from(vec!["too", "short"]))
+            .is_none());
+        assert_eq!(host("user@mail.example/inbox"), "mail.example");
+    }
+
+    #[test]
+    fn test_preview() {
+        let preview = CsvPreview::read(CHROME, None).unwrap();
+        assert_eq!(preview.layout.profile, CsvProfile::Chrome);
+        assert_eq!(preview.headers[0], "name");
+        assert_eq!(preview.rows.len(), 1);
+        assert_eq!(preview.rows[0][3], MASKED_CELL);
+
+        let preview = CsvPreview::read("Mail;me;geheim;;\n", None).unwrap();
+        assert_eq!(preview.headers.len(), 5);
+        assert!(!preview.rows[0].contains(&"geheim".to_string()));
+
+        assert!(CsvPreview::read("a,b,c\n", Some(CsvProfile::Firefox)).is_err());
+    }
+}
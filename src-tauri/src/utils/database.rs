@@ -1,14 +1,60 @@
+use std::fs;
 use std::path::{Path, PathBuf};
 
+use base64::engine::general_purpose::STANDARD;
+use base64::Engine;
 use chrono::Utc;
 use log::{error, info};
-use rusqlite::Connection;
+use rusqlite::{Connection, OptionalExtension};
 use serde::{Deserialize, Serialize};
 
-use crate::utils::Encryption;
+use crate::utils::{Encryption, KeyParams};
 
 use super::models::Model;
 
+/// Columns added to the passwords table after the initial schema, in the order they were introduced.
+const PASSWORD_MIGRATIONS: &[(&str, &str)] = &[
+    ("require_master", "INTEGER NOT NULL DEFAULT 0"),
+    ("recovery_codes", "TEXT NOT NULL DEFAULT ''"),
+    ("totp_secret", "TEXT NOT NULL DEFAULT ''"),
+    ("folder_id", "INTEGER"),
+    ("locked", "INTEGER NOT NULL DEFAULT 0"),
+    ("deleted_at", "TEXT"),
+    ("expires_on", "TEXT"),
+];
+
+/// Marks a username, URL or notes field that is encrypted in addition to the database.
+const ENCRYPTED_FIELD_PREFIX: &str = "enc:";
+/// The encrypted columns of the payment cards table.
+const CARD_FIELDS: &[&str] = &["holder", "number", "expiry", "cvc"];
+/// The encrypted columns of the identities table.
+const IDENTITY_FIELDS: &[&str] = &["name", "address", "phone"];
+/// The encrypted columns of the servers table.
+const SERVER_FIELDS: &[&str] = &["host", "username"];
+/// The tables an incremental backup carries in full besides the user and passwords tables,
+/// parents before their children.
+const INCREMENT_TABLES: &[&str] = &[
+    "health_history",
+    "entry_permissions",
+    "pinned_entries",
+    "generator_history",
+    "folders",
+    "tags",
+    "entry_tags",
+    "expiring_notes",
+    "secure_notes",
+    "payment_cards",
+    "identities",
+    "servers",
+    "attachments",
+];
+
+/// The ID of a password entry with its stored username, URL and notes.
+type EntryFields = (i32, [String; 3]);
+
+/// The ID of a row with the values of its encrypted columns.
+type EncryptedRow = (i32, Vec<String>);
+
 #[derive(Serialize, Deserialize, Clone)]
 pub struct PasswordEntry {
     pub id: Option<i32>,
@@ -20,6 +66,201 @@ pub struct PasswordEntry {
     pub notes: String,
     pub created_at: String,
     pub updated_at: String,
+    #[serde(default)]
+    pub require_master: bool,
+    /// The encrypted recovery codes, empty if the entry has none.
+    #[serde(default)]
+    pub recovery_codes: String,
+    /// The encrypted TOTP secret, empty if the entry has none.
+    #[serde(default)]
+    pub totp_secret: String,
+    /// The folder of the entry, None if it is not filed into a folder.
+    #[serde(default)]
+    pub folder_id: Option<i32>,
+    /// Whether the entry is read-only until it is unlocked again.
+    #[serde(default)]
+    pub locked: bool,
+    /// When the entry was moved to the trash, None if it is not in the trash.
+    #[serde(default)]
+    pub deleted_at: Option<String>,
+    /// The last day the password is valid as `YYYY-MM-DD`, None if it does not expire.
+    #[serde(default)]
+    pub expires_on: Option<String>,
+}
+
+/// The metadata of a password entry that is handed to list views.
+///
+/// It carries no password material at all, neither the encrypted password nor the recovery
+/// codes or TOTP secret, those are decrypted per entry when the user asks for them.
+#[derive(Serialize, Clone, Debug, PartialEq)]
+pub struct EntrySummary {
+    pub id: Option<i32>,
+    pub user_id: i32,
+    pub service: String,
+    pub username: String,
+    pub url: String,
+    pub notes: String,
+    pub created_at: String,
+    pub updated_at: String,
+    pub require_master: bool,
+    pub has_recovery_codes: bool,
+    /// False for entries that only hold a TOTP secret.
+    pub has_password: bool,
+    pub has_totp: bool,
+    pub folder_id: Option<i32>,
+    pub locked: bool,
+    pub deleted_at: Option<String>,
+    pub expires_on: Option<String>,
+}
+
+impl From<&PasswordEntry> for EntrySummary {
+    fn from(entry: &PasswordEntry) -> Self {
+        Self {
+            id: entry.id,
+            user_id: entry.user_id,
+            service: entry.service.clone(),
+            username: entry.username.clone(),
+            url: entry.url.clone(),
+            notes: entry.notes.clone(),
+            created_at: entry.created_at.clone(),
+            updated_at: entry.updated_at.clone(),
+            require_master: entry.require_master,
+            has_recovery_codes: !entry.recovery_codes.is_empty(),
+            has_password: !entry.password.is_empty(),
+            has_totp: !entry.totp_secret.is_empty(),
+            folder_id: entry.folder_id,
+            locked: entry.locked,
+            deleted_at: entry.deleted_at.clone(),
+            expires_on: entry.expires_on.clone(),
+        }
+    }
+}
+
+/// The size and average password score of a vault on one day.
+#[derive(Serialize, Clone, Debug, PartialEq)]
+pub struct HealthSnapshot {
+    pub day: String,
+    pub entry_count: usize,
+    pub average_score: u8,
+}
+
+/// A password from the generator history.
+#[derive(Serialize, Clone, Debug, PartialEq)]
+pub struct GeneratedPassword {
+    pub password: String,
+    pub created_at: String,
+}
+
+/// A folder of password entries, folders without a parent are at the top level.
+#[derive(Serialize, Deserialize, Clone, Debug, PartialEq)]
+pub struct Folder {
+    pub id: i32,
+    pub parent_id: Option<i32>,
+    pub name: String,
+}
+
+/// A note of a password entry that is redacted once its expiry date has passed.
+#[derive(Serialize, Deserialize, Clone, Debug, PartialEq)]
+pub struct ExpiringNote {
+    pub id: i32,
+    pub entry_id: i32,
+    /// The encrypted content in the database, decrypted by the password manager, empty once redacted.
+    pub content: String,
+    /// The last day the note is kept as `YYYY-MM-DD`.
+    pub expires_on: String,
+    pub redacted_at: Option<String>,
+    pub created_at: String,
+}
+
+/// A note of a user that is not tied to a login, e.g. for license keys.
+#[derive(Serialize, Deserialize, Clone, Debug, PartialEq)]
+pub struct SecureNote {
+    pub id: i32,
+    pub title: String,
+    /// The encrypted content in the database, decrypted by the password manager.
+    pub content: String,
+    pub created_at: String,
+    pub updated_at: String,
+}
+
+/// A payment card of a user, every field but the title is encrypted in the database.
+#[derive(Serialize, Deserialize, Clone, Debug, Default, PartialEq)]
+pub struct PaymentCard {
+    #[serde(default)]
+    pub id: i32,
+    pub title: String,
+    pub holder: String,
+    /// The card number as digits only.
+    pub number: String,
+    /// The expiry date as `MM/YY`, empty if unknown.
+    pub expiry: String,
+    pub cvc: String,
+    #[serde(default)]
+    pub created_at: String,
+    #[serde(default)]
+    pub updated_at: String,
+}
+
+/// An identity of a user for filling forms, every field but the title is encrypted in the database.
+#[derive(Serialize, Deserialize, Clone, Debug, Default, PartialEq)]
+pub struct Identity {
+    #[serde(default)]
+    pub id: i32,
+    pub title: String,
+    pub name: String,
+    pub address: String,
+    pub phone: String,
+    #[serde(default)]
+    pub created_at: String,
+    #[serde(default)]
+    pub updated_at: String,
+}
+
+/// A server of a user, the host and username are encrypted in the database.
+#[derive(Serialize, Deserialize, Clone, Debug, Default, PartialEq)]
+pub struct Server {
+    #[serde(default)]
+    pub id: i32,
+    pub title: String,
+    pub host: String,
+    /// The port, 0 for the default port of the protocol.
+    pub port: u16,
+    /// The protocol in lowercase, e.g. `ssh`.
+    pub protocol: String,
+    pub username: String,
+    /// The secure note holding the SSH key of the server.
+    pub key_note_id: Option<i32>,
+    #[serde(default)]
+    pub created_at: String,
+    #[serde(default)]
+    pub updated_at: String,
+}
+
+/// A file attached to a password entry, the encrypted content is only read on demand.
+#[derive(Serialize, Deserialize, Clone, Debug, PartialEq)]
+pub struct Attachment {
+    pub id: i32,
+    pub entry_id: i32,
+    pub file_name: String,
+    /// The size of the plain file in bytes.
+    pub size: i64,
+    pub created_at: String,
+}
+
+/// All rows of a table, as carried by an incremental backup.
+#[derive(Serialize, Deserialize, Clone, Debug, PartialEq)]
+pub struct TableRows {
+    pub table: String,
+    pub columns: Vec<String>,
+    pub rows: Vec<Vec<serde_json::Value>>,
+}
+
+/// A tag of password entries with the entries carrying it.
+#[derive(Serialize, Deserialize, Clone, Debug, PartialEq)]
+pub struct Tag {
+    pub id: i32,
+    pub name: String,
+    pub entry_ids: Vec<i32>,
 }
 
 #[derive(Serialize, Deserialize, Clone)]
@@ -35,6 +276,8 @@ pub struct Database {
     pub connection: Connection,
     pub path: PathBuf,
     pub encryption: Encryption,
+    /// Whether usernames, URLs and notes are encrypted in addition to the database.
+    field_encryption: bool,
 }
 
 impl Database {
@@ -44,6 +287,7 @@ impl Database {
     ///
     /// * `path` - The path to the database file.
     /// * `master_password` - The master password for the database.
+    /// * `params` - The salt and key derivation parameters.
     ///
     /// # Returns
     ///
@@ -55,10 +299,45 @@ impl Database {
     pub fn new(
         path: PathBuf,
         master_password: &str,
-        salt: &[u8; 16],
+        params: &KeyParams,
     ) -> Result<Self, Box<dyn std::error::Error>> {
         let conn = Connection::open(&path)?;
-        let encryption = Encryption::new(master_password, salt);
+        Self::setup(conn, path, master_password, params)
+    }
+
+    /// Create a new Database that only lives in memory.
+    ///
+    /// The database is keyed and migrated like a database on disk, but nothing is written
+    /// to disk and all data is lost when it is dropped.
+    ///
+    /// # Arguments
+    ///
+    /// * `master_password` - The master password for the database.
+    /// * `params` - The salt and key derivation parameters.
+    ///
+    /// # Returns
+    ///
+    /// A Result containing the new Database or an error.
+    ///
+    /// # Errors
+    ///
+    /// If the database cannot be opened or the encryption key cannot be generated.
+    pub fn in_memory(
+        master_password: &str,
+        params: &KeyParams,
+    ) -> Result<Self, Box<dyn std::error::Error>> {
+        let conn = Connection::open_in_memory()?;
+        Self::setup(conn, PathBuf::new(), master_password, params)
+    }
+
+    /// Key the connection and create or migrate the tables.
+    fn setup(
+        conn: Connection,
+        path: PathBuf,
+        master_password: &str,
+        params: &KeyParams,
+    ) -> Result<Self, Box<dyn std::error::Error>> {
+        let encryption = Encryption::new(master_password, params);
         let key = encryption.get_key(master_password)?;
         conn.execute_batch(&format!(
             "
@@ -76,6 +355,7 @@ impl Database {
             connection: conn,
             path,
             encryption,
+            field_encryption: false,
         };
 
         db.create_tables()?;
@@ -127,6 +407,178 @@ impl Database {
                 );
             ",
         )?;
+        self.migrate_tables("main")?;
+        Ok(())
+    }
+
+    /// Add missing columns to the passwords table and missing tables to a schema.
+    ///
+    /// # Arguments
+    ///
+    /// * `schema` - The name of the attached schema to migrate.
+    ///
+    /// # Returns
+    ///
+    /// A Result containing a unit or an error.
+    ///
+    /// # Errors
+    ///
+    /// If the table info cannot be read or a column or table cannot be added.
+    fn migrate_tables(&self, schema: &str) -> Result<(), Box<dyn std::error::Error>> {
+        let columns: Vec<String> = {
+            let mut stmt = self
+                .connection
+                .prepare(&format!("PRAGMA {}.table_info(passwords)", schema))?;
+            let names = stmt
+                .query_map([], |row| row.get(1))?
+                .collect::<Result<Vec<String>, _>>()?;
+            names
+        };
+
+        for (column, definition) in PASSWORD_MIGRATIONS {
+            if !columns.iter().any(|c| c == column) {
+                info!("Adding column {} to {}.passwords", column, schema);
+                self.connection.execute_batch(&format!(
+                    "ALTER TABLE {}.passwords ADD COLUMN {} {}",
+                    schema, column, definition
+                ))?;
+            }
+        }
+
+        self.connection.execute_batch(&format!(
+            "CREATE TABLE IF NOT EXISTS {0}.health_history (
+                id INTEGER PRIMARY KEY,
+                user_id INTEGER NOT NULL,
+                day TEXT NOT NULL,
+                entry_count INTEGER NOT NULL,
+                average_score INTEGER NOT NULL,
+                UNIQUE (user_id, day),
+                FOREIGN KEY (user_id) REFERENCES user(id) ON DELETE CASCADE
+            );
+
+            CREATE TABLE IF NOT EXISTS {0}.entry_permissions (
+                entry_id INTEGER NOT NULL,
+                user_id INTEGER NOT NULL,
+                permission TEXT NOT NULL DEFAULT 'read',
+                PRIMARY KEY (entry_id, user_id),
+                FOREIGN KEY (entry_id) REFERENCES passwords(id) ON DELETE CASCADE,
+                FOREIGN KEY (user_id) REFERENCES user(id) ON DELETE CASCADE
+            );
+
+            CREATE TABLE IF NOT EXISTS {0}.pinned_entries (
+                entry_id INTEGER NOT NULL,
+                user_id INTEGER NOT NULL,
+                pinned_at TEXT NOT NULL,
+                PRIMARY KEY (entry_id, user_id),
+                FOREIGN KEY (entry_id) REFERENCES passwords(id) ON DELETE CASCADE,
+                FOREIGN KEY (user_id) REFERENCES user(id) ON DELETE CASCADE
+            );
+
+            CREATE TABLE IF NOT EXISTS {0}.generator_history (
+                id INTEGER PRIMARY KEY,
+                user_id INTEGER NOT NULL,
+                password TEXT NOT NULL,
+                created_at TEXT NOT NULL,
+                FOREIGN KEY (user_id) REFERENCES user(id) ON DELETE CASCADE
+            );
+
+            CREATE TABLE IF NOT EXISTS {0}.folders (
+                id INTEGER PRIMARY KEY,
+                user_id INTEGER NOT NULL,
+                parent_id INTEGER,
+                name TEXT NOT NULL,
+                created_at TEXT NOT NULL,
+                FOREIGN KEY (user_id) REFERENCES user(id) ON DELETE CASCADE
+            );
+
+            CREATE TABLE IF NOT EXISTS {0}.tags (
+                id INTEGER PRIMARY KEY,
+                user_id INTEGER NOT NULL,
+                name TEXT NOT NULL,
+                created_at TEXT NOT NULL,
+                FOREIGN KEY (user_id) REFERENCES user(id) ON DELETE CASCADE
+            );
+
+            CREATE TABLE IF NOT EXISTS {0}.entry_tags (
+                entry_id INTEGER NOT NULL,
+                tag_id INTEGER NOT NULL,
+                PRIMARY KEY (entry_id, tag_id),
+                FOREIGN KEY (entry_id) REFERENCES passwords(id) ON DELETE CASCADE,
+                FOREIGN KEY (tag_id) REFERENCES tags(id) ON DELETE CASCADE
+            );
+
+            CREATE TABLE IF NOT EXISTS {0}.expiring_notes (
+                id INTEGER PRIMARY KEY,
+                entry_id INTEGER NOT NULL,
+                content TEXT NOT NULL,
+                expires_on TEXT NOT NULL,
+                redacted_at TEXT,
+                created_at TEXT NOT NULL,
+                FOREIGN KEY (entry_id) REFERENCES passwords(id) ON DELETE CASCADE
+            );
+
+            CREATE TABLE IF NOT EXISTS {0}.secure_notes (
+                id INTEGER PRIMARY KEY,
+                user_id INTEGER NOT NULL,
+                title TEXT NOT NULL,
+                content TEXT NOT NULL,
+                created_at TEXT NOT NULL,
+                updated_at TEXT NOT NULL,
+                FOREIGN KEY (user_id) REFERENCES user(id) ON DELETE CASCADE
+            );
+
+            CREATE TABLE IF NOT EXISTS {0}.payment_cards (
+                id INTEGER PRIMARY KEY,
+                user_id INTEGER NOT NULL,
+                title TEXT NOT NULL,
+                holder TEXT NOT NULL,
+                number TEXT NOT NULL,
+                expiry TEXT NOT NULL,
+                cvc TEXT NOT NULL,
+                created_at TEXT NOT NULL,
+                updated_at TEXT NOT NULL,
+                FOREIGN KEY (user_id) REFERENCES user(id) ON DELETE CASCADE
+            );
+
+            CREATE TABLE IF NOT EXISTS {0}.identities (
+                id INTEGER PRIMARY KEY,
+                user_id INTEGER NOT NULL,
+                title TEXT NOT NULL,
+                name TEXT NOT NULL,
+                address TEXT NOT NULL,
+                phone TEXT NOT NULL,
+                created_at TEXT NOT NULL,
+                updated_at TEXT NOT NULL,
+                FOREIGN KEY (user_id) REFERENCES user(id) ON DELETE CASCADE
+            );
+
+            CREATE TABLE IF NOT EXISTS {0}.servers (
+                id INTEGER PRIMARY KEY,
+                user_id INTEGER NOT NULL,
+                title TEXT NOT NULL,
+                host TEXT NOT NULL,
+                port INTEGER NOT NULL,
+                protocol TEXT NOT NULL,
+                username TEXT NOT NULL,
+                key_note_id INTEGER,
+                created_at TEXT NOT NULL,
+                updated_at TEXT NOT NULL,
+                FOREIGN KEY (user_id) REFERENCES user(id) ON DELETE CASCADE,
+                FOREIGN KEY (key_note_id) REFERENCES secure_notes(id) ON DELETE SET NULL
+            );
+
+            CREATE TABLE IF NOT EXISTS {0}.attachments (
+                id INTEGER PRIMARY KEY,
+                entry_id INTEGER NOT NULL,
+                file_name TEXT NOT NULL,
+                size INTEGER NOT NULL,
+                content TEXT NOT NULL,
+                created_at TEXT NOT NULL,
+                FOREIGN KEY (entry_id) REFERENCES passwords(id) ON DELETE CASCADE
+            );",
+            schema
+        ))?;
+
         Ok(())
     }
 
@@ -145,6 +597,61 @@ impl Database {
     /// If the insertion fails.
     pub fn create<T: Model>(&self, model: &T) -> Result<(), Box<dyn std::error::Error>> {
         info!("Creating new {} entry", T::table_name());
+        let sealed = self.seal(model)?;
+        match Self::insert(&self.connection, sealed.as_ref().unwrap_or(model)) {
+            Ok(_) => {
+                info!("Successfully created {} entry", T::table_name());
+                Ok(())
+            }
+            Err(e) => {
+                error!("Failed to create {} entry: {}", T::table_name(), e);
+                Err(e.into())
+            }
+        }
+    }
+
+    /// Create database entries for several models in one transaction.
+    ///
+    /// Either all models are created or, if one insertion fails, none of them.
+    ///
+    /// # Arguments
+    ///
+    /// * `models` - The models to create.
+    ///
+    /// # Returns
+    ///
+    /// A Result containing the IDs of the new entries in the order of the models or an error.
+    ///
+    /// # Errors
+    ///
+    /// If an insertion fails.
+    pub fn create_all<T: Model>(
+        &self,
+        models: &[T],
+    ) -> Result<Vec<i32>, Box<dyn std::error::Error>> {
+        info!("Creating {} new {} entries", models.len(), T::table_name());
+        let tx = self.connection.unchecked_transaction()?;
+        let mut ids = Vec::with_capacity(models.len());
+        for model in models {
+            let sealed = self.seal(model)?;
+            Self::insert(&tx, sealed.as_ref().unwrap_or(model)).map_err(|e| {
+                error!("Failed to create {} entries: {}", T::table_name(), e);
+                e
+            })?;
+            ids.push(tx.last_insert_rowid() as i32);
+        }
+        tx.commit()?;
+        info!(
+            "Successfully created {} {} entries",
+            ids.len(),
+            T::table_name()
+        );
+
+        Ok(ids)
+    }
+
+    /// Insert a model with the given connection or transaction
+    fn insert<T: Model>(connection: &Connection, model: &T) -> rusqlite::Result<usize> {
         let params = model.to_params();
         let fields: Vec<&str> = params.iter().map(|(name, _)| *name).collect();
         let placeholders: Vec<String> = (1..=params.len()).map(|i| format!("?{}", i)).collect();
@@ -157,17 +664,7 @@ impl Database {
         );
 
         let values: Vec<&dyn rusqlite::ToSql> = params.iter().map(|(_, value)| *value).collect();
-
-        match self.connection.execute(&query, &values[..]) {
-            Ok(_) => {
-                info!("Successfully created {} entry", T::table_name());
-                Ok(())
-            }
-            Err(e) => {
-                error!("Failed to create {} entry: {}", T::table_name(), e);
-                Err(e.into())
-            }
-        }
+        connection.execute(&query, &values[..])
     }
 
     /// Read all entries for a model.
@@ -188,7 +685,9 @@ impl Database {
 
         let mut result = Vec::new();
         for entry in entries {
-            result.push(entry?);
+            let mut entry = entry?;
+            entry.decrypt_fields(&self.encryption);
+            result.push(entry);
         }
 
         match result.len() {
@@ -217,7 +716,8 @@ impl Database {
         let mut stmt = self
             .connection
             .prepare(&format!("SELECT * FROM {} WHERE id = ?1", T::table_name()))?;
-        let result = stmt.query_row([id], |row| T::from_row(row))?;
+        let mut result = stmt.query_row([id], |row| T::from_row(row))?;
+        result.decrypt_fields(&self.encryption);
 
         Ok(result)
     }
@@ -237,6 +737,8 @@ impl Database {
     /// If the update fails.
     pub fn update<T: Model>(&self, model: &T) -> Result<(), Box<dyn std::error::Error>> {
         info!("Updating {} entry", T::table_name());
+        let sealed = self.seal(model)?;
+        let model = sealed.as_ref().unwrap_or(model);
         let params = model.to_params();
         let fields: Vec<String> = params
             .iter()
@@ -269,6 +771,19 @@ impl Database {
         }
     }
 
+    /// Encrypt the fields of a model that are protected in addition to the database.
+    ///
+    /// # Returns
+    ///
+    /// The encrypted copy, or None if field encryption is off or the model has no such fields.
+    fn seal<T: Model>(&self, model: &T) -> Result<Option<T>, Box<dyn std::error::Error>> {
+        if !self.field_encryption {
+            return Ok(None);
+        }
+
+        model.encrypt_fields(&self.encryption)
+    }
+
     /// Delete a model entry by ID.
     ///
     /// # Arguments
@@ -296,8 +811,254 @@ impl Database {
         }
     }
 
+    /// Check if the database only lives in memory.
+    ///
+    /// # Returns
+    ///
+    /// True if the database is not stored on disk.
+    pub fn is_in_memory(&self) -> bool {
+        self.path.as_os_str().is_empty()
+    }
+
+    /// Get the version of the SQLCipher library the database is encrypted with.
+    ///
+    /// # Returns
+    ///
+    /// A Result containing the version or an error.
+    ///
+    /// # Errors
+    ///
+    /// If the library is plain SQLite without encryption support.
+    pub fn cipher_version(&self) -> Result<String, Box<dyn std::error::Error>> {
+        self.connection
+            .query_row("PRAGMA cipher_version;", [], |row| row.get(0))
+            .map_err(|_| "The database is not encrypted with SQLCipher".into())
+    }
+
+    /// Get the size of the database on disk.
+    ///
+    /// The write-ahead log and its index are included, because they hold changes that are
+    /// not yet written back to the database file.
+    ///
+    /// # Returns
+    ///
+    /// The size of the database files in bytes.
+    pub fn disk_usage(&self) -> u64 {
+        ["", "-wal", "-shm"]
+            .iter()
+            .filter_map(|suffix| {
+                let mut path = self.path.clone().into_os_string();
+                path.push(suffix);
+                fs::metadata(path).ok()
+            })
+            .map(|meta| meta.len())
+            .sum()
+    }
+
+    /// Switch the journal mode for databases in cloud-synced folders.
+    ///
+    /// The write-ahead log keeps recent changes in a separate -wal file. Sync clients upload
+    /// the database and the log independently, which can corrupt the database. The rollback
+    /// journal only exists during a write, so the database file is always complete.
+    ///
+    /// # Arguments
+    ///
+    /// * `enabled` - Whether to use the rollback journal instead of the write-ahead log.
+    ///
+    /// # Returns
+    ///
+    /// A Result containing a unit or an error.
+    ///
+    /// # Errors
+    ///
+    /// If the journal mode cannot be changed.
+    pub fn set_sync_safe_mode(&self, enabled: bool) -> Result<(), Box<dyn std::error::Error>> {
+        let mode = if enabled { "DELETE" } else { "WAL" };
+        self.connection
+            .query_row(&format!("PRAGMA journal_mode = {};", mode), [], |_| Ok(()))?;
+
+        Ok(())
+    }
+
+    /// Encrypt usernames, URLs and notes of new and changed entries in addition to the
+    /// database.
+    ///
+    /// Entries that are already stored are not touched, they are converted with
+    /// `migrate_fields`. Encrypted fields are always decrypted when read, so a vault stays
+    /// readable while it is converted.
+    ///
+    /// # Arguments
+    ///
+    /// * `enabled` - Whether to encrypt the fields.
+    pub fn set_field_encryption(&mut self, enabled: bool) {
+        self.field_encryption = enabled;
+    }
+
+    /// Check if usernames, URLs and notes are encrypted in addition to the database.
+    ///
+    /// # Returns
+    ///
+    /// True if new and changed entries are stored with encrypted fields.
+    pub fn field_encryption(&self) -> bool {
+        self.field_encryption
+    }
+
+    /// Count the password entries whose username, URL or notes are not yet stored as the
+    /// field encryption demands.
+    ///
+    /// # Returns
+    ///
+    /// A Result containing the number of entries to convert or an error.
+    ///
+    /// # Errors
+    ///
+    /// If the entries cannot be read.
+    pub fn pending_field_migration(&self) -> Result<usize, Box<dyn std::error::Error>> {
+        let (condition, min_length) = self.unmigrated_condition()?;
+        let count: i64 = self.connection.query_row(
+            &format!("SELECT COUNT(*) FROM passwords WHERE {}", condition),
+            rusqlite::params![ENCRYPTED_FIELD_PREFIX, min_length],
+            |row| row.get(0),
+        )?;
+        Ok(count as usize)
+    }
+
+    /// Convert the next batch of password entries to the current field encryption.
+    ///
+    /// Each batch is written in its own transaction, so an interrupted conversion resumes
+    /// with the remaining entries. The change time of the entries is kept.
+    ///
+    /// # Arguments
+    ///
+    /// * `batch_size` - The maximum number of entries to convert.
+    ///
+    /// # Returns
+    ///
+    /// A Result containing the number of converted entries, 0 once all are converted.
+    ///
+    /// # Errors
+    ///
+    /// If a field cannot be encrypted or the entries cannot be written.
+    pub fn migrate_fields(&self, batch_size: usize) -> Result<usize, Box<dyn std::error::Error>> {
+        let rows = self.unmigrated_entries(batch_size)?;
+
+        let tx = self.connection.unchecked_transaction()?;
+        for (id, fields) in &rows {
+            let mut converted = Vec::with_capacity(fields.len());
+            for field in fields {
+                converted.push(match decrypt_field(&self.encryption, field) {
+                    Some(plain) if !self.field_encryption => plain,
+                    None if self.field_encryption => encrypt_field(&self.encryption, field)?,
+                    _ => field.clone(),
+                });
+            }
+            tx.execute(
+                "UPDATE passwords SET username = ?1, url = ?2, notes = ?3 WHERE id = ?4",
+                rusqlite::params![converted[0], converted[1], converted[2], id],
+            )?;
+        }
+        tx.commit()?;
+
+        if !rows.is_empty() {
+            info!("Converted the fields of {} password entries", rows.len());
+        }
+        Ok(rows.len())
+    }
+
+    /// Build the SQL condition matching the entries whose fields look like they are not
+    /// stored as the field encryption demands.
+    ///
+    /// A field looks encrypted if it carries the prefix and is at least as long as the
+    /// shortest encrypted value. The condition takes the prefix and that length as `?1` and
+    /// `?2`.
+    fn unmigrated_condition(&self) -> Result<(String, i64), Box<dyn std::error::Error>> {
+        let min_length = encrypt_field(&self.encryption, " ")?.len() as i64;
+        let encrypted = |column: &str| {
+            format!(
+                "(substr({0}, 1, length(?1)) = ?1 AND length({0}) >= ?2)",
+                column
+            )
+        };
+        let condition = ["username", "url", "notes"]
+            .iter()
+            .map(|column| {
+                if self.field_encryption {
+                    format!("({} != '' AND NOT {})", column, encrypted(column))
+                } else {
+                    encrypted(column)
+                }
+            })
+            .collect::<Vec<_>>()
+            .join(" OR ");
+
+        Ok((condition, min_length))
+    }
+
+    /// Read the username, URL and notes of the entries not stored as the field encryption
+    /// demands.
+    ///
+    /// The prefix check in SQL preselects the entries, so a batch does not decrypt the
+    /// fields of the entries that are already converted.
+    fn unmigrated_entries(
+        &self,
+        limit: usize,
+    ) -> Result<Vec<EntryFields>, Box<dyn std::error::Error>> {
+        let (condition, min_length) = self.unmigrated_condition()?;
+        let mut stmt = self.connection.prepare(&format!(
+            "SELECT id, username, url, notes FROM passwords WHERE {} ORDER BY id",
+            condition
+        ))?;
+        let rows = stmt.query_map(
+            rusqlite::params![ENCRYPTED_FIELD_PREFIX, min_length],
+            |row| Ok((row.get(0)?, [row.get(1)?, row.get(2)?, row.get(3)?])),
+        )?;
+
+        let mut result = Vec::new();
+        for row in rows {
+            let (id, fields): EntryFields = row?;
+            let pending = fields.iter().any(|field| {
+                let encrypted = decrypt_field(&self.encryption, field).is_some();
+                encrypted != (self.field_encryption && !field.is_empty())
+            });
+            if pending {
+                result.push((id, fields));
+                if result.len() >= limit {
+                    break;
+                }
+            }
+        }
+
+        Ok(result)
+    }
+
+    /// Write all changes from the write-ahead log back to the database file.
+    ///
+    /// The log is truncated afterwards, so the database file alone holds every committed
+    /// change. Does nothing in sync-safe mode, where no log is kept.
+    ///
+    /// # Returns
+    ///
+    /// A Result containing a unit or an error.
+    ///
+    /// # Errors
+    ///
+    /// If the checkpoint cannot be completed, e.g. because another connection is reading.
+    pub fn checkpoint(&self) -> Result<(), Box<dyn std::error::Error>> {
+        let busy: i32 =
+            self.connection
+                .query_row("PRAGMA wal_checkpoint(TRUNCATE);", [], |row| row.get(0))?;
+        if busy != 0 {
+            return Err("Database checkpoint could not be completed".into());
+        }
+
+        Ok(())
+    }
+
     /// Create a database dump.
     ///
+    /// Pending changes in the write-ahead log are checkpointed first, so the dump contains
+    /// every committed change.
+    ///
     /// # Arguments
     ///
     /// * `path` - The path to save the dump file.
@@ -315,6 +1076,7 @@ impl Database {
         path: &Path,
         master_password: &str,
     ) -> Result<(), Box<dyn std::error::Error>> {
+        self.checkpoint()?;
         let key = self.encryption.get_key(master_password)?;
 
         self.connection.execute_batch(&format!(
@@ -368,12 +1130,40 @@ impl Database {
             key
         ))?;
 
+        self.migrate_tables("dump")?;
+
         self.connection.execute_batch(
             "BEGIN TRANSACTION;
+                 DELETE FROM attachments;
+                 DELETE FROM servers;
+                 DELETE FROM identities;
+                 DELETE FROM payment_cards;
+                 DELETE FROM secure_notes;
+                 DELETE FROM expiring_notes;
+                 DELETE FROM entry_tags;
+                 DELETE FROM tags;
+                 DELETE FROM folders;
+                 DELETE FROM generator_history;
+                 DELETE FROM pinned_entries;
+                 DELETE FROM entry_permissions;
+                 DELETE FROM health_history;
                  DELETE FROM passwords;
                  DELETE FROM user;
                  INSERT INTO user SELECT * FROM dump.user;
                  INSERT INTO passwords SELECT * FROM dump.passwords;
+                 INSERT INTO health_history SELECT * FROM dump.health_history;
+                 INSERT INTO entry_permissions SELECT * FROM dump.entry_permissions;
+                 INSERT INTO pinned_entries SELECT * FROM dump.pinned_entries;
+                 INSERT INTO generator_history SELECT * FROM dump.generator_history;
+                 INSERT INTO folders SELECT * FROM dump.folders;
+                 INSERT INTO tags SELECT * FROM dump.tags;
+                 INSERT INTO entry_tags SELECT * FROM dump.entry_tags;
+                 INSERT INTO expiring_notes SELECT * FROM dump.expiring_notes;
+                 INSERT INTO secure_notes SELECT * FROM dump.secure_notes;
+                 INSERT INTO payment_cards SELECT * FROM dump.payment_cards;
+                 INSERT INTO identities SELECT * FROM dump.identities;
+                 INSERT INTO servers SELECT * FROM dump.servers;
+                 INSERT INTO attachments SELECT * FROM dump.attachments;
                  COMMIT;",
         )?;
 
@@ -381,25 +1171,2303 @@ impl Database {
 
         Ok(())
     }
-}
 
-impl PasswordEntry {
-    /// Create a new PasswordEntry.
+    /// Replace the encrypted recovery codes of a password entry.
+    ///
+    /// The codes are not part of the regular update, so editing an entry keeps them.
     ///
     /// # Arguments
     ///
-    /// * `service` - The name of the service.
-    /// * `username` - The username for the service.
-    /// * `password` - The password for the service.
-    /// * `url` - The URL for the service.
-    /// * `notes` - Any notes for the service.
+    /// * `id` - The ID of the password entry.
+    /// * `recovery_codes` - The encrypted recovery codes, empty to remove them.
     ///
     /// # Returns
     ///
-    /// A new PasswordEntry.
-    pub fn new(
-        user_id: i32,
-        service: String,
+    /// A Result containing a unit or an error.
+    ///
+    /// # Errors
+    ///
+    /// If the entry does not exist or cannot be updated.
+    pub fn update_recovery_codes(
+        &self,
+        id: i32,
+        recovery_codes: &str,
+    ) -> Result<(), Box<dyn std::error::Error>> {
+        let changed = self.connection.execute(
+            "UPDATE passwords SET recovery_codes = ?1, updated_at = ?2 WHERE id = ?3",
+            rusqlite::params![recovery_codes, Utc::now().to_rfc3339(), id],
+        )?;
+        if changed == 0 {
+            return Err("Password entry not found".into());
+        }
+
+        Ok(())
+    }
+
+    /// Replace the encrypted TOTP secret of a password entry.
+    ///
+    /// # Arguments
+    ///
+    /// * `id` - The ID of the password entry.
+    /// * `totp_secret` - The encrypted TOTP secret, empty to remove it.
+    ///
+    /// # Returns
+    ///
+    /// A Result containing a unit or an error.
+    ///
+    /// # Errors
+    ///
+    /// If the entry does not exist or cannot be updated.
+    pub fn update_totp_secret(
+        &self,
+        id: i32,
+        totp_secret: &str,
+    ) -> Result<(), Box<dyn std::error::Error>> {
+        let changed = self.connection.execute(
+            "UPDATE passwords SET totp_secret = ?1, updated_at = ?2 WHERE id = ?3",
+            rusqlite::params![totp_secret, Utc::now().to_rfc3339(), id],
+        )?;
+        if changed == 0 {
+            return Err("Password entry not found".into());
+        }
+
+        Ok(())
+    }
+
+    /// Store the health snapshot of a user for a day.
+    ///
+    /// A later snapshot of the same day replaces the earlier one, so the history keeps one
+    /// point per day.
+    ///
+    /// # Arguments
+    ///
+    /// * `user_id` - The ID of the user.
+    /// * `snapshot` - The snapshot to store.
+    ///
+    /// # Returns
+    ///
+    /// A Result containing a unit or an error.
+    ///
+    /// # Errors
+    ///
+    /// If the snapshot cannot be stored.
+    pub fn record_health_snapshot(
+        &self,
+        user_id: i32,
+        snapshot: &HealthSnapshot,
+    ) -> Result<(), Box<dyn std::error::Error>> {
+        self.connection.execute(
+            "INSERT INTO health_history (user_id, day, entry_count, average_score)
+             VALUES (?1, ?2, ?3, ?4)
+             ON CONFLICT (user_id, day)
+             DO UPDATE SET entry_count = excluded.entry_count, average_score = excluded.average_score",
+            rusqlite::params![
+                user_id,
+                snapshot.day,
+                snapshot.entry_count,
+                snapshot.average_score
+            ],
+        )?;
+
+        Ok(())
+    }
+
+    /// Get the latest health snapshots of a user.
+    ///
+    /// # Arguments
+    ///
+    /// * `user_id` - The ID of the user.
+    /// * `limit` - The maximum number of snapshots.
+    ///
+    /// # Returns
+    ///
+    /// A Result containing the snapshots, oldest first, or an error.
+    ///
+    /// # Errors
+    ///
+    /// If the snapshots cannot be read.
+    pub fn get_health_history(
+        &self,
+        user_id: i32,
+        limit: usize,
+    ) -> Result<Vec<HealthSnapshot>, Box<dyn std::error::Error>> {
+        let mut stmt = self.connection.prepare(
+            "SELECT day, entry_count, average_score FROM health_history
+             WHERE user_id = ?1 ORDER BY day DESC LIMIT ?2",
+        )?;
+        let mut snapshots = stmt
+            .query_map(rusqlite::params![user_id, limit], |row| {
+                Ok(HealthSnapshot {
+                    day: row.get(0)?,
+                    entry_count: row.get(1)?,
+                    average_score: row.get(2)?,
+                })
+            })?
+            .collect::<Result<Vec<_>, _>>()?;
+        snapshots.reverse();
+
+        Ok(snapshots)
+    }
+
+    /// Re-encrypt the database and all encrypted fields with a new key.
+    ///
+    /// The passwords, recovery codes, generator history and master keys are encrypted again in
+    /// one transaction,
+    /// then the database itself is rekeyed. If the process stops between both steps, the
+    /// database still opens with the old key and `finish_rotation` completes the rekey.
+    ///
+    /// # Arguments
+    ///
+    /// * `master_password` - The master password the new key is derived from.
+    /// * `params` - The salt and key derivation parameters of the new key.
+    /// * `on_progress` - Called with the number of re-encrypted rows and the total.
+    ///
+    /// # Returns
+    ///
+    /// A Result containing a unit or an error.
+    ///
+    /// # Errors
+    ///
+    /// If the database only lives in memory, a field cannot be decrypted or the database
+    /// cannot be rekeyed.
+    pub fn reencrypt(
+        &mut self,
+        master_password: &str,
+        params: &KeyParams,
+        mut on_progress: impl FnMut(usize, usize),
+    ) -> Result<(), Box<dyn std::error::Error>> {
+        if self.is_in_memory() {
+            return Err("In-memory databases cannot be re-encrypted".into());
+        }
+
+        let encryption = Encryption::new(master_password, params);
+        let entries = self.read_all::<PasswordEntry>()?;
+        let users = self.read_all::<User>()?;
+        let history: Vec<(i32, String)> = {
+            let mut stmt = self
+                .connection
+                .prepare("SELECT id, password FROM generator_history")?;
+            let rows = stmt
+                .query_map([], |row| Ok((row.get(0)?, row.get(1)?)))?
+                .collect::<Result<Vec<_>, _>>()?;
+            rows
+        };
+        let notes: Vec<(i32, String)> = {
+            let mut stmt = self
+                .connection
+                .prepare("SELECT id, content FROM expiring_notes WHERE content != ''")?;
+            let rows = stmt
+                .query_map([], |row| Ok((row.get(0)?, row.get(1)?)))?
+                .collect::<Result<Vec<_>, _>>()?;
+            rows
+        };
+        let secure_notes: Vec<(i32, String)> = {
+            let mut stmt = self
+                .connection
+                .prepare("SELECT id, content FROM secure_notes")?;
+            let rows = stmt
+                .query_map([], |row| Ok((row.get(0)?, row.get(1)?)))?
+                .collect::<Result<Vec<_>, _>>()?;
+            rows
+        };
+        let cards = self.encrypted_rows("payment_cards", CARD_FIELDS)?;
+        let identities = self.encrypted_rows("identities", IDENTITY_FIELDS)?;
+        let servers = self.encrypted_rows("servers", SERVER_FIELDS)?;
+        let attachments = self.encrypted_rows("attachments", &["content"])?;
+        let total = entries.len()
+            + users.len()
+            + history.len()
+            + notes.len()
+            + secure_notes.len()
+            + cards.len()
+            + identities.len()
+            + servers.len()
+            + attachments.len();
+
+        let tx = self.connection.unchecked_transaction()?;
+        for (done, entry) in entries.iter().enumerate() {
+            let password = if entry.password.is_empty() {
+                String::new()
+            } else {
+                self.reencrypt_field(&encryption, &entry.password)?
+            };
+            let recovery_codes = if entry.recovery_codes.is_empty() {
+                String::new()
+            } else {
+                self.reencrypt_field(&encryption, &entry.recovery_codes)?
+            };
+            let totp_secret = if entry.totp_secret.is_empty() {
+                String::new()
+            } else {
+                self.reencrypt_field(&encryption, &entry.totp_secret)?
+            };
+            let mut fields = [&entry.username, &entry.url, &entry.notes].map(String::clone);
+            if self.field_encryption {
+                for field in fields.iter_mut() {
+                    *field = encrypt_field(&encryption, field)?;
+                }
+            }
+            tx.execute(
+                "UPDATE passwords SET password = ?1, recovery_codes = ?2, totp_secret = ?3,
+                 username = ?4, url = ?5, notes = ?6 WHERE id = ?7",
+                rusqlite::params![
+                    password,
+                    recovery_codes,
+                    totp_secret,
+                    fields[0],
+                    fields[1],
+                    fields[2],
+                    entry.id
+                ],
+            )?;
+            on_progress(done + 1, total);
+        }
+        for (done, user) in users.iter().enumerate() {
+            let master_key =
+                self.reencrypt_field(&encryption, std::str::from_utf8(&user.master_key)?)?;
+            tx.execute(
+                "UPDATE user SET master_key = ?1 WHERE id = ?2",
+                rusqlite::params![master_key.into_bytes(), user.id],
+            )?;
+            on_progress(entries.len() + done + 1, total);
+        }
+        for (done, (id, password)) in history.iter().enumerate() {
+            let password = self.reencrypt_field(&encryption, password)?;
+            tx.execute(
+                "UPDATE generator_history SET password = ?1 WHERE id = ?2",
+                rusqlite::params![password, id],
+            )?;
+            on_progress(entries.len() + users.len() + done + 1, total);
+        }
+        for (done, (id, content)) in notes.iter().enumerate() {
+            let content = self.reencrypt_field(&encryption, content)?;
+            tx.execute(
+                "UPDATE expiring_notes SET content = ?1 WHERE id = ?2",
+                rusqlite::params![content, id],
+            )?;
+            on_progress(
+                entries.len() + users.len() + history.len() + done + 1,
+                total,
+            );
+        }
+        for (done, (id, content)) in secure_notes.iter().enumerate() {
+            let content = self.reencrypt_field(&encryption, content)?;
+            tx.execute(
+                "UPDATE secure_notes SET content = ?1 WHERE id = ?2",
+                rusqlite::params![content, id],
+            )?;
+            on_progress(
+                entries.len() + users.len() + history.len() + notes.len() + done + 1,
+                total,
+            );
+        }
+        let items = [
+            ("payment_cards", CARD_FIELDS, &cards),
+            ("identities", IDENTITY_FIELDS, &identities),
+            ("servers", SERVER_FIELDS, &servers),
+            ("attachments", &["content"], &attachments),
+        ];
+        let mut done = total - cards.len() - identities.len() - servers.len() - attachments.len();
+        for (table, columns, rows) in items {
+            let assignments = columns
+                .iter()
+                .enumerate()
+                .map(|(i, column)| format!("{} = ?{}", column, i + 1))
+                .collect::<Vec<_>>()
+                .join(", ");
+            let sql = format!(
+                "UPDATE {} SET {} WHERE id = ?{}",
+                table,
+                assignments,
+                columns.len() + 1
+            );
+            for (id, values) in rows.iter() {
+                let mut params = Vec::with_capacity(values.len() + 1);
+                for value in values {
+                    params.push(if value.is_empty() {
+                        String::new()
+                    } else {
+                        self.reencrypt_field(&encryption, value)?
+                    });
+                }
+                params.push(id.to_string());
+                tx.execute(&sql, rusqlite::params_from_iter(params))?;
+                done += 1;
+                on_progress(done, total);
+            }
+        }
+        tx.commit()?;
+
+        let key = encryption.get_key(master_password)?;
+        self.connection
+            .execute_batch(&format!("PRAGMA rekey = '{}';", key))?;
+        self.encryption = encryption;
+
+        info!(
+            "Re-encrypted {} rows with key version {}",
+            total, params.version
+        );
+        Ok(())
+    }
+
+    /// Finish a re-encryption that stopped after the fields were encrypted with the new key.
+    ///
+    /// The master keys of the users tell which key the fields are encrypted with. If they
+    /// only decrypt with the new key, the database is rekeyed to it.
+    ///
+    /// # Arguments
+    ///
+    /// * `master_password` - The master password the keys are derived from.
+    /// * `params` - The salt and key derivation parameters of the new key.
+    ///
+    /// # Returns
+    ///
+    /// A Result containing whether the database was rekeyed or an error.
+    ///
+    /// # Errors
+    ///
+    /// If the fields decrypt with neither key or the database cannot be rekeyed.
+    pub fn finish_rotation(
+        &mut self,
+        master_password: &str,
+        params: &KeyParams,
+    ) -> Result<bool, Box<dyn std::error::Error>> {
+        let Some(user) = self.read_all::<User>()?.into_iter().next() else {
+            return Ok(false);
+        };
+        let master_key = STANDARD.decode(&user.master_key)?;
+        if self.encryption.decrypt(&master_key).is_ok() {
+            return Ok(false);
+        }
+
+        let encryption = Encryption::new(master_password, params);
+        if encryption.decrypt(&master_key).is_err() {
+            return Err("The vault fields match neither key".into());
+        }
+        let key = encryption.get_key(master_password)?;
+        self.connection
+            .execute_batch(&format!("PRAGMA rekey = '{}';", key))?;
+        self.encryption = encryption;
+
+        info!("Finished re-encryption with key version {}", params.version);
+        Ok(true)
+    }
+
+    /// Read the ID and the given encrypted columns of all rows of a table.
+    fn encrypted_rows(
+        &self,
+        table: &str,
+        columns: &[&str],
+    ) -> Result<Vec<EncryptedRow>, Box<dyn std::error::Error>> {
+        let mut stmt = self.connection.prepare(&format!(
+            "SELECT id, {} FROM {}",
+            columns.join(", "),
+            table
+        ))?;
+        let rows = stmt
+            .query_map([], |row| {
+                let values = (1..=columns.len())
+                    .map(|i| row.get(i))
+                    .collect::<Result<Vec<String>, _>>()?;
+                Ok((row.get(0)?, values))
+            })?
+            .collect::<Result<Vec<_>, _>>()?;
+
+        Ok(rows)
+    }
+
+    /// Decrypt a base64 encoded field with the current key and encrypt it with a new one.
+    fn reencrypt_field(
+        &self,
+        encryption: &Encryption,
+        encoded: &str,
+    ) -> Result<String, Box<dyn std::error::Error>> {
+        let decoded = STANDARD.decode(encoded)?;
+        let plain = self
+            .encryption
+            .decrypt(&decoded)
+            .map_err(|_| "Failed to decrypt field")?;
+        let encrypted = encryption
+            .encrypt(&plain)
+            .map_err(|_| "Failed to encrypt field")?;
+
+        Ok(STANDARD.encode(encrypted))
+    }
+
+    /// Share a password entry read-only with another user.
+    ///
+    /// # Arguments
+    ///
+    /// * `entry_id` - The ID of the password entry.
+    /// * `user_id` - The ID of the user to share the entry with.
+    ///
+    /// # Returns
+    ///
+    /// A Result containing a unit or an error.
+    ///
+    /// # Errors
+    ///
+    /// If the entry or the user does not exist.
+    pub fn grant_read_access(
+        &self,
+        entry_id: i32,
+        user_id: i32,
+    ) -> Result<(), Box<dyn std::error::Error>> {
+        self.connection.execute(
+            "INSERT OR IGNORE INTO entry_permissions (entry_id, user_id, permission)
+             VALUES (?1, ?2, 'read')",
+            rusqlite::params![entry_id, user_id],
+        )?;
+
+        Ok(())
+    }
+
+    /// Stop sharing a password entry with a user.
+    ///
+    /// # Arguments
+    ///
+    /// * `entry_id` - The ID of the password entry.
+    /// * `user_id` - The ID of the user the entry is shared with.
+    ///
+    /// # Returns
+    ///
+    /// A Result containing a unit or an error.
+    ///
+    /// # Errors
+    ///
+    /// If the permission cannot be removed.
+    pub fn revoke_access(
+        &self,
+        entry_id: i32,
+        user_id: i32,
+    ) -> Result<(), Box<dyn std::error::Error>> {
+        self.connection.execute(
+            "DELETE FROM entry_permissions WHERE entry_id = ?1 AND user_id = ?2",
+            rusqlite::params![entry_id, user_id],
+        )?;
+
+        Ok(())
+    }
+
+    /// Get the IDs of the password entries shared with a user.
+    ///
+    /// # Arguments
+    ///
+    /// * `user_id` - The ID of the user.
+    ///
+    /// # Returns
+    ///
+    /// A Result containing the IDs of the shared entries or an error.
+    ///
+    /// # Errors
+    ///
+    /// If the permissions cannot be read.
+    pub fn shared_entry_ids(&self, user_id: i32) -> Result<Vec<i32>, Box<dyn std::error::Error>> {
+        let mut stmt = self
+            .connection
+            .prepare("SELECT entry_id FROM entry_permissions WHERE user_id = ?1")?;
+        let ids = stmt
+            .query_map([user_id], |row| row.get(0))?
+            .collect::<Result<Vec<i32>, _>>()?;
+
+        Ok(ids)
+    }
+
+    /// Pin a password entry for a user, e.g. to offer it in the tray menu.
+    ///
+    /// # Arguments
+    ///
+    /// * `entry_id` - The ID of the password entry.
+    /// * `user_id` - The ID of the user pinning the entry.
+    ///
+    /// # Returns
+    ///
+    /// A Result containing a unit or an error.
+    ///
+    /// # Errors
+    ///
+    /// If the entry or the user does not exist.
+    pub fn pin_entry(&self, entry_id: i32, user_id: i32) -> Result<(), Box<dyn std::error::Error>> {
+        self.connection.execute(
+            "INSERT OR IGNORE INTO pinned_entries (entry_id, user_id, pinned_at)
+             VALUES (?1, ?2, ?3)",
+            rusqlite::params![entry_id, user_id, Utc::now().to_rfc3339()],
+        )?;
+
+        Ok(())
+    }
+
+    /// Remove the pin of a password entry for a user.
+    ///
+    /// # Arguments
+    ///
+    /// * `entry_id` - The ID of the password entry.
+    /// * `user_id` - The ID of the user.
+    ///
+    /// # Returns
+    ///
+    /// A Result containing a unit or an error.
+    ///
+    /// # Errors
+    ///
+    /// If the pin cannot be removed.
+    pub fn unpin_entry(
+        &self,
+        entry_id: i32,
+        user_id: i32,
+    ) -> Result<(), Box<dyn std::error::Error>> {
+        self.connection.execute(
+            "DELETE FROM pinned_entries WHERE entry_id = ?1 AND user_id = ?2",
+            rusqlite::params![entry_id, user_id],
+        )?;
+
+        Ok(())
+    }
+
+    /// Get the IDs of the password entries a user pinned, oldest pin first.
+    ///
+    /// # Arguments
+    ///
+    /// * `user_id` - The ID of the user.
+    ///
+    /// # Returns
+    ///
+    /// A Result containing the IDs of the pinned entries or an error.
+    ///
+    /// # Errors
+    ///
+    /// If the pins cannot be read.
+    pub fn pinned_entry_ids(&self, user_id: i32) -> Result<Vec<i32>, Box<dyn std::error::Error>> {
+        let mut stmt = self.connection.prepare(
+            "SELECT entry_id FROM pinned_entries WHERE user_id = ?1 ORDER BY pinned_at, rowid",
+        )?;
+        let ids = stmt
+            .query_map([user_id], |row| row.get(0))?
+            .collect::<Result<Vec<i32>, _>>()?;
+
+        Ok(ids)
+    }
+
+    /// Add a generated password to the history of a user.
+    ///
+    /// The history is a ring buffer, the oldest passwords beyond `limit` are removed.
+    ///
+    /// # Arguments
+    ///
+    /// * `user_id` - The ID of the user.
+    /// * `password` - The encrypted password.
+    /// * `limit` - The maximum number of passwords to keep.
+    ///
+    /// # Returns
+    ///
+    /// A Result containing a unit or an error.
+    ///
+    /// # Errors
+    ///
+    /// If the password cannot be stored or the history cannot be trimmed.
+    pub fn record_generated_password(
+        &self,
+        user_id: i32,
+        password: &str,
+        limit: usize,
+    ) -> Result<(), Box<dyn std::error::Error>> {
+        let tx = self.connection.unchecked_transaction()?;
+        tx.execute(
+            "INSERT INTO generator_history (user_id, password, created_at) VALUES (?1, ?2, ?3)",
+            rusqlite::params![user_id, password, Utc::now().to_rfc3339()],
+        )?;
+        tx.execute(
+            "DELETE FROM generator_history WHERE user_id = ?1 AND id NOT IN (
+                SELECT id FROM generator_history WHERE user_id = ?1 ORDER BY id DESC LIMIT ?2
+             )",
+            rusqlite::params![user_id, limit],
+        )?;
+        tx.commit()?;
+
+        Ok(())
+    }
+
+    /// Get the generator history of a user, newest first.
+    ///
+    /// # Arguments
+    ///
+    /// * `user_id` - The ID of the user.
+    ///
+    /// # Returns
+    ///
+    /// A Result containing the encrypted passwords or an error.
+    ///
+    /// # Errors
+    ///
+    /// If the history cannot be read.
+    pub fn generator_history(
+        &self,
+        user_id: i32,
+    ) -> Result<Vec<GeneratedPassword>, Box<dyn std::error::Error>> {
+        let mut stmt = self.connection.prepare(
+            "SELECT password, created_at FROM generator_history
+             WHERE user_id = ?1 ORDER BY id DESC",
+        )?;
+        let history = stmt
+            .query_map([user_id], |row| {
+                Ok(GeneratedPassword {
+                    password: row.get(0)?,
+                    created_at: row.get(1)?,
+                })
+            })?
+            .collect::<Result<Vec<_>, _>>()?;
+
+        Ok(history)
+    }
+
+    /// Remove the generator history of a user.
+    ///
+    /// # Arguments
+    ///
+    /// * `user_id` - The ID of the user.
+    ///
+    /// # Returns
+    ///
+    /// A Result containing a unit or an error.
+    ///
+    /// # Errors
+    ///
+    /// If the history cannot be removed.
+    pub fn clear_generator_history(&self, user_id: i32) -> Result<(), Box<dyn std::error::Error>> {
+        self.connection.execute(
+            "DELETE FROM generator_history WHERE user_id = ?1",
+            [user_id],
+        )?;
+
+        Ok(())
+    }
+
+    /// Get the folders of a user, sorted by name.
+    ///
+    /// # Arguments
+    ///
+    /// * `user_id` - The ID of the user.
+    ///
+    /// # Returns
+    ///
+    /// A Result containing the folders or an error.
+    ///
+    /// # Errors
+    ///
+    /// If the folders cannot be read.
+    pub fn folders(&self, user_id: i32) -> Result<Vec<Folder>, Box<dyn std::error::Error>> {
+        let mut stmt = self.connection.prepare(
+            "SELECT id, parent_id, name FROM folders
+             WHERE user_id = ?1 ORDER BY name COLLATE NOCASE, id",
+        )?;
+        let folders = stmt
+            .query_map([user_id], |row| {
+                Ok(Folder {
+                    id: row.get(0)?,
+                    parent_id: row.get(1)?,
+                    name: row.get(2)?,
+                })
+            })?
+            .collect::<Result<Vec<_>, _>>()?;
+
+        Ok(folders)
+    }
+
+    /// Create a folder for a user.
+    ///
+    /// # Arguments
+    ///
+    /// * `user_id` - The ID of the user.
+    /// * `name` - The name of the folder.
+    /// * `parent_id` - The folder to create it in, None for the top level.
+    ///
+    /// # Returns
+    ///
+    /// A Result containing the ID of the new folder or an error.
+    ///
+    /// # Errors
+    ///
+    /// If the folder cannot be created.
+    pub fn create_folder(
+        &self,
+        user_id: i32,
+        name: &str,
+        parent_id: Option<i32>,
+    ) -> Result<i32, Box<dyn std::error::Error>> {
+        self.connection.execute(
+            "INSERT INTO folders (user_id, parent_id, name, created_at) VALUES (?1, ?2, ?3, ?4)",
+            rusqlite::params![user_id, parent_id, name, Utc::now().to_rfc3339()],
+        )?;
+
+        Ok(self.connection.last_insert_rowid() as i32)
+    }
+
+    /// Rename a folder of a user.
+    ///
+    /// # Arguments
+    ///
+    /// * `user_id` - The ID of the user.
+    /// * `id` - The ID of the folder.
+    /// * `name` - The new name.
+    ///
+    /// # Returns
+    ///
+    /// A Result containing a unit or an error.
+    ///
+    /// # Errors
+    ///
+    /// If the folder does not exist or cannot be renamed.
+    pub fn rename_folder(
+        &self,
+        user_id: i32,
+        id: i32,
+        name: &str,
+    ) -> Result<(), Box<dyn std::error::Error>> {
+        let changed = self.connection.execute(
+            "UPDATE folders SET name = ?1 WHERE id = ?2 AND user_id = ?3",
+            rusqlite::params![name, id, user_id],
+        )?;
+        if changed == 0 {
+            return Err("Folder not found".into());
+        }
+
+        Ok(())
+    }
+
+    /// Delete a folder of a user.
+    ///
+    /// The entries and subfolders of the folder are moved into its parent, so deleting a
+    /// folder never deletes password entries.
+    ///
+    /// # Arguments
+    ///
+    /// * `user_id` - The ID of the user.
+    /// * `id` - The ID of the folder.
+    ///
+    /// # Returns
+    ///
+    /// A Result containing a unit or an error.
+    ///
+    /// # Errors
+    ///
+    /// If the folder does not exist or cannot be deleted.
+    pub fn delete_folder(&self, user_id: i32, id: i32) -> Result<(), Box<dyn std::error::Error>> {
+        let folder = self
+            .folders(user_id)?
+            .into_iter()
+            .find(|f| f.id == id)
+            .ok_or("Folder not found")?;
+
+        let tx = self.connection.unchecked_transaction()?;
+        tx.execute(
+            "UPDATE passwords SET folder_id = ?1, updated_at = ?2 WHERE folder_id = ?3",
+            rusqlite::params![folder.parent_id, Utc::now().to_rfc3339(), id],
+        )?;
+        tx.execute(
+            "UPDATE folders SET parent_id = ?1 WHERE parent_id = ?2",
+            rusqlite::params![folder.parent_id, id],
+        )?;
+        tx.execute("DELETE FROM folders WHERE id = ?1", [id])?;
+        tx.commit()?;
+
+        Ok(())
+    }
+
+    /// Move a password entry into a folder.
+    ///
+    /// # Arguments
+    ///
+    /// * `id` - The ID of the password entry.
+    /// * `folder_id` - The ID of the folder, None to take the entry out of its folder.
+    ///
+    /// # Returns
+    ///
+    /// A Result containing a unit or an error.
+    ///
+    /// # Errors
+    ///
+    /// If the entry does not exist or cannot be updated.
+    pub fn move_entry_to_folder(
+        &self,
+        id: i32,
+        folder_id: Option<i32>,
+    ) -> Result<(), Box<dyn std::error::Error>> {
+        let changed = self.connection.execute(
+            "UPDATE passwords SET folder_id = ?1, updated_at = ?2 WHERE id = ?3",
+            rusqlite::params![folder_id, Utc::now().to_rfc3339(), id],
+        )?;
+        if changed == 0 {
+            return Err("Password entry not found".into());
+        }
+
+        Ok(())
+    }
+
+    /// Get the tags of a user with their entries, sorted by name.
+    ///
+    /// # Arguments
+    ///
+    /// * `user_id` - The ID of the user.
+    ///
+    /// # Returns
+    ///
+    /// A Result containing the tags or an error.
+    ///
+    /// # Errors
+    ///
+    /// If the tags cannot be read.
+    pub fn tags(&self, user_id: i32) -> Result<Vec<Tag>, Box<dyn std::error::Error>> {
+        let mut stmt = self.connection.prepare(
+            "SELECT id, name FROM tags WHERE user_id = ?1 ORDER BY name COLLATE NOCASE, id",
+        )?;
+        let mut tags = stmt
+            .query_map([user_id], |row| {
+                Ok(Tag {
+                    id: row.get(0)?,
+                    name: row.get(1)?,
+                    entry_ids: Vec::new(),
+                })
+            })?
+            .collect::<Result<Vec<_>, _>>()?;
+
+        let mut stmt = self.connection.prepare(
+            "SELECT entry_tags.tag_id, entry_tags.entry_id FROM entry_tags
+             JOIN tags ON tags.id = entry_tags.tag_id
+             WHERE tags.user_id = ?1 ORDER BY entry_tags.entry_id",
+        )?;
+        let links = stmt
+            .query_map([user_id], |row| Ok((row.get::<_, i32>(0)?, row.get(1)?)))?
+            .collect::<Result<Vec<_>, _>>()?;
+        for (tag_id, entry_id) in links {
+            if let Some(tag) = tags.iter_mut().find(|t| t.id == tag_id) {
+                tag.entry_ids.push(entry_id);
+            }
+        }
+
+        Ok(tags)
+    }
+
+    /// Create a tag for a user.
+    ///
+    /// # Arguments
+    ///
+    /// * `user_id` - The ID of the user.
+    /// * `name` - The name of the tag.
+    ///
+    /// # Returns
+    ///
+    /// A Result containing the ID of the new tag or an error.
+    ///
+    /// # Errors
+    ///
+    /// If the tag cannot be created.
+    pub fn create_tag(&self, user_id: i32, name: &str) -> Result<i32, Box<dyn std::error::Error>> {
+        self.connection.execute(
+            "INSERT INTO tags (user_id, name, created_at) VALUES (?1, ?2, ?3)",
+            rusqlite::params![user_id, name, Utc::now().to_rfc3339()],
+        )?;
+
+        Ok(self.connection.last_insert_rowid() as i32)
+    }
+
+    /// Rename a tag of a user.
+    ///
+    /// # Arguments
+    ///
+    /// * `user_id` - The ID of the user.
+    /// * `id` - The ID of the tag.
+    /// * `name` - The new name.
+    ///
+    /// # Returns
+    ///
+    /// A Result containing a unit or an error.
+    ///
+    /// # Errors
+    ///
+    /// If the tag does not exist or cannot be renamed.
+    pub fn rename_tag(
+        &self,
+        user_id: i32,
+        id: i32,
+        name: &str,
+    ) -> Result<(), Box<dyn std::error::Error>> {
+        let changed = self.connection.execute(
+            "UPDATE tags SET name = ?1 WHERE id = ?2 AND user_id = ?3",
+            rusqlite::params![name, id, user_id],
+        )?;
+        if changed == 0 {
+            return Err("Tag not found".into());
+        }
+
+        Ok(())
+    }
+
+    /// Delete a tag of a user, the entries only lose the tag.
+    ///
+    /// # Arguments
+    ///
+    /// * `user_id` - The ID of the user.
+    /// * `id` - The ID of the tag.
+    ///
+    /// # Returns
+    ///
+    /// A Result containing a unit or an error.
+    ///
+    /// # Errors
+    ///
+    /// If the tag does not exist or cannot be deleted.
+    pub fn delete_tag(&self, user_id: i32, id: i32) -> Result<(), Box<dyn std::error::Error>> {
+        let changed = self.connection.execute(
+            "DELETE FROM tags WHERE id = ?1 AND user_id = ?2",
+            rusqlite::params![id, user_id],
+        )?;
+        if changed == 0 {
+            return Err("Tag not found".into());
+        }
+
+        Ok(())
+    }
+
+    /// Add a tag to a password entry, adding it twice has no effect.
+    ///
+    /// # Arguments
+    ///
+    /// * `entry_id` - The ID of the password entry.
+    /// * `tag_id` - The ID of the tag.
+    ///
+    /// # Returns
+    ///
+    /// A Result containing a unit or an error.
+    ///
+    /// # Errors
+    ///
+    /// If the tag cannot be added.
+    pub fn add_entry_tag(
+        &self,
+        entry_id: i32,
+        tag_id: i32,
+    ) -> Result<(), Box<dyn std::error::Error>> {
+        self.connection.execute(
+            "INSERT OR IGNORE INTO entry_tags (entry_id, tag_id) VALUES (?1, ?2)",
+            [entry_id, tag_id],
+        )?;
+
+        Ok(())
+    }
+
+    /// Remove a tag from a password entry.
+    ///
+    /// # Arguments
+    ///
+    /// * `entry_id` - The ID of the password entry.
+    /// * `tag_id` - The ID of the tag.
+    ///
+    /// # Returns
+    ///
+    /// A Result containing a unit or an error.
+    ///
+    /// # Errors
+    ///
+    /// If the tag cannot be removed.
+    pub fn remove_entry_tag(
+        &self,
+        entry_id: i32,
+        tag_id: i32,
+    ) -> Result<(), Box<dyn std::error::Error>> {
+        self.connection.execute(
+            "DELETE FROM entry_tags WHERE entry_id = ?1 AND tag_id = ?2",
+            [entry_id, tag_id],
+        )?;
+
+        Ok(())
+    }
+
+    /// Get the expiring notes of a password entry, sorted by expiry date.
+    ///
+    /// # Arguments
+    ///
+    /// * `entry_id` - The ID of the password entry.
+    ///
+    /// # Returns
+    ///
+    /// A Result containing the notes with their encrypted content or an error.
+    ///
+    /// # Errors
+    ///
+    /// If the notes cannot be read.
+    pub fn expiring_notes(
+        &self,
+        entry_id: i32,
+    ) -> Result<Vec<ExpiringNote>, Box<dyn std::error::Error>> {
+        let mut stmt = self.connection.prepare(
+            "SELECT id, entry_id, content, expires_on, redacted_at, created_at FROM expiring_notes
+             WHERE entry_id = ?1 ORDER BY expires_on, id",
+        )?;
+        let notes = stmt
+            .query_map([entry_id], |row| {
+                Ok(ExpiringNote {
+                    id: row.get(0)?,
+                    entry_id: row.get(1)?,
+                    content: row.get(2)?,
+                    expires_on: row.get(3)?,
+                    redacted_at: row.get(4)?,
+                    created_at: row.get(5)?,
+                })
+            })?
+            .collect::<Result<Vec<_>, _>>()?;
+
+        Ok(notes)
+    }
+
+    /// Add an expiring note to a password entry.
+    ///
+    /// # Arguments
+    ///
+    /// * `entry_id` - The ID of the password entry.
+    /// * `content` - The encrypted content of the note.
+    /// * `expires_on` - The last day the note is kept as `YYYY-MM-DD`.
+    ///
+    /// # Returns
+    ///
+    /// A Result containing the ID of the new note or an error.
+    ///
+    /// # Errors
+    ///
+    /// If the note cannot be created.
+    pub fn create_expiring_note(
+        &self,
+        entry_id: i32,
+        content: &str,
+        expires_on: &str,
+    ) -> Result<i32, Box<dyn std::error::Error>> {
+        self.connection.execute(
+            "INSERT INTO expiring_notes (entry_id, content, expires_on, created_at)
+             VALUES (?1, ?2, ?3, ?4)",
+            rusqlite::params![entry_id, content, expires_on, Utc::now().to_rfc3339()],
+        )?;
+
+        Ok(self.connection.last_insert_rowid() as i32)
+    }
+
+    /// Delete an expiring note of a password entry.
+    ///
+    /// # Arguments
+    ///
+    /// * `entry_id` - The ID of the password entry.
+    /// * `id` - The ID of the note.
+    ///
+    /// # Returns
+    ///
+    /// A Result containing a unit or an error.
+    ///
+    /// # Errors
+    ///
+    /// If the note does not exist or cannot be deleted.
+    pub fn delete_expiring_note(
+        &self,
+        entry_id: i32,
+        id: i32,
+    ) -> Result<(), Box<dyn std::error::Error>> {
+        let changed = self.connection.execute(
+            "DELETE FROM expiring_notes WHERE id = ?1 AND entry_id = ?2",
+            [id, entry_id],
+        )?;
+        if changed == 0 {
+            return Err("Note not found".into());
+        }
+
+        Ok(())
+    }
+
+    /// Redact the content of the expiring notes of a user whose expiry date has passed.
+    ///
+    /// The notes stay with an empty content, so the user sees that they expired.
+    ///
+    /// # Arguments
+    ///
+    /// * `user_id` - The ID of the user.
+    /// * `today` - The current day as `YYYY-MM-DD`, notes expiring before it are redacted.
+    ///
+    /// # Returns
+    ///
+    /// A Result containing the number of redacted notes or an error.
+    ///
+    /// # Errors
+    ///
+    /// If the notes cannot be updated.
+    pub fn redact_expiring_notes(
+        &self,
+        user_id: i32,
+        today: &str,
+    ) -> Result<usize, Box<dyn std::error::Error>> {
+        let redacted = self.connection.execute(
+            "UPDATE expiring_notes SET content = '', redacted_at = ?1
+             WHERE redacted_at IS NULL AND expires_on < ?2
+             AND entry_id IN (SELECT id FROM passwords WHERE user_id = ?3)",
+            rusqlite::params![Utc::now().to_rfc3339(), today, user_id],
+        )?;
+
+        Ok(redacted)
+    }
+
+    /// Get the secure notes of a user sorted by title.
+    ///
+    /// # Arguments
+    ///
+    /// * `user_id` - The ID of the user.
+    ///
+    /// # Returns
+    ///
+    /// A Result containing the notes with their encrypted content or an error.
+    ///
+    /// # Errors
+    ///
+    /// If the notes cannot be read.
+    pub fn secure_notes(
+        &self,
+        user_id: i32,
+    ) -> Result<Vec<SecureNote>, Box<dyn std::error::Error>> {
+        let mut stmt = self.connection.prepare(
+            "SELECT id, title, content, created_at, updated_at FROM secure_notes
+             WHERE user_id = ?1 ORDER BY title COLLATE NOCASE, id",
+        )?;
+        let notes = stmt
+            .query_map([user_id], |row| {
+                Ok(SecureNote {
+                    id: row.get(0)?,
+                    title: row.get(1)?,
+                    content: row.get(2)?,
+                    created_at: row.get(3)?,
+                    updated_at: row.get(4)?,
+                })
+            })?
+            .collect::<Result<Vec<_>, _>>()?;
+
+        Ok(notes)
+    }
+
+    /// Create a secure note for a user.
+    ///
+    /// # Arguments
+    ///
+    /// * `user_id` - The ID of the user.
+    /// * `title` - The title of the note.
+    /// * `content` - The encrypted content of the note.
+    ///
+    /// # Returns
+    ///
+    /// A Result containing the ID of the new note or an error.
+    ///
+    /// # Errors
+    ///
+    /// If the note cannot be created.
+    pub fn create_secure_note(
+        &self,
+        user_id: i32,
+        title: &str,
+        content: &str,
+    ) -> Result<i32, Box<dyn std::error::Error>> {
+        let now = Utc::now().to_rfc3339();
+        self.connection.execute(
+            "INSERT INTO secure_notes (user_id, title, content, created_at, updated_at)
+             VALUES (?1, ?2, ?3, ?4, ?4)",
+            rusqlite::params![user_id, title, content, now],
+        )?;
+
+        Ok(self.connection.last_insert_rowid() as i32)
+    }
+
+    /// Replace the title and content of a secure note of a user.
+    ///
+    /// # Arguments
+    ///
+    /// * `user_id` - The ID of the user.
+    /// * `id` - The ID of the note.
+    /// * `title` - The new title of the note.
+    /// * `content` - The new encrypted content of the note.
+    ///
+    /// # Returns
+    ///
+    /// A Result containing a unit or an error.
+    ///
+    /// # Errors
+    ///
+    /// If the note does not exist or cannot be updated.
+    pub fn update_secure_note(
+        &self,
+        user_id: i32,
+        id: i32,
+        title: &str,
+        content: &str,
+    ) -> Result<(), Box<dyn std::error::Error>> {
+        let changed = self.connection.execute(
+            "UPDATE secure_notes SET title = ?1, content = ?2, updated_at = ?3
+             WHERE id = ?4 AND user_id = ?5",
+            rusqlite::params![title, content, Utc::now().to_rfc3339(), id, user_id],
+        )?;
+        if changed == 0 {
+            return Err("Note not found".into());
+        }
+
+        Ok(())
+    }
+
+    /// Delete a secure note of a user.
+    ///
+    /// # Arguments
+    ///
+    /// * `user_id` - The ID of the user.
+    /// * `id` - The ID of the note.
+    ///
+    /// # Returns
+    ///
+    /// A Result containing a unit or an error.
+    ///
+    /// # Errors
+    ///
+    /// If the note does not exist or cannot be deleted.
+    pub fn delete_secure_note(
+        &self,
+        user_id: i32,
+        id: i32,
+    ) -> Result<(), Box<dyn std::error::Error>> {
+        let changed = self.connection.execute(
+            "DELETE FROM secure_notes WHERE id = ?1 AND user_id = ?2",
+            [id, user_id],
+        )?;
+        if changed == 0 {
+            return Err("Note not found".into());
+        }
+
+        Ok(())
+    }
+
+    /// Get the payment cards of a user sorted by title.
+    ///
+    /// # Arguments
+    ///
+    /// * `user_id` - The ID of the user.
+    ///
+    /// # Returns
+    ///
+    /// A Result containing the cards with their encrypted fields or an error.
+    ///
+    /// # Errors
+    ///
+    /// If the cards cannot be read.
+    pub fn payment_cards(
+        &self,
+        user_id: i32,
+    ) -> Result<Vec<PaymentCard>, Box<dyn std::error::Error>> {
+        let mut stmt = self.connection.prepare(
+            "SELECT id, title, holder, number, expiry, cvc, created_at, updated_at
+             FROM payment_cards WHERE user_id = ?1 ORDER BY title COLLATE NOCASE, id",
+        )?;
+        let cards = stmt
+            .query_map([user_id], |row| {
+                Ok(PaymentCard {
+                    id: row.get(0)?,
+                    title: row.get(1)?,
+                    holder: row.get(2)?,
+                    number: row.get(3)?,
+                    expiry: row.get(4)?,
+                    cvc: row.get(5)?,
+                    created_at: row.get(6)?,
+                    updated_at: row.get(7)?,
+                })
+            })?
+            .collect::<Result<Vec<_>, _>>()?;
+
+        Ok(cards)
+    }
+
+    /// Create a payment card for a user.
+    ///
+    /// # Arguments
+    ///
+    /// * `user_id` - The ID of the user.
+    /// * `card` - The card with its encrypted fields, the ID is ignored.
+    ///
+    /// # Returns
+    ///
+    /// A Result containing the ID of the new card or an error.
+    ///
+    /// # Errors
+    ///
+    /// If the card cannot be created.
+    pub fn create_payment_card(
+        &self,
+        user_id: i32,
+        card: &PaymentCard,
+    ) -> Result<i32, Box<dyn std::error::Error>> {
+        self.connection.execute(
+            "INSERT INTO payment_cards
+             (user_id, title, holder, number, expiry, cvc, created_at, updated_at)
+             VALUES (?1, ?2, ?3, ?4, ?5, ?6, ?7, ?7)",
+            rusqlite::params![
+                user_id,
+                card.title,
+                card.holder,
+                card.number,
+                card.expiry,
+                card.cvc,
+                Utc::now().to_rfc3339()
+            ],
+        )?;
+
+        Ok(self.connection.last_insert_rowid() as i32)
+    }
+
+    /// Replace the fields of a payment card of a user.
+    ///
+    /// # Arguments
+    ///
+    /// * `user_id` - The ID of the user.
+    /// * `card` - The card with its ID and encrypted fields.
+    ///
+    /// # Returns
+    ///
+    /// A Result containing a unit or an error.
+    ///
+    /// # Errors
+    ///
+    /// If the card does not exist or cannot be updated.
+    pub fn update_payment_card(
+        &self,
+        user_id: i32,
+        card: &PaymentCard,
+    ) -> Result<(), Box<dyn std::error::Error>> {
+        let changed = self.connection.execute(
+            "UPDATE payment_cards SET title = ?1, holder = ?2, number = ?3, expiry = ?4,
+             cvc = ?5, updated_at = ?6 WHERE id = ?7 AND user_id = ?8",
+            rusqlite::params![
+                card.title,
+                card.holder,
+                card.number,
+                card.expiry,
+                card.cvc,
+                Utc::now().to_rfc3339(),
+                card.id,
+                user_id
+            ],
+        )?;
+        if changed == 0 {
+            return Err("Card not found".into());
+        }
+
+        Ok(())
+    }
+
+    /// Delete a payment card of a user.
+    ///
+    /// # Arguments
+    ///
+    /// * `user_id` - The ID of the user.
+    /// * `id` - The ID of the card.
+    ///
+    /// # Returns
+    ///
+    /// A Result containing a unit or an error.
+    ///
+    /// # Errors
+    ///
+    /// If the card does not exist or cannot be deleted.
+    pub fn delete_payment_card(
+        &self,
+        user_id: i32,
+        id: i32,
+    ) -> Result<(), Box<dyn std::error::Error>> {
+        let changed = self.connection.execute(
+            "DELETE FROM payment_cards WHERE id = ?1 AND user_id = ?2",
+            [id, user_id],
+        )?;
+        if changed == 0 {
+            return Err("Card not found".into());
+        }
+
+        Ok(())
+    }
+
+    /// Get the identities of a user sorted by title.
+    ///
+    /// # Arguments
+    ///
+    /// * `user_id` - The ID of the user.
+    ///
+    /// # Returns
+    ///
+    /// A Result containing the identities with their encrypted fields or an error.
+    ///
+    /// # Errors
+    ///
+    /// If the identities cannot be read.
+    pub fn identities(&self, user_id: i32) -> Result<Vec<Identity>, Box<dyn std::error::Error>> {
+        let mut stmt = self.connection.prepare(
+            "SELECT id, title, name, address, phone, created_at, updated_at
+             FROM identities WHERE user_id = ?1 ORDER BY title COLLATE NOCASE, id",
+        )?;
+        let identities = stmt
+            .query_map([user_id], |row| {
+                Ok(Identity {
+                    id: row.get(0)?,
+                    title: row.get(1)?,
+                    name: row.get(2)?,
+                    address: row.get(3)?,
+                    phone: row.get(4)?,
+                    created_at: row.get(5)?,
+                    updated_at: row.get(6)?,
+                })
+            })?
+            .collect::<Result<Vec<_>, _>>()?;
+
+        Ok(identities)
+    }
+
+    /// Create an identity for a user.
+    ///
+    /// # Arguments
+    ///
+    /// * `user_id` - The ID of the user.
+    /// * `identity` - The identity with its encrypted fields, the ID is ignored.
+    ///
+    /// # Returns
+    ///
+    /// A Result containing the ID of the new identity or an error.
+    ///
+    /// # Errors
+    ///
+    /// If the identity cannot be created.
+    pub fn create_identity(
+        &self,
+        user_id: i32,
+        identity: &Identity,
+    ) -> Result<i32, Box<dyn std::error::Error>> {
+        self.connection.execute(
+            "INSERT INTO identities (user_id, title, name, address, phone, created_at, updated_at)
+             VALUES (?1, ?2, ?3, ?4, ?5, ?6, ?6)",
+            rusqlite::params![
+                user_id,
+                identity.title,
+                identity.name,
+                identity.address,
+                identity.phone,
+                Utc::now().to_rfc3339()
+            ],
+        )?;
+
+        Ok(self.connection.last_insert_rowid() as i32)
+    }
+
+    /// Replace the fields of an identity of a user.
+    ///
+    /// # Arguments
+    ///
+    /// * `user_id` - The ID of the user.
+    /// * `identity` - The identity with its ID and encrypted fields.
+    ///
+    /// # Returns
+    ///
+    /// A Result containing a unit or an error.
+    ///
+    /// # Errors
+    ///
+    /// If the identity does not exist or cannot be updated.
+    pub fn update_identity(
+        &self,
+        user_id: i32,
+        identity: &Identity,
+    ) -> Result<(), Box<dyn std::error::Error>> {
+        let changed = self.connection.execute(
+            "UPDATE identities SET title = ?1, name = ?2, address = ?3, phone = ?4,
+             updated_at = ?5 WHERE id = ?6 AND user_id = ?7",
+            rusqlite::params![
+                identity.title,
+                identity.name,
+                identity.address,
+                identity.phone,
+                Utc::now().to_rfc3339(),
+                identity.id,
+                user_id
+            ],
+        )?;
+        if changed == 0 {
+            return Err("Identity not found".into());
+        }
+
+        Ok(())
+    }
+
+    /// Delete an identity of a user.
+    ///
+    /// # Arguments
+    ///
+    /// * `user_id` - The ID of the user.
+    /// * `id` - The ID of the identity.
+    ///
+    /// # Returns
+    ///
+    /// A Result containing a unit or an error.
+    ///
+    /// # Errors
+    ///
+    /// If the identity does not exist or cannot be deleted.
+    pub fn delete_identity(&self, user_id: i32, id: i32) -> Result<(), Box<dyn std::error::Error>> {
+        let changed = self.connection.execute(
+            "DELETE FROM identities WHERE id = ?1 AND user_id = ?2",
+            [id, user_id],
+        )?;
+        if changed == 0 {
+            return Err("Identity not found".into());
+        }
+
+        Ok(())
+    }
+
+    /// Get the servers of a user sorted by title.
+    ///
+    /// # Arguments
+    ///
+    /// * `user_id` - The ID of the user.
+    ///
+    /// # Returns
+    ///
+    /// A Result containing the servers with their encrypted fields or an error.
+    ///
+    /// # Errors
+    ///
+    /// If the servers cannot be read.
+    pub fn servers(&self, user_id: i32) -> Result<Vec<Server>, Box<dyn std::error::Error>> {
+        let mut stmt = self.connection.prepare(
+            "SELECT id, title, host, port, protocol, username, key_note_id, created_at, updated_at
+             FROM servers WHERE user_id = ?1 ORDER BY title COLLATE NOCASE, id",
+        )?;
+        let servers = stmt
+            .query_map([user_id], |row| {
+                Ok(Server {
+                    id: row.get(0)?,
+                    title: row.get(1)?,
+                    host: row.get(2)?,
+                    port: row.get(3)?,
+                    protocol: row.get(4)?,
+                    username: row.get(5)?,
+                    key_note_id: row.get(6)?,
+                    created_at: row.get(7)?,
+                    updated_at: row.get(8)?,
+                })
+            })?
+            .collect::<Result<Vec<_>, _>>()?;
+
+        Ok(servers)
+    }
+
+    /// Create a server for a user.
+    ///
+    /// # Arguments
+    ///
+    /// * `user_id` - The ID of the user.
+    /// * `server` - The server with its encrypted fields, the ID is ignored.
+    ///
+    /// # Returns
+    ///
+    /// A Result containing the ID of the new server or an error.
+    ///
+    /// # Errors
+    ///
+    /// If the server cannot be created.
+    pub fn create_server(
+        &self,
+        user_id: i32,
+        server: &Server,
+    ) -> Result<i32, Box<dyn std::error::Error>> {
+        self.connection.execute(
+            "INSERT INTO servers
+             (user_id, title, host, port, protocol, username, key_note_id, created_at, updated_at)
+             VALUES (?1, ?2, ?3, ?4, ?5, ?6, ?7, ?8, ?8)",
+            rusqlite::params![
+                user_id,
+                server.title,
+                server.host,
+                server.port,
+                server.protocol,
+                server.username,
+                server.key_note_id,
+                Utc::now().to_rfc3339()
+            ],
+        )?;
+
+        Ok(self.connection.last_insert_rowid() as i32)
+    }
+
+    /// Replace the fields of a server of a user.
+    ///
+    /// # Arguments
+    ///
+    /// * `user_id` - The ID of the user.
+    /// * `server` - The server with its ID and encrypted fields.
+    ///
+    /// # Returns
+    ///
+    /// A Result containing a unit or an error.
+    ///
+    /// # Errors
+    ///
+    /// If the server does not exist or cannot be updated.
+    pub fn update_server(
+        &self,
+        user_id: i32,
+        server: &Server,
+    ) -> Result<(), Box<dyn std::error::Error>> {
+        let changed = self.connection.execute(
+            "UPDATE servers SET title = ?1, host = ?2, port = ?3, protocol = ?4, username = ?5,
+             key_note_id = ?6, updated_at = ?7 WHERE id = ?8 AND user_id = ?9",
+            rusqlite::params![
+                server.title,
+                server.host,
+                server.port,
+                server.protocol,
+                server.username,
+                server.key_note_id,
+                Utc::now().to_rfc3339(),
+                server.id,
+                user_id
+            ],
+        )?;
+        if changed == 0 {
+            return Err("Server not found".into());
+        }
+
+        Ok(())
+    }
+
+    /// Delete a server of a user.
+    ///
+    /// # Arguments
+    ///
+    /// * `user_id` - The ID of the user.
+    /// * `id` - The ID of the server.
+    ///
+    /// # Returns
+    ///
+    /// A Result containing a unit or an error.
+    ///
+    /// # Errors
+    ///
+    /// If the server does not exist or cannot be deleted.
+    pub fn delete_server(&self, user_id: i32, id: i32) -> Result<(), Box<dyn std::error::Error>> {
+        let changed = self.connection.execute(
+            "DELETE FROM servers WHERE id = ?1 AND user_id = ?2",
+            [id, user_id],
+        )?;
+        if changed == 0 {
+            return Err("Server not found".into());
+        }
+
+        Ok(())
+    }
+
+    /// Get the attachments of a password entry sorted by file name.
+    ///
+    /// # Arguments
+    ///
+    /// * `entry_id` - The ID of the password entry.
+    ///
+    /// # Returns
+    ///
+    /// A Result containing the attachments without their content or an error.
+    ///
+    /// # Errors
+    ///
+    /// If the attachments cannot be read.
+    pub fn attachments(
+        &self,
+        entry_id: i32,
+    ) -> Result<Vec<Attachment>, Box<dyn std::error::Error>> {
+        let mut stmt = self.connection.prepare(
+            "SELECT id, entry_id, file_name, size, created_at FROM attachments
+             WHERE entry_id = ?1 ORDER BY file_name COLLATE NOCASE, id",
+        )?;
+        let attachments = stmt
+            .query_map([entry_id], |row| {
+                Ok(Attachment {
+                    id: row.get(0)?,
+                    entry_id: row.get(1)?,
+                    file_name: row.get(2)?,
+                    size: row.get(3)?,
+                    created_at: row.get(4)?,
+                })
+            })?
+            .collect::<Result<Vec<_>, _>>()?;
+
+        Ok(attachments)
+    }
+
+    /// Get the encrypted content of an attachment of a password entry.
+    ///
+    /// # Arguments
+    ///
+    /// * `entry_id` - The ID of the password entry.
+    /// * `id` - The ID of the attachment.
+    ///
+    /// # Returns
+    ///
+    /// A Result containing the file name and the encrypted content or an error.
+    ///
+    /// # Errors
+    ///
+    /// If the attachment does not exist or cannot be read.
+    pub fn attachment_content(
+        &self,
+        entry_id: i32,
+        id: i32,
+    ) -> Result<(String, String), Box<dyn std::error::Error>> {
+        self.connection
+            .query_row(
+                "SELECT file_name, content FROM attachments WHERE id = ?1 AND entry_id = ?2",
+                [id, entry_id],
+                |row| Ok((row.get(0)?, row.get(1)?)),
+            )
+            .optional()?
+            .ok_or_else(|| "Attachment not found".into())
+    }
+
+    /// Attach a file to a password entry.
+    ///
+    /// # Arguments
+    ///
+    /// * `entry_id` - The ID of the password entry.
+    /// * `file_name` - The name of the file without its directory.
+    /// * `size` - The size of the plain file in bytes.
+    /// * `content` - The encrypted content of the file.
+    ///
+    /// # Returns
+    ///
+    /// A Result containing the ID of the new attachment or an error.
+    ///
+    /// # Errors
+    ///
+    /// If the attachment cannot be created.
+    pub fn create_attachment(
+        &self,
+        entry_id: i32,
+        file_name: &str,
+        size: i64,
+        content: &str,
+    ) -> Result<i32, Box<dyn std::error::Error>> {
+        self.connection.execute(
+            "INSERT INTO attachments (entry_id, file_name, size, content, created_at)
+             VALUES (?1, ?2, ?3, ?4, ?5)",
+            rusqlite::params![entry_id, file_name, size, content, Utc::now().to_rfc3339()],
+        )?;
+
+        Ok(self.connection.last_insert_rowid() as i32)
+    }
+
+    /// Delete an attachment of a password entry.
+    ///
+    /// # Arguments
+    ///
+    /// * `entry_id` - The ID of the password entry.
+    /// * `id` - The ID of the attachment.
+    ///
+    /// # Returns
+    ///
+    /// A Result containing a unit or an error.
+    ///
+    /// # Errors
+    ///
+    /// If the attachment does not exist or cannot be deleted.
+    pub fn delete_attachment(
+        &self,
+        entry_id: i32,
+        id: i32,
+    ) -> Result<(), Box<dyn std::error::Error>> {
+        let changed = self.connection.execute(
+            "DELETE FROM attachments WHERE id = ?1 AND entry_id = ?2",
+            [id, entry_id],
+        )?;
+        if changed == 0 {
+            return Err("Attachment not found".into());
+        }
+
+        Ok(())
+    }
+
+    /// Lock or unlock a password entry against changes.
+    ///
+    /// # Arguments
+    ///
+    /// * `id` - The ID of the password entry.
+    /// * `locked` - Whether the entry is read-only.
+    ///
+    /// # Returns
+    ///
+    /// A Result containing a unit or an error.
+    ///
+    /// # Errors
+    ///
+    /// If the entry does not exist or cannot be updated.
+    pub fn update_locked(&self, id: i32, locked: bool) -> Result<(), Box<dyn std::error::Error>> {
+        let changed = self.connection.execute(
+            "UPDATE passwords SET locked = ?1, updated_at = ?2 WHERE id = ?3",
+            rusqlite::params![locked, Utc::now().to_rfc3339(), id],
+        )?;
+        if changed == 0 {
+            return Err("Password entry not found".into());
+        }
+
+        Ok(())
+    }
+
+    /// Set or remove the expiry date of a password entry.
+    ///
+    /// The modification date is kept, so the age of the password is not reset.
+    ///
+    /// # Arguments
+    ///
+    /// * `id` - The ID of the password entry.
+    /// * `expires_on` - The last day the password is valid as `YYYY-MM-DD`, None to remove it.
+    ///
+    /// # Returns
+    ///
+    /// A Result containing a unit or an error.
+    ///
+    /// # Errors
+    ///
+    /// If the entry does not exist or cannot be updated.
+    pub fn update_expiry(
+        &self,
+        id: i32,
+        expires_on: Option<&str>,
+    ) -> Result<(), Box<dyn std::error::Error>> {
+        let changed = self.connection.execute(
+            "UPDATE passwords SET expires_on = ?1 WHERE id = ?2",
+            rusqlite::params![expires_on, id],
+        )?;
+        if changed == 0 {
+            return Err("Password entry not found".into());
+        }
+
+        Ok(())
+    }
+
+    /// Move a password entry to the trash or restore it from there.
+    ///
+    /// # Arguments
+    ///
+    /// * `id` - The ID of the password entry.
+    /// * `deleted_at` - When the entry was moved to the trash, None to restore it.
+    ///
+    /// # Returns
+    ///
+    /// A Result containing a unit or an error.
+    ///
+    /// # Errors
+    ///
+    /// If the entry does not exist or cannot be updated.
+    pub fn update_deleted_at(
+        &self,
+        id: i32,
+        deleted_at: Option<&str>,
+    ) -> Result<(), Box<dyn std::error::Error>> {
+        let changed = self.connection.execute(
+            "UPDATE passwords SET deleted_at = ?1, updated_at = ?2 WHERE id = ?3",
+            rusqlite::params![deleted_at, Utc::now().to_rfc3339(), id],
+        )?;
+        if changed == 0 {
+            return Err("Password entry not found".into());
+        }
+
+        Ok(())
+    }
+
+    /// Permanently delete the password entries of a user that are in the trash.
+    ///
+    /// # Arguments
+    ///
+    /// * `user_id` - The ID of the user.
+    /// * `before` - Only delete entries moved to the trash before this RFC 3339 timestamp,
+    ///   None deletes all of them.
+    ///
+    /// # Returns
+    ///
+    /// A Result containing the number of deleted entries or an error.
+    ///
+    /// # Errors
+    ///
+    /// If the entries cannot be deleted.
+    pub fn purge_trash(
+        &self,
+        user_id: i32,
+        before: Option<&str>,
+    ) -> Result<usize, Box<dyn std::error::Error>> {
+        let deleted = self.connection.execute(
+            "DELETE FROM passwords
+             WHERE user_id = ?1 AND deleted_at IS NOT NULL AND (?2 IS NULL OR deleted_at < ?2)",
+            rusqlite::params![user_id, before],
+        )?;
+
+        Ok(deleted)
+    }
+
+    /// Read all rows of the tables an incremental backup carries besides users and entries.
+    ///
+    /// # Returns
+    ///
+    /// A Result containing the rows of each table or an error.
+    ///
+    /// # Errors
+    ///
+    /// If a table cannot be read.
+    pub fn increment_tables(&self) -> Result<Vec<TableRows>, Box<dyn std::error::Error>> {
+        INCREMENT_TABLES
+            .iter()
+            .map(|table| {
+                let columns: Vec<String> = Self::table_columns(&self.connection, table)?
+                    .into_iter()
+                    .map(|(name, _)| name)
+                    .collect();
+                let mut stmt = self.connection.prepare(&format!(
+                    "SELECT {} FROM {}",
+                    columns.join(", "),
+                    table
+                ))?;
+                let rows = stmt
+                    .query_map([], |row| {
+                        (0..columns.len())
+                            .map(|i| row.get_ref(i).map(sql_to_json))
+                            .collect::<Result<Vec<_>, _>>()
+                    })?
+                    .collect::<Result<Vec<_>, _>>()?;
+
+                Ok(TableRows {
+                    table: table.to_string(),
+                    columns,
+                    rows,
+                })
+            })
+            .collect()
+    }
+
+    /// Read the columns of a table with whether they belong to its primary key.
+    fn table_columns(
+        connection: &Connection,
+        table: &str,
+    ) -> Result<Vec<(String, bool)>, Box<dyn std::error::Error>> {
+        let mut stmt = connection.prepare(&format!("PRAGMA table_info({})", table))?;
+        let columns = stmt
+            .query_map([], |row| Ok((row.get(1)?, row.get::<_, i32>(5)? > 0)))?
+            .collect::<Result<Vec<_>, _>>()?;
+        Ok(columns)
+    }
+
+    /// Replace the rows of a table with the rows carried by an increment.
+    ///
+    /// Rows are matched by their primary key, so updating a row never deletes its children.
+    fn apply_table_rows(
+        connection: &Connection,
+        rows: &TableRows,
+    ) -> Result<(), Box<dyn std::error::Error>> {
+        if !INCREMENT_TABLES.contains(&rows.table.as_str()) {
+            return Err(format!("Unknown table {} in increment", rows.table).into());
+        }
+        let known = Self::table_columns(connection, &rows.table)?;
+        if let Some(column) = rows
+            .columns
+            .iter()
+            .find(|column| !known.iter().any(|(name, _)| name == *column))
+        {
+            return Err(format!("Unknown column {} in increment", column).into());
+        }
+
+        let key: Vec<usize> = rows
+            .columns
+            .iter()
+            .enumerate()
+            .filter(|(_, column)| known.iter().any(|(name, pk)| *pk && name == *column))
+            .map(|(i, _)| i)
+            .collect();
+        if key.is_empty() {
+            return Err(format!("Increment misses the key of {}", rows.table).into());
+        }
+        let key_columns: Vec<&str> = key.iter().map(|&i| rows.columns[i].as_str()).collect();
+        let kept: Vec<Vec<&serde_json::Value>> = rows
+            .rows
+            .iter()
+            .map(|row| key.iter().map(|&i| &row[i]).collect())
+            .collect();
+
+        let existing: Vec<Vec<serde_json::Value>> = {
+            let mut stmt = connection.prepare(&format!(
+                "SELECT {} FROM {}",
+                key_columns.join(", "),
+                rows.table
+            ))?;
+            let keys = stmt
+                .query_map([], |row| {
+                    (0..key.len())
+                        .map(|i| row.get_ref(i).map(sql_to_json))
+                        .collect::<Result<Vec<_>, _>>()
+                })?
+                .collect::<Result<Vec<_>, _>>()?;
+            keys
+        };
+        let delete = format!(
+            "DELETE FROM {} WHERE {}",
+            rows.table,
+            key_columns
+                .iter()
+                .enumerate()
+                .map(|(i, column)| format!("{} = ?{}", column, i + 1))
+                .collect::<Vec<_>>()
+                .join(" AND ")
+        );
+        for values in existing
+            .iter()
+            .filter(|values| !kept.iter().any(|k| k.iter().copied().eq(values.iter())))
+        {
+            let params = values
+                .iter()
+                .map(json_to_sql)
+                .collect::<Result<Vec<_>, _>>()?;
+            connection.execute(&delete, rusqlite::params_from_iter(params))?;
+        }
+
+        let updates: Vec<String> = rows
+            .columns
+            .iter()
+            .filter(|column| !key_columns.contains(&column.as_str()))
+            .map(|column| format!("{0} = excluded.{0}", column))
+            .collect();
+        let upsert = format!(
+            "INSERT INTO {} ({}) VALUES ({}) ON CONFLICT DO {}",
+            rows.table,
+            rows.columns.join(", "),
+            (1..=rows.columns.len())
+                .map(|i| format!("?{}", i))
+                .collect::<Vec<_>>()
+                .join(", "),
+            if updates.is_empty() {
+                "NOTHING".to_string()
+            } else {
+                format!("UPDATE SET {}", updates.join(", "))
+            }
+        );
+        for row in &rows.rows {
+            if row.len() != rows.columns.len() {
+                return Err(format!("Malformed row in {} of increment", rows.table).into());
+            }
+            let params = row.iter().map(json_to_sql).collect::<Result<Vec<_>, _>>()?;
+            connection.execute(&upsert, rusqlite::params_from_iter(params))?;
+        }
+
+        Ok(())
+    }
+
+    /// Apply the changes of an incremental backup.
+    ///
+    /// Changed entries are inserted or updated by ID, entries missing from `password_ids` are
+    /// deleted. The other tables are brought to the state carried by the increment.
+    ///
+    /// # Arguments
+    ///
+    /// * `users` - The user rows at the time of the increment.
+    /// * `changed` - The password entries changed since the base backup.
+    /// * `password_ids` - The IDs of all password entries at the time of the increment.
+    /// * `tables` - The rows of the other tables at the time of the increment.
+    ///
+    /// # Returns
+    ///
+    /// A Result containing a unit or an error.
+    ///
+    /// # Errors
+    ///
+    /// If the changes cannot be applied.
+    pub fn apply_increment(
+        &self,
+        users: &[User],
+        changed: &[PasswordEntry],
+        password_ids: &[i32],
+        tables: &[TableRows],
+    ) -> Result<(), Box<dyn std::error::Error>> {
+        let tx = self.connection.unchecked_transaction()?;
+        tx.execute_batch("PRAGMA defer_foreign_keys = ON")?;
+
+        for user in users {
+            tx.execute(
+                "UPDATE user SET username = ?1, master_key = ?2, created_at = ?3, last_login = ?4
+                 WHERE id = ?5",
+                rusqlite::params![
+                    user.username,
+                    user.master_key,
+                    user.created_at,
+                    user.last_login,
+                    user.id
+                ],
+            )?;
+        }
+
+        for entry in changed {
+            tx.execute(
+                "INSERT INTO passwords
+                 (id, user_id, service, username, password, url, notes, created_at, updated_at,
+                  require_master, recovery_codes, totp_secret, folder_id, locked, deleted_at,
+                  expires_on)
+                 VALUES (?1, ?2, ?3, ?4, ?5, ?6, ?7, ?8, ?9, ?10, ?11, ?12, ?13, ?14, ?15, ?16)
+                 ON CONFLICT(id) DO UPDATE SET
+                  user_id = excluded.user_id, service = excluded.service,
+                  username = excluded.username, password = excluded.password,
+                  url = excluded.url, notes = excluded.notes, created_at = excluded.created_at,
+                  updated_at = excluded.updated_at, require_master = excluded.require_master,
+                  recovery_codes = excluded.recovery_codes, totp_secret = excluded.totp_secret,
+                  folder_id = excluded.folder_id, locked = excluded.locked,
+                  deleted_at = excluded.deleted_at, expires_on = excluded.expires_on",
+                rusqlite::params![
+                    entry.id,
+                    entry.user_id,
+                    entry.service,
+                    entry.username,
+                    entry.password,
+                    entry.url,
+                    entry.notes,
+                    entry.created_at,
+                    entry.updated_at,
+                    entry.require_master,
+                    entry.recovery_codes,
+                    entry.totp_secret,
+                    entry.folder_id,
+                    entry.locked,
+                    entry.deleted_at,
+                    entry.expires_on
+                ],
+            )?;
+        }
+
+        let existing: Vec<i32> = {
+            let mut stmt = tx.prepare("SELECT id FROM passwords")?;
+            let ids = stmt
+                .query_map([], |row| row.get(0))?
+                .collect::<Result<Vec<i32>, _>>()?;
+            ids
+        };
+        for id in existing.iter().filter(|id| !password_ids.contains(id)) {
+            tx.execute("DELETE FROM passwords WHERE id = ?1", [id])?;
+        }
+
+        for rows in tables {
+            Self::apply_table_rows(&tx, rows)?;
+        }
+
+        tx.commit()?;
+
+        info!("Applied increment with {} changed entries", changed.len());
+        Ok(())
+    }
+}
+
+/// Convert a column value into JSON so it can be carried by an incremental backup.
+fn sql_to_json(value: rusqlite::types::ValueRef) -> serde_json::Value {
+    use rusqlite::types::ValueRef;
+
+    match value {
+        ValueRef::Null => serde_json::Value::Null,
+        ValueRef::Integer(i) => i.into(),
+        ValueRef::Real(f) => f.into(),
+        ValueRef::Text(text) => String::from_utf8_lossy(text).into(),
+        ValueRef::Blob(blob) => blob.to_vec().into(),
+    }
+}
+
+/// Convert a JSON value of an incremental backup back into a column value.
+fn json_to_sql(
+    value: &serde_json::Value,
+) -> Result<rusqlite::types::Value, Box<dyn std::error::Error>> {
+    use rusqlite::types::Value;
+
+    Ok(match value {
+        serde_json::Value::Null => Value::Null,
+        serde_json::Value::Bool(b) => Value::Integer(*b as i64),
+        serde_json::Value::Number(n) => match n.as_i64() {
+            Some(i) => Value::Integer(i),
+            None => Value::Real(n.as_f64().ok_or("Invalid number in increment")?),
+        },
+        serde_json::Value::String(s) => Value::Text(s.clone()),
+        serde_json::Value::Array(bytes) => Value::Blob(
+            bytes
+                .iter()
+                .map(|b| b.as_u64().and_then(|b| u8::try_from(b).ok()))
+                .collect::<Option<Vec<u8>>>()
+                .ok_or("Invalid blob in increment")?,
+        ),
+        serde_json::Value::Object(_) => return Err("Invalid value in increment".into()),
+    })
+}
+
+/// Encrypt a username, URL or notes field in addition to the database.
+///
+/// # Arguments
+///
+/// * `encryption` - The encryption of the database.
+/// * `value` - The plaintext value, empty values stay empty.
+///
+/// # Returns
+///
+/// A Result containing the marked and base64 encoded ciphertext or an error.
+///
+/// # Errors
+///
+/// If the value cannot be encrypted.
+pub(super) fn encrypt_field(
+    encryption: &Encryption,
+    value: &str,
+) -> Result<String, Box<dyn std::error::Error>> {
+    if value.is_empty() {
+        return Ok(String::new());
+    }
+    let encrypted = encryption
+        .encrypt(value)
+        .map_err(|_| "Failed to encrypt field")?;
+
+    Ok(format!(
+        "{}{}",
+        ENCRYPTED_FIELD_PREFIX,
+        STANDARD.encode(encrypted)
+    ))
+}
+
+/// Decrypt a username, URL or notes field that is encrypted in addition to the database.
+///
+/// # Arguments
+///
+/// * `encryption` - The encryption of the database.
+/// * `value` - The stored value.
+///
+/// # Returns
+///
+/// The plaintext, or None if the value is not encrypted with this key, e.g. because it is
+/// stored as plaintext.
+pub(super) fn decrypt_field(encryption: &Encryption, value: &str) -> Option<String> {
+    let encoded = value.strip_prefix(ENCRYPTED_FIELD_PREFIX)?;
+    let decoded = STANDARD.decode(encoded).ok()?;
+    encryption.decrypt(&decoded).ok()
+}
+
+impl PasswordEntry {
+    /// Create a new PasswordEntry.
+    ///
+    /// # Arguments
+    ///
+    /// * `service` - The name of the service.
+    /// * `username` - The username for the service.
+    /// * `password` - The password for the service.
+    /// * `url` - The URL for the service.
+    /// * `notes` - Any notes for the service.
+    ///
+    /// # Returns
+    ///
+    /// A new PasswordEntry.
+    pub fn new(
+        user_id: i32,
+        service: String,
         username: String,
         password: String,
         url: String,
@@ -415,12 +3483,20 @@ impl PasswordEntry {
             notes,
             created_at: Utc::now().to_rfc3339(),
             updated_at: Utc::now().to_rfc3339(),
+            require_master: false,
+            recovery_codes: String::new(),
+            totp_secret: String::new(),
+            folder_id: None,
+            locked: false,
+            deleted_at: None,
+            expires_on: None,
         }
     }
 }
 
 #[cfg(test)]
 mod tests {
+    use crate::utils::Cipher;
     use crate::Auth;
 
     use super::*;
@@ -429,14 +3505,17 @@ mod tests {
     fn setup_test_db() -> (TempDir, Database) {
         let temp = TempDir::new().unwrap();
         let db_path = temp.path().join("test.db");
-        let salt = [0u8; 16];
-        let db = Database::new(db_path, "test_password", &salt).unwrap();
+        let db = Database::new(db_path, "test_password", &KeyParams::legacy([0u8; 16])).unwrap();
         (temp, db)
     }
 
+    fn setup_memory_db() -> Database {
+        Database::in_memory("test_password", &KeyParams::legacy([0u8; 16])).unwrap()
+    }
+
     #[test]
     fn test_database_creation() {
-        let (_temp, db) = setup_test_db();
+        let db = setup_memory_db();
 
         let tables = db
             .connection
@@ -449,11 +3528,12 @@ mod tests {
 
         assert!(tables.contains(&"user".to_string()));
         assert!(tables.contains(&"passwords".to_string()));
+        assert!(!db.cipher_version().unwrap().is_empty());
     }
 
     #[test]
     fn test_single_user_constraint() {
-        let (_temp, db) = setup_test_db();
+        let db = setup_memory_db();
 
         let user1 = User {
             id: None,
@@ -476,7 +3556,7 @@ mod tests {
 
     #[test]
     fn test_password_entry_crud() {
-        let (_temp, db) = setup_test_db();
+        let db = setup_memory_db();
 
         let user = User {
             id: None,
@@ -518,9 +3598,74 @@ mod tests {
         assert!(entries.is_empty());
     }
 
+    #[test]
+    fn test_field_encryption() {
+        let (_temp, mut db) = setup_test_db();
+        Auth::new(&db)
+            .register("testuser", "test_password")
+            .unwrap();
+        let raw_username = |db: &Database, id: i32| -> String {
+            db.connection
+                .query_row(
+                    "SELECT username FROM passwords WHERE id = ?1",
+                    [id],
+                    |row| row.get(0),
+                )
+                .unwrap()
+        };
+        let entry = |db: &Database, username: &str| {
+            PasswordEntry::new(
+                1,
+                "mail".to_string(),
+                username.to_string(),
+                STANDARD.encode(db.encryption.encrypt("secret").unwrap()),
+                "https://mail.example.com".to_string(),
+                String::new(),
+            )
+        };
+
+        db.create(&entry(&db, "enc:plain")).unwrap();
+        assert_eq!(db.pending_field_migration().unwrap(), 0);
+
+        db.set_field_encryption(true);
+        db.create(&entry(&db, "me")).unwrap();
+        assert!(raw_username(&db, 2).starts_with(ENCRYPTED_FIELD_PREFIX));
+        assert_eq!(db.pending_field_migration().unwrap(), 1);
+        assert_eq!(db.migrate_fields(10).unwrap(), 1);
+        assert_eq!(db.migrate_fields(10).unwrap(), 0);
+        assert_ne!(raw_username(&db, 1), "enc:plain");
+
+        let entries = db.read_all::<PasswordEntry>().unwrap();
+        assert_eq!(entries[0].username, "enc:plain");
+        assert_eq!(entries[1].username, "me");
+        assert_eq!(entries[1].url, "https://mail.example.com");
+        assert_eq!(entries[1].notes, "");
+        assert!(entries
+            .iter()
+            .filter(|entry| entry.username.contains("me"))
+            .any(|entry| entry.id == Some(2)));
+
+        let params = KeyParams {
+            salt: [9u8; 16],
+            version: 2,
+            iterations: 1_000,
+            cipher: Cipher::Aes256Gcm,
+        };
+        db.reencrypt("test_password", &params, |_, _| {}).unwrap();
+        assert_eq!(db.read_by_id::<PasswordEntry>(2).unwrap().username, "me");
+        assert_eq!(db.pending_field_migration().unwrap(), 0);
+
+        db.set_field_encryption(false);
+        assert_eq!(db.pending_field_migration().unwrap(), 2);
+        assert_eq!(db.migrate_fields(1).unwrap(), 1);
+        assert_eq!(db.migrate_fields(1).unwrap(), 1);
+        assert_eq!(raw_username(&db, 1), "enc:plain");
+        assert_eq!(raw_username(&db, 2), "me");
+    }
+
     #[test]
     fn test_foreign_key_constraint() {
-        let (_temp, db) = setup_test_db();
+        let db = setup_memory_db();
 
         let entry = PasswordEntry::new(
             999,
@@ -536,7 +3681,7 @@ mod tests {
 
     #[test]
     fn test_user_cascade_delete() {
-        let (_temp, db) = setup_test_db();
+        let db = setup_memory_db();
 
         let user = User {
             id: None,
@@ -563,6 +3708,47 @@ mod tests {
         assert!(entries.is_empty());
     }
 
+    #[test]
+    fn test_create_all() {
+        let db = setup_memory_db();
+
+        let user = User {
+            id: None,
+            username: "test".to_string(),
+            master_key: vec![1, 2, 3],
+            created_at: Utc::now().to_rfc3339(),
+            last_login: Utc::now().to_rfc3339(),
+        };
+        db.create(&user).unwrap();
+
+        let entry = |user_id: i32, service: &str| {
+            PasswordEntry::new(
+                user_id,
+                service.to_string(),
+                "username".to_string(),
+                "password".to_string(),
+                "url".to_string(),
+                "notes".to_string(),
+            )
+        };
+
+        let ids = db
+            .create_all(&[entry(1, "first"), entry(1, "second")])
+            .unwrap();
+        assert_eq!(ids.len(), 2);
+        assert_eq!(
+            db.read_by_id::<PasswordEntry>(ids[1]).unwrap().service,
+            "second"
+        );
+
+        assert!(db
+            .create_all(&[entry(1, "third"), entry(999, "orphan")])
+            .is_err());
+        assert_eq!(db.read_all::<PasswordEntry>().unwrap().len(), 2);
+
+        assert!(db.create_all::<PasswordEntry>(&[]).unwrap().is_empty());
+    }
+
     #[test]
     fn test_database_dump() {
         let (temp, db) = setup_test_db();
@@ -583,18 +3769,148 @@ mod tests {
             .is_err());
     }
 
+    #[test]
+    fn test_in_memory_database() {
+        let temp = TempDir::new().unwrap();
+        let db = setup_memory_db();
+        assert!(db.is_in_memory());
+        assert_eq!(db.disk_usage(), 0);
+        assert!(!setup_test_db().1.is_in_memory());
+
+        let user = User {
+            id: None,
+            username: "test".to_string(),
+            master_key: vec![1, 2, 3],
+            created_at: Utc::now().to_rfc3339(),
+            last_login: Utc::now().to_rfc3339(),
+        };
+        db.create(&user).unwrap();
+
+        let dump_path = temp.path().join("memory.dump");
+        db.create_dump(&dump_path, "test_password").unwrap();
+        assert!(dump_path.exists());
+        assert!(fs::read_dir(temp.path()).unwrap().count() == 1);
+    }
+
+    #[test]
+    fn test_disk_usage() {
+        let (temp, db) = setup_test_db();
+        let db_size = || fs::metadata(temp.path().join("test.db")).unwrap().len();
+        assert!(db.disk_usage() > db_size());
+
+        db.set_sync_safe_mode(true).unwrap();
+        assert_eq!(db.disk_usage(), db_size());
+    }
+
+    #[test]
+    fn test_checkpoint() {
+        let (temp, db) = setup_test_db();
+        let wal_path = temp.path().join("test.db-wal");
+        let auth = Auth::new(&db);
+        auth.register("test", "test_password").unwrap();
+        assert!(fs::metadata(&wal_path).unwrap().len() > 0);
+
+        db.checkpoint().unwrap();
+        assert_eq!(fs::metadata(&wal_path).unwrap().len(), 0);
+
+        db.set_sync_safe_mode(true).unwrap();
+        db.checkpoint().unwrap();
+    }
+
+    #[test]
+    fn test_sync_safe_mode() {
+        let (temp, db) = setup_test_db();
+        let wal_path = temp.path().join("test.db-wal");
+        assert!(wal_path.exists());
+
+        db.set_sync_safe_mode(true).unwrap();
+        let auth = Auth::new(&db);
+        auth.register("test", "test_password").unwrap();
+        assert!(!wal_path.exists());
+
+        let mode: String = db
+            .connection
+            .query_row("PRAGMA journal_mode;", [], |row| row.get(0))
+            .unwrap();
+        assert_eq!(mode, "delete");
+    }
+
+    #[test]
+    fn test_migrate_legacy_table() {
+        let temp = TempDir::new().unwrap();
+        let db_path = temp.path().join("legacy.db");
+
+        let db = Database::new(
+            db_path.clone(),
+            "test_password",
+            &KeyParams::legacy([0u8; 16]),
+        )
+        .unwrap();
+        db.connection
+            .execute_batch(
+                "ALTER TABLE passwords DROP COLUMN expires_on;
+                 ALTER TABLE passwords DROP COLUMN deleted_at;
+                 ALTER TABLE passwords DROP COLUMN locked;
+                 ALTER TABLE passwords DROP COLUMN folder_id;
+                 ALTER TABLE passwords DROP COLUMN totp_secret;
+                 ALTER TABLE passwords DROP COLUMN recovery_codes;
+                 ALTER TABLE passwords DROP COLUMN require_master;
+                 DROP TABLE health_history;",
+            )
+            .unwrap();
+        drop(db);
+
+        let db = Database::new(db_path, "test_password", &KeyParams::legacy([0u8; 16])).unwrap();
+        let user = User {
+            id: None,
+            username: "test".to_string(),
+            master_key: vec![1, 2, 3],
+            created_at: Utc::now().to_rfc3339(),
+            last_login: Utc::now().to_rfc3339(),
+        };
+        db.create(&user).unwrap();
+
+        let mut entry = PasswordEntry::new(
+            1,
+            "bank".to_string(),
+            "user".to_string(),
+            "password".to_string(),
+            "url".to_string(),
+            "".to_string(),
+        );
+        entry.require_master = true;
+        db.create(&entry).unwrap();
+
+        let entries = db.read_all::<PasswordEntry>().unwrap();
+        assert!(entries[0].require_master);
+        assert!(entries[0].recovery_codes.is_empty());
+        assert!(entries[0].totp_secret.is_empty());
+        assert_eq!(entries[0].folder_id, None);
+        assert!(!entries[0].locked);
+        assert_eq!(entries[0].deleted_at, None);
+        assert_eq!(entries[0].expires_on, None);
+        assert!(db.get_health_history(1, 10).unwrap().is_empty());
+    }
+
     #[test]
     fn test_database_restore() {
         let (temp, db) = setup_test_db();
 
         let auth = Auth::new(&db);
         auth.register("testuser", "testpass").unwrap();
+        let snapshot = HealthSnapshot {
+            day: "2024-05-01".to_string(),
+            entry_count: 3,
+            average_score: 72,
+        };
+        db.record_health_snapshot(1, &snapshot).unwrap();
 
         let dump_path = temp.path().join("test_dump.db");
         db.create_dump(&dump_path, "test_password").unwrap();
 
         let new_db_path = temp.path().join("new.db");
-        let new_db = Database::new(new_db_path, "test_password", &[0u8; 16]).unwrap();
+        let new_db =
+            Database::new(new_db_path, "test_password", &KeyParams::legacy([0u8; 16])).unwrap();
 
         new_db
             .restore_from_dump(&dump_path, "test_password")
@@ -603,5 +3919,145 @@ mod tests {
         let users = new_db.read_all::<User>().unwrap();
         assert_eq!(users.len(), 1);
         assert_eq!(users[0].username, "testuser");
+        assert_eq!(new_db.get_health_history(1, 10).unwrap(), vec![snapshot]);
+    }
+
+    #[test]
+    fn test_reencrypt() {
+        let (_temp, mut db) = setup_test_db();
+        Auth::new(&db)
+            .register("testuser", "test_password")
+            .unwrap();
+        let encode =
+            |db: &Database, value: &str| STANDARD.encode(db.encryption.encrypt(value).unwrap());
+        let entry = PasswordEntry::new(
+            1,
+            "mail".to_string(),
+            "me".to_string(),
+            encode(&db, "secret"),
+            "".to_string(),
+            "".to_string(),
+        );
+        db.create(&entry).unwrap();
+        db.update_recovery_codes(1, &encode(&db, "codes")).unwrap();
+        db.update_totp_secret(1, &encode(&db, "GEZDGNBV")).unwrap();
+        db.record_generated_password(1, &encode(&db, "generated"), 5)
+            .unwrap();
+
+        let params = KeyParams {
+            salt: [9u8; 16],
+            version: 2,
+            iterations: 1_000,
+            cipher: Cipher::Aes256Gcm,
+        };
+        let mut progress = Vec::new();
+        db.reencrypt("test_password", &params, |done, total| {
+            progress.push((done, total))
+        })
+        .unwrap();
+        assert_eq!(progress, vec![(1, 3), (2, 3), (3, 3)]);
+        assert_eq!(db.encryption.params(), &params);
+
+        let decrypt = |db: &Database, value: &str| {
+            db.encryption
+                .decrypt(&STANDARD.decode(value).unwrap())
+                .unwrap()
+        };
+        let entry = db.read_by_id::<PasswordEntry>(1).unwrap();
+        assert_eq!(decrypt(&db, &entry.password), "secret");
+        assert_eq!(decrypt(&db, &entry.recovery_codes), "codes");
+        assert_eq!(decrypt(&db, &entry.totp_secret), "GEZDGNBV");
+        assert_eq!(
+            decrypt(&db, &db.generator_history(1).unwrap()[0].password),
+            "generated"
+        );
+        assert_eq!(
+            Auth::new(&db).login("testuser", "test_password").unwrap(),
+            1
+        );
+
+        let path = db.path.clone();
+        drop(db);
+        assert!(
+            Database::new(path.clone(), "test_password", &KeyParams::legacy([0u8; 16])).is_err()
+        );
+        let db = Database::new(path, "test_password", &params).unwrap();
+        assert_eq!(db.read_all::<PasswordEntry>().unwrap().len(), 1);
+
+        let mut memory = setup_memory_db();
+        assert!(memory
+            .reencrypt("test_password", &params, |_, _| {})
+            .is_err());
+    }
+
+    #[test]
+    fn test_finish_rotation() {
+        let (_temp, mut db) = setup_test_db();
+        Auth::new(&db)
+            .register("testuser", "test_password")
+            .unwrap();
+        let old_key = db.encryption.get_key("test_password").unwrap();
+        let params = KeyParams {
+            salt: [9u8; 16],
+            version: 2,
+            iterations: 1_000,
+            cipher: Cipher::Aes256Gcm,
+        };
+        assert!(!db.finish_rotation("test_password", &params).unwrap());
+
+        // Simulate a stop between re-encrypting the fields and rekeying the database.
+        db.reencrypt("test_password", &params, |_, _| {}).unwrap();
+        db.connection
+            .execute_batch(&format!("PRAGMA rekey = '{}';", old_key))
+            .unwrap();
+        let path = db.path.clone();
+        drop(db);
+
+        let legacy = KeyParams::legacy([0u8; 16]);
+        let mut db = Database::new(path.clone(), "test_password", &legacy).unwrap();
+        assert!(Auth::new(&db).login("testuser", "test_password").is_err());
+        assert!(db.finish_rotation("test_password", &params).unwrap());
+        assert_eq!(
+            Auth::new(&db).login("testuser", "test_password").unwrap(),
+            1
+        );
+
+        drop(db);
+        assert!(Database::new(path.clone(), "test_password", &legacy).is_err());
+        assert!(Database::new(path, "test_password", &params).is_ok());
+    }
+
+    #[test]
+    fn test_health_history() {
+        let db = setup_memory_db();
+        Auth::new(&db).register("testuser", "testpass").unwrap();
+
+        let snapshot = |day: &str, entry_count, average_score| HealthSnapshot {
+            day: day.to_string(),
+            entry_count,
+            average_score,
+        };
+        db.record_health_snapshot(1, &snapshot("2024-05-02", 4, 60))
+            .unwrap();
+        db.record_health_snapshot(1, &snapshot("2024-05-01", 3, 50))
+            .unwrap();
+        db.record_health_snapshot(1, &snapshot("2024-05-02", 5, 65))
+            .unwrap();
+        db.record_health_snapshot(1, &snapshot("2024-05-03", 5, 70))
+            .unwrap();
+
+        assert_eq!(
+            db.get_health_history(1, 10).unwrap(),
+            vec![
+                snapshot("2024-05-01", 3, 50),
+                snapshot("2024-05-02", 5, 65),
+                snapshot("2024-05-03", 5, 70),
+            ]
+        );
+        assert_eq!(
+            db.get_health_history(1, 2).unwrap(),
+            vec![snapshot("2024-05-02", 5, 65), snapshot("2024-05-03", 5, 70)]
+        );
+        assert!(db.get_health_history(2, 10).unwrap().is_empty());
     }
 }
@@ -0,0 +1,170 @@
+use log::warn;
+use std::collections::VecDeque;
+use std::sync::{Arc, Mutex};
+
+use super::Clock;
+
+/// The time window the decryptions are counted in, in seconds.
+const WINDOW_SECS: u64 = 60;
+
+/// The start of the error returned when the limit is reached, followed by the wait time.
+pub const DECRYPT_LIMIT_ERROR: &str = "Decrypt limit reached";
+
+/// Limits how many secrets are decrypted per minute.
+///
+/// Malware driving the UI or the IPC interface could otherwise dump hundreds of secrets in
+/// seconds. A decryption confirmed with the master password is not limited and starts a
+/// new window.
+pub struct DecryptLimiter {
+    limit: u32,
+    clock: Arc<dyn Clock>,
+    recent: Mutex<VecDeque<u64>>,
+}
+
+impl DecryptLimiter {
+    /// Create a new limiter without a limit
+    ///
+    /// # Arguments
+    ///
+    /// * `clock` - The clock the decryptions are timed with
+    ///
+    /// # Returns
+    ///
+    /// A new limiter
+    pub fn new(clock: Arc<dyn Clock>) -> Self {
+        Self {
+            limit: 0,
+            clock,
+            recent: Mutex::new(VecDeque::new()),
+        }
+    }
+
+    /// Set the decryptions allowed per minute
+    ///
+    /// # Arguments
+    ///
+    /// * `limit` - The decryptions allowed per minute, 0 if unlimited
+    pub fn set_limit(&mut self, limit: u32) {
+        self.limit = limit;
+    }
+
+    /// Count a decryption
+    ///
+    /// # Errors
+    ///
+    /// Returns an error with the seconds until the next decryption is allowed if the limit
+    /// of the current minute is reached
+    pub fn acquire(&self) -> Result<(), Box<dyn std::error::Error>> {
+        self.acquire_many(1)
+    }
+
+    /// Count the decryptions of a list, either all of them or none
+    ///
+    /// # Arguments
+    ///
+    /// * `count` - The number of secrets that are decrypted
+    ///
+    /// # Errors
+    ///
+    /// Returns an error with the seconds until the decryptions are allowed if they exceed
+    /// the limit of the current minute, or without a wait time if they exceed the limit
+    /// on their own
+    pub fn acquire_many(&self, count: usize) -> Result<(), Box<dyn std::error::Error>> {
+        let limit = self.limit as usize;
+        if limit == 0 || count == 0 {
+            return Ok(());
+        }
+        if count > limit {
+            warn!(
+                "Decryption of {} secrets refused, the limit is {}",
+                count, limit
+            );
+            return Err(format!(
+                "{}, {} secrets exceed the limit of {} per minute",
+                DECRYPT_LIMIT_ERROR, count, limit
+            )
+            .into());
+        }
+
+        let now = self.clock.timestamp();
+        let mut recent = self.recent.lock().unwrap();
+        while recent
+            .front()
+            .is_some_and(|&time| time + WINDOW_SECS <= now)
+        {
+            recent.pop_front();
+        }
+
+        let excess = (recent.len() + count).saturating_sub(limit);
+        if excess > 0 {
+            let retry_after = recent[excess - 1] + WINDOW_SECS - now;
+            warn!(
+                "Decryption refused after {} decryptions in the last minute",
+                recent.len()
+            );
+            return Err(format!("{}, retry in {}s", DECRYPT_LIMIT_ERROR, retry_after).into());
+        }
+
+        recent.extend(std::iter::repeat_n(now, count));
+        Ok(())
+    }
+
+    /// Forget the counted decryptions, e.g. after the master password was confirmed
+    pub fn reset(&self) {
+        self.recent.lock().unwrap().clear();
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::utils::FixedClock;
+    use chrono::{Duration, Utc};
+
+    #[test]
+    fn test_acquire() {
+        let clock = Arc::new(FixedClock::new(Utc::now()));
+        let mut limiter = DecryptLimiter::new(clock.clone());
+        for _ in 0..10 {
+            assert!(limiter.acquire().is_ok());
+        }
+
+        limiter.set_limit(2);
+        limiter.reset();
+        assert!(limiter.acquire().is_ok());
+        clock.advance(Duration::seconds(20));
+        assert!(limiter.acquire().is_ok());
+
+        let error = limiter.acquire().unwrap_err().to_string();
+        assert_eq!(error, format!("{}, retry in 40s", DECRYPT_LIMIT_ERROR));
+
+        clock.advance(Duration::seconds(40));
+        assert!(limiter.acquire().is_ok());
+        assert!(limiter.acquire().is_err());
+
+        limiter.reset();
+        assert!(limiter.acquire().is_ok());
+    }
+
+    #[test]
+    fn test_acquire_many() {
+        let clock = Arc::new(FixedClock::new(Utc::now()));
+        let mut limiter = DecryptLimiter::new(clock.clone());
+        assert!(limiter.acquire_many(100).is_ok());
+
+        limiter.set_limit(5);
+        limiter.reset();
+        assert!(limiter.acquire_many(6).is_err());
+        assert!(limiter.acquire_many(2).is_ok());
+        clock.advance(Duration::seconds(30));
+        assert!(limiter.acquire_many(3).is_ok());
+
+        let error = limiter.acquire_many(2).unwrap_err().to_string();
+        assert_eq!(error, format!("{}, retry in 30s", DECRYPT_LIMIT_ERROR));
+        assert!(limiter.acquire_many(0).is_ok());
+
+        clock.advance(Duration::seconds(30));
+        assert!(limiter.acquire_many(2).is_ok());
+        assert!(limiter.acquire().is_err());
+    }
+}
@@ -0,0 +1,218 @@
+use base64::engine::general_purpose::STANDARD;
+use base64::Engine;
+use serde::{Deserialize, Serialize};
+use zeroize::Zeroizing;
+
+use super::{Cipher, Encryption, KeyParams, KEY_DERIVATION};
+
+/// The file extension of encrypted exports.
+pub const EXPORT_EXTENSION: &str = "kcexport";
+/// Identifies the file as an encrypted export.
+const EXPORT_FORMAT: &str = "karncrypt-export";
+/// The version of the export format, raised when the payload changes incompatibly.
+const EXPORT_VERSION: u32 = 1;
+/// The shortest passphrase an export can be protected with.
+pub const MIN_PASSPHRASE_LENGTH: usize = 12;
+/// The most PBKDF2 iterations a crafted export may ask for.
+const MAX_ITERATIONS: u32 = 10_000_000;
+
+/// A password entry in an encrypted export, with its secrets in plain text.
+#[derive(Serialize, Deserialize, Debug, Default, PartialEq)]
+pub struct ExportEntry {
+    pub service: String,
+    pub username: String,
+    pub password: String,
+    pub url: String,
+    pub notes: String,
+    #[serde(default)]
+    pub require_master: bool,
+    /// The base32 secret or `otpauth://` URI, empty if the entry has none.
+    #[serde(default)]
+    pub totp_secret: String,
+    /// The names of the folder and its parents, starting at the top level.
+    #[serde(default)]
+    pub folder: Vec<String>,
+    #[serde(default)]
+    pub tags: Vec<String>,
+}
+
+/// The content of an export, encrypted as a whole.
+#[derive(Serialize, Deserialize)]
+struct ExportPayload {
+    created_at: String,
+    app_version: String,
+    entries: Vec<ExportEntry>,
+}
+
+/// The unencrypted envelope of an export with the parameters to derive the key.
+#[derive(Serialize, Deserialize)]
+struct ExportFile {
+    format: String,
+    version: u32,
+    kdf: String,
+    iterations: u32,
+    salt: String,
+    cipher: Cipher,
+    data: String,
+}
+
+pub struct EncryptedExport;
+
+impl EncryptedExport {
+    /// Encrypt entries into the content of an export file
+    ///
+    /// The key is derived from the passphrase with a fresh salt, the entries are encrypted
+    /// with AES-256-GCM as one JSON document.
+    ///
+    /// # Arguments
+    ///
+    /// * `entries` - The entries to export
+    /// * `passphrase` - The passphrase chosen to protect the export
+    ///
+    /// # Returns
+    ///
+    /// The JSON content of the export file
+    ///
+    /// # Errors
+    ///
+    /// Returns an error if the passphrase is too short or the entries cannot be encrypted
+    pub fn seal(
+        entries: Vec<ExportEntry>,
+        passphrase: &str,
+    ) -> Result<String, Box<dyn std::error::Error>> {
+        if passphrase.chars().count() < MIN_PASSPHRASE_LENGTH {
+            return Err(format!(
+                "The passphrase must be at least {} characters long",
+                MIN_PASSPHRASE_LENGTH
+            )
+            .into());
+        }
+
+        let params =
+            KeyParams::generate(Cipher::Aes256Gcm).map_err(|_| "Failed to generate a salt")?;
+        let payload = Zeroizing::new(serde_json::to_string(&ExportPayload {
+            created_at: chrono::Utc::now().to_rfc3339(),
+            app_version: env!("CARGO_PKG_VERSION").to_string(),
+            entries,
+        })?);
+        let encrypted = Encryption::new(passphrase, &params)
+            .encrypt(&payload)
+            .map_err(|_| "Failed to encrypt the export")?;
+
+        Ok(serde_json::to_string_pretty(&ExportFile {
+            format: EXPORT_FORMAT.to_string(),
+            version: EXPORT_VERSION,
+            kdf: KEY_DERIVATION.to_string(),
+            iterations: params.iterations,
+            salt: STANDARD.encode(params.salt),
+            cipher: params.cipher,
+            data: STANDARD.encode(encrypted),
+        })?)
+    }
+
+    /// Decrypt the entries of an export file
+    ///
+    /// # Arguments
+    ///
+    /// * `content` - The JSON content of the export file
+    /// * `passphrase` - The passphrase the export is protected with
+    ///
+    /// # Returns
+    ///
+    /// The exported entries
+    ///
+    /// # Errors
+    ///
+    /// Returns an error if the file is no export, has a newer version or the passphrase is wrong
+    pub fn open(
+        content: &str,
+        passphrase: &str,
+    ) -> Result<Vec<ExportEntry>, Box<dyn std::error::Error>> {
+        let file: ExportFile =
+            serde_json::from_str(content).map_err(|_| "The file is no KarnCrypt export")?;
+        if file.format != EXPORT_FORMAT {
+            return Err("The file is no KarnCrypt export".into());
+        }
+        if file.version > EXPORT_VERSION {
+            return Err(format!(
+                "The export has version {}, update KarnCrypt to import it",
+                file.version
+            )
+            .into());
+        }
+        if file.kdf != KEY_DERIVATION || file.iterations == 0 || file.iterations > MAX_ITERATIONS {
+            return Err("The export uses unsupported key derivation parameters".into());
+        }
+
+        let salt: [u8; 16] = STANDARD
+            .decode(&file.salt)?
+            .try_into()
+            .map_err(|_| "The export has an invalid salt")?;
+        let params = KeyParams {
+            salt,
+            version: super::encryption::CURRENT_KEY_VERSION,
+            iterations: file.iterations,
+            cipher: file.cipher,
+        };
+        let payload = Zeroizing::new(
+            Encryption::new(passphrase, &params)
+                .decrypt(&STANDARD.decode(&file.data)?)
+                .map_err(|_| "Wrong passphrase or damaged export")?,
+        );
+        let payload: ExportPayload = serde_json::from_str(&payload)?;
+
+        Ok(payload.entries)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn entry() -> ExportEntry {
+        ExportEntry {
+            service: "Mail".to_string(),
+            username: "me@example.com".to_string(),
+            password: "geheim123".to_string(),
+            url: "https://mail.example".to_string(),
+            notes: "privat".to_string(),
+            require_master: true,
+            totp_secret: "GEZDGNBV".to_string(),
+            folder: vec!["Privat".to_string(), "Mail".to_string()],
+            tags: vec!["Wichtig".to_string()],
+        }
+    }
+
+    #[test]
+    fn test_seal_and_open() {
+        let sealed = EncryptedExport::seal(vec![entry()], "ein langes Passwort").unwrap();
+        assert!(!sealed.contains("geheim123"));
+        assert!(!sealed.contains("Mail"));
+
+        let entries = EncryptedExport::open(&sealed, "ein langes Passwort").unwrap();
+        assert_eq!(entries, vec![entry()]);
+
+        assert!(EncryptedExport::open(&sealed, "ein falsches Passwort").is_err());
+        assert!(EncryptedExport::seal(vec![entry()], "zu kurz").is_err());
+    }
+
+    #[test]
+    fn test_open_rejects_foreign_files() {
+        assert!(EncryptedExport::open("{}", "ein langes Passwort").is_err());
+        assert!(EncryptedExport::open("Service;User", "ein langes Passwort").is_err());
+
+        let sealed = EncryptedExport::seal(Vec::new(), "ein langes Passwort").unwrap();
+        let mut file: ExportFile = serde_json::from_str(&sealed).unwrap();
+        file.version = EXPORT_VERSION + 1;
+        let newer = serde_json::to_string(&file).unwrap();
+        assert!(EncryptedExport::open(&newer, "ein langes Passwort")
+            .unwrap_err()
+            .to_string()
+            .contains("update"));
+
+        file.version = EXPORT_VERSION;
+        file.iterations = MAX_ITERATIONS + 1;
+        let costly = serde_json::to_string(&file).unwrap();
+        assert!(EncryptedExport::open(&costly, "ein langes Passwort").is_err());
+    }
+}
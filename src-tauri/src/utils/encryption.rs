@@ -1,22 +1,220 @@
 use ring::{
     aead::{self, Nonce},
-    pbkdf2,
+    digest, hkdf, pbkdf2,
     rand::{SecureRandom, SystemRandom},
 };
+use serde::{Deserialize, Serialize};
 use std::num::NonZeroU32;
+use zeroize::Zeroize;
+
+/// Key version of vaults created before the parameters were stored next to the salt.
+const LEGACY_VERSION: u8 = 1;
+const LEGACY_ITERATIONS: u32 = 100_000;
+/// Key version and PBKDF2 iterations used for new and rotated vaults.
+pub const CURRENT_KEY_VERSION: u8 = 2;
+const CURRENT_ITERATIONS: u32 = 600_000;
+/// The function the vault and database keys are derived from the master password with.
+pub const KEY_DERIVATION: &str = "PBKDF2-HMAC-SHA256";
+/// Number of SHA-256 bytes shown in the salt fingerprint.
+const FINGERPRINT_LEN: usize = 8;
+
+/// Version of the ciphertext header written in front of every encrypted blob.
+///
+/// Blobs without the header are legacy ChaCha20-Poly1305 data of the form
+/// `nonce || ciphertext || tag`.
+const CIPHERTEXT_VERSION: u8 = 1;
+const HEADER_LEN: usize = 2;
+const NONCE_LEN: usize = 12;
+
+/// The AEAD algorithm of an encrypted blob, stored as the second header byte.
+///
+/// AES-256-GCM is the default for new vaults. ChaCha20-Poly1305 is faster on devices
+/// without AES hardware acceleration and is used by all legacy vaults.
+#[derive(Clone, Copy, Debug, Default, PartialEq, Serialize, Deserialize)]
+pub enum Cipher {
+    #[default]
+    #[serde(rename = "aes-256-gcm")]
+    Aes256Gcm,
+    #[serde(rename = "chacha20-poly1305")]
+    ChaCha20Poly1305,
+}
+
+impl Cipher {
+    fn id(self) -> u8 {
+        match self {
+            Self::ChaCha20Poly1305 => 1,
+            Self::Aes256Gcm => 2,
+        }
+    }
+
+    fn from_id(id: u8) -> Option<Self> {
+        match id {
+            1 => Some(Self::ChaCha20Poly1305),
+            2 => Some(Self::Aes256Gcm),
+            _ => None,
+        }
+    }
+}
+
+/// Output length for deriving the AES key from the vault key with HKDF.
+struct KeyLen;
+
+impl hkdf::KeyType for KeyLen {
+    fn len(&self) -> usize {
+        32
+    }
+}
+
+/// The salt and key derivation parameters of a vault, stored in the `.salt` file.
+///
+/// Legacy files only contain the 16 salt bytes, newer files append the version and the
+/// iteration count, so the parameters can be upgraded without breaking existing vaults.
+#[derive(Clone, Copy, Debug, PartialEq)]
+pub struct KeyParams {
+    pub salt: [u8; 16],
+    pub version: u8,
+    pub iterations: u32,
+    pub cipher: Cipher,
+}
+
+impl KeyParams {
+    /// Create the parameters of a vault created before key versions existed.
+    ///
+    /// # Arguments
+    ///
+    /// * `salt` - The salt of the vault.
+    ///
+    /// # Returns
+    ///
+    /// The legacy key parameters.
+    pub fn legacy(salt: [u8; 16]) -> Self {
+        Self {
+            salt,
+            version: LEGACY_VERSION,
+            iterations: LEGACY_ITERATIONS,
+            cipher: Cipher::ChaCha20Poly1305,
+        }
+    }
+
+    /// Generate parameters with a fresh salt and the current key version.
+    ///
+    /// # Arguments
+    ///
+    /// * `cipher` - The algorithm the vault is encrypted with.
+    ///
+    /// # Returns
+    ///
+    /// A Result containing the new key parameters or an error.
+    ///
+    /// # Errors
+    ///
+    /// If no random salt can be generated.
+    pub fn generate(cipher: Cipher) -> Result<Self, ring::error::Unspecified> {
+        let mut salt = [0u8; 16];
+        SystemRandom::new().fill(&mut salt)?;
+
+        Ok(Self {
+            salt,
+            version: CURRENT_KEY_VERSION,
+            iterations: CURRENT_ITERATIONS,
+            cipher,
+        })
+    }
+
+    /// Read the parameters from the content of a `.salt` file.
+    ///
+    /// # Arguments
+    ///
+    /// * `data` - The content of the file.
+    ///
+    /// # Returns
+    ///
+    /// A Result containing the key parameters or an error.
+    ///
+    /// # Errors
+    ///
+    /// If the file is too short or the version is unknown.
+    pub fn from_bytes(data: &[u8]) -> Result<Self, Box<dyn std::error::Error>> {
+        let salt: [u8; 16] = data.get(..16).ok_or("Salt file is too short")?.try_into()?;
+
+        let Some(extra) = data.get(16..21) else {
+            return Ok(Self::legacy(salt));
+        };
+
+        let version = extra[0];
+        if !(LEGACY_VERSION..=CURRENT_KEY_VERSION).contains(&version) {
+            return Err(format!("Unsupported key version {}", version).into());
+        }
+
+        let cipher = match data.get(21) {
+            Some(id) => Cipher::from_id(*id).ok_or(format!("Unsupported cipher {}", id))?,
+            None => Cipher::ChaCha20Poly1305,
+        };
+
+        Ok(Self {
+            salt,
+            version,
+            iterations: u32::from_le_bytes(extra[1..].try_into()?),
+            cipher,
+        })
+    }
+
+    /// Serialize the parameters for the `.salt` file.
+    ///
+    /// # Returns
+    ///
+    /// The salt, followed by the version, iterations and cipher for non-legacy parameters.
+    pub fn to_bytes(self) -> Vec<u8> {
+        let mut data = self.salt.to_vec();
+        if self.version > LEGACY_VERSION {
+            data.push(self.version);
+            data.extend_from_slice(&self.iterations.to_le_bytes());
+            data.push(self.cipher.id());
+        }
+        data
+    }
+
+    /// Check if the parameters are older than the current key version.
+    ///
+    /// # Returns
+    ///
+    /// A boolean indicating if the keys should be rotated.
+    pub fn is_outdated(&self) -> bool {
+        self.version < CURRENT_KEY_VERSION || self.iterations < CURRENT_ITERATIONS
+    }
+
+    /// Get a short fingerprint of the salt.
+    ///
+    /// The fingerprint identifies the salt without revealing it, so users can check whether
+    /// two copies of a vault share the same key parameters.
+    ///
+    /// # Returns
+    ///
+    /// The first bytes of the SHA-256 hash of the salt as colon separated hex pairs.
+    pub fn salt_fingerprint(&self) -> String {
+        digest::digest(&digest::SHA256, &self.salt).as_ref()[..FINGERPRINT_LEN]
+            .chunks(2)
+            .map(|pair| format!("{:02x}{:02x}", pair[0], pair[1]))
+            .collect::<Vec<_>>()
+            .join(":")
+    }
+}
 
 #[derive(Clone)]
 pub struct Encryption {
     key: aead::LessSafeKey,
+    aes_key: aead::LessSafeKey,
+    cipher: Cipher,
+    params: KeyParams,
 }
 
 impl Encryption {
-    pub fn new(master_password: &str, salt: &[u8; 16]) -> Self {
+    pub fn new(master_password: &str, params: &KeyParams) -> Self {
         let mut key = [0u8; 32];
         pbkdf2::derive(
             pbkdf2::PBKDF2_HMAC_SHA256,
-            NonZeroU32::new(100_000).unwrap(),
-            salt,
+            Self::iterations(params),
+            &params.salt,
             master_password.as_bytes(),
             &mut key,
         );
@@ -24,54 +222,128 @@ impl Encryption {
         let unbound_key =
             aead::UnboundKey::new(&aead::CHACHA20_POLY1305, &key).expect("Failed to create key");
 
+        let mut aes_key = [0u8; 32];
+        hkdf::Prk::new_less_safe(hkdf::HKDF_SHA256, &key)
+            .expand(&[b"karncrypt aes-256-gcm"], KeyLen)
+            .and_then(|okm| okm.fill(&mut aes_key))
+            .expect("Failed to derive key");
+        let aes_unbound_key =
+            aead::UnboundKey::new(&aead::AES_256_GCM, &aes_key).expect("Failed to create key");
+        key.zeroize();
+        aes_key.zeroize();
+
         Self {
             key: aead::LessSafeKey::new(unbound_key),
+            aes_key: aead::LessSafeKey::new(aes_unbound_key),
+            cipher: params.cipher,
+            params: *params,
         }
     }
 
+    pub fn params(&self) -> &KeyParams {
+        &self.params
+    }
+
+    fn iterations(params: &KeyParams) -> NonZeroU32 {
+        NonZeroU32::new(params.iterations).unwrap_or(NonZeroU32::new(LEGACY_ITERATIONS).unwrap())
+    }
+
+    fn key_for(&self, cipher: Cipher) -> &aead::LessSafeKey {
+        match cipher {
+            Cipher::ChaCha20Poly1305 => &self.key,
+            Cipher::Aes256Gcm => &self.aes_key,
+        }
+    }
+
+    /// Encrypt data with the configured algorithm in the current ciphertext format.
+    ///
+    /// The result is `version || algorithm || nonce || ciphertext || tag`. The header is
+    /// authenticated as additional data, so it cannot be swapped without failing decryption.
+    /// Because every write uses this format, legacy blobs are migrated as they are rewritten.
     pub fn encrypt(&self, data: &str) -> Result<Vec<u8>, ring::error::Unspecified> {
         let rng = SystemRandom::new();
-        let mut nonce_bytes = [0u8; 12];
+        let mut nonce_bytes = [0u8; NONCE_LEN];
         rng.fill(&mut nonce_bytes)?;
         let nonce = Nonce::assume_unique_for_key(nonce_bytes);
 
+        let header = [CIPHERTEXT_VERSION, self.cipher.id()];
         let mut in_out = data.as_bytes().to_vec();
-        self.key
-            .seal_in_place_append_tag(nonce, aead::Aad::empty(), &mut in_out)?;
-
-        let mut result = Vec::with_capacity(nonce_bytes.len() + in_out.len());
+        self.key_for(self.cipher).seal_in_place_append_tag(
+            nonce,
+            aead::Aad::from(header),
+            &mut in_out,
+        )?;
+
+        let mut result = Vec::with_capacity(HEADER_LEN + NONCE_LEN + in_out.len());
+        result.extend_from_slice(&header);
         result.extend_from_slice(&nonce_bytes);
         result.extend_from_slice(&in_out);
 
         Ok(result)
     }
 
+    /// Decrypt data in the current or the legacy ciphertext format.
+    ///
+    /// Legacy blobs start with a random nonce, so a blob that only looks like it has a
+    /// header is retried in the legacy format when authentication fails.
     pub fn decrypt(&self, encryted_data: &[u8]) -> Result<String, ring::error::Unspecified> {
-        if encryted_data.len() < 12 {
+        let plain_text = match Self::cipher_of(encryted_data) {
+            Some(cipher) => self
+                .open(
+                    cipher,
+                    &encryted_data[..HEADER_LEN],
+                    &encryted_data[HEADER_LEN..],
+                )
+                .or_else(|_| self.open(Cipher::ChaCha20Poly1305, &[], encryted_data))?,
+            None => self.open(Cipher::ChaCha20Poly1305, &[], encryted_data)?,
+        };
+
+        String::from_utf8(plain_text).map_err(|_| ring::error::Unspecified)
+    }
+
+    /// Read the algorithm from the header if the data looks like the current format.
+    fn cipher_of(encryted_data: &[u8]) -> Option<Cipher> {
+        match encryted_data {
+            [CIPHERTEXT_VERSION, id, rest @ ..] if rest.len() >= NONCE_LEN => Cipher::from_id(*id),
+            _ => None,
+        }
+    }
+
+    fn open(
+        &self,
+        cipher: Cipher,
+        header: &[u8],
+        data: &[u8],
+    ) -> Result<Vec<u8>, ring::error::Unspecified> {
+        if data.len() < NONCE_LEN {
             return Err(ring::error::Unspecified);
         }
 
         let nonce = Nonce::assume_unique_for_key(
-            encryted_data[..12]
+            data[..NONCE_LEN]
                 .try_into()
                 .map_err(|_| ring::error::Unspecified)?,
         );
-        let mut in_out = encryted_data[12..].to_vec();
+        let mut in_out = data[NONCE_LEN..].to_vec();
 
-        let plain_text = self
-            .key
-            .open_in_place(nonce, aead::Aad::empty(), &mut in_out)?;
+        let plain_text =
+            self.key_for(cipher)
+                .open_in_place(nonce, aead::Aad::from(header), &mut in_out)?;
 
-        String::from_utf8(plain_text.to_vec()).map_err(|_| ring::error::Unspecified)
+        Ok(plain_text.to_vec())
     }
 
     pub fn get_key(&self, master_password: &str) -> Result<String, Box<dyn std::error::Error>> {
         let mut key_bytes = [0u8; 32];
 
+        let mut db_salt = b"db_encryption".to_vec();
+        if self.params.version > LEGACY_VERSION {
+            db_salt.extend_from_slice(&self.params.salt);
+        }
         pbkdf2::derive(
             pbkdf2::PBKDF2_HMAC_SHA256,
-            NonZeroU32::new(100_000).unwrap(),
-            b"db_encryption",
+            Self::iterations(&self.params),
+            &db_salt,
             master_password.as_bytes(),
             &mut key_bytes,
         );
@@ -83,6 +355,7 @@ impl Encryption {
                 write!(&mut acc, "{:02x}", b).expect("Failed to write to string");
                 acc
             });
+        key_bytes.zeroize();
 
         Ok(hex_string)
     }
@@ -92,11 +365,11 @@ impl Encryption {
 mod tests {
     use super::*;
 
-    fn create_test_salt() -> [u8; 16] {
+    fn create_test_salt() -> KeyParams {
         let mut salt = [0u8; 16];
         let rng = SystemRandom::new();
         rng.fill(&mut salt).unwrap();
-        salt
+        KeyParams::legacy(salt)
     }
 
     #[test]
@@ -196,4 +469,127 @@ mod tests {
         assert_eq!(encryption.decrypt(&enc1).unwrap(), data);
         assert_eq!(encryption.decrypt(&enc2).unwrap(), data);
     }
+
+    #[test]
+    fn test_key_params_roundtrip() {
+        let legacy = KeyParams::legacy([7u8; 16]);
+        assert_eq!(legacy.to_bytes(), vec![7u8; 16]);
+        assert_eq!(KeyParams::from_bytes(&[7u8; 16]).unwrap(), legacy);
+        assert!(legacy.is_outdated());
+
+        let current = KeyParams::generate(Cipher::Aes256Gcm).unwrap();
+        assert_eq!(current.to_bytes().len(), 22);
+        assert_eq!(KeyParams::from_bytes(&current.to_bytes()).unwrap(), current);
+        assert!(!current.is_outdated());
+
+        let chacha = KeyParams::generate(Cipher::ChaCha20Poly1305).unwrap();
+        assert_eq!(KeyParams::from_bytes(&chacha.to_bytes()).unwrap(), chacha);
+        assert_eq!(
+            KeyParams::from_bytes(&chacha.to_bytes()[..21])
+                .unwrap()
+                .cipher,
+            Cipher::ChaCha20Poly1305
+        );
+
+        let mut unknown_cipher = current.to_bytes();
+        unknown_cipher[21] = 0;
+        assert!(KeyParams::from_bytes(&unknown_cipher).is_err());
+
+        let mut unknown = current.to_bytes();
+        unknown[16] = CURRENT_KEY_VERSION + 1;
+        assert!(KeyParams::from_bytes(&unknown).is_err());
+        assert!(KeyParams::from_bytes(&[0u8; 8]).is_err());
+    }
+
+    #[test]
+    fn test_salt_fingerprint() {
+        let params = KeyParams::legacy([7u8; 16]);
+        let fingerprint = params.salt_fingerprint();
+        assert_eq!(fingerprint.len(), 19);
+        assert_eq!(fingerprint.matches(':').count(), 3);
+        assert_eq!(fingerprint, KeyParams::legacy([7u8; 16]).salt_fingerprint());
+        assert_ne!(fingerprint, KeyParams::legacy([8u8; 16]).salt_fingerprint());
+    }
+
+    #[test]
+    fn test_key_versions_differ() {
+        let legacy = KeyParams::legacy([1u8; 16]);
+        let current = KeyParams {
+            version: CURRENT_KEY_VERSION,
+            iterations: LEGACY_ITERATIONS,
+            ..legacy
+        };
+
+        let old = Encryption::new("password", &legacy);
+        let new = Encryption::new("password", &current);
+        assert_ne!(
+            old.get_key("password").unwrap(),
+            new.get_key("password").unwrap()
+        );
+        assert_eq!(new.params(), &current);
+    }
+
+    /// Encrypt like releases before the ciphertext header did.
+    fn encrypt_legacy(
+        encryption: &Encryption,
+        data: &str,
+        nonce_bytes: [u8; NONCE_LEN],
+    ) -> Vec<u8> {
+        let mut in_out = data.as_bytes().to_vec();
+        encryption
+            .key
+            .seal_in_place_append_tag(
+                Nonce::assume_unique_for_key(nonce_bytes),
+                aead::Aad::empty(),
+                &mut in_out,
+            )
+            .unwrap();
+
+        [nonce_bytes.to_vec(), in_out].concat()
+    }
+
+    #[test]
+    fn test_ciphertext_header() {
+        let encryption = Encryption::new("password", &create_test_salt());
+
+        let chacha = encryption.encrypt("data").unwrap();
+        assert_eq!(chacha[..HEADER_LEN], [CIPHERTEXT_VERSION, 1]);
+
+        let aes_encryption = Encryption::new(
+            "password",
+            &KeyParams {
+                cipher: Cipher::Aes256Gcm,
+                ..*encryption.params()
+            },
+        );
+        let aes = aes_encryption.encrypt("data").unwrap();
+        assert_eq!(aes[..HEADER_LEN], [CIPHERTEXT_VERSION, 2]);
+
+        assert_eq!(encryption.decrypt(&aes).unwrap(), "data");
+        assert_eq!(aes_encryption.decrypt(&chacha).unwrap(), "data");
+
+        let mut swapped = aes.clone();
+        swapped[1] = Cipher::ChaCha20Poly1305.id();
+        assert!(encryption.decrypt(&swapped).is_err());
+    }
+
+    #[test]
+    fn test_decrypt_legacy_format() {
+        let encryption = Encryption::new("password", &create_test_salt());
+
+        let legacy = encrypt_legacy(&encryption, "legacy", [9u8; NONCE_LEN]);
+        assert_eq!(encryption.decrypt(&legacy).unwrap(), "legacy");
+
+        let mut nonce = [9u8; NONCE_LEN];
+        nonce[0] = CIPHERTEXT_VERSION;
+        nonce[1] = Cipher::Aes256Gcm.id();
+        let ambiguous = encrypt_legacy(&encryption, "legacy", nonce);
+        assert_eq!(encryption.decrypt(&ambiguous).unwrap(), "legacy");
+
+        let migrated = encryption
+            .encrypt(&encryption.decrypt(&legacy).unwrap())
+            .unwrap();
+        assert_eq!(migrated[0], CIPHERTEXT_VERSION);
+        assert_eq!(encryption.decrypt(&migrated).unwrap(), "legacy");
+    }
 }
@@ -0,0 +1,58 @@
+const FNV_OFFSET: u64 = 0xcbf2_9ce4_8422_2325;
+const FNV_PRIME: u64 = 0x0000_0100_0000_01b3;
+
+/// Compute the 64 bit FNV-1a fingerprint of a password
+///
+/// # Arguments
+///
+/// * `password` - The password to fingerprint
+///
+/// # Returns
+///
+/// The fingerprint, stable across builds and platforms
+pub fn fingerprint(password: &str) -> u64 {
+    password.bytes().fold(FNV_OFFSET, |hash, byte| {
+        (hash ^ u64::from(byte)).wrapping_mul(FNV_PRIME)
+    })
+}
+
+/// Fingerprint a list with one password per line
+///
+/// Empty lines and lines starting with `#` are skipped.
+///
+/// # Arguments
+///
+/// * `content` - The list to fingerprint
+///
+/// # Returns
+///
+/// The sorted fingerprints without duplicates
+pub fn fingerprints(content: &str) -> Vec<u64> {
+    let mut fingerprints = content
+        .lines()
+        .map(|line| line.trim_end_matches('\r'))
+        .filter(|line| !line.is_empty() && !line.starts_with('#'))
+        .map(fingerprint)
+        .collect::<Vec<_>>();
+    fingerprints.sort_unstable();
+    fingerprints.dedup();
+    fingerprints
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_fingerprint() {
+        assert_eq!(fingerprint(""), 0xcbf2_9ce4_8422_2325);
+        assert_eq!(fingerprint("a"), 0xaf63_dc4c_8601_ec8c);
+        assert_ne!(fingerprint("password"), fingerprint("Password"));
+
+        assert_eq!(fingerprints("# comment\r\nqwerty\r\n\n123456\nqwerty\n"), {
+            let mut expected = vec![fingerprint("qwerty"), fingerprint("123456")];
+            expected.sort_unstable();
+            expected
+        });
+    }
+}
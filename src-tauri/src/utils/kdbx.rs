@@ -0,0 +1,718 @@
+use aes::cipher::{block_padding::Pkcs7, BlockDecryptMut, BlockEncrypt, KeyInit, KeyIvInit};
+use aes::Aes256;
+use argon2::{Algorithm, Argon2, Params, Version};
+use base64::engine::general_purpose::STANDARD;
+use base64::Engine;
+use chacha20::cipher::StreamCipher;
+use chacha20::ChaCha20;
+use flate2::read::GzDecoder;
+use ring::{digest, hmac};
+use std::collections::HashMap;
+use std::io::Read;
+use zeroize::Zeroizing;
+
+const SIGNATURE_1: u32 = 0x9AA2_D903;
+const SIGNATURE_2: u32 = 0xB54B_FB67;
+const CIPHER_AES256: [u8; 16] = [
+    0x31, 0xc1, 0xf2, 0xe6, 0xbf, 0x71, 0x43, 0x50, 0xbe, 0x58, 0x05, 0x21, 0x6a, 0xfc, 0x5a, 0xff,
+];
+const CIPHER_CHACHA20: [u8; 16] = [
+    0xd6, 0x03, 0x8a, 0x2b, 0x8b, 0x6f, 0x4c, 0xb5, 0xa5, 0x24, 0x33, 0x9a, 0x31, 0xdb, 0xb5, 0x9a,
+];
+const KDF_AES: [u8; 16] = [
+    0x7c, 0x02, 0xbb, 0x82, 0x79, 0xa7, 0x4a, 0xc0, 0x92, 0x7d, 0x11, 0x4a, 0x00, 0x64, 0x82, 0x38,
+];
+const KDF_AES_LEGACY: [u8; 16] = [
+    0xc9, 0xd9, 0xf3, 0x9a, 0x62, 0x8a, 0x44, 0x60, 0xbf, 0x74, 0x0d, 0x08, 0xc1, 0x8a, 0x4f, 0xea,
+];
+const KDF_ARGON2D: [u8; 16] = [
+    0xef, 0x63, 0x6d, 0xdf, 0x8c, 0x29, 0x44, 0x4b, 0x91, 0xf7, 0xa9, 0xa4, 0x03, 0xe3, 0x0a, 0x0c,
+];
+const KDF_ARGON2ID: [u8; 16] = [
+    0x9e, 0x29, 0x8b, 0x19, 0x56, 0xdb, 0x47, 0x73, 0xb2, 0x3d, 0xfc, 0x3e, 0xc6, 0xf0, 0xa1, 0xe6,
+];
+/// The inner random stream KDBX 4 uses to protect passwords inside the XML.
+const INNER_STREAM_CHACHA20: u32 = 3;
+/// The most memory a crafted file may make Argon2 allocate, in bytes.
+const MAX_ARGON2_MEMORY: u64 = 1 << 30;
+
+/// A group of a KeePass database with its subgroups and entries.
+#[derive(Debug, Default, PartialEq)]
+pub struct KdbxGroup {
+    pub name: String,
+    pub groups: Vec<KdbxGroup>,
+    pub entries: Vec<KdbxEntry>,
+}
+
+/// An entry of a KeePass database.
+#[derive(Debug, Default, PartialEq)]
+pub struct KdbxEntry {
+    pub title: String,
+    pub username: String,
+    pub password: Zeroizing<String>,
+    pub url: String,
+    pub notes: String,
+}
+
+/// A decrypted KeePass database in the KDBX 4 format.
+///
+/// Only the data needed to import entries is read: the group tree and the title, username,
+/// password, URL and notes of each entry. The recycle bin and the entry history are skipped.
+#[derive(Debug)]
+pub struct KdbxDatabase {
+    pub root: KdbxGroup,
+}
+
+/// A value of a KDBX variant dictionary, e.g. a KDF parameter.
+enum Variant {
+    UInt(u64),
+    Bytes(Vec<u8>),
+}
+
+/// A cursor over the bytes of a KDBX file.
+struct ByteReader<'a> {
+    data: &'a [u8],
+    pos: usize,
+}
+
+impl<'a> ByteReader<'a> {
+    fn new(data: &'a [u8]) -> Self {
+        Self { data, pos: 0 }
+    }
+
+    fn take(&mut self, len: usize) -> Result<&'a [u8], Box<dyn std::error::Error>> {
+        let end = self
+            .pos
+            .checked_add(len)
+            .filter(|end| *end <= self.data.len())
+            .ok_or("The KeePass database is truncated")?;
+        let bytes = &self.data[self.pos..end];
+        self.pos = end;
+        Ok(bytes)
+    }
+
+    fn u8(&mut self) -> Result<u8, Box<dyn std::error::Error>> {
+        Ok(self.take(1)?[0])
+    }
+
+    fn u16(&mut self) -> Result<u16, Box<dyn std::error::Error>> {
+        Ok(u16::from_le_bytes(self.take(2)?.try_into()?))
+    }
+
+    fn u32(&mut self) -> Result<u32, Box<dyn std::error::Error>> {
+        Ok(u32::from_le_bytes(self.take(4)?.try_into()?))
+    }
+
+    /// Read a type-length-value field of a header
+    fn field(&mut self) -> Result<(u8, &'a [u8]), Box<dyn std::error::Error>> {
+        let id = self.u8()?;
+        let len = self.u32()? as usize;
+        Ok((id, self.take(len)?))
+    }
+}
+
+impl KdbxDatabase {
+    /// Decrypt a KeePass database
+    ///
+    /// Databases protected by a password are supported, key files are not. The outer cipher
+    /// may be AES-256 or ChaCha20, the key derivation AES-KDF, Argon2d or Argon2id.
+    ///
+    /// # Arguments
+    ///
+    /// * `data` - The content of the `.kdbx` file
+    /// * `password` - The master password of the KeePass database
+    ///
+    /// # Returns
+    ///
+    /// The decrypted database
+    ///
+    /// # Errors
+    ///
+    /// Returns an error if the file is no KDBX 4 database, uses an unsupported cipher, the
+    /// password is wrong or the file is corrupted
+    pub fn open(data: &[u8], password: &str) -> Result<Self, Box<dyn std::error::Error>> {
+        let mut reader = ByteReader::new(data);
+        if reader.u32()? != SIGNATURE_1 || reader.u32()? != SIGNATURE_2 {
+            return Err("The file is no KeePass database".into());
+        }
+        let minor = reader.u16()?;
+        let major = reader.u16()?;
+        if major != 4 {
+            return Err(format!(
+                "KDBX {}.{} is not supported, save the database in the KDBX 4 format first",
+                major, minor
+            )
+            .into());
+        }
+
+        let mut cipher = None;
+        let mut compressed = false;
+        let mut master_seed = None;
+        let mut iv = None;
+        let mut kdf = None;
+        loop {
+            let (id, value) = reader.field()?;
+            match id {
+                0 => break,
+                2 => cipher = Some(value),
+                3 => compressed = u32::from_le_bytes(value.try_into()?) == 1,
+                4 => master_seed = Some(value),
+                7 => iv = Some(value),
+                11 => kdf = Some(Self::parse_variants(value)?),
+                _ => {}
+            }
+        }
+        let header = &data[..reader.pos];
+        let cipher = cipher.ok_or("The database header has no cipher")?;
+        let master_seed = master_seed.ok_or("The database header has no master seed")?;
+        let iv = iv.ok_or("The database header has no IV")?;
+        let kdf = kdf.ok_or("The database header has no key derivation")?;
+
+        if digest::digest(&digest::SHA256, header).as_ref() != reader.take(32)? {
+            return Err("The database header is corrupted".into());
+        }
+
+        let composite = digest::digest(
+            &digest::SHA256,
+            digest::digest(&digest::SHA256, password.as_bytes()).as_ref(),
+        );
+        let transformed = Self::transform_key(composite.as_ref(), &kdf)?;
+
+        let master_key = Zeroizing::new(
+            digest::digest(&digest::SHA256, &[master_seed, &transformed[..]].concat())
+                .as_ref()
+                .to_vec(),
+        );
+        let hmac_key = Zeroizing::new(
+            digest::digest(
+                &digest::SHA512,
+                &[master_seed, &transformed[..], &[1]].concat(),
+            )
+            .as_ref()
+            .to_vec(),
+        );
+
+        hmac::verify(
+            &Self::block_key(&hmac_key, u64::MAX),
+            header,
+            reader.take(32)?,
+        )
+        .map_err(|_| "Wrong password or corrupted database")?;
+
+        let mut payload = Zeroizing::new(Vec::new());
+        for index in 0u64.. {
+            let mac = reader.take(32)?;
+            let size = reader.u32()?;
+            let block = reader.take(size as usize)?;
+            let message = [&index.to_le_bytes()[..], &size.to_le_bytes(), block].concat();
+            hmac::verify(&Self::block_key(&hmac_key, index), &message, mac)
+                .map_err(|_| "The database is corrupted")?;
+            if size == 0 {
+                break;
+            }
+            payload.extend_from_slice(block);
+        }
+
+        let mut decrypted = match cipher.try_into()? {
+            CIPHER_AES256 => {
+                let plain = cbc::Decryptor::<Aes256>::new_from_slices(&master_key, iv)
+                    .map_err(|_| "Invalid AES key or IV")?
+                    .decrypt_padded_mut::<Pkcs7>(&mut payload)
+                    .map_err(|_| "Wrong password or corrupted database")?
+                    .len();
+                payload.truncate(plain);
+                payload
+            }
+            CIPHER_CHACHA20 => {
+                ChaCha20::new_from_slices(&master_key, iv)
+                    .map_err(|_| "Invalid ChaCha20 key or nonce")?
+                    .apply_keystream(&mut payload);
+                payload
+            }
+            _ => return Err("The cipher of the database is not supported".into()),
+        };
+
+        if compressed {
+            let mut inflated = Zeroizing::new(Vec::new());
+            GzDecoder::new(&decrypted[..]).read_to_end(&mut inflated)?;
+            decrypted = inflated;
+        }
+
+        let mut inner = ByteReader::new(&decrypted);
+        let mut stream_id = None;
+        let mut stream_key = None;
+        loop {
+            let (id, value) = inner.field()?;
+            match id {
+                0 => break,
+                1 => stream_id = Some(u32::from_le_bytes(value.try_into()?)),
+                2 => stream_key = Some(value),
+                _ => {}
+            }
+        }
+        if stream_id != Some(INNER_STREAM_CHACHA20) {
+            return Err("The protection of the passwords in the database is not supported".into());
+        }
+        let stream_key = digest::digest(
+            &digest::SHA512,
+            stream_key.ok_or("The database has no stream key")?,
+        );
+        let mut stream =
+            ChaCha20::new_from_slices(&stream_key.as_ref()[..32], &stream_key.as_ref()[32..44])
+                .map_err(|_| "Invalid stream key")?;
+
+        let xml = std::str::from_utf8(&decrypted[inner.pos..])?;
+        let document = roxmltree::Document::parse(xml)?;
+
+        let mut protected = HashMap::new();
+        for node in document.descendants().filter(|n| {
+            n.has_tag_name("Value") && n.attribute("Protected").is_some_and(|p| p == "True")
+        }) {
+            let mut value = STANDARD.decode(node.text().unwrap_or("").trim())?;
+            stream.apply_keystream(&mut value);
+            protected.insert(node.id(), Zeroizing::new(String::from_utf8(value)?));
+        }
+
+        let root = document.root_element();
+        let meta = child(root, "Meta");
+        let recycle_bin = meta
+            .filter(|meta| child_text(*meta, "RecycleBinEnabled") != "False")
+            .map(|meta| child_text(meta, "RecycleBinUUID"))
+            .filter(|uuid| !uuid.is_empty());
+        let group = child(root, "Root")
+            .and_then(|r| child(r, "Group"))
+            .ok_or("The database contains no groups")?;
+
+        Ok(Self {
+            root: Self::parse_group(group, recycle_bin, &protected),
+        })
+    }
+
+    /// Derive the transformed key from the composite key with the KDF of the header
+    fn transform_key(
+        composite: &[u8],
+        kdf: &HashMap<String, Variant>,
+    ) -> Result<Zeroizing<Vec<u8>>, Box<dyn std::error::Error>> {
+        let bytes = |key: &str| match kdf.get(key) {
+            Some(Variant::Bytes(bytes)) => Ok(bytes.as_slice()),
+            _ => Err(format!("The key derivation has no parameter {}", key)),
+        };
+        let uint = |key: &str| match kdf.get(key) {
+            Some(Variant::UInt(value)) => Ok(*value),
+            _ => Err(format!("The key derivation has no parameter {}", key)),
+        };
+
+        let uuid: [u8; 16] = bytes("$UUID")?.try_into()?;
+        let mut transformed = Zeroizing::new(vec![0u8; 32]);
+        match uuid {
+            KDF_AES | KDF_AES_LEGACY => {
+                let cipher = Aes256::new_from_slice(bytes("S")?).map_err(|_| "Invalid AES seed")?;
+                transformed.copy_from_slice(composite);
+                for _ in 0..uint("R")? {
+                    for block in transformed.chunks_exact_mut(16) {
+                        cipher.encrypt_block(block.into());
+                    }
+                }
+                let hashed = digest::digest(&digest::SHA256, &transformed);
+                transformed.copy_from_slice(hashed.as_ref());
+            }
+            KDF_ARGON2D | KDF_ARGON2ID => {
+                let memory = uint("M")?;
+                if memory > MAX_ARGON2_MEMORY {
+                    return Err("The key derivation needs too much memory".into());
+                }
+                let params = Params::new(
+                    (memory / 1024) as u32,
+                    uint("I")?.try_into()?,
+                    uint("P")?.try_into()?,
+                    Some(32),
+                )
+                .map_err(|e| format!("Invalid Argon2 parameters: {}", e))?;
+                let version = match uint("V")? {
+                    0x10 => Version::V0x10,
+                    _ => Version::V0x13,
+                };
+                let algorithm = if uuid == KDF_ARGON2D {
+                    Algorithm::Argon2d
+                } else {
+                    Algorithm::Argon2id
+                };
+                Argon2::new(algorithm, version, params)
+                    .hash_password_into(composite, bytes("S")?, &mut transformed)
+                    .map_err(|e| format!("Failed to derive the key: {}", e))?;
+            }
+            _ => return Err("The key derivation of the database is not supported".into()),
+        }
+
+        Ok(transformed)
+    }
+
+    /// Derive the HMAC key of a block, the header uses the index `u64::MAX`
+    fn block_key(hmac_key: &[u8], index: u64) -> hmac::Key {
+        let key = digest::digest(
+            &digest::SHA512,
+            &[&index.to_le_bytes()[..], hmac_key].concat(),
+        );
+        hmac::Key::new(hmac::HMAC_SHA256, key.as_ref())
+    }
+
+    /// Parse a variant dictionary, the integer types are widened to u64
+    fn parse_variants(data: &[u8]) -> Result<HashMap<String, Variant>, Box<dyn std::error::Error>> {
+        let mut reader = ByteReader::new(data);
+        if reader.u16()? >> 8 != 1 {
+            return Err("Unsupported variant dictionary version".into());
+        }
+
+        let mut variants = HashMap::new();
+        loop {
+            let kind = reader.u8()?;
+            if kind == 0 {
+                break;
+            }
+            let key_len = reader.u32()? as usize;
+            let key = String::from_utf8(reader.take(key_len)?.to_vec())?;
+            let value_len = reader.u32()? as usize;
+            let value = reader.take(value_len)?;
+            let variant = match kind {
+                0x04 => Variant::UInt(u32::from_le_bytes(value.try_into()?).into()),
+                0x05 => Variant::UInt(u64::from_le_bytes(value.try_into()?)),
+                0x42 => Variant::Bytes(value.to_vec()),
+                _ => continue,
+            };
+            variants.insert(key, variant);
+        }
+
+        Ok(variants)
+    }
+
+    /// Parse a group with its subgroups and entries, leaving out the recycle bin
+    fn parse_group(
+        node: roxmltree::Node,
+        recycle_bin: Option<&str>,
+        protected: &HashMap<roxmltree::NodeId, Zeroizing<String>>,
+    ) -> KdbxGroup {
+        let mut group = KdbxGroup {
+            name: child_text(node, "Name").to_string(),
+            ..Default::default()
+        };
+
+        for child in node.children() {
+            if child.has_tag_name("Group") {
+                if recycle_bin.is_some_and(|uuid| child_text(child, "UUID") == uuid) {
+                    continue;
+                }
+                group
+                    .groups
+                    .push(Self::parse_group(child, recycle_bin, protected));
+            } else if child.has_tag_name("Entry") {
+                group.entries.push(Self::parse_entry(child, protected));
+            }
+        }
+
+        group
+    }
+
+    /// Parse the current version of an entry, the history is not imported
+    fn parse_entry(
+        node: roxmltree::Node,
+        protected: &HashMap<roxmltree::NodeId, Zeroizing<String>>,
+    ) -> KdbxEntry {
+        let mut entry = KdbxEntry::default();
+        for field in node.children().filter(|c| c.has_tag_name("String")) {
+            let Some(value) = child(field, "Value") else {
+                continue;
+            };
+            let value = match protected.get(&value.id()) {
+                Some(decrypted) => decrypted.to_string(),
+                None => value.text().unwrap_or("").to_string(),
+            };
+
+            match child_text(field, "Key") {
+                "Title" => entry.title = value,
+                "UserName" => entry.username = value,
+                "Password" => entry.password = Zeroizing::new(value),
+                "URL" => entry.url = value,
+                "Notes" => entry.notes = value,
+                _ => {}
+            }
+        }
+
+        entry
+    }
+}
+
+/// Find the first child element with a tag name
+fn child<'a, 'input>(
+    node: roxmltree::Node<'a, 'input>,
+    name: &str,
+) -> Option<roxmltree::Node<'a, 'input>> {
+    node.children().find(|c| c.has_tag_name(name))
+}
+
+/// Get the text of the first child element with a tag name, empty if there is none
+fn child_text<'a>(node: roxmltree::Node<'a, '_>, name: &str) -> &'a str {
+    child(node, name).and_then(|c| c.text()).unwrap_or("")
+}
+
+#[cfg(test)]
+pub(crate) mod tests {
+    use super::*;
+    use aes::cipher::BlockEncryptMut;
+    use flate2::write::GzEncoder;
+    use flate2::Compression;
+    use std::io::Write;
+
+    const STREAM_KEY: [u8; 64] = [7; 64];
+
+    fn field(out: &mut Vec<u8>, id: u8, value: &[u8]) {
+        out.push(id);
+        out.extend_from_slice(&(value.len() as u32).to_le_bytes());
+        out.extend_from_slice(value);
+    }
+
+    fn variant(out: &mut Vec<u8>, kind: u8, key: &str, value: &[u8]) {
+        out.push(kind);
+        out.extend_from_slice(&(key.len() as u32).to_le_bytes());
+        out.extend_from_slice(key.as_bytes());
+        out.extend_from_slice(&(value.len() as u32).to_le_bytes());
+        out.extend_from_slice(value);
+    }
+
+    fn protect(stream: &mut ChaCha20, value: &str) -> String {
+        let mut bytes = value.as_bytes().to_vec();
+        stream.apply_keystream(&mut bytes);
+        STANDARD.encode(bytes)
+    }
+
+    fn xml() -> String {
+        let key = digest::digest(&digest::SHA512, &STREAM_KEY);
+        let mut stream =
+            ChaCha20::new_from_slices(&key.as_ref()[..32], &key.as_ref()[32..44]).unwrap();
+        let first = protect(&mut stream, "geheim");
+        let history = protect(&mut stream, "alt");
+        let second = protect(&mut stream, "pa$$wort");
+        let trashed = protect(&mut stream, "weg");
+
+        format!(
+            r#"<?xml version="1.0" encoding="utf-8" standalone="yes"?>
+<KeePassFile>
+    <Meta>
+        <RecycleBinEnabled>True</RecycleBinEnabled>
+        <RecycleBinUUID>YmluYmluYmluYmluYmluYg==</RecycleBinUUID>
+    </Meta>
+    <Root>
+        <Group>
+            <UUID>cm9vdHJvb3Ryb290cm9vdA==</UUID>
+            <Name>Datenbank</Name>
+            <Entry>
+                <String><Key>Title</Key><Value>Mail</Value></String>
+                <String><Key>UserName</Key><Value>karl@example.com</Value></String>
+                <String><Key>Password</Key><Value Protected="True">{first}</Value></String>
+                <String><Key>URL</Key><Value>https://mail.example.com</Value></String>
+                <String><Key>Notes</Key><Value>Zeile 1&#10;Zeile 2</Value></String>
+                <History>
+                    <Entry>
+                        <String><Key>Title</Key><Value>Mail</Value></String>
+                        <String><Key>Password</Key><Value Protected="True">{history}</Value></String>
+                    </Entry>
+                </History>
+            </Entry>
+            <Group>
+                <UUID>d29ya3dvcmt3b3Jrd29yaw==</UUID>
+                <Name>Arbeit</Name>
+                <Group>
+                    <UUID>c2VydmVyc2VydmVyc2VydmVy</UUID>
+                    <Name>Server</Name>
+                    <Entry>
+                        <String><Key>Title</Key><Value>SSH</Value></String>
+                        <String><Key>UserName</Key><Value>root</Value></String>
+                        <String><Key>Password</Key><Value Protected="True">{second}</Value></String>
+                    </Entry>
+                </Group>
+            </Group>
+            <Group>
+                <UUID>YmluYmluYmluYmluYmluYg==</UUID>
+                <Name>Papierkorb</Name>
+                <Entry>
+                    <String><Key>Title</Key><Value>Alt</Value></String>
+                    <String><Key>Password</Key><Value Protected="True">{trashed}</Value></String>
+                </Entry>
+            </Group>
+        </Group>
+    </Root>
+</KeePassFile>"#
+        )
+    }
+
+    /// Write a KDBX 4 file with the given cipher and KDF parameters
+    fn write_kdbx(password: &str, cipher: [u8; 16], kdf: &[u8], compressed: bool) -> Vec<u8> {
+        let master_seed = [1u8; 32];
+        let iv: Vec<u8> = if cipher == CIPHER_AES256 {
+            vec![2; 16]
+        } else {
+            vec![2; 12]
+        };
+
+        let mut header = Vec::new();
+        header.extend_from_slice(&SIGNATURE_1.to_le_bytes());
+        header.extend_from_slice(&SIGNATURE_2.to_le_bytes());
+        header.extend_from_slice(&1u16.to_le_bytes());
+        header.extend_from_slice(&4u16.to_le_bytes());
+        field(&mut header, 2, &cipher);
+        field(&mut header, 3, &u32::from(compressed).to_le_bytes());
+        field(&mut header, 4, &master_seed);
+        field(&mut header, 7, &iv);
+        field(&mut header, 11, kdf);
+        field(&mut header, 0, b"\r\n\r\n");
+
+        let mut inner = Vec::new();
+        field(&mut inner, 1, &INNER_STREAM_CHACHA20.to_le_bytes());
+        field(&mut inner, 2, &STREAM_KEY);
+        field(&mut inner, 0, &[]);
+        inner.extend_from_slice(xml().as_bytes());
+        if compressed {
+            let mut encoder = GzEncoder::new(Vec::new(), Compression::default());
+            encoder.write_all(&inner).unwrap();
+            inner = encoder.finish().unwrap();
+        }
+
+        let composite = digest::digest(
+            &digest::SHA256,
+            digest::digest(&digest::SHA256, password.as_bytes()).as_ref(),
+        );
+        let variants = KdbxDatabase::parse_variants(kdf).unwrap();
+        let transformed = KdbxDatabase::transform_key(composite.as_ref(), &variants).unwrap();
+        let master_key =
+            digest::digest(&digest::SHA256, &[&master_seed[..], &transformed].concat());
+        let hmac_key = digest::digest(
+            &digest::SHA512,
+            &[&master_seed[..], &transformed, &[1]].concat(),
+        );
+
+        let payload = if cipher == CIPHER_AES256 {
+            let len = inner.len();
+            let mut buffer = inner;
+            buffer.resize(len + 16, 0);
+            let encrypted = cbc::Encryptor::<Aes256>::new_from_slices(master_key.as_ref(), &iv)
+                .unwrap()
+                .encrypt_padded_mut::<Pkcs7>(&mut buffer, len)
+                .unwrap()
+                .len();
+            buffer.truncate(encrypted);
+            buffer
+        } else {
+            let mut payload = inner;
+            ChaCha20::new_from_slices(master_key.as_ref(), &iv)
+                .unwrap()
+                .apply_keystream(&mut payload);
+            payload
+        };
+
+        let mut out = header.clone();
+        out.extend_from_slice(digest::digest(&digest::SHA256, &header).as_ref());
+        out.extend_from_slice(
+            hmac::sign(
+                &KdbxDatabase::block_key(hmac_key.as_ref(), u64::MAX),
+                &header,
+            )
+            .as_ref(),
+        );
+        for (index, block) in [&payload[..], &[]].into_iter().enumerate() {
+            let size = (block.len() as u32).to_le_bytes();
+            let message = [&(index as u64).to_le_bytes()[..], &size, block].concat();
+            let mac = hmac::sign(
+                &KdbxDatabase::block_key(hmac_key.as_ref(), index as u64),
+                &message,
+            );
+            out.extend_from_slice(mac.as_ref());
+            out.extend_from_slice(&size);
+            out.extend_from_slice(block);
+        }
+        out
+    }
+
+    /// Write the sample KeePass database of the tests, protected by AES-KDF with few rounds
+    pub(crate) fn sample_kdbx(password: &str) -> Vec<u8> {
+        write_kdbx(password, CIPHER_AES256, &aes_kdf(), true)
+    }
+
+    fn aes_kdf() -> Vec<u8> {
+        let mut kdf = 0x0100u16.to_le_bytes().to_vec();
+        variant(&mut kdf, 0x42, "$UUID", &KDF_AES);
+        variant(&mut kdf, 0x05, "R", &10u64.to_le_bytes());
+        variant(&mut kdf, 0x42, "S", &[3; 32]);
+        kdf.push(0);
+        kdf
+    }
+
+    fn argon2_kdf(memory: u64) -> Vec<u8> {
+        let mut kdf = 0x0100u16.to_le_bytes().to_vec();
+        variant(&mut kdf, 0x42, "$UUID", &KDF_ARGON2D);
+        variant(&mut kdf, 0x42, "S", &[4; 32]);
+        variant(&mut kdf, 0x04, "P", &1u32.to_le_bytes());
+        variant(&mut kdf, 0x05, "M", &memory.to_le_bytes());
+        variant(&mut kdf, 0x05, "I", &2u64.to_le_bytes());
+        variant(&mut kdf, 0x04, "V", &0x13u32.to_le_bytes());
+        kdf.push(0);
+        kdf
+    }
+
+    fn assert_content(database: &KdbxDatabase) {
+        let root = &database.root;
+        assert_eq!(root.name, "Datenbank");
+        assert_eq!(root.entries.len(), 1);
+        let mail = &root.entries[0];
+        assert_eq!(mail.title, "Mail");
+        assert_eq!(mail.username, "karl@example.com");
+        assert_eq!(mail.password.as_str(), "geheim");
+        assert_eq!(mail.url, "https://mail.example.com");
+        assert_eq!(mail.notes, "Zeile 1\nZeile 2");
+
+        assert_eq!(root.groups.len(), 1);
+        let work = &root.groups[0];
+        assert_eq!(work.name, "Arbeit");
+        assert!(work.entries.is_empty());
+        let server = &work.groups[0];
+        assert_eq!(server.name, "Server");
+        assert_eq!(server.entries[0].title, "SSH");
+        assert_eq!(server.entries[0].password.as_str(), "pa$$wort");
+    }
+
+    #[test]
+    fn test_open_aes_kdbx() {
+        let data = write_kdbx("master", CIPHER_AES256, &aes_kdf(), true);
+        assert_content(&KdbxDatabase::open(&data, "master").unwrap());
+
+        let error = KdbxDatabase::open(&data, "falsch").unwrap_err();
+        assert_eq!(error.to_string(), "Wrong password or corrupted database");
+    }
+
+    #[test]
+    fn test_open_chacha20_argon2_kdbx() {
+        let data = write_kdbx("master", CIPHER_CHACHA20, &argon2_kdf(64 * 1024), false);
+        assert_content(&KdbxDatabase::open(&data, "master").unwrap());
+        assert!(KdbxDatabase::open(&data, "falsch").is_err());
+    }
+
+    #[test]
+    fn test_open_invalid_kdbx() {
+        let mut data = write_kdbx("master", CIPHER_AES256, &aes_kdf(), false);
+        let last = data.len() - 40;
+        data[last] ^= 1;
+        assert!(KdbxDatabase::open(&data, "master").is_err());
+
+        let mut kdbx3 = SIGNATURE_1.to_le_bytes().to_vec();
+        kdbx3.extend_from_slice(&SIGNATURE_2.to_le_bytes());
+        kdbx3.extend_from_slice(&1u16.to_le_bytes());
+        kdbx3.extend_from_slice(&3u16.to_le_bytes());
+        let error = KdbxDatabase::open(&kdbx3, "master").unwrap_err();
+        assert!(error.to_string().contains("KDBX 3.1"));
+
+        assert!(KdbxDatabase::open(b"keine Datenbank", "master").is_err());
+        assert!(KdbxDatabase::transform_key(
+            &[0; 32],
+            &KdbxDatabase::parse_variants(&argon2_kdf(MAX_ARGON2_MEMORY + 1024)).unwrap()
+        )
+        .is_err());
+    }
+}
@@ -0,0 +1,206 @@
+use log::{info, warn};
+use serde::{Deserialize, Serialize};
+use std::fs;
+use std::path::{Path, PathBuf};
+use std::time::{SystemTime, UNIX_EPOCH};
+
+const MAX_FAILED_ATTEMPTS: u32 = 5;
+const BASE_LOCKOUT_SECS: u64 = 30;
+const MAX_LOCKOUT_SECS: u64 = 15 * 60;
+
+#[derive(Serialize, Deserialize, Default)]
+struct LoginAttempts {
+    failed_attempts: u32,
+    locked_until: u64,
+}
+
+pub struct LoginGuard {
+    state_path: PathBuf,
+}
+
+impl LoginGuard {
+    /// Create a new login guard
+    ///
+    /// The failed attempts are stored in the config directory so a restart does not reset
+    /// the lockout.
+    ///
+    /// # Arguments
+    ///
+    /// * `config_dir` - The directory to store the failed attempts
+    ///
+    /// # Returns
+    ///
+    /// A new login guard
+    pub fn new(config_dir: &Path) -> Self {
+        Self {
+            state_path: config_dir.join(".login_attempts"),
+        }
+    }
+
+    /// Get the remaining lockout time
+    ///
+    /// # Returns
+    ///
+    /// The remaining lockout time in seconds, 0 if logins are allowed
+    pub fn remaining_lockout(&self) -> u64 {
+        self.load().locked_until.saturating_sub(Self::now())
+    }
+
+    /// Record a failed login attempt
+    ///
+    /// After `MAX_FAILED_ATTEMPTS` failures every further failure locks the login, starting
+    /// with `BASE_LOCKOUT_SECS` and doubling up to `MAX_LOCKOUT_SECS`.
+    ///
+    /// # Returns
+    ///
+    /// A Result containing the lockout time in seconds if the login is now locked or an error
+    ///
+    /// # Errors
+    ///
+    /// If the failed attempts cannot be saved
+    pub fn record_failure(&self) -> Result<Option<u64>, Box<dyn std::error::Error>> {
+        let mut attempts = self.load();
+        attempts.failed_attempts += 1;
+
+        let lockout = if attempts.failed_attempts >= MAX_FAILED_ATTEMPTS {
+            let exponent = (attempts.failed_attempts - MAX_FAILED_ATTEMPTS).min(16);
+            let lockout = (BASE_LOCKOUT_SECS << exponent).min(MAX_LOCKOUT_SECS);
+            attempts.locked_until = Self::now() + lockout;
+            warn!(
+                "Login locked for {}s after {} failed attempts",
+                lockout, attempts.failed_attempts
+            );
+            Some(lockout)
+        } else {
+            None
+        };
+
+        fs::write(&self.state_path, serde_json::to_string(&attempts)?)?;
+        Ok(lockout)
+    }
+
+    /// Reset the failed attempts after a successful login
+    ///
+    /// # Returns
+    ///
+    /// A Result containing a unit or an error
+    ///
+    /// # Errors
+    ///
+    /// If the failed attempts cannot be removed
+    pub fn reset(&self) -> Result<(), Box<dyn std::error::Error>> {
+        if self.state_path.exists() {
+            fs::remove_file(&self.state_path)?;
+            info!("Reset failed login attempts");
+        }
+        Ok(())
+    }
+
+    /// Run a login attempt, counting any error as a failed login
+    ///
+    /// Opening the vault with a wrong master password fails before the credentials are
+    /// checked, so the whole attempt has to run inside the guard. Check the remaining
+    /// lockout before asking for the master password.
+    ///
+    /// # Arguments
+    ///
+    /// * `attempt` - Opens the vault and checks the credentials
+    ///
+    /// # Returns
+    ///
+    /// A Result containing the value of the attempt or an error
+    ///
+    /// # Errors
+    ///
+    /// If the attempt fails or the failed attempts cannot be saved
+    pub fn attempt<T>(
+        &self,
+        attempt: impl FnOnce() -> Result<T, Box<dyn std::error::Error>>,
+    ) -> Result<T, Box<dyn std::error::Error>> {
+        match attempt() {
+            Ok(value) => {
+                self.reset()?;
+                Ok(value)
+            }
+            Err(e) => match self.record_failure()? {
+                Some(lockout) => Err(format!("Login locked for {}s", lockout).into()),
+                None => Err(e),
+            },
+        }
+    }
+
+    /// Load the failed attempts, treating a missing or broken file as no attempts
+    fn load(&self) -> LoginAttempts {
+        fs::read_to_string(&self.state_path)
+            .ok()
+            .and_then(|content| serde_json::from_str(&content).ok())
+            .unwrap_or_default()
+    }
+
+    fn now() -> u64 {
+        SystemTime::now()
+            .duration_since(UNIX_EPOCH)
+            .unwrap()
+            .as_secs()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::utils::{Database, KeyParams};
+    use tempfile::TempDir;
+
+    #[test]
+    fn test_lockout_after_max_attempts() {
+        let temp = TempDir::new().unwrap();
+        let guard = LoginGuard::new(temp.path());
+
+        for _ in 1..MAX_FAILED_ATTEMPTS {
+            assert_eq!(guard.record_failure().unwrap(), None);
+            assert_eq!(guard.remaining_lockout(), 0);
+        }
+
+        assert_eq!(guard.record_failure().unwrap(), Some(BASE_LOCKOUT_SECS));
+        assert!(guard.remaining_lockout() > 0);
+
+        assert_eq!(guard.record_failure().unwrap(), Some(BASE_LOCKOUT_SECS * 2));
+
+        guard.reset().unwrap();
+        assert_eq!(guard.remaining_lockout(), 0);
+        assert_eq!(guard.record_failure().unwrap(), None);
+    }
+
+    #[test]
+    fn test_attempt_counts_wrong_master_password() {
+        let temp = TempDir::new().unwrap();
+        let guard = LoginGuard::new(temp.path());
+        let db_path = temp.path().join("vault.db");
+        let params = KeyParams::legacy([0u8; 16]);
+        drop(Database::new(db_path.clone(), "master", &params).unwrap());
+
+        let result = guard.attempt(|| Database::new(db_path.clone(), "wrong", &params));
+        assert!(result.is_err());
+        assert_eq!(guard.load().failed_attempts, 1);
+        assert!(temp.path().join(".login_attempts").exists());
+
+        guard
+            .attempt(|| Database::new(db_path.clone(), "master", &params))
+            .unwrap();
+        assert_eq!(guard.load().failed_attempts, 0);
+    }
+
+    #[test]
+    fn test_lockout_is_capped() {
+        let temp = TempDir::new().unwrap();
+        let guard = LoginGuard::new(temp.path());
+
+        let mut lockout = None;
+        for _ in 0..MAX_FAILED_ATTEMPTS + 40 {
+            lockout = guard.record_failure().unwrap();
+        }
+
+        assert_eq!(lockout, Some(MAX_LOCKOUT_SECS));
+        assert!(guard.remaining_lockout() <= MAX_LOCKOUT_SECS);
+    }
+}
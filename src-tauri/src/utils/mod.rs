@@ -1,18 +1,107 @@
+mod account_provider;
+mod askpass;
+mod attachment_scanner;
+mod audit;
 mod auth;
 mod backup;
 mod backup_compressor;
+mod backup_naming;
+mod bitwarden;
+mod breach_check;
+mod browser_import;
+mod categorizer;
+mod clipboard;
+mod clock;
+mod collation;
 mod config;
+mod confirmation_pin;
+mod csv_import;
 mod database;
+mod decrypt_limiter;
+mod encrypted_export;
 mod encryption;
+mod fingerprint;
+mod kdbx;
+mod login_guard;
 mod models;
+mod native_messaging;
+mod otp_import;
 mod password_health;
+mod paths;
+mod recipients;
+mod recovery_codes;
+mod rpc;
+mod search;
+mod secure_delete;
 mod session;
+mod startup_check;
+mod storage;
+mod system_idle;
+#[cfg(test)]
+mod test_rng;
+mod totp;
+mod url_metadata;
+mod vault_storage;
 
+pub use account_provider::{account_provider, base_domain};
+pub use askpass::PasswordSource;
+pub use attachment_scanner::AttachmentScanner;
+pub use audit::{AuditEvent, AuditExportFormat, AuditLog};
 pub use auth::Auth;
-pub use backup::BackupManager;
+pub use backup::{
+    BackupInfo, BackupManager, BackupPreview, BackupSnapshot, ExportFormat, ExportScope,
+    ImportFormat, ImportResult, SnapshotEntry,
+};
 pub use backup_compressor::{BackupCompressor, BackupFile};
-pub use config::{BackupInterval, Config};
-pub use database::{Database, PasswordEntry, User};
-pub use encryption::Encryption;
-pub use password_health::PasswordHealth;
-pub use session::TokenManager;
+pub use backup_naming::{BackupNaming, DEFAULT_BACKUP_NAME};
+pub use bitwarden::{BitwardenExport, BITWARDEN_LOGIN};
+pub use breach_check::BreachHash;
+pub use browser_import::{BrowserKind, BrowserProfile};
+pub use categorizer::{Categorizer, CategorySuggestion, CategoryTarget};
+pub use clipboard::{ClipboardProtection, SecureClipboard};
+#[cfg(test)]
+pub use clock::FixedClock;
+pub use clock::{Clock, SystemClock};
+pub use collation::{Collation, DEFAULT_LOCALE};
+pub use config::{
+    AutostartMode, BackupInterval, BackupRecipient, BrowserConfig, Config, DefaultTab, EntryConfig,
+    GeneratorConfig, GeneratorMode, GeneratorOptions, RowAction, SortOrder, ViewConfig,
+    VAULT_LOCATION_UNAVAILABLE,
+};
+pub use confirmation_pin::ConfirmationPin;
+pub use csv_import::{CsvLayout, CsvPreview, CsvProfile};
+pub use database::{
+    Attachment, Database, EntrySummary, ExpiringNote, Folder, GeneratedPassword, HealthSnapshot,
+    Identity, PasswordEntry, PaymentCard, SecureNote, Server, TableRows, Tag, User,
+};
+pub use decrypt_limiter::DecryptLimiter;
+#[cfg(test)]
+pub use decrypt_limiter::DECRYPT_LIMIT_ERROR;
+pub use encrypted_export::{EncryptedExport, ExportEntry, EXPORT_EXTENSION};
+pub use encryption::{Cipher, Encryption, KeyParams, KEY_DERIVATION};
+pub use kdbx::{KdbxDatabase, KdbxGroup};
+pub use login_guard::LoginGuard;
+pub use native_messaging::{
+    extension_origin, install_host, read_message, write_message, BROWSER_METHOD_PREFIX,
+    FIREFOX_EXTENSION_ID,
+};
+pub use otp_import::{OtpExport, OtpFormat};
+pub use password_health::{
+    dictionary_words, CommonPasswords, PasswordHealth, PasswordIssue, EXPIRY_WARNING_DAYS,
+};
+pub use paths::{detect_cloud_provider, CloudProvider};
+pub use recipients::{RecipientEncryption, RecipientKeyPair};
+pub use recovery_codes::{RecoveryCode, RecoveryCodeCount, RecoveryCodes};
+pub use rpc::{
+    RpcClient, RpcError, RpcHandler, RpcServer, INVALID_PARAMS, METHOD_NOT_FOUND, SERVER_ERROR,
+    UNAUTHORIZED,
+};
+pub use search::{fuzzy_score, SearchMode, SearchQuery, SearchResult};
+pub use secure_delete::{DeletionReport, SecureDelete};
+pub use session::{SessionTimeout, TokenManager};
+pub use startup_check::{StartupCheck, StartupIssue};
+pub use storage::{FsStorage, MemoryStorage, Storage};
+pub use system_idle::{system_idle_time, SuspendDetector};
+pub use totp::{Totp, TotpCode};
+pub use url_metadata::UrlMetadata;
+pub use vault_storage::VaultStorage;
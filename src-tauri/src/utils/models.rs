@@ -1,4 +1,5 @@
-use super::{database::User, PasswordEntry};
+use super::database::{decrypt_field, encrypt_field, User};
+use super::{Encryption, PasswordEntry};
 
 pub trait Model {
     /// Get the name of the table for the model.
@@ -38,6 +39,37 @@ pub trait Model {
     ///
     /// The ID of the model if it exists.
     fn get_id(&self) -> Option<i32>;
+
+    /// Get a copy of the model with the fields encrypted that are protected in addition to
+    /// the database encryption.
+    ///
+    /// # Arguments
+    ///
+    /// * `encryption` - The encryption of the database.
+    ///
+    /// # Returns
+    ///
+    /// The encrypted copy, or None if the model has no such fields.
+    ///
+    /// # Errors
+    ///
+    /// If a field cannot be encrypted.
+    fn encrypt_fields(
+        &self,
+        _encryption: &Encryption,
+    ) -> Result<Option<Self>, Box<dyn std::error::Error>>
+    where
+        Self: Sized,
+    {
+        Ok(None)
+    }
+
+    /// Decrypt the fields that are stored encrypted, fields stored as plaintext are kept.
+    ///
+    /// # Arguments
+    ///
+    /// * `encryption` - The encryption of the database.
+    fn decrypt_fields(&mut self, _encryption: &Encryption) {}
 }
 
 impl Model for User {
@@ -85,6 +117,13 @@ impl Model for PasswordEntry {
             notes: row.get(6)?,
             created_at: row.get(7)?,
             updated_at: row.get(8)?,
+            require_master: row.get(9)?,
+            recovery_codes: row.get(10)?,
+            totp_secret: row.get(11)?,
+            folder_id: row.get(12)?,
+            locked: row.get(13)?,
+            deleted_at: row.get(14)?,
+            expires_on: row.get(15)?,
         })
     }
 
@@ -98,12 +137,33 @@ impl Model for PasswordEntry {
             ("notes", &self.notes),
             ("created_at", &self.created_at),
             ("updated_at", &self.updated_at),
+            ("require_master", &self.require_master),
         ]
     }
 
     fn get_id(&self) -> Option<i32> {
         self.id
     }
+
+    fn encrypt_fields(
+        &self,
+        encryption: &Encryption,
+    ) -> Result<Option<Self>, Box<dyn std::error::Error>> {
+        let mut entry = self.clone();
+        for field in [&mut entry.username, &mut entry.url, &mut entry.notes] {
+            *field = encrypt_field(encryption, field)?;
+        }
+
+        Ok(Some(entry))
+    }
+
+    fn decrypt_fields(&mut self, encryption: &Encryption) {
+        for field in [&mut self.username, &mut self.url, &mut self.notes] {
+            if let Some(plain) = decrypt_field(encryption, field) {
+                *field = plain;
+            }
+        }
+    }
 }
 
 #[cfg(test)]
@@ -146,11 +206,18 @@ mod tests {
             notes: "notes".to_string(),
             created_at: Utc::now().to_rfc3339(),
             updated_at: Utc::now().to_rfc3339(),
+            require_master: false,
+            recovery_codes: String::new(),
+            totp_secret: String::new(),
+            folder_id: None,
+            locked: false,
+            deleted_at: None,
+            expires_on: None,
         };
         assert_eq!(entry.get_id(), Some(1));
 
         let params = entry.to_params();
-        assert_eq!(params.len(), 8);
+        assert_eq!(params.len(), 9);
         assert_eq!(params[0].0, "user_id");
         assert_eq!(params[1].0, "service");
         assert_eq!(params[2].0, "username");
@@ -159,6 +226,7 @@ mod tests {
         assert_eq!(params[5].0, "notes");
         assert_eq!(params[6].0, "created_at");
         assert_eq!(params[7].0, "updated_at");
+        assert_eq!(params[8].0, "require_master");
     }
 
     #[test]
@@ -182,6 +250,13 @@ mod tests {
             notes: "notes".to_string(),
             created_at: Utc::now().to_rfc3339(),
             updated_at: Utc::now().to_rfc3339(),
+            require_master: false,
+            recovery_codes: String::new(),
+            totp_secret: String::new(),
+            folder_id: None,
+            locked: false,
+            deleted_at: None,
+            expires_on: None,
         };
         assert_eq!(entry.get_id(), None);
     }
@@ -0,0 +1,369 @@
+use serde_json::{json, Value};
+use std::io::{self, Read, Write};
+use std::path::{Path, PathBuf};
+
+/// The name browsers know the native messaging host by.
+pub const NATIVE_HOST_NAME: &str = "com.karncrypt.browser";
+/// The ID of the KarnCrypt extension for Firefox, allowed by default.
+pub const FIREFOX_EXTENSION_ID: &str = "browser@karncrypt";
+/// Prefix of the JSON-RPC methods the native messaging host forwards to the app.
+pub const BROWSER_METHOD_PREFIX: &str = "browser.";
+/// The maximum size of a message, browsers reject larger messages from the host.
+const MAX_MESSAGE_SIZE: usize = 1024 * 1024;
+
+/// The browsers the host can be registered for.
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub enum BrowserFamily {
+    Chromium,
+    Firefox,
+}
+
+/// Where a browser looks for native messaging hosts.
+struct Browser {
+    family: BrowserFamily,
+    /// The manifest directory below the home directory on Linux.
+    linux_dir: &'static str,
+    /// The manifest directory below the home directory on macOS.
+    macos_dir: &'static str,
+    /// The registry key below `HKEY_CURRENT_USER` on Windows.
+    #[cfg_attr(not(target_os = "windows"), allow(dead_code))]
+    windows_key: &'static str,
+}
+
+const BROWSERS: &[Browser] = &[
+    Browser {
+        family: BrowserFamily::Chromium,
+        linux_dir: ".config/google-chrome/NativeMessagingHosts",
+        macos_dir: "Library/Application Support/Google/Chrome/NativeMessagingHosts",
+        windows_key: r"Software\Google\Chrome\NativeMessagingHosts",
+    },
+    Browser {
+        family: BrowserFamily::Chromium,
+        linux_dir: ".config/chromium/NativeMessagingHosts",
+        macos_dir: "Library/Application Support/Chromium/NativeMessagingHosts",
+        windows_key: r"Software\Chromium\NativeMessagingHosts",
+    },
+    Browser {
+        family: BrowserFamily::Chromium,
+        linux_dir: ".config/BraveSoftware/Brave-Browser/NativeMessagingHosts",
+        macos_dir: "Library/Application Support/BraveSoftware/Brave-Browser/NativeMessagingHosts",
+        windows_key: r"Software\BraveSoftware\Brave-Browser\NativeMessagingHosts",
+    },
+    Browser {
+        family: BrowserFamily::Chromium,
+        linux_dir: ".config/microsoft-edge/NativeMessagingHosts",
+        macos_dir: "Library/Application Support/Microsoft Edge/NativeMessagingHosts",
+        windows_key: r"Software\Microsoft\Edge\NativeMessagingHosts",
+    },
+    Browser {
+        family: BrowserFamily::Firefox,
+        linux_dir: ".mozilla/native-messaging-hosts",
+        macos_dir: "Library/Application Support/Mozilla/NativeMessagingHosts",
+        windows_key: r"Software\Mozilla\NativeMessagingHosts",
+    },
+];
+
+/// Get the extension that launched the app as native messaging host
+///
+/// Chromium passes the origin of the extension as first argument, Firefox the path to the
+/// host manifest and the ID of the extension.
+///
+/// # Arguments
+///
+/// * `args` - The launch arguments without the program name
+///
+/// # Returns
+///
+/// The origin or ID of the extension, None if the app was not launched by a browser
+pub fn extension_origin(args: &[String]) -> Option<String> {
+    match args {
+        [origin, ..] if origin.starts_with("chrome-extension://") => Some(origin.clone()),
+        [manifest, extension_id, ..]
+            if manifest.ends_with(&format!("{}.json", NATIVE_HOST_NAME)) =>
+        {
+            Some(extension_id.clone())
+        }
+        _ => None,
+    }
+}
+
+/// Read a message from the browser
+///
+/// Every message is JSON prefixed with its length as 32-bit integer in native byte order.
+///
+/// # Arguments
+///
+/// * `reader` - The input from the browser, usually stdin
+///
+/// # Returns
+///
+/// The message, None if the browser closed the connection
+///
+/// # Errors
+///
+/// Returns an error if the message is too large, incomplete or no valid JSON
+pub fn read_message(reader: &mut impl Read) -> io::Result<Option<Value>> {
+    let mut length = [0u8; 4];
+    match reader.read_exact(&mut length) {
+        Ok(()) => {}
+        Err(e) if e.kind() == io::ErrorKind::UnexpectedEof => return Ok(None),
+        Err(e) => return Err(e),
+    }
+
+    let length = u32::from_ne_bytes(length) as usize;
+    if length > MAX_MESSAGE_SIZE {
+        return Err(io::Error::new(
+            io::ErrorKind::InvalidData,
+            "Native message too large",
+        ));
+    }
+
+    let mut message = vec![0u8; length];
+    reader.read_exact(&mut message)?;
+    Ok(Some(serde_json::from_slice(&message)?))
+}
+
+/// Write a message to the browser
+///
+/// # Arguments
+///
+/// * `writer` - The output to the browser, usually stdout
+/// * `message` - The message to send
+///
+/// # Errors
+///
+/// Returns an error if the message is too large or cannot be written
+pub fn write_message(writer: &mut impl Write, message: &Value) -> io::Result<()> {
+    let message = serde_json::to_vec(message)?;
+    if message.len() > MAX_MESSAGE_SIZE {
+        return Err(io::Error::new(
+            io::ErrorKind::InvalidData,
+            "Native message too large",
+        ));
+    }
+
+    writer.write_all(&(message.len() as u32).to_ne_bytes())?;
+    writer.write_all(&message)?;
+    writer.flush()
+}
+
+/// Build the manifest that registers the app as native messaging host
+///
+/// Chromium IDs are 32 letters from `a` to `p` and are allowed as origins, every other ID
+/// is allowed as Firefox extension.
+///
+/// # Arguments
+///
+/// * `path` - The path to the executable of the app
+/// * `extension_ids` - The extensions allowed to connect
+/// * `family` - The browser the manifest is for
+///
+/// # Returns
+///
+/// The host manifest
+pub fn host_manifest(path: &Path, extension_ids: &[String], family: BrowserFamily) -> Value {
+    let is_chromium_id =
+        |id: &&String| id.len() == 32 && id.chars().all(|c| ('a'..='p').contains(&c));
+
+    let mut manifest = json!({
+        "name": NATIVE_HOST_NAME,
+        "description": "KarnCrypt",
+        "path": path,
+        "type": "stdio",
+    });
+    match family {
+        BrowserFamily::Chromium => {
+            manifest["allowed_origins"] = extension_ids
+                .iter()
+                .filter(is_chromium_id)
+                .map(|id| format!("chrome-extension://{}/", id))
+                .collect();
+        }
+        BrowserFamily::Firefox => {
+            manifest["allowed_extensions"] = extension_ids
+                .iter()
+                .filter(|id| !is_chromium_id(id))
+                .cloned()
+                .collect();
+        }
+    }
+
+    manifest
+}
+
+/// Register the app as native messaging host for the installed browsers
+///
+/// On Linux and macOS the manifest is written to the host directory of every browser with a
+/// profile directory. On Windows the manifests are written to the config directory and
+/// registered in the registry of the current user.
+///
+/// # Arguments
+///
+/// * `exe` - The path to the executable of the app
+/// * `extension_ids` - The extensions allowed to connect
+/// * `config_dir` - The directory containing the configuration files
+///
+/// # Returns
+///
+/// The number of browsers the host was registered for
+///
+/// # Errors
+///
+/// Returns an error if the home directory is unknown or a manifest cannot be written
+pub fn install_host(
+    exe: &Path,
+    extension_ids: &[String],
+    config_dir: &Path,
+) -> Result<usize, Box<dyn std::error::Error>> {
+    let file_name = format!("{}.json", NATIVE_HOST_NAME);
+
+    #[cfg(target_os = "windows")]
+    {
+        let manifest_dir = config_dir.join("native-messaging");
+        std::fs::create_dir_all(&manifest_dir)?;
+
+        let mut installed = 0;
+        for browser in BROWSERS {
+            let path = manifest_dir.join(match browser.family {
+                BrowserFamily::Chromium => format!("chromium-{}", file_name),
+                BrowserFamily::Firefox => format!("firefox-{}", file_name),
+            });
+            let manifest = host_manifest(exe, extension_ids, browser.family);
+            std::fs::write(&path, serde_json::to_string_pretty(&manifest)?)?;
+
+            let status = std::process::Command::new("reg")
+                .args([
+                    "add",
+                    &format!(r"HKCU\{}\{}", browser.windows_key, NATIVE_HOST_NAME),
+                ])
+                .args(["/ve", "/t", "REG_SZ", "/d"])
+                .arg(&path)
+                .arg("/f")
+                .status()?;
+            if status.success() {
+                installed += 1;
+            }
+        }
+        Ok(installed)
+    }
+
+    #[cfg(not(target_os = "windows"))]
+    {
+        let _ = config_dir;
+        let home = dirs::home_dir().ok_or("Home directory not found")?;
+
+        let mut installed = 0;
+        for (dir, family) in manifest_dirs(&home) {
+            if !dir.parent().is_some_and(Path::exists) {
+                continue;
+            }
+            std::fs::create_dir_all(&dir)?;
+            let manifest = host_manifest(exe, extension_ids, family);
+            std::fs::write(
+                dir.join(&file_name),
+                serde_json::to_string_pretty(&manifest)?,
+            )?;
+            installed += 1;
+        }
+        Ok(installed)
+    }
+}
+
+/// Get the manifest directories of the browsers on this platform
+#[cfg_attr(target_os = "windows", allow(dead_code))]
+fn manifest_dirs(home: &Path) -> Vec<(PathBuf, BrowserFamily)> {
+    BROWSERS
+        .iter()
+        .map(|browser| {
+            let dir = if cfg!(target_os = "macos") {
+                browser.macos_dir
+            } else {
+                browser.linux_dir
+            };
+            (home.join(dir), browser.family)
+        })
+        .collect()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use std::io::Cursor;
+
+    #[test]
+    fn test_message_roundtrip() {
+        let mut buffer = Vec::new();
+        write_message(&mut buffer, &json!({"id": 1, "method": "get_logins"})).unwrap();
+        write_message(&mut buffer, &json!("second")).unwrap();
+
+        let mut reader = Cursor::new(buffer);
+        assert_eq!(
+            read_message(&mut reader).unwrap(),
+            Some(json!({"id": 1, "method": "get_logins"}))
+        );
+        assert_eq!(read_message(&mut reader).unwrap(), Some(json!("second")));
+        assert_eq!(read_message(&mut reader).unwrap(), None);
+    }
+
+    #[test]
+    fn test_invalid_message() {
+        let mut oversized = ((MAX_MESSAGE_SIZE + 1) as u32).to_ne_bytes().to_vec();
+        oversized.extend_from_slice(b"{}");
+        assert!(read_message(&mut Cursor::new(oversized)).is_err());
+
+        let mut truncated = 10u32.to_ne_bytes().to_vec();
+        truncated.extend_from_slice(b"{}");
+        assert!(read_message(&mut Cursor::new(truncated)).is_err());
+
+        let mut buffer = Vec::new();
+        let large = "a".repeat(MAX_MESSAGE_SIZE);
+        assert!(write_message(&mut buffer, &json!(large)).is_err());
+        assert!(buffer.is_empty());
+    }
+
+    #[test]
+    fn test_extension_origin() {
+        let args = |args: &[&str]| -> Vec<String> { args.iter().map(|a| a.to_string()).collect() };
+
+        assert_eq!(
+            extension_origin(&args(&[
+                "chrome-extension://abcdefghijklmnopabcdefghijklmnop/",
+                "--parent-window=0"
+            ]))
+            .as_deref(),
+            Some("chrome-extension://abcdefghijklmnopabcdefghijklmnop/")
+        );
+        assert_eq!(
+            extension_origin(&args(&[
+                "/home/me/.mozilla/native-messaging-hosts/com.karncrypt.browser.json",
+                FIREFOX_EXTENSION_ID
+            ]))
+            .as_deref(),
+            Some(FIREFOX_EXTENSION_ID)
+        );
+        assert_eq!(extension_origin(&args(&["--backup"])), None);
+        assert_eq!(extension_origin(&[]), None);
+    }
+
+    #[test]
+    fn test_host_manifest() {
+        let ids = vec![
+            FIREFOX_EXTENSION_ID.to_string(),
+            "abcdefghijklmnopabcdefghijklmnop".to_string(),
+        ];
+        let path = Path::new("/usr/bin/karncrypt");
+
+        let chromium = host_manifest(path, &ids, BrowserFamily::Chromium);
+        assert_eq!(chromium["name"], NATIVE_HOST_NAME);
+        assert_eq!(chromium["path"], "/usr/bin/karncrypt");
+        assert_eq!(
+            chromium["allowed_origins"],
+            json!(["chrome-extension://abcdefghijklmnopabcdefghijklmnop/"])
+        );
+        assert!(chromium.get("allowed_extensions").is_none());
+
+        let firefox = host_manifest(path, &ids, BrowserFamily::Firefox);
+        assert_eq!(firefox["allowed_extensions"], json!([FIREFOX_EXTENSION_ID]));
+        assert!(firefox.get("allowed_origins").is_none());
+    }
+}
@@ -0,0 +1,485 @@
+use serde::{Deserialize, Serialize};
+use serde_json::Value;
+use std::fs;
+use std::path::Path;
+
+use super::{EntrySummary, Totp};
+
+/// The largest backup that is read, authenticator backups are a few kilobytes.
+const MAX_BACKUP_SIZE: u64 = 10_000_000;
+
+/// An authenticator app whose backups can be imported.
+#[derive(Clone, Copy, Debug, PartialEq, Serialize, Deserialize)]
+#[serde(rename_all = "lowercase")]
+pub enum OtpFormat {
+    Aegis,
+    Andotp,
+}
+
+/// A TOTP account of an authenticator backup.
+#[derive(Clone, Debug, PartialEq)]
+pub struct OtpAccount {
+    pub issuer: String,
+    pub account: String,
+    /// The secret as `otpauth://totp/` URI, so digits, period and algorithm are kept.
+    pub secret: String,
+}
+
+/// The TOTP accounts of an authenticator backup.
+#[derive(Debug, Default)]
+pub struct OtpExport {
+    pub accounts: Vec<OtpAccount>,
+    /// The number of HOTP, Steam and other accounts that cannot be imported.
+    pub skipped: usize,
+}
+
+/// A plaintext Aegis export, `db` is a base64 string in encrypted vaults.
+#[derive(Deserialize)]
+struct AegisVault {
+    db: Value,
+}
+
+#[derive(Deserialize)]
+struct AegisDb {
+    entries: Vec<AegisEntry>,
+}
+
+#[derive(Deserialize)]
+struct AegisEntry {
+    #[serde(rename = "type")]
+    kind: String,
+    #[serde(default)]
+    name: String,
+    #[serde(default)]
+    issuer: String,
+    info: AegisInfo,
+}
+
+#[derive(Deserialize)]
+struct AegisInfo {
+    secret: String,
+    algo: Option<String>,
+    digits: Option<u32>,
+    period: Option<u64>,
+}
+
+/// An account of a plaintext andOTP backup.
+#[derive(Deserialize)]
+struct AndotpEntry {
+    #[serde(rename = "type")]
+    kind: String,
+    secret: String,
+    #[serde(default)]
+    issuer: String,
+    #[serde(default)]
+    label: String,
+    algorithm: Option<String>,
+    digits: Option<u32>,
+    period: Option<u64>,
+}
+
+impl OtpExport {
+    /// Read the backup file of an authenticator app, see `parse`
+    ///
+    /// # Errors
+    ///
+    /// Returns an error if the file is too large, encrypted or no backup of the app
+    pub fn read(path: &Path, format: OtpFormat) -> Result<Self, Box<dyn std::error::Error>> {
+        if fs::metadata(path)?.len() > MAX_BACKUP_SIZE {
+            return Err("The backup is too large".into());
+        }
+
+        Self::parse(&fs::read_to_string(path)?, format)
+    }
+
+    /// Parse the backup of an authenticator app
+    ///
+    /// Only plaintext backups are supported, Aegis vaults have to be exported without
+    /// encryption and andOTP backups as plain JSON. Accounts that are no TOTP accounts or
+    /// whose secret is invalid are skipped.
+    ///
+    /// # Arguments
+    ///
+    /// * `content` - The content of the backup file
+    /// * `format` - The app the backup was created with
+    ///
+    /// # Returns
+    ///
+    /// The TOTP accounts and the number of skipped accounts
+    ///
+    /// # Errors
+    ///
+    /// Returns an error if the backup is encrypted or no backup of the app
+    pub fn parse(content: &str, format: OtpFormat) -> Result<Self, Box<dyn std::error::Error>> {
+        match format {
+            OtpFormat::Aegis => Self::parse_aegis(content),
+            OtpFormat::Andotp => Self::parse_andotp(content),
+        }
+    }
+
+    fn parse_aegis(content: &str) -> Result<Self, Box<dyn std::error::Error>> {
+        let vault: AegisVault =
+            serde_json::from_str(content).map_err(|_| "The file is no Aegis export")?;
+        if vault.db.is_string() {
+            return Err("The Aegis vault is encrypted, export it without encryption".into());
+        }
+        let db: AegisDb =
+            serde_json::from_value(vault.db).map_err(|_| "The file is no Aegis export")?;
+
+        let mut export = Self::default();
+        for entry in db.entries {
+            if !entry.kind.eq_ignore_ascii_case("totp") {
+                export.skipped += 1;
+                continue;
+            }
+            export.push(
+                entry.issuer,
+                entry.name,
+                &entry.info.secret,
+                entry.info.algo.as_deref(),
+                entry.info.digits,
+                entry.info.period,
+            );
+        }
+
+        Ok(export)
+    }
+
+    fn parse_andotp(content: &str) -> Result<Self, Box<dyn std::error::Error>> {
+        let entries: Vec<AndotpEntry> = serde_json::from_str(content).map_err(|_| {
+            "The file is no plaintext andOTP backup, encrypted backups are not supported"
+        })?;
+
+        let mut export = Self::default();
+        for entry in entries {
+            if !entry.kind.eq_ignore_ascii_case("totp") {
+                export.skipped += 1;
+                continue;
+            }
+            // Older andOTP versions have no issuer field and keep it in the label.
+            let (issuer, account) = if entry.issuer.is_empty() {
+                match entry
+                    .label
+                    .split_once(':')
+                    .or_else(|| entry.label.split_once(" - "))
+                {
+                    Some((issuer, account)) => (issuer.trim().to_string(), account.to_string()),
+                    None => (String::new(), entry.label),
+                }
+            } else {
+                (entry.issuer, entry.label)
+            };
+            export.push(
+                issuer,
+                account,
+                &entry.secret,
+                entry.algorithm.as_deref(),
+                entry.digits,
+                entry.period,
+            );
+        }
+
+        Ok(export)
+    }
+
+    /// Add an account if its secret and parameters are valid, skip it otherwise
+    fn push(
+        &mut self,
+        issuer: String,
+        account: String,
+        secret: &str,
+        algorithm: Option<&str>,
+        digits: Option<u32>,
+        period: Option<u64>,
+    ) {
+        let issuer = issuer.trim().to_string();
+        let account = account.trim().to_string();
+        let label = if issuer.is_empty() {
+            account.clone()
+        } else {
+            format!("{}:{}", issuer, account)
+        };
+
+        let mut secret = format!(
+            "otpauth://totp/{}?secret={}",
+            encode_label(&label),
+            secret.replace(' ', "").to_uppercase()
+        );
+        if let Some(algorithm) = algorithm {
+            secret.push_str(&format!("&algorithm={}", algorithm.to_uppercase()));
+        }
+        if let Some(digits) = digits {
+            secret.push_str(&format!("&digits={}", digits));
+        }
+        if let Some(period) = period {
+            secret.push_str(&format!("&period={}", period));
+        }
+
+        match Totp::parse(&secret) {
+            Ok(_) => self.accounts.push(OtpAccount {
+                issuer,
+                account,
+                secret,
+            }),
+            Err(_) => self.skipped += 1,
+        }
+    }
+}
+
+impl OtpAccount {
+    /// Suggest the entry the account belongs to
+    ///
+    /// The issuer is compared with the service and URL of the entries and the account with
+    /// their username. An entry matching both wins, otherwise an entry is only suggested if
+    /// it is the only one matching the issuer.
+    ///
+    /// # Arguments
+    ///
+    /// * `entries` - The entries of the vault
+    ///
+    /// # Returns
+    ///
+    /// The ID of the suggested entry, None if no entry matches unambiguously
+    pub fn matching_entry(&self, entries: &[EntrySummary]) -> Option<i32> {
+        let issuer = normalize(&self.issuer);
+        let account = self.account.to_lowercase();
+
+        let candidates: Vec<&EntrySummary> = entries
+            .iter()
+            .filter(|entry| entry.deleted_at.is_none())
+            .filter(|entry| {
+                let service = normalize(&entry.service);
+                !issuer.is_empty()
+                    && ((!service.is_empty()
+                        && (service.contains(&issuer) || issuer.contains(&service)))
+                        || normalize(&entry.url).contains(&issuer))
+            })
+            .collect();
+
+        candidates
+            .iter()
+            .find(|entry| !account.is_empty() && entry.username.to_lowercase() == account)
+            .or(match candidates.as_slice() {
+                [entry] => Some(entry),
+                _ => None,
+            })
+            .and_then(|entry| entry.id)
+    }
+}
+
+/// Lowercase a name and drop everything but letters and digits, e.g. for `Git Hub` and
+/// `https://github.com`
+fn normalize(name: &str) -> String {
+    name.chars()
+        .filter(|c| c.is_alphanumeric())
+        .flat_map(char::to_lowercase)
+        .collect()
+}
+
+/// Percent-encode the label of an `otpauth://` URI
+fn encode_label(label: &str) -> String {
+    label
+        .bytes()
+        .map(|b| match b {
+            b'A'..=b'Z' | b'a'..=b'z' | b'0'..=b'9' | b'-' | b'.' | b'_' | b'~' | b'@' | b':' => {
+                (b as char).to_string()
+            }
+            _ => format!("%{:02X}", b),
+        })
+        .collect()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::utils::PasswordEntry;
+
+    const AEGIS_EXPORT: &str = r#"{
+        "version": 1,
+        "header": { "slots": null, "params": null },
+        "db": {
+            "version": 2,
+            "entries": [
+                {
+                    "type": "totp",
+                    "uuid": "3ae6f1ad-2d7b-4b0f-a1e5-0d1cb1b0e2a1",
+                    "name": "max@example.com",
+                    "issuer": "GitHub",
+                    "note": "",
+                    "icon": null,
+                    "info": { "secret": "JBSWY3DPEHPK3PXP", "algo": "SHA1", "digits": 6, "period": 30 }
+                },
+                {
+                    "type": "totp",
+                    "uuid": "9c1e0a52-7f4e-4c59-b1a4-5a2d3c4e5f60",
+                    "name": "max",
+                    "issuer": "Example Bank",
+                    "note": "",
+                    "icon": null,
+                    "info": { "secret": "GEZDGNBVGY3TQOJQ", "algo": "SHA256", "digits": 8, "period": 60 }
+                },
+                {
+                    "type": "hotp",
+                    "uuid": "0b8a7c6d-5e4f-4a3b-9c2d-1e0f9a8b7c6d",
+                    "name": "max",
+                    "issuer": "VPN",
+                    "note": "",
+                    "icon": null,
+                    "info": { "secret": "JBSWY3DPEHPK3PXP", "algo": "SHA1", "digits": 6, "counter": 3 }
+                }
+            ]
+        }
+    }"#;
+
+    const AEGIS_ENCRYPTED: &str = r#"{
+        "version": 1,
+        "header": {
+            "slots": [{ "type": 1, "uuid": "a", "key": "00", "key_params": {}, "n": 32768, "r": 8, "p": 1, "salt": "00" }],
+            "params": { "nonce": "00", "tag": "00" }
+        },
+        "db": "c2VjcmV0"
+    }"#;
+
+    const ANDOTP_EXPORT: &str = r#"[
+        {
+            "secret": "JBSWY3DPEHPK3PXP",
+            "issuer": "GitHub",
+            "label": "max@example.com",
+            "digits": 6,
+            "type": "TOTP",
+            "algorithm": "SHA1",
+            "thumbnail": "Default",
+            "last_used": 0,
+            "used_frequency": 0,
+            "period": 30,
+            "tags": []
+        },
+        {
+            "secret": "GEZD GNBV GY3T QOJQ",
+            "label": "Mailbox - max",
+            "digits": 6,
+            "type": "TOTP",
+            "algorithm": "SHA512",
+            "period": 30,
+            "tags": []
+        },
+        {
+            "secret": "JBSWY3DPEHPK3PXP",
+            "issuer": "Steam",
+            "label": "max",
+            "digits": 5,
+            "type": "STEAM",
+            "algorithm": "SHA1",
+            "period": 30,
+            "tags": []
+        },
+        {
+            "secret": "not base32!",
+            "issuer": "Broken",
+            "label": "max",
+            "digits": 6,
+            "type": "TOTP",
+            "algorithm": "SHA1",
+            "period": 30,
+            "tags": []
+        }
+    ]"#;
+
+    fn entry(id: i32, service: &str, username: &str, url: &str) -> EntrySummary {
+        let mut entry = PasswordEntry::new(
+            1,
+            service.to_string(),
+            username.to_string(),
+            "secret".to_string(),
+            url.to_string(),
+            String::new(),
+        );
+        entry.id = Some(id);
+        EntrySummary::from(&entry)
+    }
+
+    fn account(issuer: &str, account: &str) -> OtpAccount {
+        OtpAccount {
+            issuer: issuer.to_string(),
+            account: account.to_string(),
+            secret: String::new(),
+        }
+    }
+
+    #[test]
+    fn test_parse_aegis() {
+        let export = OtpExport::parse(AEGIS_EXPORT, OtpFormat::Aegis).unwrap();
+
+        assert_eq!(export.skipped, 1);
+        assert_eq!(export.accounts.len(), 2);
+        assert_eq!(export.accounts[0].issuer, "GitHub");
+        assert_eq!(export.accounts[0].account, "max@example.com");
+        assert_eq!(
+            export.accounts[0].secret,
+            "otpauth://totp/GitHub:max@example.com?secret=JBSWY3DPEHPK3PXP&algorithm=SHA1&digits=6&period=30"
+        );
+        assert_eq!(
+            export.accounts[1].secret,
+            "otpauth://totp/Example%20Bank:max?secret=GEZDGNBVGY3TQOJQ&algorithm=SHA256&digits=8&period=60"
+        );
+        assert!(Totp::parse(&export.accounts[1].secret).is_ok());
+    }
+
+    #[test]
+    fn test_parse_aegis_rejects_encrypted_vault() {
+        let error = OtpExport::parse(AEGIS_ENCRYPTED, OtpFormat::Aegis).unwrap_err();
+        assert!(error.to_string().contains("encrypted"));
+
+        assert!(OtpExport::parse(ANDOTP_EXPORT, OtpFormat::Aegis).is_err());
+    }
+
+    #[test]
+    fn test_parse_andotp() {
+        let export = OtpExport::parse(ANDOTP_EXPORT, OtpFormat::Andotp).unwrap();
+
+        // The Steam account and the invalid secret are skipped.
+        assert_eq!(export.skipped, 2);
+        assert_eq!(export.accounts.len(), 2);
+        assert_eq!(export.accounts[0].issuer, "GitHub");
+        assert_eq!(export.accounts[0].account, "max@example.com");
+        assert_eq!(export.accounts[1].issuer, "Mailbox");
+        assert_eq!(export.accounts[1].account, "max");
+        assert_eq!(
+            export.accounts[1].secret,
+            "otpauth://totp/Mailbox:max?secret=GEZDGNBVGY3TQOJQ&algorithm=SHA512&digits=6&period=30"
+        );
+    }
+
+    #[test]
+    fn test_parse_andotp_rejects_encrypted_backup() {
+        assert!(OtpExport::parse("\u{1}\u{2}binary", OtpFormat::Andotp).is_err());
+        assert!(OtpExport::parse(AEGIS_EXPORT, OtpFormat::Andotp).is_err());
+    }
+
+    #[test]
+    fn test_matching_entry() {
+        let entries = vec![
+            entry(1, "GitHub", "other@example.com", "https://github.com"),
+            entry(2, "GitHub", "Max@Example.com", "https://github.com"),
+            entry(3, "Bank", "max", "https://example-bank.com"),
+            entry(4, "Mail", "max", "https://mail.example"),
+            entry(5, "Mail (work)", "max.work", "https://mail.example"),
+        ];
+
+        // The entry with the same username wins over other entries of the issuer.
+        assert_eq!(
+            account("GitHub", "max@example.com").matching_entry(&entries),
+            Some(2)
+        );
+        // A single entry of the issuer is suggested even with another username.
+        assert_eq!(
+            account("Example Bank", "max.m").matching_entry(&entries),
+            Some(3)
+        );
+        // Several entries of the issuer without matching username are ambiguous.
+        assert_eq!(account("Mail", "someone").matching_entry(&entries), None);
+        assert_eq!(account("Mail", "max").matching_entry(&entries), Some(4));
+        assert_eq!(account("", "max").matching_entry(&entries), None);
+        assert_eq!(account("Dropbox", "max").matching_entry(&entries), None);
+    }
+}
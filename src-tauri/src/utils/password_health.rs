@@ -1,17 +1,38 @@
-use chrono::{DateTime, Utc};
+use chrono::{DateTime, NaiveDate, Utc};
 use lazy_static::lazy_static;
+use log::{info, warn};
 use serde::Serialize;
 use std::collections::{HashMap, HashSet};
+use std::fs;
+use std::path::{Path, PathBuf};
+use std::sync::RwLock;
+
+use super::fingerprint::{fingerprint, fingerprints};
+
+/// The fingerprints of the bundled list, generated by the build script as sorted u64 values.
+const EMBEDDED_COMMON_PASSWORDS: &[u8] =
+    include_bytes!(concat!(env!("OUT_DIR"), "/common_passwords.bin"));
+const COMMON_PASSWORDS_FILE: &str = "common_passwords.txt";
+const MAX_COMMON_PASSWORDS: usize = 1_000_000;
+
+const MIN_DICTIONARY_WORD_LENGTH: usize = 4;
+
+/// The days before its expiry date a password is reported as expiring soon.
+pub const EXPIRY_WARNING_DAYS: i64 = 14;
 
 lazy_static! {
-    static ref COMMON_PASSWORDS: HashSet<&'static str> = {
-        let common_passwords = include_str!("common_passwords.txt");
-        common_passwords.lines().collect()
-    };
+    static ref DICTIONARY_WORDS: HashSet<&'static str> = include_str!("dictionary_en.txt")
+        .lines()
+        .chain(include_str!("dictionary_de.txt").lines())
+        .collect();
+    static ref COMMON_PASSWORDS: RwLock<CommonPasswordTable> =
+        RwLock::new(CommonPasswordTable::embedded());
 }
 
 #[derive(Clone)]
 pub struct PasswordHealth {
+    /// The ID of the checked entry, None for passwords that are not stored yet.
+    pub entry_id: Option<i32>,
     pub service: String,
     pub username: String,
     pub last_modified: DateTime<Utc>,
@@ -29,6 +50,7 @@ pub struct PasswordHealth {
     sequential_chars: usize,
     unique_chars: usize,
     is_common_password: bool,
+    dictionary_words: Vec<String>,
     is_duplicate: bool,
 }
 
@@ -46,6 +68,7 @@ pub enum PasswordIssue {
     TooWeak,
     TooShort,
     Common,
+    DictionaryWord,
     Duplicate,
     Sequential,
     Repeated,
@@ -54,6 +77,25 @@ pub enum PasswordIssue {
     NoUppercase,
     NoLowercase,
     Outdated,
+    /// Found in a data breach by the Have I Been Pwned check.
+    Breached,
+    /// Reused only for accounts of one provider, e.g. through single sign-on.
+    SharedLogin,
+    /// The expiry date set for the entry has passed.
+    Expired,
+    /// The expiry date set for the entry is within the next `EXPIRY_WARNING_DAYS` days.
+    ExpiringSoon,
+}
+
+/// Get the words of the embedded dictionaries, e.g. to build passphrases.
+///
+/// # Returns
+///
+/// The dictionary words in alphabetical order.
+pub fn dictionary_words() -> Vec<&'static str> {
+    let mut words: Vec<&'static str> = DICTIONARY_WORDS.iter().copied().collect();
+    words.sort_unstable();
+    words
 }
 
 impl PasswordHealth {
@@ -69,6 +111,7 @@ impl PasswordHealth {
         last_modified: DateTime<Utc>,
     ) -> Self {
         Self {
+            entry_id: None,
             service,
             username,
             last_modified,
@@ -86,6 +129,7 @@ impl PasswordHealth {
             sequential_chars: 0,
             unique_chars: 0,
             is_common_password: false,
+            dictionary_words: Vec::new(),
             is_duplicate: false,
         }
     }
@@ -104,6 +148,7 @@ impl PasswordHealth {
         self.check_character_types();
         self.check_complexity();
         self.check_common_password();
+        self.check_dictionary_words();
         self.check_age();
         self.collect_issues();
         self.determine_strength();
@@ -269,7 +314,10 @@ impl PasswordHealth {
 
     /// Check if the password is a common password and assign a score based on the result.
     fn check_common_password(&mut self) {
-        self.is_common_password = COMMON_PASSWORDS.contains(self.password.as_str());
+        self.is_common_password = COMMON_PASSWORDS
+            .read()
+            .unwrap()
+            .contains(self.password.as_str());
 
         if self.is_common_password {
             self.score = self.score.saturating_sub(50);
@@ -279,6 +327,58 @@ impl PasswordHealth {
         }
     }
 
+    /// Check if the password contains German or English dictionary words and assign a score
+    /// based on how much of the password they cover.
+    ///
+    /// Common letter substitutions like `@` for `a` or `0` for `o` are undone before the check.
+    fn check_dictionary_words(&mut self) {
+        let lowercase = self.password.to_lowercase();
+        let substituted = lowercase
+            .chars()
+            .map(|c| match c {
+                '@' | '4' => 'a',
+                '3' => 'e',
+                '1' | '!' => 'i',
+                '0' => 'o',
+                '$' | '5' => 's',
+                '7' => 't',
+                _ => c,
+            })
+            .collect::<String>();
+
+        let mut covered = HashSet::new();
+        for candidate in [lowercase, substituted] {
+            let chars: Vec<char> = candidate.chars().collect();
+            for start in 0..chars.len() {
+                for end in (start + MIN_DICTIONARY_WORD_LENGTH..=chars.len()).rev() {
+                    let word = chars[start..end].iter().collect::<String>();
+                    if DICTIONARY_WORDS.contains(word.as_str()) {
+                        covered.extend(start..end);
+                        if !self.dictionary_words.contains(&word) {
+                            self.dictionary_words.push(word);
+                        }
+                        break;
+                    }
+                }
+            }
+        }
+
+        if self.dictionary_words.is_empty() {
+            return;
+        }
+
+        let deduction = if covered.len() * 2 >= self.password.chars().count() {
+            30
+        } else {
+            20
+        };
+        self.score = self.score.saturating_sub(deduction);
+        self.suggestions.push(format!(
+            "Das Passwort enthält Wörter aus dem Wörterbuch ({}). Verwende zufällige Zeichen oder eine Passphrase aus mehreren unzusammenhängenden Wörtern.",
+            self.dictionary_words.join(", ")
+        ));
+    }
+
     /// Determine the strength of the password based on the score.
     fn determine_strength(&mut self) {
         self.strength = match self.score {
@@ -333,6 +433,9 @@ impl PasswordHealth {
         if !self.has_lowercase {
             self.issues.push(PasswordIssue::NoLowercase);
         }
+        if !self.dictionary_words.is_empty() {
+            self.issues.push(PasswordIssue::DictionaryWord);
+        }
         if self.score <= 20 {
             self.issues.push(PasswordIssue::TooWeak);
         }
@@ -364,12 +467,175 @@ impl PasswordHealth {
                 );
         }
     }
+
+    /// Flag the password as reused only for accounts of one provider.
+    ///
+    /// Unlike a duplicate, the reuse is likely intentional, e.g. through single sign-on.
+    ///
+    /// # Arguments
+    ///
+    /// * `provider` - The provider sharing the password.
+    pub fn set_shared_login(&mut self, provider: &str) {
+        self.issues.push(PasswordIssue::SharedLogin);
+        self.suggestions.push(format!(
+            "Dieses Passwort wird nur für Konten von {} verwendet, etwa per Single Sign-On. Prüfe, ob das beabsichtigt ist.",
+            provider
+        ));
+    }
+
+    /// Flag the password if its expiry date has passed or is close.
+    ///
+    /// # Arguments
+    ///
+    /// * `expires_on` - The last day the password is valid.
+    /// * `today` - The current day.
+    pub fn set_expiry(&mut self, expires_on: NaiveDate, today: NaiveDate) {
+        let days_left = (expires_on - today).num_days();
+        if days_left < 0 {
+            self.issues.push(PasswordIssue::Expired);
+            self.suggestions.push(format!(
+                "Das Passwort ist am {} abgelaufen. Ändere es und setze ein neues Ablaufdatum.",
+                expires_on.format("%d.%m.%Y")
+            ));
+        } else if days_left <= EXPIRY_WARNING_DAYS {
+            self.issues.push(PasswordIssue::ExpiringSoon);
+            self.suggestions.push(format!(
+                "Das Passwort läuft am {} ab. Ändere es rechtzeitig.",
+                expires_on.format("%d.%m.%Y")
+            ));
+        }
+    }
+}
+
+/// The common passwords as sorted fingerprints, 8 bytes per password instead of a string set.
+#[derive(Debug, Default)]
+struct CommonPasswordTable(Vec<u64>);
+
+impl CommonPasswordTable {
+    /// Decode the table the build script generated from the bundled list
+    fn embedded() -> Self {
+        Self(
+            EMBEDDED_COMMON_PASSWORDS
+                .chunks_exact(8)
+                .map(|chunk| u64::from_le_bytes(chunk.try_into().unwrap()))
+                .collect(),
+        )
+    }
+
+    fn contains(&self, password: &str) -> bool {
+        self.0.binary_search(&fingerprint(password)).is_ok()
+    }
+
+    fn len(&self) -> usize {
+        self.0.len()
+    }
+
+    fn is_empty(&self) -> bool {
+        self.0.is_empty()
+    }
+}
+
+pub struct CommonPasswords;
+
+impl CommonPasswords {
+    /// Load the common passwords list used by the health check.
+    ///
+    /// An updated list in the config directory is preferred over the embedded one. A missing
+    /// or broken file keeps the embedded list.
+    ///
+    /// # Arguments
+    ///
+    /// * `config_dir` - The directory the updated list is stored in.
+    ///
+    /// # Returns
+    ///
+    /// The number of common passwords in use.
+    pub fn load(config_dir: &Path) -> usize {
+        let passwords = Self::read(config_dir);
+        let count = passwords.len();
+        *COMMON_PASSWORDS.write().unwrap() = passwords;
+        count
+    }
+
+    /// Store a downloaded common passwords list and use it for the health check.
+    ///
+    /// # Arguments
+    ///
+    /// * `config_dir` - The directory to store the list in.
+    /// * `content` - The list with one password per line.
+    ///
+    /// # Returns
+    ///
+    /// A Result containing the number of common passwords in the new list or an error.
+    ///
+    /// # Errors
+    ///
+    /// If the list is empty, too large or cannot be written.
+    pub fn update(config_dir: &Path, content: &str) -> Result<usize, Box<dyn std::error::Error>> {
+        let passwords = Self::parse(content)?;
+        let count = passwords.len();
+
+        fs::write(Self::path(config_dir), content)?;
+        *COMMON_PASSWORDS.write().unwrap() = passwords;
+        info!("Updated common passwords list with {} entries", count);
+
+        Ok(count)
+    }
+
+    /// Parse a common passwords list with one password per line.
+    ///
+    /// Empty lines and lines starting with `#` are skipped.
+    ///
+    /// # Arguments
+    ///
+    /// * `content` - The list to parse.
+    ///
+    /// # Returns
+    ///
+    /// A Result containing the table of common passwords or an error.
+    ///
+    /// # Errors
+    ///
+    /// If the list contains no passwords or more than `MAX_COMMON_PASSWORDS`.
+    fn parse(content: &str) -> Result<CommonPasswordTable, Box<dyn std::error::Error>> {
+        let passwords = CommonPasswordTable(fingerprints(content));
+
+        if passwords.is_empty() {
+            return Err("Common passwords list is empty".into());
+        }
+        if passwords.len() > MAX_COMMON_PASSWORDS {
+            return Err("Common passwords list is too large".into());
+        }
+
+        Ok(passwords)
+    }
+
+    /// Read the updated list from the config directory, falling back to the embedded list.
+    fn read(config_dir: &Path) -> CommonPasswordTable {
+        let path = Self::path(config_dir);
+        if path.exists() {
+            match fs::read_to_string(&path)
+                .map_err(|e| e.into())
+                .and_then(|content| Self::parse(&content))
+            {
+                Ok(passwords) => return passwords,
+                Err(e) => warn!("Ignoring updated common passwords list: {}", e),
+            }
+        }
+
+        CommonPasswordTable::embedded()
+    }
+
+    fn path(config_dir: &Path) -> PathBuf {
+        config_dir.join(COMMON_PASSWORDS_FILE)
+    }
 }
 
 #[cfg(test)]
 mod tests {
     use super::*;
     use chrono::Utc;
+    use tempfile::TempDir;
 
     #[test]
     fn test_weak_password() {
@@ -471,7 +737,7 @@ mod tests {
             Utc::now(),
         );
         health.analyze().unwrap();
-        health.is_duplicate(&vec!["Test123!@#".to_string(), "Test123!@#".to_string()]);
+        health.is_duplicate(&["Test123!@#".to_string(), "Test123!@#".to_string()]);
 
         assert!(health.issues.contains(&PasswordIssue::Duplicate));
     }
@@ -489,4 +755,113 @@ mod tests {
 
         assert!(health.issues.contains(&PasswordIssue::Outdated));
     }
+
+    #[test]
+    fn test_expiry() {
+        let today = NaiveDate::from_ymd_opt(2026, 10, 15).unwrap();
+        let health = || {
+            PasswordHealth::new(
+                "TestService".to_string(),
+                "TestUser".to_string(),
+                "Test123!@#",
+                Utc::now(),
+            )
+        };
+
+        let mut expired = health();
+        expired.set_expiry(today.pred_opt().unwrap(), today);
+        assert_eq!(expired.issues, vec![PasswordIssue::Expired]);
+
+        let mut soon = health();
+        soon.set_expiry(today + chrono::Duration::days(EXPIRY_WARNING_DAYS), today);
+        assert_eq!(soon.issues, vec![PasswordIssue::ExpiringSoon]);
+
+        let mut valid = health();
+        valid.set_expiry(
+            today + chrono::Duration::days(EXPIRY_WARNING_DAYS + 1),
+            today,
+        );
+        assert!(valid.issues.is_empty());
+        assert!(valid.suggestions.is_empty());
+    }
+
+    #[test]
+    fn test_dictionary_words() {
+        let mut health = PasswordHealth::new(
+            "Test".to_string(),
+            "TestUser".to_string(),
+            "Sommer2024!",
+            Utc::now(),
+        );
+        health.analyze().unwrap();
+
+        assert!(health.issues.contains(&PasswordIssue::DictionaryWord));
+        assert!(health.dictionary_words.contains(&"sommer".to_string()));
+        assert_eq!(*health.get_strength(), PasswordStrength::Weak);
+
+        let mut health = PasswordHealth::new(
+            "Test".to_string(),
+            "TestUser".to_string(),
+            "Dr@chenH3rz!93",
+            Utc::now(),
+        );
+        health.analyze().unwrap();
+
+        assert!(health.dictionary_words.contains(&"herz".to_string()));
+
+        let mut health = PasswordHealth::new(
+            "Test".to_string(),
+            "TestUser".to_string(),
+            "k9#Qm2$vL7@xP4&z",
+            Utc::now(),
+        );
+        health.analyze().unwrap();
+
+        assert!(!health.issues.contains(&PasswordIssue::DictionaryWord));
+    }
+
+    #[test]
+    fn test_parse_common_passwords() {
+        let passwords = CommonPasswords::parse("# comment\r\n123456\r\n\nqwerty\n").unwrap();
+
+        assert_eq!(passwords.len(), 2);
+        assert!(passwords.contains("123456"));
+        assert!(passwords.contains("qwerty"));
+        assert!(CommonPasswords::parse("\n# only a comment\n").is_err());
+    }
+
+    #[test]
+    fn test_read_prefers_updated_list() {
+        let temp = TempDir::new().unwrap();
+        let embedded = CommonPasswords::read(temp.path());
+        assert!(embedded.contains("password"));
+
+        fs::write(temp.path().join(COMMON_PASSWORDS_FILE), "").unwrap();
+        assert_eq!(CommonPasswords::read(temp.path()).len(), embedded.len());
+
+        fs::write(
+            temp.path().join(COMMON_PASSWORDS_FILE),
+            "hunter2\nletmein\n",
+        )
+        .unwrap();
+        let updated = CommonPasswords::read(temp.path());
+        assert_eq!(updated.len(), 2);
+        assert!(updated.contains("hunter2"));
+    }
+
+    #[test]
+    fn test_embedded_common_passwords() {
+        let bundled = include_str!("common_passwords.txt");
+        let embedded = CommonPasswordTable::embedded();
+
+        assert_eq!(
+            embedded.len(),
+            CommonPasswords::parse(bundled).unwrap().len()
+        );
+        assert!(bundled
+            .lines()
+            .filter(|line| !line.is_empty() && !line.starts_with('#'))
+            .all(|line| embedded.contains(line)));
+        assert!(!embedded.contains("k9#Qm2$vL7@xP4&z"));
+    }
 }
@@ -0,0 +1,125 @@
+use serde::Serialize;
+use std::path::Path;
+
+#[derive(Serialize, Clone, Copy, Debug, PartialEq)]
+pub enum CloudProvider {
+    Dropbox,
+    OneDrive,
+    Nextcloud,
+    #[serde(rename = "Google Drive")]
+    GoogleDrive,
+    #[serde(rename = "iCloud Drive")]
+    ICloud,
+}
+
+/// Marker files the sync clients create in the root of a synced folder.
+const SYNC_MARKERS: [(&str, CloudProvider); 4] = [
+    (".dropbox", CloudProvider::Dropbox),
+    (".dropbox.cache", CloudProvider::Dropbox),
+    (".nextcloudsync.log", CloudProvider::Nextcloud),
+    (".owncloudsync.log", CloudProvider::Nextcloud),
+];
+
+/// Detect if a path lies inside a folder that is synced by a cloud storage client.
+///
+/// The heuristics check the folder names the clients use by default and the marker files
+/// they create in the synced folder, so custom sync folders of these clients are detected
+/// as well.
+///
+/// # Arguments
+///
+/// * `path` - The path to check.
+///
+/// # Returns
+///
+/// The detected cloud provider or None if the path does not seem to be synced.
+pub fn detect_cloud_provider(path: &Path) -> Option<CloudProvider> {
+    let by_name = path
+        .components()
+        .filter_map(|component| component.as_os_str().to_str())
+        .find_map(provider_from_folder_name);
+    if by_name.is_some() {
+        return by_name;
+    }
+
+    path.ancestors().find_map(|ancestor| {
+        SYNC_MARKERS
+            .iter()
+            .find(|(marker, _)| ancestor.join(marker).exists())
+            .map(|(_, provider)| *provider)
+    })
+}
+
+/// Map the name of a folder to the cloud provider that uses it as sync folder.
+fn provider_from_folder_name(name: &str) -> Option<CloudProvider> {
+    let name = name.to_lowercase();
+
+    if name == "dropbox" || name.starts_with("dropbox (") {
+        Some(CloudProvider::Dropbox)
+    } else if name == "onedrive" || name.starts_with("onedrive - ") {
+        Some(CloudProvider::OneDrive)
+    } else if name == "nextcloud" || name == "owncloud" {
+        Some(CloudProvider::Nextcloud)
+    } else if name == "google drive" || name == "googledrive" || name == "my drive" {
+        Some(CloudProvider::GoogleDrive)
+    } else if name == "icloud drive" || name == "mobile documents" || name == "iclouddrive" {
+        Some(CloudProvider::ICloud)
+    } else {
+        None
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use std::fs;
+    use std::path::PathBuf;
+    use tempfile::TempDir;
+
+    #[test]
+    fn test_detect_by_folder_name() {
+        let cases = [
+            ("/home/user/Dropbox/vault", Some(CloudProvider::Dropbox)),
+            (
+                "/home/user/Dropbox (Firma)/vault",
+                Some(CloudProvider::Dropbox),
+            ),
+            (
+                "C:/Users/user/OneDrive - Firma/vault",
+                Some(CloudProvider::OneDrive),
+            ),
+            ("/home/user/Nextcloud", Some(CloudProvider::Nextcloud)),
+            (
+                "/Users/user/Library/Mobile Documents/vault",
+                Some(CloudProvider::ICloud),
+            ),
+            ("/home/user/.config/karncrypt", None),
+            ("/home/user/dropbox-notes", None),
+        ];
+
+        for (path, expected) in cases {
+            assert_eq!(
+                detect_cloud_provider(&PathBuf::from(path)),
+                expected,
+                "{}",
+                path
+            );
+        }
+    }
+
+    #[test]
+    fn test_detect_by_marker_file() {
+        let temp = TempDir::new().unwrap();
+        let synced = temp.path().join("cloud");
+        let vault = synced.join("vault");
+        fs::create_dir_all(&vault).unwrap();
+
+        assert_eq!(detect_cloud_provider(&vault), None);
+
+        fs::write(synced.join(".nextcloudsync.log"), "").unwrap();
+        assert_eq!(
+            detect_cloud_provider(&vault),
+            Some(CloudProvider::Nextcloud)
+        );
+    }
+}
@@ -0,0 +1,395 @@
+use base64::engine::general_purpose::URL_SAFE_NO_PAD;
+use base64::Engine;
+use ring::{
+    aead::{self, Nonce},
+    hkdf,
+    rand::{SecureRandom, SystemRandom},
+};
+use x25519_dalek::{PublicKey, StaticSecret};
+
+const MAGIC: &[u8; 4] = b"KCRB";
+/// Version 1 only bound the magic bytes to the payload, version 2 binds the whole header
+/// including the recipient stanzas.
+const LEGACY_VERSION: u8 = 1;
+const VERSION: u8 = 2;
+const PUBLIC_KEY_PREFIX: &str = "kcpub1";
+const SECRET_KEY_PREFIX: &str = "KC-SECRET-KEY-1";
+const WRAP_INFO: &[u8] = b"karncrypt-backup-recipient";
+const WRAPPED_KEY_LEN: usize = 32 + 16;
+
+pub struct RecipientKeyPair {
+    pub public_key: String,
+    pub secret_key: String,
+}
+
+pub struct RecipientEncryption;
+
+impl RecipientKeyPair {
+    /// Generate a new X25519 key pair for backup recipients.
+    ///
+    /// # Returns
+    ///
+    /// A Result containing the encoded key pair or an error.
+    ///
+    /// # Errors
+    ///
+    /// If the system random number generator fails.
+    pub fn generate() -> Result<Self, Box<dyn std::error::Error>> {
+        let secret = StaticSecret::from(random_bytes::<32>()?);
+        let public = PublicKey::from(&secret);
+
+        Ok(Self {
+            public_key: format!("{}{}", PUBLIC_KEY_PREFIX, URL_SAFE_NO_PAD.encode(public)),
+            secret_key: format!(
+                "{}{}",
+                SECRET_KEY_PREFIX,
+                URL_SAFE_NO_PAD.encode(secret.to_bytes())
+            ),
+        })
+    }
+}
+
+impl RecipientEncryption {
+    /// Check if the data was encrypted to backup recipients.
+    ///
+    /// # Arguments
+    ///
+    /// * `data` - The data to check.
+    ///
+    /// # Returns
+    ///
+    /// True if the data starts with the recipient header, false otherwise.
+    pub fn is_encrypted(data: &[u8]) -> bool {
+        data.len() > MAGIC.len() && data.starts_with(MAGIC)
+    }
+
+    /// Validate an encoded recipient public key.
+    ///
+    /// # Arguments
+    ///
+    /// * `public_key` - The encoded public key.
+    ///
+    /// # Returns
+    ///
+    /// A Result containing a unit or an error.
+    ///
+    /// # Errors
+    ///
+    /// If the key has the wrong prefix or length.
+    pub fn validate_public_key(public_key: &str) -> Result<(), Box<dyn std::error::Error>> {
+        Self::parse_public_key(public_key).map(|_| ())
+    }
+
+    /// Encrypt data so that any of the given recipients can decrypt it.
+    ///
+    /// A random file key encrypts the data; the file key itself is wrapped once per
+    /// recipient with a key derived from an ephemeral X25519 exchange. The header with all
+    /// recipient stanzas is authenticated with the data, so stanzas cannot be removed or
+    /// swapped.
+    ///
+    /// # Arguments
+    ///
+    /// * `data` - The data to encrypt.
+    /// * `recipients` - The encoded public keys of the recipients.
+    ///
+    /// # Returns
+    ///
+    /// A Result containing the encrypted data or an error.
+    ///
+    /// # Errors
+    ///
+    /// If no recipient is given, a public key is invalid or the encryption fails.
+    pub fn encrypt(
+        data: &[u8],
+        recipients: &[String],
+    ) -> Result<Vec<u8>, Box<dyn std::error::Error>> {
+        if recipients.is_empty() {
+            return Err("No backup recipients configured".into());
+        }
+        if recipients.len() > u8::MAX as usize {
+            return Err("Too many backup recipients".into());
+        }
+
+        let file_key = random_bytes::<32>()?;
+
+        let mut result = Vec::new();
+        result.extend_from_slice(MAGIC);
+        result.push(VERSION);
+        result.push(recipients.len() as u8);
+
+        for recipient in recipients {
+            let recipient_public = Self::parse_public_key(recipient)?;
+            let ephemeral = StaticSecret::from(random_bytes::<32>()?);
+            let ephemeral_public = PublicKey::from(&ephemeral);
+            let shared = ephemeral.diffie_hellman(&recipient_public);
+
+            let wrap_key = Self::derive_wrap_key(
+                shared.as_bytes(),
+                ephemeral_public.as_bytes(),
+                recipient_public.as_bytes(),
+            )?;
+
+            let mut wrapped = file_key.to_vec();
+            wrap_key
+                .seal_in_place_append_tag(
+                    Nonce::assume_unique_for_key([0u8; 12]),
+                    aead::Aad::empty(),
+                    &mut wrapped,
+                )
+                .map_err(|_| "Failed to wrap backup key")?;
+
+            result.extend_from_slice(ephemeral_public.as_bytes());
+            result.extend_from_slice(&wrapped);
+        }
+
+        let payload_key = aead::LessSafeKey::new(
+            aead::UnboundKey::new(&aead::CHACHA20_POLY1305, &file_key)
+                .map_err(|_| "Failed to create backup key")?,
+        );
+        let nonce_bytes = random_bytes::<12>()?;
+        let mut in_out = data.to_vec();
+        payload_key
+            .seal_in_place_append_tag(
+                Nonce::assume_unique_for_key(nonce_bytes),
+                aead::Aad::from(&result),
+                &mut in_out,
+            )
+            .map_err(|_| "Failed to encrypt backup")?;
+
+        result.extend_from_slice(&nonce_bytes);
+        result.extend_from_slice(&in_out);
+
+        Ok(result)
+    }
+
+    /// Decrypt data that was encrypted to backup recipients.
+    ///
+    /// # Arguments
+    ///
+    /// * `data` - The encrypted data.
+    /// * `secret_key` - The encoded secret key of one of the recipients.
+    ///
+    /// # Returns
+    ///
+    /// A Result containing the decrypted data or an error.
+    ///
+    /// # Errors
+    ///
+    /// If the data is malformed or the secret key does not belong to any recipient.
+    pub fn decrypt(data: &[u8], secret_key: &str) -> Result<Vec<u8>, Box<dyn std::error::Error>> {
+        if !Self::is_encrypted(data) || data.len() < MAGIC.len() + 2 {
+            return Err("Backup is not encrypted to recipients".into());
+        }
+
+        let mut cursor = MAGIC.len();
+        let version = data[cursor];
+        cursor += 1;
+        if version != VERSION && version != LEGACY_VERSION {
+            return Err("Unsupported recipient backup version".into());
+        }
+
+        let recipient_count = data[cursor] as usize;
+        cursor += 1;
+
+        let secret = Self::parse_secret_key(secret_key)?;
+        let own_public = PublicKey::from(&secret);
+
+        let stanzas_len = recipient_count * (32 + WRAPPED_KEY_LEN);
+        if data.len() < cursor + stanzas_len + 12 + 16 {
+            return Err("Invalid recipient backup length".into());
+        }
+
+        let mut file_key = None;
+        for _ in 0..recipient_count {
+            let ephemeral_bytes: [u8; 32] = data[cursor..cursor + 32].try_into()?;
+            cursor += 32;
+            let mut wrapped = data[cursor..cursor + WRAPPED_KEY_LEN].to_vec();
+            cursor += WRAPPED_KEY_LEN;
+
+            if file_key.is_some() {
+                continue;
+            }
+
+            let ephemeral_public = PublicKey::from(ephemeral_bytes);
+            let shared = secret.diffie_hellman(&ephemeral_public);
+            let wrap_key = Self::derive_wrap_key(
+                shared.as_bytes(),
+                ephemeral_public.as_bytes(),
+                own_public.as_bytes(),
+            )?;
+
+            if let Ok(key) = wrap_key.open_in_place(
+                Nonce::assume_unique_for_key([0u8; 12]),
+                aead::Aad::empty(),
+                &mut wrapped,
+            ) {
+                file_key = Some(key.to_vec());
+            }
+        }
+
+        let file_key = file_key.ok_or("Secret key does not match any backup recipient")?;
+        let header = match version {
+            LEGACY_VERSION => &MAGIC[..],
+            _ => &data[..cursor],
+        };
+
+        let nonce_bytes: [u8; 12] = data[cursor..cursor + 12].try_into()?;
+        cursor += 12;
+
+        let payload_key = aead::LessSafeKey::new(
+            aead::UnboundKey::new(&aead::CHACHA20_POLY1305, &file_key)
+                .map_err(|_| "Failed to create backup key")?,
+        );
+        let mut in_out = data[cursor..].to_vec();
+        let plain = payload_key
+            .open_in_place(
+                Nonce::assume_unique_for_key(nonce_bytes),
+                aead::Aad::from(header),
+                &mut in_out,
+            )
+            .map_err(|_| "Failed to decrypt backup")?;
+
+        Ok(plain.to_vec())
+    }
+
+    fn derive_wrap_key(
+        shared: &[u8; 32],
+        ephemeral_public: &[u8; 32],
+        recipient_public: &[u8; 32],
+    ) -> Result<aead::LessSafeKey, Box<dyn std::error::Error>> {
+        let mut salt_bytes = Vec::with_capacity(64);
+        salt_bytes.extend_from_slice(ephemeral_public);
+        salt_bytes.extend_from_slice(recipient_public);
+
+        let salt = hkdf::Salt::new(hkdf::HKDF_SHA256, &salt_bytes);
+        let info = [WRAP_INFO];
+        let prk = salt.extract(shared);
+        let okm = prk
+            .expand(&info, &aead::CHACHA20_POLY1305)
+            .map_err(|_| "Failed to derive wrap key")?;
+
+        Ok(aead::LessSafeKey::new(aead::UnboundKey::from(okm)))
+    }
+
+    fn parse_public_key(public_key: &str) -> Result<PublicKey, Box<dyn std::error::Error>> {
+        let encoded = public_key
+            .trim()
+            .strip_prefix(PUBLIC_KEY_PREFIX)
+            .ok_or("Invalid public key prefix")?;
+        let bytes: [u8; 32] = URL_SAFE_NO_PAD
+            .decode(encoded)?
+            .try_into()
+            .map_err(|_| "Invalid public key length")?;
+
+        Ok(PublicKey::from(bytes))
+    }
+
+    fn parse_secret_key(secret_key: &str) -> Result<StaticSecret, Box<dyn std::error::Error>> {
+        let encoded = secret_key
+            .trim()
+            .strip_prefix(SECRET_KEY_PREFIX)
+            .ok_or("Invalid secret key prefix")?;
+        let bytes: [u8; 32] = URL_SAFE_NO_PAD
+            .decode(encoded)?
+            .try_into()
+            .map_err(|_| "Invalid secret key length")?;
+
+        Ok(StaticSecret::from(bytes))
+    }
+}
+
+fn random_bytes<const N: usize>() -> Result<[u8; N], Box<dyn std::error::Error>> {
+    let mut bytes = [0u8; N];
+    SystemRandom::new()
+        .fill(&mut bytes)
+        .map_err(|_| "Failed to generate random bytes")?;
+    Ok(bytes)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_key_generation() {
+        let pair = RecipientKeyPair::generate().unwrap();
+
+        assert!(pair.public_key.starts_with(PUBLIC_KEY_PREFIX));
+        assert!(pair.secret_key.starts_with(SECRET_KEY_PREFIX));
+        assert!(RecipientEncryption::validate_public_key(&pair.public_key).is_ok());
+        assert!(RecipientEncryption::validate_public_key("kcpub1invalid").is_err());
+        assert!(RecipientEncryption::validate_public_key(&pair.secret_key).is_err());
+    }
+
+    #[test]
+    fn test_encrypt_decrypt_multiple_recipients() {
+        let alice = RecipientKeyPair::generate().unwrap();
+        let bob = RecipientKeyPair::generate().unwrap();
+        let data = b"backup data";
+
+        let encrypted =
+            RecipientEncryption::encrypt(data, &[alice.public_key.clone(), bob.public_key.clone()])
+                .unwrap();
+
+        assert!(RecipientEncryption::is_encrypted(&encrypted));
+        assert_eq!(
+            RecipientEncryption::decrypt(&encrypted, &alice.secret_key).unwrap(),
+            data
+        );
+        assert_eq!(
+            RecipientEncryption::decrypt(&encrypted, &bob.secret_key).unwrap(),
+            data
+        );
+    }
+
+    #[test]
+    fn test_decrypt_wrong_key() {
+        let alice = RecipientKeyPair::generate().unwrap();
+        let mallory = RecipientKeyPair::generate().unwrap();
+
+        let encrypted =
+            RecipientEncryption::encrypt(b"backup data", std::slice::from_ref(&alice.public_key))
+                .unwrap();
+
+        assert!(RecipientEncryption::decrypt(&encrypted, &mallory.secret_key).is_err());
+    }
+
+    #[test]
+    fn test_stanzas_are_authenticated() {
+        let alice = RecipientKeyPair::generate().unwrap();
+        let bob = RecipientKeyPair::generate().unwrap();
+        let recipients = [alice.public_key.clone(), bob.public_key.clone()];
+        let stanza_len = 32 + WRAPPED_KEY_LEN;
+        let header_len = MAGIC.len() + 2;
+
+        let encrypted = RecipientEncryption::encrypt(b"backup data", &recipients).unwrap();
+
+        let mut removed = encrypted.clone();
+        removed[MAGIC.len() + 1] = 1;
+        removed.drain(header_len + stanza_len..header_len + 2 * stanza_len);
+        assert!(RecipientEncryption::decrypt(&removed, &alice.secret_key).is_err());
+
+        let other = RecipientEncryption::encrypt(b"other data", &recipients).unwrap();
+        let mut swapped = encrypted.clone();
+        swapped[header_len + stanza_len..header_len + 2 * stanza_len]
+            .copy_from_slice(&other[header_len + stanza_len..header_len + 2 * stanza_len]);
+        assert!(RecipientEncryption::decrypt(&swapped, &alice.secret_key).is_err());
+        assert!(RecipientEncryption::decrypt(&encrypted, &alice.secret_key).is_ok());
+    }
+
+    #[test]
+    fn test_invalid_input() {
+        let alice = RecipientKeyPair::generate().unwrap();
+
+        assert!(RecipientEncryption::encrypt(b"data", &[]).is_err());
+        assert!(!RecipientEncryption::is_encrypted(&[1, 0, 0, 0, 0]));
+        assert!(RecipientEncryption::decrypt(b"KCRB\x01\x05", &alice.secret_key).is_err());
+
+        let mut encrypted =
+            RecipientEncryption::encrypt(b"backup data", std::slice::from_ref(&alice.public_key))
+                .unwrap();
+        let last = encrypted.len() - 1;
+        encrypted[last] ^= 0xFF;
+        assert!(RecipientEncryption::decrypt(&encrypted, &alice.secret_key).is_err());
+    }
+}
@@ -0,0 +1,158 @@
+use chrono::Utc;
+use serde::{Deserialize, Serialize};
+
+/// Number of unused codes at which the user is warned to generate new ones.
+const LOW_REMAINING: usize = 2;
+
+#[derive(Serialize, Deserialize, Clone, Debug, PartialEq)]
+pub struct RecoveryCode {
+    pub code: String,
+    pub used_at: Option<String>,
+}
+
+#[derive(Serialize, Debug, PartialEq)]
+pub struct RecoveryCodes {
+    pub codes: Vec<RecoveryCode>,
+    pub remaining: usize,
+    pub low: bool,
+}
+
+/// The number of used and unused recovery codes, without the codes themselves.
+#[derive(Serialize, Debug, PartialEq)]
+pub struct RecoveryCodeCount {
+    pub used: usize,
+    pub remaining: usize,
+    pub low: bool,
+}
+
+impl RecoveryCodes {
+    /// Create a new RecoveryCodes list from stored codes
+    ///
+    /// # Arguments
+    ///
+    /// * `codes` - The stored recovery codes
+    ///
+    /// # Returns
+    ///
+    /// The recovery codes with the number of unused codes
+    pub fn new(codes: Vec<RecoveryCode>) -> Self {
+        let remaining = codes.iter().filter(|c| c.used_at.is_none()).count();
+        Self {
+            low: !codes.is_empty() && remaining <= LOW_REMAINING,
+            codes,
+            remaining,
+        }
+    }
+
+    /// Parse recovery codes as they are shown by most services
+    ///
+    /// Codes are separated by line breaks or commas. Spaces inside a code are kept,
+    /// because some services group the characters of a code. Duplicates are skipped.
+    ///
+    /// # Arguments
+    ///
+    /// * `text` - The pasted recovery codes
+    ///
+    /// # Returns
+    ///
+    /// The parsed, unused recovery codes
+    pub fn parse(text: &str) -> Self {
+        let mut codes: Vec<RecoveryCode> = Vec::new();
+        for code in text.split(['\n', ',']).map(str::trim) {
+            if !code.is_empty() && !codes.iter().any(|c| c.code == code) {
+                codes.push(RecoveryCode {
+                    code: code.to_string(),
+                    used_at: None,
+                });
+            }
+        }
+
+        Self::new(codes)
+    }
+
+    /// Mark a recovery code as used or unused
+    ///
+    /// # Arguments
+    ///
+    /// * `index` - The position of the code in the list
+    /// * `used` - Whether the code was used
+    ///
+    /// # Errors
+    ///
+    /// Returns an error if there is no code at the given position
+    pub fn set_used(&mut self, index: usize, used: bool) -> Result<(), Box<dyn std::error::Error>> {
+        let code = self.codes.get_mut(index).ok_or("Recovery code not found")?;
+        code.used_at = match (&code.used_at, used) {
+            (Some(used_at), true) => Some(used_at.clone()),
+            (None, true) => Some(Utc::now().to_rfc3339()),
+            (_, false) => None,
+        };
+
+        *self = Self::new(std::mem::take(&mut self.codes));
+        Ok(())
+    }
+
+    /// Count the used and unused codes
+    ///
+    /// # Returns
+    ///
+    /// The number of used and unused codes and whether few are left
+    pub fn count(&self) -> RecoveryCodeCount {
+        RecoveryCodeCount {
+            used: self.codes.len() - self.remaining,
+            remaining: self.remaining,
+            low: self.low,
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_parse_codes() {
+        let codes = RecoveryCodes::parse("abcd-1234\n  efgh 5678 ,ijkl-9012\n\nabcd-1234\r\n");
+
+        let parsed: Vec<&str> = codes.codes.iter().map(|c| c.code.as_str()).collect();
+        assert_eq!(parsed, vec!["abcd-1234", "efgh 5678", "ijkl-9012"]);
+        assert_eq!(codes.remaining, 3);
+        assert!(!codes.low);
+
+        let empty = RecoveryCodes::parse(" \n, ");
+        assert!(empty.codes.is_empty());
+        assert!(!empty.low);
+    }
+
+    #[test]
+    fn test_mark_used() {
+        let mut codes = RecoveryCodes::parse("a\nb\nc\nd");
+
+        codes.set_used(0, true).unwrap();
+        let used_at = codes.codes[0].used_at.clone();
+        assert!(used_at.is_some());
+        assert_eq!(codes.remaining, 3);
+        assert!(!codes.low);
+
+        codes.set_used(0, true).unwrap();
+        assert_eq!(codes.codes[0].used_at, used_at);
+
+        codes.set_used(1, true).unwrap();
+        assert_eq!(codes.remaining, 2);
+        assert!(codes.low);
+        assert_eq!(
+            codes.count(),
+            RecoveryCodeCount {
+                used: 2,
+                remaining: 2,
+                low: true
+            }
+        );
+
+        codes.set_used(1, false).unwrap();
+        assert_eq!(codes.remaining, 3);
+        assert!(codes.codes[1].used_at.is_none());
+
+        assert!(codes.set_used(4, true).is_err());
+    }
+}
@@ -0,0 +1,534 @@
+use base64::engine::general_purpose::URL_SAFE_NO_PAD;
+use base64::Engine;
+use log::error;
+use ring::hmac;
+use ring::rand::{SecureRandom, SystemRandom};
+use serde::{Deserialize, Serialize};
+use serde_json::Value;
+use std::fs;
+use std::io::{BufRead, BufReader, Read, Write};
+use std::net::{Ipv4Addr, SocketAddr, TcpListener, TcpStream};
+use std::path::Path;
+use std::sync::atomic::{AtomicUsize, Ordering};
+use std::sync::Arc;
+use std::thread;
+
+const TOKEN_FILE: &str = ".rpc_token";
+const JSONRPC_VERSION: &str = "2.0";
+/// The maximum size of a request line, longer requests close the connection.
+const MAX_REQUEST_SIZE: u64 = 1024 * 1024;
+/// The maximum number of connections served at the same time, further connections are
+/// answered with an error and closed.
+const MAX_CONNECTIONS: usize = 8;
+
+pub const PARSE_ERROR: i32 = -32700;
+pub const INVALID_REQUEST: i32 = -32600;
+pub const METHOD_NOT_FOUND: i32 = -32601;
+pub const INVALID_PARAMS: i32 = -32602;
+pub const SERVER_ERROR: i32 = -32000;
+pub const UNAUTHORIZED: i32 = -32001;
+
+#[derive(Deserialize)]
+struct RpcRequest {
+    jsonrpc: String,
+    #[serde(default)]
+    id: Value,
+    method: String,
+    #[serde(default)]
+    params: Value,
+    token: Option<String>,
+}
+
+#[derive(Serialize)]
+struct RpcResponse {
+    jsonrpc: &'static str,
+    id: Value,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    result: Option<Value>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    error: Option<RpcError>,
+}
+
+#[derive(Serialize, Debug, PartialEq)]
+pub struct RpcError {
+    pub code: i32,
+    pub message: String,
+}
+
+pub type RpcHandler = dyn Fn(&str, Value) -> Result<Value, RpcError> + Send + Sync;
+
+pub struct RpcServer {
+    addr: SocketAddr,
+}
+
+/// A connection to the JSON-RPC server of the running app.
+pub struct RpcClient {
+    reader: BufReader<TcpStream>,
+    writer: TcpStream,
+    token: String,
+}
+
+impl RpcError {
+    /// Create a new JSON-RPC error
+    ///
+    /// # Arguments
+    ///
+    /// * `code` - The JSON-RPC error code
+    /// * `message` - A short description of the error
+    ///
+    /// # Returns
+    ///
+    /// A new JSON-RPC error
+    pub fn new(code: i32, message: impl Into<String>) -> Self {
+        Self {
+            code,
+            message: message.into(),
+        }
+    }
+}
+
+impl RpcServer {
+    /// Load the token clients need to authenticate, creating it on first use
+    ///
+    /// The token file is only readable by the current user.
+    ///
+    /// # Arguments
+    ///
+    /// * `config_dir` - The directory containing the configuration files
+    ///
+    /// # Returns
+    ///
+    /// The token clients have to send with every request
+    ///
+    /// # Errors
+    ///
+    /// Returns an error if the token cannot be read or written
+    pub fn load_or_create_token(config_dir: &Path) -> Result<String, Box<dyn std::error::Error>> {
+        let path = config_dir.join(TOKEN_FILE);
+        if path.exists() {
+            #[cfg(unix)]
+            {
+                use std::os::unix::fs::PermissionsExt;
+                fs::set_permissions(&path, fs::Permissions::from_mode(0o600))?;
+            }
+            let token = fs::read_to_string(&path)?.trim().to_string();
+            if !token.is_empty() {
+                return Ok(token);
+            }
+        }
+
+        let mut bytes = [0u8; 32];
+        SystemRandom::new()
+            .fill(&mut bytes)
+            .map_err(|_| "Failed to generate RPC token")?;
+        let token = URL_SAFE_NO_PAD.encode(bytes);
+
+        let mut options = fs::OpenOptions::new();
+        options.write(true).create(true).truncate(true);
+        #[cfg(unix)]
+        {
+            use std::os::unix::fs::OpenOptionsExt;
+            options.mode(0o600);
+        }
+        options.open(&path)?.write_all(token.as_bytes())?;
+
+        Ok(token)
+    }
+
+    /// Start a JSON-RPC server on the loopback interface
+    ///
+    /// Every connection is served on its own thread, at most 8 at the same time. Requests
+    /// and responses are single lines of JSON, requests without the correct token are
+    /// rejected. A request longer than 1 MiB is answered with an error and closes the
+    /// connection.
+    ///
+    /// # Arguments
+    ///
+    /// * `port` - The port to listen on, 0 picks a free port
+    /// * `token` - The token clients have to send with every request
+    /// * `handler` - Executes a method with its parameters
+    ///
+    /// # Returns
+    ///
+    /// The running server
+    ///
+    /// # Errors
+    ///
+    /// Returns an error if the port cannot be bound
+    pub fn start(
+        port: u16,
+        token: String,
+        handler: Arc<RpcHandler>,
+    ) -> Result<Self, Box<dyn std::error::Error>> {
+        let listener = TcpListener::bind((Ipv4Addr::LOCALHOST, port))?;
+        let addr = listener.local_addr()?;
+
+        let token = Arc::new(token);
+        let active = Arc::new(AtomicUsize::new(0));
+        thread::spawn(move || {
+            for stream in listener.incoming() {
+                match stream {
+                    Ok(mut stream) => {
+                        let Some(slot) = ConnectionSlot::acquire(&active) else {
+                            let response = Self::response(
+                                Value::Null,
+                                Err(RpcError::new(SERVER_ERROR, "Too many connections")),
+                            );
+                            let _ = writeln!(stream, "{}", response);
+                            continue;
+                        };
+                        let token = token.clone();
+                        let handler = handler.clone();
+                        thread::spawn(move || {
+                            Self::serve(stream, &token, handler.as_ref());
+                            drop(slot);
+                        });
+                    }
+                    Err(e) => error!("Failed to accept RPC connection: {}", e),
+                }
+            }
+        });
+
+        Ok(Self { addr })
+    }
+
+    /// Get the address the server listens on
+    ///
+    /// # Returns
+    ///
+    /// The local address of the server
+    pub fn addr(&self) -> SocketAddr {
+        self.addr
+    }
+
+    /// Answer the requests of a connection until it is closed
+    fn serve(stream: TcpStream, token: &str, handler: &RpcHandler) {
+        let Ok(mut writer) = stream.try_clone() else {
+            return;
+        };
+
+        let mut reader = BufReader::new(stream);
+        loop {
+            let mut line = String::new();
+            match (&mut reader).take(MAX_REQUEST_SIZE).read_line(&mut line) {
+                Ok(0) | Err(_) => break,
+                Ok(read) if read as u64 == MAX_REQUEST_SIZE && !line.ends_with('\n') => {
+                    let response = Self::response(
+                        Value::Null,
+                        Err(RpcError::new(INVALID_REQUEST, "Request too large")),
+                    );
+                    let _ = writeln!(writer, "{}", response);
+                    break;
+                }
+                Ok(_) => {}
+            }
+            if line.trim().is_empty() {
+                continue;
+            }
+
+            let response = Self::handle_line(&line, token, handler);
+            if writeln!(writer, "{}", response).is_err() {
+                break;
+            }
+        }
+    }
+
+    /// Parse a request, check its token and run it
+    ///
+    /// # Arguments
+    ///
+    /// * `line` - The raw JSON-RPC request
+    /// * `token` - The token the request has to contain
+    /// * `handler` - Executes a method with its parameters
+    ///
+    /// # Returns
+    ///
+    /// The serialized JSON-RPC response
+    fn handle_line(line: &str, token: &str, handler: &RpcHandler) -> String {
+        let (id, result) = match serde_json::from_str::<RpcRequest>(line) {
+            Err(e) => (Value::Null, Err(RpcError::new(PARSE_ERROR, e.to_string()))),
+            Ok(request) if request.jsonrpc != JSONRPC_VERSION => (
+                request.id,
+                Err(RpcError::new(
+                    INVALID_REQUEST,
+                    "Unsupported JSON-RPC version",
+                )),
+            ),
+            Ok(request) if !Self::token_matches(request.token.as_deref(), token) => (
+                request.id,
+                Err(RpcError::new(UNAUTHORIZED, "Invalid token")),
+            ),
+            Ok(request) => (request.id, handler(&request.method, request.params)),
+        };
+
+        Self::response(id, result)
+    }
+
+    /// Compare the token of a request in constant time
+    ///
+    /// The expected token is signed with a random key and `hmac::verify` checks the given
+    /// token against that tag, so the timing does not reveal how many bytes were correct.
+    ///
+    /// # Arguments
+    ///
+    /// * `given` - The token sent with the request
+    /// * `token` - The token the request has to contain
+    ///
+    /// # Returns
+    ///
+    /// Whether the tokens are equal
+    fn token_matches(given: Option<&str>, token: &str) -> bool {
+        let Some(given) = given else {
+            return false;
+        };
+        let Ok(key) = hmac::Key::generate(hmac::HMAC_SHA256, &SystemRandom::new()) else {
+            return false;
+        };
+        let tag = hmac::sign(&key, token.as_bytes());
+        hmac::verify(&key, given.as_bytes(), tag.as_ref()).is_ok()
+    }
+
+    /// Serialize the response to a request
+    ///
+    /// # Arguments
+    ///
+    /// * `id` - The ID of the request
+    /// * `result` - The result or the error of the request
+    ///
+    /// # Returns
+    ///
+    /// The serialized JSON-RPC response
+    fn response(id: Value, result: Result<Value, RpcError>) -> String {
+        let (result, error) = match result {
+            Ok(result) => (Some(result), None),
+            Err(error) => (None, Some(error)),
+        };
+
+        serde_json::to_string(&RpcResponse {
+            jsonrpc: JSONRPC_VERSION,
+            id,
+            result,
+            error,
+        })
+        .unwrap_or_default()
+    }
+}
+
+/// A connection counted against `MAX_CONNECTIONS`, released when dropped.
+struct ConnectionSlot(Arc<AtomicUsize>);
+
+impl ConnectionSlot {
+    /// Take a slot if fewer than `MAX_CONNECTIONS` connections are served
+    fn acquire(active: &Arc<AtomicUsize>) -> Option<Self> {
+        active
+            .fetch_update(Ordering::AcqRel, Ordering::Acquire, |count| {
+                (count < MAX_CONNECTIONS).then_some(count + 1)
+            })
+            .ok()
+            .map(|_| Self(active.clone()))
+    }
+}
+
+impl Drop for ConnectionSlot {
+    fn drop(&mut self) {
+        self.0.fetch_sub(1, Ordering::AcqRel);
+    }
+}
+
+impl RpcClient {
+    /// Connect to the JSON-RPC server on the loopback interface
+    ///
+    /// # Arguments
+    ///
+    /// * `port` - The port the server listens on
+    /// * `token` - The token sent with every request
+    ///
+    /// # Returns
+    ///
+    /// The connected client
+    ///
+    /// # Errors
+    ///
+    /// Returns an error if no server listens on the port
+    pub fn connect(port: u16, token: String) -> std::io::Result<Self> {
+        let writer = TcpStream::connect((Ipv4Addr::LOCALHOST, port))?;
+        let reader = BufReader::new(writer.try_clone()?);
+
+        Ok(Self {
+            reader,
+            writer,
+            token,
+        })
+    }
+
+    /// Call a method and wait for its response
+    ///
+    /// # Arguments
+    ///
+    /// * `id` - The ID of the request, returned in the response
+    /// * `method` - The name of the method
+    /// * `params` - The parameters of the method
+    ///
+    /// # Returns
+    ///
+    /// The JSON-RPC response with either a result or an error
+    ///
+    /// # Errors
+    ///
+    /// Returns an error if the connection is closed or the response is no valid JSON
+    pub fn call(&mut self, id: Value, method: &str, params: Value) -> std::io::Result<Value> {
+        let request = serde_json::json!({
+            "jsonrpc": JSONRPC_VERSION,
+            "id": id,
+            "method": method,
+            "params": params,
+            "token": self.token,
+        });
+        writeln!(self.writer, "{}", request)?;
+
+        let mut line = String::new();
+        if self.reader.read_line(&mut line)? == 0 {
+            return Err(std::io::ErrorKind::UnexpectedEof.into());
+        }
+        Ok(serde_json::from_str(&line)?)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use serde_json::json;
+    use tempfile::TempDir;
+
+    fn echo_handler() -> Arc<RpcHandler> {
+        Arc::new(|method: &str, params: Value| match method {
+            "echo" => Ok(params),
+            _ => Err(RpcError::new(METHOD_NOT_FOUND, "Unknown method")),
+        })
+    }
+
+    #[test]
+    fn test_token_is_persisted() {
+        let temp = TempDir::new().unwrap();
+        let token = RpcServer::load_or_create_token(temp.path()).unwrap();
+
+        assert!(token.len() >= 43);
+        assert_eq!(RpcServer::load_or_create_token(temp.path()).unwrap(), token);
+
+        #[cfg(unix)]
+        {
+            use std::os::unix::fs::PermissionsExt;
+            let mode = fs::metadata(temp.path().join(TOKEN_FILE))
+                .unwrap()
+                .permissions()
+                .mode();
+            assert_eq!(mode & 0o777, 0o600);
+        }
+    }
+
+    #[test]
+    fn test_handle_line() {
+        let handler = echo_handler();
+        let respond = |line: &str| -> Value {
+            serde_json::from_str(&RpcServer::handle_line(line, "secret", handler.as_ref())).unwrap()
+        };
+
+        let response = respond(
+            r#"{"jsonrpc":"2.0","id":1,"method":"echo","params":{"a":1},"token":"secret"}"#,
+        );
+        assert_eq!(
+            response,
+            json!({"jsonrpc": "2.0", "id": 1, "result": {"a": 1}})
+        );
+
+        let response = respond(r#"{"jsonrpc":"2.0","id":2,"method":"echo","token":"wrong"}"#);
+        assert_eq!(response["error"]["code"], UNAUTHORIZED);
+
+        let response = respond(r#"{"jsonrpc":"2.0","id":2,"method":"echo"}"#);
+        assert_eq!(response["error"]["code"], UNAUTHORIZED);
+
+        let response = respond(r#"{"jsonrpc":"2.0","id":3,"method":"missing","token":"secret"}"#);
+        assert_eq!(response["error"]["code"], METHOD_NOT_FOUND);
+
+        let response = respond("{not json");
+        assert_eq!(response["error"]["code"], PARSE_ERROR);
+        assert_eq!(response["id"], Value::Null);
+    }
+
+    #[test]
+    fn test_server_roundtrip() {
+        let server = RpcServer::start(0, "secret".to_string(), echo_handler()).unwrap();
+        assert!(server.addr().ip().is_loopback());
+
+        let mut stream = TcpStream::connect(server.addr()).unwrap();
+        writeln!(
+            stream,
+            r#"{{"jsonrpc":"2.0","id":7,"method":"echo","params":[1,2],"token":"secret"}}"#
+        )
+        .unwrap();
+
+        let mut line = String::new();
+        BufReader::new(stream).read_line(&mut line).unwrap();
+        let response: Value = serde_json::from_str(&line).unwrap();
+        assert_eq!(response["id"], 7);
+        assert_eq!(response["result"], json!([1, 2]));
+    }
+
+    #[test]
+    fn test_server_limits_connections() {
+        let server = RpcServer::start(0, "secret".to_string(), echo_handler()).unwrap();
+        let request = r#"{"jsonrpc":"2.0","id":1,"method":"echo","token":"secret"}"#;
+
+        let mut open = Vec::new();
+        for _ in 0..MAX_CONNECTIONS {
+            let mut stream = TcpStream::connect(server.addr()).unwrap();
+            writeln!(stream, "{}", request).unwrap();
+            let mut reader = BufReader::new(stream);
+            let mut line = String::new();
+            reader.read_line(&mut line).unwrap();
+            assert!(line.contains("\"result\""));
+            open.push(reader);
+        }
+
+        let stream = TcpStream::connect(server.addr()).unwrap();
+        let mut line = String::new();
+        BufReader::new(stream).read_line(&mut line).unwrap();
+        let response: Value = serde_json::from_str(&line).unwrap();
+        assert_eq!(response["error"]["code"], SERVER_ERROR);
+    }
+
+    #[test]
+    fn test_server_rejects_oversized_request() {
+        let server = RpcServer::start(0, "secret".to_string(), echo_handler()).unwrap();
+
+        let mut stream = TcpStream::connect(server.addr()).unwrap();
+        stream
+            .write_all(&vec![b'a'; MAX_REQUEST_SIZE as usize])
+            .unwrap();
+
+        let mut reader = BufReader::new(stream);
+        let mut line = String::new();
+        reader.read_line(&mut line).unwrap();
+        let response: Value = serde_json::from_str(&line).unwrap();
+        assert_eq!(response["error"]["code"], INVALID_REQUEST);
+
+        line.clear();
+        assert_eq!(reader.read_line(&mut line).unwrap(), 0);
+    }
+
+    #[test]
+    fn test_client_roundtrip() {
+        let server = RpcServer::start(0, "secret".to_string(), echo_handler()).unwrap();
+        let port = server.addr().port();
+
+        let mut client = RpcClient::connect(port, "secret".to_string()).unwrap();
+        let response = client.call(json!(1), "echo", json!({"a": 1})).unwrap();
+        assert_eq!(response["result"], json!({"a": 1}));
+        let response = client.call(json!(2), "missing", Value::Null).unwrap();
+        assert_eq!(response["error"]["code"], METHOD_NOT_FOUND);
+
+        let mut client = RpcClient::connect(port, "wrong".to_string()).unwrap();
+        let response = client.call(json!(3), "echo", Value::Null).unwrap();
+        assert_eq!(response["error"]["code"], UNAUTHORIZED);
+    }
+}
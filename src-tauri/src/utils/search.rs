@@ -0,0 +1,373 @@
+use regex::{Regex, RegexBuilder};
+use serde::{Deserialize, Serialize};
+
+use super::collation::fold;
+use super::{Collation, EntrySummary, PasswordEntry};
+
+/// The longest regex or glob pattern that is accepted.
+const MAX_PATTERN_LENGTH: usize = 256;
+/// The memory a compiled pattern may use, keeps pathological patterns from exhausting memory.
+const PATTERN_SIZE_LIMIT: usize = 1 << 20;
+/// The fuzzy score of a character that directly follows the previous match.
+const FUZZY_CONSECUTIVE_BONUS: u32 = 4;
+/// The fuzzy score of a character that starts a word.
+const FUZZY_WORD_START_BONUS: u32 = 6;
+
+/// How the search query is interpreted.
+#[derive(Deserialize, Clone, Copy, Debug, Default, PartialEq)]
+#[serde(rename_all = "snake_case")]
+pub enum SearchMode {
+    /// The query is searched as plain text.
+    #[default]
+    Text,
+    /// The query is a regular expression.
+    Regex,
+    /// The query is a glob pattern that has to match a whole field, e.g. `*bank*`.
+    Glob,
+}
+
+/// A compiled search query.
+pub enum SearchQuery {
+    Text(String),
+    Pattern(Regex),
+}
+
+/// A field of a password entry that is searched.
+#[derive(Serialize, Clone, Copy, Debug, PartialEq)]
+#[serde(rename_all = "snake_case")]
+pub enum SearchField {
+    Service,
+    Username,
+    Url,
+    Notes,
+}
+
+/// The position of a search hit in a field, counted in characters.
+#[derive(Serialize, Clone, Debug, PartialEq)]
+pub struct SearchMatch {
+    pub field: SearchField,
+    pub start: usize,
+    pub end: usize,
+}
+
+/// A password entry that matched a search together with the positions of the hits.
+#[derive(Serialize, Clone)]
+pub struct SearchResult {
+    pub entry: EntrySummary,
+    pub matches: Vec<SearchMatch>,
+}
+
+impl SearchQuery {
+    /// Compile a search query
+    ///
+    /// Patterns are matched case-insensitively. The regex engine runs in linear time, the
+    /// pattern length and the size of the compiled pattern are limited on top of that.
+    ///
+    /// # Arguments
+    ///
+    /// * `query` - The text or pattern to search for
+    /// * `mode` - How the query is interpreted
+    ///
+    /// # Returns
+    ///
+    /// The compiled search query
+    ///
+    /// # Errors
+    ///
+    /// Returns an error if the pattern is too long or invalid
+    pub fn new(query: &str, mode: SearchMode) -> Result<Self, Box<dyn std::error::Error>> {
+        let pattern = match mode {
+            SearchMode::Text => return Ok(Self::Text(query.to_string())),
+            SearchMode::Regex => query.to_string(),
+            SearchMode::Glob => glob_to_regex(query.trim()),
+        };
+        if query.len() > MAX_PATTERN_LENGTH {
+            return Err(format!(
+                "The pattern is longer than {} characters",
+                MAX_PATTERN_LENGTH
+            )
+            .into());
+        }
+
+        let regex = RegexBuilder::new(&pattern)
+            .case_insensitive(true)
+            .size_limit(PATTERN_SIZE_LIMIT)
+            .dfa_size_limit(PATTERN_SIZE_LIMIT)
+            .build()
+            .map_err(|e| format!("Invalid pattern: {}", e))?;
+
+        Ok(Self::Pattern(regex))
+    }
+
+    /// Find all hits of the query in a text
+    ///
+    /// # Arguments
+    ///
+    /// * `text` - The text to search
+    /// * `collation` - Decides which letters match in a text query
+    ///
+    /// # Returns
+    ///
+    /// The character ranges of the hits
+    fn find_in(&self, text: &str, collation: &Collation) -> Vec<(usize, usize)> {
+        match self {
+            Self::Text(query) => find_in(text, query, collation),
+            Self::Pattern(regex) => regex
+                .find_iter(text)
+                .filter(|m| !m.is_empty())
+                .map(|m| {
+                    let start = text[..m.start()].chars().count();
+                    (start, start + m.as_str().chars().count())
+                })
+                .collect(),
+        }
+    }
+}
+
+impl SearchResult {
+    /// Search a password entry for a query
+    ///
+    /// The query is matched case-insensitively against the service, username, URL and notes.
+    /// Text queries also ignore the accents the locale does not treat as separate letters.
+    ///
+    /// # Arguments
+    ///
+    /// * `entry` - The password entry to search
+    /// * `query` - The compiled query to search for
+    /// * `collation` - The collation of the configured locale
+    ///
+    /// # Returns
+    ///
+    /// The search result or None if the entry does not match
+    pub fn find(entry: &PasswordEntry, query: &SearchQuery, collation: &Collation) -> Option<Self> {
+        let fields = [
+            (SearchField::Service, &entry.service),
+            (SearchField::Username, &entry.username),
+            (SearchField::Url, &entry.url),
+            (SearchField::Notes, &entry.notes),
+        ];
+
+        let matches: Vec<SearchMatch> = fields
+            .into_iter()
+            .flat_map(|(field, text)| {
+                query
+                    .find_in(text, collation)
+                    .into_iter()
+                    .map(move |(start, end)| SearchMatch { field, start, end })
+            })
+            .collect();
+
+        (!matches.is_empty()).then(|| Self {
+            entry: EntrySummary::from(entry),
+            matches,
+        })
+    }
+}
+
+/// Find all non-overlapping occurrences of a query in a text, comparing letter by letter.
+///
+/// Offsets are counted in characters, so the frontend can highlight them without knowing
+/// the byte layout of the text.
+fn find_in(text: &str, query: &str, collation: &Collation) -> Vec<(usize, usize)> {
+    let text: Vec<char> = text.chars().map(fold).collect();
+    let query: Vec<char> = query.trim().chars().map(fold).collect();
+    if query.is_empty() || query.len() > text.len() {
+        return Vec::new();
+    }
+
+    let mut matches = Vec::new();
+    let mut start = 0;
+    while start + query.len() <= text.len() {
+        if text[start..start + query.len()]
+            .iter()
+            .zip(&query)
+            .all(|(a, b)| collation.same_letter(*a, *b))
+        {
+            matches.push((start, start + query.len()));
+            start += query.len();
+        } else {
+            start += 1;
+        }
+    }
+
+    matches
+}
+
+/// Score how well a query matches a text as fuzzy subsequence
+///
+/// All characters of the query have to appear in the text in order, e.g. `gml` matches
+/// `Gmail`. Characters that follow each other or start a word score higher, so `gm` ranks
+/// `Gmail` above `Programm`. Whitespace in the query is ignored.
+///
+/// # Arguments
+///
+/// * `query` - The text typed by the user
+/// * `text` - The text to match
+/// * `collation` - Decides which letters match
+///
+/// # Returns
+///
+/// The score, higher is better, None if the text does not contain the query
+pub fn fuzzy_score(query: &str, text: &str, collation: &Collation) -> Option<u32> {
+    let text: Vec<char> = text.chars().map(fold).collect();
+    let query: Vec<char> = query
+        .chars()
+        .filter(|c| !c.is_whitespace())
+        .map(fold)
+        .collect();
+    if query.is_empty() {
+        return None;
+    }
+
+    let mut score = 0;
+    let mut start = 0;
+    let mut previous = None;
+    for c in query {
+        let found = (start..text.len()).find(|&i| collation.same_letter(text[i], c))?;
+        score += 1;
+        if found > 0 && previous == Some(found - 1) {
+            score += FUZZY_CONSECUTIVE_BONUS;
+        }
+        if found == 0 || !text[found - 1].is_alphanumeric() {
+            score += FUZZY_WORD_START_BONUS;
+        }
+        previous = Some(found);
+        start = found + 1;
+    }
+
+    Some(score)
+}
+
+/// Translate a glob pattern into an anchored regular expression.
+///
+/// `*` matches any number of characters and `?` a single one, everything else is literal.
+fn glob_to_regex(glob: &str) -> String {
+    let mut pattern = String::from("^");
+    for c in glob.chars() {
+        match c {
+            '*' => pattern.push_str(".*"),
+            '?' => pattern.push('.'),
+            c => pattern.push_str(&regex::escape(&c.to_string())),
+        }
+    }
+    pattern.push('$');
+
+    pattern
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_find_in() {
+        let collation = Collation::default();
+        assert_eq!(
+            find_in("Kundennummer 4711", "NUMMER", &collation),
+            vec![(6, 12)]
+        );
+        assert_eq!(find_in("aaaa", "aa", &collation), vec![(0, 2), (2, 4)]);
+        assert_eq!(find_in("Größe Öl", "öl", &collation), vec![(6, 8)]);
+        assert_eq!(find_in("Müller GmbH", "muller", &collation), vec![(0, 6)]);
+        assert!(find_in("mail", "  ", &collation).is_empty());
+        assert!(find_in("mail", "mailbox", &collation).is_empty());
+
+        let swedish = Collation::new("sv").unwrap();
+        assert!(find_in("Åsa", "asa", &swedish).is_empty());
+    }
+
+    #[test]
+    fn test_find_entry() {
+        let mut entry = PasswordEntry::new(
+            1,
+            "Bank".to_string(),
+            "me".to_string(),
+            "secret".to_string(),
+            "https://bank.example".to_string(),
+            "Kundennummer bank-4711".to_string(),
+        );
+        entry.id = Some(1);
+
+        let collation = Collation::default();
+        let query = |q| SearchQuery::new(q, SearchMode::Text).unwrap();
+        let result = SearchResult::find(&entry, &query("bank"), &collation).unwrap();
+        assert_eq!(
+            result.matches,
+            vec![
+                SearchMatch {
+                    field: SearchField::Service,
+                    start: 0,
+                    end: 4
+                },
+                SearchMatch {
+                    field: SearchField::Url,
+                    start: 8,
+                    end: 12
+                },
+                SearchMatch {
+                    field: SearchField::Notes,
+                    start: 13,
+                    end: 17
+                },
+            ]
+        );
+
+        assert!(SearchResult::find(&entry, &query("secret"), &collation).is_none());
+        assert_eq!(
+            SearchResult::find(&entry, &query("4711"), &collation)
+                .unwrap()
+                .matches[0]
+                .field,
+            SearchField::Notes
+        );
+    }
+
+    #[test]
+    fn test_fuzzy_score() {
+        let collation = Collation::default();
+        let score = |query, text| fuzzy_score(query, text, &collation);
+
+        assert!(score("gml", "Gmail").is_some());
+        assert!(score("gm", "Gmail") > score("gm", "Programm"));
+        assert!(score("bank", "Sparkasse Bank") > score("bank", "Babanek"));
+        assert!(score("mul", "Müller GmbH").is_some());
+        assert_eq!(score("lg", "Gmail"), None);
+        assert_eq!(score(" ", "Gmail"), None);
+        assert_eq!(score("gmail", "Gm"), None);
+    }
+
+    #[test]
+    fn test_pattern_queries() {
+        let collation = Collation::default();
+        let regex = SearchQuery::new(r"\d{4}", SearchMode::Regex).unwrap();
+        assert_eq!(regex.find_in("Größe 4711", &collation), vec![(6, 10)]);
+        assert!(regex.find_in("keine Nummer", &collation).is_empty());
+        assert!(SearchQuery::new("a*", SearchMode::Regex)
+            .unwrap()
+            .find_in("bbb", &collation)
+            .is_empty());
+
+        let glob = SearchQuery::new("*.example", SearchMode::Glob).unwrap();
+        assert_eq!(
+            glob.find_in("https://Bank.EXAMPLE", &collation),
+            vec![(0, 20)]
+        );
+        assert!(glob
+            .find_in("https://bank.example/login", &collation)
+            .is_empty());
+        assert_eq!(
+            SearchQuery::new("b?nk", SearchMode::Glob)
+                .unwrap()
+                .find_in("bank", &collation),
+            vec![(0, 4)]
+        );
+        assert!(SearchQuery::new("a+b", SearchMode::Glob)
+            .unwrap()
+            .find_in("aab", &collation)
+            .is_empty());
+
+        assert!(SearchQuery::new("(unclosed", SearchMode::Regex).is_err());
+        assert!(SearchQuery::new(&"a".repeat(MAX_PATTERN_LENGTH + 1), SearchMode::Regex).is_err());
+        assert!(SearchQuery::new(r"\w{1000}{1000}", SearchMode::Regex).is_err());
+    }
+}
@@ -0,0 +1,230 @@
+use log::{info, warn};
+use ring::rand::{SecureRandom, SystemRandom};
+use serde::Serialize;
+use std::fs::{self, File, OpenOptions};
+use std::io::{Seek, SeekFrom, Write};
+use std::path::{Path, PathBuf};
+
+const CHUNK_SIZE: usize = 64 * 1024;
+
+/// Limits of overwriting that apply on every platform.
+const GENERAL_CAVEATS: &[&str] = &[
+    "SSDs und USB-Sticks verteilen Schreibzugriffe auf andere Speicherzellen (Wear Leveling), \
+     alte Daten können dort erhalten bleiben, bis das Laufwerk die freigegebenen Blöcke per TRIM verwirft.",
+    "Backups, Exporte und Kopien in Cloud-Ordnern werden nicht gelöscht.",
+];
+
+/// Limits of overwriting that depend on the file systems of the platform.
+#[cfg(target_os = "linux")]
+const PLATFORM_CAVEATS: &[&str] = &[
+    "Copy-on-Write-Dateisysteme wie Btrfs oder ZFS schreiben neue Blöcke, statt die alten zu \
+     überschreiben, und Snapshots behalten ältere Stände der Datei.",
+];
+#[cfg(target_os = "macos")]
+const PLATFORM_CAVEATS: &[&str] = &[
+    "APFS schreibt neue Blöcke, statt die alten zu überschreiben, und lokale Time-Machine-Snapshots \
+     behalten ältere Stände der Datei.",
+];
+#[cfg(target_os = "windows")]
+const PLATFORM_CAVEATS: &[&str] = &[
+    "Schattenkopien und der Dateiversionsverlauf von Windows können ältere Stände der Datei enthalten.",
+];
+#[cfg(not(any(target_os = "linux", target_os = "macos", target_os = "windows")))]
+const PLATFORM_CAVEATS: &[&str] = &[];
+
+/// The outcome of a secure delete, reported to the user together with its limits.
+#[derive(Serialize)]
+pub struct DeletionReport {
+    /// The files that existed and were removed.
+    pub removed: Vec<PathBuf>,
+    /// Whether the contents were overwritten before removing the files.
+    pub overwritten: bool,
+    /// Why the data might still be recoverable on this platform.
+    pub caveats: Vec<&'static str>,
+}
+
+/// Removes files so their contents are harder to recover.
+///
+/// Overwriting only reaches the original blocks on file systems that write in place, see
+/// `DeletionReport::caveats` for what remains on SSDs and copy-on-write file systems.
+pub struct SecureDelete {
+    overwrite: bool,
+    rng: SystemRandom,
+}
+
+impl SecureDelete {
+    /// Create a new secure delete
+    ///
+    /// # Arguments
+    ///
+    /// * `overwrite` - Whether to overwrite the contents with random data before removing
+    ///   a file
+    ///
+    /// # Returns
+    ///
+    /// A new secure delete
+    pub fn new(overwrite: bool) -> Self {
+        Self {
+            overwrite,
+            rng: SystemRandom::new(),
+        }
+    }
+
+    /// Remove the given files, missing files are skipped
+    ///
+    /// Each file is optionally overwritten, then truncated and unlinked. Truncating frees the
+    /// blocks before the unlink, so file systems mounted with discard can trim them right away.
+    ///
+    /// # Arguments
+    ///
+    /// * `paths` - The files to remove
+    ///
+    /// # Returns
+    ///
+    /// A Result containing the removed files and the limits of the platform or an error
+    ///
+    /// # Errors
+    ///
+    /// If a file cannot be overwritten, truncated or removed
+    pub fn delete_files(
+        &self,
+        paths: &[PathBuf],
+    ) -> Result<DeletionReport, Box<dyn std::error::Error>> {
+        let mut removed = Vec::new();
+        for path in paths {
+            if self.delete_file(path)? {
+                removed.push(path.clone());
+            }
+        }
+
+        Ok(DeletionReport {
+            removed,
+            overwritten: self.overwrite,
+            caveats: Self::caveats(),
+        })
+    }
+
+    /// Remove a single file
+    ///
+    /// # Arguments
+    ///
+    /// * `path` - The file to remove
+    ///
+    /// # Returns
+    ///
+    /// A Result containing whether the file existed or an error
+    ///
+    /// # Errors
+    ///
+    /// If the file cannot be overwritten, truncated or removed
+    pub fn delete_file(&self, path: &Path) -> Result<bool, Box<dyn std::error::Error>> {
+        if !path.is_file() {
+            return Ok(false);
+        }
+
+        let mut file = OpenOptions::new().write(true).open(path)?;
+        if self.overwrite {
+            let len = file.metadata()?.len();
+            self.scrub(&mut file, len)?;
+        }
+        file.set_len(0)?;
+        if let Err(e) = file.sync_all() {
+            warn!("Failed to sync truncated file {}: {}", path.display(), e);
+        }
+        drop(file);
+
+        fs::remove_file(path)?;
+        info!(
+            "Securely deleted {} (overwritten: {})",
+            path.display(),
+            self.overwrite
+        );
+        Ok(true)
+    }
+
+    /// Get the reasons deleted data might still be recoverable on this platform
+    ///
+    /// # Returns
+    ///
+    /// The caveats in the language of the user interface
+    pub fn caveats() -> Vec<&'static str> {
+        GENERAL_CAVEATS
+            .iter()
+            .chain(PLATFORM_CAVEATS)
+            .copied()
+            .collect()
+    }
+
+    /// Overwrite the first `len` bytes of the file with random data and flush them to disk
+    fn scrub(&self, file: &mut File, len: u64) -> Result<(), Box<dyn std::error::Error>> {
+        let mut buffer = vec![0u8; CHUNK_SIZE];
+        let mut remaining = len;
+        file.seek(SeekFrom::Start(0))?;
+
+        while remaining > 0 {
+            let chunk = remaining.min(CHUNK_SIZE as u64) as usize;
+            self.rng
+                .fill(&mut buffer[..chunk])
+                .map_err(|_| "Failed to generate random data")?;
+            file.write_all(&buffer[..chunk])?;
+            remaining -= chunk as u64;
+        }
+
+        file.sync_all()?;
+        Ok(())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use tempfile::TempDir;
+
+    #[test]
+    fn test_scrub_replaces_contents() {
+        let temp = TempDir::new().unwrap();
+        let path = temp.path().join("vault.db");
+        let original = vec![0x42u8; CHUNK_SIZE * 2 + 17];
+        fs::write(&path, &original).unwrap();
+
+        let mut file = OpenOptions::new().write(true).open(&path).unwrap();
+        SecureDelete::new(true)
+            .scrub(&mut file, original.len() as u64)
+            .unwrap();
+        drop(file);
+
+        let scrubbed = fs::read(&path).unwrap();
+        assert_eq!(scrubbed.len(), original.len());
+        assert_ne!(scrubbed, original);
+    }
+
+    #[test]
+    fn test_delete_files_skips_missing() {
+        let temp = TempDir::new().unwrap();
+        let db = temp.path().join("vault.db");
+        let wal = temp.path().join("vault.db-wal");
+        fs::write(&db, b"secret").unwrap();
+
+        let report = SecureDelete::new(true)
+            .delete_files(&[db.clone(), wal.clone()])
+            .unwrap();
+
+        assert_eq!(report.removed, vec![db.clone()]);
+        assert!(report.overwritten);
+        assert!(!report.caveats.is_empty());
+        assert!(!db.exists());
+        assert!(!wal.exists());
+    }
+
+    #[test]
+    fn test_delete_without_overwrite() {
+        let temp = TempDir::new().unwrap();
+        let path = temp.path().join(".salt");
+        fs::write(&path, b"salt").unwrap();
+
+        let deleter = SecureDelete::new(false);
+        assert!(deleter.delete_file(&path).unwrap());
+        assert!(!path.exists());
+        assert!(!deleter.delete_file(&path).unwrap());
+    }
+}
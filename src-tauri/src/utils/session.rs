@@ -1,10 +1,18 @@
-use crate::utils::Encryption;
+use super::backup_naming::hostname;
+use crate::utils::{AuditEvent, AuditLog, Clock, Encryption, FsStorage, Storage, SystemClock};
 use crate::Config;
-use log::{error, info};
+use log::{error, info, warn};
+use ring::{
+    digest,
+    rand::{SecureRandom, SystemRandom},
+};
 use serde::{Deserialize, Serialize};
-use std::fs;
 use std::path::PathBuf;
-use std::time::{SystemTime, UNIX_EPOCH};
+use std::sync::Arc;
+use zeroize::Zeroize;
+
+/// The number of random bytes of the install ID.
+const INSTALL_ID_LEN: usize = 16;
 
 #[derive(Serialize, Deserialize)]
 pub struct SessionToken {
@@ -12,6 +20,20 @@ pub struct SessionToken {
     user_id: i32,
     created_at: u64,
     expires_at: u64,
+    last_activity: u64,
+    idle_timeout: u64,
+    /// The machine the session was created on, empty for tokens of older versions.
+    #[serde(default)]
+    machine_id: String,
+}
+
+/// The time left until a session ends.
+#[derive(Clone, Copy, Debug, PartialEq, Serialize)]
+pub struct SessionTimeout {
+    /// Seconds until the session ends.
+    pub seconds: u64,
+    /// Whether activity can postpone the end, false if the absolute lifetime is reached first.
+    pub extendable: bool,
 }
 
 impl SessionToken {
@@ -21,37 +43,75 @@ impl SessionToken {
     ///
     /// * `master_key` - The master key for the session
     /// * `user_id` - The user ID for the session
-    /// * `duration_minutes` - The duration in minutes for the session
+    /// * `idle_timeout` - The inactivity timeout in seconds for the session
+    /// * `max_lifetime` - The absolute lifetime in seconds for the session
+    /// * `now` - The current time in seconds since the Unix epoch
     ///
     /// # Returns
     ///
     /// A new session token
-    pub fn new(master_key: Vec<u8>, user_id: i32, duration: u64) -> Self {
-        let now = SystemTime::now()
-            .duration_since(UNIX_EPOCH)
-            .unwrap()
-            .as_secs();
-
+    pub fn new(
+        master_key: Vec<u8>,
+        user_id: i32,
+        idle_timeout: u64,
+        max_lifetime: u64,
+        now: u64,
+    ) -> Self {
         Self {
             master_key,
             user_id,
             created_at: now,
-            expires_at: now + duration,
+            expires_at: now + max_lifetime,
+            last_activity: now,
+            idle_timeout,
+            machine_id: String::new(),
         }
     }
 
     /// Check if the session token is valid
     ///
+    /// A session is valid until it was inactive for longer than the inactivity timeout
+    /// or reached its absolute lifetime, whichever comes first.
+    ///
+    /// # Arguments
+    ///
+    /// * `now` - The current time in seconds since the Unix epoch
+    ///
     /// # Returns
     ///
     /// True if the session token is valid, false otherwise
-    pub fn is_valid(&self) -> bool {
-        let now = SystemTime::now()
-            .duration_since(UNIX_EPOCH)
-            .unwrap()
-            .as_secs();
+    pub fn is_valid(&self, now: u64) -> bool {
+        self.expires_at > now && self.last_activity + self.idle_timeout > now
+    }
 
-        self.expires_at > now
+    /// Mark the session as active, extending the inactivity timeout
+    ///
+    /// The absolute lifetime is not extended.
+    ///
+    /// # Arguments
+    ///
+    /// * `now` - The current time in seconds since the Unix epoch
+    pub fn touch(&mut self, now: u64) {
+        self.last_activity = now;
+    }
+
+    /// Get the time left until the session ends
+    ///
+    /// # Arguments
+    ///
+    /// * `now` - The current time in seconds since the Unix epoch
+    ///
+    /// # Returns
+    ///
+    /// The seconds until the inactivity timeout or the absolute lifetime ends the session
+    pub fn timeout(&self, now: u64) -> SessionTimeout {
+        let idle = (self.last_activity + self.idle_timeout).saturating_sub(now);
+        let lifetime = self.expires_at.saturating_sub(now);
+
+        SessionTimeout {
+            seconds: idle.min(lifetime),
+            extendable: idle < lifetime,
+        }
     }
 
     /// Get the master key for the session
@@ -75,7 +135,18 @@ impl SessionToken {
 
 pub struct TokenManager {
     token_path: PathBuf,
+    install_id_path: PathBuf,
+    hostname: String,
+    audit: AuditLog,
     pub encryption: Encryption,
+    clock: Arc<dyn Clock>,
+    storage: Arc<dyn Storage>,
+}
+
+impl Drop for SessionToken {
+    fn drop(&mut self) {
+        self.master_key.zeroize();
+    }
 }
 
 impl TokenManager {
@@ -90,9 +161,40 @@ impl TokenManager {
     ///
     /// A new token manager
     pub fn new(config_dir: PathBuf, encryption: Encryption) -> Self {
+        Self::with_environment(
+            config_dir,
+            encryption,
+            Arc::new(SystemClock),
+            Arc::new(FsStorage),
+        )
+    }
+
+    /// Create a new token manager with a custom clock and storage
+    ///
+    /// # Arguments
+    ///
+    /// * `config_dir` - The directory to store the session token
+    /// * `encryption` - The encryption instance to use
+    /// * `clock` - The clock used to check the session expiry
+    /// * `storage` - The storage the session token is written to
+    ///
+    /// # Returns
+    ///
+    /// A new token manager
+    pub fn with_environment(
+        config_dir: PathBuf,
+        encryption: Encryption,
+        clock: Arc<dyn Clock>,
+        storage: Arc<dyn Storage>,
+    ) -> Self {
         Self {
             token_path: config_dir.join(".session_token"),
+            install_id_path: config_dir.join(".install_id"),
+            hostname: hostname(),
+            audit: AuditLog::new(&config_dir),
             encryption,
+            clock,
+            storage,
         }
     }
 
@@ -102,6 +204,8 @@ impl TokenManager {
     ///
     /// * `master_pass` - The master password for the session
     /// * `user_id` - The user ID for the session
+    /// * `duration` - The inactivity timeout in minutes, defaults to the configured auto logout
+    /// * `max_lifetime` - The absolute lifetime in minutes, defaults to the configured maximum
     ///
     /// # Returns
     ///
@@ -115,23 +219,69 @@ impl TokenManager {
         master_pass: &str,
         user_id: i32,
         duration: Option<u64>,
+        max_lifetime: Option<u64>,
     ) -> Result<(), Box<dyn std::error::Error>> {
         info!("Creating new session for user {}", user_id);
-        let duration = match duration {
-            Some(d) => d * 60,
-            None => {
+        let (duration, max_lifetime) = match (duration, max_lifetime) {
+            (Some(d), Some(m)) => (d, m),
+            _ => {
                 let config = Config::load()?;
-                config.app.auto_logout_duration * 60
+                (
+                    duration.unwrap_or(config.app.auto_logout_duration),
+                    max_lifetime.unwrap_or(config.app.session_max_lifetime),
+                )
             }
         };
         let master_key = self.encryption.get_key(master_pass)?.into_bytes();
 
-        let token = SessionToken::new(master_key, user_id, duration);
+        let mut token = SessionToken::new(
+            master_key,
+            user_id,
+            duration * 60,
+            max_lifetime * 60,
+            self.clock.timestamp(),
+        );
+        token.machine_id = self.machine_id()?;
+
+        self.write_session(&token)
+    }
+
+    /// Get the session token and mark the session as active
+    ///
+    /// # Returns
+    ///
+    /// A Result containing the session token or an error
+    ///
+    /// # Errors
+    ///
+    /// If the session token cannot be retrieved or written
+    pub fn refresh_session(&self) -> Result<SessionToken, Box<dyn std::error::Error>> {
+        let mut token = self.get_session()?;
+        token.touch(self.clock.timestamp());
+        self.write_session(&token)?;
+
+        Ok(token)
+    }
 
-        let token_str = serde_json::to_string(&token)?;
+    /// Encrypt and write the session token
+    ///
+    /// # Arguments
+    ///
+    /// * `token` - The session token to write
+    ///
+    /// # Returns
+    ///
+    /// A Result containing a unit or an error
+    ///
+    /// # Errors
+    ///
+    /// If the session token cannot be written
+    fn write_session(&self, token: &SessionToken) -> Result<(), Box<dyn std::error::Error>> {
+        let mut token_str = serde_json::to_string(token)?;
         let encrypted_token = self.encryption.encrypt(&token_str).unwrap();
+        token_str.zeroize();
 
-        match fs::write(&self.token_path, encrypted_token) {
+        match self.storage.write(&self.token_path, &encrypted_token) {
             Ok(_) => {
                 info!("Successfully created session");
                 Ok(())
@@ -145,6 +295,8 @@ impl TokenManager {
 
     /// Get the session token
     ///
+    /// A session token created on another machine is removed and recorded in the audit log.
+    ///
     /// # Returns
     ///
     /// A Result containing the session token or an error
@@ -153,16 +305,24 @@ impl TokenManager {
     ///
     /// If the session token cannot be retrieved
     pub fn get_session(&self) -> Result<SessionToken, Box<dyn std::error::Error>> {
-        if !self.token_path.exists() {
+        if !self.storage.exists(&self.token_path) {
             info!("No session token found");
             return Err("No session token found".into());
         }
 
-        let encrypted_token = fs::read(&self.token_path)?;
-        let token_str = self.encryption.decrypt(&encrypted_token).unwrap();
-        let token: SessionToken = serde_json::from_str(&token_str)?;
-
-        if !token.is_valid() {
+        let token = self.read_session()?;
+        if token.machine_id != self.machine_id()? {
+            warn!("Rejected session token of another machine");
+            if let Err(e) = self.audit.record(
+                AuditEvent::SessionRejected,
+                "Session token of another machine",
+            ) {
+                error!("Failed to write audit log: {}", e);
+            }
+            self.clear_session()?;
+            return Err("Session token belongs to another machine".into());
+        }
+        if !token.is_valid(self.clock.timestamp()) {
             info!("Session token expired");
             self.clear_session()?;
             return Err("Session token expired".into());
@@ -172,6 +332,68 @@ impl TokenManager {
         Ok(token)
     }
 
+    /// Get the time left until the session ends without marking it as active
+    ///
+    /// # Returns
+    ///
+    /// A Result containing the time left, None if there is no session, or an error
+    ///
+    /// # Errors
+    ///
+    /// If the session token cannot be read
+    pub fn session_timeout(&self) -> Result<Option<SessionTimeout>, Box<dyn std::error::Error>> {
+        if !self.storage.exists(&self.token_path) {
+            return Ok(None);
+        }
+
+        let token = self.read_session()?;
+        if token.machine_id != self.machine_id()? {
+            return Ok(None);
+        }
+
+        Ok(Some(token.timeout(self.clock.timestamp())))
+    }
+
+    /// Get the identifier of this machine
+    ///
+    /// The identifier hashes the hostname together with a random install ID, which is
+    /// created on first use and kept in the config directory.
+    ///
+    /// # Returns
+    ///
+    /// A Result containing the identifier or an error
+    ///
+    /// # Errors
+    ///
+    /// If the install ID cannot be read or created
+    fn machine_id(&self) -> Result<String, Box<dyn std::error::Error>> {
+        if !self.storage.exists(&self.install_id_path) {
+            let mut install_id = [0u8; INSTALL_ID_LEN];
+            SystemRandom::new()
+                .fill(&mut install_id)
+                .map_err(|_| "Failed to generate install ID")?;
+            self.storage
+                .write(&self.install_id_path, to_hex(&install_id).as_bytes())?;
+        }
+        let install_id = self.storage.read(&self.install_id_path)?;
+
+        let digest = digest::digest(
+            &digest::SHA256,
+            &[self.hostname.as_bytes(), b":", install_id.trim_ascii()].concat(),
+        );
+        Ok(to_hex(digest.as_ref()))
+    }
+
+    /// Read and decrypt the session token without checking its validity
+    fn read_session(&self) -> Result<SessionToken, Box<dyn std::error::Error>> {
+        let encrypted_token = self.storage.read(&self.token_path)?;
+        let mut token_str = self.encryption.decrypt(&encrypted_token).unwrap();
+        let token = serde_json::from_str::<SessionToken>(&token_str);
+        token_str.zeroize();
+
+        Ok(token?)
+    }
+
     /// Clear the session token
     ///
     /// # Returns
@@ -183,8 +405,8 @@ impl TokenManager {
     /// If the session token cannot be cleared
     pub fn clear_session(&self) -> Result<(), Box<dyn std::error::Error>> {
         info!("Clearing session");
-        if self.token_path.exists() {
-            match fs::remove_file(&self.token_path) {
+        if self.storage.exists(&self.token_path) {
+            match self.storage.remove(&self.token_path) {
                 Ok(_) => info!("Successfully cleared session"),
                 Err(e) => error!("Failed to clear session: {}", e),
             }
@@ -202,44 +424,169 @@ impl TokenManager {
     }
 }
 
+/// Encode bytes as lowercase hex
+fn to_hex(bytes: &[u8]) -> String {
+    bytes.iter().map(|b| format!("{:02x}", b)).collect()
+}
+
 #[cfg(test)]
 mod tests {
     use super::*;
-    use std::thread::sleep;
-    use std::time::Duration;
+    use crate::utils::{FixedClock, KeyParams, MemoryStorage};
+    use chrono::{DateTime, Duration};
+    use std::path::Path;
     use tempfile::TempDir;
 
+    const NOW: u64 = 1_700_000_000;
+
     fn setup_test_manager() -> (TempDir, TokenManager) {
         let temp = TempDir::new().unwrap();
         let temp_path = temp.path().to_path_buf();
-        let salt = [0u8; 16];
-        let encryption = Encryption::new("test_password", &salt);
+        let encryption = Encryption::new("test_password", &KeyParams::legacy([0u8; 16]));
         (temp, TokenManager::new(temp_path, encryption))
     }
 
+    fn setup_memory_manager() -> (Arc<FixedClock>, Arc<MemoryStorage>, TokenManager) {
+        let clock = Arc::new(FixedClock::new(
+            DateTime::from_timestamp(NOW as i64, 0).unwrap(),
+        ));
+        let storage = Arc::new(MemoryStorage::new(clock.clone()));
+        let encryption = Encryption::new("test_password", &KeyParams::legacy([0u8; 16]));
+        let manager = TokenManager::with_environment(
+            PathBuf::from("/config"),
+            encryption,
+            clock.clone(),
+            storage.clone(),
+        );
+        (clock, storage, manager)
+    }
+
     #[test]
     fn test_session_token_creation() {
-        let token = SessionToken::new(vec![1, 2, 3], 1, 30);
+        let token = SessionToken::new(vec![1, 2, 3], 1, 30, 60, NOW);
 
         assert_eq!(token.get_user_id(), 1);
         assert_eq!(token.get_master_key(), vec![1, 2, 3]);
-        assert!(token.is_valid());
+        assert!(token.is_valid(NOW));
     }
 
     #[test]
     fn test_session_expiration() {
-        let token = SessionToken::new(vec![1, 2, 3], 1, 1);
-        assert!(token.is_valid());
+        let token = SessionToken::new(vec![1, 2, 3], 1, 1, 60, NOW);
+        assert!(token.is_valid(NOW));
+        assert!(!token.is_valid(NOW + 1));
+    }
+
+    #[test]
+    fn test_session_inactivity_and_lifetime() {
+        let mut token = SessionToken::new(vec![1, 2, 3], 1, 10, 60, NOW);
+
+        assert!(!token.is_valid(NOW + 20));
 
-        sleep(Duration::from_secs(2));
-        assert!(!token.is_valid());
+        token.touch(NOW + 20);
+        assert!(token.is_valid(NOW + 20));
+
+        token.touch(NOW + 59);
+        assert!(token.is_valid(NOW + 59));
+        assert!(!token.is_valid(NOW + 60));
+    }
+
+    #[test]
+    fn test_session_expiry_with_clock() {
+        let (clock, storage, manager) = setup_memory_manager();
+
+        manager
+            .create_session("test_pass", 1, Some(10), Some(30))
+            .unwrap();
+        assert!(storage.exists(Path::new("/config/.session_token")));
+
+        clock.advance(Duration::minutes(9));
+        assert!(manager.refresh_session().is_ok());
+
+        clock.advance(Duration::minutes(9));
+        assert!(manager.refresh_session().is_ok());
+
+        clock.advance(Duration::minutes(11));
+        assert!(!manager.has_valid_session());
+        assert!(!storage.exists(Path::new("/config/.session_token")));
+    }
+
+    #[test]
+    fn test_session_lifetime_with_clock() {
+        let (clock, _storage, manager) = setup_memory_manager();
+
+        manager
+            .create_session("test_pass", 1, Some(10), Some(30))
+            .unwrap();
+        for _ in 0..3 {
+            clock.advance(Duration::minutes(9));
+            assert!(manager.refresh_session().is_ok());
+        }
+
+        clock.advance(Duration::minutes(3));
+        assert!(!manager.has_valid_session());
+    }
+
+    #[test]
+    fn test_session_timeout() {
+        let (clock, _storage, manager) = setup_memory_manager();
+        assert_eq!(manager.session_timeout().unwrap(), None);
+
+        manager
+            .create_session("test_pass", 1, Some(10), Some(15))
+            .unwrap();
+        clock.advance(Duration::minutes(9));
+        assert_eq!(
+            manager.session_timeout().unwrap(),
+            Some(SessionTimeout {
+                seconds: 60,
+                extendable: true,
+            })
+        );
+
+        manager.refresh_session().unwrap();
+        clock.advance(Duration::minutes(5));
+        assert_eq!(
+            manager.session_timeout().unwrap(),
+            Some(SessionTimeout {
+                seconds: 60,
+                extendable: false,
+            })
+        );
+
+        clock.advance(Duration::minutes(2));
+        assert_eq!(manager.session_timeout().unwrap().unwrap().seconds, 0);
+    }
+
+    #[test]
+    fn test_refresh_session() {
+        let (_temp, manager) = setup_test_manager();
+
+        manager
+            .create_session("test_pass", 1, Some(10), Some(60))
+            .unwrap();
+        let session = manager.get_session().unwrap();
+
+        let mut stale = session;
+        stale.last_activity -= 5;
+        manager.write_session(&stale).unwrap();
+
+        let refreshed = manager.refresh_session().unwrap();
+        assert!(refreshed.last_activity > stale.last_activity);
+        assert_eq!(refreshed.expires_at, stale.expires_at);
+        assert_eq!(
+            manager.get_session().unwrap().last_activity,
+            refreshed.last_activity
+        );
     }
 
     #[test]
     fn test_token_manager_workflow() {
         let (_temp, manager) = setup_test_manager();
 
-        assert!(manager.create_session("test_pass", 1, Some(10)).is_ok());
+        assert!(manager
+            .create_session("test_pass", 1, Some(10), Some(60))
+            .is_ok());
         assert!(manager.has_valid_session());
 
         let session = manager.get_session().unwrap();
@@ -256,21 +603,53 @@ mod tests {
         assert!(!manager.has_valid_session());
         assert!(manager.get_session().is_err());
 
-        assert!(manager.create_session("test_pass", 1, Some(10)).is_ok());
-        let token = SessionToken::new(vec![1, 2, 3], 1, 0);
-        let token_str = serde_json::to_string(&token).unwrap();
-        let encrypted = manager.encryption.encrypt(&token_str).unwrap();
-        fs::write(&manager.token_path, encrypted).unwrap();
+        assert!(manager
+            .create_session("test_pass", 1, Some(10), Some(60))
+            .is_ok());
+        let token = SessionToken::new(vec![1, 2, 3], 1, 0, 0, manager.clock.timestamp());
+        manager.write_session(&token).unwrap();
 
         assert!(!manager.has_valid_session());
         assert!(manager.get_session().is_err());
     }
 
+    #[test]
+    fn test_session_machine_binding() {
+        let (temp, manager) = setup_test_manager();
+
+        manager
+            .create_session("test_pass", 1, Some(10), Some(60))
+            .unwrap();
+        assert!(manager.has_valid_session());
+
+        let (_other_temp, mut other) = setup_test_manager();
+        other.token_path = manager.token_path.clone();
+        other.install_id_path = manager.install_id_path.clone();
+        other.audit = AuditLog::new(temp.path());
+        other.hostname = format!("{}-copy", manager.hostname);
+
+        assert_eq!(other.session_timeout().unwrap(), None);
+        assert!(other.get_session().is_err());
+        assert!(!manager.token_path.exists());
+
+        let entries = AuditLog::new(temp.path()).read_range(None, None).unwrap();
+        assert_eq!(entries.len(), 1);
+        assert_eq!(entries[0].event, AuditEvent::SessionRejected);
+
+        manager
+            .create_session("test_pass", 1, Some(10), Some(60))
+            .unwrap();
+        std::fs::remove_file(&manager.install_id_path).unwrap();
+        assert!(!manager.has_valid_session());
+    }
+
     #[test]
     fn test_session_persistence() {
         let (_temp, manager) = setup_test_manager();
 
-        manager.create_session("test_pass", 1, Some(10)).unwrap();
+        manager
+            .create_session("test_pass", 1, Some(10), Some(60))
+            .unwrap();
 
         assert!(manager.token_path.exists());
 
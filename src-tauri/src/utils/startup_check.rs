@@ -0,0 +1,272 @@
+use serde::Serialize;
+use std::fs::{self, File};
+use std::io::Read;
+use std::path::{Path, PathBuf};
+
+use super::Config;
+
+const SQLITE_HEADER: &[u8; 16] = b"SQLite format 3\0";
+const WRITE_PROBE: &str = ".write_probe";
+
+#[derive(Serialize, Clone, Copy, Debug, PartialEq)]
+#[serde(rename_all = "snake_case")]
+pub enum StartupIssueKind {
+    ConfigInvalid,
+    SaltMissing,
+    DatabaseLocationUnavailable,
+    DatabaseMissing,
+    DatabaseUnreadable,
+    BackupFolderNotWritable,
+}
+
+#[derive(Serialize, Clone, Debug)]
+pub struct StartupIssue {
+    pub kind: StartupIssueKind,
+    pub details: String,
+    pub blocking: bool,
+}
+
+pub struct StartupCheck {
+    config_dir: PathBuf,
+}
+
+impl StartupIssue {
+    fn new(kind: StartupIssueKind, details: impl Into<String>) -> Self {
+        Self {
+            kind,
+            details: details.into(),
+            blocking: kind != StartupIssueKind::BackupFolderNotWritable,
+        }
+    }
+}
+
+impl StartupCheck {
+    /// Create a new startup check
+    ///
+    /// # Arguments
+    ///
+    /// * `config_dir` - The directory containing the configuration files
+    ///
+    /// # Returns
+    ///
+    /// A new startup check
+    pub fn new(config_dir: &Path) -> Self {
+        Self {
+            config_dir: config_dir.to_path_buf(),
+        }
+    }
+
+    /// Check that the files of an initialized installation exist and are consistent
+    ///
+    /// A missing config file means the app is not set up yet, which is not an issue.
+    ///
+    /// # Returns
+    ///
+    /// The issues found, empty if the installation is fine
+    pub fn run(&self) -> Vec<StartupIssue> {
+        let config_path = self.config_dir.join("config.toml");
+        if !config_path.exists() {
+            return Vec::new();
+        }
+
+        let config = match fs::read_to_string(&config_path)
+            .map_err(|e| e.to_string())
+            .and_then(|content| toml::from_str::<Config>(&content).map_err(|e| e.to_string()))
+        {
+            Ok(config) => config,
+            Err(e) => return vec![StartupIssue::new(StartupIssueKind::ConfigInvalid, e)],
+        };
+
+        if !config.app.is_initialized {
+            return Vec::new();
+        }
+
+        let mut issues = Vec::new();
+
+        let salt_path = self.config_dir.join(".salt");
+        if fs::metadata(&salt_path).map_or(true, |meta| meta.len() < 16) {
+            issues.push(StartupIssue::new(
+                StartupIssueKind::SaltMissing,
+                salt_path.display().to_string(),
+            ));
+        }
+
+        let db_path = config.database.db_path.join(&config.database.db_name);
+        if !config.database.db_path.is_dir() {
+            issues.push(StartupIssue::new(
+                StartupIssueKind::DatabaseLocationUnavailable,
+                config.database.db_path.display().to_string(),
+            ));
+        } else if !db_path.exists() {
+            issues.push(StartupIssue::new(
+                StartupIssueKind::DatabaseMissing,
+                db_path.display().to_string(),
+            ));
+        } else if !Self::is_encrypted_database(&db_path) {
+            issues.push(StartupIssue::new(
+                StartupIssueKind::DatabaseUnreadable,
+                db_path.display().to_string(),
+            ));
+        }
+
+        if config.backup.enabled && !Self::is_writable(&config.backup.backup_path) {
+            issues.push(StartupIssue::new(
+                StartupIssueKind::BackupFolderNotWritable,
+                config.backup.backup_path.display().to_string(),
+            ));
+        }
+
+        issues
+    }
+
+    /// Check if a database file can be read and looks like an encrypted database
+    ///
+    /// The key is not known before login, so only the file itself is checked: it must be
+    /// readable, large enough for a database page and must not be an unencrypted database.
+    fn is_encrypted_database(path: &Path) -> bool {
+        let mut header = [0u8; 16];
+        File::open(path)
+            .and_then(|mut file| file.read_exact(&mut header))
+            .is_ok_and(|_| &header != SQLITE_HEADER)
+    }
+
+    /// Check if files can be created in a directory, creating the directory if needed
+    fn is_writable(path: &Path) -> bool {
+        let probe = path.join(WRITE_PROBE);
+        let writable = fs::create_dir_all(path).is_ok() && fs::write(&probe, b"").is_ok();
+        let _ = fs::remove_file(probe);
+        writable
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use tempfile::TempDir;
+
+    fn write_config(config_dir: &Path, is_initialized: bool, backup_path: &Path) {
+        let config = format!(
+            r#"
+            [logging]
+            level = "info"
+
+            [database]
+            db_name = "pass.db"
+            db_path = "{dir}"
+
+            [app]
+            is_initialized = {is_initialized}
+            auto_logout_duration = 10
+
+            [generator]
+            default_length = 16
+
+            [backup]
+            enabled = true
+            interval = "Weekly"
+            max_backups = 7
+            backup_path = "{backup}"
+            export_path = "{dir}"
+            "#,
+            dir = config_dir.display(),
+            backup = backup_path.display(),
+        );
+        fs::write(config_dir.join("config.toml"), config).unwrap();
+    }
+
+    fn kinds(issues: &[StartupIssue]) -> Vec<StartupIssueKind> {
+        issues.iter().map(|issue| issue.kind).collect()
+    }
+
+    #[test]
+    fn test_fresh_installation() {
+        let temp = TempDir::new().unwrap();
+        assert!(StartupCheck::new(temp.path()).run().is_empty());
+
+        write_config(temp.path(), false, &temp.path().join("backups"));
+        assert!(StartupCheck::new(temp.path()).run().is_empty());
+    }
+
+    #[test]
+    fn test_invalid_config() {
+        let temp = TempDir::new().unwrap();
+        fs::write(temp.path().join("config.toml"), "[app\nbroken").unwrap();
+
+        let issues = StartupCheck::new(temp.path()).run();
+        assert_eq!(kinds(&issues), vec![StartupIssueKind::ConfigInvalid]);
+        assert!(issues[0].blocking);
+    }
+
+    #[test]
+    fn test_missing_files() {
+        let temp = TempDir::new().unwrap();
+        write_config(temp.path(), true, &temp.path().join("backups"));
+
+        let issues = StartupCheck::new(temp.path()).run();
+        assert_eq!(
+            kinds(&issues),
+            vec![
+                StartupIssueKind::SaltMissing,
+                StartupIssueKind::DatabaseMissing
+            ]
+        );
+    }
+
+    #[test]
+    fn test_database_location_unavailable() {
+        let temp = TempDir::new().unwrap();
+        write_config(temp.path(), true, &temp.path().join("backups"));
+        fs::write(temp.path().join(".salt"), [0u8; 16]).unwrap();
+        let config = fs::read_to_string(temp.path().join("config.toml")).unwrap();
+        let unmounted = temp.path().join("usb");
+        let config = config.replacen(
+            &format!("db_path = \"{}\"", temp.path().display()),
+            &format!("db_path = \"{}\"", unmounted.display()),
+            1,
+        );
+        fs::write(temp.path().join("config.toml"), config).unwrap();
+
+        let issues = StartupCheck::new(temp.path()).run();
+        assert_eq!(
+            kinds(&issues),
+            vec![StartupIssueKind::DatabaseLocationUnavailable]
+        );
+        assert!(!unmounted.exists());
+    }
+
+    #[test]
+    fn test_consistent_installation() {
+        let temp = TempDir::new().unwrap();
+        write_config(temp.path(), true, &temp.path().join("backups"));
+        fs::write(temp.path().join(".salt"), [0u8; 16]).unwrap();
+        fs::write(temp.path().join("pass.db"), [7u8; 64]).unwrap();
+
+        assert!(StartupCheck::new(temp.path()).run().is_empty());
+        assert!(!temp.path().join("backups").join(WRITE_PROBE).exists());
+
+        let mut plain = SQLITE_HEADER.to_vec();
+        plain.extend_from_slice(&[0u8; 48]);
+        fs::write(temp.path().join("pass.db"), plain).unwrap();
+        assert_eq!(
+            kinds(&StartupCheck::new(temp.path()).run()),
+            vec![StartupIssueKind::DatabaseUnreadable]
+        );
+    }
+
+    #[test]
+    fn test_backup_folder_not_writable() {
+        let temp = TempDir::new().unwrap();
+        let blocked = temp.path().join("file");
+        fs::write(&blocked, "").unwrap();
+        write_config(temp.path(), true, &blocked.join("backups"));
+        fs::write(temp.path().join(".salt"), [0u8; 16]).unwrap();
+        fs::write(temp.path().join("pass.db"), [7u8; 64]).unwrap();
+
+        let issues = StartupCheck::new(temp.path()).run();
+        assert_eq!(
+            kinds(&issues),
+            vec![StartupIssueKind::BackupFolderNotWritable]
+        );
+        assert!(!issues[0].blocking);
+    }
+}
@@ -0,0 +1,182 @@
+use chrono::{DateTime, Utc};
+use std::collections::HashMap;
+use std::fs;
+use std::io;
+use std::path::{Path, PathBuf};
+use std::sync::{Arc, Mutex};
+
+use super::Clock;
+
+/// File access used by the core utils, so file-based logic can be tested in memory.
+pub trait Storage: Send + Sync {
+    /// Read the content of a file
+    fn read(&self, path: &Path) -> io::Result<Vec<u8>>;
+
+    /// Create or replace a file
+    fn write(&self, path: &Path, data: &[u8]) -> io::Result<()>;
+
+    /// Remove a file
+    fn remove(&self, path: &Path) -> io::Result<()>;
+
+    /// Check if a file exists
+    fn exists(&self, path: &Path) -> bool;
+
+    /// List the files in a directory
+    fn list(&self, dir: &Path) -> io::Result<Vec<PathBuf>>;
+
+    /// Get the time a file was last modified
+    fn modified(&self, path: &Path) -> io::Result<DateTime<Utc>>;
+}
+
+/// Storage on the local file system.
+pub struct FsStorage;
+
+impl Storage for FsStorage {
+    fn read(&self, path: &Path) -> io::Result<Vec<u8>> {
+        fs::read(path)
+    }
+
+    fn write(&self, path: &Path, data: &[u8]) -> io::Result<()> {
+        fs::write(path, data)
+    }
+
+    fn remove(&self, path: &Path) -> io::Result<()> {
+        fs::remove_file(path)
+    }
+
+    fn exists(&self, path: &Path) -> bool {
+        path.exists()
+    }
+
+    fn list(&self, dir: &Path) -> io::Result<Vec<PathBuf>> {
+        Ok(fs::read_dir(dir)?
+            .filter_map(|entry| entry.ok())
+            .map(|entry| entry.path())
+            .collect())
+    }
+
+    fn modified(&self, path: &Path) -> io::Result<DateTime<Utc>> {
+        Ok(fs::metadata(path)?.modified()?.into())
+    }
+}
+
+struct MemoryFile {
+    data: Vec<u8>,
+    modified: DateTime<Utc>,
+}
+
+/// Storage that keeps files in memory and takes modification times from a clock.
+pub struct MemoryStorage {
+    files: Mutex<HashMap<PathBuf, MemoryFile>>,
+    clock: Arc<dyn Clock>,
+}
+
+impl MemoryStorage {
+    /// Create a new empty in-memory storage
+    ///
+    /// # Arguments
+    ///
+    /// * `clock` - The clock used for the modification times
+    ///
+    /// # Returns
+    ///
+    /// A new MemoryStorage
+    pub fn new(clock: Arc<dyn Clock>) -> Self {
+        Self {
+            files: Mutex::new(HashMap::new()),
+            clock,
+        }
+    }
+
+    fn not_found(path: &Path) -> io::Error {
+        io::Error::new(
+            io::ErrorKind::NotFound,
+            format!("{} not found", path.display()),
+        )
+    }
+}
+
+impl Storage for MemoryStorage {
+    fn read(&self, path: &Path) -> io::Result<Vec<u8>> {
+        self.files
+            .lock()
+            .unwrap()
+            .get(path)
+            .map(|file| file.data.clone())
+            .ok_or_else(|| Self::not_found(path))
+    }
+
+    fn write(&self, path: &Path, data: &[u8]) -> io::Result<()> {
+        self.files.lock().unwrap().insert(
+            path.to_path_buf(),
+            MemoryFile {
+                data: data.to_vec(),
+                modified: self.clock.now(),
+            },
+        );
+        Ok(())
+    }
+
+    fn remove(&self, path: &Path) -> io::Result<()> {
+        self.files
+            .lock()
+            .unwrap()
+            .remove(path)
+            .map(|_| ())
+            .ok_or_else(|| Self::not_found(path))
+    }
+
+    fn exists(&self, path: &Path) -> bool {
+        self.files.lock().unwrap().contains_key(path)
+    }
+
+    fn list(&self, dir: &Path) -> io::Result<Vec<PathBuf>> {
+        Ok(self
+            .files
+            .lock()
+            .unwrap()
+            .keys()
+            .filter(|path| path.parent() == Some(dir))
+            .cloned()
+            .collect())
+    }
+
+    fn modified(&self, path: &Path) -> io::Result<DateTime<Utc>> {
+        self.files
+            .lock()
+            .unwrap()
+            .get(path)
+            .map(|file| file.modified)
+            .ok_or_else(|| Self::not_found(path))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::utils::FixedClock;
+    use chrono::Duration;
+
+    #[test]
+    fn test_memory_storage() {
+        let clock = Arc::new(FixedClock::new(Utc::now()));
+        let storage = MemoryStorage::new(clock.clone());
+        let dir = Path::new("/backups");
+
+        storage.write(&dir.join("a"), b"first").unwrap();
+        clock.advance(Duration::hours(1));
+        storage.write(&dir.join("b"), b"second").unwrap();
+        storage.write(Path::new("/other/c"), b"third").unwrap();
+
+        assert_eq!(storage.read(&dir.join("a")).unwrap(), b"first");
+        assert_eq!(storage.list(dir).unwrap().len(), 2);
+        assert_eq!(
+            storage.modified(&dir.join("b")).unwrap() - storage.modified(&dir.join("a")).unwrap(),
+            Duration::hours(1)
+        );
+
+        storage.remove(&dir.join("a")).unwrap();
+        assert!(!storage.exists(&dir.join("a")));
+        assert!(storage.read(&dir.join("a")).is_err());
+    }
+}
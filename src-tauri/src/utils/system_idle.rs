@@ -0,0 +1,196 @@
+use std::time::{Duration, SystemTime};
+
+#[cfg(any(target_os = "linux", target_os = "macos"))]
+use std::process::Command;
+
+/// How much longer than the check interval a check may take before the system counts as
+/// suspended.
+const SUSPEND_TOLERANCE: Duration = Duration::from_secs(30);
+
+/// Detects a suspend of the system from a gap in the wall clock between two checks.
+///
+/// No thread runs while the system sleeps, so after a resume the wall clock has advanced much
+/// further since the last check than the check interval.
+pub struct SuspendDetector {
+    interval: Duration,
+    last_check: SystemTime,
+}
+
+impl SuspendDetector {
+    /// Create a new suspend detector
+    ///
+    /// # Arguments
+    ///
+    /// * `interval` - The time between two checks
+    /// * `now` - The current time
+    ///
+    /// # Returns
+    ///
+    /// A new suspend detector
+    pub fn new(interval: Duration, now: SystemTime) -> Self {
+        Self {
+            interval,
+            last_check: now,
+        }
+    }
+
+    /// Check if the system was suspended since the last check
+    ///
+    /// # Arguments
+    ///
+    /// * `now` - The current time
+    ///
+    /// # Returns
+    ///
+    /// True if the time since the last check exceeds the interval by far
+    pub fn check(&mut self, now: SystemTime) -> bool {
+        let elapsed = now.duration_since(self.last_check).unwrap_or_default();
+        self.last_check = now;
+        elapsed > self.interval + SUSPEND_TOLERANCE
+    }
+}
+
+/// Get the time since the last keyboard or mouse input of the user in the whole system
+///
+/// Windows asks the system directly, macOS reads the HID idle time from `ioreg` and Linux
+/// asks the idle monitor of GNOME or the screensaver of KDE over D-Bus, with `xprintidle`
+/// as fallback on X11.
+///
+/// # Returns
+///
+/// The idle time, None if the platform or desktop does not report it
+pub fn system_idle_time() -> Option<Duration> {
+    #[cfg(target_os = "windows")]
+    {
+        use windows_sys::Win32::System::SystemInformation::GetTickCount;
+        use windows_sys::Win32::UI::Input::KeyboardAndMouse::{GetLastInputInfo, LASTINPUTINFO};
+
+        let mut info = LASTINPUTINFO {
+            cbSize: std::mem::size_of::<LASTINPUTINFO>() as u32,
+            dwTime: 0,
+        };
+        // SAFETY: `info` is a valid LASTINPUTINFO with its size set as the call requires.
+        if unsafe { GetLastInputInfo(&mut info) } == 0 {
+            return None;
+        }
+        // SAFETY: GetTickCount has no preconditions.
+        let now = unsafe { GetTickCount() };
+        Some(Duration::from_millis(now.wrapping_sub(info.dwTime) as u64))
+    }
+
+    #[cfg(target_os = "macos")]
+    {
+        let output = Command::new("ioreg")
+            .args(["-c", "IOHIDSystem", "-d", "4"])
+            .output()
+            .ok()?;
+        parse_ioreg_idle(&String::from_utf8_lossy(&output.stdout))
+    }
+
+    #[cfg(target_os = "linux")]
+    {
+        let queries: [&[&str]; 2] = [
+            &[
+                "--dest",
+                "org.gnome.Mutter.IdleMonitor",
+                "--object-path",
+                "/org/gnome/Mutter/IdleMonitor/Core",
+                "--method",
+                "org.gnome.Mutter.IdleMonitor.GetIdletime",
+            ],
+            &[
+                "--dest",
+                "org.freedesktop.ScreenSaver",
+                "--object-path",
+                "/org/freedesktop/ScreenSaver",
+                "--method",
+                "org.freedesktop.ScreenSaver.GetSessionIdleTime",
+            ],
+        ];
+
+        queries
+            .iter()
+            .find_map(|query| {
+                let output = Command::new("gdbus")
+                    .args(["call", "--session"])
+                    .args(*query)
+                    .output()
+                    .ok()
+                    .filter(|output| output.status.success())?;
+                parse_millis(&String::from_utf8_lossy(&output.stdout))
+            })
+            .or_else(|| {
+                let output = Command::new("xprintidle")
+                    .output()
+                    .ok()
+                    .filter(|output| output.status.success())?;
+                parse_millis(&String::from_utf8_lossy(&output.stdout))
+            })
+    }
+
+    #[cfg(not(any(target_os = "windows", target_os = "macos", target_os = "linux")))]
+    {
+        None
+    }
+}
+
+/// Parse the HID idle time in nanoseconds from the output of `ioreg`
+#[cfg_attr(not(target_os = "macos"), allow(dead_code))]
+fn parse_ioreg_idle(output: &str) -> Option<Duration> {
+    output.lines().find_map(|line| {
+        let (_, value) = line.split_once("\"HIDIdleTime\" = ")?;
+        value.trim().parse().ok().map(Duration::from_nanos)
+    })
+}
+
+/// Parse an idle time in milliseconds from the output of `gdbus` or `xprintidle`,
+/// e.g. `(uint64 12345,)` or `12345`
+#[cfg_attr(not(target_os = "linux"), allow(dead_code))]
+fn parse_millis(output: &str) -> Option<Duration> {
+    let value = output
+        .trim()
+        .trim_start_matches('(')
+        .trim_end_matches(')')
+        .trim_end_matches(',');
+    let value = value
+        .split_once(' ')
+        .filter(|(kind, _)| kind.starts_with("uint"))
+        .map_or(value, |(_, number)| number);
+
+    value.trim().parse().ok().map(Duration::from_millis)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_suspend_detector() {
+        let start = SystemTime::UNIX_EPOCH + Duration::from_secs(1_000_000);
+        let mut detector = SuspendDetector::new(Duration::from_secs(10), start);
+
+        assert!(!detector.check(start + Duration::from_secs(10)));
+        assert!(!detector.check(start + Duration::from_secs(25)));
+        assert!(detector.check(start + Duration::from_secs(3600)));
+        assert!(!detector.check(start + Duration::from_secs(3610)));
+        assert!(!detector.check(start));
+    }
+
+    #[test]
+    fn test_parse_idle_time() {
+        let ioreg = "    | |   \"HIDIdleTime\" = 2500000000\n    | |   \"HIDParameters\" = {}";
+        assert_eq!(parse_ioreg_idle(ioreg), Some(Duration::from_millis(2500)));
+        assert_eq!(parse_ioreg_idle("no idle time"), None);
+
+        assert_eq!(
+            parse_millis("(uint64 12345,)\n"),
+            Some(Duration::from_millis(12345))
+        );
+        assert_eq!(
+            parse_millis("(uint32 60000,)"),
+            Some(Duration::from_secs(60))
+        );
+        assert_eq!(parse_millis("420\n"), Some(Duration::from_millis(420)));
+        assert_eq!(parse_millis("Error: no such method"), None);
+    }
+}
@@ -0,0 +1,184 @@
+use ring::hmac;
+use serde::Serialize;
+
+const BASE32_ALPHABET: &[u8] = b"ABCDEFGHIJKLMNOPQRSTUVWXYZ234567";
+const DEFAULT_DIGITS: u32 = 6;
+const DEFAULT_PERIOD: u64 = 30;
+
+/// A time-based one-time password generator as defined in RFC 6238.
+pub struct Totp {
+    secret: Vec<u8>,
+    digits: u32,
+    period: u64,
+    algorithm: hmac::Algorithm,
+}
+
+/// A one-time code and the seconds until it expires.
+#[derive(Serialize, Clone, Debug, PartialEq)]
+pub struct TotpCode {
+    pub code: String,
+    pub remaining: u64,
+}
+
+impl Totp {
+    /// Parse a TOTP secret
+    ///
+    /// Accepts a base32 secret as shown by most services, spaces and lowercase letters are
+    /// ignored, or an `otpauth://totp/` URI from a QR code with optional `digits`, `period`
+    /// and `algorithm` parameters.
+    ///
+    /// # Arguments
+    ///
+    /// * `input` - The secret or URI
+    ///
+    /// # Returns
+    ///
+    /// The TOTP generator
+    ///
+    /// # Errors
+    ///
+    /// Returns an error if the secret is not valid base32 or the URI has invalid parameters
+    pub fn parse(input: &str) -> Result<Self, Box<dyn std::error::Error>> {
+        let input = input.trim();
+        let mut totp = Self {
+            secret: Vec::new(),
+            digits: DEFAULT_DIGITS,
+            period: DEFAULT_PERIOD,
+            algorithm: hmac::HMAC_SHA1_FOR_LEGACY_USE_ONLY,
+        };
+
+        let secret = match input.strip_prefix("otpauth://totp/") {
+            Some(uri) => {
+                let query = uri.split_once('?').map(|(_, query)| query).unwrap_or("");
+                let mut secret = None;
+                for (key, value) in query.split('&').filter_map(|p| p.split_once('=')) {
+                    match key.to_ascii_lowercase().as_str() {
+                        "secret" => secret = Some(value),
+                        "digits" => totp.digits = value.parse()?,
+                        "period" => totp.period = value.parse()?,
+                        "algorithm" => {
+                            totp.algorithm = match value.to_ascii_uppercase().as_str() {
+                                "SHA1" => hmac::HMAC_SHA1_FOR_LEGACY_USE_ONLY,
+                                "SHA256" => hmac::HMAC_SHA256,
+                                "SHA512" => hmac::HMAC_SHA512,
+                                _ => return Err("Unsupported TOTP algorithm".into()),
+                            }
+                        }
+                        _ => {}
+                    }
+                }
+                secret.ok_or("The URI contains no secret")?
+            }
+            None if input.starts_with("otpauth://") => {
+                return Err("Only time-based one-time passwords are supported".into())
+            }
+            None => input,
+        };
+
+        if !(6..=8).contains(&totp.digits) || totp.period == 0 {
+            return Err("Invalid TOTP parameters".into());
+        }
+        totp.secret = decode_base32(secret).ok_or("The secret is not valid base32")?;
+        if totp.secret.is_empty() {
+            return Err("The secret is empty".into());
+        }
+
+        Ok(totp)
+    }
+
+    /// Compute the code for a point in time
+    ///
+    /// # Arguments
+    ///
+    /// * `timestamp` - The seconds since the Unix epoch
+    ///
+    /// # Returns
+    ///
+    /// The code and the seconds it stays valid
+    pub fn code_at(&self, timestamp: u64) -> TotpCode {
+        let counter = timestamp / self.period;
+        let key = hmac::Key::new(self.algorithm, &self.secret);
+        let tag = hmac::sign(&key, &counter.to_be_bytes());
+        let hash = tag.as_ref();
+
+        let offset = (hash[hash.len() - 1] & 0x0f) as usize;
+        let binary = u32::from_be_bytes([
+            hash[offset] & 0x7f,
+            hash[offset + 1],
+            hash[offset + 2],
+            hash[offset + 3],
+        ]);
+        let code = binary % 10u32.pow(self.digits);
+
+        TotpCode {
+            code: format!("{:0width$}", code, width = self.digits as usize),
+            remaining: self.period - timestamp % self.period,
+        }
+    }
+}
+
+/// Decode base32 text without padding, ignoring spaces, dashes and case.
+fn decode_base32(input: &str) -> Option<Vec<u8>> {
+    let mut bytes = Vec::new();
+    let mut buffer = 0u32;
+    let mut bits = 0;
+
+    for c in input.chars().filter(|c| !matches!(c, ' ' | '-' | '=')) {
+        let value = BASE32_ALPHABET
+            .iter()
+            .position(|&a| a as char == c.to_ascii_uppercase())?;
+        buffer = (buffer << 5) | value as u32;
+        bits += 5;
+        if bits >= 8 {
+            bits -= 8;
+            bytes.push((buffer >> bits) as u8);
+            buffer &= (1 << bits) - 1;
+        }
+    }
+
+    Some(bytes)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_decode_base32() {
+        assert_eq!(decode_base32("MZXW6YTBOI").unwrap(), b"foobar");
+        assert_eq!(decode_base32("mzxw 6ytb oi======").unwrap(), b"foobar");
+        assert!(decode_base32("MZXW1").is_none());
+    }
+
+    #[test]
+    fn test_rfc6238_vectors() {
+        // The SHA1 secret of RFC 6238 is "12345678901234567890".
+        let totp =
+            Totp::parse("otpauth://totp/Test?secret=GEZDGNBVGY3TQOJQGEZDGNBVGY3TQOJQ&digits=8")
+                .unwrap();
+
+        assert_eq!(totp.code_at(59).code, "94287082");
+        assert_eq!(totp.code_at(1111111109).code, "07081804");
+        assert_eq!(totp.code_at(2000000000).code, "69279037");
+        assert_eq!(totp.code_at(59).remaining, 1);
+        assert_eq!(totp.code_at(60).remaining, 30);
+    }
+
+    #[test]
+    fn test_parse() {
+        let totp = Totp::parse("gezd gnbv gy3t qojq").unwrap();
+        assert_eq!(totp.code_at(59).code.len(), 6);
+
+        let totp = Totp::parse(
+            "otpauth://totp/Bank:me?secret=GEZDGNBVGY3TQOJQ&issuer=Bank&period=60&algorithm=SHA256",
+        )
+        .unwrap();
+        assert_eq!(totp.code_at(0).remaining, 60);
+
+        assert!(Totp::parse("").is_err());
+        assert!(Totp::parse("not base32!").is_err());
+        assert!(Totp::parse("otpauth://hotp/Bank?secret=GEZDGNBV&counter=1").is_err());
+        assert!(Totp::parse("otpauth://totp/Bank?secret=GEZDGNBV&digits=12").is_err());
+        assert!(Totp::parse("otpauth://totp/Bank?secret=GEZDGNBV&algorithm=MD5").is_err());
+    }
+}
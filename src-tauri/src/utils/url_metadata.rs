@@ -0,0 +1,209 @@
+use base64::{engine::general_purpose::STANDARD, Engine};
+use lazy_static::lazy_static;
+use regex::Regex;
+use reqwest::Url;
+use serde::Serialize;
+
+/// The maximum number of characters taken from the page title.
+const MAX_SERVICE_LENGTH: usize = 64;
+
+lazy_static! {
+    static ref TITLE: Regex = Regex::new(r"(?is)<title[^>]*>(.*?)</title>").unwrap();
+    static ref LINK: Regex = Regex::new(r"(?is)<link\b[^>]*>").unwrap();
+    static ref ATTRIBUTE: Regex =
+        Regex::new(r#"(?is)\b(rel|href)\s*=\s*(?:"([^"]*)"|'([^']*)'|([^\s>]+))"#).unwrap();
+}
+
+/// The values of a new entry derived from the URL of the service.
+#[derive(Debug, Clone, PartialEq, Serialize)]
+pub struct UrlMetadata {
+    pub url: String,
+    pub service: String,
+    /// The favicon of the page as data URL, None if it was not fetched.
+    pub icon: Option<String>,
+}
+
+impl UrlMetadata {
+    /// Derive the metadata from the URL alone
+    ///
+    /// The scheme defaults to https and the service is the host without `www.`.
+    ///
+    /// # Arguments
+    ///
+    /// * `input` - The URL as entered by the user
+    ///
+    /// # Returns
+    ///
+    /// The metadata of the URL
+    ///
+    /// # Errors
+    ///
+    /// Returns an error if the input is not a http or https URL with a host
+    pub fn from_url(input: &str) -> Result<Self, Box<dyn std::error::Error>> {
+        let input = input.trim();
+        let url = if input.contains("://") {
+            Url::parse(input)
+        } else {
+            Url::parse(&format!("https://{}", input))
+        }
+        .map_err(|_| format!("Invalid URL: {}", input))?;
+
+        if !matches!(url.scheme(), "http" | "https") {
+            return Err(format!("Unsupported URL scheme: {}", url.scheme()).into());
+        }
+        let host = url.host_str().ok_or("URL has no host")?;
+
+        Ok(Self {
+            service: host.trim_start_matches("www.").to_string(),
+            url: url.to_string(),
+            icon: None,
+        })
+    }
+
+    /// Use the title of the page as service name
+    ///
+    /// The host stays the service name if the page has no title.
+    ///
+    /// # Arguments
+    ///
+    /// * `html` - The HTML of the page
+    pub fn apply_title(&mut self, html: &str) {
+        let title = TITLE
+            .captures(html)
+            .map(|captures| decode_entities(&captures[1]))
+            .map(|title| title.split_whitespace().collect::<Vec<_>>().join(" "))
+            .unwrap_or_default();
+
+        if !title.is_empty() {
+            self.service = title.chars().take(MAX_SERVICE_LENGTH).collect();
+        }
+    }
+
+    /// Get the URL of the favicon of the page
+    ///
+    /// # Arguments
+    ///
+    /// * `html` - The HTML of the page
+    ///
+    /// # Returns
+    ///
+    /// The URL of the first icon linked by the page, otherwise `/favicon.ico` of the host
+    pub fn icon_url(&self, html: &str) -> Option<Url> {
+        let base = Url::parse(&self.url).ok()?;
+        let linked = LINK.find_iter(html).find_map(|link| {
+            let mut rel = None;
+            let mut href = None;
+            for captures in ATTRIBUTE.captures_iter(link.as_str()) {
+                let value = captures
+                    .get(2)
+                    .or(captures.get(3))
+                    .or(captures.get(4))
+                    .map(|value| decode_entities(value.as_str()));
+                match captures[1].to_ascii_lowercase().as_str() {
+                    "rel" => rel = value,
+                    _ => href = value,
+                }
+            }
+
+            rel.filter(|rel| {
+                rel.split_whitespace()
+                    .any(|token| token.eq_ignore_ascii_case("icon"))
+            })
+            .and(href)
+        });
+
+        base.join(linked.as_deref().unwrap_or("/favicon.ico")).ok()
+    }
+
+    /// Set the favicon from the downloaded image
+    ///
+    /// # Arguments
+    ///
+    /// * `content_type` - The content type of the download
+    /// * `bytes` - The image data
+    pub fn set_icon(&mut self, content_type: &str, bytes: &[u8]) {
+        let content_type = content_type.split(';').next().unwrap_or_default().trim();
+        if content_type.starts_with("image/") && !bytes.is_empty() {
+            self.icon = Some(format!(
+                "data:{};base64,{}",
+                content_type,
+                STANDARD.encode(bytes)
+            ));
+        }
+    }
+}
+
+/// Decode the HTML entities common in titles and attributes
+fn decode_entities(text: &str) -> String {
+    text.replace("&quot;", "\"")
+        .replace("&#39;", "'")
+        .replace("&#x27;", "'")
+        .replace("&lt;", "<")
+        .replace("&gt;", ">")
+        .replace("&nbsp;", " ")
+        .replace("&amp;", "&")
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_from_url() {
+        let metadata = UrlMetadata::from_url(" www.example.com/login ").unwrap();
+        assert_eq!(metadata.url, "https://www.example.com/login");
+        assert_eq!(metadata.service, "example.com");
+        assert_eq!(metadata.icon, None);
+
+        let metadata = UrlMetadata::from_url("http://intranet:8080").unwrap();
+        assert_eq!(metadata.service, "intranet");
+
+        assert!(UrlMetadata::from_url("ftp://example.com").is_err());
+        assert!(UrlMetadata::from_url("").is_err());
+    }
+
+    #[test]
+    fn test_apply_title() {
+        let mut metadata = UrlMetadata::from_url("example.com").unwrap();
+        metadata.apply_title("<html><head></head></html>");
+        assert_eq!(metadata.service, "example.com");
+
+        metadata.apply_title("<HEAD><Title lang=\"de\">\n  Tom &amp; Jerry\n  Shop </Title>");
+        assert_eq!(metadata.service, "Tom & Jerry Shop");
+    }
+
+    #[test]
+    fn test_icon_url() {
+        let metadata = UrlMetadata::from_url("https://example.com/account/login").unwrap();
+        assert_eq!(
+            metadata
+                .icon_url("<title>Example</title>")
+                .unwrap()
+                .as_str(),
+            "https://example.com/favicon.ico"
+        );
+
+        let html = r#"<link rel="stylesheet" href="/style.css">
+                      <link href='icons/fav.png' rel='shortcut icon'>"#;
+        assert_eq!(
+            metadata.icon_url(html).unwrap().as_str(),
+            "https://example.com/account/icons/fav.png"
+        );
+
+        let html = r#"<link rel=icon href="https://cdn.example.net/i.svg?a=1&amp;b=2">"#;
+        assert_eq!(
+            metadata.icon_url(html).unwrap().as_str(),
+            "https://cdn.example.net/i.svg?a=1&b=2"
+        );
+    }
+
+    #[test]
+    fn test_set_icon() {
+        let mut metadata = UrlMetadata::from_url("example.com").unwrap();
+        metadata.set_icon("text/html; charset=utf-8", b"<html>");
+        assert_eq!(metadata.icon, None);
+
+        metadata.set_icon("image/png", &[1, 2, 3]);
+        assert_eq!(metadata.icon.as_deref(), Some("data:image/png;base64,AQID"));
+    }
+}
@@ -0,0 +1,647 @@
+use std::error::Error;
+use std::path::Path;
+
+use super::database::{
+    Attachment, Database, ExpiringNote, Folder, GeneratedPassword, HealthSnapshot, Identity,
+    PasswordEntry, PaymentCard, SecureNote, Server, TableRows, Tag, User,
+};
+use super::{Encryption, KeyParams};
+
+/// The storage of a vault, so the password manager works on any backend.
+///
+/// The SQLCipher database is the default backend. Other backends, like an append-only
+/// file that is easier to sync, only need to implement this trait.
+pub trait VaultStorage: Send {
+    /// Get the encryption derived from the master password
+    fn encryption(&self) -> &Encryption;
+
+    /// Check if the vault only lives in memory and is discarded when dropped
+    fn is_in_memory(&self) -> bool;
+
+    /// Write pending changes through to the vault file
+    fn checkpoint(&self) -> Result<(), Box<dyn Error>>;
+
+    /// Encrypt the vault with a new master password or new key parameters
+    fn reencrypt(
+        &mut self,
+        master_password: &str,
+        params: &KeyParams,
+        on_progress: &mut dyn FnMut(usize, usize),
+    ) -> Result<(), Box<dyn Error>>;
+
+    /// Get the path of the vault file, its name is kept in full backups
+    fn path(&self) -> &Path;
+
+    /// Write an encrypted copy of the whole vault to a file for a full backup
+    fn create_dump(&self, path: &Path, master_password: &str) -> Result<(), Box<dyn Error>>;
+
+    /// Get the rows of the tables an incremental backup carries besides users and entries
+    fn increment_tables(&self) -> Result<Vec<TableRows>, Box<dyn Error>>;
+
+    /// Get all users
+    fn users(&self) -> Result<Vec<User>, Box<dyn Error>>;
+
+    /// Get a user by ID
+    fn user(&self, id: i32) -> Result<User, Box<dyn Error>>;
+
+    /// Add a new user
+    fn create_user(&self, user: &User) -> Result<(), Box<dyn Error>>;
+
+    /// Replace a stored user
+    fn update_user(&self, user: &User) -> Result<(), Box<dyn Error>>;
+
+    /// Get all password entries, including the trashed ones
+    fn entries(&self) -> Result<Vec<PasswordEntry>, Box<dyn Error>>;
+
+    /// Get a password entry by ID
+    fn entry(&self, id: i32) -> Result<PasswordEntry, Box<dyn Error>>;
+
+    /// Add a new password entry
+    fn create_entry(&self, entry: &PasswordEntry) -> Result<(), Box<dyn Error>>;
+
+    /// Add several password entries, either all or none of them, and get their IDs
+    fn create_entries(&self, entries: &[PasswordEntry]) -> Result<Vec<i32>, Box<dyn Error>>;
+
+    /// Replace a stored password entry
+    fn update_entry(&self, entry: &PasswordEntry) -> Result<(), Box<dyn Error>>;
+
+    /// Replace the encrypted recovery codes of an entry, regular updates keep them
+    fn update_recovery_codes(&self, id: i32, recovery_codes: &str) -> Result<(), Box<dyn Error>>;
+
+    /// Replace the encrypted TOTP secret of an entry, regular updates keep it
+    fn update_totp_secret(&self, id: i32, totp_secret: &str) -> Result<(), Box<dyn Error>>;
+
+    /// Lock or unlock an entry against changes
+    fn update_locked(&self, id: i32, locked: bool) -> Result<(), Box<dyn Error>>;
+
+    /// Move an entry to the trash or restore it
+    fn update_deleted_at(&self, id: i32, deleted_at: Option<&str>) -> Result<(), Box<dyn Error>>;
+
+    /// Set or remove the expiry date of an entry
+    fn update_expiry(&self, id: i32, expires_on: Option<&str>) -> Result<(), Box<dyn Error>>;
+
+    /// Permanently delete the trashed entries of a user and get their number
+    fn purge_trash(&self, user_id: i32, before: Option<&str>) -> Result<usize, Box<dyn Error>>;
+
+    /// Let a user read an entry of another user
+    fn grant_read_access(&self, entry_id: i32, user_id: i32) -> Result<(), Box<dyn Error>>;
+
+    /// Take back the access of a user to an entry
+    fn revoke_access(&self, entry_id: i32, user_id: i32) -> Result<(), Box<dyn Error>>;
+
+    /// Get the IDs of the entries shared with a user
+    fn shared_entry_ids(&self, user_id: i32) -> Result<Vec<i32>, Box<dyn Error>>;
+
+    /// Pin an entry for a user
+    fn pin_entry(&self, entry_id: i32, user_id: i32) -> Result<(), Box<dyn Error>>;
+
+    /// Unpin an entry for a user
+    fn unpin_entry(&self, entry_id: i32, user_id: i32) -> Result<(), Box<dyn Error>>;
+
+    /// Get the IDs of the entries a user pinned, oldest pin first
+    fn pinned_entry_ids(&self, user_id: i32) -> Result<Vec<i32>, Box<dyn Error>>;
+
+    /// Add a generated password to the history of a user, keeping at most `limit`
+    fn record_generated_password(
+        &self,
+        user_id: i32,
+        password: &str,
+        limit: usize,
+    ) -> Result<(), Box<dyn Error>>;
+
+    /// Get the generator history of a user, newest first
+    fn generator_history(&self, user_id: i32) -> Result<Vec<GeneratedPassword>, Box<dyn Error>>;
+
+    /// Remove the generator history of a user
+    fn clear_generator_history(&self, user_id: i32) -> Result<(), Box<dyn Error>>;
+
+    /// Store the health snapshot of a user for a day, replacing one of the same day
+    fn record_health_snapshot(
+        &self,
+        user_id: i32,
+        snapshot: &HealthSnapshot,
+    ) -> Result<(), Box<dyn Error>>;
+
+    /// Get the latest health snapshots of a user
+    fn get_health_history(
+        &self,
+        user_id: i32,
+        limit: usize,
+    ) -> Result<Vec<HealthSnapshot>, Box<dyn Error>>;
+
+    /// Get the folders of a user
+    fn folders(&self, user_id: i32) -> Result<Vec<Folder>, Box<dyn Error>>;
+
+    /// Add a folder and get its ID
+    fn create_folder(
+        &self,
+        user_id: i32,
+        name: &str,
+        parent_id: Option<i32>,
+    ) -> Result<i32, Box<dyn Error>>;
+
+    /// Rename a folder
+    fn rename_folder(&self, user_id: i32, id: i32, name: &str) -> Result<(), Box<dyn Error>>;
+
+    /// Delete a folder, its entries and subfolders move into its parent
+    fn delete_folder(&self, user_id: i32, id: i32) -> Result<(), Box<dyn Error>>;
+
+    /// Move an entry to a folder or to the top level
+    fn move_entry_to_folder(&self, id: i32, folder_id: Option<i32>) -> Result<(), Box<dyn Error>>;
+
+    /// Get the tags of a user
+    fn tags(&self, user_id: i32) -> Result<Vec<Tag>, Box<dyn Error>>;
+
+    /// Add a tag and get its ID
+    fn create_tag(&self, user_id: i32, name: &str) -> Result<i32, Box<dyn Error>>;
+
+    /// Rename a tag
+    fn rename_tag(&self, user_id: i32, id: i32, name: &str) -> Result<(), Box<dyn Error>>;
+
+    /// Delete a tag and remove it from all entries
+    fn delete_tag(&self, user_id: i32, id: i32) -> Result<(), Box<dyn Error>>;
+
+    /// Add a tag to an entry
+    fn add_entry_tag(&self, entry_id: i32, tag_id: i32) -> Result<(), Box<dyn Error>>;
+
+    /// Remove a tag from an entry
+    fn remove_entry_tag(&self, entry_id: i32, tag_id: i32) -> Result<(), Box<dyn Error>>;
+
+    /// Get the expiring notes of an entry
+    fn expiring_notes(&self, entry_id: i32) -> Result<Vec<ExpiringNote>, Box<dyn Error>>;
+
+    /// Add an expiring note to an entry and get its ID
+    fn create_expiring_note(
+        &self,
+        entry_id: i32,
+        content: &str,
+        expires_on: &str,
+    ) -> Result<i32, Box<dyn Error>>;
+
+    /// Delete an expiring note of an entry
+    fn delete_expiring_note(&self, entry_id: i32, id: i32) -> Result<(), Box<dyn Error>>;
+
+    /// Redact the content of the expired notes of a user and get their number
+    fn redact_expiring_notes(&self, user_id: i32, today: &str) -> Result<usize, Box<dyn Error>>;
+
+    /// Get the secure notes of a user
+    fn secure_notes(&self, user_id: i32) -> Result<Vec<SecureNote>, Box<dyn Error>>;
+
+    /// Create a secure note for a user and get its ID
+    fn create_secure_note(
+        &self,
+        user_id: i32,
+        title: &str,
+        content: &str,
+    ) -> Result<i32, Box<dyn Error>>;
+
+    /// Replace the title and content of a secure note of a user
+    fn update_secure_note(
+        &self,
+        user_id: i32,
+        id: i32,
+        title: &str,
+        content: &str,
+    ) -> Result<(), Box<dyn Error>>;
+
+    /// Delete a secure note of a user
+    fn delete_secure_note(&self, user_id: i32, id: i32) -> Result<(), Box<dyn Error>>;
+
+    /// Get the payment cards of a user
+    fn payment_cards(&self, user_id: i32) -> Result<Vec<PaymentCard>, Box<dyn Error>>;
+
+    /// Create a payment card for a user and get its ID
+    fn create_payment_card(&self, user_id: i32, card: &PaymentCard) -> Result<i32, Box<dyn Error>>;
+
+    /// Replace the fields of a payment card of a user
+    fn update_payment_card(&self, user_id: i32, card: &PaymentCard) -> Result<(), Box<dyn Error>>;
+
+    /// Delete a payment card of a user
+    fn delete_payment_card(&self, user_id: i32, id: i32) -> Result<(), Box<dyn Error>>;
+
+    /// Get the identities of a user
+    fn identities(&self, user_id: i32) -> Result<Vec<Identity>, Box<dyn Error>>;
+
+    /// Create an identity for a user and get its ID
+    fn create_identity(&self, user_id: i32, identity: &Identity) -> Result<i32, Box<dyn Error>>;
+
+    /// Replace the fields of an identity of a user
+    fn update_identity(&self, user_id: i32, identity: &Identity) -> Result<(), Box<dyn Error>>;
+
+    /// Delete an identity of a user
+    fn delete_identity(&self, user_id: i32, id: i32) -> Result<(), Box<dyn Error>>;
+
+    /// Get the servers of a user
+    fn servers(&self, user_id: i32) -> Result<Vec<Server>, Box<dyn Error>>;
+
+    /// Create a server for a user and get its ID
+    fn create_server(&self, user_id: i32, server: &Server) -> Result<i32, Box<dyn Error>>;
+
+    /// Replace the fields of a server of a user
+    fn update_server(&self, user_id: i32, server: &Server) -> Result<(), Box<dyn Error>>;
+
+    /// Delete a server of a user
+    fn delete_server(&self, user_id: i32, id: i32) -> Result<(), Box<dyn Error>>;
+
+    /// Get the attachments of an entry without their content
+    fn attachments(&self, entry_id: i32) -> Result<Vec<Attachment>, Box<dyn Error>>;
+
+    /// Get the file name and the encrypted content of an attachment of an entry
+    fn attachment_content(
+        &self,
+        entry_id: i32,
+        id: i32,
+    ) -> Result<(String, String), Box<dyn Error>>;
+
+    /// Attach a file to an entry and get its ID
+    fn create_attachment(
+        &self,
+        entry_id: i32,
+        file_name: &str,
+        size: i64,
+        content: &str,
+    ) -> Result<i32, Box<dyn Error>>;
+
+    /// Delete an attachment of an entry
+    fn delete_attachment(&self, entry_id: i32, id: i32) -> Result<(), Box<dyn Error>>;
+}
+
+impl VaultStorage for Database {
+    fn encryption(&self) -> &Encryption {
+        &self.encryption
+    }
+
+    fn is_in_memory(&self) -> bool {
+        Database::is_in_memory(self)
+    }
+
+    fn checkpoint(&self) -> Result<(), Box<dyn Error>> {
+        Database::checkpoint(self)
+    }
+
+    fn reencrypt(
+        &mut self,
+        master_password: &str,
+        params: &KeyParams,
+        on_progress: &mut dyn FnMut(usize, usize),
+    ) -> Result<(), Box<dyn Error>> {
+        Database::reencrypt(self, master_password, params, on_progress)
+    }
+
+    fn path(&self) -> &Path {
+        &self.path
+    }
+
+    fn create_dump(&self, path: &Path, master_password: &str) -> Result<(), Box<dyn Error>> {
+        Database::create_dump(self, path, master_password)
+    }
+
+    fn increment_tables(&self) -> Result<Vec<TableRows>, Box<dyn Error>> {
+        Database::increment_tables(self)
+    }
+
+    fn users(&self) -> Result<Vec<User>, Box<dyn Error>> {
+        self.read_all::<User>()
+    }
+
+    fn user(&self, id: i32) -> Result<User, Box<dyn Error>> {
+        self.read_by_id::<User>(id)
+    }
+
+    fn create_user(&self, user: &User) -> Result<(), Box<dyn Error>> {
+        self.create(user)
+    }
+
+    fn update_user(&self, user: &User) -> Result<(), Box<dyn Error>> {
+        self.update(user)
+    }
+
+    fn entries(&self) -> Result<Vec<PasswordEntry>, Box<dyn Error>> {
+        self.read_all::<PasswordEntry>()
+    }
+
+    fn entry(&self, id: i32) -> Result<PasswordEntry, Box<dyn Error>> {
+        self.read_by_id::<PasswordEntry>(id)
+    }
+
+    fn create_entry(&self, entry: &PasswordEntry) -> Result<(), Box<dyn Error>> {
+        self.create(entry)
+    }
+
+    fn create_entries(&self, entries: &[PasswordEntry]) -> Result<Vec<i32>, Box<dyn Error>> {
+        self.create_all(entries)
+    }
+
+    fn update_entry(&self, entry: &PasswordEntry) -> Result<(), Box<dyn Error>> {
+        self.update(entry)
+    }
+
+    fn update_recovery_codes(&self, id: i32, recovery_codes: &str) -> Result<(), Box<dyn Error>> {
+        Database::update_recovery_codes(self, id, recovery_codes)
+    }
+
+    fn update_totp_secret(&self, id: i32, totp_secret: &str) -> Result<(), Box<dyn Error>> {
+        Database::update_totp_secret(self, id, totp_secret)
+    }
+
+    fn update_locked(&self, id: i32, locked: bool) -> Result<(), Box<dyn Error>> {
+        Database::update_locked(self, id, locked)
+    }
+
+    fn update_deleted_at(&self, id: i32, deleted_at: Option<&str>) -> Result<(), Box<dyn Error>> {
+        Database::update_deleted_at(self, id, deleted_at)
+    }
+
+    fn update_expiry(&self, id: i32, expires_on: Option<&str>) -> Result<(), Box<dyn Error>> {
+        Database::update_expiry(self, id, expires_on)
+    }
+
+    fn purge_trash(&self, user_id: i32, before: Option<&str>) -> Result<usize, Box<dyn Error>> {
+        Database::purge_trash(self, user_id, before)
+    }
+
+    fn grant_read_access(&self, entry_id: i32, user_id: i32) -> Result<(), Box<dyn Error>> {
+        Database::grant_read_access(self, entry_id, user_id)
+    }
+
+    fn revoke_access(&self, entry_id: i32, user_id: i32) -> Result<(), Box<dyn Error>> {
+        Database::revoke_access(self, entry_id, user_id)
+    }
+
+    fn shared_entry_ids(&self, user_id: i32) -> Result<Vec<i32>, Box<dyn Error>> {
+        Database::shared_entry_ids(self, user_id)
+    }
+
+    fn pin_entry(&self, entry_id: i32, user_id: i32) -> Result<(), Box<dyn Error>> {
+        Database::pin_entry(self, entry_id, user_id)
+    }
+
+    fn unpin_entry(&self, entry_id: i32, user_id: i32) -> Result<(), Box<dyn Error>> {
+        Database::unpin_entry(self, entry_id, user_id)
+    }
+
+    fn pinned_entry_ids(&self, user_id: i32) -> Result<Vec<i32>, Box<dyn Error>> {
+        Database::pinned_entry_ids(self, user_id)
+    }
+
+    fn record_generated_password(
+        &self,
+        user_id: i32,
+        password: &str,
+        limit: usize,
+    ) -> Result<(), Box<dyn Error>> {
+        Database::record_generated_password(self, user_id, password, limit)
+    }
+
+    fn generator_history(&self, user_id: i32) -> Result<Vec<GeneratedPassword>, Box<dyn Error>> {
+        Database::generator_history(self, user_id)
+    }
+
+    fn clear_generator_history(&self, user_id: i32) -> Result<(), Box<dyn Error>> {
+        Database::clear_generator_history(self, user_id)
+    }
+
+    fn record_health_snapshot(
+        &self,
+        user_id: i32,
+        snapshot: &HealthSnapshot,
+    ) -> Result<(), Box<dyn Error>> {
+        Database::record_health_snapshot(self, user_id, snapshot)
+    }
+
+    fn get_health_history(
+        &self,
+        user_id: i32,
+        limit: usize,
+    ) -> Result<Vec<HealthSnapshot>, Box<dyn Error>> {
+        Database::get_health_history(self, user_id, limit)
+    }
+
+    fn folders(&self, user_id: i32) -> Result<Vec<Folder>, Box<dyn Error>> {
+        Database::folders(self, user_id)
+    }
+
+    fn create_folder(
+        &self,
+        user_id: i32,
+        name: &str,
+        parent_id: Option<i32>,
+    ) -> Result<i32, Box<dyn Error>> {
+        Database::create_folder(self, user_id, name, parent_id)
+    }
+
+    fn rename_folder(&self, user_id: i32, id: i32, name: &str) -> Result<(), Box<dyn Error>> {
+        Database::rename_folder(self, user_id, id, name)
+    }
+
+    fn delete_folder(&self, user_id: i32, id: i32) -> Result<(), Box<dyn Error>> {
+        Database::delete_folder(self, user_id, id)
+    }
+
+    fn move_entry_to_folder(&self, id: i32, folder_id: Option<i32>) -> Result<(), Box<dyn Error>> {
+        Database::move_entry_to_folder(self, id, folder_id)
+    }
+
+    fn tags(&self, user_id: i32) -> Result<Vec<Tag>, Box<dyn Error>> {
+        Database::tags(self, user_id)
+    }
+
+    fn create_tag(&self, user_id: i32, name: &str) -> Result<i32, Box<dyn Error>> {
+        Database::create_tag(self, user_id, name)
+    }
+
+    fn rename_tag(&self, user_id: i32, id: i32, name: &str) -> Result<(), Box<dyn Error>> {
+        Database::rename_tag(self, user_id, id, name)
+    }
+
+    fn delete_tag(&self, user_id: i32, id: i32) -> Result<(), Box<dyn Error>> {
+        Database::delete_tag(self, user_id, id)
+    }
+
+    fn add_entry_tag(&self, entry_id: i32, tag_id: i32) -> Result<(), Box<dyn Error>> {
+        Database::add_entry_tag(self, entry_id, tag_id)
+    }
+
+    fn remove_entry_tag(&self, entry_id: i32, tag_id: i32) -> Result<(), Box<dyn Error>> {
+        Database::remove_entry_tag(self, entry_id, tag_id)
+    }
+
+    fn expiring_notes(&self, entry_id: i32) -> Result<Vec<ExpiringNote>, Box<dyn Error>> {
+        Database::expiring_notes(self, entry_id)
+    }
+
+    fn create_expiring_note(
+        &self,
+        entry_id: i32,
+        content: &str,
+        expires_on: &str,
+    ) -> Result<i32, Box<dyn Error>> {
+        Database::create_expiring_note(self, entry_id, content, expires_on)
+    }
+
+    fn delete_expiring_note(&self, entry_id: i32, id: i32) -> Result<(), Box<dyn Error>> {
+        Database::delete_expiring_note(self, entry_id, id)
+    }
+
+    fn redact_expiring_notes(&self, user_id: i32, today: &str) -> Result<usize, Box<dyn Error>> {
+        Database::redact_expiring_notes(self, user_id, today)
+    }
+
+    fn secure_notes(&self, user_id: i32) -> Result<Vec<SecureNote>, Box<dyn Error>> {
+        Database::secure_notes(self, user_id)
+    }
+
+    fn create_secure_note(
+        &self,
+        user_id: i32,
+        title: &str,
+        content: &str,
+    ) -> Result<i32, Box<dyn Error>> {
+        Database::create_secure_note(self, user_id, title, content)
+    }
+
+    fn update_secure_note(
+        &self,
+        user_id: i32,
+        id: i32,
+        title: &str,
+        content: &str,
+    ) -> Result<(), Box<dyn Error>> {
+        Database::update_secure_note(self, user_id, id, title, content)
+    }
+
+    fn delete_secure_note(&self, user_id: i32, id: i32) -> Result<(), Box<dyn Error>> {
+        Database::delete_secure_note(self, user_id, id)
+    }
+
+    fn payment_cards(&self, user_id: i32) -> Result<Vec<PaymentCard>, Box<dyn Error>> {
+        Database::payment_cards(self, user_id)
+    }
+
+    fn create_payment_card(&self, user_id: i32, card: &PaymentCard) -> Result<i32, Box<dyn Error>> {
+        Database::create_payment_card(self, user_id, card)
+    }
+
+    fn update_payment_card(&self, user_id: i32, card: &PaymentCard) -> Result<(), Box<dyn Error>> {
+        Database::update_payment_card(self, user_id, card)
+    }
+
+    fn delete_payment_card(&self, user_id: i32, id: i32) -> Result<(), Box<dyn Error>> {
+        Database::delete_payment_card(self, user_id, id)
+    }
+
+    fn identities(&self, user_id: i32) -> Result<Vec<Identity>, Box<dyn Error>> {
+        Database::identities(self, user_id)
+    }
+
+    fn create_identity(&self, user_id: i32, identity: &Identity) -> Result<i32, Box<dyn Error>> {
+        Database::create_identity(self, user_id, identity)
+    }
+
+    fn update_identity(&self, user_id: i32, identity: &Identity) -> Result<(), Box<dyn Error>> {
+        Database::update_identity(self, user_id, identity)
+    }
+
+    fn delete_identity(&self, user_id: i32, id: i32) -> Result<(), Box<dyn Error>> {
+        Database::delete_identity(self, user_id, id)
+    }
+
+    fn servers(&self, user_id: i32) -> Result<Vec<Server>, Box<dyn Error>> {
+        Database::servers(self, user_id)
+    }
+
+    fn create_server(&self, user_id: i32, server: &Server) -> Result<i32, Box<dyn Error>> {
+        Database::create_server(self, user_id, server)
+    }
+
+    fn update_server(&self, user_id: i32, server: &Server) -> Result<(), Box<dyn Error>> {
+        Database::update_server(self, user_id, server)
+    }
+
+    fn delete_server(&self, user_id: i32, id: i32) -> Result<(), Box<dyn Error>> {
+        Database::delete_server(self, user_id, id)
+    }
+
+    fn attachments(&self, entry_id: i32) -> Result<Vec<Attachment>, Box<dyn Error>> {
+        Database::attachments(self, entry_id)
+    }
+
+    fn attachment_content(
+        &self,
+        entry_id: i32,
+        id: i32,
+    ) -> Result<(String, String), Box<dyn Error>> {
+        Database::attachment_content(self, entry_id, id)
+    }
+
+    fn create_attachment(
+        &self,
+        entry_id: i32,
+        file_name: &str,
+        size: i64,
+        content: &str,
+    ) -> Result<i32, Box<dyn Error>> {
+        Database::create_attachment(self, entry_id, file_name, size, content)
+    }
+
+    fn delete_attachment(&self, entry_id: i32, id: i32) -> Result<(), Box<dyn Error>> {
+        Database::delete_attachment(self, entry_id, id)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use tempfile::TempDir;
+
+    #[test]
+    fn test_database_as_vault_storage() {
+        let temp_dir = TempDir::new().unwrap();
+        let db = Database::new(
+            temp_dir.path().join("test.db"),
+            "test_password",
+            &KeyParams::legacy([0u8; 16]),
+        )
+        .unwrap();
+        let storage: &dyn VaultStorage = &db;
+        storage
+            .create_user(&User {
+                id: None,
+                username: "user".to_string(),
+                master_key: Vec::new(),
+                created_at: String::new(),
+                last_login: String::new(),
+            })
+            .unwrap();
+        assert_eq!(storage.users().unwrap().len(), 1);
+
+        let entry = PasswordEntry::new(
+            1,
+            "Service".to_string(),
+            "user".to_string(),
+            "password".to_string(),
+            "https://example.com".to_string(),
+            String::new(),
+        );
+        let ids = storage
+            .create_entries(&[entry.clone(), entry.clone()])
+            .unwrap();
+        assert_eq!(ids.len(), 2);
+
+        let mut stored = storage.entry(ids[0]).unwrap();
+        assert_eq!(stored.service, "Service");
+        stored.service = "Renamed".to_string();
+        storage.update_entry(&stored).unwrap();
+
+        let entries = storage.entries().unwrap();
+        assert_eq!(entries.len(), 2);
+        assert!(entries.iter().any(|e| e.service == "Renamed"));
+
+        let folder_id = storage.create_folder(1, "Work", None).unwrap();
+        storage
+            .move_entry_to_folder(ids[1], Some(folder_id))
+            .unwrap();
+        assert_eq!(storage.entry(ids[1]).unwrap().folder_id, Some(folder_id));
+        assert!(!storage.is_in_memory());
+    }
+}
@@ -1,49 +1,118 @@
 use leptos::*;
+use std::time::Duration;
 use wasm_bindgen::prelude::*;
 use web_sys::Node;
 
 use crate::components::icons::Icon;
-use crate::components::password_health::Dashboard;
-use crate::components::password_manager::PasswordManager;
-use crate::components::settings::SettingsPanel;
+use crate::components::password_health::{Dashboard, ScoreBadge};
+use crate::components::password_manager::{
+    PasswordManager, QuickSearch, SecureNotes, Servers, Wallet,
+};
+use crate::components::settings::{SettingsPanel, SnapshotView};
+use crate::components::utils::provide_view_state;
 
-use super::components::auth::Login;
+use super::components::auth::{BrowserAuthorization, Login, SessionWarning, WelcomeBack};
 use super::components::info::{About, Guide, InfoModal, License};
 use super::components::password_generator::PasswordGenerator;
-use super::components::setup::Init;
+use super::components::setup::{BrowserImport, BrowserProfile, Init, RepairAssistant};
 
 #[wasm_bindgen]
 extern "C" {
     #[wasm_bindgen(js_namespace = ["window", "__TAURI__", "core"])]
     pub async fn invoke(cmd: &str, args: JsValue) -> JsValue;
+
+    #[wasm_bindgen(js_namespace = ["window", "__TAURI__", "event"])]
+    pub async fn listen(event: &str, handler: &Closure<dyn FnMut(JsValue)>) -> JsValue;
 }
 
+/// How often user activity is reported to the backend session at most.
+const ACTIVITY_REPORT_INTERVAL: Duration = Duration::from_secs(30);
+
 #[derive(Clone, Copy, PartialEq)]
 enum DashboardTab {
     Dashboard,
     Passwords,
+    Notes,
+    Wallet,
+    Servers,
     Generator,
 }
 
+impl DashboardTab {
+    fn as_str(&self) -> &'static str {
+        match self {
+            DashboardTab::Dashboard => "dashboard",
+            DashboardTab::Passwords => "passwords",
+            DashboardTab::Notes => "notes",
+            DashboardTab::Wallet => "wallet",
+            DashboardTab::Servers => "servers",
+            DashboardTab::Generator => "generator",
+        }
+    }
+}
+
+#[derive(Clone, Copy, serde::Deserialize)]
+struct SessionTimeout {
+    seconds: u64,
+    extendable: bool,
+}
+
+#[derive(serde::Deserialize)]
+struct SessionExpiringEvent {
+    payload: SessionTimeout,
+}
+
+#[derive(serde::Serialize)]
+struct SaveLastViewArgs {
+    tab: String,
+}
+
+#[derive(serde::Deserialize)]
+struct WindowPin {
+    always_on_top: bool,
+}
+
+#[derive(serde::Serialize)]
+struct AlwaysOnTopArgs {
+    enable: bool,
+}
+
 #[component]
 pub fn App() -> impl IntoView {
     let (is_initialized, set_is_initialized) = create_signal(false);
+    let (needs_repair, set_needs_repair) = create_signal(false);
     let (is_authenticated, set_is_authenticated) = create_signal(false);
+    let (is_demo, set_is_demo) = create_signal(false);
+    let (show_welcome, set_show_welcome) = create_signal(false);
+    let (session_warning, set_session_warning) = create_signal(None::<SessionTimeout>);
     let (current_tab, set_current_tab) = create_signal(DashboardTab::Dashboard);
     let (show_about, set_show_about) = create_signal(false);
     let (show_guide, set_show_guide) = create_signal(false);
     let (show_license, set_show_license) = create_signal(false);
     let (show_settings, set_show_settings) = create_signal(false);
     let (info_dropdown, set_info_dropdown) = create_signal(false);
+    let (always_on_top, set_always_on_top) = create_signal(false);
+    let (browser_import, set_browser_import) = create_signal(Vec::<BrowserProfile>::new());
     let info_dropdown_ref = create_node_ref::<html::Div>();
+    let view_state = provide_view_state();
+
+    create_effect(move |_| {
+        if !is_authenticated.get() {
+            view_state.reset();
+        }
+    });
 
     let info_icon = create_memo(move |_| "information-circle");
     let settings_icon = create_memo(move |_| "cog-6-tooth");
+    let pin_icon = create_memo(move |_| "bookmark");
     let logout_icon = create_memo(move |_| "arrow-left-start-on-rectangle");
     let about_icon = create_memo(move |_| "information-circle");
     let guide_icon = create_memo(move |_| "book-open");
     let license_icon = create_memo(move |_| "document-text");
     let passwords_icon = create_memo(move |_| "key");
+    let notes_icon = create_memo(move |_| "document-text");
+    let wallet_icon = create_memo(move |_| "identification");
+    let servers_icon = create_memo(move |_| "server-stack");
     let generator_icon = create_memo(move |_| "sparkles");
     let dashboard_icon = create_memo(move |_| "chart-pie");
 
@@ -59,50 +128,152 @@ pub fn App() -> impl IntoView {
         }
     });
 
+    // Activity only marks the session as used, the interval reports it at most every
+    // ACTIVITY_REPORT_INTERVAL. A hidden window does not count as being used.
+    let activity_seen = store_value(false);
+    let mark_activity = move || {
+        if !document().hidden() {
+            activity_seen.set_value(true);
+        }
+    };
+    window_event_listener(ev::mousemove, move |_| mark_activity());
+    window_event_listener(ev::mousedown, move |_| mark_activity());
+    window_event_listener(ev::keydown, move |_| mark_activity());
+    window_event_listener(ev::wheel, move |_| mark_activity());
+    window_event_listener(ev::touchstart, move |_| mark_activity());
+
+    if let Ok(handle) = set_interval_with_handle(
+        move || {
+            if !activity_seen.get_value()
+                || document().hidden()
+                || !is_authenticated.get_untracked()
+                || is_demo.get_untracked()
+            {
+                return;
+            }
+            activity_seen.set_value(false);
+            spawn_local(async move {
+                let response = invoke("extend_session", JsValue::NULL).await;
+                if serde_wasm_bindgen::from_value::<()>(response).is_ok()
+                    && session_warning.with_untracked(|w| w.is_some_and(|w| w.extendable))
+                {
+                    set_session_warning.set(None);
+                }
+            });
+        },
+        ACTIVITY_REPORT_INTERVAL,
+    ) {
+        on_cleanup(move || handle.clear());
+    }
+
+    spawn_local(async move {
+        let closure = Closure::wrap(Box::new(move |_: JsValue| {
+            set_session_warning.set(None);
+            set_is_authenticated.set(false);
+        }) as Box<dyn FnMut(JsValue)>);
+
+        listen("vault-locked", &closure).await;
+        closure.forget();
+    });
+
     spawn_local(async move {
-        let response = invoke("check_is_initialized", JsValue::NULL).await;
-        if let Ok(initialized) = serde_wasm_bindgen::from_value::<bool>(response) {
-            set_is_initialized.set(initialized);
+        let closure = Closure::wrap(Box::new(move |event: JsValue| {
+            if let Ok(event) = serde_wasm_bindgen::from_value::<SessionExpiringEvent>(event) {
+                set_session_warning.set(Some(event.payload));
+            }
+        }) as Box<dyn FnMut(JsValue)>);
+
+        listen("session-expiring", &closure).await;
+        closure.forget();
+    });
+
+    spawn_local(async move {
+        let response = invoke("get_window_pin", JsValue::NULL).await;
+        if let Ok(pin) = serde_wasm_bindgen::from_value::<WindowPin>(response) {
+            set_always_on_top.set(pin.always_on_top);
         }
     });
 
-    create_effect(move |_| {
-        if is_authenticated.get() {
-            let check_interval = 30_000;
-
-            let closure = Closure::wrap(Box::new(move || {
-                spawn_local(async move {
-                    let response = invoke("check_users_session", JsValue::NULL).await;
-                    if let Ok(is_valid) = serde_wasm_bindgen::from_value::<bool>(response) {
-                        if !is_valid {
-                            let window = web_sys::window().unwrap();
-                            window.location().reload().unwrap();
-                        }
-                    }
-                });
-            }) as Box<dyn FnMut()>);
+    let toggle_always_on_top = move || {
+        let enable = !always_on_top.get_untracked();
+        spawn_local(async move {
+            let args = serde_wasm_bindgen::to_value(&AlwaysOnTopArgs { enable }).unwrap();
+            let response = invoke("set_always_on_top", args).await;
+            if serde_wasm_bindgen::from_value::<()>(response).is_ok() {
+                set_always_on_top.set(enable);
+            }
+        });
+    };
 
-            let window = web_sys::window().unwrap();
-            window
-                .set_interval_with_callback_and_timeout_and_arguments_0(
-                    closure.as_ref().unchecked_ref(),
-                    check_interval,
-                )
-                .unwrap();
+    let load_initialized = move || {
+        spawn_local(async move {
+            let response = invoke("check_is_initialized", JsValue::NULL).await;
+            if let Ok(initialized) = serde_wasm_bindgen::from_value::<bool>(response) {
+                set_is_initialized.set(initialized);
+            }
+        });
+    };
 
-            closure.forget();
+    spawn_local(async move {
+        let response = invoke("get_startup_issues", JsValue::NULL).await;
+        if js_sys::Array::is_array(&response) && js_sys::Array::from(&response).length() > 0 {
+            set_needs_repair.set(true);
+        } else {
+            load_initialized();
         }
     });
 
     let on_auth_success = move |_| {
         set_is_authenticated.set(true);
+        set_show_welcome.set(true);
+        spawn_local(async move {
+            let response = invoke("get_launch_view", JsValue::NULL).await;
+            if let Ok(tab) = serde_wasm_bindgen::from_value::<String>(response) {
+                set_current_tab.set(match tab.as_str() {
+                    "passwords" => DashboardTab::Passwords,
+                    "notes" => DashboardTab::Notes,
+                    "wallet" => DashboardTab::Wallet,
+                    "servers" => DashboardTab::Servers,
+                    "generator" => DashboardTab::Generator,
+                    _ => DashboardTab::Dashboard,
+                });
+            }
+        });
     };
 
-    let on_logout = move |_| {
+    create_effect(move |_| {
+        let tab = current_tab.get();
+        if !is_authenticated.get_untracked() || is_demo.get_untracked() {
+            return;
+        }
+        spawn_local(async move {
+            let args = serde_wasm_bindgen::to_value(&SaveLastViewArgs {
+                tab: tab.as_str().to_string(),
+            })
+            .unwrap();
+            invoke("save_last_view", args).await;
+        });
+    });
+
+    let on_logout = move || {
         spawn_local(async move {
             let response = invoke("logout", JsValue::NULL).await;
             if serde_wasm_bindgen::from_value::<()>(response).is_ok() {
+                set_show_welcome.set(false);
+                set_session_warning.set(None);
                 set_is_authenticated.set(false);
+                set_is_demo.set(false);
+            }
+        });
+    };
+
+    let on_demo = move |_| {
+        spawn_local(async move {
+            let response = invoke("start_demo", JsValue::NULL).await;
+            if serde_wasm_bindgen::from_value::<()>(response).is_ok() {
+                set_is_demo.set(true);
+                set_current_tab.set(DashboardTab::Dashboard);
+                set_is_authenticated.set(true);
             }
         });
     };
@@ -110,12 +281,23 @@ pub fn App() -> impl IntoView {
     view! {
         <div class="min-h-screen bg-background">
             {move || {
-                if !is_initialized.get() {
+                if needs_repair.get() {
                     view! {
-                        <Init
+                        <RepairAssistant
                             on_complete=move |_| {
+                                set_needs_repair.set(false);
+                                load_initialized();
+                            }
+                        />
+                    }.into_view()
+                } else if !is_initialized.get() && !is_demo.get() {
+                    view! {
+                        <Init
+                            on_complete=move |profiles| {
+                                set_browser_import.set(profiles);
                                 set_is_initialized.set(true);
                             }
+                            on_demo=on_demo
                         />
                     }.into_view()
                 } else if is_authenticated.get() {
@@ -153,6 +335,48 @@ pub fn App() -> impl IntoView {
                                                 "Passwörter"
                                             </button>
 
+                                            <button
+                                                class=move || format!("inline-flex items-center px-1 pt-1 border-b-2 text-sm font-medium {}",
+                                                    if current_tab.get() == DashboardTab::Notes {
+                                                        "border-primary-100 text-white"
+                                                    } else {
+                                                        "border-transparent text-gray-400 hover:text-gray-300 hover:border-gray-600"
+                                                    }
+                                                )
+                                                on:click=move |_| set_current_tab.set(DashboardTab::Notes)
+                                            >
+                                                <Icon icon=notes_icon.into() class="w-5 h-5 mr-2" />
+                                                "Notizen"
+                                            </button>
+
+                                            <button
+                                                class=move || format!("inline-flex items-center px-1 pt-1 border-b-2 text-sm font-medium {}",
+                                                    if current_tab.get() == DashboardTab::Wallet {
+                                                        "border-primary-100 text-white"
+                                                    } else {
+                                                        "border-transparent text-gray-400 hover:text-gray-300 hover:border-gray-600"
+                                                    }
+                                                )
+                                                on:click=move |_| set_current_tab.set(DashboardTab::Wallet)
+                                            >
+                                                <Icon icon=wallet_icon.into() class="w-5 h-5 mr-2" />
+                                                "Wallet"
+                                            </button>
+
+                                            <button
+                                                class=move || format!("inline-flex items-center px-1 pt-1 border-b-2 text-sm font-medium {}",
+                                                    if current_tab.get() == DashboardTab::Servers {
+                                                        "border-primary-100 text-white"
+                                                    } else {
+                                                        "border-transparent text-gray-400 hover:text-gray-300 hover:border-gray-600"
+                                                    }
+                                                )
+                                                on:click=move |_| set_current_tab.set(DashboardTab::Servers)
+                                            >
+                                                <Icon icon=servers_icon.into() class="w-5 h-5 mr-2" />
+                                                "Server"
+                                            </button>
+
                                             <button
                                                 class=move || format!("inline-flex items-center px-1 pt-1 border-b-2 text-sm font-medium {}",
                                                     if current_tab.get() == DashboardTab::Generator {
@@ -169,6 +393,7 @@ pub fn App() -> impl IntoView {
                                         </div>
                                     </div>
                                     <div class="flex items-center space-x-6">
+                                        <ScoreBadge on_click=move |_| set_current_tab.set(DashboardTab::Dashboard) />
                                         <div class="relative">
                                             <button
                                                 class="group relative flex items-center text-gray-400 hover:text-white transition-colors"
@@ -236,20 +461,45 @@ pub fn App() -> impl IntoView {
                                             }}
                                         </div>
                                         <button
-                                            class="group relative flex items-center text-gray-400 hover:text-white transition-colors"
-                                            on:click=move |_| set_show_settings.set(true)
+                                            class=move || format!(
+                                                "group relative flex items-center hover:text-white transition-colors {}",
+                                                if always_on_top.get() { "text-primary-100" } else { "text-gray-400" }
+                                            )
+                                            on:click=move |_| toggle_always_on_top()
                                         >
                                             <Icon
-                                                icon=settings_icon.into()
+                                                icon=pin_icon.into()
                                                 class="w-5 h-5"
                                             />
                                             <span class="ml-2 whitespace-nowrap max-w-0 overflow-hidden group-hover:max-w-[100px] transition-all duration-300 text-primary-100">
-                                                "Einstellungen"
+                                                "Im Vordergrund"
                                             </span>
                                         </button>
+                                        {move || if is_demo.get() {
+                                            view! {
+                                                <span class="text-sm text-primary-100 border border-primary-100 rounded px-2 py-0.5">
+                                                    "Demo"
+                                                </span>
+                                            }.into_view()
+                                        } else {
+                                            view! {
+                                                <button
+                                                    class="group relative flex items-center text-gray-400 hover:text-white transition-colors"
+                                                    on:click=move |_| set_show_settings.set(true)
+                                                >
+                                                    <Icon
+                                                        icon=settings_icon.into()
+                                                        class="w-5 h-5"
+                                                    />
+                                                    <span class="ml-2 whitespace-nowrap max-w-0 overflow-hidden group-hover:max-w-[100px] transition-all duration-300 text-primary-100">
+                                                        "Einstellungen"
+                                                    </span>
+                                                </button>
+                                            }.into_view()
+                                        }}
                                         <button
                                             class="group relative flex items-center text-gray-400 hover:text-white transition-colors"
-                                            on:click=on_logout
+                                            on:click=move |_| on_logout()
                                         >
                                             <Icon
                                                 icon=logout_icon.into()
@@ -275,6 +525,21 @@ pub fn App() -> impl IntoView {
                                         <PasswordManager />
                                     </div>
                                 }.into_view(),
+                                DashboardTab::Notes => view! {
+                                    <div class="bg-background-card shadow-lg rounded-lg p-6 h-[calc(100vh-150px)]">
+                                        <SecureNotes />
+                                    </div>
+                                }.into_view(),
+                                DashboardTab::Wallet => view! {
+                                    <div class="bg-background-card shadow-lg rounded-lg p-6 h-[calc(100vh-150px)]">
+                                        <Wallet />
+                                    </div>
+                                }.into_view(),
+                                DashboardTab::Servers => view! {
+                                    <div class="bg-background-card shadow-lg rounded-lg p-6 h-[calc(100vh-150px)]">
+                                        <Servers />
+                                    </div>
+                                }.into_view(),
                                 DashboardTab::Generator => view! {
                                     <div class="bg-background-card shadow-lg rounded-lg p-6">
                                         <PasswordGenerator />
@@ -286,12 +551,44 @@ pub fn App() -> impl IntoView {
                 } else {
                     view! {
                         <div class="min-h-screen flex items-center justify-center">
-                            <Login on_success=on_auth_success />
+                            <Login
+                                on_success=on_auth_success
+                                on_vault_unavailable=move |_| set_needs_repair.set(true)
+                            />
                         </div>
                     }.into_view()
                 }
             }}
 
+            {move || is_authenticated.get().then(|| view! {
+                <BrowserAuthorization />
+            })}
+
+            {move || (show_welcome.get() && is_authenticated.get()).then(|| view! {
+                <WelcomeBack on_close=move |_| set_show_welcome.set(false) />
+            })}
+            {move || (!show_welcome.get() && is_authenticated.get() && !browser_import.with(|p| p.is_empty())).then(|| view! {
+                <BrowserImport
+                    profiles=browser_import.get_untracked()
+                    on_close=move |_| set_browser_import.set(Vec::new())
+                />
+            })}
+
+            {move || session_warning.get().filter(|_| is_authenticated.get()).map(|timeout| view! {
+                <SessionWarning
+                    seconds=timeout.seconds
+                    extendable=timeout.extendable
+                    on_extend=move |_| {
+                        set_session_warning.set(None);
+                        spawn_local(async move {
+                            invoke("extend_session", JsValue::NULL).await;
+                        });
+                    }
+                    on_dismiss=move |_| set_session_warning.set(None)
+                    on_logout=move |_| on_logout()
+                />
+            })}
+
             {move || {
                 if show_about.get() {
                     view! {
@@ -344,3 +641,36 @@ pub fn App() -> impl IntoView {
         </div>
     }
 }
+
+#[component]
+pub fn GeneratorWindow() -> impl IntoView {
+    view! {
+        <div class="min-h-screen bg-background p-4">
+            <div class="bg-background-card shadow-lg rounded-lg p-6">
+                <PasswordGenerator standalone=true />
+            </div>
+        </div>
+    }
+}
+
+#[component]
+pub fn SnapshotWindow() -> impl IntoView {
+    view! {
+        <div class="min-h-screen bg-background p-4">
+            <div class="bg-background-card shadow-lg rounded-lg p-6">
+                <SnapshotView />
+            </div>
+        </div>
+    }
+}
+
+#[component]
+pub fn QuickSearchWindow() -> impl IntoView {
+    view! {
+        <div class="min-h-screen bg-background p-2">
+            <div class="bg-background-card shadow-lg rounded-lg p-4">
+                <QuickSearch />
+            </div>
+        </div>
+    }
+}
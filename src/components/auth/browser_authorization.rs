@@ -0,0 +1,114 @@
+use crate::app::{invoke, listen};
+use crate::components::icons::Icon;
+use leptos::*;
+use serde::{Deserialize, Serialize};
+use wasm_bindgen::prelude::*;
+
+#[derive(Clone, Deserialize)]
+struct AuthorizationRequest {
+    request_id: u64,
+    site: String,
+    origin: String,
+    action: String,
+    username: Option<String>,
+}
+
+#[derive(Deserialize)]
+struct AuthorizationEvent {
+    payload: AuthorizationRequest,
+}
+
+#[derive(Serialize)]
+struct AnswerArgs {
+    #[serde(rename = "requestId")]
+    request_id: u64,
+    allow: bool,
+    remember: bool,
+}
+
+#[component]
+pub fn BrowserAuthorization() -> impl IntoView {
+    let (requests, set_requests) = create_signal(Vec::<AuthorizationRequest>::new());
+
+    let link_icon = create_memo(move |_| "link");
+
+    spawn_local(async move {
+        let closure = Closure::wrap(Box::new(move |event: JsValue| {
+            if let Ok(event) = serde_wasm_bindgen::from_value::<AuthorizationEvent>(event) {
+                set_requests.update(|requests| requests.push(event.payload));
+            }
+        }) as Box<dyn FnMut(JsValue)>);
+
+        listen("browser-authorization", &closure).await;
+        closure.forget();
+    });
+
+    let answer = move |request_id: u64, allow: bool, remember: bool| {
+        set_requests.update(|requests| requests.retain(|r| r.request_id != request_id));
+        spawn_local(async move {
+            let args = serde_wasm_bindgen::to_value(&AnswerArgs {
+                request_id,
+                allow,
+                remember,
+            })
+            .unwrap();
+            invoke("answer_browser_authorization", args).await;
+        });
+    };
+
+    view! {
+        {move || requests.get().first().cloned().map(|request| {
+            let request_id = request.request_id;
+            let is_save = request.action == "save";
+            view! {
+                <div class="fixed inset-0 bg-black bg-opacity-50 flex items-center justify-center z-50">
+                    <div class="bg-background-card rounded-lg p-6 w-full max-w-md">
+                        <h2 class="text-xl font-bold mb-4 bg-gradient-primary bg-clip-text text-transparent flex items-center">
+                            <Icon icon=link_icon.into() class="w-8 h-8 mr-2 text-primary-100" />
+                            "Browser-Erweiterung"
+                        </h2>
+                        <p class="text-gray-300 mb-2">
+                            {if is_save {
+                                format!(
+                                    "Die Browser-Erweiterung möchte die Anmeldung {} für {} im Tresor speichern.",
+                                    request.username.clone().unwrap_or_default(),
+                                    request.site
+                                )
+                            } else {
+                                format!(
+                                    "Die Browser-Erweiterung möchte auf die Zugangsdaten für {} zugreifen.",
+                                    request.site
+                                )
+                            }}
+                        </p>
+                        <p class="text-xs text-gray-400 mb-6 break-all">
+                            {format!("Erweiterung: {}", request.origin)}
+                        </p>
+                        <div class="flex justify-end gap-2">
+                            <button
+                                class="border border-gray-600 text-gray-300 px-4 py-2 rounded hover:bg-background transition-colors"
+                                on:click=move |_| answer(request_id, false, false)
+                            >
+                                "Ablehnen"
+                            </button>
+                            {(!is_save).then(|| view! {
+                                <button
+                                    class="border border-primary-100 text-white px-4 py-2 rounded hover:bg-primary-400/10 transition-colors"
+                                    on:click=move |_| answer(request_id, true, true)
+                                >
+                                    "Immer erlauben"
+                                </button>
+                            })}
+                            <button
+                                class="bg-gradient-primary text-white font-bold px-4 py-2 rounded hover:opacity-90"
+                                on:click=move |_| answer(request_id, true, false)
+                            >
+                                {if is_save { "Speichern" } else { "Einmal erlauben" }}
+                            </button>
+                        </div>
+                    </div>
+                </div>
+            }
+        })}
+    }
+}
@@ -1,6 +1,13 @@
-use crate::{app::invoke, components::icons::Icon};
-use leptos::*;
+use crate::{
+    app::invoke,
+    components::{auth::SoftKeyboard, icons::Icon},
+};
+use leptos::{leptos_dom::helpers::IntervalHandle, *};
 use serde::Serialize;
+use std::time::Duration;
+use wasm_bindgen::JsValue;
+
+const VAULT_LOCATION_UNAVAILABLE: &str = "Vault location unavailable";
 
 #[derive(Serialize)]
 struct LoginArgs<'a> {
@@ -10,11 +17,17 @@ struct LoginArgs<'a> {
 }
 
 #[component]
-pub fn Login(#[prop(into)] on_success: Callback<()>) -> impl IntoView {
+pub fn Login(
+    #[prop(into)] on_success: Callback<()>,
+    #[prop(optional, into)] on_vault_unavailable: Option<Callback<()>>,
+) -> impl IntoView {
     let (username, set_username) = create_signal(String::new());
     let (password, set_password) = create_signal(String::new());
     let (error, set_error) = create_signal(String::new());
     let (show_password, set_show_password) = create_signal(false);
+    let (lockout, set_lockout) = create_signal(0u64);
+    let (soft_keyboard, set_soft_keyboard) = create_signal(false);
+    let (show_keyboard, set_show_keyboard) = create_signal(false);
 
     let show_password_icon = create_memo(move |_| {
         if show_password.get() {
@@ -26,6 +39,57 @@ pub fn Login(#[prop(into)] on_success: Callback<()>) -> impl IntoView {
     let user_icon = create_memo(move |_| "user");
     let key_icon = create_memo(move |_| "key");
     let login_icon = create_memo(move |_| "arrow-right-end-on-rectangle");
+    let generator_icon = create_memo(move |_| "sparkles");
+    let lock_icon = create_memo(move |_| "lock-closed");
+    let keyboard_icon = create_memo(move |_| "computer-desktop");
+
+    spawn_local(async move {
+        let response = invoke("get_soft_keyboard", JsValue::NULL).await;
+        if let Ok(enabled) = serde_wasm_bindgen::from_value::<bool>(response) {
+            set_soft_keyboard.set(enabled);
+        }
+    });
+
+    let countdown = store_value(None::<IntervalHandle>);
+
+    let start_countdown = move |seconds: u64| {
+        set_lockout.set(seconds);
+        if seconds == 0 || countdown.get_value().is_some() {
+            return;
+        }
+
+        let handle = set_interval_with_handle(
+            move || {
+                set_lockout.update(|remaining| *remaining = remaining.saturating_sub(1));
+                if lockout.get_untracked() == 0 {
+                    if let Some(handle) = countdown.get_value() {
+                        handle.clear();
+                    }
+                    countdown.set_value(None);
+                    set_error.set(String::new());
+                }
+            },
+            Duration::from_secs(1),
+        );
+        countdown.set_value(handle.ok());
+    };
+
+    let check_lockout = move || {
+        spawn_local(async move {
+            let response = invoke("get_login_lockout", JsValue::NULL).await;
+            if let Ok(seconds) = serde_wasm_bindgen::from_value::<u64>(response) {
+                start_countdown(seconds);
+            }
+        });
+    };
+
+    check_lockout();
+
+    on_cleanup(move || {
+        if let Some(handle) = countdown.get_value() {
+            handle.clear();
+        }
+    });
 
     let handle_submit = move |ev: ev::SubmitEvent| {
         ev.prevent_default();
@@ -33,6 +97,10 @@ pub fn Login(#[prop(into)] on_success: Callback<()>) -> impl IntoView {
         let username = username.get();
         let password = password.get();
 
+        if lockout.get() > 0 {
+            return;
+        }
+
         if username.is_empty() || password.is_empty() {
             set_error.set("Bitte fülle alle Felder aus".to_string());
             return;
@@ -49,8 +117,14 @@ pub fn Login(#[prop(into)] on_success: Callback<()>) -> impl IntoView {
 
             if serde_wasm_bindgen::from_value::<()>(response.clone()).is_ok() {
                 on_success.call(());
+            } else if response.as_string().as_deref() == Some(VAULT_LOCATION_UNAVAILABLE) {
+                set_error.set("Der Speicherort der Datenbank ist nicht verfügbar".to_string());
+                if let Some(on_vault_unavailable) = on_vault_unavailable {
+                    on_vault_unavailable.call(());
+                }
             } else {
                 set_error.set("Anmeldung fehlgeschlagen".to_string());
+                check_lockout();
             }
         });
     };
@@ -62,7 +136,19 @@ pub fn Login(#[prop(into)] on_success: Callback<()>) -> impl IntoView {
                     "Willkommen zurück"
                 </h2>
 
-                {move || (!error.get().is_empty()).then(||
+                {move || (lockout.get() > 0).then(||
+                    view! {
+                        <div class="mb-4 text-primary-100 text-sm flex items-center justify-center">
+                            <Icon icon=lock_icon.into() class="w-4 h-4 mr-2" />
+                            {format!(
+                                "Zu viele Fehlversuche. Versuche es in {}s erneut.",
+                                lockout.get()
+                            )}
+                        </div>
+                    }
+                )}
+
+                {move || (lockout.get() == 0 && !error.get().is_empty()).then(||
                     view! {
                         <div class="mb-4 text-primary-100 text-sm text-center">
                             {error.get()}
@@ -120,18 +206,54 @@ pub fn Login(#[prop(into)] on_success: Callback<()>) -> impl IntoView {
                                 />
                             </button>
                         </div>
+                        {move || soft_keyboard.get().then(|| if show_keyboard.get() {
+                            view! {
+                                <SoftKeyboard
+                                    on_key=move |key: char| set_password.update(|password| password.push(key))
+                                    on_backspace=move |_| set_password.update(|password| {
+                                        password.pop();
+                                    })
+                                    on_close=move |_| set_show_keyboard.set(false)
+                                />
+                            }.into_view()
+                        } else {
+                            view! {
+                                <button
+                                    type="button"
+                                    class="mt-2 text-sm text-gray-400 hover:text-white transition-colors flex items-center"
+                                    on:click=move |_| set_show_keyboard.set(true)
+                                >
+                                    <Icon icon=keyboard_icon.into() class="w-4 h-4 mr-2" />
+                                    "Bildschirmtastatur verwenden"
+                                </button>
+                            }.into_view()
+                        })}
                     </div>
 
                     <div class="flex justify-center">
                         <button
                             type="submit"
-                            class="bg-gradient-primary text-white font-bold py-2 px-8 rounded focus:outline-none hover:opacity-90 transition-opacity flex items-center"
+                            class="bg-gradient-primary text-white font-bold py-2 px-8 rounded focus:outline-none hover:opacity-90 transition-opacity flex items-center disabled:opacity-50"
+                            prop:disabled=move || lockout.get() > 0
                         >
                             <span>"Anmelden"</span>
                             <Icon icon=login_icon.into() class="w-5 h-5 ml-2" />
                         </button>
                     </div>
                 </form>
+
+                <div class="mt-4 flex justify-center">
+                    <button
+                        type="button"
+                        class="text-sm text-gray-400 hover:text-white transition-colors flex items-center"
+                        on:click=move |_| spawn_local(async move {
+                            invoke("open_generator_window", JsValue::NULL).await;
+                        })
+                    >
+                        <Icon icon=generator_icon.into() class="w-4 h-4 mr-2" />
+                        "Nur Generator öffnen"
+                    </button>
+                </div>
             </div>
         </div>
     }
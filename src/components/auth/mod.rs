@@ -1,5 +1,13 @@
+mod browser_authorization;
 mod login;
 mod register;
+mod session_warning;
+mod soft_keyboard;
+mod welcome_back;
 
+pub use browser_authorization::BrowserAuthorization;
 pub use login::Login;
 pub use register::Register;
+pub use session_warning::SessionWarning;
+pub use soft_keyboard::SoftKeyboard;
+pub use welcome_back::WelcomeBack;
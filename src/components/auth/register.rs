@@ -7,6 +7,7 @@ struct RegisterArgs<'a> {
     username: &'a str,
     #[serde(rename = "masterPass")]
     master_pass: &'a str,
+    cipher: &'a str,
 }
 
 #[derive(Serialize)]
@@ -15,7 +16,10 @@ struct GeneratePasswordArgs {
 }
 
 #[component]
-pub fn Register(#[prop(into)] on_success: Callback<()>) -> impl IntoView {
+pub fn Register(
+    #[prop(into)] cipher: Signal<String>,
+    #[prop(into)] on_success: Callback<()>,
+) -> impl IntoView {
     let (username, set_username) = create_signal(String::new());
     let (password, set_password) = create_signal(String::new());
     let (confirm_password, set_confirm_password) = create_signal(String::new());
@@ -57,10 +61,12 @@ pub fn Register(#[prop(into)] on_success: Callback<()>) -> impl IntoView {
             return;
         }
 
+        let cipher = cipher.get();
         spawn_local(async move {
             let args = serde_wasm_bindgen::to_value(&RegisterArgs {
                 username: &username,
                 master_pass: &password,
+                cipher: &cipher,
             })
             .unwrap();
 
@@ -0,0 +1,65 @@
+use crate::components::icons::Icon;
+use leptos::*;
+use std::time::Duration;
+
+#[component]
+pub fn SessionWarning(
+    seconds: u64,
+    extendable: bool,
+    #[prop(into)] on_extend: Callback<()>,
+    #[prop(into)] on_dismiss: Callback<()>,
+    #[prop(into)] on_logout: Callback<()>,
+) -> impl IntoView {
+    let (remaining, set_remaining) = create_signal(seconds);
+
+    let clock_icon = create_memo(move |_| "clock");
+
+    if let Ok(handle) = set_interval_with_handle(
+        move || set_remaining.update(|seconds| *seconds = seconds.saturating_sub(1)),
+        Duration::from_secs(1),
+    ) {
+        on_cleanup(move || handle.clear());
+    }
+
+    view! {
+        <div class="fixed inset-0 bg-black bg-opacity-50 flex items-center justify-center z-50">
+            <div class="bg-background-card rounded-lg p-6 w-full max-w-md">
+                <h2 class="text-xl font-bold mb-4 bg-gradient-primary bg-clip-text text-transparent flex items-center">
+                    <Icon icon=clock_icon.into() class="w-8 h-8 mr-2 text-primary-100" />
+                    "Du wirst bald abgemeldet"
+                </h2>
+                <p class="text-gray-300 mb-6">
+                    {move || if extendable {
+                        format!(
+                            "Wegen Inaktivität wird der Tresor in {} Sekunden gesperrt. Angemeldet bleiben?",
+                            remaining.get()
+                        )
+                    } else {
+                        format!(
+                            "Die maximale Sitzungsdauer ist erreicht. Der Tresor wird in {} Sekunden gesperrt.",
+                            remaining.get()
+                        )
+                    }}
+                </p>
+                <div class="flex justify-end gap-2">
+                    <button
+                        class="border border-gray-600 text-gray-300 px-4 py-2 rounded hover:bg-background transition-colors"
+                        on:click=move |_| on_logout.call(())
+                    >
+                        "Abmelden"
+                    </button>
+                    <button
+                        class="bg-gradient-primary text-white font-bold px-4 py-2 rounded hover:opacity-90"
+                        on:click=move |_| if extendable {
+                            on_extend.call(())
+                        } else {
+                            on_dismiss.call(())
+                        }
+                    >
+                        {if extendable { "Angemeldet bleiben" } else { "Verstanden" }}
+                    </button>
+                </div>
+            </div>
+        </div>
+    }
+}
@@ -0,0 +1,112 @@
+use crate::components::icons::Icon;
+use leptos::*;
+
+const LETTERS: &str = "abcdefghijklmnopqrstuvwxyz";
+const DIGITS: &str = "0123456789";
+const SYMBOLS: &str = "!@#$%^&*()-_=+[]{};:,.<>?/";
+
+/// Shuffle the keys with a Fisher-Yates shuffle.
+///
+/// The order is only meant to differ between presses, so the JavaScript random
+/// number generator is good enough here.
+fn shuffled(keys: &str) -> Vec<char> {
+    let mut keys: Vec<char> = keys.chars().collect();
+    for i in (1..keys.len()).rev() {
+        let j = (js_sys::Math::random() * (i + 1) as f64) as usize;
+        keys.swap(i, j);
+    }
+    keys
+}
+
+/// Shuffle all key groups of the keyboard.
+fn layout() -> Vec<Vec<char>> {
+    vec![shuffled(DIGITS), shuffled(LETTERS), shuffled(SYMBOLS)]
+}
+
+#[component]
+pub fn SoftKeyboard(
+    #[prop(into)] on_key: Callback<char>,
+    #[prop(into)] on_backspace: Callback<()>,
+    #[prop(into)] on_close: Callback<()>,
+) -> impl IntoView {
+    let (keys, set_keys) = create_signal(layout());
+    let (shift, set_shift) = create_signal(false);
+
+    let exit_icon = create_memo(move |_| "x-mark");
+    let shuffle_icon = create_memo(move |_| "arrow-path");
+    let left_icon = create_memo(move |_| "arrow-left");
+
+    let press = move |key: char| {
+        let key = if shift.get() {
+            key.to_ascii_uppercase()
+        } else {
+            key
+        };
+        on_key.call(key);
+        set_shift.set(false);
+        set_keys.set(layout());
+    };
+
+    view! {
+        <div class="mt-2 p-3 rounded border border-gray-600 bg-background space-y-2">
+            <div class="flex justify-between items-center">
+                <span class="text-xs text-gray-400">
+                    "Die Tasten werden nach jeder Eingabe neu angeordnet"
+                </span>
+                <button
+                    type="button"
+                    class="text-gray-400 hover:text-white"
+                    on:click=move |_| on_close.call(())
+                >
+                    <Icon icon=exit_icon.into() class="w-4 h-4" />
+                </button>
+            </div>
+
+            {move || keys.get().into_iter().map(|row| view! {
+                <div class="flex flex-wrap gap-1">
+                    {row.into_iter().map(|key| view! {
+                        <button
+                            type="button"
+                            class="w-8 h-8 rounded bg-background-card text-white font-mono hover:bg-primary-400/20 transition-colors"
+                            on:click=move |_| press(key)
+                        >
+                            {move || if shift.get() {
+                                key.to_ascii_uppercase()
+                            } else {
+                                key
+                            }}
+                        </button>
+                    }).collect_view()}
+                </div>
+            }).collect_view()}
+
+            <div class="flex gap-1">
+                <button
+                    type="button"
+                    class=move || if shift.get() {
+                        "flex-1 h-8 rounded bg-primary-400/20 text-white text-sm"
+                    } else {
+                        "flex-1 h-8 rounded bg-background-card text-white text-sm hover:bg-primary-400/20"
+                    }
+                    on:click=move |_| set_shift.update(|shift| *shift = !*shift)
+                >
+                    "Umschalt"
+                </button>
+                <button
+                    type="button"
+                    class="px-3 h-8 rounded bg-background-card text-white hover:bg-primary-400/20 flex items-center"
+                    on:click=move |_| set_keys.set(layout())
+                >
+                    <Icon icon=shuffle_icon.into() class="w-4 h-4" />
+                </button>
+                <button
+                    type="button"
+                    class="px-3 h-8 rounded bg-background-card text-white hover:bg-primary-400/20 flex items-center"
+                    on:click=move |_| on_backspace.call(())
+                >
+                    <Icon icon=left_icon.into() class="w-4 h-4" />
+                </button>
+            </div>
+        </div>
+    }
+}
@@ -0,0 +1,127 @@
+use crate::{
+    app::invoke,
+    components::{icons::Icon, utils::LoadingSpinner},
+};
+use leptos::*;
+use serde::Deserialize;
+
+#[derive(Clone, Deserialize)]
+struct UnlockSummary {
+    entry_count: usize,
+    due_for_rotation: usize,
+    health_issues: usize,
+    score: Option<u8>,
+    last_backup_days: Option<i64>,
+}
+
+/// Describe the age of the last backup.
+fn backup_age(days: Option<i64>) -> String {
+    match days {
+        None => "Noch kein Backup vorhanden".to_string(),
+        Some(0) => "Heute".to_string(),
+        Some(1) => "Vor einem Tag".to_string(),
+        Some(days) => format!("Vor {} Tagen", days),
+    }
+}
+
+#[component]
+fn SummaryItem(
+    icon: &'static str,
+    label: &'static str,
+    value: String,
+    warn: bool,
+) -> impl IntoView {
+    let icon = create_memo(move |_| icon);
+
+    view! {
+        <div class="flex items-center justify-between p-3 rounded bg-background border border-gray-600">
+            <span class="flex items-center text-gray-300">
+                <Icon icon=icon.into() class="w-5 h-5 mr-3 text-primary-100" />
+                {label}
+            </span>
+            <span class=if warn { "text-primary-100 font-semibold" } else { "text-white" }>
+                {value}
+            </span>
+        </div>
+    }
+}
+
+#[component]
+pub fn WelcomeBack(#[prop(into)] on_close: Callback<()>) -> impl IntoView {
+    let (summary, set_summary) = create_signal(None::<UnlockSummary>);
+    let (error, set_error) = create_signal(String::new());
+
+    let shield_icon = create_memo(move |_| "shield-check");
+    let arrow_right_icon = create_memo(move |_| "arrow-right");
+
+    spawn_local(async move {
+        let response = invoke("get_unlock_summary", wasm_bindgen::JsValue::NULL).await;
+        match serde_wasm_bindgen::from_value::<UnlockSummary>(response.clone()) {
+            Ok(loaded) => set_summary.set(Some(loaded)),
+            Err(_) => set_error.set(
+                response
+                    .as_string()
+                    .unwrap_or("Unbekannter Fehler".to_string()),
+            ),
+        }
+    });
+
+    view! {
+        <div class="fixed inset-0 bg-black bg-opacity-50 flex items-center justify-center z-50">
+            <div class="bg-background-card rounded-lg p-6 w-full max-w-md">
+                <h2 class="text-xl font-bold mb-6 bg-gradient-primary bg-clip-text text-transparent flex items-center">
+                    <Icon icon=shield_icon.into() class="w-8 h-8 mr-2 text-primary-100" />
+                    "Willkommen zurück"
+                </h2>
+
+                {move || (!error.get().is_empty()).then(|| view! {
+                    <div class="mb-4 text-primary-100 text-sm">{error.get()}</div>
+                })}
+
+                {move || match summary.get() {
+                    Some(summary) => {
+                        let entries = match summary.score {
+                            Some(score) => format!("{} (Score {})", summary.entry_count, score),
+                            None => summary.entry_count.to_string(),
+                        };
+                        let backup_outdated = summary.last_backup_days.is_none_or(|days| days > 7);
+
+                        view! {
+                            <div class="space-y-2 mb-6">
+                                <SummaryItem icon="key" label="Einträge" value=entries warn=false />
+                                <SummaryItem
+                                    icon="arrow-path"
+                                    label="Wechsel fällig"
+                                    value=summary.due_for_rotation.to_string()
+                                    warn={summary.due_for_rotation > 0}
+                                />
+                                <SummaryItem
+                                    icon="exclamation-triangle"
+                                    label="Offene Probleme"
+                                    value=summary.health_issues.to_string()
+                                    warn={summary.health_issues > 0}
+                                />
+                                <SummaryItem
+                                    icon="archive-box"
+                                    label="Letztes Backup"
+                                    value=backup_age(summary.last_backup_days)
+                                    warn=backup_outdated
+                                />
+                            </div>
+                        }.into_view()
+                    }
+                    None if error.get().is_empty() => view! { <LoadingSpinner /> }.into_view(),
+                    None => ().into_view(),
+                }}
+
+                <button
+                    class="w-full bg-gradient-primary text-white font-bold py-2 px-4 rounded hover:opacity-90 flex items-center justify-center"
+                    on:click=move |_| on_close.call(())
+                >
+                    <span>"Weiter"</span>
+                    <Icon icon=arrow_right_icon.into() class="w-5 h-5 ml-2" />
+                </button>
+            </div>
+        </div>
+    }
+}
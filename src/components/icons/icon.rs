@@ -6,6 +6,7 @@ pub fn Icon(icon: Signal<&'static str>, #[prop(optional)] class: &'static str) -
         "eye" => include_str!("../../assets/icons/eye.svg"),
         "eye-slash" => include_str!("../../assets/icons/eye-slash.svg"),
         "arrow-path" => include_str!("../../assets/icons/arrow-path.svg"),
+        "arrow-uturn-left" => include_str!("../../assets/icons/arrow-uturn-left.svg"),
         "clipboard" => include_str!("../../assets/icons/clipboard.svg"),
         "arrow-left-start-on-rectangle" => {
             include_str!("../../assets/icons/arrow-left-start-on-rectangle.svg")
@@ -19,6 +20,7 @@ pub fn Icon(icon: Signal<&'static str>, #[prop(optional)] class: &'static str) -
         "key" => include_str!("../../assets/icons/key.svg"),
         "link" => include_str!("../../assets/icons/link.svg"),
         "magnifying-glass" => include_str!("../../assets/icons/magnifying-glass.svg"),
+        "paper-clip" => include_str!("../../assets/icons/paper-clip.svg"),
         "pencil-square" => include_str!("../../assets/icons/pencil-square.svg"),
         "plus" => include_str!("../../assets/icons/plus.svg"),
         "trash" => include_str!("../../assets/icons/trash.svg"),
@@ -27,10 +29,12 @@ pub fn Icon(icon: Signal<&'static str>, #[prop(optional)] class: &'static str) -
         "cog-6-tooth" => include_str!("../../assets/icons/cog-6-tooth.svg"),
         "information-circle" => include_str!("../../assets/icons/information-circle.svg"),
         "folder-open" => include_str!("../../assets/icons/folder-open.svg"),
+        "tag" => include_str!("../../assets/icons/tag.svg"),
         "archive-box" => include_str!("../../assets/icons/archive-box.svg"),
         "archive-box-arrow-down" => include_str!("../../assets/icons/archive-box-arrow-down.svg"),
         "chevron-down" => include_str!("../../assets/icons/chevron-down.svg"),
         "lock-closed" => include_str!("../../assets/icons/lock-closed.svg"),
+        "lock-open" => include_str!("../../assets/icons/lock-open.svg"),
         "paint-brush" => include_str!("../../assets/icons/paint-brush.svg"),
         "language" => include_str!("../../assets/icons/language.svg"),
         "rocket-launch" => include_str!("../../assets/icons/rocket-launch.svg"),
@@ -56,6 +60,8 @@ pub fn Icon(icon: Signal<&'static str>, #[prop(optional)] class: &'static str) -
         "chart-bar" => include_str!("../../assets/icons/chart-bar.svg"),
         "exclamation-triangle" => include_str!("../../assets/icons/exclamation-triangle.svg"),
         "light-bulb" => include_str!("../../assets/icons/light-bulb.svg"),
+        "device-phone-mobile" => include_str!("../../assets/icons/device-phone-mobile.svg"),
+        "server-stack" => include_str!("../../assets/icons/server-stack.svg"),
         _ => "",
     };
 
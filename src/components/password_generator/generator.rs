@@ -1,30 +1,38 @@
-use crate::{app::invoke, components::icons::Icon};
+use crate::{
+    app::invoke,
+    components::{
+        icons::Icon,
+        password_manager::{DialogAction, PasswordDialog, QuickAddDialog},
+    },
+};
 
 use leptos::*;
 
-use serde::{Deserialize, Serialize};
+use serde::Serialize;
 
-#[derive(Serialize)]
-struct GeneratePasswordArgs {
-    length: usize,
-}
+use super::history::GeneratorHistory;
+use super::options::{
+    load_generator_defaults, GeneratePasswordArgs, GeneratorOptions, GeneratorOptionsForm,
+};
 
 #[derive(Serialize)]
 struct ClipboardArgs<'a> {
     text: &'a str,
 }
 
-#[derive(Deserialize)]
-struct GeneratorLengthResponse {
-    default_length: usize,
-}
-
 #[component]
-pub fn PasswordGenerator() -> impl IntoView {
+pub fn PasswordGenerator(#[prop(optional)] standalone: bool) -> impl IntoView {
     let (length, set_length) = create_signal(16);
+    let (options, set_options) = create_signal(GeneratorOptions::default());
+    let (error, set_error) = create_signal(String::new());
+    let (history_enabled, set_history_enabled) = create_signal(false);
+    let (show_history_dialog, set_show_history_dialog) = create_signal(false);
+    let (history_master_pass, set_history_master_pass) = create_signal(None::<String>);
     let (password, set_password) = create_signal(String::new());
     let (is_copied, set_is_copied) = create_signal(false);
     let (is_loading, set_is_loading) = create_signal(false);
+    let (show_quick_add, set_show_quick_add) = create_signal(false);
+    let (quick_add_status, set_quick_add_status) = create_signal(String::new());
 
     let copied_icon = create_memo(move |_| {
         if is_copied.get() {
@@ -37,11 +45,14 @@ pub fn PasswordGenerator() -> impl IntoView {
     let generate_icon = create_memo(move |_| "arrow-path");
     let sparkles_icon = create_memo(move |_| "sparkles");
     let slider_icon = create_memo(move |_| "adjustments-horizontal");
+    let plus_icon = create_memo(move |_| "plus");
+    let history_icon = create_memo(move |_| "clock");
 
     spawn_local(async move {
-        let response = invoke("get_default_generator_length", wasm_bindgen::JsValue::NULL).await;
-        if let Ok(generator) = serde_wasm_bindgen::from_value::<GeneratorLengthResponse>(response) {
-            set_length.set(generator.default_length);
+        if let Some(defaults) = load_generator_defaults().await {
+            set_length.set(defaults.default_length);
+            set_options.set(defaults.options);
+            set_history_enabled.set(defaults.history_size > 0);
         }
         set_is_loading.set(false);
     });
@@ -63,7 +74,7 @@ pub fn PasswordGenerator() -> impl IntoView {
                                 "Passwort Generator"
                             </h2>
 
-                            <div class="mb-6">
+                            <div class="mb-6" class:hidden=move || options.with(|o| o.mode == "passphrase")>
                                 <label class="block text-white text-sm font-bold mb-2 flex items-center">
                                     <Icon icon=slider_icon.into() class="w-4 h-4 mr-2 text-primary-100" />
                                     <span>"Passwortlänge: "</span>
@@ -94,18 +105,32 @@ pub fn PasswordGenerator() -> impl IntoView {
                                 </div>
                             </div>
 
+                            <div class="mb-6">
+                                <GeneratorOptionsForm options=options set_options=set_options />
+                            </div>
+
                             <button
                                 class="w-full flex justify-center items-center bg-gradient-primary text-white font-bold py-2 px-4 rounded focus:outline-none hover:opacity-90 transition-opacity"
                                 on:click=move |_| {
                                     spawn_local(async move {
                                         let args = serde_wasm_bindgen::to_value(&GeneratePasswordArgs {
-                                            length: length.get()
+                                            length: length.get(),
+                                            options: options.get(),
                                         }).unwrap();
 
                                         let response = invoke("generate_password", args).await;
 
-                                        if let Ok(new_pass) = serde_wasm_bindgen::from_value(response) {
-                                            set_password.set(new_pass);
+                                        match serde_wasm_bindgen::from_value(response.clone()) {
+                                            Ok(new_pass) => {
+                                                set_password.set(new_pass);
+                                                set_quick_add_status.set(String::new());
+                                                set_error.set(String::new());
+                                            }
+                                            Err(_) => set_error.set(
+                                                response
+                                                    .as_string()
+                                                    .unwrap_or("Unbekannter Fehler".to_string()),
+                                            ),
                                         }
                                     });
                                 }
@@ -113,6 +138,37 @@ pub fn PasswordGenerator() -> impl IntoView {
                                 <Icon icon=generate_icon.into() class="w-5 h-5 mr-2" />
                                 "Generiere Passwort"
                             </button>
+
+                            {move || (!error.get().is_empty()).then(|| view! {
+                                <div class="mt-2 text-primary-100 text-sm text-center">
+                                    {error.get()}
+                                </div>
+                            })}
+
+                            {(!standalone).then(|| view! {
+                                <button
+                                    class="w-full mt-4 flex justify-center items-center bg-background border border-primary-100 hover:bg-primary-400/10 text-white py-2 px-4 rounded focus:outline-none transition-all duration-200 disabled:opacity-50 disabled:cursor-not-allowed"
+                                    prop:disabled=move || password.get().is_empty()
+                                    on:click=move |_| set_show_quick_add.set(true)
+                                >
+                                    <Icon icon=plus_icon.into() class="w-5 h-5 mr-2 text-primary-100" />
+                                    "Als Eintrag speichern"
+                                </button>
+                            })}
+                            {move || (!standalone && history_enabled.get()).then(|| view! {
+                                <button
+                                    class="w-full mt-4 flex justify-center items-center bg-background border border-gray-600 hover:border-primary-100 text-white py-2 px-4 rounded focus:outline-none transition-all duration-200"
+                                    on:click=move |_| set_show_history_dialog.set(true)
+                                >
+                                    <Icon icon=history_icon.into() class="w-5 h-5 mr-2 text-primary-100" />
+                                    "Verlauf anzeigen"
+                                </button>
+                            })}
+                            {move || (!quick_add_status.get().is_empty()).then(|| view! {
+                                <div class="mt-2 text-primary-100 text-sm text-center">
+                                    {quick_add_status.get()}
+                                </div>
+                            })}
                         </div>
 
                         <div class="w-1/2">
@@ -167,6 +223,30 @@ pub fn PasswordGenerator() -> impl IntoView {
                     }.into_view()
                 }
             }}
+
+            {move || show_history_dialog.get().then(|| view! {
+                <PasswordDialog
+                    action=DialogAction::Verify
+                    on_close=move |_| set_show_history_dialog.set(false)
+                    on_verify=move |_| ()
+                    on_master_password=move |master_pass| set_history_master_pass.set(Some(master_pass))
+                />
+            })}
+
+            {move || history_master_pass.get().map(|master_pass| view! {
+                <GeneratorHistory
+                    master_pass=master_pass
+                    on_close=move |_| set_history_master_pass.set(None)
+                />
+            })}
+
+            {move || show_quick_add.get().then(|| view! {
+                <QuickAddDialog
+                    password=password.get()
+                    on_close=move |_| set_show_quick_add.set(false)
+                    on_saved=move |_| set_quick_add_status.set("Eintrag gespeichert".to_string())
+                />
+            })}
         </div>
     }
 }
@@ -0,0 +1,139 @@
+use crate::{app::invoke, components::icons::Icon};
+use leptos::*;
+use serde::{Deserialize, Serialize};
+
+#[derive(Clone, Deserialize)]
+struct GeneratedPassword {
+    password: String,
+    created_at: String,
+}
+
+#[derive(Serialize)]
+struct GeneratorHistoryArgs {
+    #[serde(rename = "masterPass")]
+    master_pass: String,
+}
+
+#[derive(Serialize)]
+struct ClipboardArgs<'a> {
+    text: &'a str,
+}
+
+fn format_timestamp(timestamp: &str) -> String {
+    let date = web_sys::js_sys::Date::new(&wasm_bindgen::JsValue::from_str(timestamp));
+    format!(
+        "{:02}.{:02}.{} {:02}:{:02}",
+        date.get_date(),
+        date.get_month() + 1,
+        date.get_full_year(),
+        date.get_hours(),
+        date.get_minutes()
+    )
+}
+
+#[component]
+pub fn GeneratorHistory(
+    master_pass: String,
+    #[prop(into)] on_close: Callback<()>,
+) -> impl IntoView {
+    let (history, set_history) = create_signal(Vec::<GeneratedPassword>::new());
+    let (error, set_error) = create_signal(String::new());
+
+    let exit_icon = create_memo(move |_| "x-mark");
+    let history_icon = create_memo(move |_| "clock");
+    let clipboard_icon = create_memo(move |_| "clipboard");
+    let trash_icon = create_memo(move |_| "trash");
+
+    spawn_local(async move {
+        let args = serde_wasm_bindgen::to_value(&GeneratorHistoryArgs { master_pass }).unwrap();
+        let response = invoke("get_generator_history", args).await;
+        match serde_wasm_bindgen::from_value::<Vec<GeneratedPassword>>(response.clone()) {
+            Ok(loaded) => set_history.set(loaded),
+            Err(_) => set_error.set(
+                response
+                    .as_string()
+                    .unwrap_or("Unbekannter Fehler".to_string()),
+            ),
+        }
+    });
+
+    let handle_clear = move |_| {
+        spawn_local(async move {
+            let response = invoke("clear_generator_history", wasm_bindgen::JsValue::NULL).await;
+            match serde_wasm_bindgen::from_value::<()>(response.clone()) {
+                Ok(_) => set_history.set(Vec::new()),
+                Err(_) => set_error.set(
+                    response
+                        .as_string()
+                        .unwrap_or("Unbekannter Fehler".to_string()),
+                ),
+            }
+        });
+    };
+
+    let copy_password = move |password: String| {
+        spawn_local(async move {
+            let args = serde_wasm_bindgen::to_value(&ClipboardArgs { text: &password }).unwrap();
+            let _ = invoke("copy_to_clipboard", args).await;
+        });
+    };
+
+    view! {
+        <div class="fixed inset-0 bg-black bg-opacity-50 flex items-center justify-center z-50">
+            <div class="bg-background-card rounded-lg p-6 w-full max-w-lg">
+                <div class="flex justify-between items-center mb-6">
+                    <h2 class="text-xl font-bold bg-gradient-primary bg-clip-text text-transparent flex items-center">
+                        <Icon icon=history_icon.into() class="w-8 h-8 mr-2 text-primary-100" />
+                        "Generierte Passwörter"
+                    </h2>
+                    <button
+                        class="text-gray-400 hover:text-white"
+                        on:click=move |_| on_close.call(())
+                    >
+                        <Icon icon=exit_icon.into() class="w-5 h-5" />
+                    </button>
+                </div>
+
+                {move || (!error.get().is_empty()).then(|| view! {
+                    <div class="mb-4 text-primary-100 text-sm">{error.get()}</div>
+                })}
+
+                <div class="space-y-2 max-h-96 overflow-y-auto mb-6">
+                    {move || if history.get().is_empty() {
+                        view! {
+                            <p class="text-sm text-gray-400">"Der Verlauf ist leer."</p>
+                        }.into_view()
+                    } else {
+                        history.get().into_iter().map(|generated| {
+                            let password = generated.password.clone();
+                            view! {
+                                <div class="flex items-center justify-between p-3 rounded bg-background border border-gray-600">
+                                    <div class="min-w-0">
+                                        <div class="text-white font-mono truncate">{generated.password}</div>
+                                        <div class="text-xs text-gray-400">{format_timestamp(&generated.created_at)}</div>
+                                    </div>
+                                    <button
+                                        class="ml-4 p-2 text-gray-400 hover:text-primary-100"
+                                        title="Kopieren"
+                                        on:click=move |_| copy_password(password.clone())
+                                    >
+                                        <Icon icon=clipboard_icon.into() class="w-5 h-5" />
+                                    </button>
+                                </div>
+                            }
+                        }).collect_view()
+                    }}
+                </div>
+
+                <button
+                    class="w-full flex justify-center items-center bg-background border border-primary-100 hover:bg-primary-400/10 text-white py-2 px-4 rounded focus:outline-none transition-all duration-200 disabled:opacity-50 disabled:cursor-not-allowed"
+                    prop:disabled=move || history.get().is_empty()
+                    on:click=handle_clear
+                >
+                    <Icon icon=trash_icon.into() class="w-5 h-5 mr-2 text-primary-100" />
+                    "Verlauf löschen"
+                </button>
+            </div>
+        </div>
+    }
+}
@@ -1,3 +1,8 @@
 mod generator;
+mod history;
+mod options;
 
 pub use generator::PasswordGenerator;
+pub use options::{
+    load_generator_defaults, GeneratePasswordArgs, GeneratorOptions, GeneratorOptionsForm,
+};
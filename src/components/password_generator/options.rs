@@ -0,0 +1,165 @@
+use crate::app::invoke;
+use leptos::*;
+use serde::{Deserialize, Serialize};
+
+#[derive(Clone, PartialEq, Serialize, Deserialize)]
+pub struct GeneratorOptions {
+    pub uppercase: bool,
+    pub lowercase: bool,
+    pub digits: bool,
+    pub symbols: bool,
+    pub exclude_ambiguous: bool,
+    pub custom_symbols: String,
+    pub mode: String,
+    pub passphrase_words: usize,
+}
+
+impl Default for GeneratorOptions {
+    fn default() -> Self {
+        Self {
+            uppercase: true,
+            lowercase: true,
+            digits: true,
+            symbols: true,
+            exclude_ambiguous: false,
+            custom_symbols: String::new(),
+            mode: "characters".to_string(),
+            passphrase_words: 6,
+        }
+    }
+}
+
+#[derive(Clone, Deserialize)]
+pub struct GeneratorDefaults {
+    pub default_length: usize,
+    pub options: GeneratorOptions,
+    pub history_size: usize,
+}
+
+#[derive(Serialize)]
+pub struct GeneratePasswordArgs {
+    pub length: usize,
+    pub options: GeneratorOptions,
+}
+
+/// Load the generator defaults from the application settings.
+pub async fn load_generator_defaults() -> Option<GeneratorDefaults> {
+    let response = invoke("get_generator_defaults", wasm_bindgen::JsValue::NULL).await;
+    serde_wasm_bindgen::from_value(response).ok()
+}
+
+#[component]
+fn OptionToggle(
+    label: &'static str,
+    #[prop(into)] checked: Signal<bool>,
+    #[prop(into)] on_change: Callback<bool>,
+) -> impl IntoView {
+    view! {
+        <label class="flex items-center space-x-3 text-white text-sm">
+            <input
+                type="checkbox"
+                class="w-4 h-4 bg-background border-gray-600 rounded focus:ring-primary-100"
+                on:change=move |ev| on_change.call(event_target_checked(&ev))
+                prop:checked=checked
+            />
+            <span>{label}</span>
+        </label>
+    }
+}
+
+#[component]
+pub fn GeneratorOptionsForm(
+    options: ReadSignal<GeneratorOptions>,
+    set_options: WriteSignal<GeneratorOptions>,
+) -> impl IntoView {
+    let is_passphrase = move || options.with(|o| o.mode == "passphrase");
+
+    view! {
+        <div class="space-y-4">
+            <div>
+                <label class="block text-white text-sm font-bold mb-2">"Art"</label>
+                <select
+                    class="w-full p-2 rounded bg-background text-white border border-gray-600 focus:border-primary-100 focus:outline-none"
+                    on:change=move |ev| set_options.update(|o| o.mode = event_target_value(&ev))
+                    prop:value=move || options.with(|o| o.mode.clone())
+                >
+                    <option value="characters">"Zeichen"</option>
+                    <option value="passphrase">"Passphrase"</option>
+                </select>
+            </div>
+
+            {move || if is_passphrase() {
+                view! {
+                    <div>
+                        <label class="block text-white text-sm font-bold mb-2">
+                            "Anzahl der Wörter: "
+                            <span class="text-primary-100">{move || options.with(|o| o.passphrase_words)}</span>
+                        </label>
+                        <input
+                            type="range"
+                            min="3"
+                            max="12"
+                            class="w-full h-2 bg-background rounded-lg appearance-none cursor-pointer border border-gray-600"
+                            on:input=move |ev| set_options.update(|o| {
+                                o.passphrase_words = event_target_value(&ev).parse().unwrap_or(6)
+                            })
+                            prop:value=move || options.with(|o| o.passphrase_words)
+                        />
+                        <p class="mt-1 text-sm text-gray-400">
+                            "Mit Großbuchstaben beginnt jedes Wort groß, mit Ziffern erhält ein zufälliges Wort eine Ziffer"
+                        </p>
+                    </div>
+                }.into_view()
+            } else {
+                ().into_view()
+            }}
+
+            <div class="grid grid-cols-2 gap-2">
+                <OptionToggle
+                    label="Großbuchstaben (A-Z)"
+                    checked=Signal::derive(move || options.with(|o| o.uppercase))
+                    on_change=move |checked| set_options.update(|o| o.uppercase = checked)
+                />
+                <OptionToggle
+                    label="Ziffern (0-9)"
+                    checked=Signal::derive(move || options.with(|o| o.digits))
+                    on_change=move |checked| set_options.update(|o| o.digits = checked)
+                />
+                {move || (!is_passphrase()).then(|| view! {
+                    <OptionToggle
+                        label="Kleinbuchstaben (a-z)"
+                        checked=Signal::derive(move || options.with(|o| o.lowercase))
+                        on_change=move |checked| set_options.update(|o| o.lowercase = checked)
+                    />
+                    <OptionToggle
+                        label="Sonderzeichen (!@#$)"
+                        checked=Signal::derive(move || options.with(|o| o.symbols))
+                        on_change=move |checked| set_options.update(|o| o.symbols = checked)
+                    />
+                    <OptionToggle
+                        label="Verwechselbare Zeichen vermeiden (l, I, 1, O, 0, o)"
+                        checked=Signal::derive(move || options.with(|o| o.exclude_ambiguous))
+                        on_change=move |checked| set_options.update(|o| o.exclude_ambiguous = checked)
+                    />
+                })}
+            </div>
+
+            {move || (!is_passphrase() && options.with(|o| o.symbols)).then(|| view! {
+                <div>
+                    <label class="block text-white text-sm font-bold mb-2">"Eigene Sonderzeichen"</label>
+                    <input
+                        type="text"
+                        maxlength="32"
+                        class="w-full p-2 rounded bg-background text-white border border-gray-600 focus:border-primary-100 focus:outline-none font-mono"
+                        placeholder="!@#$%^&*()-_=+"
+                        on:input=move |ev| set_options.update(|o| o.custom_symbols = event_target_value(&ev))
+                        prop:value=move || options.with(|o| o.custom_symbols.clone())
+                    />
+                    <p class="mt-1 text-sm text-gray-400">
+                        "Leer lassen für die Standard-Sonderzeichen. Buchstaben, Ziffern und Leerzeichen sind nicht erlaubt"
+                    </p>
+                </div>
+            })}
+        </div>
+    }
+}
@@ -1,30 +1,68 @@
 use leptos::*;
-use serde::Serialize;
+use serde::{Deserialize, Serialize};
 
 use crate::{
     app::invoke,
     components::{
         icons::Icon,
-        password_health::{TableCheckItemArgs, TableCheckItems},
-        utils::LoadingSpinner,
+        password_health::{HealthSnapshot, TableCheckItemArgs, TableCheckItems, TrendChart},
+        utils::{use_view_state, LoadingSpinner, ScoreOrder},
     },
 };
 
 #[derive(Serialize)]
-struct GetPasswordsHealthArgs {}
+struct GetPasswordsHealthArgs {
+    filter: Option<String>,
+}
+
+#[derive(Deserialize)]
+struct CachedHealth {
+    checked_at: String,
+    passwords: Vec<TableCheckItemArgs>,
+}
 
-#[derive(Clone, PartialEq)]
-enum SortOrder {
-    ScoreAsc,
-    ScoreDesc,
+#[derive(Deserialize)]
+struct BreachReport {
+    breached: usize,
+}
+
+#[derive(Clone, Deserialize)]
+struct DuplicateEntry {
+    id: Option<i32>,
+    service: String,
+    username: String,
+}
+
+#[derive(Clone, Deserialize)]
+struct ExpiringEntry {
+    id: Option<i32>,
+    service: String,
+    username: String,
+    expires_on: Option<String>,
+}
+
+#[derive(Clone, Deserialize)]
+struct DuplicateGroup {
+    provider: Option<String>,
+    entries: Vec<DuplicateEntry>,
 }
 
 #[component]
 pub fn Dashboard() -> impl IntoView {
     let (passwords, set_passwords) = create_signal(Vec::<TableCheckItemArgs>::new());
     let (show_filter_dropdown, set_show_filter_dropdown) = create_signal(false);
-    let (sort_order, set_sort_order) = create_signal(SortOrder::ScoreDesc);
+    let view_state = use_view_state();
+    let (sort_order, set_sort_order) = view_state.score_order.split();
     let (is_loading, set_is_loading) = create_signal(true);
+    let (is_refreshing, set_is_refreshing) = create_signal(false);
+    let (checked_at, set_checked_at) = create_signal(None::<String>);
+    let (filter, set_filter) = view_state.health_filter.split();
+    let (filtered_passwords, set_filtered_passwords) =
+        create_signal(Vec::<TableCheckItemArgs>::new());
+    let (duplicate_groups, set_duplicate_groups) = create_signal(Vec::<DuplicateGroup>::new());
+    let (trends, set_trends) = create_signal(Vec::<HealthSnapshot>::new());
+    let (expiring_entries, set_expiring_entries) = create_signal(Vec::<ExpiringEntry>::new());
+    let (breach_status, set_breach_status) = create_signal(None::<String>);
 
     let dashboard_icon = create_memo(move |_| "chart-pie");
     let refresh_icon = create_memo(move |_| "arrow-path");
@@ -34,6 +72,8 @@ pub fn Dashboard() -> impl IntoView {
     let filter_icon = create_memo(move |_| "funnel");
     let clock_icon = create_memo(move |_| "clock");
     let suggestions_icon = create_memo(move |_| "light-bulb");
+    let duplicate_icon = create_memo(move |_| "link");
+    let close_icon = create_memo(move |_| "x-mark");
 
     let total_count = create_memo(move |_| passwords.get().len());
 
@@ -50,47 +90,181 @@ pub fn Dashboard() -> impl IntoView {
         let passwords = passwords.get();
         let strong = passwords.iter().filter(|p| p.score >= 60).count();
         let weak = passwords.iter().filter(|p| p.score < 40).count();
-        let duplicates = passwords
-            .iter()
-            .filter(|p| p.issues.contains(&"Duplicate".to_string()))
-            .count();
-        (strong, weak, duplicates)
+        let count_issue = |issues: &[&str]| {
+            passwords
+                .iter()
+                .filter(|p| p.issues.iter().any(|i| issues.contains(&i.as_str())))
+                .count()
+        };
+        (
+            strong,
+            weak,
+            count_issue(&["Duplicate"]),
+            count_issue(&["Common", "Breached"]),
+            count_issue(&["Outdated"]),
+        )
     });
 
     let sorted_passwords = create_memo(move |_| {
-        let mut passwords = passwords.get();
+        let mut passwords = if filter.get().is_some() {
+            filtered_passwords.get()
+        } else {
+            passwords.get()
+        };
         match sort_order.get() {
-            SortOrder::ScoreDesc => passwords.sort_by(|a, b| b.score.cmp(&a.score)),
-            SortOrder::ScoreAsc => passwords.sort_by(|a, b| a.score.cmp(&b.score)),
+            ScoreOrder::ScoreDesc => passwords.sort_by_key(|p| std::cmp::Reverse(p.score)),
+            ScoreOrder::ScoreAsc => passwords.sort_by_key(|p| p.score),
         }
         passwords
     });
 
-    spawn_local(async move {
-        let args = serde_wasm_bindgen::to_value(&GetPasswordsHealthArgs {}).unwrap();
-        let response = invoke("check_passwords", args).await;
+    let revalidate = move || {
+        set_is_refreshing.set(true);
+        spawn_local(async move {
+            let args =
+                serde_wasm_bindgen::to_value(&GetPasswordsHealthArgs { filter: None }).unwrap();
+            let response = invoke("check_passwords", args).await;
+
+            if let Ok(passwords) =
+                serde_wasm_bindgen::from_value::<Vec<TableCheckItemArgs>>(response)
+            {
+                set_passwords.set(passwords);
+                set_checked_at.set(Some(
+                    web_sys::js_sys::Date::new_0()
+                        .to_iso_string()
+                        .as_string()
+                        .unwrap_or_default(),
+                ));
+            }
 
-        if let Ok(passwords) = serde_wasm_bindgen::from_value::<Vec<TableCheckItemArgs>>(response) {
-            set_passwords.set(passwords);
+            let response = invoke("get_breach_check", wasm_bindgen::JsValue::NULL).await;
+            if serde_wasm_bindgen::from_value::<bool>(response).unwrap_or(false) {
+                let response =
+                    invoke("check_breached_passwords", wasm_bindgen::JsValue::NULL).await;
+                match serde_wasm_bindgen::from_value::<BreachReport>(response.clone()) {
+                    Ok(report) => {
+                        set_breach_status.set((report.breached > 0).then(|| {
+                            format!(
+                                "{} Passwörter wurden in Datenlecks gefunden. Ändere sie so bald wie möglich.",
+                                report.breached
+                            )
+                        }));
+                        let response =
+                            invoke("get_cached_health", wasm_bindgen::JsValue::NULL).await;
+                        if let Ok(Some(summary)) =
+                            serde_wasm_bindgen::from_value::<Option<CachedHealth>>(response)
+                        {
+                            set_passwords.set(summary.passwords);
+                        }
+                    }
+                    Err(_) => set_breach_status.set(Some(format!(
+                        "Abgleich mit Have I Been Pwned fehlgeschlagen: {}",
+                        response
+                            .as_string()
+                            .unwrap_or("Unbekannter Fehler".to_string())
+                    ))),
+                }
+            }
+
+            let response = invoke("get_duplicate_groups", wasm_bindgen::JsValue::NULL).await;
+            if let Ok(groups) = serde_wasm_bindgen::from_value::<Vec<DuplicateGroup>>(response) {
+                set_duplicate_groups.set(groups);
+            }
+
+            let response = invoke("get_expiring_entries", wasm_bindgen::JsValue::NULL).await;
+            if let Ok(entries) = serde_wasm_bindgen::from_value::<Vec<ExpiringEntry>>(response) {
+                set_expiring_entries.set(entries);
+            }
+
+            let response = invoke("get_trends", wasm_bindgen::JsValue::NULL).await;
+            if let Ok(snapshots) = serde_wasm_bindgen::from_value::<Vec<HealthSnapshot>>(response) {
+                set_trends.set(snapshots);
+            }
+            set_is_refreshing.set(false);
+            set_is_loading.set(false);
+        });
+    };
+
+    spawn_local(async move {
+        let response = invoke("get_cached_health", wasm_bindgen::JsValue::NULL).await;
+        if let Ok(Some(summary)) = serde_wasm_bindgen::from_value::<Option<CachedHealth>>(response)
+        {
+            set_passwords.set(summary.passwords);
+            set_checked_at.set(Some(summary.checked_at));
+            set_is_loading.set(false);
         }
-        set_is_loading.set(false);
+        revalidate();
     });
 
     let handle_refresh = move |_| {
-        set_is_loading.set(true);
+        if is_refreshing.get() {
+            return;
+        }
+        set_filter.set(None);
+        revalidate();
+    };
+
+    let checked_at_label = move || {
+        checked_at.get().map(|checked_at| {
+            let date = web_sys::js_sys::Date::new(&wasm_bindgen::JsValue::from_str(&checked_at));
+            format!(
+                "Stand: {:02}.{:02}.{} {:02}:{:02} Uhr",
+                date.get_date(),
+                date.get_month() + 1,
+                date.get_full_year(),
+                date.get_hours(),
+                date.get_minutes()
+            )
+        })
+    };
+
+    let today = store_value({
+        let date = web_sys::js_sys::Date::new_0();
+        format!(
+            "{}-{:02}-{:02}",
+            date.get_full_year(),
+            date.get_month() + 1,
+            date.get_date()
+        )
+    });
+
+    let load_filter = move |value: &'static str| {
         spawn_local(async move {
-            let args = serde_wasm_bindgen::to_value(&GetPasswordsHealthArgs {}).unwrap();
+            let args = serde_wasm_bindgen::to_value(&GetPasswordsHealthArgs {
+                filter: Some(value.to_string()),
+            })
+            .unwrap();
             let response = invoke("check_passwords", args).await;
 
             if let Ok(passwords) =
                 serde_wasm_bindgen::from_value::<Vec<TableCheckItemArgs>>(response)
             {
-                set_passwords.set(passwords);
+                set_filtered_passwords.set(passwords);
+                set_filter.set(Some(value));
             }
-            set_is_loading.set(false);
         });
     };
 
+    if let Some(value) = filter.get_untracked() {
+        load_filter(value);
+    }
+
+    let handle_filter = move |value: &'static str| {
+        if filter.get() == Some(value) {
+            set_filter.set(None);
+            return;
+        }
+
+        load_filter(value);
+    };
+
+    let filter_label = move || match filter.get() {
+        Some("breached") => "Kompromittierte Passwörter",
+        Some("outdated") => "Veraltete Passwörter",
+        Some("duplicates") => "Doppelte Passwörter",
+        _ => "",
+    };
+
     view! {
         <div class="w-full flex flex-col h-full">
             <div class="flex justify-between items-center mb-4">
@@ -98,12 +272,15 @@ pub fn Dashboard() -> impl IntoView {
                     <Icon icon=dashboard_icon.into() class="w-8 h-8 mr-3 text-primary-100" />
                     "Dashboard"
                 </h2>
-                <div class="flex gap-6">
+                <div class="flex items-center gap-6">
+                    <span class="text-sm text-gray-400">{checked_at_label}</span>
                     <button
                         class="group relative flex items-center text-gray-400 hover:text-white transition-colors"
                         on:click=handle_refresh
                     >
-                        <Icon icon=refresh_icon.into() class="w-5 h-5" />
+                        <span class="flex" class:animate-spin=is_refreshing>
+                            <Icon icon=refresh_icon.into() class="w-5 h-5" />
+                        </span>
                         <span class="ml-2 whitespace-nowrap max-w-0 overflow-hidden group-hover:max-w-[100px] transition-all duration-300 text-primary-100">
                             "Aktualisieren"
                         </span>
@@ -118,6 +295,12 @@ pub fn Dashboard() -> impl IntoView {
                 } else {
                     view! {
                         <div class="flex-1 overflow-y-auto">
+                            {move || breach_status.get().map(|status| view! {
+                                <div class="flex items-center gap-3 p-4 mb-6 rounded-lg border border-red-500 bg-red-500/10 text-white">
+                                    <Icon icon=warning_icon.into() class="w-5 h-5 text-red-500 shrink-0" />
+                                    <span class="text-sm">{status}</span>
+                                </div>
+                            })}
                             <div class="grid grid-cols-1 md:grid-cols-2 gap-4 mb-6">
                                 <div class="bg-background p-6 rounded-lg border border-gray-600">
                                     <div class="flex flex-col items-center">
@@ -175,7 +358,11 @@ pub fn Dashboard() -> impl IntoView {
                                                 </span>
                                             </div>
                                         </div>
-                                        <div class="flex items-center justify-between">
+                                        <button
+                                            class="w-full flex items-center justify-between rounded hover:bg-background-light transition-colors"
+                                            class:text-primary-100=move || filter.get() == Some("duplicates")
+                                            on:click=move |_| handle_filter("duplicates")
+                                        >
                                             <span class="text-gray-400">"Doppelte Passwörter"</span>
                                             <div class="flex items-center space-x-2">
                                                 <div class="w-2 h-2 rounded-full bg-yellow-500"></div>
@@ -183,14 +370,121 @@ pub fn Dashboard() -> impl IntoView {
                                                     {move || password_stats.get().2}
                                                 </span>
                                             </div>
-                                        </div>
+                                        </button>
+                                        <button
+                                            class="w-full flex items-center justify-between rounded hover:bg-background-light transition-colors"
+                                            class:text-primary-100=move || filter.get() == Some("breached")
+                                            on:click=move |_| handle_filter("breached")
+                                        >
+                                            <span class="text-gray-400">"Kompromittierte Passwörter"</span>
+                                            <div class="flex items-center space-x-2">
+                                                <div class="w-2 h-2 rounded-full bg-red-500"></div>
+                                                <span class="text-sm text-white">
+                                                    {move || password_stats.get().3}
+                                                </span>
+                                            </div>
+                                        </button>
+                                        <button
+                                            class="w-full flex items-center justify-between rounded hover:bg-background-light transition-colors"
+                                            class:text-primary-100=move || filter.get() == Some("outdated")
+                                            on:click=move |_| handle_filter("outdated")
+                                        >
+                                            <span class="text-gray-400">"Veraltete Passwörter"</span>
+                                            <div class="flex items-center space-x-2">
+                                                <div class="w-2 h-2 rounded-full bg-gray-400"></div>
+                                                <span class="text-sm text-white">
+                                                    {move || password_stats.get().4}
+                                                </span>
+                                            </div>
+                                        </button>
                                     </div>
                                 </div>
                             </div>
 
+                            <TrendChart snapshots=trends />
+
+                            {move || (!duplicate_groups.get().is_empty()).then(|| view! {
+                                <div class="bg-background p-6 rounded-lg border border-gray-600 mb-6">
+                                    <h3 class="text-lg text-primary-100 font-semibold mb-4 flex items-center">
+                                        <Icon icon=duplicate_icon.into() class="w-5 h-5 mr-2" />
+                                        "Gemeinsam genutzte Passwörter"
+                                    </h3>
+                                    <p class="text-sm text-gray-400 mb-4">
+                                        "Diese Konten verwenden dasselbe Passwort. Ändere die Passwörter einer Gruppe gemeinsam. Gruppen eines einzelnen Anbieters, etwa per Single Sign-On, sind meist beabsichtigt."
+                                    </p>
+                                    <div class="space-y-3">
+                                        {duplicate_groups.get().into_iter().enumerate().map(|(index, group)| view! {
+                                            <div class="p-3 rounded border border-gray-600">
+                                                {match group.provider {
+                                                    Some(provider) => view! {
+                                                        <div class="text-sm text-gray-400 mb-2">
+                                                            {format!("{} · {} Konten · Single Sign-On", provider, group.entries.len())}
+                                                        </div>
+                                                    },
+                                                    None => view! {
+                                                        <div class="text-sm text-yellow-500 mb-2">
+                                                            {format!("Gruppe {} · {} Konten", index + 1, group.entries.len())}
+                                                        </div>
+                                                    },
+                                                }}
+                                                <ul class="space-y-1">
+                                                    {group.entries.into_iter().map(|entry| view! {
+                                                        <li class="text-sm text-white" data-id=entry.id>
+                                                            {entry.service}
+                                                            <span class="text-gray-400">{format!(" ({})", entry.username)}</span>
+                                                        </li>
+                                                    }).collect_view()}
+                                                </ul>
+                                            </div>
+                                        }).collect_view()}
+                                    </div>
+                                </div>
+                            })}
+
+                            {move || (!expiring_entries.get().is_empty()).then(|| view! {
+                                <div class="bg-background p-6 rounded-lg border border-gray-600 mb-6">
+                                    <h3 class="text-lg text-primary-100 font-semibold mb-4 flex items-center">
+                                        <Icon icon=clock_icon.into() class="w-5 h-5 mr-2" />
+                                        "Ablaufende Passwörter"
+                                    </h3>
+                                    <ul class="space-y-2">
+                                        {expiring_entries.get().into_iter().map(|entry| {
+                                            let expires_on = entry.expires_on.unwrap_or_default();
+                                            let expired = today.with_value(|today| expires_on < *today);
+                                            let label = match expires_on.split('-').collect::<Vec<_>>()[..] {
+                                                [year, month, day] => format!("{}.{}.{}", day, month, year),
+                                                _ => expires_on.clone(),
+                                            };
+                                            view! {
+                                                <li class="flex items-center justify-between text-sm" data-id=entry.id>
+                                                    <span class="text-white">
+                                                        {entry.service}
+                                                        <span class="text-gray-400">{format!(" ({})", entry.username)}</span>
+                                                    </span>
+                                                    <span class:text-red-500=expired class:text-yellow-500=!expired>
+                                                        {if expired { format!("abgelaufen am {}", label) } else { format!("läuft ab am {}", label) }}
+                                                    </span>
+                                                </li>
+                                            }
+                                        }).collect_view()}
+                                    </ul>
+                                </div>
+                            })}
+
                             <div class="bg-background rounded-lg border border-gray-600">
                                 <div class="p-4 border-b border-gray-600 flex justify-between items-center">
-                                    <h3 class="text-lg font-semibold text-primary-100">"Passwort Analyse"</h3>
+                                    <div class="flex items-center gap-3">
+                                        <h3 class="text-lg font-semibold text-primary-100">"Passwort Analyse"</h3>
+                                        {move || filter.get().is_some().then(|| view! {
+                                            <button
+                                                class="flex items-center gap-1 px-2 py-1 text-sm rounded border border-primary-100 text-white hover:bg-primary-400/10 transition-colors"
+                                                on:click=move |_| set_filter.set(None)
+                                            >
+                                                {filter_label}
+                                                <Icon icon=close_icon.into() class="w-4 h-4" />
+                                            </button>
+                                        })}
+                                    </div>
                                     <div class="relative">
                                         <button
                                             class="p-2 hover:bg-background rounded-lg text-gray-400 hover:text-white transition-colors"
@@ -206,9 +500,9 @@ pub fn Dashboard() -> impl IntoView {
                                                 </div>
                                                 <button
                                                     class="w-full text-left px-3 py-2 hover:bg-background-light rounded transition-colors flex items-center gap-2"
-                                                    class:text-primary-100=move || sort_order.get() == SortOrder::ScoreDesc
+                                                    class:text-primary-100=move || sort_order.get() == ScoreOrder::ScoreDesc
                                                     on:click=move |_| {
-                                                        set_sort_order.set(SortOrder::ScoreDesc);
+                                                        set_sort_order.set(ScoreOrder::ScoreDesc);
                                                         set_show_filter_dropdown.set(false);
                                                     }
                                                 >
@@ -216,9 +510,9 @@ pub fn Dashboard() -> impl IntoView {
                                                 </button>
                                                 <button
                                                     class="w-full text-left px-3 py-2 hover:bg-background-light rounded transition-colors flex items-center gap-2"
-                                                    class:text-primary-100=move || sort_order.get() == SortOrder::ScoreAsc
+                                                    class:text-primary-100=move || sort_order.get() == ScoreOrder::ScoreAsc
                                                     on:click=move |_| {
-                                                        set_sort_order.set(SortOrder::ScoreAsc);
+                                                        set_sort_order.set(ScoreOrder::ScoreAsc);
                                                         set_show_filter_dropdown.set(false);
                                                     }
                                                 >
@@ -1,5 +1,9 @@
 mod dashboard;
+mod score_badge;
 mod table_check_items;
+mod trend_chart;
 
 pub use dashboard::Dashboard;
+pub use score_badge::ScoreBadge;
 pub use table_check_items::{TableCheckItemArgs, TableCheckItems};
+pub use trend_chart::{HealthSnapshot, TrendChart};
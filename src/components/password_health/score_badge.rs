@@ -0,0 +1,47 @@
+use leptos::*;
+use std::time::Duration;
+use wasm_bindgen::JsValue;
+
+use crate::app::invoke;
+
+#[component]
+pub fn ScoreBadge(#[prop(into)] on_click: Callback<()>) -> impl IntoView {
+    let (score, set_score) = create_signal(None::<u8>);
+
+    let load_score = move || {
+        spawn_local(async move {
+            let response = invoke("get_overall_score", JsValue::NULL).await;
+            if let Ok(value) = serde_wasm_bindgen::from_value::<Option<u8>>(response) {
+                set_score.set(value);
+            }
+        });
+    };
+
+    load_score();
+    if let Ok(handle) = set_interval_with_handle(load_score, Duration::from_secs(15)) {
+        on_cleanup(move || handle.clear());
+    }
+
+    view! {
+        {move || score.get().map(|score| {
+            let color = if score >= 60 {
+                "bg-green-500"
+            } else if score >= 40 {
+                "bg-yellow-500"
+            } else {
+                "bg-red-500"
+            };
+
+            view! {
+                <button
+                    class="flex items-center space-x-2 px-3 py-1 rounded-full border border-gray-600 text-sm text-white hover:border-primary-100 transition-colors"
+                    title="Durchschnittlicher Passwort-Score"
+                    on:click=move |_| on_click.call(())
+                >
+                    <div class=format!("w-2 h-2 rounded-full {}", color)></div>
+                    <span>{score}</span>
+                </button>
+            }
+        })}
+    }
+}
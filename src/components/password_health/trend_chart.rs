@@ -0,0 +1,131 @@
+use leptos::*;
+use serde::Deserialize;
+
+use crate::components::icons::Icon;
+
+const WIDTH: f64 = 300.0;
+const HEIGHT: f64 = 100.0;
+
+#[derive(Clone, Deserialize, PartialEq)]
+pub struct HealthSnapshot {
+    pub day: String,
+    pub entry_count: usize,
+    pub average_score: u8,
+}
+
+/// Format a day like `2024-05-01` as `01.05.`.
+fn short_day(day: &str) -> String {
+    match day.split('-').collect::<Vec<_>>()[..] {
+        [_, month, day] => format!("{}.{}.", day, month),
+        _ => day.to_string(),
+    }
+}
+
+/// Build the points of an SVG polyline with values between 0 and `max`.
+fn polyline(values: &[f64], max: f64) -> String {
+    let step = if values.len() > 1 {
+        WIDTH / (values.len() - 1) as f64
+    } else {
+        0.0
+    };
+
+    values
+        .iter()
+        .enumerate()
+        .map(|(i, value)| {
+            let y = HEIGHT - (value / max.max(1.0)) * HEIGHT;
+            format!("{:.1},{:.1}", i as f64 * step, y)
+        })
+        .collect::<Vec<_>>()
+        .join(" ")
+}
+
+#[component]
+pub fn TrendChart(#[prop(into)] snapshots: Signal<Vec<HealthSnapshot>>) -> impl IntoView {
+    let trend_icon = create_memo(move |_| "chart-bar");
+
+    let score_points = create_memo(move |_| {
+        let values: Vec<f64> = snapshots
+            .get()
+            .iter()
+            .map(|s| s.average_score as f64)
+            .collect();
+        polyline(&values, 100.0)
+    });
+
+    let max_entries = create_memo(move |_| {
+        snapshots
+            .get()
+            .iter()
+            .map(|s| s.entry_count)
+            .max()
+            .unwrap_or(0)
+    });
+
+    let entry_points = create_memo(move |_| {
+        let values: Vec<f64> = snapshots
+            .get()
+            .iter()
+            .map(|s| s.entry_count as f64)
+            .collect();
+        polyline(&values, max_entries.get() as f64)
+    });
+
+    let range_label = move || {
+        let snapshots = snapshots.get();
+        match (snapshots.first(), snapshots.last()) {
+            (Some(first), Some(last)) => (short_day(&first.day), short_day(&last.day)),
+            _ => (String::new(), String::new()),
+        }
+    };
+
+    view! {
+        <div class="bg-background p-6 rounded-lg border border-gray-600 mb-6">
+            <div class="flex justify-between items-center mb-4">
+                <h3 class="text-lg text-primary-100 font-semibold flex items-center">
+                    <Icon icon=trend_icon.into() class="w-5 h-5 mr-2" />
+                    "Entwicklung"
+                </h3>
+                <div class="flex items-center gap-4 text-sm text-gray-400">
+                    <span class="flex items-center">
+                        <span class="w-3 h-0.5 bg-primary-100 mr-2"></span>
+                        "Score"
+                    </span>
+                    <span class="flex items-center">
+                        <span class="w-3 h-0.5 bg-gray-400 mr-2"></span>
+                        {move || format!("Einträge (max. {})", max_entries.get())}
+                    </span>
+                </div>
+            </div>
+            {move || if snapshots.get().len() < 2 {
+                view! {
+                    <p class="text-sm text-gray-400">
+                        "Der Verlauf erscheint, sobald deine Passwörter an mehreren Tagen geprüft wurden."
+                    </p>
+                }.into_view()
+            } else {
+                view! {
+                    <svg class="w-full h-32" viewBox="0 0 300 100" preserveAspectRatio="none">
+                        <line class="stroke-gray-600" x1="0" y1="50" x2="300" y2="50" stroke-width="0.5" stroke-dasharray="4 4" />
+                        <polyline
+                            class="stroke-gray-400 fill-none"
+                            stroke-width="1.5"
+                            vector-effect="non-scaling-stroke"
+                            points=entry_points
+                        />
+                        <polyline
+                            class="stroke-primary-100 fill-none"
+                            stroke-width="2"
+                            vector-effect="non-scaling-stroke"
+                            points=score_points
+                        />
+                    </svg>
+                    <div class="flex justify-between text-xs text-gray-400 mt-2">
+                        <span>{move || range_label().0}</span>
+                        <span>{move || range_label().1}</span>
+                    </div>
+                }.into_view()
+            }}
+        </div>
+    }
+}
@@ -0,0 +1,211 @@
+use crate::{app::invoke, components::icons::Icon};
+use leptos::*;
+use serde::{Deserialize, Serialize};
+
+use super::{DialogAction, PasswordDialog};
+
+#[derive(Clone, Deserialize, PartialEq)]
+struct Attachment {
+    id: i32,
+    file_name: String,
+    size: i64,
+}
+
+#[derive(Serialize)]
+struct EntryIdArgs {
+    id: i32,
+}
+
+#[derive(Serialize)]
+struct AttachmentArgs {
+    id: i32,
+    #[serde(rename = "attachmentId")]
+    attachment_id: i32,
+    #[serde(rename = "masterPass")]
+    master_pass: Option<String>,
+}
+
+fn size_label(size: i64) -> String {
+    if size < 1024 {
+        format!("{} B", size)
+    } else if size < 1024 * 1024 {
+        format!("{} KiB", size / 1024)
+    } else {
+        format!("{:.1} MiB", size as f64 / (1024.0 * 1024.0))
+    }
+}
+
+#[component]
+pub fn EntryAttachments(
+    id: i32,
+    require_master: bool,
+    #[prop(into)] locked: Signal<bool>,
+) -> impl IntoView {
+    let (attachments, set_attachments) = create_signal(Vec::<Attachment>::new());
+    let (error, set_error) = create_signal(String::new());
+    let (message, set_message) = create_signal(String::new());
+    let (pending_save, set_pending_save) = create_signal(None::<i32>);
+
+    let paperclip_icon = create_memo(move |_| "paper-clip");
+    let add_icon = create_memo(move |_| "plus");
+    let save_icon = create_memo(move |_| "arrow-down-tray");
+    let trash_icon = create_memo(move |_| "trash");
+
+    let load_attachments = move || {
+        spawn_local(async move {
+            let args = serde_wasm_bindgen::to_value(&EntryIdArgs { id }).unwrap();
+            let response = invoke("get_attachments", args).await;
+            if let Ok(loaded) = serde_wasm_bindgen::from_value::<Vec<Attachment>>(response) {
+                set_attachments.set(loaded);
+            }
+        });
+    };
+    load_attachments();
+
+    let show_result = move |response: wasm_bindgen::JsValue, done: &str| {
+        if serde_wasm_bindgen::from_value::<()>(response.clone()).is_ok() {
+            set_error.set(String::new());
+            set_message.set(done.to_string());
+        } else {
+            set_message.set(String::new());
+            set_error.set(
+                response
+                    .as_string()
+                    .unwrap_or("Unbekannter Fehler".to_string()),
+            );
+        }
+    };
+
+    let add_attachment = move |_| {
+        spawn_local(async move {
+            let args = serde_wasm_bindgen::to_value(&EntryIdArgs { id }).unwrap();
+            let response = invoke("add_attachment", args).await;
+            match serde_wasm_bindgen::from_value::<Option<i32>>(response.clone()) {
+                Ok(Some(_)) => {
+                    set_error.set(String::new());
+                    set_message.set("Datei angehängt".to_string());
+                    load_attachments();
+                }
+                Ok(None) => {}
+                Err(_) => show_result(response, ""),
+            }
+        });
+    };
+
+    let save_attachment = move |attachment_id: i32, master_pass: Option<String>| {
+        spawn_local(async move {
+            let args = serde_wasm_bindgen::to_value(&AttachmentArgs {
+                id,
+                attachment_id,
+                master_pass,
+            })
+            .unwrap();
+            let response = invoke("get_attachment", args).await;
+            match serde_wasm_bindgen::from_value::<Option<String>>(response.clone()) {
+                Ok(Some(path)) => {
+                    set_error.set(String::new());
+                    set_message.set(format!("Gespeichert unter {}", path));
+                }
+                Ok(None) => {}
+                Err(_) => show_result(response, ""),
+            }
+        });
+    };
+
+    let delete_attachment = move |attachment_id: i32| {
+        spawn_local(async move {
+            let args = serde_wasm_bindgen::to_value(&AttachmentArgs {
+                id,
+                attachment_id,
+                master_pass: None,
+            })
+            .unwrap();
+            show_result(invoke("delete_attachment", args).await, "Anhang gelöscht");
+            load_attachments();
+        });
+    };
+
+    view! {
+        <div>
+            <div class="flex items-center justify-between mb-2">
+                <label class="text-white text-sm font-bold flex items-center">
+                    <Icon icon=paperclip_icon.into() class="w-4 h-4 mr-2 text-primary-100" />
+                    "Anhänge"
+                </label>
+                <button
+                    type="button"
+                    class="text-gray-400 hover:text-primary-100 flex items-center text-sm disabled:opacity-50"
+                    prop:disabled=locked
+                    on:click=add_attachment
+                >
+                    <Icon icon=add_icon.into() class="w-4 h-4 mr-1" />
+                    "Datei anhängen"
+                </button>
+            </div>
+
+            {move || {
+                let current = attachments.get();
+                if current.is_empty() {
+                    view! {
+                        <p class="text-sm text-gray-400">"Keine Anhänge"</p>
+                    }.into_view()
+                } else {
+                    current.into_iter().map(|attachment| {
+                        let attachment_id = attachment.id;
+                        view! {
+                            <div class="flex items-center justify-between p-2 mb-1 rounded border border-gray-600">
+                                <span class="text-white text-sm truncate" title=attachment.file_name.clone()>
+                                    {attachment.file_name.clone()}
+                                </span>
+                                <div class="flex items-center space-x-2 ml-2">
+                                    <span class="text-xs text-gray-400 whitespace-nowrap">{size_label(attachment.size)}</span>
+                                    <button
+                                        type="button"
+                                        class="text-gray-400 hover:text-primary-100"
+                                        title="Speichern unter"
+                                        on:click=move |_| {
+                                            if require_master {
+                                                set_pending_save.set(Some(attachment_id));
+                                            } else {
+                                                save_attachment(attachment_id, None);
+                                            }
+                                        }
+                                    >
+                                        <Icon icon=save_icon.into() class="w-4 h-4" />
+                                    </button>
+                                    <button
+                                        type="button"
+                                        class="text-gray-400 hover:text-red-500 disabled:opacity-50"
+                                        title="Löschen"
+                                        prop:disabled=locked
+                                        on:click=move |_| delete_attachment(attachment_id)
+                                    >
+                                        <Icon icon=trash_icon.into() class="w-4 h-4" />
+                                    </button>
+                                </div>
+                            </div>
+                        }
+                    }).collect_view()
+                }
+            }}
+
+            {move || (!message.get().is_empty()).then(|| view! {
+                <p class="mt-1 text-xs text-primary-100">{message.get()}</p>
+            })}
+            {move || (!error.get().is_empty()).then(|| view! {
+                <p class="mt-1 text-xs text-red-500">{error.get()}</p>
+            })}
+
+            {move || pending_save.get().map(|attachment_id| view! {
+                <PasswordDialog
+                    action=DialogAction::Verify
+                    on_master_password=move |master_pass: String| {
+                        save_attachment(attachment_id, Some(master_pass));
+                    }
+                    on_verify=move |_| {}
+                    on_close=move |_| set_pending_save.set(None)
+                />
+            })}
+        </div>
+    }
+}
@@ -0,0 +1,176 @@
+use crate::{app::invoke, components::icons::Icon};
+use leptos::*;
+use serde::{Deserialize, Serialize};
+use std::collections::HashSet;
+
+#[derive(Clone, PartialEq, Serialize, Deserialize)]
+pub struct CategorySuggestion {
+    pub id: i32,
+    pub service: String,
+    pub category: String,
+}
+
+#[derive(Serialize)]
+struct CategorySuggestionsArgs {
+    ids: Option<Vec<i32>>,
+}
+
+#[derive(Serialize)]
+struct ApplyCategorySuggestionsArgs {
+    suggestions: Vec<CategorySuggestion>,
+    target: String,
+}
+
+/// Load the category suggestions for the given entries, all entries if None.
+pub async fn load_category_suggestions(ids: Option<Vec<i32>>) -> Vec<CategorySuggestion> {
+    let args = serde_wasm_bindgen::to_value(&CategorySuggestionsArgs { ids }).unwrap();
+    let response = invoke("get_category_suggestions", args).await;
+    serde_wasm_bindgen::from_value(response).unwrap_or_default()
+}
+
+#[component]
+pub fn CategoryReview(
+    suggestions: Vec<CategorySuggestion>,
+    #[prop(into)] on_applied: Callback<()>,
+    #[prop(into)] on_close: Callback<()>,
+) -> impl IntoView {
+    let (selected, set_selected) = create_signal(
+        suggestions
+            .iter()
+            .map(|suggestion| suggestion.id)
+            .collect::<HashSet<_>>(),
+    );
+    let (target, set_target) = create_signal("tag".to_string());
+    let (error, set_error) = create_signal(String::new());
+    let (is_applying, set_is_applying) = create_signal(false);
+    let suggestions = store_value(suggestions);
+
+    let exit_icon = create_memo(move |_| "x-mark");
+    let sparkles_icon = create_memo(move |_| "sparkles");
+    let check_icon = create_memo(move |_| "check");
+
+    let handle_apply = move |_| {
+        let accepted: Vec<CategorySuggestion> = suggestions.with_value(|suggestions| {
+            suggestions
+                .iter()
+                .filter(|suggestion| selected.with(|s| s.contains(&suggestion.id)))
+                .cloned()
+                .collect()
+        });
+        if accepted.is_empty() {
+            on_close.call(());
+            return;
+        }
+
+        set_is_applying.set(true);
+        spawn_local(async move {
+            let args = serde_wasm_bindgen::to_value(&ApplyCategorySuggestionsArgs {
+                suggestions: accepted,
+                target: target.get_untracked(),
+            })
+            .unwrap();
+            let response = invoke("apply_category_suggestions", args).await;
+            if serde_wasm_bindgen::from_value::<usize>(response.clone()).is_ok() {
+                on_applied.call(());
+                on_close.call(());
+            } else {
+                set_error.set(
+                    response
+                        .as_string()
+                        .unwrap_or("Unbekannter Fehler".to_string()),
+                );
+            }
+            set_is_applying.set(false);
+        });
+    };
+
+    view! {
+        <div class="fixed inset-0 bg-black bg-opacity-50 flex items-center justify-center z-50">
+            <div class="bg-background-card rounded-lg p-6 w-full max-w-md">
+                <div class="flex justify-between items-center mb-6">
+                    <h2 class="text-xl font-bold bg-gradient-primary bg-clip-text text-transparent flex items-center">
+                        <Icon icon=sparkles_icon.into() class="w-8 h-8 mr-2 text-primary-100" />
+                        "Kategorien vorschlagen"
+                    </h2>
+                    <button
+                        class="text-gray-400 hover:text-white"
+                        on:click=move |_| on_close.call(())
+                    >
+                        <Icon icon=exit_icon.into() class="w-5 h-5" />
+                    </button>
+                </div>
+
+                {move || if suggestions.with_value(|s| s.is_empty()) {
+                    view! {
+                        <p class="text-sm text-gray-400">
+                            "Für keinen Eintrag wurde eine neue Kategorie erkannt."
+                        </p>
+                    }.into_view()
+                } else {
+                    view! {
+                        <p class="text-sm text-gray-400 mb-4">
+                            "Anhand der Domain wurden Kategorien erkannt. Abgewählte Vorschläge werden ignoriert."
+                        </p>
+                        <select
+                            class="w-full p-2 mb-4 rounded bg-background text-white border border-gray-600 focus:border-primary-100 focus:outline-none"
+                            on:change=move |ev| set_target.set(event_target_value(&ev))
+                            prop:value=target
+                        >
+                            <option value="tag">"Als Tags zuweisen"</option>
+                            <option value="folder">"In Ordner verschieben (nur Einträge ohne Ordner)"</option>
+                        </select>
+                        <div class="max-h-64 overflow-y-auto space-y-1 mb-4">
+                            {suggestions.get_value().into_iter().map(|suggestion| {
+                                let id = suggestion.id;
+                                view! {
+                                    <label class="flex items-center justify-between p-2 rounded border border-gray-600 cursor-pointer">
+                                        <div class="flex items-center space-x-3 min-w-0">
+                                            <input
+                                                type="checkbox"
+                                                class="w-4 h-4 bg-background border-gray-600 rounded focus:ring-primary-100"
+                                                prop:checked=move || selected.with(|s| s.contains(&id))
+                                                on:change=move |ev| {
+                                                    let checked = event_target_checked(&ev);
+                                                    set_selected.update(|s| {
+                                                        if checked {
+                                                            s.insert(id);
+                                                        } else {
+                                                            s.remove(&id);
+                                                        }
+                                                    });
+                                                }
+                                            />
+                                            <span class="text-white text-sm truncate">{suggestion.service}</span>
+                                        </div>
+                                        <span class="ml-2 px-2 py-0.5 rounded-full border border-gray-600 text-xs text-gray-400 whitespace-nowrap">
+                                            {suggestion.category}
+                                        </span>
+                                    </label>
+                                }
+                            }).collect_view()}
+                        </div>
+                        {move || (!error.get().is_empty()).then(|| view! {
+                            <p class="mb-4 text-sm text-red-500">{error.get()}</p>
+                        })}
+                        <div class="flex space-x-2">
+                            <button
+                                class="flex-1 px-4 py-2 rounded border border-gray-600 text-gray-400 hover:text-white"
+                                on:click=move |_| on_close.call(())
+                            >
+                                "Ignorieren"
+                            </button>
+                            <button
+                                class="flex-1 flex items-center justify-center bg-gradient-primary text-white font-bold px-4 py-2 rounded hover:opacity-90 disabled:opacity-50"
+                                prop:disabled=is_applying
+                                on:click=handle_apply
+                            >
+                                <Icon icon=check_icon.into() class="w-5 h-5 mr-2" />
+                                "Alle übernehmen"
+                            </button>
+                        </div>
+                    }.into_view()
+                }}
+            </div>
+        </div>
+    }
+}
@@ -0,0 +1,197 @@
+use crate::{app::invoke, components::icons::Icon};
+use leptos::*;
+use serde::{Deserialize, Serialize};
+
+#[derive(Clone, Deserialize, PartialEq)]
+struct ExpiringNote {
+    id: i32,
+    content: String,
+    expires_on: String,
+    redacted_at: Option<String>,
+}
+
+#[derive(Serialize)]
+struct GetExpiringNotesArgs {
+    id: i32,
+    #[serde(rename = "masterPass")]
+    master_pass: Option<String>,
+}
+
+#[derive(Serialize)]
+struct AddExpiringNoteArgs {
+    id: i32,
+    content: String,
+    #[serde(rename = "expiresOn")]
+    expires_on: String,
+}
+
+#[derive(Serialize)]
+struct DeleteExpiringNoteArgs {
+    id: i32,
+    #[serde(rename = "noteId")]
+    note_id: i32,
+}
+
+/// Format a `YYYY-MM-DD` date the German way.
+fn format_day(day: &str) -> String {
+    let mut parts = day.splitn(3, '-');
+    match (parts.next(), parts.next(), parts.next()) {
+        (Some(year), Some(month), Some(day)) => format!("{}.{}.{}", day, month, year),
+        _ => day.to_string(),
+    }
+}
+
+#[component]
+pub fn ExpiringNotesDialog(
+    id: i32,
+    service: String,
+    master_pass: Option<String>,
+    #[prop(into)] on_close: Callback<()>,
+) -> impl IntoView {
+    let (notes, set_notes) = create_signal(Vec::<ExpiringNote>::new());
+    let (content, set_content) = create_signal(String::new());
+    let (expires_on, set_expires_on) = create_signal(String::new());
+    let (error, set_error) = create_signal(String::new());
+
+    let exit_icon = create_memo(move |_| "x-mark");
+    let clock_icon = create_memo(move |_| "clock");
+    let plus_icon = create_memo(move |_| "plus");
+    let trash_icon = create_memo(move |_| "trash");
+
+    let show_error = move |response: wasm_bindgen::JsValue| {
+        set_error.set(
+            response
+                .as_string()
+                .unwrap_or("Unbekannter Fehler".to_string()),
+        );
+    };
+
+    let load_notes = move || {
+        let master_pass = master_pass.clone();
+        spawn_local(async move {
+            let args =
+                serde_wasm_bindgen::to_value(&GetExpiringNotesArgs { id, master_pass }).unwrap();
+            let response = invoke("get_expiring_notes", args).await;
+            match serde_wasm_bindgen::from_value::<Vec<ExpiringNote>>(response.clone()) {
+                Ok(loaded) => set_notes.set(loaded),
+                Err(_) => show_error(response),
+            }
+        });
+    };
+    let load_notes = store_value(load_notes);
+    load_notes.with_value(|load| load());
+
+    let handle_add = move |_| {
+        spawn_local(async move {
+            let args = serde_wasm_bindgen::to_value(&AddExpiringNoteArgs {
+                id,
+                content: content.get_untracked(),
+                expires_on: expires_on.get_untracked(),
+            })
+            .unwrap();
+            let response = invoke("add_expiring_note", args).await;
+            if serde_wasm_bindgen::from_value::<i32>(response.clone()).is_ok() {
+                set_content.set(String::new());
+                set_expires_on.set(String::new());
+                set_error.set(String::new());
+                load_notes.with_value(|load| load());
+            } else {
+                show_error(response);
+            }
+        });
+    };
+
+    let handle_delete = move |note_id: i32| {
+        spawn_local(async move {
+            let args =
+                serde_wasm_bindgen::to_value(&DeleteExpiringNoteArgs { id, note_id }).unwrap();
+            let response = invoke("delete_expiring_note", args).await;
+            if response.is_null() || response.is_undefined() {
+                set_notes.update(|notes| notes.retain(|note| note.id != note_id));
+            } else {
+                show_error(response);
+            }
+        });
+    };
+
+    view! {
+        <div class="fixed inset-0 bg-black bg-opacity-50 flex items-center justify-center z-50">
+            <div class="bg-background-card rounded-lg p-6 w-full max-w-md">
+                <div class="flex justify-between items-center mb-6">
+                    <h2 class="text-xl font-bold bg-gradient-primary bg-clip-text text-transparent flex items-center">
+                        <Icon icon=clock_icon.into() class="w-8 h-8 mr-2 text-primary-100" />
+                        {format!("Ablaufende Notizen: {}", service)}
+                    </h2>
+                    <button
+                        class="text-gray-400 hover:text-white"
+                        on:click=move |_| on_close.call(())
+                    >
+                        <Icon icon=exit_icon.into() class="w-5 h-5" />
+                    </button>
+                </div>
+
+                {move || (!error.get().is_empty()).then(|| view! {
+                    <div class="mb-4 text-primary-100 text-sm">{error.get()}</div>
+                })}
+
+                <ul class="max-h-64 overflow-y-auto space-y-2 mb-4">
+                    {move || notes.get().into_iter().map(|note| {
+                        let note_id = note.id;
+                        let redacted = note.redacted_at.is_some();
+                        view! {
+                            <li class="flex items-start justify-between p-2 rounded bg-background">
+                                <div class="min-w-0 mr-2">
+                                    {if redacted {
+                                        view! {
+                                            <p class="text-gray-500 italic">"Abgelaufen, Inhalt entfernt"</p>
+                                        }
+                                    } else {
+                                        view! {
+                                            <p class="text-white whitespace-pre-wrap break-words">{note.content}</p>
+                                        }
+                                    }}
+                                    <p class="text-xs text-gray-400">
+                                        {format!("Gültig bis {}", format_day(&note.expires_on))}
+                                    </p>
+                                </div>
+                                <button
+                                    class="text-gray-400 hover:text-red-500 shrink-0"
+                                    title="Löschen"
+                                    on:click=move |_| handle_delete(note_id)
+                                >
+                                    <Icon icon=trash_icon.into() class="w-5 h-5" />
+                                </button>
+                            </li>
+                        }
+                    }).collect_view()}
+                </ul>
+
+                <div class="space-y-4">
+                    <p class="text-sm text-gray-400">
+                        "Nach dem gewählten Tag wird der Inhalt der Notiz automatisch entfernt, etwa für temporäre Codes."
+                    </p>
+                    <textarea
+                        class="w-full h-24 p-2 rounded bg-background text-white border border-gray-600 focus:outline-none focus:border-primary-100"
+                        placeholder="Notiz"
+                        on:input=move |ev| set_content.set(event_target_value(&ev))
+                        prop:value=content
+                    />
+                    <input
+                        type="date"
+                        class="w-full p-2 rounded bg-background text-white border border-gray-600 focus:outline-none focus:border-primary-100"
+                        on:input=move |ev| set_expires_on.set(event_target_value(&ev))
+                        prop:value=expires_on
+                    />
+                    <button
+                        class="w-full bg-gradient-primary text-white px-4 py-2 rounded hover:opacity-90 flex items-center justify-center disabled:opacity-50 disabled:cursor-not-allowed"
+                        prop:disabled=move || content.get().trim().is_empty() || expires_on.get().is_empty()
+                        on:click=handle_add
+                    >
+                        <Icon icon=plus_icon.into() class="w-5 h-5 mr-2" />
+                        "Notiz hinzufügen"
+                    </button>
+                </div>
+            </div>
+        </div>
+    }
+}
@@ -0,0 +1,394 @@
+use crate::{app::invoke, components::icons::Icon};
+use leptos::*;
+use serde::{Deserialize, Serialize};
+use std::collections::{HashMap, HashSet};
+
+#[derive(Clone, PartialEq, Deserialize)]
+pub struct Folder {
+    pub id: i32,
+    pub parent_id: Option<i32>,
+    pub name: String,
+}
+
+/// The part of the vault shown in the password table.
+#[derive(Clone, Copy, PartialEq)]
+pub enum FolderSelection {
+    All,
+    Unfiled,
+    Folder(i32),
+}
+
+#[derive(Clone, Copy, PartialEq)]
+enum FolderEdit {
+    Create(Option<i32>),
+    Rename(i32),
+}
+
+#[derive(Serialize)]
+struct CreateFolderArgs {
+    name: String,
+    #[serde(rename = "parentId")]
+    parent_id: Option<i32>,
+}
+
+#[derive(Serialize)]
+struct RenameFolderArgs {
+    id: i32,
+    name: String,
+}
+
+#[derive(Serialize)]
+struct FolderIdArgs {
+    id: i32,
+}
+
+/// Load the folders of the logged in user.
+pub async fn load_folders() -> Vec<Folder> {
+    let response = invoke("get_folders", wasm_bindgen::JsValue::NULL).await;
+    serde_wasm_bindgen::from_value(response).unwrap_or_default()
+}
+
+/// Sort the folders into tree order, each folder with its depth.
+///
+/// Children of collapsed folders are left out, folders with an unknown parent are shown at
+/// the top level.
+pub fn folder_tree(folders: &[Folder], collapsed: &HashSet<i32>) -> Vec<(Folder, usize)> {
+    let ids: HashSet<i32> = folders.iter().map(|f| f.id).collect();
+    let mut children: HashMap<Option<i32>, Vec<&Folder>> = HashMap::new();
+    for folder in folders {
+        let parent = folder.parent_id.filter(|id| ids.contains(id));
+        children.entry(parent).or_default().push(folder);
+    }
+
+    let mut tree = Vec::new();
+    let mut stack: Vec<(&Folder, usize)> = children
+        .get(&None)
+        .map(|roots| roots.iter().rev().map(|f| (*f, 0)).collect())
+        .unwrap_or_default();
+    while let Some((folder, depth)) = stack.pop() {
+        tree.push((folder.clone(), depth));
+        if collapsed.contains(&folder.id) {
+            continue;
+        }
+        if let Some(nested) = children.get(&Some(folder.id)) {
+            stack.extend(nested.iter().rev().map(|f| (*f, depth + 1)));
+        }
+    }
+
+    tree
+}
+
+/// Get a folder and all folders nested in it.
+pub fn folder_with_descendants(folders: &[Folder], id: i32) -> HashSet<i32> {
+    let mut ids = HashSet::from([id]);
+    loop {
+        let before = ids.len();
+        for folder in folders {
+            if folder.parent_id.is_some_and(|parent| ids.contains(&parent)) {
+                ids.insert(folder.id);
+            }
+        }
+        if ids.len() == before {
+            return ids;
+        }
+    }
+}
+
+#[component]
+pub fn FolderSidebar(
+    folders: ReadSignal<Vec<Folder>>,
+    selected: ReadSignal<FolderSelection>,
+    #[prop(into)] counts: Signal<HashMap<Option<i32>, usize>>,
+    #[prop(into)] on_select: Callback<FolderSelection>,
+    #[prop(into)] on_changed: Callback<()>,
+) -> impl IntoView {
+    let (collapsed, set_collapsed) = create_signal(HashSet::<i32>::new());
+    let (editing, set_editing) = create_signal(None::<FolderEdit>);
+    let (name, set_name) = create_signal(String::new());
+    let (error, set_error) = create_signal(String::new());
+
+    let folder_icon = create_memo(move |_| "folder-open");
+    let all_icon = create_memo(move |_| "key");
+    let unfiled_icon = create_memo(move |_| "archive-box");
+    let plus_icon = create_memo(move |_| "plus");
+    let chevron_icon = create_memo(move |_| "chevron-down");
+    let pencil_icon = create_memo(move |_| "pencil-square");
+    let trash_icon = create_memo(move |_| "trash");
+    let check_icon = create_memo(move |_| "check");
+    let cancel_icon = create_memo(move |_| "x-mark");
+
+    let start_edit = move |edit: FolderEdit, current: String| {
+        set_name.set(current);
+        set_error.set(String::new());
+        set_editing.set(Some(edit));
+    };
+
+    let handle_response = move |response: wasm_bindgen::JsValue| {
+        if response.is_string() {
+            set_error.set(
+                response
+                    .as_string()
+                    .unwrap_or("Unbekannter Fehler".to_string()),
+            );
+        } else {
+            set_error.set(String::new());
+            set_editing.set(None);
+            on_changed.call(());
+        }
+    };
+
+    let handle_submit = move || {
+        let Some(edit) = editing.get() else {
+            return;
+        };
+        let name = name.get();
+        spawn_local(async move {
+            let response = match edit {
+                FolderEdit::Create(parent_id) => {
+                    let args = serde_wasm_bindgen::to_value(&CreateFolderArgs { name, parent_id })
+                        .unwrap();
+                    if let Some(parent_id) = parent_id {
+                        set_collapsed.update(|c| {
+                            c.remove(&parent_id);
+                        });
+                    }
+                    invoke("create_folder", args).await
+                }
+                FolderEdit::Rename(id) => {
+                    let args =
+                        serde_wasm_bindgen::to_value(&RenameFolderArgs { id, name }).unwrap();
+                    invoke("rename_folder", args).await
+                }
+            };
+            handle_response(response);
+        });
+    };
+
+    let handle_delete = move |id: i32| {
+        spawn_local(async move {
+            let args = serde_wasm_bindgen::to_value(&FolderIdArgs { id }).unwrap();
+            let response = invoke("delete_folder", args).await;
+            if serde_wasm_bindgen::from_value::<()>(response.clone()).is_ok()
+                && selected.get_untracked() == FolderSelection::Folder(id)
+            {
+                on_select.call(FolderSelection::All);
+            }
+            handle_response(response);
+        });
+    };
+
+    let name_input = move |edit: FolderEdit, depth: usize| {
+        (editing.get() == Some(edit)).then(|| {
+            view! {
+                <div class="flex items-center gap-1 py-1" style=format!("padding-left: {}rem", depth as f32 + 0.5)>
+                    <input
+                        type="text"
+                        class="flex-1 min-w-0 px-2 py-1 rounded bg-background text-white text-sm border border-gray-600 focus:outline-none focus:border-primary-100"
+                        placeholder="Ordnername"
+                        prop:value=name
+                        on:input=move |ev| set_name.set(event_target_value(&ev))
+                        on:keydown=move |ev| match ev.key().as_str() {
+                            "Enter" => handle_submit(),
+                            "Escape" => set_editing.set(None),
+                            _ => {}
+                        }
+                    />
+                    <button class="text-gray-400 hover:text-primary-100" title="Speichern" on:click=move |_| handle_submit()>
+                        <Icon icon=check_icon.into() class="w-4 h-4" />
+                    </button>
+                    <button class="text-gray-400 hover:text-white" title="Abbrechen" on:click=move |_| set_editing.set(None)>
+                        <Icon icon=cancel_icon.into() class="w-4 h-4" />
+                    </button>
+                </div>
+            }
+        })
+    };
+
+    let count = move |folder: Option<i32>| counts.with(|c| c.get(&folder).copied().unwrap_or(0));
+
+    view! {
+        <div class="w-56 shrink-0 bg-background-card rounded-lg p-2 overflow-y-auto text-sm">
+            <div class="flex items-center justify-between px-2 py-2 text-gray-400 font-medium">
+                "Ordner"
+                <button
+                    class="hover:text-primary-100"
+                    title="Neuer Ordner"
+                    on:click=move |_| start_edit(FolderEdit::Create(None), String::new())
+                >
+                    <Icon icon=plus_icon.into() class="w-4 h-4" />
+                </button>
+            </div>
+
+            <button
+                class="w-full flex items-center px-2 py-1.5 rounded hover:bg-background transition-colors"
+                class:text-primary-100=move || selected.get() == FolderSelection::All
+                class:text-white=move || selected.get() != FolderSelection::All
+                on:click=move |_| on_select.call(FolderSelection::All)
+            >
+                <Icon icon=all_icon.into() class="w-4 h-4 mr-2" />
+                <span class="flex-1 text-left">"Alle Einträge"</span>
+                <span class="text-xs text-gray-400">{move || counts.with(|c| c.values().sum::<usize>())}</span>
+            </button>
+            <button
+                class="w-full flex items-center px-2 py-1.5 rounded hover:bg-background transition-colors"
+                class:text-primary-100=move || selected.get() == FolderSelection::Unfiled
+                class:text-white=move || selected.get() != FolderSelection::Unfiled
+                on:click=move |_| on_select.call(FolderSelection::Unfiled)
+            >
+                <Icon icon=unfiled_icon.into() class="w-4 h-4 mr-2" />
+                <span class="flex-1 text-left">"Ohne Ordner"</span>
+                <span class="text-xs text-gray-400">{move || count(None)}</span>
+            </button>
+
+            {move || name_input(FolderEdit::Create(None), 0)}
+
+            {move || {
+                let all = folders.get();
+                let collapsed_ids = collapsed.get();
+                folder_tree(&all, &collapsed_ids).into_iter().map(|(folder, depth)| {
+                    let id = folder.id;
+                    let has_children = all.iter().any(|f| f.parent_id == Some(id));
+                    let is_collapsed = collapsed_ids.contains(&id);
+                    let folder_name = folder.name.clone();
+                    view! {
+                        {move || if editing.get() == Some(FolderEdit::Rename(id)) {
+                            name_input(FolderEdit::Rename(id), depth).into_view()
+                        } else {
+                            let folder_name = folder_name.clone();
+                            view! {
+                                <div
+                                    class="group flex items-center pr-2 py-1.5 rounded hover:bg-background cursor-pointer transition-colors"
+                                    class:text-primary-100=move || selected.get() == FolderSelection::Folder(id)
+                                    class:text-white=move || selected.get() != FolderSelection::Folder(id)
+                                    style=format!("padding-left: {}rem", depth as f32 + 0.5)
+                                    on:click=move |_| on_select.call(FolderSelection::Folder(id))
+                                >
+                                    <button
+                                        class=match (has_children, is_collapsed) {
+                                            (false, _) => "w-4 h-4 mr-1 shrink-0 invisible",
+                                            (true, false) => "w-4 h-4 mr-1 shrink-0 text-gray-400 hover:text-white",
+                                            (true, true) => "w-4 h-4 mr-1 shrink-0 text-gray-400 hover:text-white -rotate-90",
+                                        }
+                                        on:click=move |ev| {
+                                            ev.stop_propagation();
+                                            set_collapsed.update(|c| {
+                                                if !c.remove(&id) {
+                                                    c.insert(id);
+                                                }
+                                            });
+                                        }
+                                    >
+                                        <Icon icon=chevron_icon.into() class="w-4 h-4" />
+                                    </button>
+                                    <Icon icon=folder_icon.into() class="w-4 h-4 mr-2 shrink-0" />
+                                    <span class="flex-1 truncate" title=folder_name.clone()>{folder_name.clone()}</span>
+                                    <span class="text-xs text-gray-400 group-hover:hidden">{move || count(Some(id))}</span>
+                                    <div class="hidden group-hover:flex items-center gap-1 text-gray-400">
+                                        <button
+                                            class="hover:text-primary-100"
+                                            title="Unterordner erstellen"
+                                            on:click=move |ev| {
+                                                ev.stop_propagation();
+                                                start_edit(FolderEdit::Create(Some(id)), String::new());
+                                            }
+                                        >
+                                            <Icon icon=plus_icon.into() class="w-4 h-4" />
+                                        </button>
+                                        <button
+                                            class="hover:text-primary-100"
+                                            title="Umbenennen"
+                                            on:click={
+                                                let folder_name = folder_name.clone();
+                                                move |ev| {
+                                                    ev.stop_propagation();
+                                                    start_edit(FolderEdit::Rename(id), folder_name.clone());
+                                                }
+                                            }
+                                        >
+                                            <Icon icon=pencil_icon.into() class="w-4 h-4" />
+                                        </button>
+                                        <button
+                                            class="hover:text-red-500"
+                                            title="Löschen, die Einträge bleiben erhalten"
+                                            on:click=move |ev| {
+                                                ev.stop_propagation();
+                                                handle_delete(id);
+                                            }
+                                        >
+                                            <Icon icon=trash_icon.into() class="w-4 h-4" />
+                                        </button>
+                                    </div>
+                                </div>
+                            }.into_view()
+                        }}
+                        {move || name_input(FolderEdit::Create(Some(id)), depth + 1)}
+                    }
+                }).collect_view()
+            }}
+
+            {move || (!error.get().is_empty()).then(|| view! {
+                <p class="px-2 py-2 text-primary-100">{error.get()}</p>
+            })}
+        </div>
+    }
+}
+
+#[component]
+pub fn MoveToFolderDialog(
+    service: String,
+    current: Option<i32>,
+    #[prop(into)] folders: Signal<Vec<Folder>>,
+    #[prop(into)] on_move: Callback<Option<i32>>,
+    #[prop(into)] on_close: Callback<()>,
+) -> impl IntoView {
+    let exit_icon = create_memo(move |_| "x-mark");
+    let folder_icon = create_memo(move |_| "folder-open");
+    let unfiled_icon = create_memo(move |_| "archive-box");
+
+    let option_class = move |folder: Option<i32>| {
+        if folder == current {
+            "w-full flex items-center p-2 rounded bg-background text-primary-100"
+        } else {
+            "w-full flex items-center p-2 rounded hover:bg-background text-white transition-colors"
+        }
+    };
+
+    view! {
+        <div class="fixed inset-0 bg-black bg-opacity-50 flex items-center justify-center z-50">
+            <div class="bg-background-card rounded-lg p-6 w-full max-w-md">
+                <div class="flex justify-between items-center mb-6">
+                    <h2 class="text-xl font-bold bg-gradient-primary bg-clip-text text-transparent flex items-center">
+                        <Icon icon=folder_icon.into() class="w-8 h-8 mr-2 text-primary-100" />
+                        {format!("Verschieben: {}", service)}
+                    </h2>
+                    <button
+                        class="text-gray-400 hover:text-white"
+                        on:click=move |_| on_close.call(())
+                    >
+                        <Icon icon=exit_icon.into() class="w-5 h-5" />
+                    </button>
+                </div>
+
+                <div class="max-h-80 overflow-y-auto space-y-1">
+                    <button class=option_class(None) on:click=move |_| on_move.call(None)>
+                        <Icon icon=unfiled_icon.into() class="w-4 h-4 mr-2" />
+                        "Ohne Ordner"
+                    </button>
+                    {move || folder_tree(&folders.get(), &HashSet::new()).into_iter().map(|(folder, depth)| {
+                        let id = folder.id;
+                        view! {
+                            <button
+                                class=option_class(Some(id))
+                                style=format!("padding-left: {}rem", depth as f32 + 0.5)
+                                on:click=move |_| on_move.call(Some(id))
+                            >
+                                <Icon icon=folder_icon.into() class="w-4 h-4 mr-2 shrink-0" />
+                                <span class="truncate">{folder.name}</span>
+                            </button>
+                        }
+                    }).collect_view()}
+                </div>
+            </div>
+        </div>
+    }
+}
@@ -0,0 +1,78 @@
+use leptos::*;
+use serde::Deserialize;
+
+/// Characters shown around a hit in a long field like the notes.
+const SNIPPET_CONTEXT: usize = 20;
+
+#[derive(Clone, Deserialize, PartialEq)]
+pub struct SearchMatch {
+    pub field: String,
+    pub start: usize,
+    pub end: usize,
+}
+
+/// Get the character ranges of the hits in a field.
+pub fn ranges_for(matches: &[SearchMatch], field: &str) -> Vec<(usize, usize)> {
+    matches
+        .iter()
+        .filter(|m| m.field == field)
+        .map(|m| (m.start, m.end))
+        .collect()
+}
+
+#[component]
+pub fn Highlighted(text: String, ranges: Vec<(usize, usize)>) -> impl IntoView {
+    let chars: Vec<char> = text.chars().collect();
+    let mut parts = Vec::new();
+    let mut position = 0;
+
+    for (start, end) in ranges {
+        let start = start.clamp(position, chars.len());
+        let end = end.clamp(start, chars.len());
+        parts.push((chars[position..start].iter().collect::<String>(), false));
+        parts.push((chars[start..end].iter().collect::<String>(), true));
+        position = end;
+    }
+    parts.push((chars[position..].iter().collect::<String>(), false));
+
+    parts
+        .into_iter()
+        .filter(|(part, _)| !part.is_empty())
+        .map(|(part, hit)| {
+            if hit {
+                view! { <mark class="bg-primary-100 bg-opacity-30 text-white rounded">{part}</mark> }
+                    .into_view()
+            } else {
+                part.into_view()
+            }
+        })
+        .collect_view()
+}
+
+#[component]
+pub fn MatchSnippet(
+    label: &'static str,
+    text: String,
+    ranges: Vec<(usize, usize)>,
+) -> impl IntoView {
+    let chars: Vec<char> = text.chars().collect();
+    let first = ranges.first().copied().unwrap_or((0, 0));
+    let from = first.0.saturating_sub(SNIPPET_CONTEXT);
+    let to = (first.1 + SNIPPET_CONTEXT).min(chars.len());
+
+    let snippet: String = chars[from..to].iter().collect();
+    let ranges = ranges
+        .into_iter()
+        .filter(|(start, end)| *start >= from && *end <= to)
+        .map(|(start, end)| (start - from, end - from))
+        .collect::<Vec<_>>();
+
+    view! {
+        <div class="text-xs text-gray-400 mt-1 truncate max-w-xs">
+            {label}": "
+            {(from > 0).then_some("…")}
+            <Highlighted text=snippet ranges=ranges />
+            {(to < chars.len()).then_some("…")}
+        </div>
+    }
+}
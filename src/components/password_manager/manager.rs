@@ -2,11 +2,18 @@ use crate::{
     app::invoke,
     components::{
         icons::Icon,
-        password_manager::{ModalMode, PasswordModal, TableItem, TableItemArgs},
+        password_manager::{
+            folder_with_descendants, has_all_tags, load_category_suggestions, load_folders,
+            load_tags, CategoryReview, CategorySuggestion, DialogAction, Folder, FolderSelection,
+            FolderSidebar, ModalMode, PasswordDialog, PasswordModal, QuickAddDialog, SearchMatch,
+            TableItem, TableItemArgs, Tag, TagFilter, TrashDialog,
+        },
+        utils::{use_view_state, SortOrder},
     },
 };
 use leptos::*;
-use serde::Serialize;
+use serde::{Deserialize, Serialize};
+use std::collections::{HashMap, HashSet};
 
 #[derive(Serialize)]
 struct AddPasswordArgs {
@@ -15,6 +22,10 @@ struct AddPasswordArgs {
     password: String,
     url: String,
     notes: String,
+    #[serde(rename = "requireMaster")]
+    require_master: bool,
+    #[serde(rename = "totpSecret")]
+    totp_secret: Option<String>,
 }
 
 #[derive(Serialize)]
@@ -22,9 +33,13 @@ struct UpdatePasswordArgs {
     id: i32,
     service: String,
     username: String,
-    password: String,
+    password: Option<String>,
     url: String,
     notes: String,
+    #[serde(rename = "requireMaster")]
+    require_master: bool,
+    #[serde(rename = "masterPass")]
+    master_pass: Option<String>,
 }
 
 #[derive(Serialize)]
@@ -32,13 +47,50 @@ struct DeletePasswordArgs {
     id: i32,
 }
 
+#[derive(Serialize)]
+struct PinEntryArgs {
+    id: i32,
+}
+
+#[derive(Serialize)]
+struct MoveEntryArgs {
+    id: i32,
+    #[serde(rename = "folderId")]
+    folder_id: Option<i32>,
+}
+
+#[derive(Serialize)]
+struct SetEntryLockedArgs {
+    id: i32,
+    locked: bool,
+}
+
+#[derive(Serialize)]
+struct SetEntryExpiryArgs {
+    id: i32,
+    #[serde(rename = "expiresOn")]
+    expires_on: Option<String>,
+}
+
 #[derive(Serialize)]
 struct GetPasswordsArgs {}
 
-#[derive(Clone, PartialEq)]
-enum SortOrder {
-    ServiceAsc,
-    ServiceDesc,
+#[derive(Serialize)]
+struct SearchPasswordsArgs {
+    query: String,
+    mode: &'static str,
+}
+
+#[derive(Deserialize)]
+struct SearchResult {
+    entry: TableItemArgs,
+    matches: Vec<SearchMatch>,
+}
+
+#[derive(Deserialize)]
+struct ViewSettings {
+    default_sort: String,
+    double_click: String,
 }
 
 #[component]
@@ -46,10 +98,31 @@ pub fn PasswordManager() -> impl IntoView {
     let (passwords, set_passwords) = create_signal(Vec::<TableItemArgs>::new());
     let (is_loading, set_is_loading) = create_signal(true);
     let (show_modal, set_show_modal) = create_signal(false);
+    let (pending_unprotect, set_pending_unprotect) =
+        create_signal(None::<(TableItemArgs, Option<String>)>);
     let (modal_mode, set_modal_mode) = create_signal::<ModalMode>(ModalMode::Add);
     let (show_filter_dropdown, set_show_filter_dropdown) = create_signal(false);
-    let (sort_order, set_sort_order) = create_signal(SortOrder::ServiceAsc);
-    let (search_text, set_search_text) = create_signal(String::new());
+    let view_state = use_view_state();
+    let sort_order =
+        create_memo(move |_| view_state.sort_order.get().unwrap_or(SortOrder::ServiceAsc));
+    let set_sort_order = move |order| view_state.sort_order.set(Some(order));
+    let (search_text, set_search_text) = view_state.search_text.split();
+    let (save_error, set_save_error) = create_signal(String::new());
+    let (show_quick_add, set_show_quick_add) = create_signal(false);
+    let (show_trash, set_show_trash) = create_signal(false);
+    let (pinned, set_pinned) = create_signal(Vec::<i32>::new());
+    let (pin_error, set_pin_error) = create_signal(String::new());
+    let (search_matches, set_search_matches) =
+        create_signal(HashMap::<i32, Vec<SearchMatch>>::new());
+    let (search_mode, set_search_mode) = view_state.search_mode.split();
+    let (double_click, set_double_click) = create_signal("copy_password".to_string());
+    let (search_error, set_search_error) = create_signal(String::new());
+    let (folders, set_folders) = create_signal(Vec::<Folder>::new());
+    let (selected_folder, set_selected_folder) = view_state.selected_folder.split();
+    let (tags, set_tags) = create_signal(Vec::<Tag>::new());
+    let (selected_tags, set_selected_tags) = view_state.selected_tags.split();
+    let (category_suggestions, set_category_suggestions) =
+        create_signal(None::<Vec<CategorySuggestion>>);
 
     let plus_icon = create_memo(move |_| "plus");
     let key_icon = create_memo(move |_| "key");
@@ -57,11 +130,28 @@ pub fn PasswordManager() -> impl IntoView {
     let search_icon = create_memo(move |_| "magnifying-glass");
     let filter_icon = create_memo(move |_| "funnel");
     let refresh_icon = create_memo(move |_| "arrow-path");
+    let clipboard_icon = create_memo(move |_| "clipboard");
+    let trash_icon = create_memo(move |_| "trash");
+    let categorize_icon = create_memo(move |_| "sparkles");
 
     let head_service_icon = create_memo(move |_| "bookmark");
     let head_username_icon = create_memo(move |_| "user");
     let head_password_icon = create_memo(move |_| "key");
 
+    spawn_local(async move {
+        let response = invoke("get_view_settings", wasm_bindgen::JsValue::NULL).await;
+        if let Ok(settings) = serde_wasm_bindgen::from_value::<ViewSettings>(response) {
+            if view_state.sort_order.get_untracked().is_none() {
+                set_sort_order(if settings.default_sort == "service_desc" {
+                    SortOrder::ServiceDesc
+                } else {
+                    SortOrder::ServiceAsc
+                });
+            }
+            set_double_click.set(settings.double_click);
+        }
+    });
+
     spawn_local(async move {
         let args = serde_wasm_bindgen::to_value(&GetPasswordsArgs {}).unwrap();
         let response = invoke("get_passwords", args).await;
@@ -72,28 +162,211 @@ pub fn PasswordManager() -> impl IntoView {
         set_is_loading.set(false);
     });
 
+    create_effect(move |_| {
+        let query = search_text.get();
+        let mode = search_mode.get();
+        passwords.track();
+        if query.trim().is_empty() {
+            set_search_matches.set(HashMap::new());
+            set_search_error.set(String::new());
+            return;
+        }
+
+        spawn_local(async move {
+            let args = serde_wasm_bindgen::to_value(&SearchPasswordsArgs {
+                query: query.clone(),
+                mode,
+            })
+            .unwrap();
+            let response = invoke("search_passwords", args).await;
+            if search_text.get_untracked() != query || search_mode.get_untracked() != mode {
+                return;
+            }
+            match serde_wasm_bindgen::from_value::<Vec<SearchResult>>(response.clone()) {
+                Ok(results) => {
+                    set_search_error.set(String::new());
+                    set_search_matches.set(
+                        results
+                            .into_iter()
+                            .map(|result| (result.entry.id, result.matches))
+                            .collect(),
+                    );
+                }
+                Err(_) => {
+                    set_search_matches.set(HashMap::new());
+                    set_search_error.set(
+                        response
+                            .as_string()
+                            .unwrap_or("Unbekannter Fehler".to_string()),
+                    );
+                }
+            }
+        });
+    });
+
+    spawn_local(async move {
+        set_folders.set(load_folders().await);
+        set_tags.set(load_tags().await);
+    });
+
+    let handle_tags_changed = move |_| {
+        spawn_local(async move {
+            let loaded = load_tags().await;
+            set_selected_tags.update(|selected| {
+                selected.retain(|id| loaded.iter().any(|tag| tag.id == *id));
+            });
+            set_tags.set(loaded);
+        });
+    };
+
+    let handle_toggle_tag = move |id: i32| {
+        set_selected_tags.update(|selected| {
+            if !selected.remove(&id) {
+                selected.insert(id);
+            }
+        });
+    };
+
+    let handle_folders_changed = move |_| {
+        spawn_local(async move {
+            set_folders.set(load_folders().await);
+            let response = invoke("get_passwords", wasm_bindgen::JsValue::NULL).await;
+            if let Ok(passwords) = serde_wasm_bindgen::from_value::<Vec<TableItemArgs>>(response) {
+                set_passwords.set(passwords);
+            }
+        });
+    };
+
+    let handle_move = move |(id, folder_id): (i32, Option<i32>)| {
+        spawn_local(async move {
+            let args = serde_wasm_bindgen::to_value(&MoveEntryArgs { id, folder_id }).unwrap();
+            let response = invoke("move_entry_to_folder", args).await;
+            match serde_wasm_bindgen::from_value::<()>(response.clone()) {
+                Ok(_) => {
+                    set_pin_error.set(String::new());
+                    set_passwords.update(|passwords| {
+                        if let Some(password) = passwords.iter_mut().find(|p| p.id == id) {
+                            password.folder_id = folder_id;
+                        }
+                    });
+                }
+                Err(_) => set_pin_error.set(
+                    response
+                        .as_string()
+                        .unwrap_or("Unbekannter Fehler".to_string()),
+                ),
+            }
+        });
+    };
+
+    let handle_toggle_lock = move |(id, locked): (i32, bool)| {
+        spawn_local(async move {
+            let args = serde_wasm_bindgen::to_value(&SetEntryLockedArgs { id, locked }).unwrap();
+            let response = invoke("set_entry_locked", args).await;
+            match serde_wasm_bindgen::from_value::<()>(response.clone()) {
+                Ok(_) => {
+                    set_save_error.set(String::new());
+                    set_passwords.update(|passwords| {
+                        if let Some(password) = passwords.iter_mut().find(|p| p.id == id) {
+                            password.locked = locked;
+                        }
+                    });
+                }
+                Err(_) => set_save_error.set(
+                    response
+                        .as_string()
+                        .unwrap_or("Unbekannter Fehler".to_string()),
+                ),
+            }
+        });
+    };
+
+    let modal_locked = Signal::derive(move || match modal_mode.get() {
+        ModalMode::Edit(item) => passwords.with(|p| p.iter().any(|p| p.id == item.id && p.locked)),
+        ModalMode::Add => false,
+    });
+
+    // Entries in folders that no longer exist, e.g. after restoring an older backup, count
+    // as not filed.
+    let known_folder = move |folder_id: Option<i32>| {
+        folder_id.filter(|id| folders.with(|folders| folders.iter().any(|f| f.id == *id)))
+    };
+
+    let folder_counts = create_memo(move |_| {
+        let mut counts = HashMap::<Option<i32>, usize>::new();
+        for password in passwords.get() {
+            *counts.entry(known_folder(password.folder_id)).or_default() += 1;
+        }
+        counts
+    });
+
+    let load_pinned = move || {
+        spawn_local(async move {
+            let response = invoke("get_pinned_entries", wasm_bindgen::JsValue::NULL).await;
+            if let Ok(ids) = serde_wasm_bindgen::from_value::<Vec<i32>>(response) {
+                set_pinned.set(ids);
+            }
+        });
+    };
+    load_pinned();
+
+    let handle_toggle_pin = move |id: i32| {
+        let command = if pinned.get().contains(&id) {
+            "unpin_entry"
+        } else {
+            "pin_entry"
+        };
+        spawn_local(async move {
+            let args = serde_wasm_bindgen::to_value(&PinEntryArgs { id }).unwrap();
+            let response = invoke(command, args).await;
+            match serde_wasm_bindgen::from_value::<()>(response.clone()) {
+                Ok(_) => {
+                    set_pin_error.set(String::new());
+                    load_pinned();
+                }
+                Err(_) => set_pin_error.set(
+                    response
+                        .as_string()
+                        .unwrap_or("Unbekannter Fehler".to_string()),
+                ),
+            }
+        });
+    };
+
     let handle_add = move |_| {
         set_modal_mode.set(ModalMode::Add);
+        set_save_error.set(String::new());
         set_show_modal.set(true);
     };
 
     let handle_edit = move |id: i32| {
         if let Some(password) = passwords.get().iter().find(|p| p.id == id) {
             set_modal_mode.set(ModalMode::Edit(password.clone()));
+            set_save_error.set(String::new());
             set_show_modal.set(true);
         }
     };
 
-    let handle_save = move |item: TableItemArgs| {
+    let save_entry = move |item: TableItemArgs,
+                           password: Option<String>,
+                           totp_secret: Option<String>,
+                           master_pass: Option<String>| {
         spawn_local(async move {
+            let expires_on = item.expires_on.clone();
+            let edited = match modal_mode.get_untracked() {
+                ModalMode::Edit(original) => Some((original.id, original.expires_on)),
+                ModalMode::Add => None,
+            };
             let response = match modal_mode.get() {
                 ModalMode::Add => {
                     let args = serde_wasm_bindgen::to_value(&AddPasswordArgs {
                         service: item.service,
                         username: item.username,
-                        password: item.password,
+                        password: password.unwrap_or_default(),
                         url: item.url,
                         notes: item.notes,
+                        require_master: item.require_master,
+                        totp_secret,
                     })
                     .unwrap();
                     invoke("add_password", args).await
@@ -103,16 +376,61 @@ pub fn PasswordManager() -> impl IntoView {
                         id: item.id,
                         service: item.service,
                         username: item.username,
-                        password: item.password,
+                        password,
                         url: item.url,
                         notes: item.notes,
+                        require_master: item.require_master,
+                        master_pass,
                     })
                     .unwrap();
                     invoke("update_password", args).await
                 }
             };
 
-            if serde_wasm_bindgen::from_value::<()>(response).is_ok() {
+            if serde_wasm_bindgen::from_value::<()>(response.clone()).is_err() {
+                set_save_error.set(format!(
+                    "Speichern fehlgeschlagen: {}",
+                    response
+                        .as_string()
+                        .unwrap_or("Unbekannter Fehler".to_string())
+                ));
+                return;
+            }
+
+            let known: HashSet<i32> = passwords
+                .with_untracked(|passwords| passwords.iter().map(|password| password.id).collect());
+            let response = invoke("get_passwords", wasm_bindgen::JsValue::NULL).await;
+            if let Ok(passwords) = serde_wasm_bindgen::from_value::<Vec<TableItemArgs>>(response) {
+                set_passwords.set(passwords);
+            }
+
+            let added: Vec<i32> = passwords.with_untracked(|passwords| {
+                passwords
+                    .iter()
+                    .map(|password| password.id)
+                    .filter(|id| !known.contains(id))
+                    .collect()
+            });
+
+            let expiry_target = match edited {
+                Some((id, original)) => (original != expires_on).then_some(id),
+                None if expires_on.is_some() && added.len() == 1 => Some(added[0]),
+                None => None,
+            };
+            if let Some(id) = expiry_target {
+                let args =
+                    serde_wasm_bindgen::to_value(&SetEntryExpiryArgs { id, expires_on }).unwrap();
+                let response = invoke("set_entry_expiry", args).await;
+                if serde_wasm_bindgen::from_value::<()>(response.clone()).is_err() {
+                    set_save_error.set(format!(
+                        "Ablaufdatum konnte nicht gespeichert werden: {}",
+                        response
+                            .as_string()
+                            .unwrap_or("Unbekannter Fehler".to_string())
+                    ));
+                    return;
+                }
+
                 let response = invoke("get_passwords", wasm_bindgen::JsValue::NULL).await;
                 if let Ok(passwords) =
                     serde_wasm_bindgen::from_value::<Vec<TableItemArgs>>(response)
@@ -121,6 +439,32 @@ pub fn PasswordManager() -> impl IntoView {
                 }
             }
             set_show_modal.set(false);
+
+            if !added.is_empty() {
+                let suggestions = load_category_suggestions(Some(added)).await;
+                if !suggestions.is_empty() {
+                    set_category_suggestions.set(Some(suggestions));
+                }
+            }
+        });
+    };
+
+    let handle_save =
+        move |(item, password, totp_secret): (TableItemArgs, Option<String>, Option<String>)| {
+            let removes_protection = matches!(
+                modal_mode.get_untracked(),
+                ModalMode::Edit(original) if original.require_master && !item.require_master
+            );
+            if removes_protection {
+                set_pending_unprotect.set(Some((item, password)));
+                return;
+            }
+            save_entry(item, password, totp_secret, None);
+        };
+
+    let handle_categorize = move |_| {
+        spawn_local(async move {
+            set_category_suggestions.set(Some(load_category_suggestions(None).await));
         });
     };
 
@@ -136,32 +480,57 @@ pub fn PasswordManager() -> impl IntoView {
                 {
                     set_passwords.set(passwords);
                 }
+                handle_tags_changed(());
             }
         });
     };
 
     let filtered_and_sorted_passwords = create_memo(move |_| {
+        let in_folder = match selected_folder.get() {
+            FolderSelection::Folder(id) => Some(folders.with(|f| folder_with_descendants(f, id))),
+            _ => None,
+        };
         let mut results = passwords
             .get()
             .into_iter()
+            .filter(|p| match selected_folder.get() {
+                FolderSelection::All => true,
+                FolderSelection::Unfiled => known_folder(p.folder_id).is_none(),
+                FolderSelection::Folder(_) => p
+                    .folder_id
+                    .is_some_and(|id| in_folder.as_ref().is_some_and(|ids| ids.contains(&id))),
+            })
+            .filter(|p| tags.with(|tags| has_all_tags(tags, &selected_tags.get(), p.id)))
             .filter(|p| {
-                let search = search_text.get().to_lowercase();
-                if search.is_empty() {
-                    return true;
-                }
-                p.service.to_lowercase().contains(&search)
-                    || p.username.to_lowercase().contains(&search)
+                search_text.get().trim().is_empty() || search_matches.get().contains_key(&p.id)
             })
             .collect::<Vec<_>>();
 
-        match sort_order.get() {
-            SortOrder::ServiceAsc => results.sort_by(|a, b| a.service.cmp(&b.service)),
-            SortOrder::ServiceDesc => results.sort_by(|a, b| b.service.cmp(&a.service)),
+        // The backend returns the entries sorted by the configured locale.
+        if sort_order.get() == SortOrder::ServiceDesc {
+            results.reverse();
         }
 
         results
     });
 
+    let handle_seed_demo = move |_| {
+        set_is_loading.set(true);
+        spawn_local(async move {
+            let response = invoke("seed_demo_data", wasm_bindgen::JsValue::NULL).await;
+            if serde_wasm_bindgen::from_value::<usize>(response).is_ok() {
+                let response = invoke("get_passwords", wasm_bindgen::JsValue::NULL).await;
+                if let Ok(passwords) =
+                    serde_wasm_bindgen::from_value::<Vec<TableItemArgs>>(response)
+                {
+                    set_passwords.set(passwords);
+                }
+            }
+
+            set_is_loading.set(false);
+        });
+    };
+
     let handle_refresh = move |_| {
         set_is_loading.set(true);
         spawn_local(async move {
@@ -191,6 +560,33 @@ pub fn PasswordManager() -> impl IntoView {
                             "Hinzufügen"
                         </span>
                     </button>
+                    <button
+                        class="group relative flex items-center text-gray-400 hover:text-white transition-colors"
+                        on:click=move |_| set_show_quick_add.set(true)
+                    >
+                        <Icon icon=clipboard_icon.into() class="w-5 h-5" />
+                        <span class="ml-2 whitespace-nowrap max-w-0 overflow-hidden group-hover:max-w-[160px] transition-all duration-300 text-primary-100">
+                            "Aus Zwischenablage"
+                        </span>
+                    </button>
+                    <button
+                        class="group relative flex items-center text-gray-400 hover:text-white transition-colors"
+                        on:click=move |_| set_show_trash.set(true)
+                    >
+                        <Icon icon=trash_icon.into() class="w-5 h-5" />
+                        <span class="ml-2 whitespace-nowrap max-w-0 overflow-hidden group-hover:max-w-[100px] transition-all duration-300 text-primary-100">
+                            "Papierkorb"
+                        </span>
+                    </button>
+                    <button
+                        class="group relative flex items-center text-gray-400 hover:text-white transition-colors"
+                        on:click=handle_categorize
+                    >
+                        <Icon icon=categorize_icon.into() class="w-5 h-5" />
+                        <span class="ml-2 whitespace-nowrap max-w-0 overflow-hidden group-hover:max-w-[120px] transition-all duration-300 text-primary-100">
+                            "Kategorisieren"
+                        </span>
+                    </button>
                     <button
                         class="group relative flex items-center text-gray-400 hover:text-white transition-colors"
                         on:click=handle_refresh
@@ -222,11 +618,25 @@ pub fn PasswordManager() -> impl IntoView {
                                 <Icon icon=plus_icon.into() class="w-5 h-5 mr-2" />
                                 "Erstes Passwort hinzufügen"
                             </button>
+                            <button
+                                class="mt-3 text-sm text-gray-400 hover:text-white transition-colors"
+                                on:click=handle_seed_demo
+                            >
+                                "Beispieldaten einfügen"
+                            </button>
                         </div>
                     }.into_view()
                 } else {
                     view! {
-                        <div class="flex-1 flex flex-col bg-background-card rounded-lg min-h-0">
+                        <div class="flex-1 flex gap-4 min-h-0">
+                        <FolderSidebar
+                            folders=folders
+                            selected=selected_folder
+                            counts=folder_counts
+                            on_select=move |selection| set_selected_folder.set(selection)
+                            on_changed=handle_folders_changed
+                        />
+                        <div class="flex-1 flex flex-col bg-background-card rounded-lg min-h-0 min-w-0">
                             <div class="w-full flex items-center gap-2 bg-background-card rounded-lg p-2">
                                 <div class="flex items-center flex-1 relative">
                                     <Icon
@@ -235,7 +645,12 @@ pub fn PasswordManager() -> impl IntoView {
                                     />
                                     <input
                                         type="text"
-                                        placeholder="Suchen..."
+                                        placeholder=move || match search_mode.get() {
+                                            "regex" => "Regulärer Ausdruck, z.B. \\d{4}",
+                                            "glob" => "Muster, z.B. *bank*",
+                                            _ => "Suchen...",
+                                        }
+                                        prop:value=search_text
                                         on:input=move |ev| set_search_text.set(event_target_value(&ev))
                                         class="w-full pl-10 pr-4 py-2 bg-background border border-gray-600 rounded-lg focus:outline-none focus:border-primary-100 text-white"
                                     />
@@ -257,7 +672,7 @@ pub fn PasswordManager() -> impl IntoView {
                                                 class="w-full text-left px-3 py-2 hover:bg-background-light rounded transition-colors flex items-center gap-2"
                                                 class:text-primary-100=move || sort_order.get() == SortOrder::ServiceAsc
                                                 on:click=move |_| {
-                                                    set_sort_order.set(SortOrder::ServiceAsc);
+                                                    set_sort_order(SortOrder::ServiceAsc);
                                                     set_show_filter_dropdown.set(false);
                                                 }
                                             >
@@ -267,17 +682,50 @@ pub fn PasswordManager() -> impl IntoView {
                                                 class="w-full text-left px-3 py-2 hover:bg-background-light rounded transition-colors flex items-center gap-2"
                                                 class:text-primary-100=move || sort_order.get() == SortOrder::ServiceDesc
                                                 on:click=move |_| {
-                                                    set_sort_order.set(SortOrder::ServiceDesc);
+                                                    set_sort_order(SortOrder::ServiceDesc);
                                                     set_show_filter_dropdown.set(false);
                                                 }
                                             >
                                                 "Service (Z-A)"
                                             </button>
+                                            <div class="text-sm text-gray-400 px-3 py-2 font-medium border-t border-gray-600 mt-2">
+                                                "Suchmodus"
+                                            </div>
+                                            {[("text", "Text"), ("regex", "Regex"), ("glob", "Glob")]
+                                                .into_iter()
+                                                .map(|(mode, label)| view! {
+                                                    <button
+                                                        class="w-full text-left px-3 py-2 hover:bg-background-light rounded transition-colors flex items-center gap-2"
+                                                        class:text-primary-100=move || search_mode.get() == mode
+                                                        on:click=move |_| {
+                                                            set_search_mode.set(mode);
+                                                            set_show_filter_dropdown.set(false);
+                                                        }
+                                                    >
+                                                        {label}
+                                                    </button>
+                                                })
+                                                .collect_view()}
                                         </div>
                                     })}
                                 </div>
                             </div>
 
+                            <TagFilter
+                                tags=tags
+                                selected=selected_tags
+                                on_toggle=handle_toggle_tag
+                                on_clear=move |_| set_selected_tags.set(HashSet::new())
+                            />
+
+                            {move || (!search_error.get().is_empty()).then(|| view! {
+                                <p class="px-4 pb-2 text-sm text-primary-100">{search_error.get()}</p>
+                            })}
+
+                            {move || (!pin_error.get().is_empty()).then(|| view! {
+                                <p class="px-4 pb-2 text-sm text-primary-100">{pin_error.get()}</p>
+                            })}
+
                             <div class="w-full overflow-auto">
                                 <table class="w-full">
                                     <thead class="bg-background sticky top-0">
@@ -307,11 +755,21 @@ pub fn PasswordManager() -> impl IntoView {
                                     </thead>
                                     <tbody class="divide-y divide-gray-600">
                                         {move || filtered_and_sorted_passwords.get().into_iter().map(|password| {
+                                            let id = password.id;
+                                            let matches = search_matches.get().get(&id).cloned().unwrap_or_default();
                                             view! {
                                                 <TableItem
                                                     item=password
+                                                    pinned=Signal::derive(move || pinned.get().contains(&id))
+                                                    double_click=double_click
                                                     on_edit=Callback::from(handle_edit)
                                                     on_delete=Callback::from(handle_delete)
+                                                    on_toggle_pin=Callback::from(handle_toggle_pin)
+                                                    folders=folders
+                                                    on_move=Callback::from(handle_move)
+                                                    tags=tags
+                                                    on_tags_changed=Callback::from(handle_tags_changed)
+                                                    matches=matches
                                                 />
                                             }
                                         }).collect_view()}
@@ -319,6 +777,7 @@ pub fn PasswordManager() -> impl IntoView {
                                 </table>
                             </div>
                         </div>
+                        </div>
                     }.into_view()
                 }
             }}
@@ -329,6 +788,9 @@ pub fn PasswordManager() -> impl IntoView {
                         <PasswordModal
                             mode=modal_mode.get()
                             on_save=Callback::from(handle_save)
+                            save_error=save_error
+                            locked=modal_locked
+                            on_toggle_lock=Callback::from(handle_toggle_lock)
                             on_close=move |_| set_show_modal.set(false)
                         />
                     }.into_view()
@@ -336,6 +798,63 @@ pub fn PasswordManager() -> impl IntoView {
                     view! { <div/> }.into_view()
                 }
             }}
+
+            {move || pending_unprotect.get().is_some().then(|| view! {
+                <PasswordDialog
+                    action=DialogAction::Verify
+                    on_close=move |_| set_pending_unprotect.set(None)
+                    on_verify=move |_| ()
+                    on_master_password=move |master_pass: String| {
+                        if let Some((item, password)) = pending_unprotect.get_untracked() {
+                            save_entry(item, password, None, Some(master_pass));
+                        }
+                    }
+                />
+            })}
+
+            {move || category_suggestions.get().map(|suggestions| view! {
+                <CategoryReview
+                    suggestions=suggestions
+                    on_applied=move |_| {
+                        handle_folders_changed(());
+                        handle_tags_changed(());
+                    }
+                    on_close=move |_| set_category_suggestions.set(None)
+                />
+            })}
+
+            {move || show_trash.get().then(|| view! {
+                <TrashDialog
+                    on_close=move |_| set_show_trash.set(false)
+                    on_restored=move |_| {
+                        spawn_local(async move {
+                            let response = invoke("get_passwords", wasm_bindgen::JsValue::NULL).await;
+                            if let Ok(passwords) =
+                                serde_wasm_bindgen::from_value::<Vec<TableItemArgs>>(response)
+                            {
+                                set_passwords.set(passwords);
+                            }
+                        });
+                        handle_tags_changed(());
+                    }
+                />
+            })}
+
+            {move || show_quick_add.get().then(|| view! {
+                <QuickAddDialog
+                    on_close=move |_| set_show_quick_add.set(false)
+                    on_saved=move |_| {
+                        spawn_local(async move {
+                            let response = invoke("get_passwords", wasm_bindgen::JsValue::NULL).await;
+                            if let Ok(passwords) =
+                                serde_wasm_bindgen::from_value::<Vec<TableItemArgs>>(response)
+                            {
+                                set_passwords.set(passwords);
+                            }
+                        });
+                    }
+                />
+            })}
         </div>
     }
 }
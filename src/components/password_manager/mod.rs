@@ -1,9 +1,37 @@
+mod attachments;
+mod category_review;
+mod expiring_notes;
+mod folders;
+mod highlight;
 mod manager;
 mod modal;
 mod password_dialog;
+mod quick_add;
+mod quick_search;
+mod recovery_codes;
+mod secure_notes;
+mod servers;
 mod table_item;
+mod tags;
+mod totp;
+mod trash;
+mod wallet;
 
+pub use attachments::EntryAttachments;
+pub use category_review::{load_category_suggestions, CategoryReview, CategorySuggestion};
+pub use expiring_notes::ExpiringNotesDialog;
+pub use folders::{folder_with_descendants, load_folders, Folder, FolderSelection, FolderSidebar};
+pub use highlight::SearchMatch;
 pub use manager::PasswordManager;
 pub use modal::{ModalMode, PasswordModal};
 pub use password_dialog::{DialogAction, PasswordDialog};
+pub use quick_add::QuickAddDialog;
+pub use quick_search::QuickSearch;
+pub use recovery_codes::RecoveryCodesDialog;
+pub use secure_notes::SecureNotes;
+pub use servers::Servers;
 pub use table_item::{TableItem, TableItemArgs};
+pub use tags::{has_all_tags, load_tags, Tag, TagFilter};
+pub use totp::{LiveTotpCode, TotpDialog};
+pub use trash::TrashDialog;
+pub use wallet::Wallet;
@@ -2,13 +2,14 @@ use crate::{
     app::invoke,
     components::{
         icons::Icon,
+        password_generator::{load_generator_defaults, GeneratePasswordArgs, GeneratorOptions},
         password_manager::password_dialog::{DialogAction, PasswordDialog},
     },
 };
 use leptos::*;
 use serde::{Deserialize, Serialize};
 
-use super::TableItemArgs;
+use super::{EntryAttachments, TableItemArgs};
 
 #[derive(Clone, PartialEq)]
 pub enum ModalMode {
@@ -17,38 +18,56 @@ pub enum ModalMode {
 }
 
 #[derive(Serialize)]
-struct GeneratePasswordArgs {
-    length: usize,
+struct DecryptPasswordArgs<'a> {
+    id: i32,
+    #[serde(rename = "masterPass")]
+    master_pass: &'a str,
+}
+
+#[derive(Deserialize)]
+struct AppSettingsResponse {
+    max_notes_length: usize,
 }
 
 #[derive(Serialize)]
-struct DecryptPasswordArgs<'a> {
-    #[serde(rename = "encryptedPassword")]
-    encrypted_password: &'a str,
+struct UrlArgs {
+    url: String,
 }
 
 #[derive(Deserialize)]
-struct GeneratorLengthResponse {
-    default_length: usize,
+struct UrlMetadataResponse {
+    url: String,
+    service: String,
+    icon: Option<String>,
 }
 
 #[component]
 pub fn PasswordModal(
     #[prop(into)] mode: ModalMode,
-    #[prop(into)] on_save: Callback<TableItemArgs>,
+    #[prop(into)] on_save: Callback<(TableItemArgs, Option<String>, Option<String>)>,
     #[prop(into)] on_close: Callback<()>,
+    #[prop(into)] save_error: Signal<String>,
+    #[prop(into)] locked: Signal<bool>,
+    #[prop(into)] on_toggle_lock: Callback<(i32, bool)>,
 ) -> impl IntoView {
     let (length, set_length) = create_signal(16);
+    let (generator_options, set_generator_options) = create_signal(GeneratorOptions::default());
+    let (max_notes_length, set_max_notes_length) = create_signal(None::<usize>);
     let (service, set_service) = create_signal(String::new());
     let (username, set_username) = create_signal(String::new());
     let (password, set_password) = create_signal(String::new());
     let (url, set_url) = create_signal(String::new());
     let (notes, set_notes) = create_signal(String::new());
+    let (require_master, set_require_master) = create_signal(false);
+    let (expires_on, set_expires_on) = create_signal(String::new());
+    let (totp_only, set_totp_only) = create_signal(false);
+    let (totp_secret, set_totp_secret) = create_signal(String::new());
     let (show_password, set_show_password) = create_signal(false);
     let (show_password_dialog, set_show_password_dialog) = create_signal(false);
     let (decrypted_password, set_decrypted_password) = create_signal(String::new());
     let (password_verified, set_password_verified) = create_signal(false);
     let (is_original_password, set_is_original_password) = create_signal(false);
+    let (favicon, set_favicon) = create_signal(None::<String>);
 
     let eye_icon = create_memo(move |_| {
         if show_password.get() {
@@ -62,7 +81,11 @@ pub fn PasswordModal(
     let user_icon = create_memo(move |_| "user");
     let key_icon = create_memo(move |_| "key");
     let link_icon = create_memo(move |_| "link");
+    let totp_icon = create_memo(move |_| "device-phone-mobile");
     let note_icon = create_memo(move |_| "document-text");
+    let clock_icon = create_memo(move |_| "clock");
+    let lock_icon = create_memo(move |_| "lock-closed");
+    let unlock_icon = create_memo(move |_| "lock-open");
     let cancel_icon = create_memo(move |_| "x-mark");
     let add_icon = create_memo(move |_| "plus");
     let edit_icon = create_memo(move |_| "pencil-square");
@@ -99,27 +122,59 @@ pub fn PasswordModal(
     };
 
     spawn_local(async move {
+        if let Some(defaults) = load_generator_defaults().await {
+            set_length.set(defaults.default_length);
+            set_generator_options.set(defaults.options);
+        }
+
         let response = invoke("get_default_generator_length", wasm_bindgen::JsValue::NULL).await;
-        if let Ok(generator) = serde_wasm_bindgen::from_value::<GeneratorLengthResponse>(response) {
-            set_length.set(generator.default_length);
+        if let Ok(settings) = serde_wasm_bindgen::from_value::<AppSettingsResponse>(response) {
+            set_max_notes_length.set(Some(settings.max_notes_length));
         }
     });
 
     if let ModalMode::Edit(ref item) = mode.get() {
         set_service.set(item.service.clone());
         set_username.set(item.username.clone());
-        set_password.set(item.password.clone());
         set_url.set(item.url.clone());
         set_notes.set(item.notes.clone());
+        set_require_master.set(item.require_master);
+        set_expires_on.set(item.expires_on.clone().unwrap_or_default());
         set_is_original_password.set(true);
     }
 
+    let notes_length = move || notes.with(|notes| notes.chars().count());
+    let notes_too_long = move || {
+        max_notes_length
+            .get()
+            .is_some_and(|max| notes_length() > max)
+    };
+
     let handle_password_change = move |new_password: String| {
         set_password.set(new_password);
         set_is_original_password.set(false);
         set_password_verified.set(false);
     };
 
+    let handle_url_change = move || {
+        let entered = url.get_untracked();
+        if !matches!(mode.get_untracked(), ModalMode::Add) || entered.trim().is_empty() {
+            return;
+        }
+
+        spawn_local(async move {
+            let args = serde_wasm_bindgen::to_value(&UrlArgs { url: entered }).unwrap();
+            let response = invoke("create_entry_from_url", args).await;
+            if let Ok(metadata) = serde_wasm_bindgen::from_value::<UrlMetadataResponse>(response) {
+                set_url.set(metadata.url);
+                if service.get_untracked().trim().is_empty() {
+                    set_service.set(metadata.service);
+                }
+                set_favicon.set(metadata.icon);
+            }
+        });
+    };
+
     view! {
         <div class="absolute inset-0 flex items-center justify-center bg-black bg-opacity-50 overflow-auto p-4">
             <div class="bg-background-card rounded-lg p-6 w-full max-w-md max-h-[90%] flex flex-col">
@@ -141,16 +196,30 @@ pub fn PasswordModal(
                     </button>
                 </div>
 
+                {move || locked.get().then(|| view! {
+                    <div class="mb-4 p-3 rounded border border-gray-600 text-gray-400 text-sm flex items-center">
+                        <Icon icon=lock_icon.into() class="w-4 h-4 mr-2 text-primary-100" />
+                        "Dieser Eintrag ist schreibgeschützt. Entsperre ihn, um ihn zu bearbeiten oder zu löschen"
+                    </div>
+                })}
+
                 <div class="flex-1 overflow-y-auto">
-                    <div class="space-y-4">
+                    <fieldset class="space-y-4" prop:disabled=locked>
                         <div>
                             <label class="block text-white text-sm font-bold mb-2 flex items-center">
-                                <Icon icon=service_icon.into() class="w-4 h-4 mr-2 text-primary-100" />
+                                {move || match favicon.get() {
+                                    Some(icon) => view! {
+                                        <img src=icon alt="" class="w-4 h-4 mr-2 rounded-sm" />
+                                    }.into_view(),
+                                    None => view! {
+                                        <Icon icon=service_icon.into() class="w-4 h-4 mr-2 text-primary-100" />
+                                    }.into_view(),
+                                }}
                                 "Service"
                             </label>
                             <input
                                 type="text"
-                                value=service
+                                prop:value=service
                                 class="w-full p-2 rounded bg-background text-white border border-gray-600 focus:outline-none focus:border-primary-100"
                                 on:input=move |ev| set_service.set(event_target_value(&ev))
                             />
@@ -169,7 +238,40 @@ pub fn PasswordModal(
                             />
                         </div>
 
-                        <div>
+                        {move || matches!(mode.get(), ModalMode::Add).then(|| view! {
+                            <div class="flex items-center">
+                                <input
+                                    type="checkbox"
+                                    id="totp-only"
+                                    class="mr-2"
+                                    prop:checked=totp_only
+                                    on:change=move |ev| set_totp_only.set(event_target_checked(&ev))
+                                />
+                                <label for="totp-only" class="text-white text-sm flex items-center">
+                                    <Icon icon=totp_icon.into() class="w-4 h-4 mr-2 text-primary-100" />
+                                    "Nur Zwei-Faktor-Code, ohne Passwort"
+                                </label>
+                            </div>
+                        })}
+
+                        <div class:hidden=move || !totp_only.get()>
+                            <label class="block text-white text-sm font-bold mb-2 flex items-center">
+                                <Icon icon=totp_icon.into() class="w-4 h-4 mr-2 text-primary-100" />
+                                "Geheimer Schlüssel"
+                            </label>
+                            <input
+                                type="text"
+                                prop:value=totp_secret
+                                placeholder="JBSW Y3DP EHPK 3PXP"
+                                class="w-full p-2 rounded bg-background text-white font-mono border border-gray-600 focus:outline-none focus:border-primary-100"
+                                on:input=move |ev| set_totp_secret.set(event_target_value(&ev))
+                            />
+                            <p class="mt-1 text-xs text-gray-400">
+                                "Der Schlüssel oder die otpauth://-Adresse aus dem QR-Code des Anbieters"
+                            </p>
+                        </div>
+
+                        <div class:hidden=totp_only>
                             <label class="block text-white text-sm font-bold mb-2 flex items-center">
                                 <Icon icon=key_icon.into() class="w-4 h-4 mr-2 text-primary-100" />
                                 "Passwort"
@@ -178,6 +280,7 @@ pub fn PasswordModal(
                                 <input
                                     type=input_type
                                     value=password_value
+                                    placeholder=move || is_original_password.get().then_some("Unverändert")
                                     class="w-full p-2 rounded bg-background text-white border border-gray-600 focus:outline-none focus:border-primary-100 pr-20"
                                     on:input=move |ev| handle_password_change(event_target_value(&ev))
                                 />
@@ -200,11 +303,13 @@ pub fn PasswordModal(
                         </div>
 
                         <button
+                            class:hidden=totp_only
                             class="w-full flex justify-center items-center bg-background border border-primary-100 hover:bg-primary-400/10 text-white font-bold py-2 px-4 rounded focus:outline-none transition-all duration-200"
                             on:click=move |_| {
                                 spawn_local(async move {
                                     let args = serde_wasm_bindgen::to_value(&GeneratePasswordArgs {
                                         length: length.get(),
+                                        options: generator_options.get(),
                                     }).unwrap();
 
                                     let response = invoke("generate_password", args).await;
@@ -226,9 +331,10 @@ pub fn PasswordModal(
                             </label>
                             <input
                                 type="url"
-                                value=url
+                                prop:value=url
                                 class="w-full p-2 rounded bg-background text-white border border-gray-600 focus:outline-none focus:border-primary-100"
                                 on:input=move |ev| set_url.set(event_target_value(&ev))
+                                on:change=move |_| handle_url_change()
                             />
                         </div>
 
@@ -242,11 +348,97 @@ pub fn PasswordModal(
                                 class="w-full p-2 rounded bg-background text-white border border-gray-600 focus:outline-none focus:border-primary-100 resize-none h-24"
                                 on:input=move |ev| set_notes.set(event_target_value(&ev))
                             />
+                            {move || max_notes_length.get().map(|max| view! {
+                                <p class="text-xs text-right" class:text-gray-400=move || !notes_too_long() class:text-red-500=notes_too_long>
+                                    {move || format!("{} / {} Zeichen", notes_length(), max)}
+                                </p>
+                            })}
+                        </div>
+
+                        <div>
+                            <label class="block text-white text-sm font-bold mb-2 flex items-center">
+                                <Icon icon=clock_icon.into() class="w-4 h-4 mr-2 text-primary-100" />
+                                "Läuft ab am"
+                            </label>
+                            <input
+                                type="date"
+                                prop:value=expires_on
+                                class="w-full p-2 rounded bg-background text-white border border-gray-600 focus:outline-none focus:border-primary-100"
+                                on:input=move |ev| set_expires_on.set(event_target_value(&ev))
+                            />
                         </div>
+
+                        <div class="flex items-center">
+                            <input
+                                type="checkbox"
+                                id="require-master"
+                                class="mr-2"
+                                prop:checked=require_master
+                                on:change=move |ev| set_require_master.set(event_target_checked(&ev))
+                            />
+                            <label for="require-master" class="text-white text-sm flex items-center">
+                                <Icon icon=lock_icon.into() class="w-4 h-4 mr-2 text-primary-100" />
+                                "Master-Passwort immer abfragen"
+                            </label>
+                        </div>
+
+                    </fieldset>
+
+                    <div class="mt-4">
+                        {move || match mode.get() {
+                            ModalMode::Edit(item) => view! {
+                                <EntryAttachments id=item.id require_master=item.require_master locked=locked />
+                            }.into_view(),
+                            ModalMode::Add => view! {
+                                <p class="text-sm text-gray-400">"Dateien können nach dem Speichern angehängt werden"</p>
+                            }.into_view(),
+                        }}
                     </div>
                 </div>
 
+                {move || {
+                    let message = if notes_too_long() {
+                        max_notes_length
+                            .get()
+                            .map(|max| format!(
+                                "Die Notizen überschreiten das Limit von {} Zeichen ({} verwendet)",
+                                max,
+                                notes_length()
+                            ))
+                            .unwrap_or_default()
+                    } else {
+                        save_error.get()
+                    };
+                    (!message.is_empty()).then(|| view! {
+                        <div class="mt-4 text-red-500 text-sm text-center">{message}</div>
+                    })
+                }}
+
                 <div class="flex justify-end space-x-3 mt-6 pt-4 border-t border-gray-600">
+                    {move || match mode.get() {
+                        ModalMode::Edit(item) => {
+                            let id = item.id;
+                            view! {
+                                <button
+                                    class="mr-auto px-4 py-2 text-white hover:text-primary-100 flex items-center"
+                                    on:click=move |_| on_toggle_lock.call((id, !locked.get()))
+                                >
+                                    {move || if locked.get() {
+                                        view! {
+                                            <Icon icon=unlock_icon.into() class="w-5 h-5 mr-2" />
+                                            "Entsperren"
+                                        }.into_view()
+                                    } else {
+                                        view! {
+                                            <Icon icon=lock_icon.into() class="w-5 h-5 mr-2" />
+                                            "Sperren"
+                                        }.into_view()
+                                    }}
+                                </button>
+                            }.into_view()
+                        }
+                        ModalMode::Add => view! { <div/> }.into_view(),
+                    }}
                     <button
                         class="px-4 py-2 text-white hover:text-primary-100 flex items-center"
                         on:click=move |_| on_close.call(())
@@ -255,7 +447,8 @@ pub fn PasswordModal(
                         "Abbrechen"
                     </button>
                     <button
-                        class="bg-gradient-primary text-white px-4 py-2 rounded hover:opacity-90 flex items-center"
+                        class="bg-gradient-primary text-white px-4 py-2 rounded hover:opacity-90 flex items-center disabled:opacity-50"
+                        prop:disabled=move || notes_too_long() || locked.get()
                         on:click=move |_| {
                             let new_item = TableItemArgs {
                                 id: match mode.get() {
@@ -264,11 +457,34 @@ pub fn PasswordModal(
                                 },
                                 service: service.get(),
                                 username: username.get(),
-                                password: password.get(),
                                 url: url.get(),
                                 notes: notes.get(),
+                                require_master: require_master.get(),
+                                has_totp: match mode.get() {
+                                    ModalMode::Edit(ref item) => item.has_totp,
+                                    ModalMode::Add => totp_only.get(),
+                                },
+                                has_password: match mode.get() {
+                                    ModalMode::Edit(ref item) => item.has_password,
+                                    ModalMode::Add => !totp_only.get(),
+                                },
+                                folder_id: match mode.get() {
+                                    ModalMode::Edit(ref item) => item.folder_id,
+                                    ModalMode::Add => None,
+                                },
+                                locked: false,
+                                expires_on: Some(expires_on.get()).filter(|date| !date.is_empty()),
                             };
-                            on_save.call(new_item);
+                            if totp_only.get() {
+                                on_save.call((new_item, None, Some(totp_secret.get())));
+                                return;
+                            }
+                            let new_password = if is_original_password.get() {
+                                None
+                            } else {
+                                Some(password.get())
+                            };
+                            on_save.call((new_item, new_password, None));
                         }
                     >
                         {move || {
@@ -295,21 +511,24 @@ pub fn PasswordModal(
                     view! {
                         <PasswordDialog
                         action=DialogAction::Verify
+                        on_master_password=move |master_pass: String| {
+                            let ModalMode::Edit(item) = mode.get() else {
+                                return;
+                            };
+                            spawn_local(async move {
+                                let args = serde_wasm_bindgen::to_value(&DecryptPasswordArgs {
+                                    id: item.id,
+                                    master_pass: &master_pass,
+                                })
+                                .unwrap();
+                                if let Ok(decrypted) =
+                                    serde_wasm_bindgen::from_value(invoke("decrypt_password", args).await)
+                                {
+                                    set_decrypted_password.set(decrypted);
+                                }
+                            });
+                        }
                         on_verify=move |verified| {
-                            if verified {
-                                let password = password.get().clone();
-                                spawn_local(async move {
-                                    let args = serde_wasm_bindgen::to_value(&DecryptPasswordArgs {
-                                        encrypted_password: &password,
-                                    })
-                                    .unwrap();
-                                    if let Ok(decrypted) =
-                                        serde_wasm_bindgen::from_value(invoke("decrypt_password", args).await)
-                                    {
-                                        set_decrypted_password.set(decrypted);
-                                    }
-                                });
-                            }
                             set_password_verified.set(verified);
                             set_show_password.set(true);
                         }
@@ -1,4 +1,10 @@
-use crate::{app::invoke, components::icons::Icon};
+use crate::{
+    app::invoke,
+    components::{
+        icons::Icon,
+        settings::{ExportConfirmation, ExportScope, PrepareExportArgs},
+    },
+};
 use leptos::*;
 use serde::Serialize;
 
@@ -6,8 +12,12 @@ use serde::Serialize;
 pub enum DialogAction {
     Verify,
     CreateBackup,
-    ExportPasswords,
+    /// Export the passwords, carries which entries are exported.
+    ExportPasswords(&'static str),
     RestoreBackup,
+    PreviewBackup,
+    /// Open a backup as snapshot, carries the file name of the backup.
+    OpenSnapshot(String),
 }
 
 #[derive(Serialize)]
@@ -16,19 +26,52 @@ struct PasswordDialogArgs<'a> {
     master_pass: &'a str,
 }
 
+#[derive(Serialize)]
+struct ExportPasswordsArgs<'a> {
+    filter: &'a str,
+    scope: &'a ExportScope,
+    #[serde(rename = "confirmationPin")]
+    confirmation_pin: Option<&'a str>,
+}
+
+#[derive(Serialize)]
+struct RestoreBackupArgs<'a> {
+    #[serde(rename = "masterPass")]
+    master_pass: &'a str,
+    #[serde(rename = "confirmationPin")]
+    confirmation_pin: Option<&'a str>,
+}
+
 #[component]
 pub fn PasswordDialog(
     #[prop(into)] on_close: Callback<()>,
     #[prop(into)] on_verify: Callback<bool>,
     #[prop(into)] action: DialogAction,
+    #[prop(optional, into)] on_master_password: Option<Callback<String>>,
 ) -> impl IntoView {
     let (master_password, set_master_password) = create_signal(String::new());
     let (error, set_error) = create_signal(String::new());
+    let (confirmation_pin, set_confirmation_pin) = create_signal(String::new());
+    let (pin_required, set_pin_required) = create_signal(false);
+    let (export_scope, set_export_scope) = create_signal(None::<(&'static str, ExportScope)>);
 
     let exit_icon = create_memo(move |_| "x-mark");
     let key_icon = create_memo(move |_| "key");
     let master_key_icon = create_memo(move |_| "shield-check");
     let check_icon = create_memo(move |_| "check");
+    let pin_icon = create_memo(move |_| "lock-closed");
+
+    if matches!(
+        action,
+        DialogAction::ExportPasswords(_) | DialogAction::RestoreBackup
+    ) {
+        spawn_local(async move {
+            let response = invoke("has_confirmation_pin", wasm_bindgen::JsValue::NULL).await;
+            if let Ok(has_pin) = serde_wasm_bindgen::from_value::<bool>(response) {
+                set_pin_required.set(has_pin);
+            }
+        });
+    }
 
     let handle_password = move |ev: ev::SubmitEvent| {
         ev.prevent_default();
@@ -40,6 +83,12 @@ pub fn PasswordDialog(
             return;
         }
 
+        let pin = confirmation_pin.get();
+        if pin_required.get() && pin.is_empty() {
+            set_error.set("Bitte gib deine Bestätigungs-PIN ein".into());
+            return;
+        }
+
         spawn_local(async move {
             let args = serde_wasm_bindgen::to_value(&PasswordDialogArgs {
                 master_pass: &master_pass,
@@ -52,6 +101,9 @@ pub fn PasswordDialog(
                     match serde_wasm_bindgen::from_value::<bool>(response) {
                         Ok(true) => {
                             set_error.set("".into());
+                            if let Some(on_master_password) = on_master_password {
+                                on_master_password.call(master_pass.clone());
+                            }
                             on_verify.call(true);
                             on_close.call(());
                         }
@@ -72,20 +124,32 @@ pub fn PasswordDialog(
                         set_error.set(format!("Backup fehlgeschlagen: {}", error_msg));
                     }
                 }
-                DialogAction::ExportPasswords => {
-                    let response = invoke("export_passwords", args).await;
-
-                    if response.is_null() || response.as_bool().unwrap_or(false) {
-                        set_error.set("Export erfolgreich".into());
-                        on_close.call(());
-                    } else {
-                        let error_msg = response
-                            .as_string()
-                            .unwrap_or("Unbekannter Fehler".to_string());
-                        set_error.set(format!("Export fehlgeschlagen: {}", error_msg));
+                DialogAction::ExportPasswords(filter) => {
+                    let args = serde_wasm_bindgen::to_value(&PrepareExportArgs {
+                        format: "csv",
+                        filter: Some(filter),
+                    })
+                    .unwrap();
+                    let response = invoke("prepare_export", args).await;
+                    match serde_wasm_bindgen::from_value::<ExportScope>(response.clone()) {
+                        Ok(scope) => {
+                            set_error.set("".into());
+                            set_export_scope.set(Some((filter, scope)));
+                        }
+                        Err(_) => {
+                            let error_msg = response
+                                .as_string()
+                                .unwrap_or("Unbekannter Fehler".to_string());
+                            set_error.set(format!("Export fehlgeschlagen: {}", error_msg));
+                        }
                     }
                 }
                 DialogAction::RestoreBackup => {
+                    let args = serde_wasm_bindgen::to_value(&RestoreBackupArgs {
+                        master_pass: &master_pass,
+                        confirmation_pin: pin_required.get().then_some(pin.as_str()),
+                    })
+                    .unwrap();
                     let response = invoke("restore_backup", args).await;
 
                     if response.is_null() || response.as_bool().unwrap_or(false) {
@@ -98,10 +162,44 @@ pub fn PasswordDialog(
                         set_error.set(format!("Wiederherstellung fehlgeschlagen: {}", error_msg));
                     }
                 }
+                DialogAction::PreviewBackup | DialogAction::OpenSnapshot(_) => {
+                    if let Some(on_master_password) = on_master_password {
+                        on_master_password.call(master_pass.clone());
+                    }
+                    on_close.call(());
+                }
             };
         });
     };
 
+    let handle_export = move |_| {
+        let Some((filter, scope)) = export_scope.get_untracked() else {
+            return;
+        };
+        set_export_scope.set(None);
+        let pin = confirmation_pin.get_untracked();
+
+        spawn_local(async move {
+            let args = serde_wasm_bindgen::to_value(&ExportPasswordsArgs {
+                filter,
+                scope: &scope,
+                confirmation_pin: pin_required.get_untracked().then_some(pin.as_str()),
+            })
+            .unwrap();
+            let response = invoke("export_passwords", args).await;
+
+            if response.is_null() || response.as_bool().unwrap_or(false) {
+                set_error.set("Export erfolgreich".into());
+                on_close.call(());
+            } else {
+                let error_msg = response
+                    .as_string()
+                    .unwrap_or("Unbekannter Fehler".to_string());
+                set_error.set(format!("Export fehlgeschlagen: {}", error_msg));
+            }
+        });
+    };
+
     view! {
         <div class="fixed inset-0 bg-black bg-opacity-50 flex items-center justify-center">
             <div class="bg-background-card rounded-lg p-6 w-full max-w-md">
@@ -138,6 +236,22 @@ pub fn PasswordDialog(
                         />
                     </div>
 
+                    <Show when=move || pin_required.get()>
+                        <div>
+                            <label class="block text-white text-sm font-bold mb-2 flex items-center">
+                                <Icon icon=pin_icon.into() class="w-4 h-4 mr-2 text-primary-100" />
+                                "Bestätigungs-PIN"
+                            </label>
+                            <input
+                                type="password"
+                                class="w-full p-2 rounded bg-background text-white border border-gray-600 focus:outline-none focus:border-primary-100"
+                                placeholder="Gib deine Bestätigungs-PIN ein"
+                                on:input=move |ev| set_confirmation_pin.set(event_target_value(&ev))
+                                prop:value=confirmation_pin
+                            />
+                        </div>
+                    </Show>
+
                     {move || {
                         if !error.get().is_empty() {
                             view! {
@@ -159,6 +273,14 @@ pub fn PasswordDialog(
                     </button>
                 </form>
             </div>
+
+            {move || export_scope.get().map(|(_, scope)| view! {
+                <ExportConfirmation
+                    scope=scope
+                    on_confirm=handle_export
+                    on_close=move |_| set_export_scope.set(None)
+                />
+            })}
         </div>
     }
 }
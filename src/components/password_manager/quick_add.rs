@@ -0,0 +1,119 @@
+use crate::{app::invoke, components::icons::Icon};
+use leptos::*;
+use serde::Serialize;
+
+#[derive(Serialize)]
+struct QuickAddArgs {
+    service: String,
+    username: Option<String>,
+    password: Option<String>,
+}
+
+#[component]
+pub fn QuickAddDialog(
+    #[prop(optional)] password: Option<String>,
+    #[prop(into)] on_close: Callback<()>,
+    #[prop(into)] on_saved: Callback<()>,
+) -> impl IntoView {
+    let (service, set_service) = create_signal(String::new());
+    let (username, set_username) = create_signal(String::new());
+    let (error, set_error) = create_signal(String::new());
+
+    let exit_icon = create_memo(move |_| "x-mark");
+    let plus_icon = create_memo(move |_| "plus");
+    let check_icon = create_memo(move |_| "check");
+
+    let source = if password.is_some() {
+        "Das generierte Passwort wird gespeichert"
+    } else {
+        "Das Passwort wird aus der Zwischenablage übernommen"
+    };
+
+    let handle_submit = move |ev: ev::SubmitEvent| {
+        ev.prevent_default();
+        if service.get().trim().is_empty() {
+            set_error.set("Bitte gib einen Service an".into());
+            return;
+        }
+
+        let password = password.clone();
+        spawn_local(async move {
+            let username = username.get();
+            let args = serde_wasm_bindgen::to_value(&QuickAddArgs {
+                service: service.get(),
+                username: (!username.is_empty()).then_some(username),
+                password,
+            })
+            .unwrap();
+            let response = invoke("quick_add_password", args).await;
+
+            if serde_wasm_bindgen::from_value::<()>(response.clone()).is_ok() {
+                on_saved.call(());
+                on_close.call(());
+            } else {
+                set_error.set(format!(
+                    "Speichern fehlgeschlagen: {}",
+                    response
+                        .as_string()
+                        .unwrap_or("Unbekannter Fehler".to_string())
+                ));
+            }
+        });
+    };
+
+    view! {
+        <div class="fixed inset-0 bg-black bg-opacity-50 flex items-center justify-center">
+            <div class="bg-background-card rounded-lg p-6 w-full max-w-md">
+                <div class="flex justify-between items-center mb-6">
+                    <h2 class="text-xl font-bold bg-gradient-primary bg-clip-text text-transparent flex items-center">
+                        <div class="flex items-center">
+                            <Icon icon=plus_icon.into() class="w-8 h-8 mr-2 text-primary-100" />
+                            "Schnell speichern"
+                        </div>
+                    </h2>
+                    <button
+                        class="text-gray-400 hover:text-white"
+                        on:click=move |_| on_close.call(())
+                    >
+                        <Icon icon=exit_icon.into() class="w-5 h-5" />
+                    </button>
+                </div>
+
+                <form on:submit=handle_submit class="space-y-4">
+                    <p class="text-sm text-gray-400">{source}</p>
+                    <div>
+                        <label class="block text-white text-sm font-bold mb-2">"Service"</label>
+                        <input
+                            type="text"
+                            class="w-full p-2 rounded bg-background text-white border border-gray-600 focus:outline-none focus:border-primary-100"
+                            placeholder="z.B. example.com"
+                            on:input=move |ev| set_service.set(event_target_value(&ev))
+                            prop:value=service
+                        />
+                    </div>
+                    <div>
+                        <label class="block text-white text-sm font-bold mb-2">"Benutzername (optional)"</label>
+                        <input
+                            type="text"
+                            class="w-full p-2 rounded bg-background text-white border border-gray-600 focus:outline-none focus:border-primary-100"
+                            on:input=move |ev| set_username.set(event_target_value(&ev))
+                            prop:value=username
+                        />
+                    </div>
+
+                    {move || (!error.get().is_empty()).then(|| view! {
+                        <div class="text-primary-100 text-sm">{error.get()}</div>
+                    })}
+
+                    <button
+                        type="submit"
+                        class="w-full bg-gradient-primary text-white px-4 py-2 rounded hover:opacity-90 flex items-center justify-center"
+                    >
+                        <Icon icon=check_icon.into() class="w-5 h-5 mr-2" />
+                        "Speichern"
+                    </button>
+                </form>
+            </div>
+        </div>
+    }
+}
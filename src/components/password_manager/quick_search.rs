@@ -0,0 +1,168 @@
+use crate::app::invoke;
+use leptos::*;
+use serde::{Deserialize, Serialize};
+
+use crate::components::icons::Icon;
+
+#[derive(Serialize)]
+struct QuickSearchArgs {
+    query: String,
+}
+
+#[derive(Serialize)]
+struct QuickSearchEntryArgs {
+    id: i32,
+}
+
+#[derive(Clone, Deserialize)]
+struct QuickSearchEntry {
+    id: Option<i32>,
+    service: String,
+    username: String,
+}
+
+#[component]
+pub fn QuickSearch() -> impl IntoView {
+    let (query, set_query) = create_signal(String::new());
+    let (entries, set_entries) = create_signal(Vec::<QuickSearchEntry>::new());
+    let (selected, set_selected) = create_signal(0usize);
+    let (error, set_error) = create_signal(String::new());
+
+    let search_icon = create_memo(move |_| "magnifying-glass");
+    let key_icon = create_memo(move |_| "key");
+
+    let search = move |text: String| {
+        spawn_local(async move {
+            let args = serde_wasm_bindgen::to_value(&QuickSearchArgs {
+                query: text.clone(),
+            })
+            .unwrap();
+            let response = invoke("quick_search", args).await;
+            if query.get_untracked() != text {
+                return;
+            }
+            match serde_wasm_bindgen::from_value::<Vec<QuickSearchEntry>>(response.clone()) {
+                Ok(found) => {
+                    set_error.set(String::new());
+                    set_entries.set(found);
+                    set_selected.set(0);
+                }
+                Err(_) => {
+                    set_entries.set(Vec::new());
+                    set_error.set(match response.as_string().as_deref() {
+                        Some("Not logged in") => {
+                            "Der Tresor ist gesperrt. Entsperre ihn im Hauptfenster.".to_string()
+                        }
+                        other => other.unwrap_or("Unbekannter Fehler").to_string(),
+                    });
+                }
+            }
+        });
+    };
+    search(String::new());
+
+    let copy = move |id: Option<i32>| {
+        let Some(id) = id else {
+            return;
+        };
+        spawn_local(async move {
+            let args = serde_wasm_bindgen::to_value(&QuickSearchEntryArgs { id }).unwrap();
+            let response = invoke("copy_quick_search_entry", args).await;
+            if serde_wasm_bindgen::from_value::<()>(response.clone()).is_err() {
+                set_error.set(
+                    response
+                        .as_string()
+                        .unwrap_or("Unbekannter Fehler".to_string()),
+                );
+            }
+        });
+    };
+
+    let close = move || {
+        spawn_local(async move {
+            invoke("close_quick_search", wasm_bindgen::JsValue::NULL).await;
+        });
+    };
+
+    let handle_keydown = move |ev: ev::KeyboardEvent| {
+        let count = entries.with(|entries| entries.len());
+        match ev.key().as_str() {
+            "ArrowDown" if count > 0 => {
+                ev.prevent_default();
+                set_selected.update(|i| *i = (*i + 1) % count);
+            }
+            "ArrowUp" if count > 0 => {
+                ev.prevent_default();
+                set_selected.update(|i| *i = (*i + count - 1) % count);
+            }
+            "Enter" => {
+                let id = entries.with(|entries| entries.get(selected.get()).and_then(|e| e.id));
+                copy(id);
+            }
+            "Escape" => close(),
+            _ => {}
+        }
+    };
+
+    view! {
+        <div class="space-y-3">
+            <div class="relative">
+                <Icon icon=search_icon.into() class="w-5 h-5 text-gray-400 absolute left-3 top-3" />
+                <input
+                    type="text"
+                    class="w-full pl-10 p-2.5 rounded bg-background text-white border border-gray-600 focus:outline-none focus:border-primary-100"
+                    placeholder="Eintrag suchen, Enter kopiert das Passwort"
+                    autofocus
+                    prop:value=query
+                    on:input=move |ev| {
+                        let text = event_target_value(&ev);
+                        set_query.set(text.clone());
+                        search(text);
+                    }
+                    on:keydown=handle_keydown
+                />
+            </div>
+
+            {move || (!error.get().is_empty()).then(|| view! {
+                <div class="text-primary-100 text-sm text-center">{error.get()}</div>
+            })}
+
+            <ul class="space-y-1">
+                {move || {
+                    entries
+                        .get()
+                        .into_iter()
+                        .enumerate()
+                        .map(|(index, entry)| {
+                            let id = entry.id;
+                            view! {
+                                <li
+                                    class="flex items-center px-3 py-2 rounded cursor-pointer text-white"
+                                    class:bg-primary-400=move || selected.get() == index
+                                    on:mouseenter=move |_| set_selected.set(index)
+                                    on:click=move |_| copy(id)
+                                >
+                                    <Icon icon=key_icon.into() class="w-4 h-4 mr-3 text-primary-100" />
+                                    <div class="min-w-0">
+                                        <div class="truncate">{entry.service}</div>
+                                        <div class="text-xs text-gray-400 truncate">{entry.username}</div>
+                                    </div>
+                                </li>
+                            }
+                        })
+                        .collect_view()
+                }}
+            </ul>
+
+            {move || (error.get().is_empty() && entries.with(|entries| entries.is_empty())).then(|| view! {
+                <p class="text-sm text-gray-400 text-center">
+                    {if query.get().trim().is_empty() {
+                        "Tippe, um deine Einträge zu durchsuchen"
+                    } else {
+                        "Keine passenden Einträge"
+                    }}
+                </p>
+            })}
+        </div>
+    }
+}
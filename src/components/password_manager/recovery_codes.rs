@@ -0,0 +1,229 @@
+use crate::{app::invoke, components::icons::Icon};
+use leptos::*;
+use serde::{Deserialize, Serialize};
+
+#[derive(Clone, Deserialize, PartialEq)]
+struct RecoveryCode {
+    code: String,
+    used_at: Option<String>,
+}
+
+#[derive(Clone, Default, Deserialize, PartialEq)]
+struct RecoveryCodes {
+    codes: Vec<RecoveryCode>,
+    remaining: usize,
+    low: bool,
+}
+
+#[derive(Serialize)]
+struct GetRecoveryCodesArgs {
+    id: i32,
+    #[serde(rename = "masterPass")]
+    master_pass: Option<String>,
+}
+
+#[derive(Serialize)]
+struct SetRecoveryCodesArgs {
+    id: i32,
+    codes: String,
+}
+
+#[derive(Deserialize)]
+struct RecoveryCodeCount {
+    remaining: usize,
+    low: bool,
+}
+
+#[derive(Serialize)]
+struct MarkRecoveryCodeArgs {
+    id: i32,
+    index: usize,
+    used: bool,
+    #[serde(rename = "masterPass")]
+    master_pass: Option<String>,
+}
+
+#[component]
+pub fn RecoveryCodesDialog(
+    id: i32,
+    service: String,
+    master_pass: Option<String>,
+    #[prop(into)] on_close: Callback<()>,
+) -> impl IntoView {
+    let (codes, set_codes) = create_signal(RecoveryCodes::default());
+    let (is_editing, set_is_editing) = create_signal(false);
+    let (input, set_input) = create_signal(String::new());
+    let (error, set_error) = create_signal(String::new());
+
+    let exit_icon = create_memo(move |_| "x-mark");
+    let shield_icon = create_memo(move |_| "shield-check");
+    let warning_icon = create_memo(move |_| "exclamation-triangle");
+    let check_icon = create_memo(move |_| "check");
+
+    let apply_response = move |response: wasm_bindgen::JsValue| {
+        if let Ok(loaded) = serde_wasm_bindgen::from_value::<RecoveryCodes>(response.clone()) {
+            set_is_editing.set(loaded.codes.is_empty());
+            set_codes.set(loaded);
+            set_error.set(String::new());
+        } else {
+            set_error.set(
+                response
+                    .as_string()
+                    .unwrap_or("Unbekannter Fehler".to_string()),
+            );
+        }
+    };
+
+    let master_pass = store_value(master_pass);
+
+    spawn_local(async move {
+        let args = serde_wasm_bindgen::to_value(&GetRecoveryCodesArgs {
+            id,
+            master_pass: master_pass.get_value(),
+        })
+        .unwrap();
+        apply_response(invoke("get_recovery_codes", args).await);
+    });
+
+    let handle_save = move |_| {
+        spawn_local(async move {
+            let args = serde_wasm_bindgen::to_value(&SetRecoveryCodesArgs {
+                id,
+                codes: input.get(),
+            })
+            .unwrap();
+            apply_response(invoke("set_recovery_codes", args).await);
+            set_input.set(String::new());
+        });
+    };
+
+    let toggle_used = move |index: usize, used: bool| {
+        spawn_local(async move {
+            let args = serde_wasm_bindgen::to_value(&MarkRecoveryCodeArgs {
+                id,
+                index,
+                used,
+                master_pass: master_pass.get_value(),
+            })
+            .unwrap();
+            let response = invoke("mark_recovery_code", args).await;
+            match serde_wasm_bindgen::from_value::<RecoveryCodeCount>(response.clone()) {
+                Ok(count) => {
+                    set_codes.update(|codes| {
+                        if let Some(code) = codes.codes.get_mut(index) {
+                            if used != code.used_at.is_some() {
+                                code.used_at = used.then(String::new);
+                            }
+                        }
+                        codes.remaining = count.remaining;
+                        codes.low = count.low;
+                    });
+                    set_error.set(String::new());
+                }
+                Err(_) => set_error.set(
+                    response
+                        .as_string()
+                        .unwrap_or("Unbekannter Fehler".to_string()),
+                ),
+            }
+        });
+    };
+
+    view! {
+        <div class="fixed inset-0 bg-black bg-opacity-50 flex items-center justify-center z-50">
+            <div class="bg-background-card rounded-lg p-6 w-full max-w-md">
+                <div class="flex justify-between items-center mb-6">
+                    <h2 class="text-xl font-bold bg-gradient-primary bg-clip-text text-transparent flex items-center">
+                        <Icon icon=shield_icon.into() class="w-8 h-8 mr-2 text-primary-100" />
+                        {format!("Wiederherstellungscodes: {}", service)}
+                    </h2>
+                    <button
+                        class="text-gray-400 hover:text-white"
+                        on:click=move |_| on_close.call(())
+                    >
+                        <Icon icon=exit_icon.into() class="w-5 h-5" />
+                    </button>
+                </div>
+
+                {move || codes.get().low.then(|| view! {
+                    <div class="mb-4 p-3 border border-primary-100 rounded text-primary-100 text-sm flex items-center">
+                        <Icon icon=warning_icon.into() class="w-5 h-5 mr-2 shrink-0" />
+                        {format!(
+                            "Nur noch {} unbenutzte Codes. Erstelle neue Codes beim Anbieter.",
+                            codes.get().remaining
+                        )}
+                    </div>
+                })}
+
+                {move || (!error.get().is_empty()).then(|| view! {
+                    <div class="mb-4 text-primary-100 text-sm">{error.get()}</div>
+                })}
+
+                {move || if is_editing.get() {
+                    view! {
+                        <div class="space-y-4">
+                            <p class="text-sm text-gray-400">
+                                "Füge die Codes ein, einen pro Zeile. Bestehende Codes werden ersetzt."
+                            </p>
+                            <textarea
+                                class="w-full h-40 p-2 rounded bg-background text-white font-mono border border-gray-600 focus:outline-none focus:border-primary-100"
+                                on:input=move |ev| set_input.set(event_target_value(&ev))
+                                prop:value=input
+                            />
+                            <button
+                                class="w-full bg-gradient-primary text-white px-4 py-2 rounded hover:opacity-90 flex items-center justify-center"
+                                on:click=handle_save
+                            >
+                                <Icon icon=check_icon.into() class="w-5 h-5 mr-2" />
+                                "Codes speichern"
+                            </button>
+                        </div>
+                    }.into_view()
+                } else {
+                    view! {
+                        <div class="space-y-4">
+                            <p class="text-sm text-gray-400">
+                                {move || format!(
+                                    "{} von {} Codes unbenutzt",
+                                    codes.get().remaining,
+                                    codes.get().codes.len()
+                                )}
+                            </p>
+                            <ul class="max-h-64 overflow-y-auto space-y-1">
+                                {move || codes.get().codes.into_iter().enumerate().map(|(index, code)| {
+                                    let used = code.used_at.is_some();
+                                    view! {
+                                        <li class="flex items-center justify-between p-2 rounded bg-background">
+                                            <span class=if used {
+                                                "font-mono text-gray-500 line-through"
+                                            } else {
+                                                "font-mono text-white"
+                                            }>
+                                                {code.code}
+                                            </span>
+                                            <label class="flex items-center text-sm text-gray-400">
+                                                <input
+                                                    type="checkbox"
+                                                    class="mr-2"
+                                                    prop:checked=used
+                                                    on:change=move |ev| toggle_used(index, event_target_checked(&ev))
+                                                />
+                                                "Verwendet"
+                                            </label>
+                                        </li>
+                                    }
+                                }).collect_view()}
+                            </ul>
+                            <button
+                                class="w-full border border-gray-600 text-gray-300 px-4 py-2 rounded hover:bg-background transition-colors"
+                                on:click=move |_| set_is_editing.set(true)
+                            >
+                                "Codes ersetzen"
+                            </button>
+                        </div>
+                    }.into_view()
+                }}
+            </div>
+        </div>
+    }
+}
@@ -0,0 +1,223 @@
+use crate::{app::invoke, components::icons::Icon};
+use leptos::*;
+use serde::{Deserialize, Serialize};
+
+#[derive(Clone, Deserialize, PartialEq)]
+struct SecureNote {
+    id: i32,
+    title: String,
+    content: String,
+    updated_at: String,
+}
+
+#[derive(Serialize)]
+struct AddSecureNoteArgs {
+    title: String,
+    content: String,
+}
+
+#[derive(Serialize)]
+struct UpdateSecureNoteArgs {
+    id: i32,
+    title: String,
+    content: String,
+}
+
+#[derive(Serialize)]
+struct DeleteSecureNoteArgs {
+    id: i32,
+}
+
+/// Format an RFC 3339 timestamp as a German date.
+fn format_date(timestamp: &str) -> String {
+    let day = timestamp.split('T').next().unwrap_or(timestamp);
+    let mut parts = day.splitn(3, '-');
+    match (parts.next(), parts.next(), parts.next()) {
+        (Some(year), Some(month), Some(day)) => format!("{}.{}.{}", day, month, year),
+        _ => day.to_string(),
+    }
+}
+
+#[component]
+pub fn SecureNotes() -> impl IntoView {
+    let (notes, set_notes) = create_signal(Vec::<SecureNote>::new());
+    let (selected, set_selected) = create_signal(None::<i32>);
+    let (title, set_title) = create_signal(String::new());
+    let (content, set_content) = create_signal(String::new());
+    let (error, set_error) = create_signal(String::new());
+    let (is_saving, set_is_saving) = create_signal(false);
+
+    let notes_icon = create_memo(move |_| "document-text");
+    let plus_icon = create_memo(move |_| "plus");
+    let trash_icon = create_memo(move |_| "trash");
+
+    let show_error = move |response: wasm_bindgen::JsValue| {
+        set_error.set(
+            response
+                .as_string()
+                .unwrap_or("Unbekannter Fehler".to_string()),
+        );
+    };
+
+    let load_notes = move || {
+        spawn_local(async move {
+            let response = invoke("get_secure_notes", wasm_bindgen::JsValue::NULL).await;
+            match serde_wasm_bindgen::from_value::<Vec<SecureNote>>(response.clone()) {
+                Ok(loaded) => set_notes.set(loaded),
+                Err(_) => show_error(response),
+            }
+        });
+    };
+    load_notes();
+
+    let select_note = move |note: Option<SecureNote>| {
+        set_error.set(String::new());
+        match note {
+            Some(note) => {
+                set_selected.set(Some(note.id));
+                set_title.set(note.title);
+                set_content.set(note.content);
+            }
+            None => {
+                set_selected.set(None);
+                set_title.set(String::new());
+                set_content.set(String::new());
+            }
+        }
+    };
+
+    let handle_save = move |_| {
+        set_is_saving.set(true);
+        spawn_local(async move {
+            let title = title.get_untracked();
+            let content = content.get_untracked();
+            let response = match selected.get_untracked() {
+                Some(id) => {
+                    let args =
+                        serde_wasm_bindgen::to_value(&UpdateSecureNoteArgs { id, title, content })
+                            .unwrap();
+                    let response = invoke("update_secure_note", args).await;
+                    (response.is_null() || response.is_undefined())
+                        .then_some(id)
+                        .ok_or(response)
+                }
+                None => {
+                    let args = serde_wasm_bindgen::to_value(&AddSecureNoteArgs { title, content })
+                        .unwrap();
+                    let response = invoke("add_secure_note", args).await;
+                    serde_wasm_bindgen::from_value::<i32>(response.clone()).map_err(|_| response)
+                }
+            };
+            match response {
+                Ok(id) => {
+                    set_selected.set(Some(id));
+                    set_error.set(String::new());
+                    load_notes();
+                }
+                Err(response) => show_error(response),
+            }
+            set_is_saving.set(false);
+        });
+    };
+
+    let handle_delete = move |_| {
+        let Some(id) = selected.get_untracked() else {
+            return;
+        };
+        spawn_local(async move {
+            let args = serde_wasm_bindgen::to_value(&DeleteSecureNoteArgs { id }).unwrap();
+            let response = invoke("delete_secure_note", args).await;
+            if response.is_null() || response.is_undefined() {
+                set_notes.update(|notes| notes.retain(|note| note.id != id));
+                select_note(None);
+            } else {
+                show_error(response);
+            }
+        });
+    };
+
+    view! {
+        <div class="flex gap-6 h-full">
+            <div class="w-72 flex flex-col">
+                <button
+                    class="bg-gradient-primary text-white font-bold px-4 py-2 rounded hover:opacity-90 flex items-center justify-center mb-4"
+                    on:click=move |_| select_note(None)
+                >
+                    <Icon icon=plus_icon.into() class="w-5 h-5 mr-2" />
+                    "Neue Notiz"
+                </button>
+                <ul class="flex-1 overflow-y-auto space-y-2">
+                    {move || notes.get().into_iter().map(|note| {
+                        let id = note.id;
+                        let clicked = note.clone();
+                        view! {
+                            <li
+                                class=move || format!("p-3 rounded cursor-pointer border {}",
+                                    if selected.get() == Some(id) {
+                                        "border-primary-100 bg-background"
+                                    } else {
+                                        "border-gray-700 hover:border-gray-600"
+                                    }
+                                )
+                                on:click=move |_| select_note(Some(clicked.clone()))
+                            >
+                                <p class="text-white font-semibold truncate">{note.title}</p>
+                                <p class="text-xs text-gray-400">
+                                    {format!("Geändert am {}", format_date(&note.updated_at))}
+                                </p>
+                            </li>
+                        }
+                    }).collect_view()}
+                </ul>
+                {move || notes.with(|notes| notes.is_empty()).then(|| view! {
+                    <p class="text-gray-400 text-sm">
+                        "Noch keine Notizen, etwa für Lizenzschlüssel oder Wiederherstellungscodes."
+                    </p>
+                })}
+            </div>
+
+            <div class="flex-1 flex flex-col">
+                <h2 class="text-xl font-bold mb-4 bg-gradient-primary bg-clip-text text-transparent flex items-center">
+                    <Icon icon=notes_icon.into() class="w-8 h-8 mr-2 text-primary-100" />
+                    {move || if selected.get().is_some() { "Notiz bearbeiten" } else { "Neue Notiz" }}
+                </h2>
+                <input
+                    type="text"
+                    class="w-full p-2 mb-4 rounded bg-background text-white border border-gray-600 focus:outline-none focus:border-primary-100"
+                    placeholder="Titel"
+                    on:input=move |ev| set_title.set(event_target_value(&ev))
+                    prop:value=title
+                />
+                <textarea
+                    class="w-full flex-1 p-2 rounded bg-background text-white font-mono border border-gray-600 focus:outline-none focus:border-primary-100"
+                    placeholder="Inhalt"
+                    on:input=move |ev| set_content.set(event_target_value(&ev))
+                    prop:value=content
+                />
+                {move || (!error.get().is_empty()).then(|| view! {
+                    <div class="text-primary-100 text-sm text-center mt-4">{error.get()}</div>
+                })}
+                <div class="flex justify-end gap-2 mt-4">
+                    {move || selected.get().is_some().then(|| view! {
+                        <button
+                            class="border border-gray-600 text-gray-300 px-4 py-2 rounded hover:bg-background transition-colors flex items-center"
+                            on:click=handle_delete
+                        >
+                            <Icon icon=trash_icon.into() class="w-5 h-5 mr-2" />
+                            "Löschen"
+                        </button>
+                    })}
+                    <button
+                        class="bg-gradient-primary text-white font-bold px-4 py-2 rounded hover:opacity-90 disabled:opacity-50 disabled:cursor-not-allowed"
+                        prop:disabled=move || {
+                            is_saving.get() || title.get().trim().is_empty() || content.get().trim().is_empty()
+                        }
+                        on:click=handle_save
+                    >
+                        "Speichern"
+                    </button>
+                </div>
+            </div>
+        </div>
+    }
+}
@@ -0,0 +1,438 @@
+use crate::{app::invoke, components::icons::Icon};
+use leptos::*;
+use serde::{Deserialize, Serialize};
+
+/// The protocols a server can be reached with and their default ports.
+const PROTOCOLS: &[(&str, &str, u16)] = &[
+    ("ssh", "SSH", 22),
+    ("sftp", "SFTP", 22),
+    ("rdp", "RDP", 3389),
+    ("vnc", "VNC", 5900),
+    ("https", "HTTPS", 443),
+];
+
+#[derive(Clone, Deserialize, Serialize, PartialEq)]
+struct Server {
+    id: i32,
+    title: String,
+    host: String,
+    port: u16,
+    protocol: String,
+    username: String,
+    key_note_id: Option<i32>,
+}
+
+impl Default for Server {
+    fn default() -> Self {
+        Self {
+            id: 0,
+            title: String::new(),
+            host: String::new(),
+            port: 0,
+            protocol: "ssh".to_string(),
+            username: String::new(),
+            key_note_id: None,
+        }
+    }
+}
+
+#[derive(Clone, Deserialize)]
+struct KeyNote {
+    id: i32,
+    title: String,
+}
+
+#[derive(Deserialize)]
+struct ClipboardProtection {}
+
+#[derive(Serialize)]
+struct ServerArgs {
+    server: Server,
+}
+
+#[derive(Serialize)]
+struct IdArgs {
+    id: i32,
+}
+
+/// The port of a server, the default port of its protocol if none is set.
+fn effective_port(server: &Server) -> u16 {
+    if server.port != 0 {
+        return server.port;
+    }
+    PROTOCOLS
+        .iter()
+        .find(|(protocol, _, _)| *protocol == server.protocol)
+        .map_or(0, |(_, _, port)| *port)
+}
+
+fn show_error(set_error: WriteSignal<String>, response: wasm_bindgen::JsValue) {
+    set_error.set(
+        response
+            .as_string()
+            .unwrap_or("Unbekannter Fehler".to_string()),
+    );
+}
+
+#[component]
+pub fn Servers() -> impl IntoView {
+    let (servers, set_servers) = create_signal(Vec::<Server>::new());
+    let (keys, set_keys) = create_signal(Vec::<KeyNote>::new());
+    let (modal, set_modal) = create_signal(None::<Server>);
+    let (copied, set_copied) = create_signal(None::<(i32, &'static str)>);
+    let (error, set_error) = create_signal(String::new());
+
+    let server_icon = create_memo(move |_| "server-stack");
+    let plus_icon = create_memo(move |_| "plus");
+    let pencil_icon = create_memo(move |_| "pencil-square");
+    let trash_icon = create_memo(move |_| "trash");
+
+    let load_servers = move || {
+        spawn_local(async move {
+            let response = invoke("get_servers", wasm_bindgen::JsValue::NULL).await;
+            match serde_wasm_bindgen::from_value::<Vec<Server>>(response.clone()) {
+                Ok(loaded) => set_servers.set(loaded),
+                Err(_) => show_error(set_error, response),
+            }
+            let response = invoke("get_secure_notes", wasm_bindgen::JsValue::NULL).await;
+            if let Ok(loaded) = serde_wasm_bindgen::from_value::<Vec<KeyNote>>(response) {
+                set_keys.set(loaded);
+            }
+        });
+    };
+    load_servers();
+
+    let handle_delete = move |id: i32| {
+        spawn_local(async move {
+            let args = serde_wasm_bindgen::to_value(&IdArgs { id }).unwrap();
+            let response = invoke("delete_server", args).await;
+            if response.is_null() || response.is_undefined() {
+                set_error.set(String::new());
+                load_servers();
+            } else {
+                show_error(set_error, response);
+            }
+        });
+    };
+
+    let handle_copy = move |command: &'static str, id: i32| {
+        spawn_local(async move {
+            let args = serde_wasm_bindgen::to_value(&IdArgs { id }).unwrap();
+            let response = invoke(command, args).await;
+            if serde_wasm_bindgen::from_value::<ClipboardProtection>(response.clone()).is_ok() {
+                set_error.set(String::new());
+                set_copied.set(Some((id, command)));
+            } else {
+                show_error(set_error, response);
+            }
+        });
+    };
+
+    let key_title = move |id: Option<i32>| {
+        id.and_then(|id| {
+            keys.with(|keys| {
+                keys.iter()
+                    .find(|key| key.id == id)
+                    .map(|key| key.title.clone())
+            })
+        })
+    };
+
+    view! {
+        <div class="h-full overflow-y-auto">
+            <div class="flex justify-between items-center mb-4">
+                <h2 class="text-xl font-bold bg-gradient-primary bg-clip-text text-transparent flex items-center">
+                    <Icon icon=server_icon.into() class="w-8 h-8 mr-2 text-primary-100" />
+                    "Server"
+                </h2>
+                <button
+                    class="bg-gradient-primary text-white font-bold px-4 py-2 rounded hover:opacity-90 flex items-center"
+                    on:click=move |_| set_modal.set(Some(Server::default()))
+                >
+                    <Icon icon=plus_icon.into() class="w-5 h-5 mr-2" />
+                    "Server hinzufügen"
+                </button>
+            </div>
+
+            {move || (!error.get().is_empty()).then(|| view! {
+                <div class="text-primary-100 text-sm text-center mb-4">{error.get()}</div>
+            })}
+
+            <table class="w-full">
+                <thead class="bg-background">
+                    <tr class="border-b border-gray-600">
+                        <th class="text-left p-4 text-gray-400 font-medium">"Bezeichnung"</th>
+                        <th class="text-left p-4 text-gray-400 font-medium">"Protokoll"</th>
+                        <th class="text-left p-4 text-gray-400 font-medium">"Host"</th>
+                        <th class="text-left p-4 text-gray-400 font-medium">"Benutzer"</th>
+                        <th class="text-left p-4 text-gray-400 font-medium">"SSH-Schlüssel"</th>
+                        <th class="text-right p-4 text-gray-400 font-medium w-40">"Aktionen"</th>
+                    </tr>
+                </thead>
+                <tbody class="divide-y divide-gray-600">
+                    {move || servers.get().into_iter().map(|server| {
+                        let id = server.id;
+                        let edited = server.clone();
+                        let has_key = server.key_note_id.is_some();
+                        let connection_icon = create_memo(move |_| {
+                            if copied.get() == Some((id, "copy_server_connection")) {
+                                "check"
+                            } else {
+                                "clipboard"
+                            }
+                        });
+                        let key_icon = create_memo(move |_| {
+                            if copied.get() == Some((id, "copy_server_key")) {
+                                "check"
+                            } else {
+                                "key"
+                            }
+                        });
+                        view! {
+                            <tr class="hover:bg-background">
+                                <td class="p-4 text-white">{server.title.clone()}</td>
+                                <td class="p-4 text-white uppercase">{server.protocol.clone()}</td>
+                                <td class="p-4 text-white font-mono whitespace-nowrap">
+                                    {format!("{}:{}", server.host, effective_port(&server))}
+                                </td>
+                                <td class="p-4 text-white">{server.username.clone()}</td>
+                                <td class="p-4 text-gray-400">
+                                    {move || key_title(server.key_note_id).unwrap_or_default()}
+                                </td>
+                                <td class="p-4">
+                                    <div class="flex justify-end space-x-2">
+                                        <button
+                                            class="text-gray-400 hover:text-primary-100"
+                                            title="Verbindung kopieren"
+                                            on:click=move |_| handle_copy("copy_server_connection", id)
+                                            on:mouseleave=move |_| set_copied.set(None)
+                                        >
+                                            <Icon icon=connection_icon.into() class="w-5 h-5" />
+                                        </button>
+                                        {has_key.then(|| view! {
+                                            <button
+                                                class="text-gray-400 hover:text-primary-100"
+                                                title="SSH-Schlüssel kopieren"
+                                                on:click=move |_| handle_copy("copy_server_key", id)
+                                                on:mouseleave=move |_| set_copied.set(None)
+                                            >
+                                                <Icon icon=key_icon.into() class="w-5 h-5" />
+                                            </button>
+                                        })}
+                                        <button
+                                            class="text-gray-400 hover:text-primary-100"
+                                            title="Bearbeiten"
+                                            on:click=move |_| set_modal.set(Some(edited.clone()))
+                                        >
+                                            <Icon icon=pencil_icon.into() class="w-5 h-5" />
+                                        </button>
+                                        <button
+                                            class="text-gray-400 hover:text-red-500"
+                                            title="Löschen"
+                                            on:click=move |_| handle_delete(id)
+                                        >
+                                            <Icon icon=trash_icon.into() class="w-5 h-5" />
+                                        </button>
+                                    </div>
+                                </td>
+                            </tr>
+                        }
+                    }).collect_view()}
+                </tbody>
+            </table>
+            {move || servers.with(|servers| servers.is_empty()).then(|| view! {
+                <p class="text-gray-400 text-sm p-4">"Noch keine Server gespeichert."</p>
+            })}
+
+            {move || modal.get().map(|server| view! {
+                <ServerModal
+                    server=server
+                    keys=keys.get()
+                    on_close=move |_| set_modal.set(None)
+                    on_saved=move |_| {
+                        set_modal.set(None);
+                        load_servers();
+                    }
+                />
+            })}
+        </div>
+    }
+}
+
+#[component]
+fn ServerModal(
+    server: Server,
+    keys: Vec<KeyNote>,
+    #[prop(into)] on_close: Callback<()>,
+    #[prop(into)] on_saved: Callback<()>,
+) -> impl IntoView {
+    let id = server.id;
+    let (title, set_title) = create_signal(server.title);
+    let (protocol, set_protocol) = create_signal(server.protocol);
+    let (host, set_host) = create_signal(server.host);
+    let (port, set_port) = create_signal(if server.port == 0 {
+        String::new()
+    } else {
+        server.port.to_string()
+    });
+    let (username, set_username) = create_signal(server.username);
+    let (key_note_id, set_key_note_id) = create_signal(server.key_note_id);
+    let (error, set_error) = create_signal(String::new());
+
+    let server_icon = create_memo(move |_| "server-stack");
+    let uses_key = move || matches!(protocol.get().as_str(), "ssh" | "sftp");
+
+    let handle_save = move |_| {
+        let port = port.get_untracked();
+        let port = if port.trim().is_empty() {
+            0
+        } else {
+            match port.trim().parse::<u16>() {
+                Ok(port) if port > 0 => port,
+                _ => {
+                    set_error.set("Ungültiger Port".to_string());
+                    return;
+                }
+            }
+        };
+        spawn_local(async move {
+            let server = Server {
+                id,
+                title: title.get_untracked(),
+                host: host.get_untracked(),
+                port,
+                protocol: protocol.get_untracked(),
+                username: username.get_untracked(),
+                key_note_id: if uses_key() {
+                    key_note_id.get_untracked()
+                } else {
+                    None
+                },
+            };
+            let command = if id == 0 {
+                "add_server"
+            } else {
+                "update_server"
+            };
+            let args = serde_wasm_bindgen::to_value(&ServerArgs { server }).unwrap();
+            let response = invoke(command, args).await;
+            if response.as_string().is_some() {
+                show_error(set_error, response);
+            } else {
+                on_saved.call(());
+            }
+        });
+    };
+
+    view! {
+        <div class="fixed inset-0 bg-black bg-opacity-50 flex items-center justify-center z-50">
+            <div class="bg-background-card rounded-lg p-6 w-full max-w-md">
+                <h2 class="text-xl font-bold mb-4 bg-gradient-primary bg-clip-text text-transparent flex items-center">
+                    <Icon icon=server_icon.into() class="w-8 h-8 mr-2 text-primary-100" />
+                    {if id == 0 { "Server hinzufügen" } else { "Server bearbeiten" }}
+                </h2>
+                <div class="space-y-4">
+                    <div>
+                        <label class="block text-white text-sm font-bold mb-2">"Bezeichnung"</label>
+                        <input
+                            type="text"
+                            class="w-full p-2 rounded bg-background text-white border border-gray-600 focus:outline-none focus:border-primary-100"
+                            placeholder="z.B. NAS im Keller"
+                            on:input=move |ev| set_title.set(event_target_value(&ev))
+                            prop:value=title
+                        />
+                    </div>
+                    <div class="flex gap-4">
+                        <div class="flex-1">
+                            <label class="block text-white text-sm font-bold mb-2">"Protokoll"</label>
+                            <select
+                                class="w-full p-2 rounded bg-background text-white border border-gray-600 focus:outline-none focus:border-primary-100"
+                                on:change=move |ev| set_protocol.set(event_target_value(&ev))
+                                prop:value=protocol
+                            >
+                                {PROTOCOLS.iter().map(|(value, label, _)| view! {
+                                    <option value=*value>{*label}</option>
+                                }).collect_view()}
+                            </select>
+                        </div>
+                        <div class="flex-1">
+                            <label class="block text-white text-sm font-bold mb-2">"Port"</label>
+                            <input
+                                type="number"
+                                min="1"
+                                max="65535"
+                                class="w-full p-2 rounded bg-background text-white border border-gray-600 focus:outline-none focus:border-primary-100"
+                                placeholder=move || effective_port(&Server {
+                                    protocol: protocol.get(),
+                                    ..Server::default()
+                                }).to_string()
+                                on:input=move |ev| set_port.set(event_target_value(&ev))
+                                prop:value=port
+                            />
+                        </div>
+                    </div>
+                    <div>
+                        <label class="block text-white text-sm font-bold mb-2">"Host"</label>
+                        <input
+                            type="text"
+                            autocomplete="off"
+                            class="w-full p-2 rounded bg-background text-white font-mono border border-gray-600 focus:outline-none focus:border-primary-100"
+                            placeholder="z.B. 192.168.1.10 oder nas.local"
+                            on:input=move |ev| set_host.set(event_target_value(&ev))
+                            prop:value=host
+                        />
+                    </div>
+                    <div>
+                        <label class="block text-white text-sm font-bold mb-2">"Benutzer"</label>
+                        <input
+                            type="text"
+                            autocomplete="off"
+                            class="w-full p-2 rounded bg-background text-white border border-gray-600 focus:outline-none focus:border-primary-100"
+                            on:input=move |ev| set_username.set(event_target_value(&ev))
+                            prop:value=username
+                        />
+                    </div>
+                    {move || uses_key().then(|| {
+                        let keys = keys.clone();
+                        view! {
+                            <div>
+                                <label class="block text-white text-sm font-bold mb-2">"SSH-Schlüssel"</label>
+                                <select
+                                    class="w-full p-2 rounded bg-background text-white border border-gray-600 focus:outline-none focus:border-primary-100"
+                                    on:change=move |ev| set_key_note_id.set(event_target_value(&ev).parse().ok())
+                                    prop:value=move || key_note_id.get().map(|id| id.to_string()).unwrap_or_default()
+                                >
+                                    <option value="">"Kein Schlüssel"</option>
+                                    {keys.into_iter().map(|key| view! {
+                                        <option value=key.id.to_string()>{key.title}</option>
+                                    }).collect_view()}
+                                </select>
+                                <p class="text-xs text-gray-400 mt-1">
+                                    "Private Schlüssel werden als sichere Notiz gespeichert und hier verknüpft."
+                                </p>
+                            </div>
+                        }
+                    })}
+                </div>
+                {move || (!error.get().is_empty()).then(|| view! {
+                    <div class="text-primary-100 text-sm text-center mt-4">{error.get()}</div>
+                })}
+                <div class="flex justify-end gap-2 mt-6">
+                    <button
+                        class="border border-gray-600 text-gray-300 px-4 py-2 rounded hover:bg-background transition-colors"
+                        on:click=move |_| on_close.call(())
+                    >
+                        "Abbrechen"
+                    </button>
+                    <button
+                        class="bg-gradient-primary text-white font-bold px-4 py-2 rounded hover:opacity-90 disabled:opacity-50 disabled:cursor-not-allowed"
+                        prop:disabled=move || title.get().trim().is_empty() || host.get().trim().is_empty()
+                        on:click=handle_save
+                    >
+                        "Speichern"
+                    </button>
+                </div>
+            </div>
+        </div>
+    }
+}
@@ -5,35 +5,100 @@ use crate::{
 use leptos::*;
 use serde::{Deserialize, Serialize};
 
-use super::password_dialog::PasswordDialog;
+use super::{
+    folders::{Folder, MoveToFolderDialog},
+    highlight::{ranges_for, Highlighted, MatchSnippet, SearchMatch},
+    password_dialog::PasswordDialog,
+    tags::{EntryTagsDialog, Tag},
+    ExpiringNotesDialog, LiveTotpCode, RecoveryCodesDialog, TotpDialog,
+};
 
 #[derive(Clone, Serialize, PartialEq, Deserialize)]
 pub struct TableItemArgs {
     pub id: i32,
     pub service: String,
     pub username: String,
-    pub password: String,
     pub url: String,
     pub notes: String,
+    #[serde(default)]
+    pub require_master: bool,
+    #[serde(default)]
+    pub has_totp: bool,
+    #[serde(default = "default_has_password")]
+    pub has_password: bool,
+    #[serde(default)]
+    pub folder_id: Option<i32>,
+    #[serde(default)]
+    pub locked: bool,
+    #[serde(default)]
+    pub expires_on: Option<String>,
+}
+
+fn default_has_password() -> bool {
+    true
+}
+
+/// The seconds to wait if the backend refused to decrypt because of the decrypt limit.
+fn decrypt_limit_wait(error: &str) -> Option<&str> {
+    error
+        .strip_prefix("Decrypt limit reached, retry in ")?
+        .strip_suffix('s')
 }
 
 #[derive(Serialize)]
 struct DecryptPasswordArgs<'a> {
-    #[serde(rename = "encryptedPassword")]
-    encrypted_password: &'a str,
+    id: i32,
+    #[serde(rename = "masterPass")]
+    master_pass: &'a str,
+}
+
+#[derive(Serialize)]
+struct EntryIdArgs {
+    id: i32,
+}
+
+#[derive(Serialize)]
+struct CopyPasswordArgs {
+    id: i32,
+    #[serde(rename = "masterPass")]
+    master_pass: Option<String>,
 }
 
 #[component]
 pub fn TableItem(
     item: TableItemArgs,
+    #[prop(into)] pinned: Signal<bool>,
+    #[prop(into)] double_click: Signal<String>,
     on_edit: Callback<i32>,
     on_delete: Callback<i32>,
+    on_toggle_pin: Callback<i32>,
+    #[prop(into)] folders: Signal<Vec<Folder>>,
+    on_move: Callback<(i32, Option<i32>)>,
+    #[prop(into)] tags: Signal<Vec<Tag>>,
+    on_tags_changed: Callback<()>,
+    #[prop(optional)] matches: Vec<SearchMatch>,
 ) -> impl IntoView {
     let (show_password_dialog, set_show_password_dialog) = create_signal(false);
     let (is_copied_username, set_is_copied_username) = create_signal(false);
     let (password_verified, set_password_verified) = create_signal(false);
     let (is_copied_password, set_is_copied_password) = create_signal(false);
     let (decrypted_password, set_decrypted_password) = create_signal(String::new());
+    let (clipboard_note, set_clipboard_note) = create_signal(String::new());
+    let (limit_note, set_limit_note) = create_signal(String::new());
+    let (copy_requested, set_copy_requested) = create_signal(false);
+    let (recovery_requested, set_recovery_requested) = create_signal(false);
+    let (recovery_master_pass, set_recovery_master_pass) = create_signal(None::<String>);
+    let (show_recovery_codes, set_show_recovery_codes) = create_signal(false);
+    let (notes_requested, set_notes_requested) = create_signal(false);
+    let (notes_master_pass, set_notes_master_pass) = create_signal(None::<String>);
+    let (show_expiring_notes, set_show_expiring_notes) = create_signal(false);
+    let (totp_requested, set_totp_requested) = create_signal(false);
+    let (totp_master_pass, set_totp_master_pass) = create_signal(None::<String>);
+    let (show_totp, set_show_totp) = create_signal(false);
+    let (code_master_pass, set_code_master_pass) = create_signal(None::<String>);
+    let (has_totp, set_has_totp) = create_signal(item.has_totp);
+    let (show_move, set_show_move) = create_signal(false);
+    let (show_tags, set_show_tags) = create_signal(false);
 
     let eye_icon = create_memo(move |_| {
         if password_verified.get() {
@@ -60,27 +125,149 @@ pub fn TableItem(
         }
     });
 
+    let lock_icon = create_memo(move |_| "lock-closed");
+    let recovery_icon = create_memo(move |_| "shield-check");
+    let expiring_icon = create_memo(move |_| "clock");
+    let pin_icon = create_memo(move |_| "bookmark");
+    let totp_icon = create_memo(move |_| "device-phone-mobile");
+    let folder_icon = create_memo(move |_| "folder-open");
+    let tag_icon = create_memo(move |_| "tag");
+
+    let service_ranges = ranges_for(&matches, "service");
+    let username_ranges = ranges_for(&matches, "username");
+    let url_ranges = ranges_for(&matches, "url");
+    let notes_ranges = ranges_for(&matches, "notes");
+    let (service, username) = (item.service.clone(), item.username.clone());
+    let (url, notes) = (item.url.clone(), item.notes.clone());
+
     let item = create_memo(move |_| item.clone());
 
+    let copy_password = move |master_pass: Option<String>| {
+        let id = item.get().id;
+        spawn_local(async move {
+            let args = serde_wasm_bindgen::to_value(&CopyPasswordArgs { id, master_pass }).unwrap();
+            let response = invoke("copy_password", args).await;
+            match serde_wasm_bindgen::from_value::<ClipboardProtection>(response.clone()) {
+                Ok(protection) => {
+                    set_is_copied_password.set(true);
+                    set_limit_note.set(String::new());
+                    set_clipboard_note.set(if protection.excluded_from_history {
+                        protection.note
+                    } else {
+                        format!("Achtung: {}", protection.note)
+                    });
+                }
+                Err(_) => {
+                    let error = response.as_string().unwrap_or_default();
+                    if let Some(wait) = decrypt_limit_wait(&error) {
+                        set_limit_note.set(format!(
+                            "Zu viele Passwörter in kurzer Zeit entschlüsselt. Warte {} Sekunden oder bestätige mit dem Master-Passwort.",
+                            wait
+                        ));
+                        set_copy_requested.set(true);
+                        set_show_password_dialog.set(true);
+                    }
+                }
+            }
+        });
+    };
+
+    let copy_username = move || {
+        let username = item.get().username.clone();
+        spawn_local(async move {
+            let args = serde_wasm_bindgen::to_value(&ClipboardArgs { text: &username }).unwrap();
+            if serde_wasm_bindgen::from_value::<ClipboardProtection>(
+                invoke("copy_to_clipboard", args).await,
+            )
+            .is_ok()
+            {
+                set_is_copied_username.set(true);
+            }
+        });
+    };
+
+    let handle_double_click = move |ev: ev::MouseEvent| {
+        if event_target::<web_sys::Element>(&ev)
+            .closest("button, input")
+            .ok()
+            .flatten()
+            .is_some()
+        {
+            return;
+        }
+
+        let id = item.get().id;
+        match double_click.get().as_str() {
+            "copy_username" => copy_username(),
+            "open_detail" => on_edit.call(id),
+            "open_url" => spawn_local(async move {
+                let args = serde_wasm_bindgen::to_value(&EntryIdArgs { id }).unwrap();
+                invoke("open_entry_url", args).await;
+            }),
+            _ => {
+                if item.get().require_master {
+                    set_copy_requested.set(true);
+                    set_show_password_dialog.set(true);
+                } else {
+                    copy_password(None);
+                }
+            }
+        }
+    };
+
     view! {
-        <tr class="hover:bg-background">
-            <td class="p-4 text-white whitespace-nowrap">{move || item.get().service}</td>
+        <tr class="hover:bg-background" on:dblclick=handle_double_click>
+            <td class="p-4 text-white whitespace-nowrap">
+                <div class="flex items-center">
+                    <Highlighted text=service ranges=service_ranges />
+                    {move || {
+                        if item.get().require_master {
+                            view! {
+                                <span title="Master-Passwort wird immer abgefragt">
+                                    <Icon icon=lock_icon.into() class="w-4 h-4 ml-2 text-primary-100" />
+                                </span>
+                            }.into_view()
+                        } else {
+                            view! { <div/> }.into_view()
+                        }
+                    }}
+                    {move || item.get().locked.then(|| view! {
+                        <span title="Schreibgeschützt">
+                            <Icon icon=lock_icon.into() class="w-4 h-4 ml-2 text-gray-400" />
+                        </span>
+                    })}
+                </div>
+                {move || {
+                    let id = item.get().id;
+                    let names = tags.with(|tags| {
+                        tags.iter()
+                            .filter(|tag| tag.entry_ids.contains(&id))
+                            .map(|tag| tag.name.clone())
+                            .collect::<Vec<_>>()
+                    });
+                    (!names.is_empty()).then(|| view! {
+                        <div class="flex flex-wrap gap-1 mt-1">
+                            {names.into_iter().map(|name| view! {
+                                <span class="px-2 py-0.5 rounded-full border border-gray-600 text-xs text-gray-400">
+                                    {name}
+                                </span>
+                            }).collect_view()}
+                        </div>
+                    })
+                }}
+                {(!url_ranges.is_empty()).then(|| view! {
+                    <MatchSnippet label="URL" text=url ranges=url_ranges />
+                })}
+                {(!notes_ranges.is_empty()).then(|| view! {
+                    <MatchSnippet label="Notiz" text=notes ranges=notes_ranges />
+                })}
+            </td>
             <td class="p-4">
                 <div class="flex items-center text-white whitespace-nowrap">
-                    {move || item.get().username}
+                    <Highlighted text=username ranges=username_ranges />
                     <button
                         class="ml-2 text-gray-400 hover:text-primary-100"
-                        on:click=move |_| {
-                            let username = item.get().username.clone();
-                            spawn_local(async move {
-                                let args = serde_wasm_bindgen::to_value(&ClipboardArgs {
-                                    text: &username
-                                }).unwrap();
-                                if serde_wasm_bindgen::from_value::<()>(invoke("copy_to_clipboard", args).await).is_ok() {
-                                    set_is_copied_username.set(true);
-                                }
-                            });
-                        }
+                        on:click=move |_| copy_username()
                         on:mouseleave=move |_| set_is_copied_username.set(false)
                     >
                         <Icon icon=username_icon.into() class="w-4 h-4" />
@@ -90,7 +277,13 @@ pub fn TableItem(
             <td class="p-4">
                 <div class="flex items-center text-white">
                     {move || {
-                        if !password_verified.get() {
+                        if !item.get().has_password
+                            && (!item.get().require_master || password_verified.get())
+                        {
+                            view! {
+                                <LiveTotpCode id=item.get().id master_pass=code_master_pass.get() />
+                            }.into_view()
+                        } else if !password_verified.get() {
                             view! {
                                 <span class="whitespace-nowrap">"••••••••"</span>
                             }.into_view()
@@ -101,34 +294,34 @@ pub fn TableItem(
                         }
                     }}
 
-                    <button
-                        class="ml-2 text-gray-400 hover:text-primary-100"
-                        on:click=move |_| {
-                            if !password_verified.get() {
-                                set_show_password_dialog.set(true);
-                            } else {
-                                set_password_verified.set(false);
+                    {move || (item.get().has_password || item.get().require_master).then(|| view! {
+                        <button
+                            class="ml-2 text-gray-400 hover:text-primary-100"
+                            on:click=move |_| {
+                                if !password_verified.get() {
+                                    set_show_password_dialog.set(true);
+                                } else {
+                                    set_password_verified.set(false);
+                                }
                             }
-                        }
-                    >
-                        <Icon icon=eye_icon.into() class="w-4 h-4" />
-                    </button>
+                        >
+                            <Icon icon=eye_icon.into() class="w-4 h-4" />
+                        </button>
+                    })}
                     {move || {
-                        if password_verified.get() {
+                        if password_verified.get() && item.get().has_password {
                             view! {
                                 <button
                                     class="ml-2 text-gray-400 hover:text-primary-100"
                                     on:click=move |_| {
-                                        let password = decrypted_password.get().clone();
-                                        spawn_local(async move {
-                                            let args = serde_wasm_bindgen::to_value(&ClipboardArgs {
-                                                text: &password
-                                            }).unwrap();
-                                            if serde_wasm_bindgen::from_value::<()>(invoke("copy_to_clipboard", args).await).is_ok() {
-                                                set_is_copied_password.set(true);
-                                            }
-                                        });
+                                        if item.get().require_master {
+                                            set_copy_requested.set(true);
+                                            set_show_password_dialog.set(true);
+                                        } else {
+                                            copy_password(None);
+                                        }
                                     }
+                                    title=move || clipboard_note.get()
                                 >
                                     <Icon icon=clipboard_icon.into() class="w-4 h-4" />
                                 </button>
@@ -138,9 +331,84 @@ pub fn TableItem(
                         }
                     }}
                 </div>
+                {move || (!limit_note.get().is_empty()).then(|| view! {
+                    <p class="mt-1 text-xs text-red-500">{limit_note.get()}</p>
+                })}
             </td>
             <td class="p-4">
                 <div class="flex justify-end space-x-2">
+                    {move || (!item.get().require_master).then(|| view! {
+                        <button
+                            class="hover:text-primary-100"
+                            class:text-primary-100=move || pinned.get()
+                            class:text-gray-400=move || !pinned.get()
+                            title=move || if pinned.get() { "Aus dem Tray-Menü entfernen" } else { "Im Tray-Menü anheften" }
+                            on:click=move |_| on_toggle_pin.call(item.get().id)
+                        >
+                            <Icon icon=pin_icon.into() class="w-5 h-5" />
+                        </button>
+                    })}
+                    <button
+                        class="text-gray-400 hover:text-primary-100"
+                        title="Wiederherstellungscodes"
+                        on:click=move |_| {
+                            if item.get().require_master {
+                                set_recovery_requested.set(true);
+                                set_show_password_dialog.set(true);
+                            } else {
+                                set_recovery_master_pass.set(None);
+                                set_show_recovery_codes.set(true);
+                            }
+                        }
+                    >
+                        <Icon icon=recovery_icon.into() class="w-5 h-5" />
+                    </button>
+                    <button
+                        class="text-gray-400 hover:text-primary-100"
+                        title="Ablaufende Notizen"
+                        on:click=move |_| {
+                            if item.get().require_master {
+                                set_notes_requested.set(true);
+                                set_show_password_dialog.set(true);
+                            } else {
+                                set_notes_master_pass.set(None);
+                                set_show_expiring_notes.set(true);
+                            }
+                        }
+                    >
+                        <Icon icon=expiring_icon.into() class="w-5 h-5" />
+                    </button>
+                    <button
+                        class="hover:text-primary-100"
+                        class:text-primary-100=move || has_totp.get()
+                        class:text-gray-400=move || !has_totp.get()
+                        title="Zwei-Faktor-Code"
+                        on:click=move |_| {
+                            if item.get().require_master && has_totp.get() {
+                                set_totp_requested.set(true);
+                                set_show_password_dialog.set(true);
+                            } else {
+                                set_totp_master_pass.set(None);
+                                set_show_totp.set(true);
+                            }
+                        }
+                    >
+                        <Icon icon=totp_icon.into() class="w-5 h-5" />
+                    </button>
+                    <button
+                        class="text-gray-400 hover:text-primary-100"
+                        title="In Ordner verschieben"
+                        on:click=move |_| set_show_move.set(true)
+                    >
+                        <Icon icon=folder_icon.into() class="w-5 h-5" />
+                    </button>
+                    <button
+                        class="text-gray-400 hover:text-primary-100"
+                        title="Tags"
+                        on:click=move |_| set_show_tags.set(true)
+                    >
+                        <Icon icon=tag_icon.into() class="w-5 h-5" />
+                    </button>
                     <button
                         class="text-gray-400 hover:text-primary-100"
                         on:click=move |_| on_edit.call(item.get().id)
@@ -148,7 +416,13 @@ pub fn TableItem(
                         <Icon icon=pencil_icon.into() class="w-5 h-5" />
                     </button>
                     <button
-                        class="text-gray-400 hover:text-red-500"
+                        class="text-gray-400 hover:text-red-500 disabled:opacity-50 disabled:hover:text-gray-400"
+                        title=move || if item.get().locked {
+                            "Schreibgeschützte Einträge müssen zuerst entsperrt werden"
+                        } else {
+                            "Löschen"
+                        }
+                        prop:disabled=move || item.get().locked
                         on:click=move |_| on_delete.call(item.get().id)
                     >
                         <Icon icon=trash_icon.into() class="w-5 h-5" />
@@ -161,30 +435,119 @@ pub fn TableItem(
                     view! {
                         <PasswordDialog
                         action=DialogAction::Verify
+                        on_master_password=move |master_pass: String| {
+                            if recovery_requested.get() {
+                                set_recovery_master_pass.set(Some(master_pass));
+                                set_show_recovery_codes.set(true);
+                                return;
+                            }
+                            if notes_requested.get() {
+                                set_notes_master_pass.set(Some(master_pass));
+                                set_show_expiring_notes.set(true);
+                                return;
+                            }
+                            if totp_requested.get() {
+                                set_totp_master_pass.set(Some(master_pass));
+                                set_show_totp.set(true);
+                                return;
+                            }
+                            if copy_requested.get() {
+                                copy_password(Some(master_pass));
+                                return;
+                            }
+                            if !item.get().has_password {
+                                set_code_master_pass.set(Some(master_pass));
+                                return;
+                            }
+
+                            let id = item.get().id;
+                            spawn_local(async move {
+                                let args = serde_wasm_bindgen::to_value(&DecryptPasswordArgs {
+                                    id,
+                                    master_pass: &master_pass,
+                                })
+                                .unwrap();
+                                if let Ok(decrypted) =
+                                    serde_wasm_bindgen::from_value(invoke("decrypt_password", args).await)
+                                {
+                                    set_decrypted_password.set(decrypted);
+                                }
+                            });
+                        }
                         on_verify=move |verified| {
-                            if verified {
-                                let password = item.get().password.clone();
-                                spawn_local(async move {
-                                    let args = serde_wasm_bindgen::to_value(&DecryptPasswordArgs {
-                                        encrypted_password: &password,
-                                    })
-                                    .unwrap();
-                                    if let Ok(decrypted) =
-                                        serde_wasm_bindgen::from_value(invoke("decrypt_password", args).await)
-                                    {
-                                        set_decrypted_password.set(decrypted);
-                                    }
-                                });
+                            if !copy_requested.get()
+                                && !recovery_requested.get()
+                                && !notes_requested.get()
+                                && !totp_requested.get()
+                            {
+                                set_password_verified.set(verified);
                             }
-                            set_password_verified.set(verified);
                         }
-                            on_close=move |_| set_show_password_dialog.set(false)
+                            on_close=move |_| {
+                                set_copy_requested.set(false);
+                                set_recovery_requested.set(false);
+                                set_notes_requested.set(false);
+                                set_totp_requested.set(false);
+                                set_show_password_dialog.set(false);
+                            }
                         />
                     }.into_view()
                 } else {
                     view! { <div/> }.into_view()
                 }
             }}
+
+            {move || show_recovery_codes.get().then(|| view! {
+                <RecoveryCodesDialog
+                    id=item.get().id
+                    service=item.get().service
+                    master_pass=recovery_master_pass.get()
+                    on_close=move |_| set_show_recovery_codes.set(false)
+                />
+            })}
+
+            {move || show_expiring_notes.get().then(|| view! {
+                <ExpiringNotesDialog
+                    id=item.get().id
+                    service=item.get().service
+                    master_pass=notes_master_pass.get()
+                    on_close=move |_| set_show_expiring_notes.set(false)
+                />
+            })}
+
+            {move || show_totp.get().then(|| view! {
+                <TotpDialog
+                    id=item.get().id
+                    service=item.get().service
+                    master_pass=totp_master_pass.get()
+                    has_secret=has_totp.get_untracked()
+                    on_change=move |saved| set_has_totp.set(saved)
+                    on_close=move |_| set_show_totp.set(false)
+                />
+            })}
+
+            {move || show_move.get().then(|| view! {
+                <MoveToFolderDialog
+                    service=item.get().service
+                    current=item.get().folder_id
+                    folders=folders
+                    on_move=move |folder_id| {
+                        set_show_move.set(false);
+                        on_move.call((item.get().id, folder_id));
+                    }
+                    on_close=move |_| set_show_move.set(false)
+                />
+            })}
+
+            {move || show_tags.get().then(|| view! {
+                <EntryTagsDialog
+                    id=item.get().id
+                    service=item.get().service
+                    tags=tags
+                    on_changed=on_tags_changed
+                    on_close=move |_| set_show_tags.set(false)
+                />
+            })}
         </tr>
     }
 }
@@ -193,3 +556,9 @@ pub fn TableItem(
 struct ClipboardArgs<'a> {
     text: &'a str,
 }
+
+#[derive(Deserialize)]
+struct ClipboardProtection {
+    excluded_from_history: bool,
+    note: String,
+}
@@ -0,0 +1,294 @@
+use crate::{app::invoke, components::icons::Icon};
+use leptos::*;
+use serde::{Deserialize, Serialize};
+use std::collections::HashSet;
+
+#[derive(Clone, PartialEq, Deserialize)]
+pub struct Tag {
+    pub id: i32,
+    pub name: String,
+    pub entry_ids: Vec<i32>,
+}
+
+#[derive(Serialize)]
+struct CreateTagArgs {
+    name: String,
+}
+
+#[derive(Serialize)]
+struct RenameTagArgs {
+    id: i32,
+    name: String,
+}
+
+#[derive(Serialize)]
+struct TagIdArgs {
+    id: i32,
+}
+
+#[derive(Serialize)]
+struct SetEntryTagArgs {
+    id: i32,
+    #[serde(rename = "tagId")]
+    tag_id: i32,
+    tagged: bool,
+}
+
+/// Load the tags of the logged in user.
+pub async fn load_tags() -> Vec<Tag> {
+    let response = invoke("get_tags", wasm_bindgen::JsValue::NULL).await;
+    serde_wasm_bindgen::from_value(response).unwrap_or_default()
+}
+
+/// Check if an entry carries all selected tags, no selection matches every entry.
+pub fn has_all_tags(tags: &[Tag], selected: &HashSet<i32>, entry_id: i32) -> bool {
+    selected.iter().all(|id| {
+        tags.iter()
+            .any(|tag| tag.id == *id && tag.entry_ids.contains(&entry_id))
+    })
+}
+
+#[component]
+pub fn TagFilter(
+    tags: ReadSignal<Vec<Tag>>,
+    selected: ReadSignal<HashSet<i32>>,
+    #[prop(into)] on_toggle: Callback<i32>,
+    #[prop(into)] on_clear: Callback<()>,
+) -> impl IntoView {
+    let tag_icon = create_memo(move |_| "tag");
+
+    view! {
+        {move || (!tags.get().is_empty()).then(|| view! {
+            <div class="flex flex-wrap items-center gap-2 px-4 pb-2 text-sm">
+                <span class="flex items-center text-gray-400" title="Es werden nur Einträge mit allen gewählten Tags angezeigt">
+                    <Icon icon=tag_icon.into() class="w-4 h-4 mr-1" />
+                    "Tags"
+                </span>
+                {tags.get().into_iter().map(|tag| {
+                    let id = tag.id;
+                    view! {
+                        <button
+                            class="px-2 py-0.5 rounded-full border transition-colors"
+                            class:border-primary-100=move || selected.get().contains(&id)
+                            class:text-primary-100=move || selected.get().contains(&id)
+                            class:border-gray-600=move || !selected.get().contains(&id)
+                            class:text-gray-400=move || !selected.get().contains(&id)
+                            on:click=move |_| on_toggle.call(id)
+                        >
+                            {format!("{} ({})", tag.name, tag.entry_ids.len())}
+                        </button>
+                    }
+                }).collect_view()}
+                {move || (!selected.get().is_empty()).then(|| view! {
+                    <button
+                        class="text-gray-400 hover:text-white transition-colors"
+                        on:click=move |_| on_clear.call(())
+                    >
+                        "Zurücksetzen"
+                    </button>
+                })}
+            </div>
+        })}
+    }
+}
+
+#[component]
+pub fn EntryTagsDialog(
+    id: i32,
+    service: String,
+    #[prop(into)] tags: Signal<Vec<Tag>>,
+    #[prop(into)] on_changed: Callback<()>,
+    #[prop(into)] on_close: Callback<()>,
+) -> impl IntoView {
+    let (new_name, set_new_name) = create_signal(String::new());
+    let (renaming, set_renaming) = create_signal(None::<i32>);
+    let (rename_to, set_rename_to) = create_signal(String::new());
+    let (error, set_error) = create_signal(String::new());
+
+    let exit_icon = create_memo(move |_| "x-mark");
+    let tag_icon = create_memo(move |_| "tag");
+    let plus_icon = create_memo(move |_| "plus");
+    let pencil_icon = create_memo(move |_| "pencil-square");
+    let trash_icon = create_memo(move |_| "trash");
+    let check_icon = create_memo(move |_| "check");
+    let cancel_icon = create_memo(move |_| "x-mark");
+
+    let handle_response = move |response: wasm_bindgen::JsValue| -> bool {
+        if response.is_string() {
+            set_error.set(
+                response
+                    .as_string()
+                    .unwrap_or("Unbekannter Fehler".to_string()),
+            );
+            false
+        } else {
+            set_error.set(String::new());
+            on_changed.call(());
+            true
+        }
+    };
+
+    let handle_toggle = move |tag_id: i32, tagged: bool| {
+        spawn_local(async move {
+            let args =
+                serde_wasm_bindgen::to_value(&SetEntryTagArgs { id, tag_id, tagged }).unwrap();
+            handle_response(invoke("set_entry_tag", args).await);
+        });
+    };
+
+    let handle_create = move || {
+        let name = new_name.get();
+        spawn_local(async move {
+            let args = serde_wasm_bindgen::to_value(&CreateTagArgs { name }).unwrap();
+            let response = invoke("create_tag", args).await;
+            match serde_wasm_bindgen::from_value::<Tag>(response.clone()) {
+                Ok(tag) => {
+                    set_new_name.set(String::new());
+                    let args = serde_wasm_bindgen::to_value(&SetEntryTagArgs {
+                        id,
+                        tag_id: tag.id,
+                        tagged: true,
+                    })
+                    .unwrap();
+                    handle_response(invoke("set_entry_tag", args).await);
+                }
+                Err(_) => {
+                    handle_response(response);
+                }
+            }
+        });
+    };
+
+    let handle_rename = move || {
+        let Some(tag_id) = renaming.get() else {
+            return;
+        };
+        let name = rename_to.get();
+        spawn_local(async move {
+            let args = serde_wasm_bindgen::to_value(&RenameTagArgs { id: tag_id, name }).unwrap();
+            if handle_response(invoke("rename_tag", args).await) {
+                set_renaming.set(None);
+            }
+        });
+    };
+
+    let handle_delete = move |tag_id: i32| {
+        spawn_local(async move {
+            let args = serde_wasm_bindgen::to_value(&TagIdArgs { id: tag_id }).unwrap();
+            handle_response(invoke("delete_tag", args).await);
+        });
+    };
+
+    view! {
+        <div class="fixed inset-0 bg-black bg-opacity-50 flex items-center justify-center z-50">
+            <div class="bg-background-card rounded-lg p-6 w-full max-w-md">
+                <div class="flex justify-between items-center mb-6">
+                    <h2 class="text-xl font-bold bg-gradient-primary bg-clip-text text-transparent flex items-center">
+                        <Icon icon=tag_icon.into() class="w-8 h-8 mr-2 text-primary-100" />
+                        {format!("Tags: {}", service)}
+                    </h2>
+                    <button
+                        class="text-gray-400 hover:text-white"
+                        on:click=move |_| on_close.call(())
+                    >
+                        <Icon icon=exit_icon.into() class="w-5 h-5" />
+                    </button>
+                </div>
+
+                <div class="max-h-80 overflow-y-auto space-y-1 text-sm">
+                    {move || tags.get().is_empty().then(|| view! {
+                        <p class="text-gray-400">"Noch keine Tags vorhanden"</p>
+                    })}
+                    {move || tags.get().into_iter().map(|tag| {
+                        let tag_id = tag.id;
+                        let tagged = tag.entry_ids.contains(&id);
+                        if renaming.get() == Some(tag_id) {
+                            view! {
+                                <div class="flex items-center gap-1 p-2">
+                                    <input
+                                        type="text"
+                                        class="flex-1 min-w-0 px-2 py-1 rounded bg-background text-white border border-gray-600 focus:outline-none focus:border-primary-100"
+                                        prop:value=rename_to
+                                        on:input=move |ev| set_rename_to.set(event_target_value(&ev))
+                                        on:keydown=move |ev| match ev.key().as_str() {
+                                            "Enter" => handle_rename(),
+                                            "Escape" => set_renaming.set(None),
+                                            _ => {}
+                                        }
+                                    />
+                                    <button class="text-gray-400 hover:text-primary-100" title="Speichern" on:click=move |_| handle_rename()>
+                                        <Icon icon=check_icon.into() class="w-4 h-4" />
+                                    </button>
+                                    <button class="text-gray-400 hover:text-white" title="Abbrechen" on:click=move |_| set_renaming.set(None)>
+                                        <Icon icon=cancel_icon.into() class="w-4 h-4" />
+                                    </button>
+                                </div>
+                            }.into_view()
+                        } else {
+                            let name = tag.name.clone();
+                            view! {
+                                <div class="group flex items-center p-2 rounded hover:bg-background transition-colors">
+                                    <input
+                                        type="checkbox"
+                                        id=format!("tag-{}", tag_id)
+                                        class="mr-2"
+                                        prop:checked=tagged
+                                        on:change=move |ev| handle_toggle(tag_id, event_target_checked(&ev))
+                                    />
+                                    <label for=format!("tag-{}", tag_id) class="flex-1 truncate text-white">
+                                        {tag.name}
+                                    </label>
+                                    <div class="hidden group-hover:flex items-center gap-1 text-gray-400">
+                                        <button
+                                            class="hover:text-primary-100"
+                                            title="Umbenennen"
+                                            on:click=move |_| {
+                                                set_rename_to.set(name.clone());
+                                                set_renaming.set(Some(tag_id));
+                                            }
+                                        >
+                                            <Icon icon=pencil_icon.into() class="w-4 h-4" />
+                                        </button>
+                                        <button
+                                            class="hover:text-red-500"
+                                            title="Löschen, die Einträge bleiben erhalten"
+                                            on:click=move |_| handle_delete(tag_id)
+                                        >
+                                            <Icon icon=trash_icon.into() class="w-4 h-4" />
+                                        </button>
+                                    </div>
+                                </div>
+                            }.into_view()
+                        }
+                    }).collect_view()}
+                </div>
+
+                <div class="flex items-center gap-2 mt-4 pt-4 border-t border-gray-600">
+                    <input
+                        type="text"
+                        class="flex-1 p-2 rounded bg-background text-white border border-gray-600 focus:outline-none focus:border-primary-100"
+                        placeholder="Neuer Tag"
+                        prop:value=new_name
+                        on:input=move |ev| set_new_name.set(event_target_value(&ev))
+                        on:keydown=move |ev| {
+                            if ev.key() == "Enter" {
+                                handle_create();
+                            }
+                        }
+                    />
+                    <button
+                        class="bg-gradient-primary text-white p-2 rounded hover:opacity-90"
+                        title="Tag erstellen und zuweisen"
+                        on:click=move |_| handle_create()
+                    >
+                        <Icon icon=plus_icon.into() class="w-5 h-5" />
+                    </button>
+                </div>
+
+                {move || (!error.get().is_empty()).then(|| view! {
+                    <p class="mt-4 text-sm text-primary-100">{error.get()}</p>
+                })}
+            </div>
+        </div>
+    }
+}
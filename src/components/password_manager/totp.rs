@@ -0,0 +1,299 @@
+use crate::{app::invoke, components::icons::Icon};
+use leptos::*;
+use serde::{Deserialize, Serialize};
+use std::time::Duration;
+
+#[derive(Clone, Deserialize, PartialEq)]
+struct TotpCode {
+    code: String,
+    remaining: u64,
+}
+
+#[derive(Serialize)]
+struct GetTotpCodeArgs {
+    id: i32,
+    #[serde(rename = "masterPass")]
+    master_pass: Option<String>,
+}
+
+#[derive(Serialize)]
+struct SetTotpSecretArgs {
+    id: i32,
+    secret: String,
+}
+
+#[derive(Serialize)]
+struct ClipboardArgs<'a> {
+    text: &'a str,
+}
+
+#[component]
+pub fn TotpDialog(
+    id: i32,
+    service: String,
+    master_pass: Option<String>,
+    has_secret: bool,
+    #[prop(into)] on_change: Callback<bool>,
+    #[prop(into)] on_close: Callback<()>,
+) -> impl IntoView {
+    let (code, set_code) = create_signal(None::<TotpCode>);
+    let (is_editing, set_is_editing) = create_signal(!has_secret);
+    let (input, set_input) = create_signal(String::new());
+    let (error, set_error) = create_signal(String::new());
+    let (is_copied, set_is_copied) = create_signal(false);
+
+    let exit_icon = create_memo(move |_| "x-mark");
+    let phone_icon = create_memo(move |_| "device-phone-mobile");
+    let check_icon = create_memo(move |_| "check");
+    let trash_icon = create_memo(move |_| "trash");
+    let clipboard_icon = create_memo(move |_| {
+        if is_copied.get() {
+            "check"
+        } else {
+            "clipboard"
+        }
+    });
+
+    let load_code = move || {
+        let master_pass = master_pass.clone();
+        spawn_local(async move {
+            let args = serde_wasm_bindgen::to_value(&GetTotpCodeArgs { id, master_pass }).unwrap();
+            let response = invoke("get_totp_code", args).await;
+            match serde_wasm_bindgen::from_value::<TotpCode>(response.clone()) {
+                Ok(loaded) => {
+                    if code.with_untracked(|c| c.as_ref().map(|c| &c.code) != Some(&loaded.code)) {
+                        set_is_copied.set(false);
+                    }
+                    set_code.set(Some(loaded));
+                }
+                Err(_) => {
+                    set_code.set(None);
+                    set_error.set(
+                        response
+                            .as_string()
+                            .unwrap_or("Unbekannter Fehler".to_string()),
+                    );
+                }
+            }
+        });
+    };
+
+    let tick = {
+        let load_code = load_code.clone();
+        move || {
+            if is_editing.get_untracked() {
+                return;
+            }
+            match code.get_untracked() {
+                Some(current) if current.remaining > 1 => set_code.set(Some(TotpCode {
+                    remaining: current.remaining - 1,
+                    ..current
+                })),
+                _ => load_code(),
+            }
+        }
+    };
+
+    if has_secret {
+        load_code();
+    }
+    if let Ok(handle) = set_interval_with_handle(tick, Duration::from_secs(1)) {
+        on_cleanup(move || handle.clear());
+    }
+
+    let save_secret = move |secret: String| {
+        let load_code = load_code.clone();
+        spawn_local(async move {
+            let removed = secret.trim().is_empty();
+            let args = serde_wasm_bindgen::to_value(&SetTotpSecretArgs { id, secret }).unwrap();
+            let response = invoke("set_totp_secret", args).await;
+            match serde_wasm_bindgen::from_value::<()>(response.clone()) {
+                Ok(_) => {
+                    set_input.set(String::new());
+                    set_error.set(String::new());
+                    set_code.set(None);
+                    set_is_editing.set(removed);
+                    on_change.call(!removed);
+                    if !removed {
+                        load_code();
+                    }
+                }
+                Err(_) => set_error.set(
+                    response
+                        .as_string()
+                        .unwrap_or("Unbekannter Fehler".to_string()),
+                ),
+            }
+        });
+    };
+    let save_input = save_secret.clone();
+
+    let copy_code = move |_| {
+        if let Some(current) = code.get() {
+            spawn_local(async move {
+                let args = serde_wasm_bindgen::to_value(&ClipboardArgs {
+                    text: &current.code,
+                })
+                .unwrap();
+                let _ = invoke("copy_to_clipboard", args).await;
+                set_is_copied.set(true);
+            });
+        }
+    };
+
+    view! {
+        <div class="fixed inset-0 bg-black bg-opacity-50 flex items-center justify-center z-50">
+            <div class="bg-background-card rounded-lg p-6 w-full max-w-md">
+                <div class="flex justify-between items-center mb-6">
+                    <h2 class="text-xl font-bold bg-gradient-primary bg-clip-text text-transparent flex items-center">
+                        <Icon icon=phone_icon.into() class="w-8 h-8 mr-2 text-primary-100" />
+                        {format!("Zwei-Faktor-Code: {}", service)}
+                    </h2>
+                    <button
+                        class="text-gray-400 hover:text-white"
+                        on:click=move |_| on_close.call(())
+                    >
+                        <Icon icon=exit_icon.into() class="w-5 h-5" />
+                    </button>
+                </div>
+
+                {move || (!error.get().is_empty()).then(|| view! {
+                    <div class="mb-4 text-primary-100 text-sm">{error.get()}</div>
+                })}
+
+                {move || if is_editing.get() {
+                    let save_input = save_input.clone();
+                    view! {
+                        <div class="space-y-4">
+                            <p class="text-sm text-gray-400">
+                                "Füge den geheimen Schlüssel oder die otpauth://-Adresse aus dem QR-Code des Anbieters ein."
+                            </p>
+                            <input
+                                type="text"
+                                class="w-full p-2 rounded bg-background text-white font-mono border border-gray-600 focus:outline-none focus:border-primary-100"
+                                placeholder="JBSW Y3DP EHPK 3PXP"
+                                on:input=move |ev| set_input.set(event_target_value(&ev))
+                                prop:value=input
+                            />
+                            <button
+                                class="w-full bg-gradient-primary text-white px-4 py-2 rounded hover:opacity-90 flex items-center justify-center disabled:opacity-50 disabled:cursor-not-allowed"
+                                prop:disabled=move || input.get().trim().is_empty()
+                                on:click=move |_| save_input(input.get())
+                            >
+                                <Icon icon=check_icon.into() class="w-5 h-5 mr-2" />
+                                "Schlüssel speichern"
+                            </button>
+                        </div>
+                    }.into_view()
+                } else {
+                    let save_secret = save_secret.clone();
+                    view! {
+                        <div class="space-y-4">
+                            <div class="flex items-center justify-between p-4 rounded bg-background">
+                                <span class="font-mono text-3xl tracking-widest text-white">
+                                    {move || code.get().map(|c| c.code).unwrap_or("------".to_string())}
+                                </span>
+                                <button
+                                    class="p-2 text-gray-400 hover:text-primary-100"
+                                    title="Kopieren"
+                                    on:click=copy_code
+                                >
+                                    <Icon icon=clipboard_icon.into() class="w-5 h-5" />
+                                </button>
+                            </div>
+                            <p class="text-sm text-gray-400">
+                                {move || code.get().map(|c| format!("Gültig für {} Sekunden", c.remaining)).unwrap_or_default()}
+                            </p>
+                            <div class="grid grid-cols-2 gap-4">
+                                <button
+                                    class="border border-gray-600 text-gray-300 px-4 py-2 rounded hover:bg-background transition-colors"
+                                    on:click=move |_| set_is_editing.set(true)
+                                >
+                                    "Schlüssel ersetzen"
+                                </button>
+                                <button
+                                    class="flex items-center justify-center border border-gray-600 text-gray-300 px-4 py-2 rounded hover:text-red-500 transition-colors"
+                                    on:click=move |_| save_secret(String::new())
+                                >
+                                    <Icon icon=trash_icon.into() class="w-5 h-5 mr-2" />
+                                    "Entfernen"
+                                </button>
+                            </div>
+                        </div>
+                    }.into_view()
+                }}
+            </div>
+        </div>
+    }
+}
+
+#[component]
+pub fn LiveTotpCode(id: i32, master_pass: Option<String>) -> impl IntoView {
+    let (code, set_code) = create_signal(None::<TotpCode>);
+    let (is_copied, set_is_copied) = create_signal(false);
+
+    let clipboard_icon = create_memo(move |_| {
+        if is_copied.get() {
+            "check"
+        } else {
+            "clipboard"
+        }
+    });
+
+    let load_code = move || {
+        let master_pass = master_pass.clone();
+        spawn_local(async move {
+            let args = serde_wasm_bindgen::to_value(&GetTotpCodeArgs { id, master_pass }).unwrap();
+            let loaded =
+                serde_wasm_bindgen::from_value::<TotpCode>(invoke("get_totp_code", args).await)
+                    .ok();
+            if code
+                .with_untracked(|c| c.as_ref().map(|c| &c.code) != loaded.as_ref().map(|c| &c.code))
+            {
+                set_is_copied.set(false);
+            }
+            set_code.set(loaded);
+        });
+    };
+
+    load_code();
+    let tick = move || match code.get_untracked() {
+        Some(current) if current.remaining > 1 => set_code.set(Some(TotpCode {
+            remaining: current.remaining - 1,
+            ..current
+        })),
+        _ => load_code(),
+    };
+    if let Ok(handle) = set_interval_with_handle(tick, Duration::from_secs(1)) {
+        on_cleanup(move || handle.clear());
+    }
+
+    let copy_code = move |_| {
+        if let Some(current) = code.get() {
+            spawn_local(async move {
+                let args = serde_wasm_bindgen::to_value(&ClipboardArgs {
+                    text: &current.code,
+                })
+                .unwrap();
+                let _ = invoke("copy_to_clipboard", args).await;
+                set_is_copied.set(true);
+            });
+        }
+    };
+
+    view! {
+        <span class="font-mono tracking-widest whitespace-nowrap">
+            {move || code.get().map(|c| c.code).unwrap_or("------".to_string())}
+        </span>
+        <span class="ml-2 text-sm text-gray-400 whitespace-nowrap">
+            {move || code.get().map(|c| format!("{} s", c.remaining)).unwrap_or_default()}
+        </span>
+        <button
+            class="ml-2 text-gray-400 hover:text-primary-100"
+            title="Code kopieren"
+            on:click=copy_code
+        >
+            <Icon icon=clipboard_icon.into() class="w-4 h-4" />
+        </button>
+    }
+}
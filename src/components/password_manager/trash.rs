@@ -0,0 +1,153 @@
+use crate::{app::invoke, components::icons::Icon};
+use leptos::*;
+use serde::{Deserialize, Serialize};
+
+#[derive(Clone, Deserialize)]
+struct TrashedEntry {
+    id: i32,
+    service: String,
+    username: String,
+    deleted_at: Option<String>,
+}
+
+#[derive(Serialize)]
+struct RestorePasswordArgs {
+    id: i32,
+}
+
+fn format_timestamp(timestamp: &str) -> String {
+    let date = web_sys::js_sys::Date::new(&wasm_bindgen::JsValue::from_str(timestamp));
+    format!(
+        "{:02}.{:02}.{} {:02}:{:02} Uhr",
+        date.get_date(),
+        date.get_month() + 1,
+        date.get_full_year(),
+        date.get_hours(),
+        date.get_minutes()
+    )
+}
+
+#[component]
+pub fn TrashDialog(
+    #[prop(into)] on_restored: Callback<()>,
+    #[prop(into)] on_close: Callback<()>,
+) -> impl IntoView {
+    let (entries, set_entries) = create_signal(Vec::<TrashedEntry>::new());
+    let (error, set_error) = create_signal(String::new());
+
+    let exit_icon = create_memo(move |_| "x-mark");
+    let trash_icon = create_memo(move |_| "trash");
+    let restore_icon = create_memo(move |_| "arrow-uturn-left");
+
+    let load_entries = move || {
+        spawn_local(async move {
+            let response = invoke("get_trashed_passwords", wasm_bindgen::JsValue::NULL).await;
+            match serde_wasm_bindgen::from_value::<Vec<TrashedEntry>>(response.clone()) {
+                Ok(trashed) => set_entries.set(trashed),
+                Err(_) => set_error.set(
+                    response
+                        .as_string()
+                        .unwrap_or("Unbekannter Fehler".to_string()),
+                ),
+            }
+        });
+    };
+
+    load_entries();
+
+    let handle_restore = move |id: i32| {
+        spawn_local(async move {
+            let args = serde_wasm_bindgen::to_value(&RestorePasswordArgs { id }).unwrap();
+            let response = invoke("restore_password", args).await;
+            if serde_wasm_bindgen::from_value::<()>(response.clone()).is_ok() {
+                set_error.set(String::new());
+                set_entries.update(|entries| entries.retain(|e| e.id != id));
+                on_restored.call(());
+            } else {
+                set_error.set(
+                    response
+                        .as_string()
+                        .unwrap_or("Unbekannter Fehler".to_string()),
+                );
+            }
+        });
+    };
+
+    let handle_purge = move |_| {
+        spawn_local(async move {
+            let response = invoke("purge_trash", wasm_bindgen::JsValue::NULL).await;
+            match serde_wasm_bindgen::from_value::<usize>(response.clone()) {
+                Ok(_) => {
+                    set_error.set(String::new());
+                    load_entries();
+                }
+                Err(_) => set_error.set(
+                    response
+                        .as_string()
+                        .unwrap_or("Unbekannter Fehler".to_string()),
+                ),
+            }
+        });
+    };
+
+    view! {
+        <div class="fixed inset-0 bg-black bg-opacity-50 flex items-center justify-center z-50">
+            <div class="bg-background-card rounded-lg p-6 w-full max-w-md">
+                <div class="flex justify-between items-center mb-6">
+                    <h2 class="text-xl font-bold bg-gradient-primary bg-clip-text text-transparent flex items-center">
+                        <Icon icon=trash_icon.into() class="w-8 h-8 mr-2 text-primary-100" />
+                        "Papierkorb"
+                    </h2>
+                    <button
+                        class="text-gray-400 hover:text-white"
+                        on:click=move |_| on_close.call(())
+                    >
+                        <Icon icon=exit_icon.into() class="w-5 h-5" />
+                    </button>
+                </div>
+
+                <div class="max-h-80 overflow-y-auto space-y-1 text-sm">
+                    {move || entries.get().is_empty().then(|| view! {
+                        <p class="text-gray-400">"Der Papierkorb ist leer"</p>
+                    })}
+                    {move || entries.get().into_iter().map(|entry| {
+                        let id = entry.id;
+                        view! {
+                            <div class="flex items-center p-2 rounded hover:bg-background transition-colors">
+                                <div class="flex-1 min-w-0">
+                                    <p class="truncate text-white">{entry.service}</p>
+                                    <p class="truncate text-gray-400">
+                                        {entry.username}
+                                        {entry.deleted_at.map(|deleted_at| format!(" · gelöscht am {}", format_timestamp(&deleted_at)))}
+                                    </p>
+                                </div>
+                                <button
+                                    class="ml-2 text-gray-400 hover:text-primary-100"
+                                    title="Wiederherstellen"
+                                    on:click=move |_| handle_restore(id)
+                                >
+                                    <Icon icon=restore_icon.into() class="w-5 h-5" />
+                                </button>
+                            </div>
+                        }
+                    }).collect_view()}
+                </div>
+
+                {move || (!error.get().is_empty()).then(|| view! {
+                    <p class="mt-4 text-sm text-primary-100">{error.get()}</p>
+                })}
+
+                <div class="flex justify-end mt-4 pt-4 border-t border-gray-600">
+                    <button
+                        class="flex items-center px-4 py-2 text-white hover:text-red-500 disabled:opacity-50"
+                        prop:disabled=move || entries.get().is_empty()
+                        on:click=handle_purge
+                    >
+                        <Icon icon=trash_icon.into() class="w-5 h-5 mr-2" />
+                        "Papierkorb leeren"
+                    </button>
+                </div>
+            </div>
+        </div>
+    }
+}
@@ -0,0 +1,549 @@
+use crate::{app::invoke, components::icons::Icon};
+use leptos::*;
+use serde::{Deserialize, Serialize};
+
+#[derive(Clone, Default, Deserialize, Serialize, PartialEq)]
+struct PaymentCard {
+    id: i32,
+    title: String,
+    holder: String,
+    number: String,
+    expiry: String,
+    cvc: String,
+}
+
+#[derive(Clone, Default, Deserialize, Serialize, PartialEq)]
+struct Identity {
+    id: i32,
+    title: String,
+    name: String,
+    address: String,
+    phone: String,
+}
+
+#[derive(Serialize)]
+struct PaymentCardArgs {
+    card: PaymentCard,
+}
+
+#[derive(Serialize)]
+struct IdentityArgs {
+    identity: Identity,
+}
+
+#[derive(Serialize)]
+struct DeleteArgs {
+    id: i32,
+}
+
+/// Show only the last four digits of a card number.
+fn mask_card_number(number: &str) -> String {
+    let last = number.len().saturating_sub(4);
+    format!("•••• {}", &number[last..])
+}
+
+/// Group the digits of a card number in blocks of four.
+fn format_card_number(number: &str) -> String {
+    number
+        .as_bytes()
+        .chunks(4)
+        .map(|chunk| String::from_utf8_lossy(chunk).to_string())
+        .collect::<Vec<_>>()
+        .join(" ")
+}
+
+fn show_error(set_error: WriteSignal<String>, response: wasm_bindgen::JsValue) {
+    set_error.set(
+        response
+            .as_string()
+            .unwrap_or("Unbekannter Fehler".to_string()),
+    );
+}
+
+#[component]
+pub fn Wallet() -> impl IntoView {
+    let (cards, set_cards) = create_signal(Vec::<PaymentCard>::new());
+    let (identities, set_identities) = create_signal(Vec::<Identity>::new());
+    let (revealed_cards, set_revealed_cards) = create_signal(Vec::<i32>::new());
+    let (revealed_identities, set_revealed_identities) = create_signal(Vec::<i32>::new());
+    let (card_modal, set_card_modal) = create_signal(None::<PaymentCard>);
+    let (identity_modal, set_identity_modal) = create_signal(None::<Identity>);
+    let (error, set_error) = create_signal(String::new());
+
+    let card_icon = create_memo(move |_| "identification");
+    let plus_icon = create_memo(move |_| "plus");
+    let eye_icon = create_memo(move |_| "eye");
+    let pencil_icon = create_memo(move |_| "pencil-square");
+    let trash_icon = create_memo(move |_| "trash");
+
+    let load_items = move || {
+        spawn_local(async move {
+            let response = invoke("get_payment_cards", wasm_bindgen::JsValue::NULL).await;
+            match serde_wasm_bindgen::from_value::<Vec<PaymentCard>>(response.clone()) {
+                Ok(loaded) => set_cards.set(loaded),
+                Err(_) => show_error(set_error, response),
+            }
+            let response = invoke("get_identities", wasm_bindgen::JsValue::NULL).await;
+            match serde_wasm_bindgen::from_value::<Vec<Identity>>(response.clone()) {
+                Ok(loaded) => set_identities.set(loaded),
+                Err(_) => show_error(set_error, response),
+            }
+        });
+    };
+    load_items();
+
+    let handle_delete = move |command: &'static str, id: i32| {
+        spawn_local(async move {
+            let args = serde_wasm_bindgen::to_value(&DeleteArgs { id }).unwrap();
+            let response = invoke(command, args).await;
+            if response.is_null() || response.is_undefined() {
+                set_error.set(String::new());
+                load_items();
+            } else {
+                show_error(set_error, response);
+            }
+        });
+    };
+
+    let toggle = |set_revealed: WriteSignal<Vec<i32>>, id: i32| {
+        set_revealed.update(|revealed| {
+            if revealed.contains(&id) {
+                revealed.retain(|r| *r != id);
+            } else {
+                revealed.push(id);
+            }
+        });
+    };
+
+    view! {
+        <div class="h-full overflow-y-auto space-y-8">
+            {move || (!error.get().is_empty()).then(|| view! {
+                <div class="text-primary-100 text-sm text-center">{error.get()}</div>
+            })}
+
+            <section>
+                <div class="flex justify-between items-center mb-4">
+                    <h2 class="text-xl font-bold bg-gradient-primary bg-clip-text text-transparent flex items-center">
+                        <Icon icon=card_icon.into() class="w-8 h-8 mr-2 text-primary-100" />
+                        "Zahlungskarten"
+                    </h2>
+                    <button
+                        class="bg-gradient-primary text-white font-bold px-4 py-2 rounded hover:opacity-90 flex items-center"
+                        on:click=move |_| set_card_modal.set(Some(PaymentCard::default()))
+                    >
+                        <Icon icon=plus_icon.into() class="w-5 h-5 mr-2" />
+                        "Karte hinzufügen"
+                    </button>
+                </div>
+                <table class="w-full">
+                    <thead class="bg-background">
+                        <tr class="border-b border-gray-600">
+                            <th class="text-left p-4 text-gray-400 font-medium">"Bezeichnung"</th>
+                            <th class="text-left p-4 text-gray-400 font-medium">"Karteninhaber"</th>
+                            <th class="text-left p-4 text-gray-400 font-medium">"Kartennummer"</th>
+                            <th class="text-left p-4 text-gray-400 font-medium">"Gültig bis"</th>
+                            <th class="text-left p-4 text-gray-400 font-medium">"CVC"</th>
+                            <th class="text-right p-4 text-gray-400 font-medium w-32">"Aktionen"</th>
+                        </tr>
+                    </thead>
+                    <tbody class="divide-y divide-gray-600">
+                        {move || cards.get().into_iter().map(|card| {
+                            let id = card.id;
+                            let is_revealed = move || revealed_cards.with(|r| r.contains(&id));
+                            let edited = card.clone();
+                            let number = card.number.clone();
+                            let cvc = card.cvc.clone();
+                            view! {
+                                <tr class="hover:bg-background">
+                                    <td class="p-4 text-white">{card.title}</td>
+                                    <td class="p-4 text-white">{card.holder}</td>
+                                    <td class="p-4 text-white whitespace-nowrap font-mono">
+                                        {move || if is_revealed() {
+                                            format_card_number(&number)
+                                        } else {
+                                            mask_card_number(&number)
+                                        }}
+                                    </td>
+                                    <td class="p-4 text-white">{card.expiry}</td>
+                                    <td class="p-4 text-white font-mono">
+                                        {move || match (is_revealed(), cvc.is_empty()) {
+                                            (_, true) => String::new(),
+                                            (true, false) => cvc.clone(),
+                                            (false, false) => "•••".to_string(),
+                                        }}
+                                    </td>
+                                    <td class="p-4">
+                                        <div class="flex justify-end space-x-2">
+                                            <button
+                                                class="text-gray-400 hover:text-primary-100"
+                                                title="Anzeigen"
+                                                on:click=move |_| toggle(set_revealed_cards, id)
+                                            >
+                                                <Icon icon=eye_icon.into() class="w-5 h-5" />
+                                            </button>
+                                            <button
+                                                class="text-gray-400 hover:text-primary-100"
+                                                title="Bearbeiten"
+                                                on:click=move |_| set_card_modal.set(Some(edited.clone()))
+                                            >
+                                                <Icon icon=pencil_icon.into() class="w-5 h-5" />
+                                            </button>
+                                            <button
+                                                class="text-gray-400 hover:text-red-500"
+                                                title="Löschen"
+                                                on:click=move |_| handle_delete("delete_payment_card", id)
+                                            >
+                                                <Icon icon=trash_icon.into() class="w-5 h-5" />
+                                            </button>
+                                        </div>
+                                    </td>
+                                </tr>
+                            }
+                        }).collect_view()}
+                    </tbody>
+                </table>
+                {move || cards.with(|cards| cards.is_empty()).then(|| view! {
+                    <p class="text-gray-400 text-sm p-4">"Noch keine Zahlungskarten gespeichert."</p>
+                })}
+            </section>
+
+            <section>
+                <div class="flex justify-between items-center mb-4">
+                    <h2 class="text-xl font-bold bg-gradient-primary bg-clip-text text-transparent flex items-center">
+                        <Icon icon=card_icon.into() class="w-8 h-8 mr-2 text-primary-100" />
+                        "Identitäten"
+                    </h2>
+                    <button
+                        class="bg-gradient-primary text-white font-bold px-4 py-2 rounded hover:opacity-90 flex items-center"
+                        on:click=move |_| set_identity_modal.set(Some(Identity::default()))
+                    >
+                        <Icon icon=plus_icon.into() class="w-5 h-5 mr-2" />
+                        "Identität hinzufügen"
+                    </button>
+                </div>
+                <table class="w-full">
+                    <thead class="bg-background">
+                        <tr class="border-b border-gray-600">
+                            <th class="text-left p-4 text-gray-400 font-medium">"Bezeichnung"</th>
+                            <th class="text-left p-4 text-gray-400 font-medium">"Name"</th>
+                            <th class="text-left p-4 text-gray-400 font-medium">"Adresse"</th>
+                            <th class="text-left p-4 text-gray-400 font-medium">"Telefon"</th>
+                            <th class="text-right p-4 text-gray-400 font-medium w-32">"Aktionen"</th>
+                        </tr>
+                    </thead>
+                    <tbody class="divide-y divide-gray-600">
+                        {move || identities.get().into_iter().map(|identity| {
+                            let id = identity.id;
+                            let is_revealed = move || revealed_identities.with(|r| r.contains(&id));
+                            let edited = identity.clone();
+                            let address = identity.address.clone();
+                            let phone = identity.phone.clone();
+                            view! {
+                                <tr class="hover:bg-background">
+                                    <td class="p-4 text-white">{identity.title}</td>
+                                    <td class="p-4 text-white">{identity.name}</td>
+                                    <td class="p-4 text-white whitespace-pre-wrap">
+                                        {move || if is_revealed() || address.is_empty() {
+                                            address.clone()
+                                        } else {
+                                            "••••••••".to_string()
+                                        }}
+                                    </td>
+                                    <td class="p-4 text-white whitespace-nowrap">
+                                        {move || if is_revealed() || phone.is_empty() {
+                                            phone.clone()
+                                        } else {
+                                            "••••••••".to_string()
+                                        }}
+                                    </td>
+                                    <td class="p-4">
+                                        <div class="flex justify-end space-x-2">
+                                            <button
+                                                class="text-gray-400 hover:text-primary-100"
+                                                title="Anzeigen"
+                                                on:click=move |_| toggle(set_revealed_identities, id)
+                                            >
+                                                <Icon icon=eye_icon.into() class="w-5 h-5" />
+                                            </button>
+                                            <button
+                                                class="text-gray-400 hover:text-primary-100"
+                                                title="Bearbeiten"
+                                                on:click=move |_| set_identity_modal.set(Some(edited.clone()))
+                                            >
+                                                <Icon icon=pencil_icon.into() class="w-5 h-5" />
+                                            </button>
+                                            <button
+                                                class="text-gray-400 hover:text-red-500"
+                                                title="Löschen"
+                                                on:click=move |_| handle_delete("delete_identity", id)
+                                            >
+                                                <Icon icon=trash_icon.into() class="w-5 h-5" />
+                                            </button>
+                                        </div>
+                                    </td>
+                                </tr>
+                            }
+                        }).collect_view()}
+                    </tbody>
+                </table>
+                {move || identities.with(|identities| identities.is_empty()).then(|| view! {
+                    <p class="text-gray-400 text-sm p-4">"Noch keine Identitäten gespeichert."</p>
+                })}
+            </section>
+
+            {move || card_modal.get().map(|card| view! {
+                <PaymentCardModal
+                    card=card
+                    on_close=move |_| set_card_modal.set(None)
+                    on_saved=move |_| {
+                        set_card_modal.set(None);
+                        load_items();
+                    }
+                />
+            })}
+            {move || identity_modal.get().map(|identity| view! {
+                <IdentityModal
+                    identity=identity
+                    on_close=move |_| set_identity_modal.set(None)
+                    on_saved=move |_| {
+                        set_identity_modal.set(None);
+                        load_items();
+                    }
+                />
+            })}
+        </div>
+    }
+}
+
+#[component]
+fn PaymentCardModal(
+    card: PaymentCard,
+    #[prop(into)] on_close: Callback<()>,
+    #[prop(into)] on_saved: Callback<()>,
+) -> impl IntoView {
+    let id = card.id;
+    let (title, set_title) = create_signal(card.title);
+    let (holder, set_holder) = create_signal(card.holder);
+    let (number, set_number) = create_signal(format_card_number(&card.number));
+    let (expiry, set_expiry) = create_signal(card.expiry);
+    let (cvc, set_cvc) = create_signal(card.cvc);
+    let (error, set_error) = create_signal(String::new());
+
+    let card_icon = create_memo(move |_| "identification");
+
+    let handle_save = move |_| {
+        spawn_local(async move {
+            let card = PaymentCard {
+                id,
+                title: title.get_untracked(),
+                holder: holder.get_untracked(),
+                number: number.get_untracked(),
+                expiry: expiry.get_untracked(),
+                cvc: cvc.get_untracked(),
+            };
+            let command = if id == 0 {
+                "add_payment_card"
+            } else {
+                "update_payment_card"
+            };
+            let args = serde_wasm_bindgen::to_value(&PaymentCardArgs { card }).unwrap();
+            let response = invoke(command, args).await;
+            if response.as_string().is_some() {
+                show_error(set_error, response);
+            } else {
+                on_saved.call(());
+            }
+        });
+    };
+
+    view! {
+        <div class="fixed inset-0 bg-black bg-opacity-50 flex items-center justify-center z-50">
+            <div class="bg-background-card rounded-lg p-6 w-full max-w-md">
+                <h2 class="text-xl font-bold mb-4 bg-gradient-primary bg-clip-text text-transparent flex items-center">
+                    <Icon icon=card_icon.into() class="w-8 h-8 mr-2 text-primary-100" />
+                    {if id == 0 { "Karte hinzufügen" } else { "Karte bearbeiten" }}
+                </h2>
+                <div class="space-y-4">
+                    <div>
+                        <label class="block text-white text-sm font-bold mb-2">"Bezeichnung"</label>
+                        <input
+                            type="text"
+                            class="w-full p-2 rounded bg-background text-white border border-gray-600 focus:outline-none focus:border-primary-100"
+                            placeholder="z.B. Kreditkarte privat"
+                            on:input=move |ev| set_title.set(event_target_value(&ev))
+                            prop:value=title
+                        />
+                    </div>
+                    <div>
+                        <label class="block text-white text-sm font-bold mb-2">"Karteninhaber"</label>
+                        <input
+                            type="text"
+                            class="w-full p-2 rounded bg-background text-white border border-gray-600 focus:outline-none focus:border-primary-100"
+                            on:input=move |ev| set_holder.set(event_target_value(&ev))
+                            prop:value=holder
+                        />
+                    </div>
+                    <div>
+                        <label class="block text-white text-sm font-bold mb-2">"Kartennummer"</label>
+                        <input
+                            type="text"
+                            inputmode="numeric"
+                            autocomplete="off"
+                            class="w-full p-2 rounded bg-background text-white font-mono border border-gray-600 focus:outline-none focus:border-primary-100"
+                            on:input=move |ev| set_number.set(event_target_value(&ev))
+                            prop:value=number
+                        />
+                    </div>
+                    <div class="flex gap-4">
+                        <div class="flex-1">
+                            <label class="block text-white text-sm font-bold mb-2">"Gültig bis"</label>
+                            <input
+                                type="text"
+                                class="w-full p-2 rounded bg-background text-white border border-gray-600 focus:outline-none focus:border-primary-100"
+                                placeholder="MM/JJ"
+                                on:input=move |ev| set_expiry.set(event_target_value(&ev))
+                                prop:value=expiry
+                            />
+                        </div>
+                        <div class="flex-1">
+                            <label class="block text-white text-sm font-bold mb-2">"CVC"</label>
+                            <input
+                                type="password"
+                                inputmode="numeric"
+                                autocomplete="off"
+                                class="w-full p-2 rounded bg-background text-white border border-gray-600 focus:outline-none focus:border-primary-100"
+                                on:input=move |ev| set_cvc.set(event_target_value(&ev))
+                                prop:value=cvc
+                            />
+                        </div>
+                    </div>
+                </div>
+                {move || (!error.get().is_empty()).then(|| view! {
+                    <div class="text-primary-100 text-sm text-center mt-4">{error.get()}</div>
+                })}
+                <div class="flex justify-end gap-2 mt-6">
+                    <button
+                        class="border border-gray-600 text-gray-300 px-4 py-2 rounded hover:bg-background transition-colors"
+                        on:click=move |_| on_close.call(())
+                    >
+                        "Abbrechen"
+                    </button>
+                    <button
+                        class="bg-gradient-primary text-white font-bold px-4 py-2 rounded hover:opacity-90 disabled:opacity-50 disabled:cursor-not-allowed"
+                        prop:disabled=move || title.get().trim().is_empty() || number.get().trim().is_empty()
+                        on:click=handle_save
+                    >
+                        "Speichern"
+                    </button>
+                </div>
+            </div>
+        </div>
+    }
+}
+
+#[component]
+fn IdentityModal(
+    identity: Identity,
+    #[prop(into)] on_close: Callback<()>,
+    #[prop(into)] on_saved: Callback<()>,
+) -> impl IntoView {
+    let id = identity.id;
+    let (title, set_title) = create_signal(identity.title);
+    let (name, set_name) = create_signal(identity.name);
+    let (address, set_address) = create_signal(identity.address);
+    let (phone, set_phone) = create_signal(identity.phone);
+    let (error, set_error) = create_signal(String::new());
+
+    let identity_icon = create_memo(move |_| "identification");
+
+    let handle_save = move |_| {
+        spawn_local(async move {
+            let identity = Identity {
+                id,
+                title: title.get_untracked(),
+                name: name.get_untracked(),
+                address: address.get_untracked(),
+                phone: phone.get_untracked(),
+            };
+            let command = if id == 0 {
+                "add_identity"
+            } else {
+                "update_identity"
+            };
+            let args = serde_wasm_bindgen::to_value(&IdentityArgs { identity }).unwrap();
+            let response = invoke(command, args).await;
+            if response.as_string().is_some() {
+                show_error(set_error, response);
+            } else {
+                on_saved.call(());
+            }
+        });
+    };
+
+    view! {
+        <div class="fixed inset-0 bg-black bg-opacity-50 flex items-center justify-center z-50">
+            <div class="bg-background-card rounded-lg p-6 w-full max-w-md">
+                <h2 class="text-xl font-bold mb-4 bg-gradient-primary bg-clip-text text-transparent flex items-center">
+                    <Icon icon=identity_icon.into() class="w-8 h-8 mr-2 text-primary-100" />
+                    {if id == 0 { "Identität hinzufügen" } else { "Identität bearbeiten" }}
+                </h2>
+                <div class="space-y-4">
+                    <div>
+                        <label class="block text-white text-sm font-bold mb-2">"Bezeichnung"</label>
+                        <input
+                            type="text"
+                            class="w-full p-2 rounded bg-background text-white border border-gray-600 focus:outline-none focus:border-primary-100"
+                            placeholder="z.B. Privat"
+                            on:input=move |ev| set_title.set(event_target_value(&ev))
+                            prop:value=title
+                        />
+                    </div>
+                    <div>
+                        <label class="block text-white text-sm font-bold mb-2">"Name"</label>
+                        <input
+                            type="text"
+                            class="w-full p-2 rounded bg-background text-white border border-gray-600 focus:outline-none focus:border-primary-100"
+                            on:input=move |ev| set_name.set(event_target_value(&ev))
+                            prop:value=name
+                        />
+                    </div>
+                    <div>
+                        <label class="block text-white text-sm font-bold mb-2">"Adresse"</label>
+                        <textarea
+                            class="w-full h-24 p-2 rounded bg-background text-white border border-gray-600 focus:outline-none focus:border-primary-100"
+                            on:input=move |ev| set_address.set(event_target_value(&ev))
+                            prop:value=address
+                        />
+                    </div>
+                    <div>
+                        <label class="block text-white text-sm font-bold mb-2">"Telefon"</label>
+                        <input
+                            type="tel"
+                            class="w-full p-2 rounded bg-background text-white border border-gray-600 focus:outline-none focus:border-primary-100"
+                            on:input=move |ev| set_phone.set(event_target_value(&ev))
+                            prop:value=phone
+                        />
+                    </div>
+                </div>
+                {move || (!error.get().is_empty()).then(|| view! {
+                    <div class="text-primary-100 text-sm text-center mt-4">{error.get()}</div>
+                })}
+                <div class="flex justify-end gap-2 mt-6">
+                    <button
+                        class="border border-gray-600 text-gray-300 px-4 py-2 rounded hover:bg-background transition-colors"
+                        on:click=move |_| on_close.call(())
+                    >
+                        "Abbrechen"
+                    </button>
+                    <button
+                        class="bg-gradient-primary text-white font-bold px-4 py-2 rounded hover:opacity-90 disabled:opacity-50 disabled:cursor-not-allowed"
+                        prop:disabled=move || title.get().trim().is_empty() || name.get().trim().is_empty()
+                        on:click=handle_save
+                    >
+                        "Speichern"
+                    </button>
+                </div>
+            </div>
+        </div>
+    }
+}
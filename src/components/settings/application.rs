@@ -3,37 +3,153 @@ use leptos::{ev::SubmitEvent, *};
 use serde::{Deserialize, Serialize};
 
 use crate::components::icons::Icon;
+use crate::components::password_generator::{
+    load_generator_defaults, GeneratorOptions, GeneratorOptionsForm,
+};
 use crate::components::utils::LoadingSpinner;
 
 #[derive(Deserialize)]
 struct AppSettingsResponse {
     default_length: usize,
+    max_notes_length: usize,
+    trash_retention_days: u32,
+    max_attachment_kb: u64,
+    attachment_extensions: Vec<String>,
+    attachment_scanner: Option<String>,
+}
+
+#[derive(Deserialize)]
+struct ViewSettingsResponse {
+    default_tab: String,
+    default_sort: String,
+    double_click: String,
+    locale: String,
 }
 
 #[derive(Serialize)]
 pub struct AppSettingsArgs {
     #[serde(rename = "defaultLength")]
     default_length: usize,
+    #[serde(rename = "maxNotesLength")]
+    max_notes_length: usize,
+    #[serde(rename = "trashRetentionDays")]
+    trash_retention_days: u32,
+    #[serde(rename = "maxAttachmentKb")]
+    max_attachment_kb: u64,
+    #[serde(rename = "attachmentExtensions")]
+    attachment_extensions: Vec<String>,
+    #[serde(rename = "attachmentScanner")]
+    attachment_scanner: Option<String>,
+    #[serde(rename = "defaultTab")]
+    default_tab: String,
+    #[serde(rename = "defaultSort")]
+    default_sort: String,
+    #[serde(rename = "doubleClick")]
+    double_click: String,
+    #[serde(rename = "generatorOptions")]
+    generator_options: GeneratorOptions,
+    #[serde(rename = "generatorHistorySize")]
+    generator_history_size: usize,
+    locale: String,
 }
 
 #[component]
 pub fn ApplicationSettings() -> impl IntoView {
     let (password_length, set_password_length) = create_signal(16);
+    let (generator_options, set_generator_options) = create_signal(GeneratorOptions::default());
+    let (history_size, set_history_size) = create_signal(0);
+    let (max_notes_length, set_max_notes_length) = create_signal(1000);
+    let (trash_retention_days, set_trash_retention_days) = create_signal(30);
+    let (max_attachment_kb, set_max_attachment_kb) = create_signal(5120u64);
+    let (attachment_extensions, set_attachment_extensions) = create_signal(String::new());
+    let (attachment_scanner, set_attachment_scanner) = create_signal(String::new());
+    let (default_tab, set_default_tab) = create_signal("dashboard".to_string());
+    let (default_sort, set_default_sort) = create_signal("service_asc".to_string());
+    let (double_click, set_double_click) = create_signal("copy_password".to_string());
+    let (locale, set_locale) = create_signal("de".to_string());
     let (error, set_error) = create_signal(String::new());
     let (is_loading, set_is_loading) = create_signal(true);
 
     let lock_icon = create_memo(move |_| "lock-closed");
     let palette_icon = create_memo(move |_| "paint-brush");
     let language_icon = create_memo(move |_| "language");
+    let transfer_icon = create_memo(move |_| "arrow-up-down");
+    let note_icon = create_memo(move |_| "document-text");
+    let view_icon = create_memo(move |_| "computer-desktop");
+
+    let load_view_settings = move || {
+        spawn_local(async move {
+            let response = invoke("get_view_settings", wasm_bindgen::JsValue::NULL).await;
+            if let Ok(settings) = serde_wasm_bindgen::from_value::<ViewSettingsResponse>(response) {
+                set_default_tab.set(settings.default_tab);
+                set_default_sort.set(settings.default_sort);
+                set_double_click.set(settings.double_click);
+                set_locale.set(settings.locale);
+            }
+        });
+    };
+
+    load_view_settings();
+    let export_icon = create_memo(move |_| "arrow-up-tray");
+    let import_icon = create_memo(move |_| "arrow-down-tray");
 
     spawn_local(async move {
+        if let Some(defaults) = load_generator_defaults().await {
+            set_generator_options.set(defaults.options);
+            set_history_size.set(defaults.history_size);
+        }
+
         let response = invoke("get_default_generator_length", wasm_bindgen::JsValue::NULL).await;
         if let Ok(settings) = serde_wasm_bindgen::from_value::<AppSettingsResponse>(response) {
             set_password_length.set(settings.default_length);
+            set_max_notes_length.set(settings.max_notes_length);
+            set_trash_retention_days.set(settings.trash_retention_days);
+            set_max_attachment_kb.set(settings.max_attachment_kb);
+            set_attachment_extensions.set(settings.attachment_extensions.join(", "));
+            set_attachment_scanner.set(settings.attachment_scanner.unwrap_or_default());
             set_is_loading.set(false);
         }
     });
 
+    let handle_export_settings = move |_| {
+        spawn_local(async move {
+            let response = invoke("export_settings", wasm_bindgen::JsValue::NULL).await;
+            match serde_wasm_bindgen::from_value::<String>(response) {
+                Ok(path) => set_error.set(format!("Einstellungen exportiert nach {}", path)),
+                Err(_) => {
+                    set_error.set("Einstellungen konnten nicht exportiert werden".to_string())
+                }
+            }
+        });
+    };
+
+    let handle_import_settings = move |_| {
+        spawn_local(async move {
+            let response = invoke("import_settings", wasm_bindgen::JsValue::NULL).await;
+            if serde_wasm_bindgen::from_value::<()>(response).is_err() {
+                set_error.set("Einstellungen konnten nicht importiert werden".to_string());
+                return;
+            }
+
+            let response =
+                invoke("get_default_generator_length", wasm_bindgen::JsValue::NULL).await;
+            if let Ok(settings) = serde_wasm_bindgen::from_value::<AppSettingsResponse>(response) {
+                set_password_length.set(settings.default_length);
+                set_max_notes_length.set(settings.max_notes_length);
+                set_trash_retention_days.set(settings.trash_retention_days);
+                set_max_attachment_kb.set(settings.max_attachment_kb);
+                set_attachment_extensions.set(settings.attachment_extensions.join(", "));
+                set_attachment_scanner.set(settings.attachment_scanner.unwrap_or_default());
+            }
+            if let Some(defaults) = load_generator_defaults().await {
+                set_generator_options.set(defaults.options);
+                set_history_size.set(defaults.history_size);
+            }
+            load_view_settings();
+            set_error.set("Einstellungen importiert".to_string());
+        });
+    };
+
     let handle_save_settings = move |ev: SubmitEvent| {
         ev.prevent_default();
 
@@ -41,6 +157,22 @@ pub fn ApplicationSettings() -> impl IntoView {
 
         let args = serde_wasm_bindgen::to_value(&AppSettingsArgs {
             default_length: password_length.get(),
+            max_notes_length: max_notes_length.get(),
+            trash_retention_days: trash_retention_days.get(),
+            max_attachment_kb: max_attachment_kb.get(),
+            attachment_extensions: attachment_extensions
+                .get()
+                .split(',')
+                .map(|extension| extension.trim().to_string())
+                .filter(|extension| !extension.is_empty())
+                .collect(),
+            attachment_scanner: Some(attachment_scanner.get()),
+            default_tab: default_tab.get(),
+            default_sort: default_sort.get(),
+            double_click: double_click.get(),
+            generator_options: generator_options.get(),
+            generator_history_size: history_size.get(),
+            locale: locale.get(),
         })
         .unwrap();
         spawn_local(async move {
@@ -113,6 +245,196 @@ pub fn ApplicationSettings() -> impl IntoView {
                                         </div>
                                         <p class="mt-1 text-sm text-gray-400">"Legt die Standardlänge neu generierter Passwörter fest"</p>
                                     </div>
+
+                                    <GeneratorOptionsForm options=generator_options set_options=set_generator_options />
+                                    <p class="mt-1 text-sm text-gray-400">"Gilt für den Generator, neue Einträge und das Master-Passwort"</p>
+
+                                    <div>
+                                        <label class="block text-white text-sm font-bold mb-2">
+                                            "Verlauf generierter Passwörter"
+                                        </label>
+                                        <input
+                                            type="number"
+                                            min="0"
+                                            max="50"
+                                            class="w-full shadow appearance-none border border-gray-600 rounded py-2 px-3 bg-background text-white leading-tight focus:outline-none focus:border-primary-100"
+                                            on:input=move |ev| set_history_size.set(event_target_value(&ev).parse().unwrap_or(0))
+                                            prop:value=history_size
+                                        />
+                                        <p class="mt-1 text-sm text-gray-400">
+                                            "Anzahl der zuletzt generierten Passwörter, die verschlüsselt im Tresor aufbewahrt werden, falls du vergisst einen Eintrag zu speichern. 0 schaltet den Verlauf aus"
+                                        </p>
+                                    </div>
+                                </fieldset>
+
+                                <fieldset class="space-y-4">
+                                    <h3 class="text-lg font-semibold bg-gradient-primary bg-clip-text text-transparent mb-4">
+                                        <div class="flex items-center space-x-2">
+                                            <Icon icon=view_icon.into() class="w-5 h-5 text-primary-100" />
+                                            <span>"Ansicht"</span>
+                                        </div>
+                                    </h3>
+
+                                    <div class="grid grid-cols-2 gap-4">
+                                        <div>
+                                            <label class="block text-white text-sm font-bold mb-2">
+                                                "Start-Ansicht"
+                                            </label>
+                                            <select
+                                                class="w-full p-2 rounded bg-background text-white border border-gray-600 focus:border-primary-100 focus:outline-none"
+                                                on:change=move |ev| set_default_tab.set(event_target_value(&ev))
+                                                prop:value=default_tab
+                                            >
+                                                <option value="dashboard">"Dashboard"</option>
+                                                <option value="passwords">"Passwörter"</option>
+                                                <option value="notes">"Notizen"</option>
+                                                <option value="wallet">"Wallet"</option>
+                                                <option value="servers">"Server"</option>
+                                                <option value="generator">"Generator"</option>
+                                            </select>
+                                        </div>
+                                        <div>
+                                            <label class="block text-white text-sm font-bold mb-2">
+                                                "Sortierung"
+                                            </label>
+                                            <select
+                                                class="w-full p-2 rounded bg-background text-white border border-gray-600 focus:border-primary-100 focus:outline-none"
+                                                on:change=move |ev| set_default_sort.set(event_target_value(&ev))
+                                                prop:value=default_sort
+                                            >
+                                                <option value="service_asc">"Service (A-Z)"</option>
+                                                <option value="service_desc">"Service (Z-A)"</option>
+                                            </select>
+                                        </div>
+                                    </div>
+                                    <p class="mt-1 text-sm text-gray-400">"Legt fest, welche Ansicht und Sortierung nach dem Entsperren angezeigt wird"</p>
+
+                                    <div>
+                                        <label class="block text-white text-sm font-bold mb-2">
+                                            "Doppelklick auf einen Eintrag"
+                                        </label>
+                                        <select
+                                            class="w-full p-2 rounded bg-background text-white border border-gray-600 focus:border-primary-100 focus:outline-none"
+                                            on:change=move |ev| set_double_click.set(event_target_value(&ev))
+                                            prop:value=double_click
+                                        >
+                                            <option value="copy_password">"Passwort kopieren"</option>
+                                            <option value="copy_username">"Benutzername kopieren"</option>
+                                            <option value="open_detail">"Eintrag öffnen"</option>
+                                            <option value="open_url">"URL öffnen"</option>
+                                        </select>
+                                    </div>
+                                </fieldset>
+
+                                <fieldset class="space-y-4">
+                                    <h3 class="text-lg font-semibold bg-gradient-primary bg-clip-text text-transparent mb-4">
+                                        <div class="flex items-center space-x-2">
+                                            <Icon icon=note_icon.into() class="w-5 h-5 text-primary-100" />
+                                            <span>"Eintrags-Einstellungen"</span>
+                                        </div>
+                                    </h3>
+
+                                    <div>
+                                        <label class="block text-white text-sm font-bold mb-2">
+                                            "Maximale Länge der Notizen"
+                                        </label>
+                                        <input
+                                            type="number"
+                                            min="100"
+                                            max="100000"
+                                            class="w-full shadow appearance-none border border-gray-600 rounded py-2 px-3 bg-background text-white leading-tight focus:outline-none focus:border-primary-100"
+                                            on:input=move |ev| set_max_notes_length.set(event_target_value(&ev).parse().unwrap_or(1000))
+                                            prop:value=max_notes_length
+                                        />
+                                        <p class="mt-1 text-sm text-gray-400">"Anzahl der Zeichen, die die Notizen eines Eintrags höchstens enthalten dürfen"</p>
+                                    </div>
+
+                                    <div>
+                                        <label class="block text-white text-sm font-bold mb-2">
+                                            "Aufbewahrung im Papierkorb (Tage)"
+                                        </label>
+                                        <input
+                                            type="number"
+                                            min="0"
+                                            max="3650"
+                                            class="w-full shadow appearance-none border border-gray-600 rounded py-2 px-3 bg-background text-white leading-tight focus:outline-none focus:border-primary-100"
+                                            on:input=move |ev| set_trash_retention_days.set(event_target_value(&ev).parse().unwrap_or(30))
+                                            prop:value=trash_retention_days
+                                        />
+                                        <p class="mt-1 text-sm text-gray-400">"Gelöschte Einträge werden danach beim Anmelden endgültig entfernt, 0 behält sie bis zum Leeren des Papierkorbs"</p>
+                                    </div>
+
+                                    <div>
+                                        <label class="block text-white text-sm font-bold mb-2">
+                                            "Maximale Größe von Anhängen (KiB)"
+                                        </label>
+                                        <input
+                                            type="number"
+                                            min="1"
+                                            max="102400"
+                                            class="w-full shadow appearance-none border border-gray-600 rounded py-2 px-3 bg-background text-white leading-tight focus:outline-none focus:border-primary-100"
+                                            on:input=move |ev| set_max_attachment_kb.set(event_target_value(&ev).parse().unwrap_or(5120))
+                                            prop:value=max_attachment_kb
+                                        />
+                                    </div>
+
+                                    <div>
+                                        <label class="block text-white text-sm font-bold mb-2">
+                                            "Erlaubte Dateitypen für Anhänge"
+                                        </label>
+                                        <input
+                                            type="text"
+                                            placeholder="pdf, txt, png"
+                                            class="w-full shadow appearance-none border border-gray-600 rounded py-2 px-3 bg-background text-white leading-tight focus:outline-none focus:border-primary-100"
+                                            on:input=move |ev| set_attachment_extensions.set(event_target_value(&ev))
+                                            prop:value=attachment_extensions
+                                        />
+                                        <p class="mt-1 text-sm text-gray-400">"Dateiendungen durch Kommas getrennt, leer erlaubt alle Dateien"</p>
+                                    </div>
+
+                                    <div>
+                                        <label class="block text-white text-sm font-bold mb-2">
+                                            "Virenscanner für Anhänge"
+                                        </label>
+                                        <input
+                                            type="text"
+                                            placeholder="clamscan --no-summary"
+                                            class="w-full shadow appearance-none border border-gray-600 rounded py-2 px-3 bg-background text-white font-mono leading-tight focus:outline-none focus:border-primary-100"
+                                            on:input=move |ev| set_attachment_scanner.set(event_target_value(&ev))
+                                            prop:value=attachment_scanner
+                                        />
+                                        <p class="mt-1 text-sm text-gray-400">"Befehl, der jede Datei vor dem Anhängen prüft. Der Pfad wird als letztes Argument übergeben, ein Fehlercode lehnt die Datei ab. Leer lässt die Prüfung aus"</p>
+                                    </div>
+                                </fieldset>
+
+                                <fieldset class="space-y-4">
+                                    <h3 class="text-lg font-semibold bg-gradient-primary bg-clip-text text-transparent mb-4">
+                                        <div class="flex items-center space-x-2">
+                                            <Icon icon=transfer_icon.into() class="w-5 h-5 text-primary-100" />
+                                            <span>"Einstellungen übertragen"</span>
+                                        </div>
+                                    </h3>
+                                    <div class="grid grid-cols-2 gap-4">
+                                        <button
+                                            type="button"
+                                            class="flex items-center justify-center space-x-2 bg-background border border-primary-100 hover:bg-primary-400/10 text-white py-2 px-4 rounded focus:outline-none transition-all duration-200"
+                                            on:click=handle_export_settings
+                                        >
+                                            <Icon icon=export_icon.into() class="w-5 h-5 text-primary-100" />
+                                            <span>"Exportieren"</span>
+                                        </button>
+                                        <button
+                                            type="button"
+                                            class="flex items-center justify-center space-x-2 bg-background border border-primary-100 hover:bg-primary-400/10 text-white py-2 px-4 rounded focus:outline-none transition-all duration-200"
+                                            on:click=handle_import_settings
+                                        >
+                                            <Icon icon=import_icon.into() class="w-5 h-5 text-primary-100" />
+                                            <span>"Importieren"</span>
+                                        </button>
+                                    </div>
+                                    <p class="mt-1 text-sm text-gray-400">
+                                        "Überträgt die Einstellungen auf einen anderen Rechner. Passwörter, private Schlüssel und Speicherorte werden nicht exportiert"
+                                    </p>
                                 </fieldset>
 
                                 <fieldset class="space-y-4 opacity-50">
@@ -127,16 +449,30 @@ pub fn ApplicationSettings() -> impl IntoView {
                                     </div>
                                 </fieldset>
 
-                                <fieldset class="space-y-4 opacity-50">
+                                <fieldset class="space-y-4">
                                     <h3 class="text-lg font-semibold bg-gradient-primary bg-clip-text text-transparent mb-4">
                                         <div class="flex items-center space-x-2">
                                             <Icon icon=language_icon.into() class="w-5 h-5 text-primary-100" />
                                             <span>"Sprach-Einstellungen"</span>
                                         </div>
                                     </h3>
-                                    <div class="flex items-center space-x-2 text-white">
-                                        <span class="text-sm italic">"Coming Soon"</span>
+                                    <div>
+                                        <label class="block text-white text-sm font-bold mb-2">
+                                            "Sortierung und Suche"
+                                        </label>
+                                        <select
+                                            class="w-full p-2 rounded bg-background text-white border border-gray-600 focus:border-primary-100 focus:outline-none"
+                                            on:change=move |ev| set_locale.set(event_target_value(&ev))
+                                            prop:value=locale
+                                        >
+                                            <option value="de">"Deutsch"</option>
+                                            <option value="de-u-co-phonebk">"Deutsch (Telefonbuch, ä wie ae)"</option>
+                                            <option value="en">"Englisch"</option>
+                                            <option value="fr">"Französisch"</option>
+                                            <option value="sv">"Schwedisch"</option>
+                                        </select>
                                     </div>
+                                    <p class="mt-1 text-sm text-gray-400">"Legt fest, wie Einträge, Ordner und Tags sortiert werden und welche Buchstaben bei der Suche als gleich gelten"</p>
                                 </fieldset>
 
                                 <div class="flex justify-end pt-4 border-t border-gray-600">
@@ -0,0 +1,88 @@
+use crate::app::invoke;
+use leptos::*;
+use serde::Serialize;
+
+use crate::components::icons::Icon;
+
+#[derive(Serialize)]
+struct ExportAuditLogArgs {
+    format: String,
+    from: Option<String>,
+    to: Option<String>,
+}
+
+#[component]
+pub fn AuditLogExport() -> impl IntoView {
+    let (format, set_format) = create_signal("csv".to_string());
+    let (from, set_from) = create_signal(String::new());
+    let (to, set_to) = create_signal(String::new());
+    let (status, set_status) = create_signal(String::new());
+
+    let export_icon = create_memo(move |_| "arrow-down-tray");
+
+    let handle_export = move |_| {
+        spawn_local(async move {
+            let non_empty = |value: String| (!value.is_empty()).then_some(value);
+            let args = serde_wasm_bindgen::to_value(&ExportAuditLogArgs {
+                format: format.get(),
+                from: non_empty(from.get()),
+                to: non_empty(to.get()),
+            })
+            .unwrap();
+
+            let response = invoke("export_audit_log", args).await;
+            match serde_wasm_bindgen::from_value::<String>(response) {
+                Ok(path) => set_status.set(format!("Audit-Log exportiert nach {}", path)),
+                Err(_) => set_status.set("Audit-Log konnte nicht exportiert werden".to_string()),
+            }
+        });
+    };
+
+    view! {
+        <div class="space-y-4">
+            <label class="block text-white text-sm font-bold">
+                "Audit-Log exportieren"
+            </label>
+            <div class="grid grid-cols-3 gap-4">
+                <select
+                    class="p-2 rounded bg-background text-white border border-gray-600 focus:border-primary-100 focus:outline-none"
+                    on:change=move |ev| set_format.set(event_target_value(&ev))
+                    prop:value=format
+                >
+                    <option value="csv">"CSV"</option>
+                    <option value="json">"JSON"</option>
+                </select>
+                <input
+                    type="date"
+                    class="p-2 rounded bg-background text-white border border-gray-600 focus:border-primary-100 focus:outline-none"
+                    on:input=move |ev| set_from.set(event_target_value(&ev))
+                    prop:value=from
+                />
+                <input
+                    type="date"
+                    class="p-2 rounded bg-background text-white border border-gray-600 focus:border-primary-100 focus:outline-none"
+                    on:input=move |ev| set_to.set(event_target_value(&ev))
+                    prop:value=to
+                />
+            </div>
+            <p class="mt-1 text-sm text-gray-400">
+                "Exportiert Anmeldungen, Exporte und Änderungen im gewählten Zeitraum in den Export-Pfad. Ohne Datum wird der gesamte Verlauf exportiert"
+            </p>
+            <button
+                type="button"
+                class="w-full flex items-center justify-center space-x-2 bg-background border border-primary-100 hover:bg-primary-400/10 text-white py-2 px-4 rounded focus:outline-none transition-all duration-200"
+                on:click=handle_export
+            >
+                <Icon icon=export_icon.into() class="w-5 h-5 text-primary-100" />
+                <span>"Audit-Log exportieren"</span>
+            </button>
+            {move || (!status.get().is_empty()).then(||
+                view! {
+                    <div class="text-primary-100 text-sm text-center">
+                        {status.get()}
+                    </div>
+                }
+            )}
+        </div>
+    }
+}
@@ -0,0 +1,172 @@
+use crate::app::invoke;
+use leptos::*;
+use serde::Deserialize;
+
+use crate::components::icons::Icon;
+
+#[derive(Clone, Deserialize)]
+struct BackupInfo {
+    name: String,
+    incremental: bool,
+    encrypted: bool,
+    size: u64,
+    uncompressed_size: Option<u64>,
+    app_version: Option<String>,
+}
+
+#[derive(Clone, Deserialize)]
+struct BackupList {
+    backups: Vec<BackupInfo>,
+    total_size: u64,
+    quota_bytes: Option<u64>,
+    quota_exceeded: bool,
+}
+
+pub(super) fn format_size(bytes: u64) -> String {
+    const UNITS: [&str; 4] = ["B", "KB", "MB", "GB"];
+    let mut size = bytes as f64;
+    let mut unit = 0;
+    while size >= 1024.0 && unit < UNITS.len() - 1 {
+        size /= 1024.0;
+        unit += 1;
+    }
+    if unit == 0 {
+        format!("{} {}", bytes, UNITS[0])
+    } else {
+        format!("{:.1} {}", size, UNITS[unit])
+    }
+}
+
+#[component]
+pub fn BackupList(#[prop(into)] on_open_snapshot: Callback<String>) -> impl IntoView {
+    let (list, set_list) = create_signal(None::<BackupList>);
+    let (status, set_status) = create_signal(String::new());
+
+    let warning_icon = create_memo(move |_| "exclamation-triangle");
+    let trash_icon = create_memo(move |_| "trash");
+    let refresh_icon = create_memo(move |_| "arrow-path");
+    let clock_icon = create_memo(move |_| "clock");
+
+    let load_backups = move || {
+        spawn_local(async move {
+            let response = invoke("list_backups", wasm_bindgen::JsValue::NULL).await;
+            if let Ok(backups) = serde_wasm_bindgen::from_value::<BackupList>(response) {
+                set_list.set(Some(backups));
+            }
+        });
+    };
+
+    load_backups();
+
+    let handle_prune = move |_| {
+        spawn_local(async move {
+            let response = invoke("prune_backups", wasm_bindgen::JsValue::NULL).await;
+            match serde_wasm_bindgen::from_value::<usize>(response) {
+                Ok(removed) => {
+                    set_status.set(format!("{} Backup-Dateien entfernt", removed));
+                    load_backups();
+                }
+                Err(_) => {
+                    set_status.set("Backups konnten nicht entfernt werden".to_string());
+                }
+            }
+        });
+    };
+
+    view! {
+        <div class="space-y-4">
+            <div class="flex items-center justify-between">
+                <label class="block text-white text-sm font-bold">
+                    "Vorhandene Backups"
+                </label>
+                <button
+                    type="button"
+                    class="p-1 hover:bg-primary-400/10 rounded transition-all duration-200"
+                    on:click=move |_| load_backups()
+                >
+                    <Icon icon=refresh_icon.into() class="w-4 h-4 text-primary-100" />
+                </button>
+            </div>
+
+            {move || list.get().map(|list| {
+                let quota_text = match list.quota_bytes {
+                    Some(quota) => format!("{} von {} belegt", format_size(list.total_size), format_size(quota)),
+                    None => format!("{} belegt", format_size(list.total_size)),
+                };
+
+                view! {
+                    <div class="space-y-2">
+                        {list.quota_exceeded.then(|| view! {
+                            <div class="flex items-center justify-between p-2 rounded border border-yellow-500 bg-yellow-500/10">
+                                <div class="flex items-center space-x-2">
+                                    <Icon icon=warning_icon.into() class="w-5 h-5 text-yellow-500" />
+                                    <span class="text-sm text-white">
+                                        "Der Backup-Ordner überschreitet das Speicherlimit"
+                                    </span>
+                                </div>
+                                <button
+                                    type="button"
+                                    class="flex items-center space-x-1 px-2 py-1 text-sm text-white border border-primary-100 rounded hover:bg-primary-400/10 transition-all duration-200"
+                                    on:click=handle_prune
+                                >
+                                    <Icon icon=trash_icon.into() class="w-4 h-4 text-primary-100" />
+                                    <span>"Alte Backups entfernen"</span>
+                                </button>
+                            </div>
+                        })}
+
+                        <p class="text-sm text-gray-400">{quota_text}</p>
+
+                        {if list.backups.is_empty() {
+                            view! {
+                                <p class="text-sm text-gray-400 italic">"Keine Backups vorhanden"</p>
+                            }.into_view()
+                        } else {
+                            list.backups.into_iter().map(|backup| {
+                                let uncompressed = backup
+                                    .uncompressed_size
+                                    .map(format_size)
+                                    .unwrap_or_else(|| "–".to_string());
+                                let name = backup.name.clone();
+                                view! {
+                                    <div class="flex items-center justify-between p-2 rounded border border-gray-600 text-sm">
+                                        <div class="flex flex-col min-w-0">
+                                            <span class="text-white truncate">{backup.name}</span>
+                                            <span class="text-gray-400 text-xs">
+                                                {if backup.incremental { "Inkrementell" } else { "Vollständig" }}
+                                                {backup.encrypted.then_some(" · Empfänger-verschlüsselt")}
+                                                {backup.app_version.map(|version| format!(" · Version {}", version))}
+                                            </span>
+                                        </div>
+                                        <div class="flex items-center space-x-3 flex-shrink-0">
+                                            <div class="flex flex-col items-end text-xs text-gray-400">
+                                                <span>{format_size(backup.size)}</span>
+                                                <span>"Entpackt: "{uncompressed}</span>
+                                            </div>
+                                            <button
+                                                type="button"
+                                                class="p-1 hover:bg-primary-400/10 rounded transition-all duration-200"
+                                                title="Als Snapshot öffnen"
+                                                on:click=move |_| on_open_snapshot.call(name.clone())
+                                            >
+                                                <Icon icon=clock_icon.into() class="w-4 h-4 text-primary-100" />
+                                            </button>
+                                        </div>
+                                    </div>
+                                }
+                            }).collect_view()
+                        }}
+                    </div>
+                }
+            })}
+
+            {move || (!status.get().is_empty()).then(||
+                view! {
+                    <div class="text-primary-100 text-sm text-center">
+                        {status.get()}
+                    </div>
+                }
+            )}
+        </div>
+    }
+}
@@ -0,0 +1,132 @@
+use crate::app::invoke;
+use leptos::*;
+use serde::{Deserialize, Serialize};
+
+use crate::components::icons::Icon;
+
+#[derive(Serialize)]
+struct SandboxRestoreArgs {
+    #[serde(rename = "masterPass")]
+    master_pass: String,
+}
+
+#[derive(Clone, Deserialize)]
+struct BackupPreviewResponse {
+    entries: Vec<BackupPreviewEntry>,
+    app_version: Option<String>,
+}
+
+#[derive(Clone, Deserialize)]
+struct BackupPreviewEntry {
+    service: String,
+    username: String,
+    url: String,
+    updated_at: String,
+}
+
+fn format_date(timestamp: &str) -> String {
+    let date = web_sys::js_sys::Date::new(&wasm_bindgen::JsValue::from_str(timestamp));
+    format!(
+        "{:02}.{:02}.{}",
+        date.get_date(),
+        date.get_month() + 1,
+        date.get_full_year()
+    )
+}
+
+#[component]
+pub fn BackupPreview(master_pass: String, #[prop(into)] on_close: Callback<()>) -> impl IntoView {
+    let (preview, set_preview) = create_signal(None::<BackupPreviewResponse>);
+    let (error, set_error) = create_signal(String::new());
+    let (is_loading, set_is_loading) = create_signal(true);
+
+    let exit_icon = create_memo(move |_| "x-mark");
+    let preview_icon = create_memo(move |_| "eye");
+
+    spawn_local(async move {
+        let args = serde_wasm_bindgen::to_value(&SandboxRestoreArgs { master_pass }).unwrap();
+        let response = invoke("sandbox_restore", args).await;
+        match serde_wasm_bindgen::from_value::<BackupPreviewResponse>(response.clone()) {
+            Ok(value) => set_preview.set(Some(value)),
+            Err(_) => set_error.set(format!(
+                "Backup konnte nicht geprüft werden: {}",
+                response
+                    .as_string()
+                    .unwrap_or("Unbekannter Fehler".to_string())
+            )),
+        }
+        set_is_loading.set(false);
+    });
+
+    view! {
+        <div class="fixed inset-0 bg-black bg-opacity-50 flex items-center justify-center">
+            <div class="bg-background-card rounded-lg p-6 w-full max-w-2xl">
+                <div class="flex justify-between items-center mb-6">
+                    <h2 class="text-xl font-bold bg-gradient-primary bg-clip-text text-transparent flex items-center">
+                        <div class="flex items-center">
+                            <Icon icon=preview_icon.into() class="w-8 h-8 mr-2 text-primary-100" />
+                            "Backup-Inhalt"
+                        </div>
+                    </h2>
+                    <button
+                        class="text-gray-400 hover:text-white"
+                        on:click=move |_| on_close.call(())
+                    >
+                        <Icon icon=exit_icon.into() class="w-5 h-5" />
+                    </button>
+                </div>
+
+                {move || {
+                    if is_loading.get() {
+                        view! {
+                            <div class="flex justify-center items-center h-32">
+                                <div class="animate-spin rounded-full h-8 w-8 border-b-2 border-primary-100"></div>
+                            </div>
+                        }.into_view()
+                    } else if !error.get().is_empty() {
+                        view! {
+                            <div class="text-primary-100 text-sm text-center">{error.get()}</div>
+                        }.into_view()
+                    } else {
+                        preview.get().map(|preview| view! {
+                            <div class="space-y-4">
+                                <p class="text-sm text-gray-400">
+                                    {format!(
+                                        "{} Einträge{}. Der Inhalt wurde nur zur Ansicht wiederhergestellt, deine aktuelle Datenbank bleibt unverändert.",
+                                        preview.entries.len(),
+                                        preview
+                                            .app_version
+                                            .map(|version| format!(", erstellt mit Version {}", version))
+                                            .unwrap_or_default()
+                                    )}
+                                </p>
+                                <div class="max-h-96 overflow-y-auto border border-gray-700 rounded-lg">
+                                    <table class="w-full text-sm text-left">
+                                        <thead class="text-gray-400 border-b border-gray-700">
+                                            <tr>
+                                                <th class="px-4 py-2">"Service"</th>
+                                                <th class="px-4 py-2">"Benutzername"</th>
+                                                <th class="px-4 py-2">"URL"</th>
+                                                <th class="px-4 py-2">"Geändert"</th>
+                                            </tr>
+                                        </thead>
+                                        <tbody>
+                                            {preview.entries.into_iter().map(|entry| view! {
+                                                <tr class="border-b border-gray-700 last:border-0 text-white">
+                                                    <td class="px-4 py-2">{entry.service}</td>
+                                                    <td class="px-4 py-2">{entry.username}</td>
+                                                    <td class="px-4 py-2 text-gray-400 truncate">{entry.url}</td>
+                                                    <td class="px-4 py-2 text-gray-400">{format_date(&entry.updated_at)}</td>
+                                                </tr>
+                                            }).collect_view()}
+                                        </tbody>
+                                    </table>
+                                </div>
+                            </div>
+                        }).into_view()
+                    }
+                }}
+            </div>
+        </div>
+    }
+}
@@ -0,0 +1,204 @@
+use crate::app::invoke;
+use leptos::*;
+use serde::{Deserialize, Serialize};
+
+use crate::components::icons::Icon;
+
+#[derive(Clone, Deserialize)]
+struct BackupRecipient {
+    name: String,
+    public_key: String,
+}
+
+#[derive(Serialize)]
+struct AddRecipientArgs<'a> {
+    name: &'a str,
+    #[serde(rename = "publicKey")]
+    public_key: &'a str,
+}
+
+#[derive(Serialize)]
+struct RemoveRecipientArgs<'a> {
+    #[serde(rename = "publicKey")]
+    public_key: &'a str,
+}
+
+#[derive(Serialize)]
+struct GenerateKeyPairArgs<'a> {
+    name: &'a str,
+}
+
+#[component]
+pub fn BackupRecipients() -> impl IntoView {
+    let (recipients, set_recipients) = create_signal(Vec::<BackupRecipient>::new());
+    let (name, set_name) = create_signal(String::new());
+    let (public_key, set_public_key) = create_signal(String::new());
+    let (status, set_status) = create_signal(String::new());
+
+    let key_icon = create_memo(move |_| "key");
+    let plus_icon = create_memo(move |_| "plus");
+    let trash_icon = create_memo(move |_| "trash");
+
+    let load_recipients = move || {
+        spawn_local(async move {
+            let response = invoke("get_backup_recipients", wasm_bindgen::JsValue::NULL).await;
+            if let Ok(list) = serde_wasm_bindgen::from_value::<Vec<BackupRecipient>>(response) {
+                set_recipients.set(list);
+            }
+        });
+    };
+
+    load_recipients();
+
+    let handle_add = move |_| {
+        if name.get().is_empty() || public_key.get().is_empty() {
+            set_status.set("Name und öffentlicher Schlüssel sind erforderlich".to_string());
+            return;
+        }
+
+        let args = serde_wasm_bindgen::to_value(&AddRecipientArgs {
+            name: &name.get(),
+            public_key: &public_key.get(),
+        })
+        .unwrap();
+        spawn_local(async move {
+            let response = invoke("add_backup_recipient", args).await;
+            match serde_wasm_bindgen::from_value::<()>(response) {
+                Ok(_) => {
+                    set_name.set(String::new());
+                    set_public_key.set(String::new());
+                    set_status.set("Empfänger hinzugefügt".to_string());
+                    load_recipients();
+                }
+                Err(_) => {
+                    set_status.set("Empfänger konnte nicht hinzugefügt werden".to_string());
+                }
+            }
+        });
+    };
+
+    let handle_generate = move |_| {
+        let key_name = if name.get().is_empty() {
+            "Eigener Schlüssel".to_string()
+        } else {
+            name.get()
+        };
+
+        let args = serde_wasm_bindgen::to_value(&GenerateKeyPairArgs { name: &key_name }).unwrap();
+        spawn_local(async move {
+            let response = invoke("generate_backup_keypair", args).await;
+            match serde_wasm_bindgen::from_value::<String>(response) {
+                Ok(_) => {
+                    set_name.set(String::new());
+                    set_status.set("Schlüsselpaar erzeugt".to_string());
+                    load_recipients();
+                }
+                Err(_) => {
+                    set_status.set("Schlüsselpaar konnte nicht erzeugt werden".to_string());
+                }
+            }
+        });
+    };
+
+    let handle_remove = move |key: String| {
+        let args = serde_wasm_bindgen::to_value(&RemoveRecipientArgs { public_key: &key }).unwrap();
+        spawn_local(async move {
+            let response = invoke("remove_backup_recipient", args).await;
+            if serde_wasm_bindgen::from_value::<()>(response).is_ok() {
+                load_recipients();
+            }
+        });
+    };
+
+    view! {
+        <div class="space-y-4">
+            <div>
+                <label class="block text-white text-sm font-bold mb-2">
+                    "Backup-Empfänger"
+                </label>
+                <p class="text-sm text-gray-400">
+                    "Backups werden zusätzlich mit den öffentlichen Schlüsseln dieser Empfänger verschlüsselt"
+                </p>
+            </div>
+
+            <div class="space-y-2">
+                {move || {
+                    let list = recipients.get();
+                    if list.is_empty() {
+                        view! {
+                            <p class="text-sm text-gray-400 italic">
+                                "Keine Empfänger hinterlegt"
+                            </p>
+                        }.into_view()
+                    } else {
+                        list.into_iter().map(|recipient| {
+                            let key = recipient.public_key.clone();
+                            view! {
+                                <div class="flex items-center justify-between p-2 rounded border border-gray-600">
+                                    <div class="flex items-center space-x-2 min-w-0">
+                                        <Icon icon=key_icon.into() class="w-4 h-4 text-primary-100 flex-shrink-0" />
+                                        <span class="text-white text-sm">{recipient.name}</span>
+                                        <span class="text-gray-400 text-xs font-mono truncate">
+                                            {recipient.public_key}
+                                        </span>
+                                    </div>
+                                    <button
+                                        type="button"
+                                        class="p-1 hover:bg-primary-400/10 rounded transition-all duration-200"
+                                        on:click=move |_| handle_remove(key.clone())
+                                    >
+                                        <Icon icon=trash_icon.into() class="w-4 h-4 text-primary-100" />
+                                    </button>
+                                </div>
+                            }
+                        }).collect_view()
+                    }
+                }}
+            </div>
+
+            <div class="grid grid-cols-2 gap-2">
+                <input
+                    type="text"
+                    class="shadow appearance-none border border-gray-600 rounded py-2 px-3 bg-background text-white leading-tight focus:outline-none focus:border-primary-100"
+                    placeholder="Name"
+                    on:input=move |ev| set_name.set(event_target_value(&ev))
+                    prop:value=name
+                />
+                <input
+                    type="text"
+                    class="shadow appearance-none border border-gray-600 rounded py-2 px-3 bg-background text-white leading-tight focus:outline-none focus:border-primary-100"
+                    placeholder="kcpub1..."
+                    on:input=move |ev| set_public_key.set(event_target_value(&ev))
+                    prop:value=public_key
+                />
+            </div>
+
+            <div class="grid grid-cols-2 gap-4">
+                <button
+                    type="button"
+                    class="flex items-center justify-center space-x-2 bg-background border border-primary-100 hover:bg-primary-400/10 text-white py-2 px-4 rounded focus:outline-none transition-all duration-200"
+                    on:click=handle_add
+                >
+                    <Icon icon=plus_icon.into() class="w-5 h-5 text-primary-100" />
+                    <span>"Empfänger hinzufügen"</span>
+                </button>
+                <button
+                    type="button"
+                    class="flex items-center justify-center space-x-2 bg-background border border-primary-100 hover:bg-primary-400/10 text-white py-2 px-4 rounded focus:outline-none transition-all duration-200"
+                    on:click=handle_generate
+                >
+                    <Icon icon=key_icon.into() class="w-5 h-5 text-primary-100" />
+                    <span>"Schlüsselpaar erzeugen"</span>
+                </button>
+            </div>
+
+            {move || (!status.get().is_empty()).then(||
+                view! {
+                    <div class="text-primary-100 text-sm text-center">
+                        {status.get()}
+                    </div>
+                }
+            )}
+        </div>
+    }
+}
@@ -0,0 +1,65 @@
+use crate::app::invoke;
+use leptos::*;
+use serde::Serialize;
+
+use crate::components::icons::Icon;
+
+#[derive(Serialize)]
+struct DetectCloudSyncArgs {
+    path: String,
+}
+
+#[component]
+pub fn CloudSyncWarning(
+    #[prop(into)] path: Signal<String>,
+    sync_safe_mode: ReadSignal<bool>,
+    set_sync_safe_mode: WriteSignal<bool>,
+) -> impl IntoView {
+    let (provider, set_provider) = create_signal(None::<String>);
+
+    let warning_icon = create_memo(move |_| "exclamation-triangle");
+
+    create_effect(move |_| {
+        let path = path.get();
+        spawn_local(async move {
+            let args = serde_wasm_bindgen::to_value(&DetectCloudSyncArgs { path }).unwrap();
+            let response = invoke("detect_cloud_sync", args).await;
+            set_provider.set(
+                serde_wasm_bindgen::from_value::<Option<String>>(response).unwrap_or_default(),
+            );
+        });
+    });
+
+    view! {
+        {move || (provider.get().is_some() || sync_safe_mode.get()).then(|| view! {
+            <div class="p-4 border border-primary-100 rounded-lg space-y-3">
+                {move || provider.get().map(|provider| view! {
+                    <div class="flex items-start space-x-2 text-primary-100">
+                        <Icon icon=warning_icon.into() class="w-5 h-5 flex-shrink-0" />
+                        <p class="text-sm">
+                            {format!(
+                                "Der Speicherort liegt in einem {}-Ordner. Synchronisiert der Client die Datenbank und ihr Änderungsprotokoll getrennt, kann die Datenbank beschädigt werden.",
+                                provider
+                            )}
+                        </p>
+                    </div>
+                })}
+                <div class="flex items-center space-x-2">
+                    <input
+                        type="checkbox"
+                        id="sync-safe-mode"
+                        class="w-4 h-4 bg-background border-gray-600 rounded focus:ring-primary-100"
+                        on:change=move |ev| set_sync_safe_mode.set(event_target_checked(&ev))
+                        prop:checked=sync_safe_mode
+                    />
+                    <label for="sync-safe-mode" class="text-white text-sm font-bold">
+                        "Sync-sicheren Modus verwenden"
+                    </label>
+                </div>
+                <p class="text-sm text-gray-400">
+                    "Schreibt Änderungen direkt in die Datenbankdatei, damit immer eine vollständige Datei synchronisiert wird"
+                </p>
+            </div>
+        })}
+    }
+}
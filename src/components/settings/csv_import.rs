@@ -0,0 +1,248 @@
+use crate::{app::invoke, components::icons::Icon};
+use leptos::*;
+use serde::{Deserialize, Serialize};
+
+#[derive(Clone, Default, Serialize, Deserialize, PartialEq)]
+struct ColumnMapping {
+    service: Option<usize>,
+    username: Option<usize>,
+    password: Option<usize>,
+    url: Option<usize>,
+    notes: Option<usize>,
+}
+
+#[derive(Clone, Serialize, Deserialize, PartialEq)]
+struct CsvLayout {
+    profile: String,
+    delimiter: u8,
+    has_header: bool,
+    columns: usize,
+    mapping: ColumnMapping,
+}
+
+#[derive(Clone, Deserialize, PartialEq)]
+struct CsvImportPreview {
+    path: String,
+    layout: CsvLayout,
+    headers: Vec<String>,
+    rows: Vec<Vec<String>>,
+}
+
+#[derive(Serialize)]
+struct PreviewCsvArgs {
+    path: Option<String>,
+    profile: Option<String>,
+}
+
+#[derive(Serialize)]
+struct ImportCsvArgs {
+    format: String,
+    path: String,
+    layout: CsvLayout,
+}
+
+/// The entry fields a column can be mapped to, with their label.
+const FIELDS: [(&str, &str); 5] = [
+    ("service", "Dienst"),
+    ("username", "Benutzername"),
+    ("password", "Passwort"),
+    ("url", "URL"),
+    ("notes", "Notizen"),
+];
+
+fn mapped_column(mapping: &ColumnMapping, field: &str) -> Option<usize> {
+    match field {
+        "service" => mapping.service,
+        "username" => mapping.username,
+        "password" => mapping.password,
+        "url" => mapping.url,
+        _ => mapping.notes,
+    }
+}
+
+fn set_mapped_column(mapping: &mut ColumnMapping, field: &str, column: Option<usize>) {
+    match field {
+        "service" => mapping.service = column,
+        "username" => mapping.username = column,
+        "password" => mapping.password = column,
+        "url" => mapping.url = column,
+        _ => mapping.notes = column,
+    }
+}
+
+#[component]
+pub fn CsvImport(
+    #[prop(into)] on_close: Callback<()>,
+    #[prop(optional, into)] on_imported: Option<Callback<()>>,
+) -> impl IntoView {
+    let (preview, set_preview) = create_signal(None::<CsvImportPreview>);
+    let (status, set_status) = create_signal(String::new());
+    let (is_importing, set_is_importing) = create_signal(false);
+
+    let exit_icon = create_memo(move |_| "x-mark");
+    let import_icon = create_memo(move |_| "arrow-up-tray");
+
+    let load_preview = move |path: Option<String>, profile: Option<String>| {
+        spawn_local(async move {
+            let args = serde_wasm_bindgen::to_value(&PreviewCsvArgs { path, profile }).unwrap();
+            let response = invoke("preview_csv_import", args).await;
+            match serde_wasm_bindgen::from_value::<Option<CsvImportPreview>>(response.clone()) {
+                Ok(Some(loaded)) => {
+                    set_preview.set(Some(loaded));
+                    set_status.set(String::new());
+                }
+                Ok(None) => on_close.call(()),
+                Err(_) => set_status.set(format!(
+                    "Vorschau fehlgeschlagen: {}",
+                    response
+                        .as_string()
+                        .unwrap_or("Unbekannter Fehler".to_string())
+                )),
+            }
+        });
+    };
+
+    load_preview(None, None);
+
+    let handle_import = move |_| {
+        let Some(current) = preview.get_untracked() else {
+            return;
+        };
+        set_is_importing.set(true);
+        spawn_local(async move {
+            let args = serde_wasm_bindgen::to_value(&ImportCsvArgs {
+                format: "csv".to_string(),
+                path: current.path,
+                layout: current.layout,
+            })
+            .unwrap();
+            let response = invoke("import_passwords", args).await;
+            match serde_wasm_bindgen::from_value::<String>(response.clone()) {
+                Ok(summary) => {
+                    set_status.set(summary);
+                    if let Some(on_imported) = on_imported {
+                        on_imported.call(());
+                    }
+                }
+                Err(_) => set_status.set(format!(
+                    "Import fehlgeschlagen: {}",
+                    response
+                        .as_string()
+                        .unwrap_or("Unbekannter Fehler".to_string())
+                )),
+            }
+            set_is_importing.set(false);
+        });
+    };
+
+    view! {
+        <div class="fixed inset-0 bg-black bg-opacity-50 flex items-center justify-center z-50">
+            <div class="bg-background-card rounded-lg p-6 w-full max-w-2xl">
+                <div class="flex justify-between items-center mb-6">
+                    <h2 class="text-xl font-bold bg-gradient-primary bg-clip-text text-transparent flex items-center">
+                        <Icon icon=import_icon.into() class="w-8 h-8 mr-2 text-primary-100" />
+                        "CSV importieren"
+                    </h2>
+                    <button
+                        class="text-gray-400 hover:text-white"
+                        on:click=move |_| on_close.call(())
+                    >
+                        <Icon icon=exit_icon.into() class="w-5 h-5" />
+                    </button>
+                </div>
+
+                {move || match preview.get() {
+                    None => view! {
+                        <p class="text-gray-400 text-center">"Datei wird gelesen..."</p>
+                    }.into_view(),
+                    Some(current) => {
+                        let path = current.path.clone();
+                        let columns = current.headers.clone();
+                        view! {
+                            <div class="space-y-4">
+                                <p class="text-sm text-gray-400 truncate">{current.path.clone()}</p>
+                                <select
+                                    class="w-full p-2 rounded bg-background text-white border border-gray-600 focus:border-primary-100 focus:outline-none"
+                                    on:change=move |ev| load_preview(Some(path.clone()), Some(event_target_value(&ev)))
+                                    prop:value=current.layout.profile.clone()
+                                >
+                                    <option value="karncrypt">"KarnCrypt-Export (durch ; getrennt)"</option>
+                                    <option value="chrome">"Google Chrome"</option>
+                                    <option value="firefox">"Mozilla Firefox"</option>
+                                    <option value="edge">"Microsoft Edge"</option>
+                                </select>
+
+                                <div class="grid grid-cols-2 gap-2">
+                                    {FIELDS.into_iter().map(|(field, label)| {
+                                        let columns = columns.clone();
+                                        view! {
+                                            <label class="text-sm text-gray-400 self-center">{label}</label>
+                                            <select
+                                                class="w-full p-2 rounded bg-background text-white border border-gray-600 focus:border-primary-100 focus:outline-none"
+                                                on:change=move |ev| {
+                                                    let column = event_target_value(&ev).parse::<usize>().ok();
+                                                    set_preview.update(|preview| {
+                                                        if let Some(preview) = preview {
+                                                            set_mapped_column(&mut preview.layout.mapping, field, column);
+                                                        }
+                                                    });
+                                                }
+                                                prop:value=mapped_column(&current.layout.mapping, field)
+                                                    .map(|c| c.to_string())
+                                                    .unwrap_or_default()
+                                            >
+                                                <option value="">
+                                                    {if field == "service" { "Aus der URL übernehmen" } else { "Nicht importieren" }}
+                                                </option>
+                                                {columns.into_iter().enumerate().map(|(index, header)| view! {
+                                                    <option value=index.to_string()>{header}</option>
+                                                }).collect_view()}
+                                            </select>
+                                        }
+                                    }).collect_view()}
+                                </div>
+
+                                <div class="overflow-x-auto max-h-48">
+                                    <table class="w-full text-sm text-left">
+                                        <thead>
+                                            <tr class="text-gray-400 border-b border-gray-600">
+                                                {current.headers.iter().map(|header| view! {
+                                                    <th class="p-2 whitespace-nowrap">{header.clone()}</th>
+                                                }).collect_view()}
+                                            </tr>
+                                        </thead>
+                                        <tbody>
+                                            {current.rows.iter().map(|row| view! {
+                                                <tr class="border-b border-gray-700 text-white">
+                                                    {row.iter().map(|cell| view! {
+                                                        <td class="p-2 whitespace-nowrap">{cell.clone()}</td>
+                                                    }).collect_view()}
+                                                </tr>
+                                            }).collect_view()}
+                                        </tbody>
+                                    </table>
+                                </div>
+                            </div>
+                        }.into_view()
+                    }
+                }}
+
+                {move || (!status.get().is_empty()).then(|| view! {
+                    <div class="mt-4 text-primary-100 text-sm text-center">{status.get()}</div>
+                })}
+
+                <button
+                    class="mt-4 w-full flex items-center justify-center space-x-2 bg-gradient-primary text-white font-bold py-2 px-4 rounded focus:outline-none hover:opacity-90 disabled:opacity-50 disabled:cursor-not-allowed"
+                    prop:disabled=move || {
+                        is_importing.get()
+                            || preview.with(|p| p.as_ref().is_none_or(|p| p.layout.mapping.password.is_none()))
+                    }
+                    on:click=handle_import
+                >
+                    <Icon icon=import_icon.into() class="w-5 h-5" />
+                    <span>"Importieren"</span>
+                </button>
+            </div>
+        </div>
+    }
+}
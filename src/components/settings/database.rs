@@ -1,6 +1,16 @@
 use crate::{
     app::invoke,
-    components::password_manager::{DialogAction, PasswordDialog},
+    components::{
+        password_manager::{
+            load_category_suggestions, CategoryReview, CategorySuggestion, DialogAction,
+            PasswordDialog,
+        },
+        settings::{
+            AuditLogExport, BackupList, BackupPreview, BackupRecipients, CloudSyncWarning,
+            CsvImport, DatabaseStats, EncryptedExport, FileImport, OtpImport, PasteImport,
+            VaultDeletion,
+        },
+    },
 };
 use leptos::{ev::SubmitEvent, *};
 use serde::{Deserialize, Serialize};
@@ -24,6 +34,11 @@ struct DatabaseSettingsResponse {
     max_backups: usize,
     backup_path: String,
     export_path: String,
+    incremental_backups: bool,
+    full_backup_every: usize,
+    backup_quota_mb: u64,
+    backup_file_name: String,
+    sync_safe_mode: bool,
 }
 
 #[derive(Serialize)]
@@ -42,6 +57,23 @@ struct DatabaseSettingsArgs<'a> {
     backup_path: &'a str,
     #[serde(rename = "exportPath")]
     export_path: &'a str,
+    #[serde(rename = "incrementalBackups")]
+    incremental_backups: bool,
+    #[serde(rename = "fullBackupEvery")]
+    full_backup_every: usize,
+    #[serde(rename = "backupQuotaMb")]
+    backup_quota_mb: u64,
+    #[serde(rename = "backupFileName")]
+    backup_file_name: &'a str,
+    #[serde(rename = "syncSafeMode")]
+    sync_safe_mode: bool,
+}
+
+#[derive(Serialize)]
+struct OpenSnapshotArgs {
+    name: String,
+    #[serde(rename = "masterPass")]
+    master_pass: String,
 }
 
 #[component]
@@ -53,19 +85,40 @@ pub fn DatabaseSettings() -> impl IntoView {
     let (export_path, set_export_path) = create_signal(String::new());
     let (max_backup, set_max_backup) = create_signal(0);
     let (backup_path, set_backup_path) = create_signal(String::new());
+    let (incremental_backups, set_incremental_backups) = create_signal(false);
+    let (full_backup_every, set_full_backup_every) = create_signal(6);
+    let (backup_quota_mb, set_backup_quota_mb) = create_signal(0u64);
+    let (backup_file_name, set_backup_file_name) = create_signal(String::new());
+    let (sync_safe_mode, set_sync_safe_mode) = create_signal(false);
     let (error, set_error) = create_signal(String::new());
-    let (im_export_status, set_im_export_status) = create_signal(String::new());
+    let (show_paste_import, set_show_paste_import) = create_signal(false);
+    let (show_file_import, set_show_file_import) = create_signal(false);
+    let (show_csv_import, set_show_csv_import) = create_signal(false);
+    let (show_otp_import, set_show_otp_import) = create_signal(false);
+    let (imported, set_imported) = create_signal(false);
+    let (category_suggestions, set_category_suggestions) =
+        create_signal(None::<Vec<CategorySuggestion>>);
+    let (show_encrypted_export, set_show_encrypted_export) = create_signal(false);
+    let (show_vault_deletion, set_show_vault_deletion) = create_signal(false);
+    let (export_filter, set_export_filter) = create_signal("all");
     let (show_password_dialog, set_show_password_dialog) = create_signal(false);
     let (current_action, set_current_action) = create_signal(DialogAction::Verify);
+    let (preview_master_pass, set_preview_master_pass) = create_signal(None::<String>);
     let (is_loading, set_is_loading) = create_signal(true);
 
     let folder_icon = create_memo(move |_| "folder-open");
     let backup_icon = create_memo(move |_| "archive-box");
     let restore_icon = create_memo(move |_| "archive-box-arrow-down");
+    let preview_icon = create_memo(move |_| "eye");
     let chevron_down_icon = create_memo(move |_| "chevron-down");
     let database_icon = create_memo(move |_| "circle-stack");
     let import_icon = create_memo(move |_| "arrow-up-tray");
+    let paste_icon = create_memo(move |_| "clipboard");
+    let key_icon = create_memo(move |_| "key");
+    let phone_icon = create_memo(move |_| "device-phone-mobile");
     let export_icon = create_memo(move |_| "arrow-down-tray");
+    let encrypted_export_icon = create_memo(move |_| "lock-closed");
+    let trash_icon = create_memo(move |_| "trash");
 
     spawn_local(async move {
         let response = invoke("get_database_settings", wasm_bindgen::JsValue::NULL).await;
@@ -83,13 +136,31 @@ pub fn DatabaseSettings() -> impl IntoView {
             set_export_path.set(settings.export_path);
             set_max_backup.set(settings.max_backups);
             set_backup_path.set(settings.backup_path);
+            set_incremental_backups.set(settings.incremental_backups);
+            set_full_backup_every.set(settings.full_backup_every);
+            set_backup_quota_mb.set(settings.backup_quota_mb);
+            set_backup_file_name.set(settings.backup_file_name);
+            set_sync_safe_mode.set(settings.sync_safe_mode);
 
             set_is_loading.set(false);
         }
     });
 
+    let suggest_categories = move || {
+        if !imported.get_untracked() {
+            return;
+        }
+        set_imported.set(false);
+        spawn_local(async move {
+            let suggestions = load_category_suggestions(None).await;
+            if !suggestions.is_empty() {
+                set_category_suggestions.set(Some(suggestions));
+            }
+        });
+    };
+
     let handle_export = move |_| {
-        set_current_action.set(DialogAction::ExportPasswords);
+        set_current_action.set(DialogAction::ExportPasswords(export_filter.get()));
         set_show_password_dialog.set(true);
     };
 
@@ -103,6 +174,31 @@ pub fn DatabaseSettings() -> impl IntoView {
         set_show_password_dialog.set(true);
     };
 
+    let handle_preview_backup = move |_| {
+        set_current_action.set(DialogAction::PreviewBackup);
+        set_show_password_dialog.set(true);
+    };
+
+    let handle_open_snapshot = move |name: String| {
+        set_current_action.set(DialogAction::OpenSnapshot(name));
+        set_show_password_dialog.set(true);
+    };
+
+    let handle_master_password = move |master_pass: String| {
+        if let DialogAction::OpenSnapshot(name) = current_action.get_untracked() {
+            spawn_local(async move {
+                let args =
+                    serde_wasm_bindgen::to_value(&OpenSnapshotArgs { name, master_pass }).unwrap();
+                let response = invoke("open_snapshot", args).await;
+                if let Some(e) = response.as_string() {
+                    set_error.set(format!("Snapshot konnte nicht geöffnet werden: {}", e));
+                }
+            });
+        } else {
+            set_preview_master_pass.set(Some(master_pass));
+        }
+    };
+
     let handle_dialog_close = move |_| {
         set_show_password_dialog.set(false);
     };
@@ -141,6 +237,11 @@ pub fn DatabaseSettings() -> impl IntoView {
             max_backups: max_backup.get(),
             backup_path: &backup_path.get(),
             export_path: &export_path.get(),
+            incremental_backups: incremental_backups.get(),
+            full_backup_every: full_backup_every.get(),
+            backup_quota_mb: backup_quota_mb.get(),
+            backup_file_name: &backup_file_name.get(),
+            sync_safe_mode: sync_safe_mode.get(),
         })
         .unwrap();
         spawn_local(async move {
@@ -172,6 +273,8 @@ pub fn DatabaseSettings() -> impl IntoView {
                     } else {
                         view! {
                             <form class="space-y-8" on:submit=handle_save_settings>
+                                <DatabaseStats />
+
                                 {move || (!error.get().is_empty()).then(||
                                     view! {
                                         <div class="text-primary-100 text-sm text-center">
@@ -228,6 +331,12 @@ pub fn DatabaseSettings() -> impl IntoView {
                                             "Speicherort der Datenbank"
                                         </p>
                                     </div>
+
+                                    <CloudSyncWarning
+                                        path=db_path
+                                        sync_safe_mode=sync_safe_mode
+                                        set_sync_safe_mode=set_sync_safe_mode
+                                    />
                                 </fieldset>
 
                                 <fieldset class="space-y-4">
@@ -362,6 +471,88 @@ pub fn DatabaseSettings() -> impl IntoView {
                                         }
                                     )}
 
+                                    <div class="flex items-center space-x-3">
+                                        <input
+                                            type="checkbox"
+                                            id="incremental-backups"
+                                            class="w-4 h-4 bg-background border-gray-600 rounded focus:ring-primary-100"
+                                            on:change=move |ev| set_incremental_backups.set(event_target_checked(&ev))
+                                            prop:checked=incremental_backups
+                                        />
+                                        <label for="incremental-backups" class="text-white text-sm font-bold">
+                                            "Inkrementelle Backups"
+                                        </label>
+                                    </div>
+                                    <p class="text-sm text-gray-400">
+                                        "Sichert nur die Änderungen seit dem letzten vollständigen Backup"
+                                    </p>
+
+                                    {move || incremental_backups.get().then(||
+                                        view! {
+                                            <div>
+                                                <label class="block text-white text-sm font-bold mb-2">
+                                                    "Vollständiges Backup nach"
+                                                </label>
+                                                <input
+                                                    type="number"
+                                                    min="1"
+                                                    max="100"
+                                                    class="w-full shadow appearance-none border border-gray-600 rounded py-2 px-3 bg-background text-white leading-tight focus:outline-none focus:border-primary-100"
+                                                    on:input=move |ev| {
+                                                        if let Ok(value) = event_target_value(&ev).parse::<usize>() {
+                                                            set_full_backup_every.set(value);
+                                                        }
+                                                    }
+                                                    prop:value=move || full_backup_every.get().to_string()
+                                                />
+                                                <p class="mt-1 text-sm text-gray-400">
+                                                    "Anzahl inkrementeller Backups, bevor wieder ein vollständiges Backup erstellt wird"
+                                                </p>
+                                            </div>
+                                        }
+                                    )}
+
+                                    <div>
+                                        <label class="block text-white text-sm font-bold mb-2">
+                                            "Speicherlimit (MB)"
+                                        </label>
+                                        <input
+                                            type="number"
+                                            min="0"
+                                            class="w-full shadow appearance-none border border-gray-600 rounded py-2 px-3 bg-background text-white leading-tight focus:outline-none focus:border-primary-100"
+                                            on:input=move |ev| {
+                                                if let Ok(value) = event_target_value(&ev).parse::<u64>() {
+                                                    set_backup_quota_mb.set(value);
+                                                }
+                                            }
+                                            prop:value=move || backup_quota_mb.get().to_string()
+                                        />
+                                        <p class="mt-1 text-sm text-gray-400">
+                                            "Warnung, wenn der Backup-Ordner größer wird (0 = kein Limit)"
+                                        </p>
+                                    </div>
+
+                                    <div>
+                                        <label class="block text-white text-sm font-bold mb-2">
+                                            "Dateiname der Backups"
+                                        </label>
+                                        <input
+                                            type="text"
+                                            maxlength="64"
+                                            placeholder="backup"
+                                            class="w-full shadow appearance-none border border-gray-600 rounded py-2 px-3 bg-background text-white leading-tight focus:outline-none focus:border-primary-100"
+                                            on:input=move |ev| set_backup_file_name.set(event_target_value(&ev))
+                                            prop:value=backup_file_name
+                                        />
+                                        <p class="mt-1 text-sm text-gray-400">
+                                            "Platzhalter: {date}, {hostname} und {vault}. Datum und Uhrzeit werden immer angehängt, Backups mit anderem Namen bleiben unberührt"
+                                        </p>
+                                    </div>
+
+                                    <BackupList on_open_snapshot=handle_open_snapshot />
+
+                                    <BackupRecipients />
+
                                     <div class="grid grid-cols-2 gap-4 pt-2">
                                         <button
                                             type="button"
@@ -380,6 +571,14 @@ pub fn DatabaseSettings() -> impl IntoView {
                                             <span>"Backup wiederherstellen"</span>
                                         </button>
                                     </div>
+                                    <button
+                                        type="button"
+                                        class="w-full flex items-center justify-center space-x-2 bg-background border border-gray-600 hover:border-primary-100 text-white py-2 px-4 rounded focus:outline-none transition-all duration-200"
+                                        on:click=handle_preview_backup
+                                    >
+                                        <Icon icon=preview_icon.into() class="w-5 h-5 text-primary-100" />
+                                        <span>"Backup-Inhalt prüfen"</span>
+                                    </button>
                                 </fieldset>
 
                                 <fieldset class="space-y-4">
@@ -414,23 +613,33 @@ pub fn DatabaseSettings() -> impl IntoView {
                                         </p>
                                     </div>
 
+                                    <div>
+                                        <label class="block text-white text-sm font-bold mb-2">
+                                            "Exportumfang"
+                                        </label>
+                                        <select
+                                            class="w-full p-2 rounded bg-background text-white border border-gray-600 focus:border-primary-100 focus:outline-none"
+                                            on:change=move |ev| set_export_filter.set(match event_target_value(&ev).as_str() {
+                                                "exclude_flagged" => "exclude_flagged",
+                                                "healthy_only" => "healthy_only",
+                                                _ => "all",
+                                            })
+                                            prop:value=export_filter
+                                        >
+                                            <option value="all">"Alle Einträge"</option>
+                                            <option value="exclude_flagged">"Ohne kompromittierte und schwache Passwörter"</option>
+                                            <option value="healthy_only">"Nur Einträge ohne Probleme"</option>
+                                        </select>
+                                        <p class="mt-1 text-sm text-gray-400">
+                                            "Nutzt das Ergebnis der letzten Passwortprüfung, sinnvoll für weniger sichere Speicherorte"
+                                        </p>
+                                    </div>
+
                                     <div class="grid grid-cols-2 gap-4">
                                         <button
                                             type="button"
                                             class="flex items-center justify-center space-x-2 bg-background border border-primary-100 hover:bg-primary-400/10 text-white py-2 px-4 rounded focus:outline-none transition-all duration-200"
-                                            on:click=move |_| {
-                                                spawn_local(async move {
-                                                    let response = invoke("import_passwords", wasm_bindgen::JsValue::NULL).await;
-                                                    match serde_wasm_bindgen::from_value(response) {
-                                                        Ok(()) => {
-                                                            set_im_export_status.set("Import erfolgreich!".to_string());
-                                                        }
-                                                        Err(_) => {
-                                                            set_im_export_status.set("Import fehlgeschlagen!".to_string());
-                                                        }
-                                                    }
-                                                });
-                                            }
+                                            on:click=move |_| set_show_csv_import.set(true)
                                         >
                                             <Icon icon=import_icon.into() class="w-5 h-5 text-primary-100" />
                                             <span>"CSV importieren"</span>
@@ -443,14 +652,62 @@ pub fn DatabaseSettings() -> impl IntoView {
                                             <Icon icon=export_icon.into() class="w-5 h-5 text-primary-100" />
                                             <span>"Als CSV exportieren"</span>
                                         </button>
+                                        <button
+                                            type="button"
+                                            class="col-span-2 flex items-center justify-center space-x-2 bg-background border border-gray-600 hover:border-primary-100 text-white py-2 px-4 rounded focus:outline-none transition-all duration-200"
+                                            on:click=move |_| set_show_encrypted_export.set(true)
+                                        >
+                                            <Icon icon=encrypted_export_icon.into() class="w-5 h-5 text-primary-100" />
+                                            <span>"Verschlüsselt exportieren"</span>
+                                        </button>
+                                        <button
+                                            type="button"
+                                            class="col-span-2 flex items-center justify-center space-x-2 bg-background border border-gray-600 hover:border-primary-100 text-white py-2 px-4 rounded focus:outline-none transition-all duration-200"
+                                            on:click=move |_| set_show_paste_import.set(true)
+                                        >
+                                            <Icon icon=paste_icon.into() class="w-5 h-5 text-primary-100" />
+                                            <span>"Text einfügen und importieren"</span>
+                                        </button>
+                                        <button
+                                            type="button"
+                                            class="col-span-2 flex items-center justify-center space-x-2 bg-background border border-gray-600 hover:border-primary-100 text-white py-2 px-4 rounded focus:outline-none transition-all duration-200"
+                                            on:click=move |_| set_show_file_import.set(true)
+                                        >
+                                            <Icon icon=key_icon.into() class="w-5 h-5 text-primary-100" />
+                                            <span>"KeePass, Bitwarden oder KarnCrypt-Export importieren"</span>
+                                        </button>
+                                        <button
+                                            type="button"
+                                            class="col-span-2 flex items-center justify-center space-x-2 bg-background border border-gray-600 hover:border-primary-100 text-white py-2 px-4 rounded focus:outline-none transition-all duration-200"
+                                            on:click=move |_| set_show_otp_import.set(true)
+                                        >
+                                            <Icon icon=phone_icon.into() class="w-5 h-5 text-primary-100" />
+                                            <span>"TOTP-Schlüssel aus Aegis oder andOTP importieren"</span>
+                                        </button>
                                     </div>
-                                    {move || (!im_export_status.get().is_empty()).then(||
-                                        view! {
-                                            <div class="text-primary-100 text-sm text-center">
-                                                {im_export_status.get()}
-                                            </div>
-                                        }
-                                    )}
+
+                                    <AuditLogExport />
+                                </fieldset>
+
+                                <fieldset class="space-y-4">
+                                    <h3 class="text-lg font-semibold bg-gradient-primary bg-clip-text text-transparent mb-4">
+                                        <div class="flex items-center space-x-2">
+                                            <Icon icon=trash_icon.into() class="w-5 h-5 text-primary-100" />
+                                            <span>"Tresor löschen"</span>
+                                        </div>
+                                    </h3>
+
+                                    <button
+                                        type="button"
+                                        class="w-full flex items-center justify-center space-x-2 bg-background border border-gray-600 hover:border-primary-100 text-white py-2 px-4 rounded focus:outline-none transition-all duration-200"
+                                        on:click=move |_| set_show_vault_deletion.set(true)
+                                    >
+                                        <Icon icon=trash_icon.into() class="w-5 h-5 text-primary-100" />
+                                        <span>"Tresor endgültig löschen"</span>
+                                    </button>
+                                    <p class="text-sm text-gray-400">
+                                        "Löscht die Datenbank und die Schlüsselparameter, optional nach dem Überschreiben der Dateien"
+                                    </p>
                                 </fieldset>
 
                                 <div class="flex justify-end pt-4 border-t border-gray-600">
@@ -472,6 +729,64 @@ pub fn DatabaseSettings() -> impl IntoView {
                     action=current_action.get()
                     on_close=handle_dialog_close
                     on_verify=move |_| ()
+                    on_master_password=handle_master_password
+                />
+            })}
+
+            {move || show_paste_import.get().then(|| view! {
+                <PasteImport
+                    on_close=move |_| {
+                        set_show_paste_import.set(false);
+                        suggest_categories();
+                    }
+                    on_imported=move |_| set_imported.set(true)
+                />
+            })}
+
+            {move || show_encrypted_export.get().then(|| view! {
+                <EncryptedExport on_close=move |_| set_show_encrypted_export.set(false) />
+            })}
+
+            {move || show_vault_deletion.get().then(|| view! {
+                <VaultDeletion on_close=move |_| set_show_vault_deletion.set(false) />
+            })}
+
+            {move || show_csv_import.get().then(|| view! {
+                <CsvImport
+                    on_close=move |_| {
+                        set_show_csv_import.set(false);
+                        suggest_categories();
+                    }
+                    on_imported=move |_| set_imported.set(true)
+                />
+            })}
+
+            {move || show_file_import.get().then(|| view! {
+                <FileImport
+                    on_close=move |_| {
+                        set_show_file_import.set(false);
+                        suggest_categories();
+                    }
+                    on_imported=move |_| set_imported.set(true)
+                />
+            })}
+
+            {move || show_otp_import.get().then(|| view! {
+                <OtpImport on_close=move |_| set_show_otp_import.set(false) />
+            })}
+
+            {move || category_suggestions.get().map(|suggestions| view! {
+                <CategoryReview
+                    suggestions=suggestions
+                    on_applied=move |_| ()
+                    on_close=move |_| set_category_suggestions.set(None)
+                />
+            })}
+
+            {move || preview_master_pass.get().map(|master_pass| view! {
+                <BackupPreview
+                    master_pass=master_pass
+                    on_close=move |_| set_preview_master_pass.set(None)
                 />
             })}
         </div>
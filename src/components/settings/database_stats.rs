@@ -0,0 +1,72 @@
+use crate::app::invoke;
+use leptos::*;
+use serde::Deserialize;
+use std::time::Duration;
+
+use super::backup_list::format_size;
+
+#[derive(Clone, Deserialize)]
+struct DatabaseStatsResponse {
+    entry_count: usize,
+    database_size: u64,
+    backup_count: usize,
+    last_backup: Option<String>,
+}
+
+fn format_timestamp(timestamp: &str) -> String {
+    let date = web_sys::js_sys::Date::new(&wasm_bindgen::JsValue::from_str(timestamp));
+    format!(
+        "{:02}.{:02}.{} {:02}:{:02} Uhr",
+        date.get_date(),
+        date.get_month() + 1,
+        date.get_full_year(),
+        date.get_hours(),
+        date.get_minutes()
+    )
+}
+
+#[component]
+pub fn DatabaseStats() -> impl IntoView {
+    let (stats, set_stats) = create_signal(None::<DatabaseStatsResponse>);
+
+    let load_stats = move || {
+        spawn_local(async move {
+            let response = invoke("get_database_stats", wasm_bindgen::JsValue::NULL).await;
+            if let Ok(stats) = serde_wasm_bindgen::from_value::<DatabaseStatsResponse>(response) {
+                set_stats.set(Some(stats));
+            }
+        });
+    };
+
+    load_stats();
+    if let Ok(handle) = set_interval_with_handle(load_stats, Duration::from_secs(15)) {
+        on_cleanup(move || handle.clear());
+    }
+
+    view! {
+        {move || stats.get().map(|stats| view! {
+            <div class="grid grid-cols-3 gap-4">
+                <div class="p-4 border border-gray-700 rounded-lg">
+                    <div class="text-sm text-gray-400">"Einträge"</div>
+                    <div class="text-xl font-bold text-white">{stats.entry_count}</div>
+                </div>
+                <div class="p-4 border border-gray-700 rounded-lg">
+                    <div class="text-sm text-gray-400">"Datenbankgröße"</div>
+                    <div class="text-xl font-bold text-white">{format_size(stats.database_size)}</div>
+                </div>
+                <div class="p-4 border border-gray-700 rounded-lg">
+                    <div class="text-sm text-gray-400">
+                        {format!("Letztes Backup ({} gesamt)", stats.backup_count)}
+                    </div>
+                    <div class="text-sm font-bold text-white">
+                        {stats
+                            .last_backup
+                            .as_deref()
+                            .map(format_timestamp)
+                            .unwrap_or("Noch kein Backup".to_string())}
+                    </div>
+                </div>
+            </div>
+        })}
+    }
+}
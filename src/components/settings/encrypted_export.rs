@@ -0,0 +1,183 @@
+use crate::{app::invoke, components::icons::Icon};
+use leptos::{ev::SubmitEvent, *};
+use serde::Serialize;
+
+use super::{ExportConfirmation, ExportScope, PrepareExportArgs};
+
+/// The shortest passphrase the backend accepts for an export.
+const MIN_PASSPHRASE_LENGTH: usize = 12;
+
+#[derive(Serialize)]
+struct ExportEncryptedArgs {
+    passphrase: String,
+    scope: ExportScope,
+    #[serde(rename = "confirmationPin")]
+    confirmation_pin: Option<String>,
+}
+
+#[component]
+pub fn EncryptedExport(#[prop(into)] on_close: Callback<()>) -> impl IntoView {
+    let (passphrase, set_passphrase) = create_signal(String::new());
+    let (repeated, set_repeated) = create_signal(String::new());
+    let (confirmation_pin, set_confirmation_pin) = create_signal(String::new());
+    let (pin_required, set_pin_required) = create_signal(false);
+    let (status, set_status) = create_signal(String::new());
+    let (is_exporting, set_is_exporting) = create_signal(false);
+    let (export_scope, set_export_scope) = create_signal(None::<ExportScope>);
+
+    let exit_icon = create_memo(move |_| "x-mark");
+    let lock_icon = create_memo(move |_| "lock-closed");
+    let export_icon = create_memo(move |_| "arrow-down-tray");
+
+    spawn_local(async move {
+        let response = invoke("has_confirmation_pin", wasm_bindgen::JsValue::NULL).await;
+        if let Ok(has_pin) = serde_wasm_bindgen::from_value::<bool>(response) {
+            set_pin_required.set(has_pin);
+        }
+    });
+
+    let validation = move || {
+        let passphrase = passphrase.get();
+        if passphrase.chars().count() < MIN_PASSPHRASE_LENGTH {
+            Some(format!(
+                "Die Passphrase muss mindestens {} Zeichen lang sein",
+                MIN_PASSPHRASE_LENGTH
+            ))
+        } else if passphrase != repeated.get() {
+            Some("Die Passphrasen stimmen nicht überein".to_string())
+        } else {
+            None
+        }
+    };
+
+    let show_error = move |response: wasm_bindgen::JsValue| {
+        set_status.set(format!(
+            "Export fehlgeschlagen: {}",
+            response
+                .as_string()
+                .unwrap_or("Unbekannter Fehler".to_string())
+        ))
+    };
+
+    let handle_submit = move |ev: SubmitEvent| {
+        ev.prevent_default();
+        set_is_exporting.set(true);
+        spawn_local(async move {
+            let args = serde_wasm_bindgen::to_value(&PrepareExportArgs {
+                format: "encrypted",
+                filter: None,
+            })
+            .unwrap();
+            let response = invoke("prepare_export", args).await;
+            match serde_wasm_bindgen::from_value::<ExportScope>(response.clone()) {
+                Ok(scope) => set_export_scope.set(Some(scope)),
+                Err(_) => show_error(response),
+            }
+            set_is_exporting.set(false);
+        });
+    };
+
+    let handle_export = move |_| {
+        let Some(scope) = export_scope.get_untracked() else {
+            return;
+        };
+        set_export_scope.set(None);
+        set_is_exporting.set(true);
+        let pin = confirmation_pin.get_untracked();
+        spawn_local(async move {
+            let args = serde_wasm_bindgen::to_value(&ExportEncryptedArgs {
+                passphrase: passphrase.get_untracked(),
+                scope,
+                confirmation_pin: (!pin.is_empty()).then_some(pin),
+            })
+            .unwrap();
+            let response = invoke("export_encrypted", args).await;
+            match serde_wasm_bindgen::from_value::<String>(response.clone()) {
+                Ok(path) => {
+                    set_passphrase.set(String::new());
+                    set_repeated.set(String::new());
+                    set_status.set(format!("Export gespeichert unter {}", path));
+                }
+                Err(_) => show_error(response),
+            }
+            set_is_exporting.set(false);
+        });
+    };
+
+    view! {
+        <div class="fixed inset-0 bg-black bg-opacity-50 flex items-center justify-center z-50">
+            <div class="bg-background-card rounded-lg p-6 w-full max-w-md">
+                <div class="flex justify-between items-center mb-6">
+                    <h2 class="text-xl font-bold bg-gradient-primary bg-clip-text text-transparent flex items-center">
+                        <Icon icon=lock_icon.into() class="w-8 h-8 mr-2 text-primary-100" />
+                        "Verschlüsselt exportieren"
+                    </h2>
+                    <button
+                        class="text-gray-400 hover:text-white"
+                        on:click=move |_| on_close.call(())
+                    >
+                        <Icon icon=exit_icon.into() class="w-5 h-5" />
+                    </button>
+                </div>
+
+                <form on:submit=handle_submit class="space-y-4">
+                    <p class="text-sm text-gray-400">
+                        "Der Export enthält alle Einträge mit Ordnern, Tags und TOTP-Schlüsseln und wird mit einer eigenen Passphrase verschlüsselt. Ohne diese Passphrase lässt er sich nicht wieder importieren."
+                    </p>
+                    <input
+                        type="password"
+                        class="w-full p-2 rounded bg-background text-white border border-gray-600 focus:outline-none focus:border-primary-100"
+                        placeholder="Passphrase"
+                        prop:value=passphrase
+                        on:input=move |ev| set_passphrase.set(event_target_value(&ev))
+                    />
+                    <input
+                        type="password"
+                        class="w-full p-2 rounded bg-background text-white border border-gray-600 focus:outline-none focus:border-primary-100"
+                        placeholder="Passphrase wiederholen"
+                        prop:value=repeated
+                        on:input=move |ev| set_repeated.set(event_target_value(&ev))
+                    />
+                    {move || pin_required.get().then(|| view! {
+                        <input
+                            type="password"
+                            inputmode="numeric"
+                            class="w-full p-2 rounded bg-background text-white border border-gray-600 focus:outline-none focus:border-primary-100"
+                            placeholder="Bestätigungs-PIN"
+                            prop:value=confirmation_pin
+                            on:input=move |ev| set_confirmation_pin.set(event_target_value(&ev))
+                        />
+                    })}
+
+                    {move || (!passphrase.get().is_empty())
+                        .then(validation)
+                        .flatten()
+                        .map(|message| view! {
+                            <div class="text-sm text-gray-400">{message}</div>
+                        })}
+
+                    {move || (!status.get().is_empty()).then(|| view! {
+                        <div class="text-primary-100 text-sm text-center break-all">{status.get()}</div>
+                    })}
+
+                    <button
+                        type="submit"
+                        class="w-full flex items-center justify-center space-x-2 bg-gradient-primary text-white font-bold py-2 px-4 rounded focus:outline-none hover:opacity-90 disabled:opacity-50 disabled:cursor-not-allowed"
+                        prop:disabled=move || is_exporting.get() || validation().is_some()
+                    >
+                        <Icon icon=export_icon.into() class="w-5 h-5" />
+                        <span>"Exportieren"</span>
+                    </button>
+                </form>
+            </div>
+
+            {move || export_scope.get().map(|scope| view! {
+                <ExportConfirmation
+                    scope=scope
+                    on_confirm=handle_export
+                    on_close=move |_| set_export_scope.set(None)
+                />
+            })}
+        </div>
+    }
+}
@@ -0,0 +1,138 @@
+use crate::components::icons::Icon;
+use leptos::*;
+use serde::{Deserialize, Serialize};
+
+/// What an export writes out of the vault, as prepared by the backend.
+///
+/// The scope is passed back unchanged when the export is confirmed, the backend refuses the
+/// export if the vault no longer matches it.
+#[derive(Clone, Serialize, Deserialize)]
+pub struct ExportScope {
+    format: String,
+    entries: usize,
+    logins: usize,
+    totp_only: usize,
+    totp_secrets: usize,
+    folders_and_tags: bool,
+    attachments: usize,
+    excluded: usize,
+    destination: String,
+}
+
+#[derive(Serialize)]
+pub struct PrepareExportArgs<'a> {
+    pub format: &'a str,
+    pub filter: Option<&'a str>,
+}
+
+fn included(included: bool) -> &'static str {
+    if included {
+        "enthalten"
+    } else {
+        "nicht enthalten"
+    }
+}
+
+#[component]
+pub fn ExportConfirmation(
+    scope: ExportScope,
+    #[prop(into)] on_confirm: Callback<()>,
+    #[prop(into)] on_close: Callback<()>,
+) -> impl IntoView {
+    let exit_icon = create_memo(move |_| "x-mark");
+    let warning_icon = create_memo(move |_| "exclamation-triangle");
+    let export_icon = create_memo(move |_| "arrow-down-tray");
+
+    let plain_text = scope.format == "csv";
+    let rows = vec![
+        (
+            "Format",
+            if plain_text {
+                "CSV-Datei (Klartext)".to_string()
+            } else {
+                "Verschlüsselte Datei".to_string()
+            },
+        ),
+        (
+            "Einträge",
+            format!(
+                "{} ({} mit Passwort, {} nur Zwei-Faktor-Code)",
+                scope.entries, scope.logins, scope.totp_only
+            ),
+        ),
+        (
+            "TOTP-Schlüssel",
+            if scope.totp_secrets > 0 {
+                format!("{} enthalten", scope.totp_secrets)
+            } else {
+                included(false).to_string()
+            },
+        ),
+        (
+            "Ordner und Tags",
+            included(scope.folders_and_tags).to_string(),
+        ),
+        (
+            "Anhänge",
+            format!("nicht enthalten, {} bleiben im Tresor", scope.attachments),
+        ),
+        (
+            "Ausgelassen",
+            format!("{} markierte Einträge", scope.excluded),
+        ),
+        ("Ziel", scope.destination.clone()),
+    ];
+
+    view! {
+        <div class="fixed inset-0 bg-black bg-opacity-50 flex items-center justify-center z-50">
+            <div class="bg-background-card rounded-lg p-6 w-full max-w-md">
+                <div class="flex justify-between items-center mb-6">
+                    <h2 class="text-xl font-bold bg-gradient-primary bg-clip-text text-transparent flex items-center">
+                        <Icon icon=warning_icon.into() class="w-8 h-8 mr-2 text-primary-100" />
+                        "Export bestätigen"
+                    </h2>
+                    <button
+                        class="text-gray-400 hover:text-white"
+                        on:click=move |_| on_close.call(())
+                    >
+                        <Icon icon=exit_icon.into() class="w-5 h-5" />
+                    </button>
+                </div>
+
+                <p class="text-sm text-gray-400 mb-4">
+                    "Folgende Daten verlassen den Tresor:"
+                </p>
+                <dl class="space-y-2 mb-4">
+                    {rows.into_iter().map(|(label, value)| view! {
+                        <div class="flex justify-between text-sm">
+                            <dt class="text-gray-400 mr-4">{label}</dt>
+                            <dd class="text-white text-right break-all">{value}</dd>
+                        </div>
+                    }).collect_view()}
+                </dl>
+
+                {plain_text.then(|| view! {
+                    <p class="text-sm text-red-500 mb-4">
+                        "Die CSV-Datei enthält deine Passwörter im Klartext. Bewahre sie sicher auf, teile sie niemals unverschlüsselt und lösche sie nach dem Import in ein anderes System."
+                    </p>
+                })}
+
+                <div class="flex space-x-2">
+                    <button
+                        class="flex-1 px-4 py-2 rounded border border-gray-600 text-gray-400 hover:text-white"
+                        on:click=move |_| on_close.call(())
+                    >
+                        "Abbrechen"
+                    </button>
+                    <button
+                        class="flex-1 flex items-center justify-center bg-gradient-primary text-white font-bold px-4 py-2 rounded hover:opacity-90"
+                        on:click=move |_| on_confirm.call(())
+                    >
+                        <Icon icon=export_icon.into() class="w-5 h-5 mr-2" />
+                        "Exportieren"
+                    </button>
+                </div>
+            </div>
+        </div>
+    }
+}
@@ -0,0 +1,122 @@
+use crate::{app::invoke, components::icons::Icon};
+use leptos::{ev::SubmitEvent, *};
+use serde::Serialize;
+
+#[derive(Serialize)]
+struct ImportFileArgs {
+    format: String,
+    password: Option<String>,
+}
+
+#[component]
+pub fn FileImport(
+    #[prop(into)] on_close: Callback<()>,
+    #[prop(optional, into)] on_imported: Option<Callback<()>>,
+) -> impl IntoView {
+    let (format, set_format) = create_signal("keepass".to_string());
+    let (password, set_password) = create_signal(String::new());
+    let (status, set_status) = create_signal(String::new());
+    let (is_importing, set_is_importing) = create_signal(false);
+
+    let exit_icon = create_memo(move |_| "x-mark");
+    let key_icon = create_memo(move |_| "key");
+    let import_icon = create_memo(move |_| "arrow-up-tray");
+
+    let needs_password = move || format.get() != "bitwarden";
+
+    let handle_submit = move |ev: SubmitEvent| {
+        ev.prevent_default();
+        set_is_importing.set(true);
+        set_status.set("Datei wird eingelesen...".to_string());
+        let password = password.get_untracked();
+        spawn_local(async move {
+            let args = serde_wasm_bindgen::to_value(&ImportFileArgs {
+                format: format.get_untracked(),
+                password: (!password.is_empty()).then_some(password),
+            })
+            .unwrap();
+            let response = invoke("import_passwords", args).await;
+            match serde_wasm_bindgen::from_value::<String>(response.clone()) {
+                Ok(summary) => {
+                    set_password.set(String::new());
+                    set_status.set(summary);
+                    if let Some(on_imported) = on_imported {
+                        on_imported.call(());
+                    }
+                }
+                Err(_) => set_status.set(format!(
+                    "Import fehlgeschlagen: {}",
+                    response
+                        .as_string()
+                        .unwrap_or("Unbekannter Fehler".to_string())
+                )),
+            }
+            set_is_importing.set(false);
+        });
+    };
+
+    view! {
+        <div class="fixed inset-0 bg-black bg-opacity-50 flex items-center justify-center z-50">
+            <div class="bg-background-card rounded-lg p-6 w-full max-w-md">
+                <div class="flex justify-between items-center mb-6">
+                    <h2 class="text-xl font-bold bg-gradient-primary bg-clip-text text-transparent flex items-center">
+                        <Icon icon=key_icon.into() class="w-8 h-8 mr-2 text-primary-100" />
+                        "Passwort-Manager importieren"
+                    </h2>
+                    <button
+                        class="text-gray-400 hover:text-white"
+                        on:click=move |_| on_close.call(())
+                    >
+                        <Icon icon=exit_icon.into() class="w-5 h-5" />
+                    </button>
+                </div>
+
+                <form on:submit=handle_submit class="space-y-4">
+                    <select
+                        class="w-full p-2 rounded bg-background text-white border border-gray-600 focus:border-primary-100 focus:outline-none"
+                        on:change=move |ev| {
+                            set_format.set(event_target_value(&ev));
+                            set_status.set(String::new());
+                        }
+                        prop:value=format
+                    >
+                        <option value="keepass">"KeePass (KDBX 4)"</option>
+                        <option value="bitwarden">"Bitwarden (JSON)"</option>
+                        <option value="kcexport">"KarnCrypt-Export (verschlüsselt)"</option>
+                    </select>
+                    <p class="text-sm text-gray-400">
+                        {move || match format.get().as_str() {
+                            "keepass" => "Unterstützt werden KDBX-4-Datenbanken mit Master-Passwort. Gruppen werden als Ordner übernommen, der Papierkorb und ältere Versionen der Einträge nicht.",
+                            "bitwarden" => "Unverschlüsselte und passwortgeschützte Exporte werden unterstützt. Ordner, Favoriten (als Tag) und TOTP-Schlüssel werden übernommen, benutzerdefinierte Felder landen in den Notizen.",
+                            _ => "Verschlüsselte Exporte einer anderen KarnCrypt-Installation inklusive Ordnern, Tags und TOTP-Schlüsseln.",
+                        }}
+                    </p>
+                    <input
+                        type="password"
+                        class="w-full p-2 rounded bg-background text-white border border-gray-600 focus:outline-none focus:border-primary-100"
+                        placeholder=move || match format.get().as_str() {
+                            "keepass" => "Master-Passwort der KeePass-Datenbank",
+                            "bitwarden" => "Passwort des Exports (leer wenn unverschlüsselt)",
+                            _ => "Passphrase des Exports",
+                        }
+                        prop:value=password
+                        on:input=move |ev| set_password.set(event_target_value(&ev))
+                    />
+
+                    {move || (!status.get().is_empty()).then(|| view! {
+                        <div class="text-primary-100 text-sm text-center">{status.get()}</div>
+                    })}
+
+                    <button
+                        type="submit"
+                        class="w-full flex items-center justify-center space-x-2 bg-gradient-primary text-white font-bold py-2 px-4 rounded focus:outline-none hover:opacity-90 disabled:opacity-50 disabled:cursor-not-allowed"
+                        prop:disabled=move || is_importing.get() || (needs_password() && password.get().is_empty())
+                    >
+                        <Icon icon=import_icon.into() class="w-5 h-5" />
+                        <span>"Datei wählen und importieren"</span>
+                    </button>
+                </form>
+            </div>
+        </div>
+    }
+}
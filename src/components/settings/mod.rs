@@ -1,11 +1,39 @@
 mod application;
+mod audit_log_export;
+mod backup_list;
+mod backup_preview;
+mod backup_recipients;
+mod cloud_sync_warning;
+mod csv_import;
 mod database;
+mod database_stats;
+mod encrypted_export;
+mod export_confirmation;
+mod file_import;
+mod otp_import;
 mod panel;
+mod paste_import;
 mod security;
+mod snapshot_view;
 mod system;
+mod vault_deletion;
 
 pub use application::ApplicationSettings;
+pub use audit_log_export::AuditLogExport;
+pub use backup_list::BackupList;
+pub use backup_preview::BackupPreview;
+pub use backup_recipients::BackupRecipients;
+pub use cloud_sync_warning::CloudSyncWarning;
+pub use csv_import::CsvImport;
 pub use database::DatabaseSettings;
+pub use database_stats::DatabaseStats;
+pub use encrypted_export::EncryptedExport;
+pub use export_confirmation::{ExportConfirmation, ExportScope, PrepareExportArgs};
+pub use file_import::FileImport;
+pub use otp_import::OtpImport;
 pub use panel::SettingsPanel;
+pub use paste_import::PasteImport;
 pub use security::SecuritySettings;
+pub use snapshot_view::SnapshotView;
 pub use system::SystemSettings;
+pub use vault_deletion::VaultDeletion;
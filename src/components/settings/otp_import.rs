@@ -0,0 +1,256 @@
+use crate::{app::invoke, components::icons::Icon};
+use leptos::*;
+use serde::{Deserialize, Serialize};
+
+#[derive(Clone, Deserialize, PartialEq)]
+struct OtpMatch {
+    index: usize,
+    issuer: String,
+    account: String,
+    entry_id: Option<i32>,
+}
+
+#[derive(Clone, Deserialize, PartialEq)]
+struct OtpImportPreview {
+    path: String,
+    accounts: Vec<OtpMatch>,
+    skipped: usize,
+}
+
+#[derive(Clone, Deserialize, PartialEq)]
+struct EntryOption {
+    id: Option<i32>,
+    service: String,
+    username: String,
+}
+
+#[derive(Deserialize)]
+struct ImportSummary {
+    imported: usize,
+    skipped: usize,
+    errors: usize,
+}
+
+#[derive(Serialize)]
+struct PreviewOtpArgs {
+    format: String,
+}
+
+#[derive(Serialize)]
+struct OtpMapping {
+    index: usize,
+    entry_id: Option<i32>,
+}
+
+#[derive(Serialize)]
+struct ImportOtpArgs {
+    path: String,
+    format: String,
+    mappings: Vec<OtpMapping>,
+}
+
+/// The choice of an account that is not imported, other choices are an entry ID or "new".
+const SKIP: &str = "skip";
+const NEW_ENTRY: &str = "new";
+
+#[component]
+pub fn OtpImport(
+    #[prop(into)] on_close: Callback<()>,
+    #[prop(optional, into)] on_imported: Option<Callback<()>>,
+) -> impl IntoView {
+    let (format, set_format) = create_signal("aegis".to_string());
+    let (preview, set_preview) = create_signal(None::<OtpImportPreview>);
+    let (entries, set_entries) = create_signal(Vec::<EntryOption>::new());
+    let (choices, set_choices) = create_signal(Vec::<String>::new());
+    let (status, set_status) = create_signal(String::new());
+    let (is_busy, set_is_busy) = create_signal(false);
+
+    let exit_icon = create_memo(move |_| "x-mark");
+    let phone_icon = create_memo(move |_| "device-phone-mobile");
+    let import_icon = create_memo(move |_| "arrow-up-tray");
+
+    let handle_pick = move |_| {
+        set_is_busy.set(true);
+        set_status.set(String::new());
+        spawn_local(async move {
+            let args = serde_wasm_bindgen::to_value(&PreviewOtpArgs {
+                format: format.get_untracked(),
+            })
+            .unwrap();
+            let response = invoke("preview_otp_import", args).await;
+            match serde_wasm_bindgen::from_value::<Option<OtpImportPreview>>(response.clone()) {
+                Ok(Some(loaded)) => {
+                    let response = invoke("get_passwords", wasm_bindgen::JsValue::NULL).await;
+                    set_entries.set(
+                        serde_wasm_bindgen::from_value::<Vec<EntryOption>>(response)
+                            .unwrap_or_default(),
+                    );
+                    set_choices.set(
+                        loaded
+                            .accounts
+                            .iter()
+                            .map(|account| match account.entry_id {
+                                Some(id) => id.to_string(),
+                                None => NEW_ENTRY.to_string(),
+                            })
+                            .collect(),
+                    );
+                    set_preview.set(Some(loaded));
+                }
+                Ok(None) => {}
+                Err(_) => set_status.set(format!(
+                    "Backup konnte nicht gelesen werden: {}",
+                    response
+                        .as_string()
+                        .unwrap_or("Unbekannter Fehler".to_string())
+                )),
+            }
+            set_is_busy.set(false);
+        });
+    };
+
+    let handle_import = move |_| {
+        let Some(current) = preview.get_untracked() else {
+            return;
+        };
+        let mappings = current
+            .accounts
+            .iter()
+            .zip(choices.get_untracked())
+            .filter(|(_, choice)| choice != SKIP)
+            .map(|(account, choice)| OtpMapping {
+                index: account.index,
+                entry_id: choice.parse().ok(),
+            })
+            .collect();
+        set_is_busy.set(true);
+        spawn_local(async move {
+            let args = serde_wasm_bindgen::to_value(&ImportOtpArgs {
+                path: current.path,
+                format: format.get_untracked(),
+                mappings,
+            })
+            .unwrap();
+            let response = invoke("import_otp_secrets", args).await;
+            match serde_wasm_bindgen::from_value::<ImportSummary>(response.clone()) {
+                Ok(summary) => {
+                    set_status.set(format!(
+                        "{} TOTP-Schlüssel importiert, {} übersprungen, {} fehlerhaft",
+                        summary.imported, summary.skipped, summary.errors
+                    ));
+                    set_preview.set(None);
+                    if let Some(on_imported) = on_imported {
+                        on_imported.call(());
+                    }
+                }
+                Err(_) => set_status.set(format!(
+                    "Import fehlgeschlagen: {}",
+                    response
+                        .as_string()
+                        .unwrap_or("Unbekannter Fehler".to_string())
+                )),
+            }
+            set_is_busy.set(false);
+        });
+    };
+
+    view! {
+        <div class="fixed inset-0 bg-black bg-opacity-50 flex items-center justify-center z-50">
+            <div class="bg-background-card rounded-lg p-6 w-full max-w-2xl">
+                <div class="flex justify-between items-center mb-6">
+                    <h2 class="text-xl font-bold bg-gradient-primary bg-clip-text text-transparent flex items-center">
+                        <Icon icon=phone_icon.into() class="w-8 h-8 mr-2 text-primary-100" />
+                        "Authenticator importieren"
+                    </h2>
+                    <button
+                        class="text-gray-400 hover:text-white"
+                        on:click=move |_| on_close.call(())
+                    >
+                        <Icon icon=exit_icon.into() class="w-5 h-5" />
+                    </button>
+                </div>
+
+                {move || match preview.get() {
+                    None => view! {
+                        <div class="space-y-4">
+                            <select
+                                class="w-full p-2 rounded bg-background text-white border border-gray-600 focus:border-primary-100 focus:outline-none"
+                                on:change=move |ev| set_format.set(event_target_value(&ev))
+                                prop:value=format
+                            >
+                                <option value="aegis">"Aegis (JSON, unverschlüsselt)"</option>
+                                <option value="andotp">"andOTP (JSON, unverschlüsselt)"</option>
+                            </select>
+                            <p class="text-sm text-gray-400">
+                                {move || match format.get().as_str() {
+                                    "aegis" => "Exportiere den Tresor in Aegis unter Einstellungen → Import & Export ohne Verschlüsselung.",
+                                    _ => "Erstelle in andOTP ein Backup im Format „Klartext“.",
+                                }}
+                                " Die Datei enthält alle Schlüssel im Klartext, lösche sie nach dem Import."
+                            </p>
+                            <button
+                                class="w-full flex items-center justify-center space-x-2 bg-gradient-primary text-white font-bold py-2 px-4 rounded focus:outline-none hover:opacity-90 disabled:opacity-50 disabled:cursor-not-allowed"
+                                prop:disabled=is_busy
+                                on:click=handle_pick
+                            >
+                                <Icon icon=import_icon.into() class="w-5 h-5" />
+                                <span>"Datei wählen"</span>
+                            </button>
+                        </div>
+                    }.into_view(),
+                    Some(current) => view! {
+                        <div class="space-y-4">
+                            <p class="text-sm text-gray-400">
+                                "Wähle für jedes Konto den Eintrag, der den TOTP-Schlüssel erhält. Vorschläge beruhen auf Dienst und Benutzername."
+                                {(current.skipped > 0).then(|| format!(
+                                    " {} Konten (z. B. HOTP oder Steam) werden nicht unterstützt.",
+                                    current.skipped
+                                ))}
+                            </p>
+                            <div class="overflow-y-auto max-h-80 space-y-2">
+                                {current.accounts.into_iter().enumerate().map(|(position, account)| view! {
+                                    <div class="grid grid-cols-2 gap-2 items-center">
+                                        <div class="min-w-0">
+                                            <div class="text-white truncate">
+                                                {if account.issuer.is_empty() { account.account.clone() } else { account.issuer.clone() }}
+                                            </div>
+                                            <div class="text-sm text-gray-400 truncate">{account.account.clone()}</div>
+                                        </div>
+                                        <select
+                                            class="w-full p-2 rounded bg-background text-white border border-gray-600 focus:border-primary-100 focus:outline-none"
+                                            on:change=move |ev| {
+                                                let choice = event_target_value(&ev);
+                                                set_choices.update(|choices| choices[position] = choice);
+                                            }
+                                            prop:value=move || choices.with(|choices| choices[position].clone())
+                                        >
+                                            <option value=SKIP>"Nicht importieren"</option>
+                                            <option value=NEW_ENTRY>"Neuer Eintrag"</option>
+                                            {entries.get().into_iter().filter_map(|entry| entry.id.map(|id| view! {
+                                                <option value=id.to_string()>
+                                                    {format!("{} – {}", entry.service, entry.username)}
+                                                </option>
+                                            })).collect_view()}
+                                        </select>
+                                    </div>
+                                }).collect_view()}
+                            </div>
+                            <button
+                                class="w-full flex items-center justify-center space-x-2 bg-gradient-primary text-white font-bold py-2 px-4 rounded focus:outline-none hover:opacity-90 disabled:opacity-50 disabled:cursor-not-allowed"
+                                prop:disabled=move || is_busy.get() || choices.with(|choices| choices.iter().all(|c| c == SKIP))
+                                on:click=handle_import
+                            >
+                                <Icon icon=import_icon.into() class="w-5 h-5" />
+                                <span>"Importieren"</span>
+                            </button>
+                        </div>
+                    }.into_view(),
+                }}
+
+                {move || (!status.get().is_empty()).then(|| view! {
+                    <div class="mt-4 text-primary-100 text-sm text-center">{status.get()}</div>
+                })}
+            </div>
+        </div>
+    }
+}
@@ -0,0 +1,100 @@
+use crate::{app::invoke, components::icons::Icon};
+use leptos::*;
+use serde::Serialize;
+
+#[derive(Serialize)]
+struct ImportTextArgs {
+    text: Option<String>,
+}
+
+#[component]
+pub fn PasteImport(
+    #[prop(into)] on_close: Callback<()>,
+    #[prop(optional, into)] on_imported: Option<Callback<()>>,
+) -> impl IntoView {
+    let (text, set_text) = create_signal(String::new());
+    let (status, set_status) = create_signal(String::new());
+    let (is_importing, set_is_importing) = create_signal(false);
+
+    let exit_icon = create_memo(move |_| "x-mark");
+    let paste_icon = create_memo(move |_| "clipboard");
+    let import_icon = create_memo(move |_| "arrow-up-tray");
+
+    let import = move |text: Option<String>| {
+        set_is_importing.set(true);
+        spawn_local(async move {
+            let args = serde_wasm_bindgen::to_value(&ImportTextArgs { text }).unwrap();
+            let response = invoke("import_passwords_from_text", args).await;
+            match serde_wasm_bindgen::from_value::<String>(response.clone()) {
+                Ok(summary) => {
+                    set_text.set(String::new());
+                    set_status.set(summary);
+                    if let Some(on_imported) = on_imported {
+                        on_imported.call(());
+                    }
+                }
+                Err(_) => set_status.set(format!(
+                    "Import fehlgeschlagen: {}",
+                    response
+                        .as_string()
+                        .unwrap_or("Unbekannter Fehler".to_string())
+                )),
+            }
+            set_is_importing.set(false);
+        });
+    };
+
+    view! {
+        <div class="fixed inset-0 bg-black bg-opacity-50 flex items-center justify-center z-50">
+            <div class="bg-background-card rounded-lg p-6 w-full max-w-lg">
+                <div class="flex justify-between items-center mb-6">
+                    <h2 class="text-xl font-bold bg-gradient-primary bg-clip-text text-transparent flex items-center">
+                        <Icon icon=paste_icon.into() class="w-8 h-8 mr-2 text-primary-100" />
+                        "Text importieren"
+                    </h2>
+                    <button
+                        class="text-gray-400 hover:text-white"
+                        on:click=move |_| on_close.call(())
+                    >
+                        <Icon icon=exit_icon.into() class="w-5 h-5" />
+                    </button>
+                </div>
+
+                <p class="text-sm text-gray-400 mb-2">
+                    "Eine Zeile pro Eintrag: Service, Benutzername, Passwort, URL und Notizen, getrennt durch Semikolon, Tabulator oder Komma"
+                </p>
+                <textarea
+                    class="w-full p-2 rounded bg-background text-white border border-gray-600 focus:outline-none focus:border-primary-100 resize-none h-40 font-mono text-sm"
+                    placeholder="Mail;ich@example.com;geheim;https://mail.example;"
+                    prop:value=text
+                    on:input=move |ev| set_text.set(event_target_value(&ev))
+                />
+
+                {move || (!status.get().is_empty()).then(|| view! {
+                    <div class="mt-2 text-primary-100 text-sm text-center">{status.get()}</div>
+                })}
+
+                <div class="grid grid-cols-2 gap-4 mt-4">
+                    <button
+                        type="button"
+                        class="flex items-center justify-center space-x-2 bg-background border border-primary-100 hover:bg-primary-400/10 text-white py-2 px-4 rounded focus:outline-none transition-all duration-200 disabled:opacity-50 disabled:cursor-not-allowed"
+                        prop:disabled=is_importing
+                        on:click=move |_| import(None)
+                    >
+                        <Icon icon=paste_icon.into() class="w-5 h-5 text-primary-100" />
+                        <span>"Aus Zwischenablage"</span>
+                    </button>
+                    <button
+                        type="button"
+                        class="flex items-center justify-center space-x-2 bg-gradient-primary text-white font-bold py-2 px-4 rounded focus:outline-none hover:opacity-90 disabled:opacity-50 disabled:cursor-not-allowed"
+                        prop:disabled=move || is_importing.get() || text.get().trim().is_empty()
+                        on:click=move |_| import(Some(text.get()))
+                    >
+                        <Icon icon=import_icon.into() class="w-5 h-5" />
+                        <span>"Importieren"</span>
+                    </button>
+                </div>
+            </div>
+        </div>
+    }
+}
@@ -1,16 +1,45 @@
-use crate::{app::invoke, components::icons::Icon};
+use crate::{
+    app::{invoke, listen},
+    components::{
+        icons::Icon,
+        password_generator::{load_generator_defaults, GeneratePasswordArgs, GeneratorOptions},
+    },
+};
 use leptos::{ev::SubmitEvent, *};
 use serde::{Deserialize, Serialize};
-
-#[derive(Serialize)]
-struct PasswordGeneratorArgs {
-    length: usize,
-}
+use wasm_bindgen::prelude::*;
 
 #[derive(Serialize)]
 struct SaveSecuritySettingsArgs {
     #[serde(rename = "autoLogoutDuration")]
     auto_logout_duration: u64,
+    #[serde(rename = "sessionMaxLifetime")]
+    session_max_lifetime: u64,
+    #[serde(rename = "contentProtection")]
+    content_protection: bool,
+    #[serde(rename = "softKeyboard")]
+    soft_keyboard: bool,
+    #[serde(rename = "lockOnMinimize")]
+    lock_on_minimize: bool,
+    #[serde(rename = "lockOnBlur")]
+    lock_on_blur: bool,
+    #[serde(rename = "lockDelay")]
+    lock_delay: u64,
+    #[serde(rename = "lockOnIdle")]
+    lock_on_idle: u64,
+    #[serde(rename = "lockOnSuspend")]
+    lock_on_suspend: bool,
+    #[serde(rename = "decryptLimit")]
+    decrypt_limit: u32,
+}
+
+#[derive(Deserialize)]
+struct AutoLockSettings {
+    lock_on_minimize: bool,
+    lock_on_blur: bool,
+    lock_delay: u64,
+    lock_on_idle: u64,
+    lock_on_suspend: bool,
 }
 
 #[derive(Serialize)]
@@ -21,9 +50,65 @@ struct UpdateMasterPasswordArgs {
     new_password: String,
 }
 
+#[derive(Serialize)]
+struct RotateVaultKeysArgs {
+    #[serde(rename = "masterPass")]
+    master_pass: String,
+}
+
+#[derive(Serialize)]
+struct SetMetadataEncryptionArgs {
+    enabled: bool,
+}
+
+#[derive(Serialize)]
+struct SetConfirmationPinArgs {
+    #[serde(rename = "masterPass")]
+    master_pass: String,
+    pin: Option<String>,
+}
+
+#[derive(Clone, Deserialize)]
+struct KeyInfo {
+    version: u8,
+    cipher: String,
+    outdated: bool,
+}
+
+#[derive(Clone, Deserialize)]
+struct CryptoInfo {
+    sqlcipher_version: String,
+    kdf: String,
+    iterations: u32,
+    key_version: u8,
+    cipher: String,
+    salt_fingerprint: String,
+    last_rotation: Option<String>,
+    encrypt_metadata: bool,
+    pending_field_migration: usize,
+}
+
+fn format_timestamp(timestamp: &str) -> String {
+    let date = web_sys::js_sys::Date::new(&wasm_bindgen::JsValue::from_str(timestamp));
+    format!(
+        "{:02}.{:02}.{} {:02}:{:02} Uhr",
+        date.get_date(),
+        date.get_month() + 1,
+        date.get_full_year(),
+        date.get_hours(),
+        date.get_minutes()
+    )
+}
+
+#[derive(Clone, Copy, Deserialize)]
+struct RotationProgress {
+    done: usize,
+    total: usize,
+}
+
 #[derive(Deserialize)]
-struct PasswordGeneratorLength {
-    default_length: usize,
+struct RotationProgressEvent {
+    payload: RotationProgress,
 }
 
 #[component]
@@ -35,11 +120,34 @@ pub fn SecuritySettings() -> impl IntoView {
     let (show_master_password, set_show_master_password) = create_signal(false);
     let (error, set_error) = create_signal(String::new());
     let (auto_logout, set_auto_logout) = create_signal(0);
+    let (session_lifetime, set_session_lifetime) = create_signal(0);
     let (is_loading, set_is_loading) = create_signal(false);
     let (password_length, set_password_length) = create_signal(0);
+    let (generator_options, set_generator_options) = create_signal(GeneratorOptions::default());
+    let (content_protection, set_content_protection) = create_signal(false);
+    let (soft_keyboard, set_soft_keyboard) = create_signal(false);
+    let (lock_on_minimize, set_lock_on_minimize) = create_signal(false);
+    let (lock_on_blur, set_lock_on_blur) = create_signal(false);
+    let (lock_delay, set_lock_delay) = create_signal(30);
+    let (lock_on_idle, set_lock_on_idle) = create_signal(0);
+    let (lock_on_suspend, set_lock_on_suspend) = create_signal(false);
+    let (decrypt_limit, set_decrypt_limit) = create_signal(0);
+    let (key_info, set_key_info) = create_signal(None::<KeyInfo>);
+    let (crypto_info, set_crypto_info) = create_signal(None::<CryptoInfo>);
+    let (rotation_password, set_rotation_password) = create_signal(String::new());
+    let (rotation_progress, set_rotation_progress) = create_signal(None::<RotationProgress>);
+    let (encrypt_metadata, set_encrypt_metadata) = create_signal(false);
+    let (field_progress, set_field_progress) = create_signal(None::<RotationProgress>);
+    let (has_pin, set_has_pin) = create_signal(false);
+    let (pin_password, set_pin_password) = create_signal(String::new());
+    let (new_pin, set_new_pin) = create_signal(String::new());
 
     let shield_icon = create_memo(move |_| "shield-check");
     let clock_icon = create_memo(move |_| "clock");
+    let screen_icon = create_memo(move |_| "computer-desktop");
+    let lock_icon = create_memo(move |_| "lock-closed");
+    let key_icon = create_memo(move |_| "key");
+    let warning_icon = create_memo(move |_| "exclamation-triangle");
 
     let show_password_icon = create_memo(move |_| {
         if show_passwords.get() {
@@ -66,9 +174,60 @@ pub fn SecuritySettings() -> impl IntoView {
             set_auto_logout.set(time);
         }
 
-        let response = invoke("get_default_generator_length", wasm_bindgen::JsValue::NULL).await;
-        if let Ok(settings) = serde_wasm_bindgen::from_value::<PasswordGeneratorLength>(response) {
-            set_password_length.set(settings.default_length);
+        let response = invoke("get_session_max_lifetime", wasm_bindgen::JsValue::NULL).await;
+        if let Ok(lifetime) = serde_wasm_bindgen::from_value::<u64>(response) {
+            set_session_lifetime.set(lifetime);
+        }
+
+        if let Some(defaults) = load_generator_defaults().await {
+            set_password_length.set(defaults.default_length);
+            set_generator_options.set(defaults.options);
+        }
+
+        let response = invoke("get_content_protection", wasm_bindgen::JsValue::NULL).await;
+        if let Ok(enabled) = serde_wasm_bindgen::from_value::<bool>(response) {
+            set_content_protection.set(enabled);
+        }
+
+        let response = invoke("get_soft_keyboard", wasm_bindgen::JsValue::NULL).await;
+        if let Ok(enabled) = serde_wasm_bindgen::from_value::<bool>(response) {
+            set_soft_keyboard.set(enabled);
+        }
+
+        let response = invoke("get_decrypt_limit", wasm_bindgen::JsValue::NULL).await;
+        if let Ok(limit) = serde_wasm_bindgen::from_value::<u32>(response) {
+            set_decrypt_limit.set(limit);
+        }
+
+        let response = invoke("get_key_info", wasm_bindgen::JsValue::NULL).await;
+        if let Ok(info) = serde_wasm_bindgen::from_value::<KeyInfo>(response) {
+            set_key_info.set(Some(info));
+        }
+
+        let response = invoke("get_crypto_info", wasm_bindgen::JsValue::NULL).await;
+        if let Ok(info) = serde_wasm_bindgen::from_value::<CryptoInfo>(response) {
+            set_encrypt_metadata.set(info.encrypt_metadata);
+            if info.pending_field_migration > 0 {
+                set_field_progress.set(Some(RotationProgress {
+                    done: 0,
+                    total: info.pending_field_migration,
+                }));
+            }
+            set_crypto_info.set(Some(info));
+        }
+
+        let response = invoke("has_confirmation_pin", wasm_bindgen::JsValue::NULL).await;
+        if let Ok(enabled) = serde_wasm_bindgen::from_value::<bool>(response) {
+            set_has_pin.set(enabled);
+        }
+
+        let response = invoke("get_auto_lock_settings", wasm_bindgen::JsValue::NULL).await;
+        if let Ok(settings) = serde_wasm_bindgen::from_value::<AutoLockSettings>(response) {
+            set_lock_on_minimize.set(settings.lock_on_minimize);
+            set_lock_on_blur.set(settings.lock_on_blur);
+            set_lock_delay.set(settings.lock_delay);
+            set_lock_on_idle.set(settings.lock_on_idle);
+            set_lock_on_suspend.set(settings.lock_on_suspend);
         }
 
         set_is_loading.set(false);
@@ -77,8 +236,9 @@ pub fn SecuritySettings() -> impl IntoView {
     let handle_password_generator = move |_| {
         set_is_loading.set(true);
         spawn_local(async move {
-            let args = serde_wasm_bindgen::to_value(&PasswordGeneratorArgs {
+            let args = serde_wasm_bindgen::to_value(&GeneratePasswordArgs {
                 length: password_length.get(),
+                options: generator_options.get(),
             })
             .unwrap();
             let response = invoke("generate_password", args).await;
@@ -114,18 +274,133 @@ pub fn SecuritySettings() -> impl IntoView {
         });
     };
 
+    spawn_local(async move {
+        let closure = Closure::wrap(Box::new(move |event: JsValue| {
+            if let Ok(event) = serde_wasm_bindgen::from_value::<RotationProgressEvent>(event) {
+                set_rotation_progress.set(Some(event.payload));
+            }
+        }) as Box<dyn FnMut(JsValue)>);
+
+        listen("vault-rotation-progress", &closure).await;
+        closure.forget();
+    });
+
+    spawn_local(async move {
+        let closure = Closure::wrap(Box::new(move |event: JsValue| {
+            if let Ok(event) = serde_wasm_bindgen::from_value::<RotationProgressEvent>(event) {
+                set_field_progress.set(Some(event.payload));
+            }
+        }) as Box<dyn FnMut(JsValue)>);
+
+        listen("field-encryption-progress", &closure).await;
+        closure.forget();
+    });
+
+    let handle_metadata_encryption = move |ev| {
+        let enabled = event_target_checked(&ev);
+        set_encrypt_metadata.set(enabled);
+        spawn_local(async move {
+            let args =
+                serde_wasm_bindgen::to_value(&SetMetadataEncryptionArgs { enabled }).unwrap();
+            let response = invoke("set_metadata_encryption", args).await;
+            if serde_wasm_bindgen::from_value::<()>(response.clone()).is_err() {
+                set_encrypt_metadata.set(!enabled);
+                set_error.set(format!(
+                    "Der Paranoid-Modus konnte nicht geändert werden: {}",
+                    response
+                        .as_string()
+                        .unwrap_or("Unbekannter Fehler".to_string())
+                ));
+            }
+        });
+    };
+
+    let handle_rotate_keys = move |_| {
+        if rotation_password.get().is_empty() {
+            set_error.set("Bitte gib dein Master-Passwort ein".to_string());
+            return;
+        }
+        set_rotation_progress.set(Some(RotationProgress { done: 0, total: 0 }));
+        spawn_local(async move {
+            let args = serde_wasm_bindgen::to_value(&RotateVaultKeysArgs {
+                master_pass: rotation_password.get(),
+            })
+            .unwrap();
+            let response = invoke("rotate_vault_keys", args).await;
+            if serde_wasm_bindgen::from_value::<()>(response.clone()).is_ok() {
+                let window = web_sys::window().unwrap();
+                window.location().reload().unwrap();
+            } else {
+                set_rotation_progress.set(None);
+                set_error.set(format!(
+                    "Die Schlüssel konnten nicht erneuert werden: {}",
+                    response
+                        .as_string()
+                        .unwrap_or("Unbekannter Fehler".to_string())
+                ));
+            }
+        });
+    };
+
+    let update_confirmation_pin = move |pin: Option<String>| {
+        if pin_password.get().is_empty() {
+            set_error.set("Bitte gib dein Master-Passwort ein".to_string());
+            return;
+        }
+        let removed = pin.is_none();
+        spawn_local(async move {
+            let args = serde_wasm_bindgen::to_value(&SetConfirmationPinArgs {
+                master_pass: pin_password.get(),
+                pin,
+            })
+            .unwrap();
+            let response = invoke("set_confirmation_pin", args).await;
+            if serde_wasm_bindgen::from_value::<()>(response.clone()).is_ok() {
+                set_has_pin.set(!removed);
+                set_pin_password.set(String::new());
+                set_new_pin.set(String::new());
+                set_error.set(if removed {
+                    "Die Bestätigungs-PIN wurde entfernt".to_string()
+                } else {
+                    "Die Bestätigungs-PIN wurde gespeichert".to_string()
+                });
+            } else {
+                set_error.set(format!(
+                    "Die Bestätigungs-PIN konnte nicht geändert werden: {}",
+                    response
+                        .as_string()
+                        .unwrap_or("Unbekannter Fehler".to_string())
+                ));
+            }
+        });
+    };
+
     let handle_save_security_settings = move |ev: SubmitEvent| {
         ev.prevent_default();
         set_is_loading.set(true);
         spawn_local(async move {
             let args = serde_wasm_bindgen::to_value(&SaveSecuritySettingsArgs {
                 auto_logout_duration: auto_logout.get(),
+                session_max_lifetime: session_lifetime.get(),
+                content_protection: content_protection.get(),
+                soft_keyboard: soft_keyboard.get(),
+                lock_on_minimize: lock_on_minimize.get(),
+                lock_on_blur: lock_on_blur.get(),
+                lock_delay: lock_delay.get(),
+                lock_on_idle: lock_on_idle.get(),
+                lock_on_suspend: lock_on_suspend.get(),
+                decrypt_limit: decrypt_limit.get(),
             })
             .unwrap();
             let response = invoke("save_security_settings", args).await;
             if serde_wasm_bindgen::from_value::<()>(response).is_ok() {
                 set_error
                     .set("Die Sicherheitseinstellungen wurden erfolgreich gespeichert".to_string());
+            } else {
+                set_error.set(
+                    "Die maximale Sitzungsdauer darf nicht kürzer als der automatische Logout sein"
+                        .to_string(),
+                );
             }
             set_is_loading.set(false);
         });
@@ -263,6 +538,199 @@ pub fn SecuritySettings() -> impl IntoView {
                                         </div>
                                 </fieldset>
 
+                                {move || crypto_info.get().map(|info| view! {
+                                    <fieldset class="space-y-4">
+                                        <h3 class="text-lg font-semibold bg-gradient-primary bg-clip-text text-transparent mb-4">
+                                            <div class="flex items-center space-x-2">
+                                                <Icon icon=lock_icon.into() class="w-5 h-5 text-primary-100" />
+                                                <span>"Verschlüsselungsstatus"</span>
+                                            </div>
+                                        </h3>
+                                        <div class="grid grid-cols-2 gap-4">
+                                            <div class="p-4 border border-gray-700 rounded-lg">
+                                                <div class="text-sm text-gray-400">"Datenbank"</div>
+                                                <div class="text-sm font-bold text-white">
+                                                    {format!("SQLCipher {}", info.sqlcipher_version)}
+                                                </div>
+                                            </div>
+                                            <div class="p-4 border border-gray-700 rounded-lg">
+                                                <div class="text-sm text-gray-400">"Verschlüsselung der Einträge"</div>
+                                                <div class="text-sm font-bold text-white">
+                                                    {match info.cipher.as_str() {
+                                                        "chacha20-poly1305" => "ChaCha20-Poly1305",
+                                                        _ => "AES-256-GCM",
+                                                    }}
+                                                </div>
+                                            </div>
+                                            <div class="p-4 border border-gray-700 rounded-lg">
+                                                <div class="text-sm text-gray-400">"Schlüsselableitung"</div>
+                                                <div class="text-sm font-bold text-white">{info.kdf}</div>
+                                                <div class="text-xs text-gray-400">
+                                                    {format!("{} Iterationen, Version {}", info.iterations, info.key_version)}
+                                                </div>
+                                            </div>
+                                            <div class="p-4 border border-gray-700 rounded-lg">
+                                                <div class="text-sm text-gray-400">"Salt-Fingerabdruck"</div>
+                                                <div class="text-sm font-bold text-white font-mono">{info.salt_fingerprint}</div>
+                                            </div>
+                                        </div>
+                                        <p class="text-sm text-gray-400">
+                                            {match info.last_rotation {
+                                                Some(timestamp) => format!("Schlüssel zuletzt erneuert am {}", format_timestamp(&timestamp)),
+                                                None => "Die Schlüssel wurden noch nie erneuert".to_string(),
+                                            }}
+                                        </p>
+                                    </fieldset>
+                                })}
+
+                                <fieldset class="space-y-4">
+                                    <h3 class="text-lg font-semibold bg-gradient-primary bg-clip-text text-transparent mb-4">
+                                        <div class="flex items-center space-x-2">
+                                            <Icon icon=shield_icon.into() class="w-5 h-5 text-primary-100" />
+                                            <span>"Paranoid-Modus"</span>
+                                        </div>
+                                    </h3>
+                                    <div class="flex items-center space-x-2">
+                                        <input
+                                            type="checkbox"
+                                            id="encrypt-metadata"
+                                            class="w-4 h-4 bg-background border-gray-600 rounded focus:ring-primary-100"
+                                            on:change=handle_metadata_encryption
+                                            prop:checked=encrypt_metadata
+                                        />
+                                        <label for="encrypt-metadata" class="text-white text-sm font-bold">
+                                            "Benutzernamen, URLs und Notizen zusätzlich verschlüsseln"
+                                        </label>
+                                    </div>
+                                    <p class="mt-1 text-sm text-gray-400">
+                                        "Verschlüsselt diese Felder wie die Passwörter einzeln, zusätzlich zur Verschlüsselung der Datenbank. Bestehende Einträge werden im Hintergrund umgestellt"
+                                    </p>
+                                    {move || field_progress.get().filter(|progress| progress.done < progress.total).map(|progress| view! {
+                                        <div class="space-y-1">
+                                            <div class="w-full h-2 rounded bg-gray-600 overflow-hidden">
+                                                <div
+                                                    class="h-2 bg-primary-100 transition-all"
+                                                    style=format!("width: {}%", progress.done * 100 / progress.total.max(1))
+                                                ></div>
+                                            </div>
+                                            <p class="text-sm text-gray-400">
+                                                {format!("{} von {} Einträgen umgestellt", progress.done, progress.total)}
+                                            </p>
+                                        </div>
+                                    })}
+                                </fieldset>
+
+                                <fieldset class="space-y-4">
+                                    <h3 class="text-lg font-semibold bg-gradient-primary bg-clip-text text-transparent mb-4">
+                                        <div class="flex items-center space-x-2">
+                                            <Icon icon=key_icon.into() class="w-5 h-5 text-primary-100" />
+                                            <span>"Schlüssel erneuern"</span>
+                                        </div>
+                                    </h3>
+                                    {move || key_info.get().map(|info| view! {
+                                        <p class="text-sm text-gray-400">
+                                            {format!("Verschlüsselung: {}", match info.cipher.as_str() {
+                                                "chacha20-poly1305" => "ChaCha20-Poly1305",
+                                                _ => "AES-256-GCM",
+                                            })}
+                                        </p>
+                                        <div class="flex items-center text-sm" class:text-primary-100=info.outdated class:text-gray-400=!info.outdated>
+                                            {info.outdated.then(|| view! {
+                                                <Icon icon=warning_icon.into() class="w-4 h-4 mr-2" />
+                                            })}
+                                            {if info.outdated {
+                                                format!("Schlüsselversion {} ist veraltet, eine Erneuerung wird empfohlen", info.version)
+                                            } else {
+                                                format!("Schlüsselversion {} ist aktuell", info.version)
+                                            }}
+                                        </div>
+                                    })}
+                                    <input
+                                        type="password"
+                                        class="shadow appearance-none border border-gray-600 rounded w-full py-2 px-3 bg-background text-white leading-tight focus:outline-none focus:border-primary-100"
+                                        placeholder="Master-Passwort zur Bestätigung"
+                                        on:input=move |ev| set_rotation_password.set(event_target_value(&ev))
+                                        prop:value=rotation_password
+                                    />
+                                    <p class="mt-1 text-sm text-gray-400">
+                                        "Verschlüsselt den Tresor mit einem neuen Salt und den aktuellen Schlüsselparametern. Vorher wird automatisch ein Backup erstellt, danach meldest du dich neu an"
+                                    </p>
+                                    {move || rotation_progress.get().map(|progress| view! {
+                                        <div class="space-y-1">
+                                            <div class="w-full h-2 rounded bg-gray-600 overflow-hidden">
+                                                <div
+                                                    class="h-2 bg-primary-100 transition-all"
+                                                    style=format!("width: {}%", progress.done * 100 / progress.total.max(1))
+                                                ></div>
+                                            </div>
+                                            <p class="text-sm text-gray-400">
+                                                {format!("{} von {} Datensätzen neu verschlüsselt", progress.done, progress.total)}
+                                            </p>
+                                        </div>
+                                    })}
+                                    <div class="flex justify-end pt-4 border-t border-gray-600">
+                                        <button
+                                            type="button"
+                                            class="bg-gradient-primary text-white font-bold py-2 px-8 rounded focus:outline-none hover:opacity-90 transition-opacity disabled:opacity-50"
+                                            prop:disabled=move || rotation_progress.get().is_some()
+                                            on:click=handle_rotate_keys
+                                        >
+                                            "Schlüssel erneuern"
+                                        </button>
+                                    </div>
+                                </fieldset>
+
+                                <fieldset class="space-y-4">
+                                    <h3 class="text-lg font-semibold bg-gradient-primary bg-clip-text text-transparent mb-4">
+                                        <div class="flex items-center space-x-2">
+                                            <Icon icon=lock_icon.into() class="w-5 h-5 text-primary-100" />
+                                            <span>"Bestätigungs-PIN"</span>
+                                        </div>
+                                    </h3>
+                                    <p class="text-sm text-gray-400">
+                                        {move || if has_pin.get() {
+                                            "Eine Bestätigungs-PIN ist eingerichtet"
+                                        } else {
+                                            "Es ist keine Bestätigungs-PIN eingerichtet"
+                                        }}
+                                    </p>
+                                    <input
+                                        type="password"
+                                        class="shadow appearance-none border border-gray-600 rounded w-full py-2 px-3 bg-background text-white leading-tight focus:outline-none focus:border-primary-100"
+                                        placeholder="Master-Passwort zur Bestätigung"
+                                        on:input=move |ev| set_pin_password.set(event_target_value(&ev))
+                                        prop:value=pin_password
+                                    />
+                                    <input
+                                        type="password"
+                                        class="shadow appearance-none border border-gray-600 rounded w-full py-2 px-3 bg-background text-white leading-tight focus:outline-none focus:border-primary-100"
+                                        placeholder="Neue PIN (mindestens 4 Zeichen)"
+                                        on:input=move |ev| set_new_pin.set(event_target_value(&ev))
+                                        prop:value=new_pin
+                                    />
+                                    <p class="mt-1 text-sm text-gray-400">
+                                        "Vor dem Export der Passwörter und dem Wiederherstellen eines Backups wird zusätzlich diese PIN abgefragt. Sie muss sich vom Master-Passwort unterscheiden"
+                                    </p>
+                                    <div class="flex justify-end space-x-4 pt-4 border-t border-gray-600">
+                                        <Show when=move || has_pin.get()>
+                                            <button
+                                                type="button"
+                                                class="bg-background text-white font-bold py-2 px-8 rounded border border-gray-600 focus:outline-none hover:border-primary-100 transition-colors"
+                                                on:click=move |_| update_confirmation_pin(None)
+                                            >
+                                                "PIN entfernen"
+                                            </button>
+                                        </Show>
+                                        <button
+                                            type="button"
+                                            class="bg-gradient-primary text-white font-bold py-2 px-8 rounded focus:outline-none hover:opacity-90 transition-opacity"
+                                            on:click=move |_| update_confirmation_pin(Some(new_pin.get()))
+                                        >
+                                            "PIN speichern"
+                                        </button>
+                                    </div>
+                                </fieldset>
+
                                 <fieldset class="space-y-4">
                                     <h3 class="text-lg font-semibold bg-gradient-primary bg-clip-text text-transparent mb-4">
                                         <div class="flex items-center space-x-2">
@@ -282,8 +750,158 @@ pub fn SecuritySettings() -> impl IntoView {
                                         <span class="text-white">"Minuten"</span>
                                     </div>
                                     <p class="mt-1 text-sm text-gray-400">
-                                        "Die Zeit in Minuten, nach der du aus Sicherheitsgründen automatisch ausgeloggt wirst (1-60 Minuten)"
+                                        "Die Zeit ohne Aktivität in Minuten, nach der du aus Sicherheitsgründen automatisch ausgeloggt wirst (1-60 Minuten)"
+                                    </p>
+                                    <div class="flex items-center space-x-4">
+                                        <input
+                                            type="number"
+                                            min="1"
+                                            max="1440"
+                                            class="w-20 p-2 rounded bg-background text-white border border-gray-600 focus:border-primary-100 focus:outline-none"
+                                            on:input=move |ev| set_session_lifetime.set(event_target_value(&ev).parse().unwrap_or(480))
+                                            prop:value=session_lifetime
+                                        />
+                                        <span class="text-white">"Minuten"</span>
+                                    </div>
+                                    <p class="mt-1 text-sm text-gray-400">
+                                        "Die maximale Dauer einer Sitzung in Minuten, unabhängig von deiner Aktivität (1-1440 Minuten)"
+                                    </p>
+                                </fieldset>
+
+                                <fieldset class="space-y-4">
+                                    <h3 class="text-lg font-semibold bg-gradient-primary bg-clip-text text-transparent mb-4">
+                                        <div class="flex items-center space-x-2">
+                                            <Icon icon=screen_icon.into() class="w-5 h-5 text-primary-100" />
+                                            <span>"Bildschirmschutz"</span>
+                                        </div>
+                                    </h3>
+                                    <div class="flex items-center space-x-3">
+                                        <input
+                                            type="checkbox"
+                                            id="content-protection"
+                                            class="w-4 h-4 bg-background border-gray-600 rounded focus:ring-primary-100"
+                                            on:change=move |ev| set_content_protection.set(event_target_checked(&ev))
+                                            prop:checked=content_protection
+                                        />
+                                        <label for="content-protection" class="text-white text-sm font-bold">
+                                            "Screenshots und Bildschirmfreigabe blockieren"
+                                        </label>
+                                    </div>
+                                    <p class="mt-1 text-sm text-gray-400">
+                                        "Screenshots und Bildschirmfreigaben zeigen nur ein schwarzes Fenster, damit angezeigte Passwörter nicht aufgezeichnet werden (nur Windows und macOS)"
+                                    </p>
+                                    <div class="flex items-center space-x-3">
+                                        <input
+                                            type="checkbox"
+                                            id="soft-keyboard"
+                                            class="w-4 h-4 bg-background border-gray-600 rounded focus:ring-primary-100"
+                                            on:change=move |ev| set_soft_keyboard.set(event_target_checked(&ev))
+                                            prop:checked=soft_keyboard
+                                        />
+                                        <label for="soft-keyboard" class="text-white text-sm font-bold">
+                                            "Bildschirmtastatur für das Master-Passwort anbieten"
+                                        </label>
+                                    </div>
+                                    <p class="mt-1 text-sm text-gray-400">
+                                        "Beim Anmelden kann das Master-Passwort über eine zufällig angeordnete Tastatur eingegeben werden, damit einfache Keylogger es nicht mitschneiden. Empfohlen für gemeinsam genutzte Rechner"
                                     </p>
+                                    <div>
+                                        <label class="block text-white text-sm font-bold mb-2">
+                                            "Entschlüsselungen begrenzen"
+                                        </label>
+                                        <div class="flex items-center space-x-4">
+                                            <input
+                                                type="number"
+                                                min="0"
+                                                max="1000"
+                                                class="w-20 p-2 rounded bg-background text-white border border-gray-600 focus:border-primary-100 focus:outline-none"
+                                                on:input=move |ev| set_decrypt_limit.set(event_target_value(&ev).parse().unwrap_or(0))
+                                                prop:value=decrypt_limit
+                                            />
+                                            <span class="text-white">"pro Minute"</span>
+                                        </div>
+                                        <p class="mt-1 text-sm text-gray-400">
+                                            "Wie viele Passwörter, Anhänge, ablaufende Notizen und Wiederherstellungscodes pro Minute ohne Master-Passwort angezeigt oder kopiert werden können, damit Schadsoftware den Tresor nicht in Sekunden auslesen kann. Darüber hinaus ist das Master-Passwort nötig. 0 deaktiviert die Begrenzung"
+                                        </p>
+                                    </div>
+                                </fieldset>
+
+                                <fieldset class="space-y-4">
+                                    <h3 class="text-lg font-semibold bg-gradient-primary bg-clip-text text-transparent mb-4">
+                                        <div class="flex items-center space-x-2">
+                                            <Icon icon=lock_icon.into() class="w-5 h-5 text-primary-100" />
+                                            <span>"Automatische Sperre"</span>
+                                        </div>
+                                    </h3>
+                                    <div class="flex items-center space-x-3">
+                                        <input
+                                            type="checkbox"
+                                            id="lock-on-minimize"
+                                            class="w-4 h-4 bg-background border-gray-600 rounded focus:ring-primary-100"
+                                            on:change=move |ev| set_lock_on_minimize.set(event_target_checked(&ev))
+                                            prop:checked=lock_on_minimize
+                                        />
+                                        <label for="lock-on-minimize" class="text-white text-sm font-bold">
+                                            "Sperren, wenn das Fenster minimiert wird"
+                                        </label>
+                                    </div>
+                                    <div class="flex items-center space-x-3">
+                                        <input
+                                            type="checkbox"
+                                            id="lock-on-blur"
+                                            class="w-4 h-4 bg-background border-gray-600 rounded focus:ring-primary-100"
+                                            on:change=move |ev| set_lock_on_blur.set(event_target_checked(&ev))
+                                            prop:checked=lock_on_blur
+                                        />
+                                        <label for="lock-on-blur" class="text-white text-sm font-bold">
+                                            "Sperren, wenn das Fenster den Fokus verliert"
+                                        </label>
+                                    </div>
+                                    <div class="flex items-center space-x-4">
+                                        <input
+                                            type="number"
+                                            min="0"
+                                            max="3600"
+                                            class="w-20 p-2 rounded bg-background text-white border border-gray-600 focus:border-primary-100 focus:outline-none"
+                                            on:input=move |ev| set_lock_delay.set(event_target_value(&ev).parse().unwrap_or(30))
+                                            prop:value=lock_delay
+                                        />
+                                        <span class="text-white">"Sekunden"</span>
+                                    </div>
+                                    <p class="mt-1 text-sm text-gray-400">
+                                        "Der Tresor wird nach dieser Zeit gesperrt, wenn du nicht zum Fenster zurückkehrst. Empfohlen für gemeinsam genutzte Rechner"
+                                    </p>
+                                    <div class="flex items-center space-x-3">
+                                        <input
+                                            type="checkbox"
+                                            id="lock-on-suspend"
+                                            class="w-4 h-4 bg-background border-gray-600 rounded focus:ring-primary-100"
+                                            on:change=move |ev| set_lock_on_suspend.set(event_target_checked(&ev))
+                                            prop:checked=lock_on_suspend
+                                        />
+                                        <label for="lock-on-suspend" class="text-white text-sm font-bold">
+                                            "Sperren, wenn der Rechner in den Ruhezustand wechselt"
+                                        </label>
+                                    </div>
+                                    <div>
+                                        <label class="block text-white text-sm font-bold mb-2">
+                                            "Sperren bei Inaktivität des Systems"
+                                        </label>
+                                        <div class="flex items-center space-x-4">
+                                            <input
+                                                type="number"
+                                                min="0"
+                                                max="1440"
+                                                class="w-20 p-2 rounded bg-background text-white border border-gray-600 focus:border-primary-100 focus:outline-none"
+                                                on:input=move |ev| set_lock_on_idle.set(event_target_value(&ev).parse().unwrap_or(0))
+                                                prop:value=lock_on_idle
+                                            />
+                                            <span class="text-white">"Minuten"</span>
+                                        </div>
+                                        <p class="mt-1 text-sm text-gray-400">
+                                            "Der Tresor wird gesperrt, wenn Tastatur und Maus so lange im gesamten System nicht benutzt wurden, auch wenn du in anderen Programmen warst. 0 deaktiviert die Sperre"
+                                        </p>
+                                    </div>
                                 </fieldset>
 
                                 <div class="flex justify-end pt-4 border-t border-gray-600">
@@ -0,0 +1,235 @@
+use crate::app::invoke;
+use leptos::*;
+use serde::{Deserialize, Serialize};
+
+use crate::components::icons::Icon;
+
+#[derive(Serialize)]
+struct SnapshotEntryArgs {
+    id: i32,
+}
+
+#[derive(Deserialize)]
+struct ClipboardProtection {}
+
+#[derive(Clone, Deserialize)]
+struct SnapshotResponse {
+    name: String,
+    created_at: Option<String>,
+    entries: Vec<SnapshotEntry>,
+}
+
+#[derive(Clone, Deserialize)]
+struct SnapshotEntry {
+    id: i32,
+    service: String,
+    username: String,
+    url: String,
+    notes: String,
+    updated_at: String,
+}
+
+fn format_date(timestamp: &str) -> String {
+    let date = web_sys::js_sys::Date::new(&wasm_bindgen::JsValue::from_str(timestamp));
+    format!(
+        "{:02}.{:02}.{}",
+        date.get_date(),
+        date.get_month() + 1,
+        date.get_full_year()
+    )
+}
+
+#[component]
+fn SnapshotRow(entry: SnapshotEntry, #[prop(into)] on_error: Callback<String>) -> impl IntoView {
+    let (password, set_password) = create_signal(None::<String>);
+    let (is_copied, set_is_copied) = create_signal(false);
+
+    let eye_icon = create_memo(move |_| {
+        if password.get().is_some() {
+            "eye-slash"
+        } else {
+            "eye"
+        }
+    });
+    let clipboard_icon = create_memo(move |_| {
+        if is_copied.get() {
+            "check"
+        } else {
+            "clipboard"
+        }
+    });
+
+    let id = entry.id;
+    let toggle_password = move |_| {
+        if password.get().is_some() {
+            set_password.set(None);
+            return;
+        }
+        spawn_local(async move {
+            let args = serde_wasm_bindgen::to_value(&SnapshotEntryArgs { id }).unwrap();
+            match serde_wasm_bindgen::from_value::<String>(
+                invoke("decrypt_snapshot_password", args).await,
+            ) {
+                Ok(decrypted) => set_password.set(Some(decrypted)),
+                Err(_) => on_error.call("Passwort konnte nicht entschlüsselt werden".to_string()),
+            }
+        });
+    };
+
+    let copy_password = move |_| {
+        spawn_local(async move {
+            let args = serde_wasm_bindgen::to_value(&SnapshotEntryArgs { id }).unwrap();
+            let response = invoke("copy_snapshot_password", args).await;
+            match serde_wasm_bindgen::from_value::<ClipboardProtection>(response.clone()) {
+                Ok(_) => set_is_copied.set(true),
+                Err(_) => on_error.call(
+                    response
+                        .as_string()
+                        .unwrap_or("Unbekannter Fehler".to_string()),
+                ),
+            }
+        });
+    };
+
+    view! {
+        <tr class="border-b border-gray-700 last:border-0 text-white align-top">
+            <td class="px-4 py-2">
+                <div>{entry.service}</div>
+                <div class="text-xs text-gray-400 truncate">{entry.url}</div>
+            </td>
+            <td class="px-4 py-2">{entry.username}</td>
+            <td class="px-4 py-2 font-mono">
+                {move || password.get().unwrap_or("••••••••".to_string())}
+            </td>
+            <td class="px-4 py-2 text-gray-400 whitespace-pre-wrap">{entry.notes}</td>
+            <td class="px-4 py-2 text-gray-400">{format_date(&entry.updated_at)}</td>
+            <td class="px-4 py-2">
+                <div class="flex space-x-1">
+                    <button
+                        class="p-1 text-gray-400 hover:text-primary-100"
+                        title="Anzeigen"
+                        on:click=toggle_password
+                    >
+                        <Icon icon=eye_icon.into() class="w-4 h-4" />
+                    </button>
+                    <button
+                        class="p-1 text-gray-400 hover:text-primary-100"
+                        title="Kopieren"
+                        on:click=copy_password
+                    >
+                        <Icon icon=clipboard_icon.into() class="w-4 h-4" />
+                    </button>
+                </div>
+            </td>
+        </tr>
+    }
+}
+
+#[component]
+pub fn SnapshotView() -> impl IntoView {
+    let (snapshot, set_snapshot) = create_signal(None::<SnapshotResponse>);
+    let (search, set_search) = create_signal(String::new());
+    let (error, set_error) = create_signal(String::new());
+
+    let clock_icon = create_memo(move |_| "clock");
+    let search_icon = create_memo(move |_| "magnifying-glass");
+
+    spawn_local(async move {
+        let response = invoke("get_snapshot", wasm_bindgen::JsValue::NULL).await;
+        match serde_wasm_bindgen::from_value::<SnapshotResponse>(response.clone()) {
+            Ok(value) => set_snapshot.set(Some(value)),
+            Err(_) => set_error.set(
+                response
+                    .as_string()
+                    .unwrap_or("Unbekannter Fehler".to_string()),
+            ),
+        }
+    });
+
+    let close = move |_| {
+        spawn_local(async move {
+            invoke("close_snapshot", wasm_bindgen::JsValue::NULL).await;
+        });
+    };
+
+    view! {
+        <div class="space-y-4">
+            <div class="flex justify-between items-center">
+                <h2 class="text-xl font-bold bg-gradient-primary bg-clip-text text-transparent flex items-center">
+                    <Icon icon=clock_icon.into() class="w-8 h-8 mr-2 text-primary-100" />
+                    "Snapshot"
+                </h2>
+                <button
+                    class="px-4 py-2 text-sm text-white border border-primary-100 rounded hover:bg-primary-400/10 transition-all duration-200"
+                    on:click=close
+                >
+                    "Schließen"
+                </button>
+            </div>
+
+            {move || snapshot.get().map(|snapshot| view! {
+                <p class="text-sm text-gray-400">
+                    {format!(
+                        "{}{} · {} Einträge. Nur zur Ansicht, Änderungen sind nicht möglich und deine aktuelle Datenbank bleibt unverändert.",
+                        snapshot.name,
+                        snapshot
+                            .created_at
+                            .map(|created_at| format!(" vom {}", format_date(&created_at)))
+                            .unwrap_or_default(),
+                        snapshot.entries.len()
+                    )}
+                </p>
+            })}
+
+            {move || (!error.get().is_empty()).then(|| view! {
+                <div class="text-primary-100 text-sm text-center">{error.get()}</div>
+            })}
+
+            <div class="relative">
+                <Icon icon=search_icon.into() class="w-4 h-4 text-gray-400 absolute left-3 top-3" />
+                <input
+                    type="text"
+                    class="w-full pl-9 p-2 rounded bg-background text-white border border-gray-600 focus:outline-none focus:border-primary-100"
+                    placeholder="Suchen..."
+                    on:input=move |ev| set_search.set(event_target_value(&ev))
+                    prop:value=search
+                />
+            </div>
+
+            <div class="border border-gray-700 rounded-lg overflow-x-auto">
+                <table class="w-full text-sm text-left">
+                    <thead class="text-gray-400 border-b border-gray-700">
+                        <tr>
+                            <th class="px-4 py-2">"Service"</th>
+                            <th class="px-4 py-2">"Benutzername"</th>
+                            <th class="px-4 py-2">"Passwort"</th>
+                            <th class="px-4 py-2">"Notizen"</th>
+                            <th class="px-4 py-2">"Geändert"</th>
+                            <th class="px-4 py-2"></th>
+                        </tr>
+                    </thead>
+                    <tbody>
+                        {move || {
+                            let query = search.get().to_lowercase();
+                            snapshot
+                                .get()
+                                .map(|snapshot| snapshot.entries)
+                                .unwrap_or_default()
+                                .into_iter()
+                                .filter(|entry| {
+                                    query.is_empty()
+                                        || entry.service.to_lowercase().contains(&query)
+                                        || entry.username.to_lowercase().contains(&query)
+                                        || entry.url.to_lowercase().contains(&query)
+                                })
+                                .map(|entry| view! {
+                                    <SnapshotRow entry=entry on_error=move |e| set_error.set(e) />
+                                })
+                                .collect_view()
+                        }}
+                    </tbody>
+                </table>
+            </div>
+        </div>
+    }
+}
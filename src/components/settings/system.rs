@@ -1,16 +1,79 @@
 use leptos::{ev::SubmitEvent, *};
+use serde::{Deserialize, Serialize};
 
 use crate::{app::invoke, components::icons::Icon};
 
+#[derive(Serialize)]
+struct ToggleAutostartArgs {
+    enable: bool,
+    mode: String,
+}
+
+#[derive(Serialize)]
+struct EnableArgs {
+    enable: bool,
+}
+
+#[derive(Serialize)]
+struct OpacityArgs {
+    opacity: u8,
+}
+
+#[derive(Deserialize)]
+struct WindowPin {
+    opacity: u8,
+}
+
+#[derive(Serialize)]
+struct ShortcutArgs {
+    shortcut: Option<String>,
+}
+
+#[derive(Serialize)]
+struct BrowserSettingsArgs {
+    enabled: bool,
+    #[serde(rename = "extensionIds")]
+    extension_ids: Vec<String>,
+}
+
+#[derive(Serialize)]
+struct SiteArgs {
+    site: String,
+}
+
+#[derive(Deserialize)]
+struct BrowserSettings {
+    enabled: bool,
+    extension_ids: Vec<String>,
+    authorized_sites: Vec<String>,
+}
+
 #[component]
 pub fn SystemSettings() -> impl IntoView {
     let (auto_start, set_auto_start) = create_signal(false);
+    let (autostart_mode, set_autostart_mode) = create_signal("locked".to_string());
+    let (offline_mode, set_offline_mode) = create_signal(false);
+    let (rpc_enabled, set_rpc_enabled) = create_signal(false);
+    let (minimize_to_tray, set_minimize_to_tray) = create_signal(false);
+    let (expiry_reminder, set_expiry_reminder) = create_signal(false);
+    let (window_opacity, set_window_opacity) = create_signal(100u8);
+    let (quick_search_shortcut, set_quick_search_shortcut) = create_signal(String::new());
+    let (browser_enabled, set_browser_enabled) = create_signal(false);
+    let (extension_ids, set_extension_ids) = create_signal(String::new());
+    let (authorized_sites, set_authorized_sites) = create_signal(Vec::<String>::new());
+    let (browser_status, set_browser_status) = create_signal(String::new());
+    let (breach_check, set_breach_check) = create_signal(false);
+    let (common_passwords_status, set_common_passwords_status) = create_signal(String::new());
     let (error, set_error) = create_signal(String::new());
     let (is_loading, set_is_loading) = create_signal(false);
 
     let rocket_icon = create_memo(move |_| "rocket-launch");
     let arrow_path_icon = create_memo(move |_| "arrow-path");
     let document_icon = create_memo(move |_| "document-text");
+    let link_icon = create_memo(move |_| "link");
+    let shield_icon = create_memo(move |_| "shield-check");
+    let search_icon = create_memo(move |_| "magnifying-glass");
+    let x_mark_icon = create_memo(move |_| "x-mark");
 
     spawn_local(async move {
         let response = invoke("is_autostart_enabled", wasm_bindgen::JsValue::NULL).await;
@@ -19,6 +82,55 @@ pub fn SystemSettings() -> impl IntoView {
         } else {
             set_error.set("Fehler beim Laden der Einstellungen".to_string());
         }
+
+        let response = invoke("get_autostart_mode", wasm_bindgen::JsValue::NULL).await;
+        if let Ok(value) = serde_wasm_bindgen::from_value::<String>(response) {
+            set_autostart_mode.set(value);
+        }
+
+        let response = invoke("get_offline_mode", wasm_bindgen::JsValue::NULL).await;
+        if let Ok(value) = serde_wasm_bindgen::from_value::<bool>(response) {
+            set_offline_mode.set(value);
+        } else {
+            set_error.set("Fehler beim Laden der Einstellungen".to_string());
+        }
+
+        let response = invoke("get_rpc_enabled", wasm_bindgen::JsValue::NULL).await;
+        if let Ok(value) = serde_wasm_bindgen::from_value::<bool>(response) {
+            set_rpc_enabled.set(value);
+        }
+
+        let response = invoke("get_minimize_to_tray", wasm_bindgen::JsValue::NULL).await;
+        if let Ok(value) = serde_wasm_bindgen::from_value::<bool>(response) {
+            set_minimize_to_tray.set(value);
+        }
+
+        let response = invoke("get_expiry_reminder", wasm_bindgen::JsValue::NULL).await;
+        if let Ok(value) = serde_wasm_bindgen::from_value::<bool>(response) {
+            set_expiry_reminder.set(value);
+        }
+
+        let response = invoke("get_window_pin", wasm_bindgen::JsValue::NULL).await;
+        if let Ok(value) = serde_wasm_bindgen::from_value::<WindowPin>(response) {
+            set_window_opacity.set(value.opacity);
+        }
+
+        let response = invoke("get_quick_search_shortcut", wasm_bindgen::JsValue::NULL).await;
+        if let Ok(value) = serde_wasm_bindgen::from_value::<Option<String>>(response) {
+            set_quick_search_shortcut.set(value.unwrap_or_default());
+        }
+
+        let response = invoke("get_browser_settings", wasm_bindgen::JsValue::NULL).await;
+        if let Ok(value) = serde_wasm_bindgen::from_value::<BrowserSettings>(response) {
+            set_browser_enabled.set(value.enabled);
+            set_extension_ids.set(value.extension_ids.join(", "));
+            set_authorized_sites.set(value.authorized_sites);
+        }
+
+        let response = invoke("get_breach_check", wasm_bindgen::JsValue::NULL).await;
+        if let Ok(value) = serde_wasm_bindgen::from_value::<bool>(response) {
+            set_breach_check.set(value);
+        }
     });
 
     let handle_open_log_folder = move |_| {
@@ -45,11 +157,150 @@ pub fn SystemSettings() -> impl IntoView {
         });
     };
 
+    let handle_update_common_passwords = move |_| {
+        set_common_passwords_status.set("Passwortliste wird aktualisiert...".to_string());
+        spawn_local(async move {
+            let response = invoke("update_common_passwords", wasm_bindgen::JsValue::NULL).await;
+            match serde_wasm_bindgen::from_value::<usize>(response) {
+                Ok(count) => set_common_passwords_status.set(format!(
+                    "Passwortliste mit {} Einträgen aktualisiert",
+                    count
+                )),
+                Err(_) => set_common_passwords_status
+                    .set("Passwortliste konnte nicht aktualisiert werden".to_string()),
+            }
+        });
+    };
+
+    let handle_install_browser_host = move |_| {
+        set_browser_status.set(String::new());
+        spawn_local(async move {
+            let response = invoke("install_browser_host", wasm_bindgen::JsValue::NULL).await;
+            match serde_wasm_bindgen::from_value::<usize>(response.clone()) {
+                Ok(count) => set_browser_status.set(format!("In {} Browsern registriert", count)),
+                Err(_) => set_browser_status.set(
+                    response
+                        .as_string()
+                        .unwrap_or("Unbekannter Fehler".to_string()),
+                ),
+            }
+        });
+    };
+
+    let handle_revoke_site = move |site: String| {
+        spawn_local(async move {
+            let args = serde_wasm_bindgen::to_value(&SiteArgs { site: site.clone() }).unwrap();
+            let response = invoke("revoke_browser_site", args).await;
+            if serde_wasm_bindgen::from_value::<()>(response).is_ok() {
+                set_authorized_sites.update(|sites| sites.retain(|s| *s != site));
+            } else {
+                set_error.set("Fehler beim Speichern der Einstellungen".to_string());
+            }
+        });
+    };
+
     let handle_save = move |ev: SubmitEvent| {
         ev.prevent_default();
         set_is_loading.set(true);
         spawn_local(async move {
-            let response = invoke("toggle_autostart", auto_start.get().into()).await;
+            let args = serde_wasm_bindgen::to_value(&ToggleAutostartArgs {
+                enable: auto_start.get(),
+                mode: autostart_mode.get(),
+            })
+            .unwrap();
+            let response = invoke("toggle_autostart", args).await;
+            if serde_wasm_bindgen::from_value::<()>(response).is_err() {
+                set_error.set("Fehler beim Speichern der Einstellungen".to_string());
+                return;
+            }
+
+            let args = serde_wasm_bindgen::to_value(&EnableArgs {
+                enable: minimize_to_tray.get(),
+            })
+            .unwrap();
+            let response = invoke("set_minimize_to_tray", args).await;
+            if serde_wasm_bindgen::from_value::<()>(response).is_err() {
+                set_error.set("Fehler beim Speichern der Einstellungen".to_string());
+                return;
+            }
+
+            let args = serde_wasm_bindgen::to_value(&EnableArgs {
+                enable: expiry_reminder.get(),
+            })
+            .unwrap();
+            let response = invoke("set_expiry_reminder", args).await;
+            if serde_wasm_bindgen::from_value::<()>(response).is_err() {
+                set_error.set("Fehler beim Speichern der Einstellungen".to_string());
+                return;
+            }
+
+            let args = serde_wasm_bindgen::to_value(&OpacityArgs {
+                opacity: window_opacity.get(),
+            })
+            .unwrap();
+            let response = invoke("set_window_opacity_percent", args).await;
+            if serde_wasm_bindgen::from_value::<()>(response).is_err() {
+                set_error.set("Fehler beim Speichern der Einstellungen".to_string());
+                return;
+            }
+
+            let shortcut = quick_search_shortcut.get();
+            let args = serde_wasm_bindgen::to_value(&ShortcutArgs {
+                shortcut: (!shortcut.trim().is_empty()).then(|| shortcut.trim().to_string()),
+            })
+            .unwrap();
+            let response = invoke("set_quick_search_shortcut", args).await;
+            if serde_wasm_bindgen::from_value::<()>(response.clone()).is_err() {
+                set_error.set(
+                    response
+                        .as_string()
+                        .unwrap_or("Unbekannter Fehler".to_string()),
+                );
+                set_is_loading.set(false);
+                return;
+            }
+
+            let args = serde_wasm_bindgen::to_value(&EnableArgs {
+                enable: offline_mode.get(),
+            })
+            .unwrap();
+            let response = invoke("set_offline_mode", args).await;
+            if serde_wasm_bindgen::from_value::<()>(response).is_err() {
+                set_error.set("Fehler beim Speichern der Einstellungen".to_string());
+                return;
+            }
+
+            let args = serde_wasm_bindgen::to_value(&EnableArgs {
+                enable: breach_check.get(),
+            })
+            .unwrap();
+            let response = invoke("set_breach_check", args).await;
+            if serde_wasm_bindgen::from_value::<()>(response).is_err() {
+                set_error.set("Fehler beim Speichern der Einstellungen".to_string());
+                return;
+            }
+
+            let args = serde_wasm_bindgen::to_value(&BrowserSettingsArgs {
+                enabled: browser_enabled.get(),
+                extension_ids: extension_ids
+                    .get()
+                    .split(',')
+                    .map(|id| id.trim().to_string())
+                    .filter(|id| !id.is_empty())
+                    .collect(),
+            })
+            .unwrap();
+            let response = invoke("save_browser_settings", args).await;
+            if serde_wasm_bindgen::from_value::<()>(response).is_err() {
+                set_error.set("Fehler beim Speichern der Einstellungen".to_string());
+                return;
+            }
+
+            let args = serde_wasm_bindgen::to_value(&EnableArgs {
+                enable: rpc_enabled.get(),
+            })
+            .unwrap();
+            let response = invoke("set_rpc_enabled", args).await;
             if serde_wasm_bindgen::from_value::<()>(response).is_ok() {
                 set_is_loading.set(false);
             } else {
@@ -99,6 +350,272 @@ pub fn SystemSettings() -> impl IntoView {
                                             "Automatisch beim Systemstart starten"
                                         </label>
                                     </div>
+                                    <div>
+                                        <label class="block text-white text-sm font-bold mb-2">
+                                            "Beim Systemstart öffnen"
+                                        </label>
+                                        <select
+                                            class="w-full p-2 rounded bg-background text-white border border-gray-600 focus:border-primary-100 focus:outline-none disabled:opacity-50"
+                                            on:change=move |ev| set_autostart_mode.set(event_target_value(&ev))
+                                            prop:value=autostart_mode
+                                            prop:disabled=move || !auto_start.get()
+                                        >
+                                            <option value="tray">"Gesperrt im Tray"</option>
+                                            <option value="locked">"Gesperrtes Fenster"</option>
+                                            <option value="last_view">"Letzte Ansicht nach dem Entsperren"</option>
+                                        </select>
+                                        <p class="mt-1 text-sm text-gray-400">
+                                            "Der Tresor bleibt nach dem Systemstart immer gesperrt"
+                                        </p>
+                                    </div>
+                                    <div class="flex items-center space-x-3">
+                                        <input
+                                            type="checkbox"
+                                            id="minimize-to-tray"
+                                            class="w-4 h-4 bg-background border-gray-600 rounded focus:ring-primary-100"
+                                            on:change=move |ev| set_minimize_to_tray.set(event_target_checked(&ev))
+                                            prop:checked=minimize_to_tray
+                                        />
+                                        <label for="minimize-to-tray" class="text-white text-sm font-bold">
+                                            "Beim Schließen in den Tray minimieren"
+                                        </label>
+                                    </div>
+                                    <p class="mt-1 text-sm text-gray-400">
+                                        "Zeigt ein Symbol im Tray, über das du den Tresor sperren, Passwörter generieren und angeheftete oder zuletzt verwendete Passwörter kopieren kannst"
+                                    </p>
+                                    <div class="flex items-center space-x-3">
+                                        <input
+                                            type="checkbox"
+                                            id="expiry-reminder"
+                                            class="w-4 h-4 bg-background border-gray-600 rounded focus:ring-primary-100"
+                                            on:change=move |ev| set_expiry_reminder.set(event_target_checked(&ev))
+                                            prop:checked=expiry_reminder
+                                        />
+                                        <label for="expiry-reminder" class="text-white text-sm font-bold">
+                                            "Beim Login an ablaufende Passwörter erinnern"
+                                        </label>
+                                    </div>
+                                    <p class="mt-1 text-sm text-gray-400">
+                                        "Zeigt eine Desktop-Benachrichtigung, wenn Passwörter abgelaufen sind oder in den nächsten 14 Tagen ablaufen"
+                                    </p>
+                                    <div>
+                                        <label class="block text-white text-sm font-bold mb-2">
+                                            "Deckkraft im Vordergrund"
+                                        </label>
+                                        <div class="flex items-center space-x-4">
+                                            <input
+                                                type="range"
+                                                min="60"
+                                                max="100"
+                                                class="w-full h-2 bg-background rounded-lg appearance-none cursor-pointer border border-gray-600
+                                                    [&::-webkit-slider-runnable-track]:bg-background
+                                                    [&::-webkit-slider-runnable-track]:rounded-lg
+                                                    [&::-webkit-slider-runnable-track]:border-gray-600
+                                                    [&::-webkit-slider-thumb]:w-4
+                                                    [&::-webkit-slider-thumb]:h-4
+                                                    [&::-webkit-slider-thumb]:bg-primary-100
+                                                    [&::-webkit-slider-thumb]:border-2
+                                                    [&::-webkit-slider-thumb]:border-background
+                                                    [&::-webkit-slider-thumb]:rounded-full
+                                                    [&::-webkit-slider-thumb]:appearance-none
+                                                    hover:[&::-webkit-slider-thumb]:bg-primary-200"
+                                                on:input=move |ev| set_window_opacity.set(event_target_value(&ev).parse().unwrap_or(100))
+                                                prop:value=window_opacity
+                                            />
+                                            <span class="text-white w-12 text-center">{move || format!("{}%", window_opacity.get())}</span>
+                                        </div>
+                                        <p class="mt-1 text-sm text-gray-400">
+                                            "Gilt, solange das Fenster über die Navigationsleiste im Vordergrund gehalten wird"
+                                        </p>
+                                    </div>
+                                </fieldset>
+
+                                <fieldset class="space-y-4">
+                                    <h3 class="text-lg font-semibold bg-gradient-primary bg-clip-text text-transparent mb-4">
+                                        <div class="flex items-center space-x-2">
+                                            <Icon icon=search_icon.into() class="w-5 h-5 text-primary-100" />
+                                            <span>"Schnellsuche"</span>
+                                        </div>
+                                    </h3>
+                                    <div>
+                                        <label for="quick-search-shortcut" class="block text-white text-sm font-bold mb-2">
+                                            "Tastenkürzel"
+                                        </label>
+                                        <input
+                                            type="text"
+                                            id="quick-search-shortcut"
+                                            class="w-full p-2 rounded bg-background text-white border border-gray-600 focus:border-primary-100 focus:outline-none"
+                                            placeholder="Ctrl+Shift+K"
+                                            on:input=move |ev| set_quick_search_shortcut.set(event_target_value(&ev))
+                                            prop:value=quick_search_shortcut
+                                        />
+                                        <p class="mt-1 text-sm text-gray-400">
+                                            "Öffnet systemweit ein Suchfenster, ein ausgewählter Eintrag kopiert sein Passwort. Leer lassen, um das Tastenkürzel zu deaktivieren"
+                                        </p>
+                                    </div>
+                                </fieldset>
+
+                                <fieldset class="space-y-4">
+                                    <h3 class="text-lg font-semibold bg-gradient-primary bg-clip-text text-transparent mb-4">
+                                        <div class="flex items-center space-x-2">
+                                            <Icon icon=link_icon.into() class="w-5 h-5 text-primary-100" />
+                                            <span>"Offline-Modus"</span>
+                                        </div>
+                                    </h3>
+                                    <div class="flex items-center space-x-3">
+                                        <input
+                                            type="checkbox"
+                                            id="offline-mode"
+                                            class="w-4 h-4 bg-background border-gray-600 rounded focus:ring-primary-100"
+                                            on:change=move |ev| set_offline_mode.set(event_target_checked(&ev))
+                                            prop:checked=offline_mode
+                                        />
+                                        <label for="offline-mode" class="text-white text-sm font-bold">
+                                            "Keine Verbindung zum Internet herstellen"
+                                        </label>
+                                    </div>
+                                    <p class="mt-1 text-sm text-gray-400">
+                                        "Deaktiviert die automatische Suche nach Updates und das Herunterladen der Passwortliste"
+                                    </p>
+                                    <div class="flex items-center space-x-3">
+                                        <input
+                                            type="checkbox"
+                                            id="rpc-enabled"
+                                            class="w-4 h-4 bg-background border-gray-600 rounded focus:ring-primary-100"
+                                            on:change=move |ev| set_rpc_enabled.set(event_target_checked(&ev))
+                                            prop:checked=rpc_enabled
+                                        />
+                                        <label for="rpc-enabled" class="text-white text-sm font-bold">
+                                            "Lokale JSON-RPC-Schnittstelle aktivieren"
+                                        </label>
+                                    </div>
+                                    <p class="mt-1 text-sm text-gray-400">
+                                        "Erlaubt Programmen auf diesem Rechner mit dem Token aus der Datei .rpc_token Zugriff auf den entsperrten Tresor. Wird nach einem Neustart aktiv."
+                                    </p>
+                                </fieldset>
+
+                                <fieldset class="space-y-4">
+                                    <h3 class="text-lg font-semibold bg-gradient-primary bg-clip-text text-transparent mb-4">
+                                        <div class="flex items-center space-x-2">
+                                            <Icon icon=link_icon.into() class="w-5 h-5 text-primary-100" />
+                                            <span>"Browser-Erweiterung"</span>
+                                        </div>
+                                    </h3>
+                                    <div class="flex items-center space-x-3">
+                                        <input
+                                            type="checkbox"
+                                            id="browser-enabled"
+                                            class="w-4 h-4 bg-background border-gray-600 rounded focus:ring-primary-100"
+                                            on:change=move |ev| set_browser_enabled.set(event_target_checked(&ev))
+                                            prop:checked=browser_enabled
+                                        />
+                                        <label for="browser-enabled" class="text-white text-sm font-bold">
+                                            "Browser-Erweiterung verbinden"
+                                        </label>
+                                    </div>
+                                    <p class="mt-1 text-sm text-gray-400">
+                                        "Die Erweiterung kann Anmeldungen ausfüllen und neue speichern. Jede Webseite muss im Hauptfenster freigegeben werden. Wird nach einem Neustart aktiv."
+                                    </p>
+                                    <div>
+                                        <label for="extension-ids" class="block text-white text-sm font-bold mb-2">
+                                            "Erlaubte Erweiterungen"
+                                        </label>
+                                        <input
+                                            type="text"
+                                            id="extension-ids"
+                                            class="w-full p-2 rounded bg-background text-white border border-gray-600 focus:border-primary-100 focus:outline-none"
+                                            on:input=move |ev| set_extension_ids.set(event_target_value(&ev))
+                                            prop:value=extension_ids
+                                        />
+                                        <p class="mt-1 text-sm text-gray-400">
+                                            "IDs der Erweiterungen, durch Komma getrennt"
+                                        </p>
+                                    </div>
+                                    <div class="flex items-center space-x-3">
+                                        <button
+                                            type="button"
+                                            class="px-4 py-2 text-sm text-white border border-primary-100 rounded hover:bg-primary-400/10 transition-all duration-200"
+                                            on:click=handle_install_browser_host
+                                        >
+                                            "In Browsern registrieren"
+                                        </button>
+                                        <span class="text-sm text-gray-400">{browser_status}</span>
+                                    </div>
+                                    {move || (!authorized_sites.get().is_empty()).then(|| view! {
+                                        <div>
+                                            <label class="block text-white text-sm font-bold mb-2">
+                                                "Immer erlaubte Webseiten"
+                                            </label>
+                                            <ul class="space-y-1">
+                                                {authorized_sites
+                                                    .get()
+                                                    .into_iter()
+                                                    .map(|site| {
+                                                        let revoked = site.clone();
+                                                        view! {
+                                                            <li class="flex justify-between items-center px-3 py-1 rounded bg-background text-sm text-white">
+                                                                <span>{site}</span>
+                                                                <button
+                                                                    type="button"
+                                                                    class="p-1 text-gray-400 hover:text-primary-100"
+                                                                    title="Freigabe entfernen"
+                                                                    on:click=move |_| handle_revoke_site(revoked.clone())
+                                                                >
+                                                                    <Icon icon=x_mark_icon.into() class="w-4 h-4" />
+                                                                </button>
+                                                            </li>
+                                                        }
+                                                    })
+                                                    .collect_view()}
+                                            </ul>
+                                        </div>
+                                    })}
+                                </fieldset>
+
+                                <fieldset class="space-y-4">
+                                    <h3 class="text-lg font-semibold bg-gradient-primary bg-clip-text text-transparent mb-4">
+                                        <div class="flex items-center space-x-2">
+                                            <Icon icon=shield_icon.into() class="w-5 h-5 text-primary-100" />
+                                            <span>"Passwortliste"</span>
+                                        </div>
+                                    </h3>
+                                    <div class="space-y-4">
+                                        <p class="text-gray-300">
+                                            "Der Sicherheitscheck vergleicht deine Passwörter mit einer Liste häufig verwendeter Passwörter"
+                                        </p>
+                                        <button
+                                            type="button"
+                                            class="flex items-center space-x-2 px-4 py-2 bg-background border border-primary-100 hover:bg-primary-400/10 text-white rounded focus:outline-none transition-all duration-200 disabled:opacity-50 disabled:cursor-not-allowed"
+                                            on:click=handle_update_common_passwords
+                                            prop:disabled=offline_mode
+                                        >
+                                            <Icon icon=arrow_path_icon.into() class="w-5 h-5 text-primary-100" />
+                                            <span>"Passwortliste aktualisieren"</span>
+                                        </button>
+                                        <div class="flex items-center space-x-3">
+                                            <input
+                                                type="checkbox"
+                                                id="breach-check"
+                                                class="w-4 h-4 bg-background border-gray-600 rounded focus:ring-primary-100"
+                                                on:change=move |ev| set_breach_check.set(event_target_checked(&ev))
+                                                prop:checked=breach_check
+                                                prop:disabled=offline_mode
+                                            />
+                                            <label for="breach-check" class="text-white text-sm font-bold">
+                                                "Passwörter mit Have I Been Pwned abgleichen"
+                                            </label>
+                                        </div>
+                                        <p class="mt-1 text-sm text-gray-400">
+                                            "Sendet nur die ersten 5 Zeichen des SHA-1-Hashs jedes Passworts. Das Passwort selbst verlässt nie den Rechner."
+                                        </p>
+                                        {move || (!common_passwords_status.get().is_empty()).then(||
+                                            view! {
+                                                <div class="text-primary-100 text-sm">
+                                                    {common_passwords_status.get()}
+                                                </div>
+                                            }
+                                        )}
+                                    </div>
                                 </fieldset>
 
                                 <fieldset class="space-y-4">
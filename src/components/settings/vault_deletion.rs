@@ -0,0 +1,156 @@
+use crate::{app::invoke, components::icons::Icon};
+use leptos::{ev::SubmitEvent, *};
+use serde::{Deserialize, Serialize};
+
+#[derive(Clone, Deserialize)]
+struct DeletionReport {
+    removed: Vec<String>,
+    overwritten: bool,
+    caveats: Vec<String>,
+}
+
+#[derive(Serialize)]
+struct DeleteVaultArgs {
+    #[serde(rename = "masterPass")]
+    master_pass: String,
+    overwrite: bool,
+}
+
+#[component]
+pub fn VaultDeletion(#[prop(into)] on_close: Callback<()>) -> impl IntoView {
+    let (master_pass, set_master_pass) = create_signal(String::new());
+    let (overwrite, set_overwrite) = create_signal(true);
+    let (report, set_report) = create_signal(None::<DeletionReport>);
+    let (error, set_error) = create_signal(String::new());
+    let (is_deleting, set_is_deleting) = create_signal(false);
+
+    let exit_icon = create_memo(move |_| "x-mark");
+    let trash_icon = create_memo(move |_| "trash");
+    let warning_icon = create_memo(move |_| "exclamation-triangle");
+
+    let handle_submit = move |ev: SubmitEvent| {
+        ev.prevent_default();
+        set_is_deleting.set(true);
+        set_error.set(String::new());
+        spawn_local(async move {
+            let args = serde_wasm_bindgen::to_value(&DeleteVaultArgs {
+                master_pass: master_pass.get_untracked(),
+                overwrite: overwrite.get_untracked(),
+            })
+            .unwrap();
+            let response = invoke("delete_vault", args).await;
+            match serde_wasm_bindgen::from_value::<DeletionReport>(response.clone()) {
+                Ok(deleted) => {
+                    set_master_pass.set(String::new());
+                    set_report.set(Some(deleted));
+                }
+                Err(_) => set_error.set(format!(
+                    "Tresor konnte nicht gelöscht werden: {}",
+                    response
+                        .as_string()
+                        .unwrap_or("Unbekannter Fehler".to_string())
+                )),
+            }
+            set_is_deleting.set(false);
+        });
+    };
+
+    let handle_finish = move |_| {
+        let window = web_sys::window().unwrap();
+        window.location().reload().unwrap();
+    };
+
+    view! {
+        <div class="fixed inset-0 bg-black bg-opacity-50 flex items-center justify-center z-50">
+            <div class="bg-background-card rounded-lg p-6 w-full max-w-md">
+                <div class="flex justify-between items-center mb-6">
+                    <h2 class="text-xl font-bold bg-gradient-primary bg-clip-text text-transparent flex items-center">
+                        <Icon icon=trash_icon.into() class="w-8 h-8 mr-2 text-primary-100" />
+                        "Tresor löschen"
+                    </h2>
+                    {move || report.get().is_none().then(|| view! {
+                        <button
+                            class="text-gray-400 hover:text-white"
+                            on:click=move |_| on_close.call(())
+                        >
+                            <Icon icon=exit_icon.into() class="w-5 h-5" />
+                        </button>
+                    })}
+                </div>
+
+                {move || match report.get() {
+                    None => view! {
+                        <form on:submit=handle_submit class="space-y-4">
+                            <p class="text-sm text-gray-400">
+                                "Die Datenbank und die Schlüsselparameter werden endgültig gelöscht und die App startet mit der Einrichtung neu. Backups und Exporte bleiben erhalten."
+                            </p>
+                            <input
+                                type="password"
+                                class="w-full p-2 rounded bg-background text-white border border-gray-600 focus:outline-none focus:border-primary-100"
+                                placeholder="Master-Passwort"
+                                prop:value=master_pass
+                                on:input=move |ev| set_master_pass.set(event_target_value(&ev))
+                            />
+                            <div class="flex items-center space-x-3">
+                                <input
+                                    type="checkbox"
+                                    id="overwrite-vault"
+                                    class="w-4 h-4 bg-background border-gray-600 rounded focus:ring-primary-100"
+                                    on:change=move |ev| set_overwrite.set(event_target_checked(&ev))
+                                    prop:checked=overwrite
+                                />
+                                <label for="overwrite-vault" class="text-white text-sm font-bold">
+                                    "Dateien vor dem Löschen überschreiben"
+                                </label>
+                            </div>
+                            <p class="text-sm text-gray-400">
+                                "Erschwert die Wiederherstellung auf Festplatten. Auf SSDs und Copy-on-Write-Dateisystemen ist das nicht garantiert."
+                            </p>
+
+                            {move || (!error.get().is_empty()).then(|| view! {
+                                <div class="text-primary-100 text-sm text-center">{error.get()}</div>
+                            })}
+
+                            <button
+                                type="submit"
+                                class="w-full flex items-center justify-center space-x-2 bg-gradient-primary text-white font-bold py-2 px-4 rounded focus:outline-none hover:opacity-90 disabled:opacity-50 disabled:cursor-not-allowed"
+                                prop:disabled=move || is_deleting.get() || master_pass.get().is_empty()
+                            >
+                                <Icon icon=trash_icon.into() class="w-5 h-5" />
+                                <span>"Endgültig löschen"</span>
+                            </button>
+                        </form>
+                    }.into_view(),
+                    Some(deleted) => view! {
+                        <div class="space-y-4">
+                            <p class="text-white text-sm">
+                                {format!(
+                                    "{} Dateien {}gelöscht.",
+                                    deleted.removed.len(),
+                                    if deleted.overwritten { "überschrieben und " } else { "" }
+                                )}
+                            </p>
+                            <div class="space-y-2">
+                                <div class="flex items-center space-x-2 text-sm font-bold text-white">
+                                    <Icon icon=warning_icon.into() class="w-5 h-5 text-primary-100" />
+                                    <span>"Grenzen auf diesem System"</span>
+                                </div>
+                                <ul class="list-disc list-inside space-y-1 text-sm text-gray-400">
+                                    {deleted.caveats.into_iter().map(|caveat| view! {
+                                        <li>{caveat}</li>
+                                    }).collect_view()}
+                                </ul>
+                            </div>
+                            <button
+                                class="w-full bg-gradient-primary text-white font-bold py-2 px-4 rounded focus:outline-none hover:opacity-90"
+                                on:click=handle_finish
+                            >
+                                "Zur Einrichtung"
+                            </button>
+                        </div>
+                    }.into_view(),
+                }}
+            </div>
+        </div>
+    }
+}
@@ -0,0 +1,250 @@
+use crate::{app::invoke, components::icons::Icon};
+use leptos::*;
+use serde::{Deserialize, Serialize};
+
+#[derive(Clone, PartialEq, Deserialize)]
+pub struct BrowserProfile {
+    browser: String,
+    name: String,
+    path: String,
+    logins: Option<usize>,
+}
+
+#[derive(Serialize)]
+struct BrowserArgs {
+    browser: String,
+}
+
+#[derive(Deserialize)]
+struct BrowserImportResult {
+    imported: usize,
+    skipped: usize,
+    errors: usize,
+}
+
+fn browser_label(browser: &str) -> &'static str {
+    match browser {
+        "chrome" => "Google Chrome",
+        "edge" => "Microsoft Edge",
+        _ => "Mozilla Firefox",
+    }
+}
+
+fn export_steps(browser: &str) -> &'static str {
+    match browser {
+        "chrome" => "Öffne chrome://password-manager/settings in Chrome und wähle „Passwörter exportieren“.",
+        "edge" => "Öffne edge://settings/passwords in Edge, öffne das Menü „…“ und wähle „Passwörter exportieren“.",
+        _ => "Öffne about:logins in Firefox, öffne das Menü „…“ und wähle „Passwörter exportieren…“.",
+    }
+}
+
+fn logins_label(profile: &BrowserProfile) -> String {
+    match profile.logins {
+        Some(1) => "1 gespeichertes Passwort".to_string(),
+        Some(count) => format!("{} gespeicherte Passwörter", count),
+        None => "Anzahl unbekannt".to_string(),
+    }
+}
+
+#[component]
+pub fn BrowserScan(#[prop(into)] on_complete: Callback<Vec<BrowserProfile>>) -> impl IntoView {
+    let (profiles, set_profiles) = create_signal(None::<Vec<BrowserProfile>>);
+    let (selected, set_selected) = create_signal(Vec::<BrowserProfile>::new());
+
+    let search_icon = create_memo(move |_| "magnifying-glass");
+    let arrow_right_icon = create_memo(move |_| "arrow-right");
+
+    spawn_local(async move {
+        let response = invoke("detect_browser_profiles", wasm_bindgen::JsValue::NULL).await;
+        let found =
+            serde_wasm_bindgen::from_value::<Vec<BrowserProfile>>(response).unwrap_or_default();
+        set_selected.set(
+            found
+                .iter()
+                .filter(|profile| profile.logins != Some(0))
+                .cloned()
+                .collect(),
+        );
+        set_profiles.set(Some(found));
+    });
+
+    view! {
+        <div class="flex gap-6">
+            <div class="flex-1 bg-background-card rounded-lg p-6 shadow-lg">
+                <h2 class="text-2xl font-bold mb-4 bg-gradient-primary bg-clip-text text-transparent flex items-center">
+                    <Icon icon=search_icon.into() class="w-8 h-8 mr-3 text-primary-100" />
+                    "Passwörter aus dem Browser übernehmen"
+                </h2>
+                <p class="text-gray-300 text-lg mb-6">
+                    "KarnCrypt hat diesen Computer nach Browsern mit gespeicherten Passwörtern durchsucht"
+                </p>
+                <div class="p-4 border border-gray-700 rounded-lg">
+                    <p class="text-gray-300 text-sm">
+                        "Browser verschlüsseln ihre Passwörter mit eigenen Schlüsseln. Nach der ersten Anmeldung führt dich KarnCrypt für jedes ausgewählte Profil durch den Passwort-Export des Browsers und importiert die exportierte Datei."
+                    </p>
+                </div>
+            </div>
+
+            <div class="flex-1 bg-background-card rounded-lg p-6 shadow-lg flex flex-col">
+                <div class="flex-1 space-y-3">
+                    {move || match profiles.get() {
+                        None => view! {
+                            <p class="text-gray-400">"Suche nach Browsern..."</p>
+                        }.into_view(),
+                        Some(found) if found.is_empty() => view! {
+                            <p class="text-gray-400">"Es wurden keine Browser mit gespeicherten Passwörtern gefunden."</p>
+                        }.into_view(),
+                        Some(found) => found.into_iter().map(|profile| {
+                            let id = profile.path.clone();
+                            let toggled = profile.clone();
+                            let is_selected = move || selected.with(|s| s.iter().any(|p| p.path == id));
+                            view! {
+                                <label class="flex items-center space-x-3 p-3 border border-gray-700 rounded-lg cursor-pointer hover:border-primary-100 transition-colors">
+                                    <input
+                                        type="checkbox"
+                                        class="w-4 h-4 bg-background border-gray-600 rounded focus:ring-primary-100"
+                                        prop:checked=is_selected
+                                        on:change=move |ev| {
+                                            let checked = event_target_checked(&ev);
+                                            let toggled = toggled.clone();
+                                            set_selected.update(|s| {
+                                                s.retain(|p| p.path != toggled.path);
+                                                if checked {
+                                                    s.push(toggled);
+                                                }
+                                            });
+                                        }
+                                    />
+                                    <div>
+                                        <div class="text-white font-semibold">
+                                            {format!("{} – {}", browser_label(&profile.browser), profile.name)}
+                                        </div>
+                                        <div class="text-sm text-gray-400">{logins_label(&profile)}</div>
+                                    </div>
+                                </label>
+                            }
+                        }).collect_view(),
+                    }}
+                </div>
+
+                <div class="flex justify-end gap-2 mt-6">
+                    <button
+                        class="border border-gray-600 text-gray-300 px-4 py-2 rounded hover:bg-background transition-colors"
+                        on:click=move |_| on_complete.call(Vec::new())
+                    >
+                        "Überspringen"
+                    </button>
+                    <button
+                        class="bg-gradient-primary text-white font-bold px-4 py-2 rounded hover:opacity-90 flex items-center disabled:opacity-50"
+                        prop:disabled=move || selected.with(|s| s.is_empty())
+                        on:click=move |_| on_complete.call(selected.get())
+                    >
+                        "Nach der Anmeldung importieren"
+                        <Icon icon=arrow_right_icon.into() class="w-5 h-5 ml-2" />
+                    </button>
+                </div>
+            </div>
+        </div>
+    }
+}
+
+#[component]
+pub fn BrowserImport(
+    profiles: Vec<BrowserProfile>,
+    #[prop(into)] on_close: Callback<()>,
+) -> impl IntoView {
+    let (results, set_results) = create_signal(Vec::<(String, String)>::new());
+    let (error, set_error) = create_signal(String::new());
+    let (is_loading, set_is_loading) = create_signal(false);
+
+    let import_icon = create_memo(move |_| "arrow-down-tray");
+
+    let import = move |profile: BrowserProfile| {
+        set_is_loading.set(true);
+        set_error.set(String::new());
+        spawn_local(async move {
+            let args = serde_wasm_bindgen::to_value(&BrowserArgs {
+                browser: profile.browser.clone(),
+            })
+            .unwrap();
+            let response = invoke("import_browser_export", args).await;
+            match serde_wasm_bindgen::from_value::<Option<BrowserImportResult>>(response.clone()) {
+                Ok(Some(result)) => set_results.update(|results| {
+                    results.retain(|(path, _)| *path != profile.path);
+                    results.push((
+                        profile.path,
+                        format!(
+                            "{} importiert, {} Duplikate übersprungen, {} fehlerhaft",
+                            result.imported, result.skipped, result.errors
+                        ),
+                    ));
+                }),
+                Ok(None) => {}
+                Err(_) => set_error.set(
+                    response
+                        .as_string()
+                        .unwrap_or("Unbekannter Fehler".to_string()),
+                ),
+            }
+            set_is_loading.set(false);
+        });
+    };
+
+    view! {
+        <div class="fixed inset-0 bg-black bg-opacity-50 flex items-center justify-center z-50">
+            <div class="bg-background-card rounded-lg p-6 w-full max-w-2xl max-h-[90vh] overflow-y-auto">
+                <h2 class="text-xl font-bold mb-4 bg-gradient-primary bg-clip-text text-transparent flex items-center">
+                    <Icon icon=import_icon.into() class="w-8 h-8 mr-2 text-primary-100" />
+                    "Passwörter aus dem Browser importieren"
+                </h2>
+                <p class="text-gray-300 mb-4">
+                    "Exportiere die Passwörter jedes Profils als CSV-Datei und wähle die Datei anschließend aus."
+                </p>
+                <div class="space-y-3">
+                    {profiles.into_iter().map(|profile| {
+                        let path = profile.path.clone();
+                        let result = move || results.with(|results| {
+                            results.iter().find(|(p, _)| *p == path).map(|(_, result)| result.clone())
+                        });
+                        let imported = profile.clone();
+                        view! {
+                            <div class="p-4 border border-gray-700 rounded-lg">
+                                <div class="flex items-center justify-between mb-2">
+                                    <div class="text-white font-semibold">
+                                        {format!("{} – {}", browser_label(&profile.browser), profile.name)}
+                                    </div>
+                                    <span class="text-sm text-gray-400">{logins_label(&profile)}</span>
+                                </div>
+                                <p class="text-sm text-gray-300 mb-3">{export_steps(&profile.browser)}</p>
+                                <div class="flex items-center justify-between gap-2">
+                                    <span class="text-sm text-primary-100">{result}</span>
+                                    <button
+                                        class="border border-primary-100 text-white px-4 py-2 rounded hover:bg-primary-400/10 transition-colors disabled:opacity-50"
+                                        prop:disabled=is_loading
+                                        on:click=move |_| import(imported.clone())
+                                    >
+                                        "Export auswählen und importieren"
+                                    </button>
+                                </div>
+                            </div>
+                        }
+                    }).collect_view()}
+                </div>
+                {move || (!error.get().is_empty()).then(|| view! {
+                    <div class="text-primary-100 text-sm text-center mt-4">{error.get()}</div>
+                })}
+                <p class="text-sm text-gray-400 mt-4">
+                    "Lösche die exportierten Dateien nach dem Import, sie enthalten deine Passwörter im Klartext."
+                </p>
+                <div class="flex justify-end mt-6">
+                    <button
+                        class="bg-gradient-primary text-white font-bold px-4 py-2 rounded hover:opacity-90"
+                        on:click=move |_| on_close.call(())
+                    >
+                        "Fertig"
+                    </button>
+                </div>
+            </div>
+        </div>
+    }
+}
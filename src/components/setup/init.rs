@@ -4,8 +4,11 @@ use crate::{
         auth::Register,
         icons::Icon,
         password_manager::{DialogAction, PasswordDialog},
+        settings::CloudSyncWarning,
     },
 };
+
+use super::browser_import::{BrowserProfile, BrowserScan};
 use leptos::*;
 use serde::{Deserialize, Serialize};
 
@@ -17,6 +20,8 @@ struct CompleteSetupArgs<'a> {
     db_name: &'a str,
     #[serde(rename = "backupPath")]
     backup_path: &'a str,
+    #[serde(rename = "syncSafeMode")]
+    sync_safe_mode: bool,
 }
 
 #[derive(Clone, Copy, PartialEq)]
@@ -25,6 +30,7 @@ enum InitStep {
     Restore,
     Database,
     MasterPassword,
+    BrowserImport,
 }
 
 #[derive(Deserialize)]
@@ -35,11 +41,16 @@ struct Config {
 }
 
 #[component]
-pub fn Init(#[prop(into)] on_complete: Callback<()>) -> impl IntoView {
+pub fn Init(
+    #[prop(into)] on_complete: Callback<Vec<BrowserProfile>>,
+    #[prop(into)] on_demo: Callback<()>,
+) -> impl IntoView {
     let (current_step, set_current_step) = create_signal(InitStep::Welcome);
     let (db_path, set_db_path) = create_signal(String::new());
     let (db_name, set_db_name) = create_signal(String::new());
     let (backup_path, set_backup_path) = create_signal(String::new());
+    let (sync_safe_mode, set_sync_safe_mode) = create_signal(false);
+    let (cipher, set_cipher) = create_signal("aes-256-gcm".to_string());
     let (restore_flow, set_restore_flow) = create_signal(false);
     let (show_password_dialog, set_show_password_dialog) = create_signal(false);
     let (current_action, set_current_action) = create_signal(DialogAction::Verify);
@@ -50,6 +61,7 @@ pub fn Init(#[prop(into)] on_complete: Callback<()>) -> impl IntoView {
     let folder_icon = create_memo(move |_| "folder-open");
     let arrow_right_icon = create_memo(move |_| "arrow-right");
     let backup_icon = create_memo(move |_| "archive-box");
+    let import_icon = create_memo(move |_| "arrow-down-tray");
     let arrow_left_icon = create_memo(move |_| "arrow-left");
 
     spawn_local(async move {
@@ -130,6 +142,17 @@ pub fn Init(#[prop(into)] on_complete: Callback<()>) -> impl IntoView {
                                         </div>
                                         <div class="text-xs mt-1 text-white">"Master-Passwort"</div>
                                     </div>
+                                    <div class="flex flex-col items-center">
+                                        <div class=move || format!("rounded-full h-12 w-12 flex items-center justify-center border-2 {}",
+                                            match current_step.get() {
+                                                InitStep::BrowserImport => "border-primary-100 text-primary-100",
+                                                _ => "border-gray-400 text-gray-400"
+                                            }
+                                        )>
+                                            <Icon icon=import_icon.into() class="w-5 h-5" />
+                                        </div>
+                                        <div class="text-xs mt-1 text-white">"Import"</div>
+                                    </div>
                                 </div>
                             }.into_view()
                         } else {
@@ -239,6 +262,20 @@ pub fn Init(#[prop(into)] on_complete: Callback<()>) -> impl IntoView {
                                             <Icon icon=arrow_right_icon.into() class="w-5 h-5 ml-2" />
                                         </button>
                                     </div>
+
+                                    <div class="bg-background-card rounded-lg p-6 shadow-lg">
+                                        <h3 class="font-semibold text-white mb-2">"Demo ansehen"</h3>
+                                        <p class="text-gray-300 text-sm mb-4">
+                                            "Probiere KarnCrypt mit Beispieldaten aus. Die Demo wird beim Abmelden verworfen."
+                                        </p>
+                                        <button
+                                            class="w-full border-2 border-gray-600 text-gray-300 font-bold py-2 px-4 rounded transition-all hover:bg-gray-600/10 flex items-center justify-center"
+                                            on:click=move |_| on_demo.call(())
+                                        >
+                                            <span>"Demo starten"</span>
+                                            <Icon icon=arrow_right_icon.into() class="w-5 h-5 ml-2" />
+                                        </button>
+                                    </div>
                                 </div>
                             </div>
                         }.into_view(),
@@ -390,6 +427,31 @@ pub fn Init(#[prop(into)] on_complete: Callback<()>) -> impl IntoView {
                                         </p>
                                     </div>
 
+                                    <div class="mb-4">
+                                        <CloudSyncWarning
+                                            path=db_path
+                                            sync_safe_mode=sync_safe_mode
+                                            set_sync_safe_mode=set_sync_safe_mode
+                                        />
+                                    </div>
+
+                                    <div class="mb-4">
+                                        <label class="block text-white text-sm font-bold mb-2">
+                                            "Verschlüsselung"
+                                        </label>
+                                        <select
+                                            class="w-full p-2 rounded bg-background text-white border border-gray-600 focus:border-primary-100 focus:outline-none"
+                                            on:change=move |ev| set_cipher.set(event_target_value(&ev))
+                                            prop:value=cipher
+                                        >
+                                            <option value="aes-256-gcm">"AES-256-GCM"</option>
+                                            <option value="chacha20-poly1305">"ChaCha20-Poly1305"</option>
+                                        </select>
+                                        <p class="text-sm text-primary-100 mt-1">
+                                            "ChaCha20-Poly1305 ist schneller auf Geräten ohne AES-Hardwarebeschleunigung. Die Wahl gilt für den gesamten Tresor."
+                                        </p>
+                                    </div>
+
                                     <div class="mb-6">
                                         <label class="block text-white text-sm font-bold mb-2">
                                             "Backup-Pfad (Optional)"
@@ -470,17 +532,19 @@ pub fn Init(#[prop(into)] on_complete: Callback<()>) -> impl IntoView {
                                     </button>
 
                                     <Register
+                                        cipher=cipher
                                         on_success=move |_| {
                                             let args = serde_wasm_bindgen::to_value(&CompleteSetupArgs {
                                                 db_path: db_path.get().as_str(),
                                                 db_name: db_name.get().as_str(),
                                                 backup_path: backup_path.get().as_str(),
+                                                sync_safe_mode: sync_safe_mode.get(),
                                             }).unwrap();
 
                                             spawn_local(async move {
                                                 let response = invoke("complete_setup", args).await;
                                                 if serde_wasm_bindgen::from_value::<()>(response).is_ok() {
-                                                    on_complete.call(());
+                                                    set_current_step.set(InitStep::BrowserImport);
                                                 }
                                             });
                                         }
@@ -488,6 +552,10 @@ pub fn Init(#[prop(into)] on_complete: Callback<()>) -> impl IntoView {
                                 </div>
                             </div>
                         }.into_view(),
+
+                        InitStep::BrowserImport => view! {
+                            <BrowserScan on_complete=on_complete />
+                        }.into_view(),
                     }}
                 </div>
             </div>
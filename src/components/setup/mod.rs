@@ -1,3 +1,7 @@
+mod browser_import;
 mod init;
+mod repair_assistant;
 
+pub use browser_import::{BrowserImport, BrowserProfile};
 pub use init::Init;
+pub use repair_assistant::RepairAssistant;
@@ -0,0 +1,211 @@
+use crate::{
+    app::invoke,
+    components::{
+        icons::Icon,
+        password_manager::{DialogAction, PasswordDialog},
+    },
+};
+use leptos::*;
+use serde::Deserialize;
+use wasm_bindgen::JsValue;
+
+#[derive(Deserialize, Clone, Copy, PartialEq)]
+#[serde(rename_all = "snake_case")]
+enum StartupIssueKind {
+    ConfigInvalid,
+    SaltMissing,
+    DatabaseLocationUnavailable,
+    DatabaseMissing,
+    DatabaseUnreadable,
+    BackupFolderNotWritable,
+}
+
+#[derive(Deserialize, Clone)]
+struct StartupIssue {
+    kind: StartupIssueKind,
+    details: String,
+    blocking: bool,
+}
+
+impl StartupIssueKind {
+    fn title(&self) -> &'static str {
+        match self {
+            StartupIssueKind::ConfigInvalid => "Konfiguration ist beschädigt",
+            StartupIssueKind::SaltMissing => "Schlüsseldatei fehlt",
+            StartupIssueKind::DatabaseLocationUnavailable => "Tresor-Speicherort nicht verfügbar",
+            StartupIssueKind::DatabaseMissing => "Datenbank nicht gefunden",
+            StartupIssueKind::DatabaseUnreadable => "Datenbank ist nicht lesbar",
+            StartupIssueKind::BackupFolderNotWritable => "Backup-Ordner ist nicht beschreibbar",
+        }
+    }
+
+    fn hint(&self) -> &'static str {
+        match self {
+            StartupIssueKind::ConfigInvalid => {
+                "Erstelle die Konfiguration neu oder stelle ein Backup wieder her."
+            }
+            StartupIssueKind::SaltMissing => {
+                "Ohne Schlüsseldatei kann die Datenbank nicht entschlüsselt werden. Stelle ein Backup wieder her."
+            }
+            StartupIssueKind::DatabaseLocationUnavailable => {
+                "Das Laufwerk oder die Netzwerkfreigabe mit deiner Datenbank ist nicht erreichbar. Verbinde es und prüfe erneut oder wähle einen neuen Speicherort."
+            }
+            StartupIssueKind::DatabaseMissing => {
+                "Wähle den neuen Speicherort der Datenbank oder stelle ein Backup wieder her."
+            }
+            StartupIssueKind::DatabaseUnreadable => {
+                "Die Datei ist keine gültige KarnCrypt Datenbank. Wähle die richtige Datei oder stelle ein Backup wieder her."
+            }
+            StartupIssueKind::BackupFolderNotWritable => {
+                "Wähle einen anderen Backup-Ordner, sonst werden keine Backups erstellt."
+            }
+        }
+    }
+}
+
+#[component]
+pub fn RepairAssistant(#[prop(into)] on_complete: Callback<()>) -> impl IntoView {
+    let (issues, set_issues) = create_signal(Vec::<StartupIssue>::new());
+    let (status, set_status) = create_signal(String::new());
+    let (show_password_dialog, set_show_password_dialog) = create_signal(false);
+
+    let warning_icon = create_memo(move |_| "exclamation-triangle");
+    let backup_icon = create_memo(move |_| "archive-box");
+    let config_icon = create_memo(move |_| "cog");
+    let database_icon = create_memo(move |_| "circle-stack");
+    let folder_icon = create_memo(move |_| "folder-open");
+    let refresh_icon = create_memo(move |_| "arrow-path");
+    let arrow_right_icon = create_memo(move |_| "arrow-right");
+
+    let recheck = move || {
+        spawn_local(async move {
+            let response = invoke("get_startup_issues", JsValue::NULL).await;
+            if let Ok(found) = serde_wasm_bindgen::from_value::<Vec<StartupIssue>>(response) {
+                if found.is_empty() {
+                    on_complete.call(());
+                } else {
+                    set_issues.set(found);
+                }
+            }
+        });
+    };
+
+    recheck();
+
+    let has_issue = move |kinds: &[StartupIssueKind]| {
+        issues.get().iter().any(|issue| kinds.contains(&issue.kind))
+    };
+    let is_blocking = move || issues.get().iter().any(|issue| issue.blocking);
+
+    let run_repair = move |command: &'static str, success: &'static str| {
+        spawn_local(async move {
+            let response = invoke(command, JsValue::NULL).await;
+            if response.is_null() {
+                set_status.set(success.to_string());
+                recheck();
+            } else {
+                set_status.set(
+                    response
+                        .as_string()
+                        .unwrap_or("Unbekannter Fehler".to_string()),
+                );
+            }
+        });
+    };
+
+    view! {
+        <div class="min-h-screen flex items-center justify-center bg-background">
+            <div class="w-full max-w-3xl py-8 px-8 space-y-6">
+                <div class="bg-background-card rounded-lg p-6 shadow-lg">
+                    <h2 class="text-2xl font-bold mb-4 bg-gradient-primary bg-clip-text text-transparent flex items-center">
+                        <Icon icon=warning_icon.into() class="w-8 h-8 mr-3 text-primary-100" />
+                        "Reparatur-Assistent"
+                    </h2>
+                    <p class="text-gray-300 mb-6">
+                        "Beim Start wurden Probleme mit deiner Installation gefunden:"
+                    </p>
+                    <div class="space-y-4">
+                        <For
+                            each=move || issues.get()
+                            key=|issue| issue.details.clone()
+                            children=move |issue| view! {
+                                <div class="p-4 border border-gray-700 rounded-lg">
+                                    <h3 class="font-semibold text-white mb-1">{issue.kind.title()}</h3>
+                                    <p class="text-gray-300 text-sm mb-1">{issue.kind.hint()}</p>
+                                    <p class="text-gray-400 text-xs break-all">{issue.details}</p>
+                                </div>
+                            }
+                        />
+                    </div>
+                </div>
+
+                <div class="bg-background-card rounded-lg p-6 shadow-lg space-y-4">
+                    <button
+                        class="w-full bg-gradient-primary text-white font-bold py-2 px-4 rounded transition-all hover:opacity-90 flex items-center justify-center"
+                        on:click=move |_| set_show_password_dialog.set(true)
+                    >
+                        <Icon icon=backup_icon.into() class="w-5 h-5 mr-2" />
+                        <span>"Backup wiederherstellen"</span>
+                    </button>
+                    {move || has_issue(&[StartupIssueKind::ConfigInvalid]).then(|| view! {
+                        <button
+                            class="w-full border-2 border-primary-100 text-primary-100 font-bold py-2 px-4 rounded transition-all hover:bg-primary-100/10 flex items-center justify-center"
+                            on:click=move |_| run_repair("regenerate_config", "Konfiguration wurde neu erstellt")
+                        >
+                            <Icon icon=config_icon.into() class="w-5 h-5 mr-2" />
+                            <span>"Konfiguration neu erstellen"</span>
+                        </button>
+                    })}
+                    {move || has_issue(&[StartupIssueKind::DatabaseLocationUnavailable, StartupIssueKind::DatabaseMissing, StartupIssueKind::DatabaseUnreadable]).then(|| view! {
+                        <button
+                            class="w-full border-2 border-primary-100 text-primary-100 font-bold py-2 px-4 rounded transition-all hover:bg-primary-100/10 flex items-center justify-center"
+                            on:click=move |_| run_repair("choose_database_location", "Datenbank-Pfad wurde geändert")
+                        >
+                            <Icon icon=database_icon.into() class="w-5 h-5 mr-2" />
+                            <span>"Datenbank auswählen"</span>
+                        </button>
+                    })}
+                    {move || has_issue(&[StartupIssueKind::BackupFolderNotWritable]).then(|| view! {
+                        <button
+                            class="w-full border-2 border-primary-100 text-primary-100 font-bold py-2 px-4 rounded transition-all hover:bg-primary-100/10 flex items-center justify-center"
+                            on:click=move |_| run_repair("choose_backup_location", "Backup-Ordner wurde geändert")
+                        >
+                            <Icon icon=folder_icon.into() class="w-5 h-5 mr-2" />
+                            <span>"Backup-Ordner auswählen"</span>
+                        </button>
+                    })}
+                    <div class="flex space-x-4">
+                        <button
+                            class="flex-1 flex items-center justify-center space-x-2 bg-background border border-primary-100 hover:bg-primary-400/10 text-white py-2 px-4 rounded focus:outline-none transition-all duration-200"
+                            on:click=move |_| recheck()
+                        >
+                            <Icon icon=refresh_icon.into() class="w-5 h-5 text-primary-100" />
+                            <span>"Erneut prüfen"</span>
+                        </button>
+                        {move || (!is_blocking()).then(|| view! {
+                            <button
+                                class="flex-1 flex items-center justify-center space-x-2 bg-background border border-gray-600 hover:bg-primary-400/10 text-white py-2 px-4 rounded focus:outline-none transition-all duration-200"
+                                on:click=move |_| on_complete.call(())
+                            >
+                                <span>"Trotzdem fortfahren"</span>
+                                <Icon icon=arrow_right_icon.into() class="w-5 h-5 text-primary-100" />
+                            </button>
+                        })}
+                    </div>
+                    {move || (!status.get().is_empty()).then(|| view! {
+                        <div class="text-primary-100 text-sm text-center">
+                            {status.get()}
+                        </div>
+                    })}
+                </div>
+            </div>
+            {move || show_password_dialog.get().then(|| view! {
+                <PasswordDialog
+                    action=DialogAction::RestoreBackup
+                    on_close=move |_| set_show_password_dialog.set(false)
+                    on_verify=move |_| ()
+                />
+            })}
+        </div>
+    }
+}
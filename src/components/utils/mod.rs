@@ -1,3 +1,5 @@
 mod loading_spinner;
+mod view_state;
 
 pub use loading_spinner::LoadingSpinner;
+pub use view_state::{provide_view_state, use_view_state, ScoreOrder, SortOrder};
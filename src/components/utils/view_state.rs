@@ -0,0 +1,68 @@
+use leptos::*;
+use std::collections::HashSet;
+
+use crate::components::password_manager::FolderSelection;
+
+/// The order of the password table.
+#[derive(Clone, Copy, PartialEq)]
+pub enum SortOrder {
+    ServiceAsc,
+    ServiceDesc,
+}
+
+/// The order of the password analysis on the dashboard.
+#[derive(Clone, Copy, PartialEq)]
+pub enum ScoreOrder {
+    ScoreAsc,
+    ScoreDesc,
+}
+
+/// The search, sort and filters of the tabs, kept while switching tabs until logout.
+#[derive(Clone, Copy)]
+pub struct ViewState {
+    pub search_text: RwSignal<String>,
+    pub search_mode: RwSignal<&'static str>,
+    /// None until the user or the default sort from the settings picked an order.
+    pub sort_order: RwSignal<Option<SortOrder>>,
+    pub selected_folder: RwSignal<FolderSelection>,
+    pub selected_tags: RwSignal<HashSet<i32>>,
+    pub health_filter: RwSignal<Option<&'static str>>,
+    pub score_order: RwSignal<ScoreOrder>,
+}
+
+impl ViewState {
+    fn new() -> Self {
+        Self {
+            search_text: create_rw_signal(String::new()),
+            search_mode: create_rw_signal("text"),
+            sort_order: create_rw_signal(None),
+            selected_folder: create_rw_signal(FolderSelection::All),
+            selected_tags: create_rw_signal(HashSet::new()),
+            health_filter: create_rw_signal(None),
+            score_order: create_rw_signal(ScoreOrder::ScoreDesc),
+        }
+    }
+
+    /// Forget the search, sort and filters, e.g. after logout.
+    pub fn reset(&self) {
+        self.search_text.set(String::new());
+        self.search_mode.set("text");
+        self.sort_order.set(None);
+        self.selected_folder.set(FolderSelection::All);
+        self.selected_tags.set(HashSet::new());
+        self.health_filter.set(None);
+        self.score_order.set(ScoreOrder::ScoreDesc);
+    }
+}
+
+/// Create the view state and share it with all components below the caller.
+pub fn provide_view_state() -> ViewState {
+    let state = ViewState::new();
+    provide_context(state);
+    state
+}
+
+/// Get the view state shared by the app.
+pub fn use_view_state() -> ViewState {
+    expect_context::<ViewState>()
+}
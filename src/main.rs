@@ -6,9 +6,29 @@ use leptos::*;
 
 fn main() {
     console_error_panic_hook::set_once();
-    mount_to_body(|| {
-        view! {
-            <App/>
+    let search = window().location().search().unwrap_or_default();
+
+    mount_to_body(move || {
+        if search.contains("view=generator") {
+            view! {
+                <GeneratorWindow/>
+            }
+            .into_view()
+        } else if search.contains("view=snapshot") {
+            view! {
+                <SnapshotWindow/>
+            }
+            .into_view()
+        } else if search.contains("view=quick-search") {
+            view! {
+                <QuickSearchWindow/>
+            }
+            .into_view()
+        } else {
+            view! {
+                <App/>
+            }
+            .into_view()
         }
     })
 }